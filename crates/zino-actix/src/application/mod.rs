@@ -1,3 +1,6 @@
 mod cluster;
 
+#[cfg(feature = "tls")]
+mod tls;
+
 pub use cluster::Cluster;
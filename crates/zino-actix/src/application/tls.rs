@@ -0,0 +1,14 @@
+use actix_tls::accept::rustls_0_23::TlsStream;
+use actix_web::{dev::Extensions, rt::net::TcpStream};
+use std::{any::Any, sync::Arc};
+use zino_core::application::ClientCertificate;
+
+/// Extracts the client certificate from the TLS connection as the connection data.
+pub(super) fn extract_client_certificate(conn: &dyn Any, ext: &mut Extensions) {
+    if let Some(tls_stream) = conn.downcast_ref::<TlsStream<TcpStream>>()
+        && let Some(certs) = tls_stream.get_ref().1.peer_certificates()
+        && let Some(client_certificate) = ClientCertificate::from_peer_certificates(certs)
+    {
+        ext.insert(Arc::new(client_certificate));
+    }
+}
@@ -0,0 +1,98 @@
+use crate::ActixRejection;
+use actix_web::{
+    Error,
+    dev::{Service, ServiceRequest, ServiceResponse, Transform, forward_ready},
+    http::header::{HeaderName, HeaderValue},
+};
+use std::{
+    future::{Future, Ready, ready},
+    pin::Pin,
+    sync::Arc,
+};
+use zino_middleware::Middleware;
+
+/// An adapter which applies a framework-neutral middleware to the `actix-web` services.
+pub struct MiddlewareAdapter<M> {
+    /// The middleware.
+    middleware: Arc<M>,
+}
+
+impl<M> MiddlewareAdapter<M> {
+    /// Creates a new instance.
+    #[inline]
+    pub fn new(middleware: M) -> Self {
+        Self {
+            middleware: Arc::new(middleware),
+        }
+    }
+}
+
+impl<S, B, M> Transform<S, ServiceRequest> for MiddlewareAdapter<M>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+    M: Middleware + 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = MiddlewareService<S, M>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(MiddlewareService {
+            service,
+            middleware: self.middleware.clone(),
+        }))
+    }
+}
+
+/// A service which runs a framework-neutral middleware.
+pub struct MiddlewareService<S, M> {
+    /// The inner service.
+    service: S,
+    /// The middleware.
+    middleware: Arc<M>,
+}
+
+impl<S, B, M> Service<ServiceRequest> for MiddlewareService<S, M>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+    M: Middleware + 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let mut req = crate::Request::from(req);
+        let state = match self.middleware.before_request(&mut req) {
+            Ok(state) => state,
+            Err(rejection) => {
+                let err = Error::from(ActixRejection::from(rejection));
+                return Box::pin(ready(Err(err)));
+            }
+        };
+
+        let fut = self.service.call(ServiceRequest::from(req));
+        let middleware = self.middleware.clone();
+        Box::pin(async move {
+            let mut res = fut.await?;
+            let mut headers = Default::default();
+            middleware.after_response(state, &mut headers);
+            for (name, value) in headers {
+                if let Some(Ok(header_name)) = name.map(|name| HeaderName::try_from(name.as_str()))
+                    && let Ok(header_value) = HeaderValue::try_from(value)
+                {
+                    res.headers_mut().insert(header_name, header_value);
+                }
+            }
+            Ok(res)
+        })
+    }
+}
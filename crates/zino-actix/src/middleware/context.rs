@@ -55,6 +55,11 @@ where
         }
 
         let fut = self.service.call(req);
+
+        // Routes the reads following a write in the request to the writer
+        #[cfg(feature = "orm")]
+        let fut = zino_orm::ReadYourWrites::new(fut);
+
         Box::pin(async move {
             let res = fut.await?;
             Ok(res)
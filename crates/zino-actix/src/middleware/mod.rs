@@ -1,8 +1,11 @@
+mod adapter;
 mod context;
 mod cors;
 mod etag;
 mod tracing;
 
+pub use self::adapter::{MiddlewareAdapter, MiddlewareService};
+
 pub(crate) use self::context::RequestContextInitializer;
 pub(crate) use self::cors::cors_middleware;
 pub(crate) use self::etag::ETagFinalizer;
@@ -14,6 +14,10 @@ use std::{
 };
 use zino_core::{error::Error, state::Data};
 use zino_http::request::{Context, RequestContext};
+use zino_router::{RouteRequest, ThreadLocal};
+
+#[cfg(feature = "tls")]
+use zino_core::application::ClientCertificate;
 
 /// An HTTP request extractor.
 pub struct Extractor<T>(T, Payload);
@@ -34,6 +38,10 @@ impl<T> DerefMut for Extractor<T> {
     }
 }
 
+impl RouteRequest for Extractor<HttpRequest> {
+    type Flavor = ThreadLocal;
+}
+
 impl RequestContext for Extractor<HttpRequest> {
     type Method = Method;
     type Uri = Uri;
@@ -79,6 +87,17 @@ impl RequestContext for Extractor<HttpRequest> {
             .and_then(|s| s.parse().ok())
     }
 
+    #[inline]
+    fn peer_ip(&self) -> Option<IpAddr> {
+        self.peer_addr().map(|socket| socket.ip())
+    }
+
+    #[cfg(feature = "tls")]
+    #[inline]
+    fn client_certificate(&self) -> Option<Arc<ClientCertificate>> {
+        self.conn_data::<Arc<ClientCertificate>>().cloned()
+    }
+
     #[inline]
     fn get_context(&self) -> Option<Arc<Context>> {
         let extensions = self.extensions();
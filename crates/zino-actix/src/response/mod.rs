@@ -1,12 +1,13 @@
 use actix_web::{
     HttpRequest, HttpResponse, Responder, ResponseError,
-    body::BoxBody,
+    body::{BodyStream, BoxBody},
     http::{
         StatusCode,
         header::{self, HeaderName, HeaderValue},
     },
 };
-use std::fmt;
+use futures::TryStreamExt;
+use std::{fmt, io};
 use zino_http::{
     response::{Rejection, Response, ResponseCode},
     timing::TimingMetric,
@@ -121,13 +122,18 @@ impl ResponseError for ActixRejection {
 
 /// Build http response from `zino_core::response::Response`.
 fn build_http_response<S: ResponseCode>(response: &mut Response<S>) -> HttpResponse<BoxBody> {
-    match response.read_bytes() {
-        Ok(data) => {
+    let result = if let Some(stream) = response.take_stream_data() {
+        let stream = stream.map_err(|err| io::Error::other(err.to_string()));
+        Ok(BoxBody::new(BodyStream::new(stream)))
+    } else {
+        response.read_bytes().map(BoxBody::new)
+    };
+    match result {
+        Ok(body) => {
             let status_code = response
                 .status_code()
                 .try_into()
                 .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
-            let body = BoxBody::new(data);
             let mut res = HttpResponse::with_body(status_code, body);
             if let Ok(header_value) = HeaderValue::try_from(response.content_type()) {
                 res.headers_mut().insert(header::CONTENT_TYPE, header_value);
@@ -0,0 +1,341 @@
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use zino_core::{JsonValue, error::Error};
+
+/// Role of the author of a chat message.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[non_exhaustive]
+pub enum Role {
+    /// System instructions.
+    System,
+    /// End user.
+    #[default]
+    User,
+    /// Model output.
+    Assistant,
+    /// Tool output.
+    Tool,
+}
+
+impl Role {
+    /// Parses the role.
+    pub fn parse(role: &str) -> Option<Self> {
+        match role {
+            "system" | "developer" => Some(Self::System),
+            "user" => Some(Self::User),
+            "assistant" => Some(Self::Assistant),
+            "tool" => Some(Self::Tool),
+            _ => None,
+        }
+    }
+
+    /// Returns the role as a string slice.
+    #[inline]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::System => "system",
+            Self::User => "user",
+            Self::Assistant => "assistant",
+            Self::Tool => "tool",
+        }
+    }
+}
+
+/// A message in a chat conversation.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChatMessage {
+    /// Role of the author.
+    role: Role,
+    /// Text content.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    content: Option<String>,
+    /// Optional name of the author.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    /// Tool calls requested by the assistant.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tool_calls: Vec<ToolCall>,
+    /// ID of the tool call which the message responds to.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool_call_id: Option<String>,
+}
+
+impl ChatMessage {
+    /// Creates a new instance with the role and content.
+    #[inline]
+    pub fn new(role: Role, content: impl Into<String>) -> Self {
+        Self {
+            role,
+            content: Some(content.into()),
+            ..Self::default()
+        }
+    }
+
+    /// Creates a system message.
+    #[inline]
+    pub fn system(content: impl Into<String>) -> Self {
+        Self::new(Role::System, content)
+    }
+
+    /// Creates a user message.
+    #[inline]
+    pub fn user(content: impl Into<String>) -> Self {
+        Self::new(Role::User, content)
+    }
+
+    /// Creates an assistant message.
+    #[inline]
+    pub fn assistant(content: impl Into<String>) -> Self {
+        Self::new(Role::Assistant, content)
+    }
+
+    /// Creates a tool message which responds to the tool call.
+    #[inline]
+    pub fn tool(tool_call_id: impl Into<String>, content: impl Into<String>) -> Self {
+        Self {
+            role: Role::Tool,
+            content: Some(content.into()),
+            tool_call_id: Some(tool_call_id.into()),
+            ..Self::default()
+        }
+    }
+
+    /// Creates an assistant message with the tool calls.
+    #[inline]
+    pub fn assistant_tool_calls(tool_calls: Vec<ToolCall>) -> Self {
+        Self {
+            role: Role::Assistant,
+            tool_calls,
+            ..Self::default()
+        }
+    }
+
+    /// Sets the name of the author.
+    #[inline]
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Returns the role.
+    #[inline]
+    pub fn role(&self) -> Role {
+        self.role
+    }
+
+    /// Returns the text content.
+    #[inline]
+    pub fn content(&self) -> Option<&str> {
+        self.content.as_deref()
+    }
+
+    /// Returns the name of the author.
+    #[inline]
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Returns the tool calls.
+    #[inline]
+    pub fn tool_calls(&self) -> &[ToolCall] {
+        &self.tool_calls
+    }
+
+    /// Returns the ID of the tool call which the message responds to.
+    #[inline]
+    pub fn tool_call_id(&self) -> Option<&str> {
+        self.tool_call_id.as_deref()
+    }
+
+    /// Returns `true` if the message has any tool calls.
+    #[inline]
+    pub fn has_tool_calls(&self) -> bool {
+        !self.tool_calls.is_empty()
+    }
+
+    /// Appends the text to the content.
+    pub(crate) fn push_content(&mut self, text: &str) {
+        self.content.get_or_insert_with(String::new).push_str(text);
+    }
+
+    /// Returns a mutable reference to the tool calls.
+    #[inline]
+    pub(crate) fn tool_calls_mut(&mut self) -> &mut Vec<ToolCall> {
+        &mut self.tool_calls
+    }
+}
+
+/// A tool call requested by the model.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ToolCall {
+    /// Tool call ID.
+    id: String,
+    /// Name of the tool.
+    name: String,
+    /// Arguments encoded as a JSON string.
+    arguments: String,
+}
+
+impl ToolCall {
+    /// Creates a new instance.
+    #[inline]
+    pub fn new(
+        id: impl Into<String>,
+        name: impl Into<String>,
+        arguments: impl Into<String>,
+    ) -> Self {
+        Self {
+            id: id.into(),
+            name: name.into(),
+            arguments: arguments.into(),
+        }
+    }
+
+    /// Returns the tool call ID.
+    #[inline]
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Returns the name of the tool.
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the arguments encoded as a JSON string.
+    #[inline]
+    pub fn arguments(&self) -> &str {
+        &self.arguments
+    }
+
+    /// Deserializes the arguments as an instance of type `T`.
+    #[inline]
+    pub fn parse_arguments<T: DeserializeOwned>(&self) -> Result<T, Error> {
+        serde_json::from_str(&self.arguments).map_err(Error::from)
+    }
+
+    /// Merges a streaming delta of the tool call.
+    pub(crate) fn merge_delta(&mut self, delta: &ToolCallDelta) {
+        if let Some(id) = delta.id() {
+            self.id = id.to_owned();
+        }
+        if let Some(name) = delta.name() {
+            self.name.push_str(name);
+        }
+        self.arguments.push_str(delta.arguments());
+    }
+}
+
+/// A streaming delta of a tool call.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ToolCallDelta {
+    /// Index of the tool call.
+    index: usize,
+    /// Tool call ID, which is present in the first delta.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<String>,
+    /// Name of the tool, which is present in the first delta.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    /// Partial arguments.
+    arguments: String,
+}
+
+impl ToolCallDelta {
+    /// Creates a new instance.
+    #[inline]
+    pub fn new(index: usize, arguments: impl Into<String>) -> Self {
+        Self {
+            index,
+            id: None,
+            name: None,
+            arguments: arguments.into(),
+        }
+    }
+
+    /// Sets the tool call ID and the name of the tool.
+    #[inline]
+    pub fn with_function(mut self, id: impl Into<String>, name: impl Into<String>) -> Self {
+        self.id = Some(id.into());
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Returns the index of the tool call.
+    #[inline]
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Returns the tool call ID.
+    #[inline]
+    pub fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    /// Returns the name of the tool.
+    #[inline]
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Returns the partial arguments.
+    #[inline]
+    pub fn arguments(&self) -> &str {
+        &self.arguments
+    }
+}
+
+/// Definition of a tool which can be called by the model.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ToolDefinition {
+    /// Name of the tool.
+    name: String,
+    /// Description of what the tool does.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    /// JSON schema of the parameters.
+    parameters: JsonValue,
+}
+
+impl ToolDefinition {
+    /// Creates a new instance with the name and JSON schema of the parameters.
+    #[inline]
+    pub fn new(name: impl Into<String>, parameters: JsonValue) -> Self {
+        Self {
+            name: name.into(),
+            description: None,
+            parameters,
+        }
+    }
+
+    /// Sets the description.
+    #[inline]
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Returns the name of the tool.
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the description.
+    #[inline]
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    /// Returns the JSON schema of the parameters.
+    #[inline]
+    pub fn parameters(&self) -> &JsonValue {
+        &self.parameters
+    }
+}
@@ -0,0 +1,438 @@
+use super::{ChatMessage, ToolCall, ToolCallDelta, ToolDefinition};
+use serde::{Deserialize, Serialize};
+use std::ops::AddAssign;
+
+/// A chat completion request.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChatRequest {
+    /// Messages of the conversation.
+    messages: Vec<ChatMessage>,
+    /// Model name, which overrides the default model of the service.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    model: Option<String>,
+    /// Sampling temperature.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    /// Maximum number of tokens to generate.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_tokens: Option<u32>,
+    /// Tools which can be called by the model.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tools: Vec<ToolDefinition>,
+    /// Stop sequences.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    stop: Vec<String>,
+}
+
+impl ChatRequest {
+    /// Creates a new instance with the messages.
+    #[inline]
+    pub fn new(messages: Vec<ChatMessage>) -> Self {
+        Self {
+            messages,
+            ..Self::default()
+        }
+    }
+
+    /// Appends a message.
+    #[inline]
+    pub fn with_message(mut self, message: ChatMessage) -> Self {
+        self.messages.push(message);
+        self
+    }
+
+    /// Sets the model name.
+    #[inline]
+    pub fn with_model(mut self, model: impl Into<String>) -> Self {
+        self.model = Some(model.into());
+        self
+    }
+
+    /// Sets the sampling temperature.
+    #[inline]
+    pub fn with_temperature(mut self, temperature: f32) -> Self {
+        self.temperature = Some(temperature);
+        self
+    }
+
+    /// Sets the maximum number of tokens to generate.
+    #[inline]
+    pub fn with_max_tokens(mut self, max_tokens: u32) -> Self {
+        self.max_tokens = Some(max_tokens);
+        self
+    }
+
+    /// Adds a tool which can be called by the model.
+    #[inline]
+    pub fn with_tool(mut self, tool: ToolDefinition) -> Self {
+        self.tools.push(tool);
+        self
+    }
+
+    /// Adds a stop sequence.
+    #[inline]
+    pub fn with_stop(mut self, stop: impl Into<String>) -> Self {
+        self.stop.push(stop.into());
+        self
+    }
+
+    /// Returns the messages.
+    #[inline]
+    pub fn messages(&self) -> &[ChatMessage] {
+        &self.messages
+    }
+
+    /// Returns the model name.
+    #[inline]
+    pub fn model(&self) -> Option<&str> {
+        self.model.as_deref()
+    }
+
+    /// Returns the sampling temperature.
+    #[inline]
+    pub fn temperature(&self) -> Option<f32> {
+        self.temperature
+    }
+
+    /// Returns the maximum number of tokens to generate.
+    #[inline]
+    pub fn max_tokens(&self) -> Option<u32> {
+        self.max_tokens
+    }
+
+    /// Returns the tools.
+    #[inline]
+    pub fn tools(&self) -> &[ToolDefinition] {
+        &self.tools
+    }
+
+    /// Returns the stop sequences.
+    #[inline]
+    pub fn stop(&self) -> &[String] {
+        &self.stop
+    }
+}
+
+/// Reason why the model stopped generating tokens.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum FinishReason {
+    /// A natural stop point or a stop sequence.
+    Stop,
+    /// The maximum number of tokens was reached.
+    Length,
+    /// The model called tools.
+    ToolCalls,
+    /// The content was omitted by the filters.
+    ContentFilter,
+    /// Other reasons.
+    #[serde(untagged)]
+    Other(String),
+}
+
+impl FinishReason {
+    /// Parses the finish reason.
+    pub fn parse(reason: &str) -> Self {
+        match reason {
+            "stop" | "end_turn" | "stop_sequence" => Self::Stop,
+            "length" | "max_tokens" => Self::Length,
+            "tool_calls" | "tool_use" | "function_call" => Self::ToolCalls,
+            "content_filter" => Self::ContentFilter,
+            _ => Self::Other(reason.to_owned()),
+        }
+    }
+
+    /// Returns the finish reason as a string slice.
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Stop => "stop",
+            Self::Length => "length",
+            Self::ToolCalls => "tool_calls",
+            Self::ContentFilter => "content_filter",
+            Self::Other(reason) => reason.as_str(),
+        }
+    }
+}
+
+/// Token usage of a request.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Usage {
+    /// Number of tokens in the prompt.
+    prompt_tokens: u64,
+    /// Number of tokens in the generated completion.
+    completion_tokens: u64,
+    /// Total number of tokens.
+    total_tokens: u64,
+}
+
+impl Usage {
+    /// Creates a new instance.
+    #[inline]
+    pub fn new(prompt_tokens: u64, completion_tokens: u64) -> Self {
+        Self {
+            prompt_tokens,
+            completion_tokens,
+            total_tokens: prompt_tokens + completion_tokens,
+        }
+    }
+
+    /// Returns the number of tokens in the prompt.
+    #[inline]
+    pub fn prompt_tokens(&self) -> u64 {
+        self.prompt_tokens
+    }
+
+    /// Returns the number of tokens in the generated completion.
+    #[inline]
+    pub fn completion_tokens(&self) -> u64 {
+        self.completion_tokens
+    }
+
+    /// Returns the total number of tokens.
+    #[inline]
+    pub fn total_tokens(&self) -> u64 {
+        self.total_tokens
+    }
+}
+
+impl AddAssign for Usage {
+    #[inline]
+    fn add_assign(&mut self, other: Self) {
+        self.prompt_tokens += other.prompt_tokens;
+        self.completion_tokens += other.completion_tokens;
+        self.total_tokens += other.total_tokens;
+    }
+}
+
+/// A chat completion generated by the model.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChatCompletion {
+    /// Completion ID.
+    id: String,
+    /// Model name.
+    model: String,
+    /// Generated message.
+    message: ChatMessage,
+    /// Reason why the model stopped generating tokens.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    finish_reason: Option<FinishReason>,
+    /// Token usage.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    usage: Option<Usage>,
+}
+
+impl ChatCompletion {
+    /// Creates a new instance.
+    #[inline]
+    pub fn new(id: impl Into<String>, model: impl Into<String>, message: ChatMessage) -> Self {
+        Self {
+            id: id.into(),
+            model: model.into(),
+            message,
+            finish_reason: None,
+            usage: None,
+        }
+    }
+
+    /// Sets the finish reason.
+    #[inline]
+    pub fn set_finish_reason(&mut self, finish_reason: FinishReason) {
+        self.finish_reason = Some(finish_reason);
+    }
+
+    /// Sets the token usage.
+    #[inline]
+    pub fn set_usage(&mut self, usage: Usage) {
+        self.usage = Some(usage);
+    }
+
+    /// Returns the completion ID.
+    #[inline]
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Returns the model name.
+    #[inline]
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+
+    /// Returns a reference to the generated message.
+    #[inline]
+    pub fn message(&self) -> &ChatMessage {
+        &self.message
+    }
+
+    /// Consumes `self` and returns the generated message.
+    #[inline]
+    pub fn into_message(self) -> ChatMessage {
+        self.message
+    }
+
+    /// Returns the text content of the generated message.
+    #[inline]
+    pub fn content(&self) -> Option<&str> {
+        self.message.content()
+    }
+
+    /// Returns the tool calls of the generated message.
+    #[inline]
+    pub fn tool_calls(&self) -> &[ToolCall] {
+        self.message.tool_calls()
+    }
+
+    /// Returns the finish reason.
+    #[inline]
+    pub fn finish_reason(&self) -> Option<&FinishReason> {
+        self.finish_reason.as_ref()
+    }
+
+    /// Returns the token usage.
+    #[inline]
+    pub fn usage(&self) -> Option<Usage> {
+        self.usage
+    }
+
+    /// Merges a streaming delta into the completion.
+    pub fn merge_delta(&mut self, delta: ChatDelta) {
+        if self.id.is_empty()
+            && let Some(id) = delta.id
+        {
+            self.id = id;
+        }
+        if self.model.is_empty()
+            && let Some(model) = delta.model
+        {
+            self.model = model;
+        }
+        if let Some(content) = delta.content {
+            self.message.push_content(&content);
+        }
+        for tool_call_delta in &delta.tool_calls {
+            let index = tool_call_delta.index();
+            let tool_calls = self.message.tool_calls_mut();
+            if tool_calls.len() <= index {
+                tool_calls.resize_with(index + 1, ToolCall::default);
+            }
+            tool_calls[index].merge_delta(tool_call_delta);
+        }
+        if delta.finish_reason.is_some() {
+            self.finish_reason = delta.finish_reason;
+        }
+        if let Some(usage) = delta.usage {
+            *self.usage.get_or_insert_default() += usage;
+        }
+    }
+}
+
+/// A streaming delta of the chat completion.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChatDelta {
+    /// Completion ID.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<String>,
+    /// Model name.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    model: Option<String>,
+    /// Partial content.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    content: Option<String>,
+    /// Partial tool calls.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tool_calls: Vec<ToolCallDelta>,
+    /// Reason why the model stopped generating tokens.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    finish_reason: Option<FinishReason>,
+    /// Token usage, which is usually present in the last delta.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    usage: Option<Usage>,
+}
+
+impl ChatDelta {
+    /// Creates a new instance with the partial content.
+    #[inline]
+    pub fn new(content: impl Into<String>) -> Self {
+        Self {
+            content: Some(content.into()),
+            ..Self::default()
+        }
+    }
+
+    /// Sets the completion ID and the model name.
+    #[inline]
+    pub fn with_source(mut self, id: impl Into<String>, model: impl Into<String>) -> Self {
+        self.id = Some(id.into());
+        self.model = Some(model.into());
+        self
+    }
+
+    /// Adds a partial tool call.
+    #[inline]
+    pub fn with_tool_call(mut self, tool_call: ToolCallDelta) -> Self {
+        self.tool_calls.push(tool_call);
+        self
+    }
+
+    /// Sets the finish reason.
+    #[inline]
+    pub fn with_finish_reason(mut self, finish_reason: FinishReason) -> Self {
+        self.finish_reason = Some(finish_reason);
+        self
+    }
+
+    /// Sets the token usage.
+    #[inline]
+    pub fn with_usage(mut self, usage: Usage) -> Self {
+        self.usage = Some(usage);
+        self
+    }
+
+    /// Returns the completion ID.
+    #[inline]
+    pub fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    /// Returns the model name.
+    #[inline]
+    pub fn model(&self) -> Option<&str> {
+        self.model.as_deref()
+    }
+
+    /// Returns the partial content.
+    #[inline]
+    pub fn content(&self) -> Option<&str> {
+        self.content.as_deref()
+    }
+
+    /// Returns the partial tool calls.
+    #[inline]
+    pub fn tool_calls(&self) -> &[ToolCallDelta] {
+        &self.tool_calls
+    }
+
+    /// Returns the finish reason.
+    #[inline]
+    pub fn finish_reason(&self) -> Option<&FinishReason> {
+        self.finish_reason.as_ref()
+    }
+
+    /// Returns the token usage.
+    #[inline]
+    pub fn usage(&self) -> Option<Usage> {
+        self.usage
+    }
+
+    /// Returns `true` if the delta has neither content nor tool calls.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.content.as_ref().is_none_or(|s| s.is_empty()) && self.tool_calls.is_empty()
+    }
+}
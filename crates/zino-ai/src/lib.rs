@@ -1,3 +1,86 @@
+#![cfg_attr(docsrs, feature(doc_cfg))]
 #![doc = include_str!("../README.md")]
 #![doc(html_favicon_url = "https://zino.cc/assets/zino-logo.png")]
 #![doc(html_logo_url = "https://zino.cc/assets/zino-logo.svg")]
+#![allow(async_fn_in_trait)]
+
+use futures::stream::BoxStream;
+use zino_core::{
+    LazyLock, application::StaticRecord, bail, error::Error, extension::TomlTableExt, state::State,
+};
+
+mod chat;
+mod completion;
+mod service;
+mod vector;
+
+#[cfg(feature = "orm")]
+mod semantic;
+
+/// Supported providers.
+mod mock;
+mod openai;
+
+pub use chat::{ChatMessage, Role, ToolCall, ToolCallDelta, ToolDefinition};
+pub use completion::{ChatCompletion, ChatDelta, ChatRequest, FinishReason, Usage};
+pub use mock::MockProvider;
+pub use openai::OpenAiProvider;
+pub use service::AiService;
+pub use vector::{Distance, FlatIndex, HnswIndex, SearchHit, VectorIndex, VectorStore};
+
+#[cfg(feature = "orm")]
+pub use semantic::{ModelEmbedding, SemanticSearch};
+
+/// A stream of the chat completion deltas.
+pub type ChatStream = BoxStream<'static, Result<ChatDelta, Error>>;
+
+/// Underlying trait of the chat completion providers.
+pub trait ChatProvider {
+    /// Creates a chat completion for the request.
+    async fn chat(&self, request: ChatRequest) -> Result<ChatCompletion, Error>;
+
+    /// Creates a chat completion for the request and streams back the deltas.
+    async fn chat_stream(&self, request: ChatRequest) -> Result<ChatStream, Error>;
+}
+
+/// Underlying trait of the embedding providers.
+pub trait EmbeddingProvider {
+    /// Creates the embeddings for the inputs in the same order.
+    async fn embed(&self, inputs: Vec<String>) -> Result<Vec<Vec<f32>>, Error>;
+
+    /// Creates the embedding for a single input.
+    async fn embed_one(&self, input: String) -> Result<Vec<f32>, Error> {
+        match self.embed(vec![input]).await?.pop() {
+            Some(embedding) => Ok(embedding),
+            None => bail!("no embedding has been created for the input"),
+        }
+    }
+}
+
+/// Global access to the shared AI services.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GlobalAi;
+
+impl GlobalAi {
+    /// Gets the AI service for the specific name.
+    #[inline]
+    pub fn get(name: &str) -> Option<&'static AiService> {
+        SHARED_AI_SERVICES.find(name)
+    }
+}
+
+/// Shared AI services.
+static SHARED_AI_SERVICES: LazyLock<StaticRecord<AiService>> = LazyLock::new(|| {
+    let mut services = StaticRecord::new();
+    if let Some(configs) = State::shared().config().get_array("ai") {
+        for config in configs.iter().filter_map(|v| v.as_table()) {
+            let provider_type = config.get_str("type").unwrap_or("unknown");
+            let name = config.get_str("name").unwrap_or(provider_type);
+            match AiService::try_new(config) {
+                Ok(service) => services.add(name, service),
+                Err(err) => tracing::error!("fail to create the AI service `{name}`: {err}"),
+            }
+        }
+    }
+    services
+});
@@ -0,0 +1,235 @@
+use super::{
+    ChatCompletion, ChatDelta, ChatMessage, ChatProvider, ChatRequest, ChatStream,
+    EmbeddingProvider, FinishReason, Role, ToolCall, ToolCallDelta, Usage,
+};
+use futures::{StreamExt, stream};
+use toml::Table;
+use zino_core::{Uuid, error::Error, extension::TomlTableExt};
+
+/// A mock provider which works offline.
+///
+/// It replies with the configured text or echoes the last user message.
+/// If the tool calls have been configured, it requests them first
+/// and replies after receiving the tool outputs.
+#[derive(Debug, Clone)]
+pub struct MockProvider {
+    /// Model name.
+    model: String,
+    /// Fixed reply.
+    reply: Option<String>,
+    /// Tool calls to request.
+    tool_calls: Vec<ToolCall>,
+    /// Dimensions of the embeddings.
+    dimensions: usize,
+}
+
+impl MockProvider {
+    /// Creates a new instance.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            model: "mock".to_owned(),
+            reply: None,
+            tool_calls: Vec::new(),
+            dimensions: 64,
+        }
+    }
+
+    /// Attempts to create a new instance with the configuration.
+    pub fn try_with_config(config: &Table) -> Result<Self, Error> {
+        let mut provider = Self::new();
+        if let Some(model) = config.get_str("model") {
+            provider.model = model.to_owned();
+        }
+        provider.reply = config.get_str("reply").map(|s| s.to_owned());
+        if let Some(dimensions) = config.get_usize("dimensions") {
+            provider.dimensions = dimensions.max(1);
+        }
+        Ok(provider)
+    }
+
+    /// Sets a fixed reply.
+    #[inline]
+    pub fn reply(mut self, reply: impl Into<String>) -> Self {
+        self.reply = Some(reply.into());
+        self
+    }
+
+    /// Adds a tool call to request.
+    #[inline]
+    pub fn tool_call(mut self, name: impl Into<String>, arguments: impl Into<String>) -> Self {
+        let id = format!("call_{}", self.tool_calls.len());
+        self.tool_calls.push(ToolCall::new(id, name, arguments));
+        self
+    }
+
+    /// Sets the dimensions of the embeddings.
+    #[inline]
+    pub fn dimensions(mut self, dimensions: usize) -> Self {
+        self.dimensions = dimensions.max(1);
+        self
+    }
+
+    /// Returns the model name.
+    #[inline]
+    pub fn model(&self) -> &str {
+        self.model.as_str()
+    }
+
+    /// Generates the completion for the request.
+    fn generate(&self, request: &ChatRequest) -> ChatCompletion {
+        let messages = request.messages();
+        let has_tool_outputs = messages.iter().any(|message| message.role() == Role::Tool);
+        let (message, finish_reason) = if !self.tool_calls.is_empty() && !has_tool_outputs {
+            let message = ChatMessage::assistant_tool_calls(self.tool_calls.clone());
+            (message, FinishReason::ToolCalls)
+        } else {
+            let reply = self.reply.clone().unwrap_or_else(|| {
+                messages
+                    .iter()
+                    .rev()
+                    .find(|message| message.role() == Role::User)
+                    .and_then(|message| message.content())
+                    .unwrap_or_default()
+                    .to_owned()
+            });
+            (ChatMessage::assistant(reply), FinishReason::Stop)
+        };
+
+        let prompt_tokens = messages
+            .iter()
+            .filter_map(|message| message.content())
+            .map(count_tokens)
+            .sum();
+        let completion_tokens = message.content().map(count_tokens).unwrap_or_default()
+            + message
+                .tool_calls()
+                .iter()
+                .map(|tool_call| count_tokens(tool_call.arguments()))
+                .sum::<u64>();
+
+        let id = format!("chatcmpl-{}", Uuid::now_v7().simple());
+        let model = request.model().unwrap_or(&self.model);
+        let mut completion = ChatCompletion::new(id, model, message);
+        completion.set_finish_reason(finish_reason);
+        completion.set_usage(Usage::new(prompt_tokens, completion_tokens));
+        completion
+    }
+}
+
+impl Default for MockProvider {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ChatProvider for MockProvider {
+    async fn chat(&self, request: ChatRequest) -> Result<ChatCompletion, Error> {
+        Ok(self.generate(&request))
+    }
+
+    async fn chat_stream(&self, request: ChatRequest) -> Result<ChatStream, Error> {
+        let completion = self.generate(&request);
+        let (id, model) = (completion.id(), completion.model());
+        let mut deltas = Vec::new();
+        if let Some(content) = completion.content() {
+            for word in content.split_inclusive(' ') {
+                deltas.push(ChatDelta::new(word).with_source(id, model));
+            }
+        }
+        for (index, tool_call) in completion.tool_calls().iter().enumerate() {
+            let tool_call_delta = ToolCallDelta::new(index, tool_call.arguments())
+                .with_function(tool_call.id(), tool_call.name());
+            deltas.push(
+                ChatDelta::default()
+                    .with_source(id, model)
+                    .with_tool_call(tool_call_delta),
+            );
+        }
+
+        let mut last_delta = ChatDelta::default().with_source(id, model);
+        if let Some(finish_reason) = completion.finish_reason() {
+            last_delta = last_delta.with_finish_reason(finish_reason.clone());
+        }
+        if let Some(usage) = completion.usage() {
+            last_delta = last_delta.with_usage(usage);
+        }
+        deltas.push(last_delta);
+        Ok(stream::iter(deltas.into_iter().map(Ok)).boxed())
+    }
+}
+
+impl EmbeddingProvider for MockProvider {
+    async fn embed(&self, inputs: Vec<String>) -> Result<Vec<Vec<f32>>, Error> {
+        let embeddings = inputs
+            .iter()
+            .map(|input| hash_embedding(input, self.dimensions))
+            .collect();
+        Ok(embeddings)
+    }
+}
+
+/// Creates a normalized embedding by hashing the words into the buckets.
+fn hash_embedding(text: &str, dimensions: usize) -> Vec<f32> {
+    let mut embedding = vec![0.0; dimensions];
+    let words = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty());
+    for word in words {
+        // The 64-bit FNV-1a hash is stable across processes.
+        let hash = word
+            .to_lowercase()
+            .bytes()
+            .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+                (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+            });
+        let index = (hash % dimensions as u64) as usize;
+        embedding[index] += if hash >> 63 == 0 { 1.0 } else { -1.0 };
+    }
+
+    let norm = embedding.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        embedding.iter_mut().for_each(|x| *x /= norm);
+    }
+    embedding
+}
+
+/// Counts the tokens roughly by splitting the text with whitespaces.
+fn count_tokens(text: &str) -> u64 {
+    text.split_whitespace().count() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MockProvider;
+    use crate::{ChatCompletion, ChatMessage, ChatProvider, ChatRequest, FinishReason};
+    use futures::{StreamExt, executor::block_on};
+
+    #[test]
+    fn it_streams_mock_completions() {
+        let provider = MockProvider::new().tool_call("get_weather", r#"{"city":"Paris"}"#);
+        let request = ChatRequest::new(vec![ChatMessage::user("What is the weather in Paris?")]);
+        let completion =
+            block_on(provider.chat(request.clone())).expect("fail to create the completion");
+        assert_eq!(completion.finish_reason(), Some(&FinishReason::ToolCalls));
+        assert_eq!(completion.tool_calls()[0].name(), "get_weather");
+
+        let tool_call_id = completion.tool_calls()[0].id().to_owned();
+        let request = request
+            .with_message(completion.into_message())
+            .with_message(ChatMessage::tool(tool_call_id, "Sunny"));
+        let mut stream =
+            block_on(provider.chat_stream(request)).expect("fail to create the stream");
+        let mut completion = ChatCompletion::default();
+        while let Some(delta) = block_on(stream.next()) {
+            completion.merge_delta(delta.expect("fail to receive the delta"));
+        }
+        assert_eq!(completion.content(), Some("What is the weather in Paris?"));
+        assert_eq!(completion.finish_reason(), Some(&FinishReason::Stop));
+        assert_eq!(
+            completion.usage().map(|usage| usage.completion_tokens()),
+            Some(6)
+        );
+    }
+}
@@ -0,0 +1,497 @@
+use super::{
+    ChatCompletion, ChatDelta, ChatMessage, ChatProvider, ChatRequest, ChatStream,
+    EmbeddingProvider, FinishReason, Role, ToolCall, ToolCallDelta, Usage,
+};
+use futures::{StreamExt, stream};
+use std::{collections::VecDeque, time::Duration};
+use toml::Table;
+use zino_core::{
+    JsonValue, Map,
+    application::Agent,
+    bail,
+    error::Error,
+    extension::{JsonObjectExt, TomlTableExt},
+    trace::TraceContext,
+    warn,
+};
+
+/// A provider for the OpenAI-compatible chat completions API.
+#[derive(Debug, Clone)]
+pub struct OpenAiProvider {
+    /// Base URL.
+    base_url: String,
+    /// API key.
+    api_key: Option<String>,
+    /// Default model.
+    model: String,
+    /// Embedding model.
+    embedding_model: Option<String>,
+    /// Custom headers.
+    headers: Map,
+    /// Request timeout.
+    timeout: Option<Duration>,
+}
+
+impl OpenAiProvider {
+    /// Creates a new instance with the base URL and the default model.
+    #[inline]
+    pub fn new(base_url: impl Into<String>, model: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into(),
+            api_key: None,
+            model: model.into(),
+            embedding_model: None,
+            headers: Map::new(),
+            timeout: None,
+        }
+    }
+
+    /// Attempts to create a new instance with the configuration,
+    /// where the `base_url` is used if the `base-url` is not specified.
+    pub fn try_with_config(config: &Table, base_url: &str) -> Result<Self, Error> {
+        let base_url = config.get_str("base-url").unwrap_or(base_url);
+        if base_url.is_empty() {
+            bail!("field `base-url` should be specified for the AI service");
+        }
+
+        let Some(model) = config.get_str("model") else {
+            bail!("field `model` should be specified for the AI service");
+        };
+
+        let mut provider = Self::new(base_url.trim_end_matches('/'), model);
+        provider.api_key = config.get_str("api-key").map(|s| s.to_owned());
+        provider.embedding_model = config.get_str("embedding-model").map(|s| s.to_owned());
+        if let Some(headers) = config.get_table("headers") {
+            for (key, value) in headers {
+                if let Some(value) = value.as_str() {
+                    provider.headers.upsert(key, value);
+                }
+            }
+        }
+        provider.timeout = config.get_duration("timeout");
+        Ok(provider)
+    }
+
+    /// Sets the API key.
+    #[inline]
+    pub fn api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+
+    /// Sets the embedding model.
+    #[inline]
+    pub fn embedding_model(mut self, model: impl Into<String>) -> Self {
+        self.embedding_model = Some(model.into());
+        self
+    }
+
+    /// Sets a custom header for the requests.
+    #[inline]
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.upsert(name, value.into());
+        self
+    }
+
+    /// Sets the request timeout.
+    #[inline]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Returns the base URL.
+    #[inline]
+    pub fn base_url(&self) -> &str {
+        self.base_url.as_str()
+    }
+
+    /// Returns the default model.
+    #[inline]
+    pub fn model(&self) -> &str {
+        self.model.as_str()
+    }
+
+    /// Builds the request body.
+    fn build_body(&self, request: &ChatRequest, stream: bool) -> Map {
+        let mut body = Map::new();
+        body.upsert("model", request.model().unwrap_or(&self.model));
+        let messages = request
+            .messages()
+            .iter()
+            .map(|message| JsonValue::Object(encode_message(message)))
+            .collect::<Vec<_>>();
+        body.upsert("messages", messages);
+        if let Some(temperature) = request.temperature() {
+            body.upsert("temperature", temperature);
+        }
+        if let Some(max_tokens) = request.max_tokens() {
+            body.upsert("max_tokens", max_tokens);
+        }
+        if !request.stop().is_empty() {
+            body.upsert("stop", request.stop());
+        }
+        if !request.tools().is_empty() {
+            let tools = request
+                .tools()
+                .iter()
+                .map(|tool| {
+                    let mut function = Map::new();
+                    function.upsert("name", tool.name());
+                    if let Some(description) = tool.description() {
+                        function.upsert("description", description);
+                    }
+                    function.upsert("parameters", tool.parameters().clone());
+
+                    let mut map = Map::new();
+                    map.upsert("type", "function");
+                    map.upsert("function", function);
+                    JsonValue::Object(map)
+                })
+                .collect::<Vec<_>>();
+            body.upsert("tools", tools);
+        }
+        if stream {
+            let mut stream_options = Map::new();
+            stream_options.upsert("include_usage", true);
+            body.upsert("stream", true);
+            body.upsert("stream_options", stream_options);
+        }
+        body
+    }
+
+    /// Sends the request to the endpoint and returns the successful response.
+    async fn send(&self, endpoint: &str, body: Map) -> Result<reqwest::Response, Error> {
+        let mut headers = self.headers.clone();
+        if let Some(api_key) = self.api_key.as_deref() {
+            headers.upsert("authorization", format!("Bearer {api_key}"));
+        }
+
+        let mut options = Map::new();
+        options.upsert("method", "POST");
+        options.upsert("data_type", "json");
+        options.upsert("body", body);
+        options.upsert("headers", headers);
+        if let Some(timeout) = self.timeout {
+            options.upsert("timeout", timeout.as_millis() as u64);
+        }
+
+        let mut trace_context = TraceContext::new();
+        trace_context.record_trace_state();
+
+        let url = format!("{}/{}", self.base_url, endpoint);
+        let response = Agent::request_builder(&url, Some(&options))?
+            .header("traceparent", trace_context.traceparent())
+            .header("tracestate", trace_context.tracestate())
+            .send()
+            .await?;
+        let status = response.status();
+        if !status.is_success() {
+            let text = response.text().await.unwrap_or_default();
+            let message = serde_json::from_str::<Map>(&text)
+                .ok()
+                .and_then(|map| {
+                    map.get_object("error")
+                        .and_then(|error| error.get_str("message"))
+                        .map(|s| s.to_owned())
+                })
+                .unwrap_or(text);
+            bail!(
+                "fail to request `{}` with `{}`: {}",
+                endpoint,
+                status,
+                message
+            );
+        }
+        Ok(response)
+    }
+}
+
+impl ChatProvider for OpenAiProvider {
+    async fn chat(&self, request: ChatRequest) -> Result<ChatCompletion, Error> {
+        let body = self.build_body(&request, false);
+        let data = self
+            .send("chat/completions", body)
+            .await?
+            .json::<Map>()
+            .await?;
+        decode_completion(&data)
+    }
+
+    async fn chat_stream(&self, request: ChatRequest) -> Result<ChatStream, Error> {
+        let body = self.build_body(&request, true);
+        let response = self.send("chat/completions", body).await?;
+        let state = (
+            response.bytes_stream().boxed(),
+            Vec::new(),
+            VecDeque::new(),
+            false,
+        );
+        let stream = stream::unfold(state, |mut state| async move {
+            let (bytes_stream, buffer, deltas, done) = &mut state;
+            loop {
+                if let Some(delta) = deltas.pop_front() {
+                    return Some((Ok(delta), state));
+                }
+                if *done {
+                    return None;
+                }
+                match bytes_stream.next().await {
+                    Some(Ok(bytes)) => {
+                        buffer.extend_from_slice(&bytes);
+                    }
+                    Some(Err(err)) => {
+                        *done = true;
+                        return Some((Err(Error::from(err)), state));
+                    }
+                    None => {
+                        *done = true;
+                        buffer.push(b'\n');
+                    }
+                }
+                for data in parse_event_data(buffer) {
+                    if data == "[DONE]" {
+                        *done = true;
+                        break;
+                    }
+                    match serde_json::from_str::<Map>(&data) {
+                        Ok(map) => deltas.extend(decode_delta(&map)),
+                        Err(err) => {
+                            *done = true;
+                            return Some((Err(Error::from(err)), state));
+                        }
+                    }
+                }
+            }
+        });
+        Ok(stream.boxed())
+    }
+}
+
+impl EmbeddingProvider for OpenAiProvider {
+    async fn embed(&self, inputs: Vec<String>) -> Result<Vec<Vec<f32>>, Error> {
+        let num_inputs = inputs.len();
+        let mut body = Map::new();
+        body.upsert(
+            "model",
+            self.embedding_model.as_deref().unwrap_or(&self.model),
+        );
+        body.upsert("input", inputs);
+
+        let data = self.send("embeddings", body).await?.json::<Map>().await?;
+        let mut embeddings = vec![Vec::new(); num_inputs];
+        for item in data.get_array("data").into_iter().flatten() {
+            if let Some(item) = item.as_object()
+                && let Some(embedding) = embeddings.get_mut(item.get_usize("index").unwrap_or(0))
+            {
+                *embedding = item.get_f32_array("embedding").unwrap_or_default();
+            }
+        }
+        if embeddings.iter().any(|embedding| embedding.is_empty()) {
+            bail!("the embeddings do not match the {} inputs", num_inputs);
+        }
+        Ok(embeddings)
+    }
+}
+
+/// Encodes the message as a JSON object.
+fn encode_message(message: &ChatMessage) -> Map {
+    let mut map = Map::new();
+    map.upsert("role", message.role().as_str());
+    if let Some(content) = message.content() {
+        map.upsert("content", content);
+    } else {
+        map.upsert("content", JsonValue::Null);
+    }
+    if let Some(name) = message.name() {
+        map.upsert("name", name);
+    }
+    if let Some(tool_call_id) = message.tool_call_id() {
+        map.upsert("tool_call_id", tool_call_id);
+    }
+    if message.has_tool_calls() {
+        let tool_calls = message
+            .tool_calls()
+            .iter()
+            .map(|tool_call| {
+                let mut function = Map::new();
+                function.upsert("name", tool_call.name());
+                function.upsert("arguments", tool_call.arguments());
+
+                let mut map = Map::new();
+                map.upsert("id", tool_call.id());
+                map.upsert("type", "function");
+                map.upsert("function", function);
+                JsonValue::Object(map)
+            })
+            .collect::<Vec<_>>();
+        map.upsert("tool_calls", tool_calls);
+    }
+    map
+}
+
+/// Decodes the message from a JSON object.
+fn decode_message(map: &Map) -> ChatMessage {
+    let role = map
+        .get_str("role")
+        .and_then(Role::parse)
+        .unwrap_or(Role::Assistant);
+    let tool_calls = map
+        .get_array("tool_calls")
+        .map(|tool_calls| {
+            tool_calls
+                .iter()
+                .filter_map(|v| v.as_object())
+                .map(|tool_call| {
+                    let id = tool_call.get_str("id").unwrap_or_default();
+                    let function = tool_call.get_object("function");
+                    let name = function.and_then(|f| f.get_str("name")).unwrap_or_default();
+                    let arguments = function
+                        .and_then(|f| f.get_str("arguments"))
+                        .unwrap_or_default();
+                    ToolCall::new(id, name, arguments)
+                })
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    let content = map.get_str("content").unwrap_or_default();
+    if tool_calls.is_empty() {
+        ChatMessage::new(role, content)
+    } else {
+        let mut message = ChatMessage::assistant_tool_calls(tool_calls);
+        if !content.is_empty() {
+            message.push_content(content);
+        }
+        message
+    }
+}
+
+/// Decodes the token usage from a JSON object.
+fn decode_usage(map: &Map) -> Usage {
+    let prompt_tokens = map.get_u64("prompt_tokens").unwrap_or_default();
+    let completion_tokens = map.get_u64("completion_tokens").unwrap_or_default();
+    Usage::new(prompt_tokens, completion_tokens)
+}
+
+/// Decodes the chat completion from a JSON object.
+fn decode_completion(data: &Map) -> Result<ChatCompletion, Error> {
+    let Some(choice) = data
+        .get_array("choices")
+        .and_then(|choices| choices.first())
+        .and_then(|choice| choice.as_object())
+    else {
+        bail!("there are no choices in the chat completion");
+    };
+
+    let message = choice
+        .get_object("message")
+        .map(decode_message)
+        .ok_or_else(|| warn!("there is no message in the chat completion"))?;
+    let id = data.get_str("id").unwrap_or_default();
+    let model = data.get_str("model").unwrap_or_default();
+    let mut completion = ChatCompletion::new(id, model, message);
+    if let Some(reason) = choice.get_str("finish_reason") {
+        completion.set_finish_reason(FinishReason::parse(reason));
+    }
+    if let Some(usage) = data.get_object("usage") {
+        completion.set_usage(decode_usage(usage));
+    }
+    Ok(completion)
+}
+
+/// Decodes the streaming delta from a JSON object.
+fn decode_delta(data: &Map) -> Option<ChatDelta> {
+    let mut delta = ChatDelta::default();
+    if let Some(choice) = data
+        .get_array("choices")
+        .and_then(|choices| choices.first())
+        .and_then(|choice| choice.as_object())
+    {
+        if let Some(map) = choice.get_object("delta") {
+            if let Some(content) = map.get_str("content") {
+                delta = ChatDelta::new(content);
+            }
+            if let Some(tool_calls) = map.get_array("tool_calls") {
+                for tool_call in tool_calls.iter().filter_map(|v| v.as_object()) {
+                    let index = tool_call.get_usize("index").unwrap_or_default();
+                    let function = tool_call.get_object("function");
+                    let arguments = function
+                        .and_then(|f| f.get_str("arguments"))
+                        .unwrap_or_default();
+                    let mut tool_call_delta = ToolCallDelta::new(index, arguments);
+                    if let Some(id) = tool_call.get_str("id") {
+                        let name = function.and_then(|f| f.get_str("name")).unwrap_or_default();
+                        tool_call_delta = tool_call_delta.with_function(id, name);
+                    }
+                    delta = delta.with_tool_call(tool_call_delta);
+                }
+            }
+        }
+        if let Some(reason) = choice.get_str("finish_reason") {
+            delta = delta.with_finish_reason(FinishReason::parse(reason));
+        }
+    }
+    if let Some(usage) = data.get_object("usage") {
+        delta = delta.with_usage(decode_usage(usage));
+    }
+    if delta == ChatDelta::default() {
+        return None;
+    }
+    if let Some(id) = data.get_str("id") {
+        let model = data.get_str("model").unwrap_or_default();
+        delta = delta.with_source(id, model);
+    }
+    Some(delta)
+}
+
+/// Parses the data of the complete server-sent events in the buffer,
+/// and leaves the incomplete line in the buffer.
+fn parse_event_data(buffer: &mut Vec<u8>) -> Vec<String> {
+    let mut data = Vec::new();
+    while let Some(index) = buffer.iter().position(|&b| b == b'\n') {
+        let line = buffer.drain(..=index).collect::<Vec<_>>();
+        let line = String::from_utf8_lossy(&line);
+        if let Some(value) = line.trim_end().strip_prefix("data:") {
+            data.push(value.trim_start().to_owned());
+        }
+    }
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_delta, parse_event_data};
+    use crate::{ChatCompletion, FinishReason};
+    use zino_core::Map;
+
+    #[test]
+    fn it_decodes_streaming_deltas() {
+        let mut buffer = concat!(
+            "data: {\"id\":\"c1\",\"model\":\"m\",\"choices\":[{\"delta\":{\"content\":\"Hel\"}}]}\n\n",
+            ": keep-alive\n",
+            "data: {\"id\":\"c1\",\"model\":\"m\",\"choices\":[{\"delta\":{\"content\":\"lo\"},",
+            "\"finish_reason\":\"stop\"}]}\r\n",
+            "data: {\"id\":\"c1\",\"choices\":[],\"usage\":{\"prompt_tokens\":3,\"completion_tokens\":2}}\n",
+            "data: [DONE]\ndata: {\"id\"",
+        )
+        .as_bytes()
+        .to_vec();
+        let data = parse_event_data(&mut buffer);
+        assert_eq!(data.len(), 4);
+        assert_eq!(data[3], "[DONE]");
+        assert_eq!(buffer, b"data: {\"id\"");
+
+        let mut completion = ChatCompletion::default();
+        for item in &data[..3] {
+            let map = serde_json::from_str::<Map>(item).expect("fail to parse the event data");
+            completion.merge_delta(decode_delta(&map).expect("fail to decode the delta"));
+        }
+        assert_eq!(completion.id(), "c1");
+        assert_eq!(completion.content(), Some("Hello"));
+        assert_eq!(completion.finish_reason(), Some(&FinishReason::Stop));
+        assert_eq!(
+            completion.usage().map(|usage| usage.total_tokens()),
+            Some(5)
+        );
+    }
+}
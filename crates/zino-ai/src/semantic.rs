@@ -0,0 +1,499 @@
+//! Semantic search for the models.
+//!
+//! The columns marked with `#[schema(embedding)]` are joined as the text to be embedded.
+//! The vector indexes are kept in process for each table and configured by the
+//! `[semantic-search]` section:
+//!
+//! ```toml
+//! [semantic-search]
+//! service = "default"
+//! index = "hnsw"
+//! distance = "cosine"
+//! accessor = "local"
+//! dir = "vectors"
+//! ```
+//!
+//! Embeddings are computed in [`ModelHooks::before_save`](zino_core::model::ModelHooks)
+//! and indexed in `after_save` once the model has been saved successfully,
+//! and they are removed in `after_delete` once the model has been deleted.
+//!
+//! The vector indexes are not persisted implicitly, so they are lost when the process exits.
+//! With the `storage` feature, they can be saved by [`SemanticSearch::save_vector_index`]
+//! and restored by [`SemanticSearch::load_vector_index`] when the application starts.
+//! Otherwise, the models should be embedded again by [`SemanticSearch::embed_model`].
+
+use super::{AiService, EmbeddingProvider, GlobalAi, SearchHit, VectorIndex, VectorStore};
+use std::{
+    collections::{HashMap, hash_map::Entry},
+    sync::RwLock,
+};
+use toml::Table;
+use zino_core::{
+    JsonValue, LazyLock, Map, bail,
+    error::Error,
+    extension::{JsonObjectExt, JsonValueExt, TomlTableExt},
+    model::{Query, QueryContext},
+    state::State,
+};
+use zino_orm::Schema;
+
+/// Embedding of a model, which can be used as the data of the model hooks.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ModelEmbedding {
+    /// Primary key of the model.
+    id: String,
+    /// Embedding vector.
+    vector: Vec<f32>,
+}
+
+impl ModelEmbedding {
+    /// Returns the primary key of the model.
+    #[inline]
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Returns the embedding vector.
+    #[inline]
+    pub fn vector(&self) -> &[f32] {
+        &self.vector
+    }
+
+    /// Returns `true` if there is no embedding vector.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.vector.is_empty()
+    }
+}
+
+/// Semantic search over the models.
+///
+/// This trait is implemented for all the models with a [`Schema`].
+pub trait SemanticSearch: Schema {
+    /// Returns the names of the columns marked with `#[schema(embedding)]`.
+    fn embedding_columns() -> Vec<&'static str> {
+        Self::columns()
+            .iter()
+            .filter(|col| col.has_attribute("embedding"))
+            .map(|col| col.name())
+            .collect()
+    }
+
+    /// Returns the text to be embedded, which joins the values of the embedding columns.
+    fn embedding_text(&self) -> String {
+        let Ok(JsonValue::Object(map)) = serde_json::to_value(self) else {
+            return String::new();
+        };
+        let mut texts = Vec::new();
+        for col in Self::embedding_columns() {
+            match map.get(col) {
+                Some(JsonValue::String(s)) if !s.is_empty() => texts.push(s.to_owned()),
+                Some(JsonValue::Array(vec)) => {
+                    let words = vec.iter().filter_map(|v| v.as_str()).collect::<Vec<_>>();
+                    if !words.is_empty() {
+                        texts.push(words.join(" "));
+                    }
+                }
+                _ => {}
+            }
+        }
+        texts.join("\n")
+    }
+
+    /// Computes the embedding of the model.
+    /// It returns an empty embedding if the model has no text to be embedded.
+    async fn prepare_embedding(&self) -> Result<ModelEmbedding, Error> {
+        self.prepare_embedding_with(embedding_service()?).await
+    }
+
+    /// Computes the embedding of the model with the provider.
+    /// It returns an empty embedding if the model has no text to be embedded.
+    async fn prepare_embedding_with<P: EmbeddingProvider>(
+        &self,
+        provider: &P,
+    ) -> Result<ModelEmbedding, Error> {
+        let text = self.embedding_text();
+        if text.is_empty() {
+            return Ok(ModelEmbedding::default());
+        }
+
+        let vector = provider.embed_one(text).await?;
+        Ok(ModelEmbedding {
+            id: self.primary_key().to_string(),
+            vector,
+        })
+    }
+
+    /// Adds the embedding into the vector index of the model.
+    fn index_embedding(embedding: ModelEmbedding) -> Result<(), Error> {
+        if embedding.is_empty() {
+            return Ok(());
+        }
+        let Ok(mut indexes) = VECTOR_INDEXES.write() else {
+            bail!("fail to acquire the vector indexes");
+        };
+        let index = match indexes.entry(Self::table_name()) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(VectorStore::try_new(&SEMANTIC_SEARCH_CONFIG)?),
+        };
+        index.insert(embedding.id, embedding.vector)
+    }
+
+    /// Adds the embedding computed in the `before_save` hook into the vector index
+    /// if the model has been saved successfully. It should be called in the `after_save` hook.
+    fn index_saved_embedding(ctx: &QueryContext, embedding: ModelEmbedding) -> Result<(), Error> {
+        if ctx.is_success() {
+            Self::index_embedding(embedding)
+        } else {
+            ctx.record_error("fail to save a model into the table");
+            Ok(())
+        }
+    }
+
+    /// Removes the embedding of the model from the vector index
+    /// if the model has been deleted successfully. It should be called in the `after_delete` hook.
+    fn remove_deleted_embedding(&self, ctx: &QueryContext) -> bool {
+        ctx.is_success() && Self::remove_embedding(self.primary_key())
+    }
+
+    /// Computes the embedding of the model and adds it into the vector index.
+    async fn embed_model(&self) -> Result<(), Error> {
+        let embedding = self.prepare_embedding().await?;
+        Self::index_embedding(embedding)
+    }
+
+    /// Removes the embedding of the model from the vector index.
+    fn remove_embedding(primary_key: &Self::PrimaryKey) -> bool {
+        VECTOR_INDEXES.write().is_ok_and(|mut indexes| {
+            indexes
+                .get_mut(Self::table_name())
+                .is_some_and(|index| index.remove(&primary_key.to_string()))
+        })
+    }
+
+    /// Returns the number of embeddings in the vector index of the model.
+    fn count_embeddings() -> usize {
+        VECTOR_INDEXES
+            .read()
+            .ok()
+            .and_then(|indexes| indexes.get(Self::table_name()).map(|index| index.len()))
+            .unwrap_or_default()
+    }
+
+    /// Searches the primary keys of the top `k` models which are most similar to the query.
+    async fn semantic_search(query: &str, k: usize) -> Result<Vec<SearchHit>, Error> {
+        Self::semantic_search_with(embedding_service()?, query, k).await
+    }
+
+    /// Searches the primary keys of the top `k` models which are most similar to the query
+    /// with the provider.
+    async fn semantic_search_with<P: EmbeddingProvider>(
+        provider: &P,
+        query: &str,
+        k: usize,
+    ) -> Result<Vec<SearchHit>, Error> {
+        let vector = provider.embed_one(query.to_owned()).await?;
+        let Ok(indexes) = VECTOR_INDEXES.read() else {
+            bail!("fail to acquire the vector indexes");
+        };
+        let hits = indexes
+            .get(Self::table_name())
+            .map(|index| index.search(&vector, k))
+            .unwrap_or_default();
+        Ok(hits)
+    }
+
+    /// Finds the top `k` models which are most similar to the query,
+    /// along with the similarity scores in descending order.
+    async fn find_similar(query: &str, k: usize) -> Result<Vec<(Map, f32)>, Error> {
+        Self::find_similar_with(embedding_service()?, query, k).await
+    }
+
+    /// Finds the top `k` models which are most similar to the query with the provider,
+    /// along with the similarity scores in descending order.
+    async fn find_similar_with<P: EmbeddingProvider>(
+        provider: &P,
+        query: &str,
+        k: usize,
+    ) -> Result<Vec<(Map, f32)>, Error> {
+        let hits = Self::semantic_search_with(provider, query, k).await?;
+        if hits.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let primary_key_name = Self::primary_key_name();
+        let ids = hits.iter().map(|hit| hit.id()).collect::<Vec<_>>();
+        let mut filters = Map::new();
+        filters.upsert(primary_key_name, Map::from_entry("$in", ids));
+
+        let mut query = Query::new(filters);
+        query.set_limit(k);
+
+        let mut models = Self::find::<Map>(&query)
+            .await?
+            .into_iter()
+            .filter_map(|model| {
+                let id = model.get(primary_key_name)?.to_string_unquoted();
+                Some((id, model))
+            })
+            .collect::<HashMap<_, _>>();
+        let entries = hits
+            .into_iter()
+            .filter_map(|hit| models.remove(hit.id()).map(|model| (model, hit.score())))
+            .collect();
+        Ok(entries)
+    }
+
+    /// Saves the vector index of the model with the configured storage accessor.
+    #[cfg(feature = "storage")]
+    async fn save_vector_index() -> Result<(), Error> {
+        let (accessor, path) = vector_index_path(Self::table_name())?;
+        let index = VECTOR_INDEXES
+            .read()
+            .ok()
+            .and_then(|indexes| indexes.get(Self::table_name()).cloned());
+        match index {
+            Some(index) => index.save(accessor, &path).await,
+            None => Ok(()),
+        }
+    }
+
+    /// Loads the vector index of the model with the configured storage accessor.
+    #[cfg(feature = "storage")]
+    async fn load_vector_index() -> Result<(), Error> {
+        let (accessor, path) = vector_index_path(Self::table_name())?;
+        let index = VectorStore::load(accessor, &path).await?;
+        let Ok(mut indexes) = VECTOR_INDEXES.write() else {
+            bail!("fail to acquire the vector indexes");
+        };
+        indexes.insert(Self::table_name(), index);
+        Ok(())
+    }
+}
+
+impl<M: Schema> SemanticSearch for M {}
+
+/// Returns the AI service for the embeddings.
+fn embedding_service() -> Result<&'static AiService, Error> {
+    let service = SEMANTIC_SEARCH_CONFIG
+        .get_str("service")
+        .unwrap_or("default");
+    match GlobalAi::get(service) {
+        Some(service) => Ok(service),
+        None => bail!("AI service `{}` does not exist", service),
+    }
+}
+
+/// Returns the storage accessor and the path of the vector index for the table.
+#[cfg(feature = "storage")]
+fn vector_index_path(table_name: &str) -> Result<(&'static str, String), Error> {
+    let Some(accessor) = SEMANTIC_SEARCH_CONFIG.get_str("accessor") else {
+        bail!("field `accessor` should be specified for the semantic search");
+    };
+    let dir = SEMANTIC_SEARCH_CONFIG.get_str("dir").unwrap_or("vectors");
+    let path = format!("{}/{table_name}.json", dir.trim_end_matches('/'));
+    Ok((accessor, path))
+}
+
+/// Configuration of the semantic search.
+static SEMANTIC_SEARCH_CONFIG: LazyLock<Table> = LazyLock::new(|| {
+    State::shared()
+        .config()
+        .get_table("semantic-search")
+        .cloned()
+        .unwrap_or_default()
+});
+
+/// Vector indexes keyed by the table name.
+static VECTOR_INDEXES: LazyLock<RwLock<HashMap<&'static str, VectorStore>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+
+#[cfg(test)]
+mod tests {
+    use super::SemanticSearch;
+    use crate::MockProvider;
+    use serde::{Deserialize, Serialize};
+    use std::{env, fs, path::PathBuf, process};
+    use toml::Table;
+    use zino_core::{
+        LazyLock, Map,
+        error::Error,
+        extension::JsonObjectExt,
+        model::{Column, Model, ModelHooks, QueryContext},
+    };
+    use zino_orm::{ConnectionPool, Dialect, Executor, PoolManager, Schema};
+
+    /// An article with the embedding columns `title` and `tags`.
+    #[derive(Debug, Clone, Default, Serialize, Deserialize)]
+    struct Article {
+        id: i64,
+        title: String,
+        tags: Vec<String>,
+    }
+
+    impl Model for Article {
+        const MODEL_NAME: &'static str = "article";
+    }
+
+    impl ModelHooks for Article {
+        type Data = Map;
+        type Extension = Map;
+    }
+
+    impl Schema for Article {
+        type PrimaryKey = i64;
+
+        const TABLE_NAME: Option<&'static str> = Some("test_article");
+
+        fn primary_key(&self) -> &Self::PrimaryKey {
+            &self.id
+        }
+
+        fn schema() -> &'static apache_avro::Schema {
+            static ARTICLE_SCHEMA: apache_avro::Schema = apache_avro::Schema::Null;
+            &ARTICLE_SCHEMA
+        }
+
+        fn columns() -> &'static [Column<'static>] {
+            ARTICLE_COLUMNS.as_slice()
+        }
+
+        fn fields() -> &'static [&'static str] {
+            &["id", "title", "tags"]
+        }
+
+        fn read_only_fields() -> &'static [&'static str] {
+            &["id"]
+        }
+
+        fn write_only_fields() -> &'static [&'static str] {
+            &[]
+        }
+
+        fn dialect() -> Dialect {
+            Dialect::Sqlite
+        }
+
+        async fn acquire_reader() -> Result<&'static ConnectionPool, Error> {
+            Ok(&CONNECTION_POOL)
+        }
+
+        async fn acquire_writer() -> Result<&'static ConnectionPool, Error> {
+            Ok(&CONNECTION_POOL)
+        }
+    }
+
+    /// Columns of the `Article` model.
+    static ARTICLE_COLUMNS: LazyLock<Vec<Column<'static>>> = LazyLock::new(|| {
+        let mut id = Column::new("id", "i64", true);
+        id.set_extra_attribute("primary_key", true);
+        let mut title = Column::new("title", "String", true);
+        title.set_extra_attribute("embedding", true);
+        let mut tags = Column::new("tags", "Vec<String>", true);
+        tags.set_extra_attribute("embedding", true);
+        vec![id, title, tags]
+    });
+
+    /// Path of the SQLite database for the tests.
+    static DATABASE_PATH: LazyLock<PathBuf> =
+        LazyLock::new(|| env::temp_dir().join(format!("zino-ai-{}.db", process::id())));
+
+    /// Connection pool of the `Article` model.
+    static CONNECTION_POOL: LazyLock<ConnectionPool> = LazyLock::new(|| {
+        let database = &*DATABASE_PATH;
+        let mut config = Table::new();
+        config.insert("name".to_owned(), "article".into());
+        config.insert(
+            "database".to_owned(),
+            database.to_string_lossy().as_ref().into(),
+        );
+        ConnectionPool::with_config(Box::leak(Box::new(config)))
+    });
+
+    #[tokio::test]
+    async fn it_searches_similar_models() {
+        let pool = CONNECTION_POOL.pool();
+        pool.execute("DROP TABLE IF EXISTS test_article;")
+            .await
+            .expect("fail to execute the statement");
+        pool.execute("CREATE TABLE test_article (id INTEGER PRIMARY KEY, title TEXT, tags TEXT);")
+            .await
+            .expect("fail to execute the statement");
+        pool.execute(
+            "INSERT INTO test_article (id, title, tags) VALUES \
+                (1, 'Rust async runtime', '[\"tokio\"]'), \
+                (2, 'Baking sourdough bread', '[\"food\"]'), \
+                (3, '', '[]');",
+        )
+        .await
+        .expect("fail to execute the statement");
+
+        let provider = MockProvider::new();
+        let articles = [
+            (1, "Rust async runtime", vec!["tokio"]),
+            (2, "Baking sourdough bread", vec!["food"]),
+            (3, "", vec![]),
+        ]
+        .map(|(id, title, tags)| Article {
+            id,
+            title: title.to_owned(),
+            tags: tags.into_iter().map(|tag| tag.to_owned()).collect(),
+        });
+        assert_eq!(Article::embedding_columns(), ["title", "tags"]);
+        assert_eq!(articles[0].embedding_text(), "Rust async runtime\ntokio");
+
+        let mut ctx = QueryContext::new(Article::MODEL_NAME);
+        ctx.set_query_result(1, true);
+        for article in &articles {
+            let embedding = article
+                .prepare_embedding_with(&provider)
+                .await
+                .expect("fail to prepare the embedding");
+            assert_eq!(embedding.is_empty(), article.id == 3);
+            Article::index_saved_embedding(&ctx, embedding).expect("fail to index the embedding");
+        }
+        assert_eq!(Article::count_embeddings(), 2);
+
+        // The embedding is not indexed if the model fails to be saved
+        let failed_ctx = QueryContext::new(Article::MODEL_NAME);
+        let embedding = Article {
+            id: 4,
+            title: "Rust web framework".to_owned(),
+            tags: Vec::new(),
+        }
+        .prepare_embedding_with(&provider)
+        .await
+        .expect("fail to prepare the embedding");
+        Article::index_saved_embedding(&failed_ctx, embedding)
+            .expect("fail to index the embedding");
+        assert_eq!(Article::count_embeddings(), 2);
+
+        let hits = Article::semantic_search_with(&provider, "tokio runtime", 2)
+            .await
+            .expect("fail to search the models");
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[0].id(), "1");
+        assert!(hits[0].score() > hits[1].score());
+
+        let models = Article::find_similar_with(&provider, "sourdough bread", 2)
+            .await
+            .expect("fail to find the models");
+        assert_eq!(models.len(), 2);
+        assert_eq!(models[0].0.get_str("title"), Some("Baking sourdough bread"));
+        assert_eq!(models[1].0.get_i64("id"), Some(1));
+        assert!(models[0].1 > models[1].1);
+
+        assert!(articles[1].remove_deleted_embedding(&ctx));
+        assert_eq!(Article::count_embeddings(), 1);
+        assert!(!articles[1].remove_deleted_embedding(&ctx));
+
+        let models = Article::find_similar_with(&provider, "sourdough bread", 2)
+            .await
+            .expect("fail to find the models");
+        assert_eq!(models.len(), 1);
+        assert_eq!(models[0].0.get_i64("id"), Some(1));
+
+        CONNECTION_POOL.pool().close().await;
+        fs::remove_file(&*DATABASE_PATH).expect("fail to remove the database");
+    }
+}
@@ -0,0 +1,138 @@
+use self::AiBackend::*;
+use super::{
+    ChatCompletion, ChatProvider, ChatRequest, ChatStream, EmbeddingProvider, MockProvider,
+    OpenAiProvider,
+};
+use toml::Table;
+use zino_core::{bail, error::Error, extension::TomlTableExt};
+
+/// Supported backends of the AI services.
+#[non_exhaustive]
+enum AiBackend {
+    /// Mock provider.
+    Mock(MockProvider),
+    /// OpenAI-compatible provider.
+    OpenAi(OpenAiProvider),
+}
+
+/// An AI service configured by the `[[ai]]` section.
+///
+/// ```toml
+/// [[ai]]
+/// type = "openai"
+/// name = "default"
+/// base-url = "https://api.openai.com/v1"
+/// api-key = "sk-..."
+/// model = "gpt-4o-mini"
+/// embedding-model = "text-embedding-3-small"
+/// timeout = "60s"
+/// ```
+pub struct AiService {
+    /// Provider type.
+    provider: &'static str,
+    /// Service name.
+    name: String,
+    /// Backend.
+    backend: AiBackend,
+}
+
+impl AiService {
+    /// Constructs a new instance with the configuration, returning an error if it fails.
+    ///
+    /// Currently, we have built-in support for the following provider types:
+    ///
+    /// - `mock`
+    /// - `openai`
+    /// - `deepseek`
+    /// - `ollama`
+    ///
+    /// Any other type is treated as an OpenAI-compatible provider
+    /// if the `base-url` has been configured.
+    pub fn try_new(config: &Table) -> Result<Self, Error> {
+        let provider_type = config.get_str("type").unwrap_or("unknown");
+        let (provider, backend) = match provider_type {
+            "mock" => ("mock", Mock(MockProvider::try_with_config(config)?)),
+            "openai" => {
+                let provider = OpenAiProvider::try_with_config(config, OPENAI_BASE_URL)?;
+                ("openai", OpenAi(provider))
+            }
+            "deepseek" => {
+                let provider = OpenAiProvider::try_with_config(config, DEEPSEEK_BASE_URL)?;
+                ("openai", OpenAi(provider))
+            }
+            "ollama" => {
+                let provider = OpenAiProvider::try_with_config(config, OLLAMA_BASE_URL)?;
+                ("openai", OpenAi(provider))
+            }
+            _ => {
+                if config.contains_key("base-url") {
+                    let provider = OpenAiProvider::try_with_config(config, "")?;
+                    ("openai", OpenAi(provider))
+                } else {
+                    bail!("AI provider type `{}` is unsupported", provider_type);
+                }
+            }
+        };
+        let name = config.get_str("name").unwrap_or(provider_type);
+        Ok(Self {
+            provider,
+            name: name.to_owned(),
+            backend,
+        })
+    }
+
+    /// Returns the provider type.
+    #[inline]
+    pub fn provider(&self) -> &'static str {
+        self.provider
+    }
+
+    /// Returns the service name.
+    #[inline]
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    /// Returns the default model.
+    #[inline]
+    pub fn model(&self) -> &str {
+        match &self.backend {
+            Mock(provider) => provider.model(),
+            OpenAi(provider) => provider.model(),
+        }
+    }
+}
+
+impl ChatProvider for AiService {
+    async fn chat(&self, request: ChatRequest) -> Result<ChatCompletion, Error> {
+        match &self.backend {
+            Mock(provider) => provider.chat(request).await,
+            OpenAi(provider) => provider.chat(request).await,
+        }
+    }
+
+    async fn chat_stream(&self, request: ChatRequest) -> Result<ChatStream, Error> {
+        match &self.backend {
+            Mock(provider) => provider.chat_stream(request).await,
+            OpenAi(provider) => provider.chat_stream(request).await,
+        }
+    }
+}
+
+impl EmbeddingProvider for AiService {
+    async fn embed(&self, inputs: Vec<String>) -> Result<Vec<Vec<f32>>, Error> {
+        match &self.backend {
+            Mock(provider) => provider.embed(inputs).await,
+            OpenAi(provider) => provider.embed(inputs).await,
+        }
+    }
+}
+
+/// Default base URL of the OpenAI API.
+const OPENAI_BASE_URL: &str = "https://api.openai.com/v1";
+
+/// Default base URL of the DeepSeek API.
+const DEEPSEEK_BASE_URL: &str = "https://api.deepseek.com/v1";
+
+/// Default base URL of the Ollama OpenAI-compatible API.
+const OLLAMA_BASE_URL: &str = "http://localhost:11434/v1";
@@ -0,0 +1,131 @@
+use super::{Distance, SearchHit, VectorIndex};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use zino_core::error::Error;
+
+/// A brute-force index which computes the exact similarities.
+///
+/// It is suitable for tens of thousands of vectors.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FlatIndex {
+    /// Distance metric.
+    distance: Distance,
+    /// Dimensions of the vectors.
+    dimensions: usize,
+    /// Vectors keyed by the ID.
+    vectors: BTreeMap<String, Vec<f32>>,
+}
+
+impl FlatIndex {
+    /// Creates a new instance with the distance metric.
+    #[inline]
+    pub fn new(distance: Distance) -> Self {
+        Self {
+            distance,
+            dimensions: 0,
+            vectors: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the vector with the ID.
+    #[inline]
+    pub fn get(&self, id: &str) -> Option<&[f32]> {
+        self.vectors.get(id).map(|v| v.as_slice())
+    }
+}
+
+impl VectorIndex for FlatIndex {
+    #[inline]
+    fn distance(&self) -> Distance {
+        self.distance
+    }
+
+    #[inline]
+    fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.vectors.len()
+    }
+
+    fn insert(&mut self, id: String, vector: Vec<f32>) -> Result<(), Error> {
+        super::check_dimensions(self.dimensions, &vector)?;
+        self.dimensions = vector.len();
+        self.vectors.insert(id, vector);
+        Ok(())
+    }
+
+    fn remove(&mut self, id: &str) -> bool {
+        let removed = self.vectors.remove(id).is_some();
+        if self.vectors.is_empty() {
+            self.dimensions = 0;
+        }
+        removed
+    }
+
+    fn search(&self, query: &[f32], k: usize) -> Vec<SearchHit> {
+        let mut hits = self
+            .vectors
+            .iter()
+            .map(|(id, vector)| SearchHit::new(id, self.distance.score(query, vector)))
+            .collect::<Vec<_>>();
+        hits.sort_by(|a, b| b.score().total_cmp(&a.score()));
+        hits.truncate(k);
+        hits
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FlatIndex;
+    use crate::{Distance, VectorIndex, VectorStore};
+
+    #[test]
+    fn it_restores_the_persisted_index() {
+        let mut index = FlatIndex::new(Distance::Euclidean);
+        index
+            .insert("a".to_owned(), vec![1.0, 0.0, 0.0])
+            .expect("fail to insert the vector");
+        index
+            .insert("b".to_owned(), vec![0.0, 1.0, 0.0])
+            .expect("fail to insert the vector");
+        index
+            .insert("c".to_owned(), vec![0.9, 0.1, 0.0])
+            .expect("fail to insert the vector");
+        assert!(index.remove("b"));
+
+        let store = VectorStore::Flat(index);
+        let bytes = store.to_bytes().expect("fail to encode the vector store");
+        let mut restored =
+            VectorStore::from_bytes(&bytes).expect("fail to decode the vector store");
+        let VectorStore::Flat(index) = &restored else {
+            panic!("the restored index should be a flat index");
+        };
+        assert_eq!(index.get("a"), Some(&[1.0, 0.0, 0.0][..]));
+        assert_eq!(index.get("b"), None);
+        assert_eq!(restored.distance(), Distance::Euclidean);
+        assert_eq!(restored.dimensions(), 3);
+        assert_eq!(restored.len(), 2);
+
+        let query = [1.0, 0.0, 0.0];
+        assert_eq!(restored.search(&query, 2), store.search(&query, 2));
+        assert_eq!(restored.search(&query, 1)[0].id(), "a");
+
+        // The dimensions are checked after the index has been restored
+        assert!(restored.insert("d".to_owned(), vec![1.0, 0.0]).is_err());
+        restored
+            .insert("d".to_owned(), vec![0.0, 0.0, 1.0])
+            .expect("fail to insert the vector");
+        assert_eq!(restored.len(), 3);
+
+        // The missing fields are filled with the default values
+        let store = VectorStore::from_bytes(br#"{"type":"flat"}"#)
+            .expect("fail to decode the vector store");
+        assert_eq!(store.distance(), Distance::Cosine);
+        assert!(store.is_empty());
+        assert!(VectorStore::from_bytes(br#"{"type":"unknown"}"#).is_err());
+    }
+}
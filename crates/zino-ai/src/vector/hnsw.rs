@@ -0,0 +1,402 @@
+use super::{Distance, SearchHit, VectorIndex};
+use serde::{Deserialize, Serialize};
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::{BinaryHeap, HashMap, HashSet},
+};
+use zino_core::error::Error;
+
+/// An approximate index based on the Hierarchical Navigable Small World graphs.
+///
+/// Removed vectors are kept as tombstones to preserve the connectivity of the graph,
+/// and the graph is rebuilt once the tombstones outnumber the live vectors.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HnswIndex {
+    /// Distance metric.
+    distance: Distance,
+    /// Dimensions of the vectors.
+    dimensions: usize,
+    /// Maximum number of connections for each node in the upper layers.
+    max_connections: usize,
+    /// Size of the dynamic candidate list for the construction.
+    ef_construction: usize,
+    /// Size of the dynamic candidate list for the search.
+    ef_search: usize,
+    /// Nodes of the graph.
+    nodes: Vec<Node>,
+    /// Slots of the live nodes keyed by the ID.
+    slots: HashMap<String, usize>,
+    /// Entry point of the graph.
+    entry_point: Option<usize>,
+    /// State of the random number generator for the node levels.
+    rng_state: u64,
+}
+
+/// A node of the graph.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Node {
+    /// ID of the vector.
+    id: String,
+    /// Vector.
+    vector: Vec<f32>,
+    /// Neighbors in each layer.
+    neighbors: Vec<Vec<usize>>,
+    /// A flag indicating whether the node has been removed.
+    deleted: bool,
+}
+
+/// A candidate node with the distance to the query.
+#[derive(Debug, Clone, Copy)]
+struct Candidate {
+    /// Distance to the query, where a smaller value means more similar.
+    distance: f32,
+    /// Slot of the node.
+    slot: usize,
+}
+
+impl PartialEq for Candidate {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.distance
+            .total_cmp(&other.distance)
+            .then(self.slot.cmp(&other.slot))
+    }
+}
+
+impl HnswIndex {
+    /// Creates a new instance with the distance metric.
+    #[inline]
+    pub fn new(distance: Distance) -> Self {
+        Self {
+            distance,
+            ..Self::default()
+        }
+    }
+
+    /// Sets the maximum number of connections for each node in the upper layers.
+    /// The nodes in the bottom layer have twice as many connections.
+    #[inline]
+    pub fn max_connections(mut self, max_connections: usize) -> Self {
+        self.max_connections = max_connections.max(2);
+        self
+    }
+
+    /// Sets the size of the dynamic candidate list for the construction.
+    #[inline]
+    pub fn ef_construction(mut self, ef_construction: usize) -> Self {
+        self.ef_construction = ef_construction.max(1);
+        self
+    }
+
+    /// Sets the size of the dynamic candidate list for the search.
+    #[inline]
+    pub fn ef_search(mut self, ef_search: usize) -> Self {
+        self.ef_search = ef_search.max(1);
+        self
+    }
+
+    /// Rebuilds the graph without the removed vectors.
+    pub fn compact(&mut self) {
+        let nodes = std::mem::take(&mut self.nodes);
+        self.slots.clear();
+        self.entry_point = None;
+        for node in nodes.into_iter().filter(|node| !node.deleted) {
+            self.insert_node(node.id, node.vector);
+        }
+    }
+
+    /// Returns the distance between the query and the node.
+    #[inline]
+    fn node_distance(&self, query: &[f32], slot: usize) -> f32 {
+        -self.distance.score(query, &self.nodes[slot].vector)
+    }
+
+    /// Generates a random level for a new node.
+    fn random_level(&mut self) -> usize {
+        // Xorshift64* generator, which is deterministic for the persisted state.
+        let mut state = self.rng_state.max(1);
+        state ^= state >> 12;
+        state ^= state << 25;
+        state ^= state >> 27;
+        self.rng_state = state;
+
+        let random = state.wrapping_mul(0x2545_f491_4f6c_dd1d) >> 11;
+        let uniform = (random as f64 + 1.0) / (1_u64 << 53) as f64;
+        let level = -uniform.ln() / (self.max_connections as f64).ln();
+        (level as usize).min(MAX_LEVEL)
+    }
+
+    /// Searches the nearest nodes in the layer, sorted by the distance in ascending order.
+    fn search_layer(
+        &self,
+        query: &[f32],
+        entry_points: &[usize],
+        ef: usize,
+        layer: usize,
+    ) -> Vec<Candidate> {
+        let mut visited = HashSet::new();
+        let mut candidates = BinaryHeap::new();
+        let mut results = BinaryHeap::new();
+        for &slot in entry_points {
+            if visited.insert(slot) {
+                let candidate = Candidate {
+                    distance: self.node_distance(query, slot),
+                    slot,
+                };
+                candidates.push(Reverse(candidate));
+                results.push(candidate);
+            }
+        }
+        while results.len() > ef {
+            results.pop();
+        }
+
+        while let Some(Reverse(candidate)) = candidates.pop() {
+            if let Some(farthest) = results.peek()
+                && results.len() >= ef
+                && candidate.distance > farthest.distance
+            {
+                break;
+            }
+
+            let neighbors = self.nodes[candidate.slot].neighbors.get(layer);
+            for &slot in neighbors.into_iter().flatten() {
+                if !visited.insert(slot) {
+                    continue;
+                }
+
+                let distance = self.node_distance(query, slot);
+                if results.len() < ef
+                    || results
+                        .peek()
+                        .is_some_and(|farthest| distance < farthest.distance)
+                {
+                    let neighbor = Candidate { distance, slot };
+                    candidates.push(Reverse(neighbor));
+                    results.push(neighbor);
+                    if results.len() > ef {
+                        results.pop();
+                    }
+                }
+            }
+        }
+        results.into_sorted_vec()
+    }
+
+    /// Inserts a new node into the graph.
+    fn insert_node(&mut self, id: String, vector: Vec<f32>) {
+        let level = self.random_level();
+        let slot = self.nodes.len();
+        self.nodes.push(Node {
+            id: id.clone(),
+            vector,
+            neighbors: vec![Vec::new(); level + 1],
+            deleted: false,
+        });
+        self.slots.insert(id, slot);
+
+        let Some(entry_point) = self.entry_point else {
+            self.entry_point = Some(slot);
+            return;
+        };
+        let query = self.nodes[slot].vector.clone();
+        let top_level = self.nodes[entry_point].neighbors.len() - 1;
+        let mut entry_points = vec![entry_point];
+        for layer in (level + 1..=top_level).rev() {
+            let nearest = self.search_layer(&query, &entry_points, 1, layer);
+            entry_points = nearest.into_iter().map(|c| c.slot).collect();
+        }
+        for layer in (0..=level.min(top_level)).rev() {
+            let candidates = self.search_layer(&query, &entry_points, self.ef_construction, layer);
+            let max_connections = self.layer_connections(layer);
+            let selected = candidates
+                .iter()
+                .take(max_connections)
+                .map(|c| c.slot)
+                .collect::<Vec<_>>();
+            for &neighbor in &selected {
+                let mut links = std::mem::take(&mut self.nodes[neighbor].neighbors[layer]);
+                links.push(slot);
+                if links.len() > max_connections {
+                    let base = &self.nodes[neighbor].vector;
+                    let mut links_with_distance = links
+                        .iter()
+                        .map(|&link| (self.node_distance(base, link), link))
+                        .collect::<Vec<_>>();
+                    links_with_distance.sort_by(|a, b| a.0.total_cmp(&b.0));
+                    links = links_with_distance
+                        .into_iter()
+                        .take(max_connections)
+                        .map(|(_, link)| link)
+                        .collect();
+                }
+                self.nodes[neighbor].neighbors[layer] = links;
+            }
+            self.nodes[slot].neighbors[layer] = selected;
+            entry_points = candidates.into_iter().map(|c| c.slot).collect();
+        }
+        if level > top_level {
+            self.entry_point = Some(slot);
+        }
+    }
+
+    /// Returns the maximum number of connections in the layer.
+    #[inline]
+    fn layer_connections(&self, layer: usize) -> usize {
+        if layer == 0 {
+            self.max_connections * 2
+        } else {
+            self.max_connections
+        }
+    }
+}
+
+impl Default for HnswIndex {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            distance: Distance::default(),
+            dimensions: 0,
+            max_connections: 16,
+            ef_construction: 200,
+            ef_search: 64,
+            nodes: Vec::new(),
+            slots: HashMap::new(),
+            entry_point: None,
+            rng_state: 0x853c_49e6_748f_ea9b,
+        }
+    }
+}
+
+impl VectorIndex for HnswIndex {
+    #[inline]
+    fn distance(&self) -> Distance {
+        self.distance
+    }
+
+    #[inline]
+    fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    fn insert(&mut self, id: String, vector: Vec<f32>) -> Result<(), Error> {
+        super::check_dimensions(self.dimensions, &vector)?;
+        self.dimensions = vector.len();
+        if let Some(slot) = self.slots.remove(&id) {
+            self.nodes[slot].deleted = true;
+        }
+        self.insert_node(id, vector);
+        Ok(())
+    }
+
+    fn remove(&mut self, id: &str) -> bool {
+        let Some(slot) = self.slots.remove(id) else {
+            return false;
+        };
+        self.nodes[slot].deleted = true;
+        if self.slots.is_empty() {
+            self.nodes.clear();
+            self.entry_point = None;
+            self.dimensions = 0;
+        } else if self.nodes.len() > 2 * self.slots.len() + MIN_TOMBSTONES {
+            self.compact();
+        }
+        true
+    }
+
+    fn search(&self, query: &[f32], k: usize) -> Vec<SearchHit> {
+        let Some(entry_point) = self.entry_point else {
+            return Vec::new();
+        };
+        if k == 0 || query.len() != self.dimensions {
+            return Vec::new();
+        }
+
+        let top_level = self.nodes[entry_point].neighbors.len() - 1;
+        let mut entry_points = vec![entry_point];
+        for layer in (1..=top_level).rev() {
+            let nearest = self.search_layer(query, &entry_points, 1, layer);
+            entry_points = nearest.into_iter().map(|c| c.slot).collect();
+        }
+
+        let ef = self.ef_search.max(k) + (self.nodes.len() - self.slots.len()).min(k);
+        self.search_layer(query, &entry_points, ef, 0)
+            .into_iter()
+            .filter(|c| !self.nodes[c.slot].deleted)
+            .take(k)
+            .map(|c| SearchHit::new(&self.nodes[c.slot].id, -c.distance))
+            .collect()
+    }
+}
+
+/// Maximum level of the nodes.
+const MAX_LEVEL: usize = 16;
+
+/// Minimum number of the tombstones to trigger the compaction.
+const MIN_TOMBSTONES: usize = 64;
+
+#[cfg(test)]
+mod tests {
+    use super::HnswIndex;
+    use crate::{Distance, FlatIndex, VectorIndex};
+
+    #[test]
+    fn it_matches_the_exact_search() {
+        let mut flat_index = FlatIndex::new(Distance::Cosine);
+        let mut hnsw_index = HnswIndex::new(Distance::Cosine).max_connections(8);
+        let mut state = 42_u64;
+        let mut vectors = Vec::new();
+        for i in 0..500 {
+            let vector = (0..16)
+                .map(|_| {
+                    state = state
+                        .wrapping_mul(6364136223846793005)
+                        .wrapping_add(1442695040888963407);
+                    (state >> 40) as f32 / (1 << 24) as f32 - 0.5
+                })
+                .collect::<Vec<_>>();
+            flat_index
+                .insert(i.to_string(), vector.clone())
+                .expect("fail to insert the model");
+            hnsw_index
+                .insert(i.to_string(), vector.clone())
+                .expect("fail to insert the model");
+            vectors.push(vector);
+        }
+        for i in 0..100 {
+            assert!(hnsw_index.remove(&(i * 5).to_string()));
+            flat_index.remove(&(i * 5).to_string());
+        }
+        assert_eq!(hnsw_index.len(), 400);
+
+        let mut num_matches = 0;
+        for vector in vectors.iter().take(50) {
+            let expected = flat_index.search(vector, 10);
+            let hits = hnsw_index.search(vector, 10);
+            num_matches += hits.iter().filter(|hit| expected.contains(hit)).count();
+        }
+        assert!(num_matches >= 450, "recall is too low: {num_matches}/500");
+    }
+}
@@ -0,0 +1,264 @@
+//! Vector indexes for the similarity search.
+
+use self::VectorStore::*;
+use serde::{Deserialize, Serialize};
+use toml::Table;
+use zino_core::{bail, error::Error, extension::TomlTableExt};
+
+mod flat;
+mod hnsw;
+
+pub use flat::FlatIndex;
+pub use hnsw::HnswIndex;
+
+/// Distance metric of the vectors.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum Distance {
+    /// Cosine similarity.
+    #[default]
+    Cosine,
+    /// Euclidean distance.
+    Euclidean,
+    /// Dot product.
+    DotProduct,
+}
+
+impl Distance {
+    /// Parses the distance metric.
+    pub fn parse(distance: &str) -> Option<Self> {
+        match distance {
+            "cosine" => Some(Self::Cosine),
+            "euclidean" | "l2" => Some(Self::Euclidean),
+            "dot_product" | "dot-product" | "ip" => Some(Self::DotProduct),
+            _ => None,
+        }
+    }
+
+    /// Returns the similarity score of two vectors, where a larger value means more similar.
+    pub fn score(&self, a: &[f32], b: &[f32]) -> f32 {
+        let dot_product = a.iter().zip(b).map(|(x, y)| x * y).sum::<f32>();
+        match self {
+            Self::Cosine => {
+                let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+                let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+                if norm_a > 0.0 && norm_b > 0.0 {
+                    dot_product / (norm_a * norm_b)
+                } else {
+                    0.0
+                }
+            }
+            Self::Euclidean => {
+                let distance = a
+                    .iter()
+                    .zip(b)
+                    .map(|(x, y)| (x - y) * (x - y))
+                    .sum::<f32>()
+                    .sqrt();
+                1.0 / (1.0 + distance)
+            }
+            Self::DotProduct => dot_product,
+        }
+    }
+}
+
+/// A search hit of the vector index.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchHit {
+    /// ID of the vector.
+    id: String,
+    /// Similarity score.
+    score: f32,
+}
+
+impl SearchHit {
+    /// Creates a new instance.
+    #[inline]
+    pub fn new(id: impl Into<String>, score: f32) -> Self {
+        Self {
+            id: id.into(),
+            score,
+        }
+    }
+
+    /// Returns the ID of the vector.
+    #[inline]
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Returns the similarity score.
+    #[inline]
+    pub fn score(&self) -> f32 {
+        self.score
+    }
+}
+
+/// Underlying trait of the vector indexes.
+pub trait VectorIndex {
+    /// Returns the distance metric.
+    fn distance(&self) -> Distance;
+
+    /// Returns the dimensions of the vectors, or `0` if the index is empty.
+    fn dimensions(&self) -> usize;
+
+    /// Returns the number of vectors.
+    fn len(&self) -> usize;
+
+    /// Inserts a vector with the ID, replacing the existing one.
+    fn insert(&mut self, id: String, vector: Vec<f32>) -> Result<(), Error>;
+
+    /// Removes the vector with the ID, returning `true` if it exists.
+    fn remove(&mut self, id: &str) -> bool;
+
+    /// Searches the top `k` vectors which are most similar to the query.
+    fn search(&self, query: &[f32], k: usize) -> Vec<SearchHit>;
+
+    /// Returns `true` if the index contains no vectors.
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// A vector store backed by one of the supported indexes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum VectorStore {
+    /// Brute-force index.
+    Flat(FlatIndex),
+    /// HNSW index.
+    Hnsw(HnswIndex),
+}
+
+impl VectorStore {
+    /// Constructs a new instance with the configuration, returning an error if it fails.
+    ///
+    /// ```toml
+    /// index = "hnsw"
+    /// distance = "cosine"
+    /// max-connections = 16
+    /// ef-construction = 200
+    /// ef-search = 64
+    /// ```
+    pub fn try_new(config: &Table) -> Result<Self, Error> {
+        let distance = match config.get_str("distance") {
+            Some(distance) => match Distance::parse(distance) {
+                Some(distance) => distance,
+                None => bail!("distance metric `{}` is unsupported", distance),
+            },
+            None => Distance::default(),
+        };
+        let store = match config.get_str("index").unwrap_or("flat") {
+            "flat" => Flat(FlatIndex::new(distance)),
+            "hnsw" => {
+                let mut index = HnswIndex::new(distance);
+                if let Some(max_connections) = config.get_usize("max-connections") {
+                    index = index.max_connections(max_connections);
+                }
+                if let Some(ef_construction) = config.get_usize("ef-construction") {
+                    index = index.ef_construction(ef_construction);
+                }
+                if let Some(ef_search) = config.get_usize("ef-search") {
+                    index = index.ef_search(ef_search);
+                }
+                Hnsw(index)
+            }
+            index => bail!("vector index `{}` is unsupported", index),
+        };
+        Ok(store)
+    }
+
+    /// Encodes the vector store as JSON bytes.
+    #[inline]
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+        serde_json::to_vec(self).map_err(Error::from)
+    }
+
+    /// Decodes the vector store from JSON bytes.
+    #[inline]
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        serde_json::from_slice(bytes).map_err(Error::from)
+    }
+
+    /// Saves the vector store to the path with the storage accessor.
+    #[cfg(feature = "storage")]
+    pub async fn save(&self, accessor: &str, path: &str) -> Result<(), Error> {
+        let Some(operator) = zino_storage::GlobalAccessor::get(accessor) else {
+            bail!("storage accessor `{}` does not exist", accessor);
+        };
+        operator.write(path, self.to_bytes()?).await?;
+        Ok(())
+    }
+
+    /// Loads the vector store from the path with the storage accessor.
+    #[cfg(feature = "storage")]
+    pub async fn load(accessor: &str, path: &str) -> Result<Self, Error> {
+        let Some(operator) = zino_storage::GlobalAccessor::get(accessor) else {
+            bail!("storage accessor `{}` does not exist", accessor);
+        };
+        let buffer = operator.read(path).await?;
+        Self::from_bytes(&buffer.to_vec())
+    }
+}
+
+impl VectorIndex for VectorStore {
+    fn distance(&self) -> Distance {
+        match self {
+            Flat(index) => index.distance(),
+            Hnsw(index) => index.distance(),
+        }
+    }
+
+    fn dimensions(&self) -> usize {
+        match self {
+            Flat(index) => index.dimensions(),
+            Hnsw(index) => index.dimensions(),
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Flat(index) => index.len(),
+            Hnsw(index) => index.len(),
+        }
+    }
+
+    fn insert(&mut self, id: String, vector: Vec<f32>) -> Result<(), Error> {
+        match self {
+            Flat(index) => index.insert(id, vector),
+            Hnsw(index) => index.insert(id, vector),
+        }
+    }
+
+    fn remove(&mut self, id: &str) -> bool {
+        match self {
+            Flat(index) => index.remove(id),
+            Hnsw(index) => index.remove(id),
+        }
+    }
+
+    fn search(&self, query: &[f32], k: usize) -> Vec<SearchHit> {
+        match self {
+            Flat(index) => index.search(query, k),
+            Hnsw(index) => index.search(query, k),
+        }
+    }
+}
+
+/// Checks the dimensions of the vector.
+fn check_dimensions(expected: usize, vector: &[f32]) -> Result<(), Error> {
+    if vector.is_empty() {
+        bail!("the vector should be nonempty");
+    }
+    if expected > 0 && vector.len() != expected {
+        bail!(
+            "the vector has {} dimensions, but {} are expected",
+            vector.len(),
+            expected
+        );
+    }
+    Ok(())
+}
@@ -1,13 +1,16 @@
-use crate::{AxumResponse, Extractor, middleware};
+use crate::{AxumResponse, Extractor, Request, Result, middleware};
 use axum::{
     BoxError, Router,
     error_handling::HandleErrorLayer,
     extract::{DefaultBodyLimit, rejection::LengthLimitError},
     http::{HeaderName, HeaderValue, StatusCode},
     middleware::from_fn,
+    routing::{MethodFilter, on},
 };
-use std::{any::Any, borrow::Cow, convert::Infallible, fs, net::SocketAddr, time::Duration};
-use tokio::{net::TcpListener, runtime::Builder, signal};
+use std::{
+    any::Any, borrow::Cow, convert::Infallible, fs, io, net::SocketAddr, sync::Arc, time::Duration,
+};
+use tokio::{net::TcpListener, runtime::Builder, time};
 use tower::{
     ServiceBuilder,
     timeout::{TimeoutLayer, error::Elapsed},
@@ -22,11 +25,19 @@ use tower_http::{
 use utoipa_rapidoc::RapiDoc;
 use zino_core::{
     LazyLock,
-    application::{AppType, Application, Plugin, ServerTag},
+    application::{AppType, Application, Plugin, ServerTag, Shutdown},
+    error::Error,
     extension::TomlTableExt,
     schedule::AsyncScheduler,
 };
-use zino_http::response::Response;
+use zino_http::{
+    request::RequestContext,
+    response::{Rejection, Response},
+};
+use zino_router::RouteTable;
+
+#[cfg(feature = "tls")]
+use super::tls::{TlsConnectInfo, TlsListener};
 
 /// An HTTP server cluster.
 #[derive(Default)]
@@ -39,6 +50,27 @@ pub struct Cluster {
     tagged_routes: Vec<(ServerTag, Vec<Router>)>,
 }
 
+impl Cluster {
+    /// Registers the framework-neutral routes.
+    pub fn register_router(mut self, router: zino_router::Router<Request, Result>) -> Self {
+        RouteTable::record(&router);
+        self.default_routes.push(build_router(router));
+        self
+    }
+
+    /// Registers the framework-neutral routes with a server tag.
+    pub fn register_router_with(
+        mut self,
+        server_tag: ServerTag,
+        router: zino_router::Router<Request, Result>,
+    ) -> Self {
+        RouteTable::record(&router);
+        self.tagged_routes
+            .push((server_tag, vec![build_router(router)]));
+        self
+    }
+}
+
 impl Application for Cluster {
     type Routes = Vec<Router>;
 
@@ -73,30 +105,51 @@ impl Application for Cluster {
         let app_env = Self::env();
         runtime.block_on(async {
             #[cfg(feature = "orm")]
-            zino_orm::GlobalPool::connect_all().await;
+            {
+                zino_orm::GlobalPool::connect_all().await;
+                zino_orm::GlobalPool::migrate_all().await;
+                Shutdown::add_hook(
+                    "orm",
+                    Box::pin(async {
+                        zino_orm::GlobalPool::close_all().await;
+                        Ok(())
+                    }),
+                );
+            }
             Self::load().await;
             app_env.load_plugins(self.custom_plugins).await;
         });
-        if scheduler.is_ready() {
+        runtime.spawn(Shutdown::listen());
+
+        #[cfg(feature = "orm")]
+        runtime.spawn(zino_orm::GlobalPool::monitor_replicas());
+
+        let scheduler_task = scheduler.is_ready().then(|| {
             if scheduler.is_blocking() {
                 runtime.spawn(async move {
                     if let Err(err) = scheduler.run().await {
                         tracing::error!("fail to run the async scheduler: {err}");
                     }
-                });
+                })
             } else {
                 runtime.spawn(async move {
-                    loop {
+                    // Stops the scheduler after the current jobs on the graceful shutdown.
+                    while !Shutdown::is_triggered() {
                         scheduler.tick().await;
 
                         // Cannot use `std::thread::sleep` because it blocks the Tokio runtime.
                         if let Some(duration) = scheduler.time_till_next_job() {
-                            tokio::time::sleep(duration).await;
+                            tokio::select! {
+                                _ = tokio::time::sleep(duration) => {},
+                                _ = Shutdown::wait() => {},
+                            }
                         }
                     }
-                });
+                    // Waits for the jobs running in the background.
+                    scheduler.tick().await;
+                })
             }
-        }
+        });
 
         runtime.block_on(async {
             let default_routes = self.default_routes;
@@ -104,6 +157,7 @@ impl Application for Cluster {
             let app_state = Self::shared_state();
             let app_name = Self::name();
             let app_version = Self::version();
+            let shutdown_timeout = Self::shutdown_timeout();
             let listeners = app_state.listeners();
             let has_debug_server = listeners.iter().any(|listener| listener.0.is_debug());
             let servers = listeners.into_iter().map(|listener| {
@@ -290,16 +344,37 @@ impl Application for Cluster {
                         ))
                         .layer(TimeoutLayer::new(request_timeout)),
                 );
+
+                // TLS config
+                #[cfg(feature = "tls")]
+                let tls_server_config = Self::tls_config(addr).map(|tls_config| {
+                    tls_config.build_server_config().unwrap_or_else(|err| {
+                        panic!("fail to build the TLS server config for {addr}: {err}")
+                    })
+                });
                 Box::pin(async move {
                     let tcp_listener = TcpListener::bind(&addr)
                         .await
                         .unwrap_or_else(|err| panic!("fail to listen on {addr}: {err}"));
-                    axum::serve(
+                    #[cfg(feature = "tls")]
+                    if let Some(server_config) = tls_server_config {
+                        let tls_listener = TlsListener::new(tcp_listener, server_config);
+                        tracing::info!("TLS is enabled for `{addr}`");
+                        let server = axum::serve(
+                            tls_listener,
+                            app.into_make_service_with_connect_info::<TlsConnectInfo>(),
+                        )
+                        .with_graceful_shutdown(Shutdown::wait());
+                        return drain_with_timeout(server.into_future(), addr, shutdown_timeout)
+                            .await;
+                    }
+
+                    let server = axum::serve(
                         tcp_listener,
                         app.into_make_service_with_connect_info::<SocketAddr>(),
                     )
-                    .with_graceful_shutdown(Self::shutdown())
-                    .await
+                    .with_graceful_shutdown(Shutdown::wait());
+                    drain_with_timeout(server.into_future(), addr, shutdown_timeout).await
                 })
             });
             for result in futures::future::join_all(servers).await {
@@ -307,32 +382,60 @@ impl Application for Cluster {
                     tracing::error!("axum server error: {err}");
                 }
             }
+            if let Some(task) = scheduler_task
+                && time::timeout(shutdown_timeout, task).await.is_err()
+            {
+                tracing::warn!("timed out to wait for the async scheduler");
+            }
+            Self::teardown().await;
         });
     }
+}
 
-    async fn shutdown() {
-        let ctrl_c = async {
-            if let Err(err) = signal::ctrl_c().await {
-                tracing::error!("fail to install the `Ctrl+C` handler: {err}");
-            }
-            #[cfg(feature = "orm")]
-            zino_orm::GlobalPool::close_all().await;
-        };
-        #[cfg(unix)]
-        let terminate = async {
-            signal::unix::signal(signal::unix::SignalKind::terminate())
-                .expect("fail to install the terminate signal handler")
-                .recv()
-                .await;
-            #[cfg(feature = "orm")]
-            zino_orm::GlobalPool::close_all().await;
-        };
-        #[cfg(not(unix))]
-        let terminate = std::future::pending::<()>();
-        tokio::select! {
-            _ = ctrl_c => {},
-            _ = terminate => {},
+/// Waits for the server until the connections have been drained
+/// or the shutdown timeout has elapsed after the graceful shutdown.
+async fn drain_with_timeout(
+    server: impl Future<Output = io::Result<()>>,
+    addr: SocketAddr,
+    shutdown_timeout: Duration,
+) -> io::Result<()> {
+    let drain_timeout = async {
+        Shutdown::wait().await;
+        time::sleep(shutdown_timeout).await;
+    };
+    tokio::select! {
+        result = server => result,
+        _ = drain_timeout => {
+            tracing::warn!("timed out to drain the connections on `{addr}`");
+            Ok(())
+        }
+    }
+}
+
+/// Builds an axum router from the framework-neutral routes.
+fn build_router(router: zino_router::Router<Request, Result>) -> Router {
+    let mut app = Router::new();
+    for route in router.into_routes() {
+        let Ok(method_filter) = MethodFilter::try_from(route.method().clone()) else {
+            tracing::warn!("HTTP method `{}` is not supported by axum", route.method());
+            continue;
         };
-        tracing::warn!("signal received, starting graceful shutdown");
+        let path = route.pattern().to_path();
+        let pattern = Arc::new(route.pattern().clone());
+        let handler = route.into_handler();
+        let method_router = on(method_filter, move |req: Request| {
+            let pattern = pattern.clone();
+            let handler = handler.clone();
+            async move {
+                // Typed captures are not supported by axum, so we have to check them here.
+                if pattern.is_typed() && pattern.matches(req.request_path()).is_none() {
+                    let err = Error::new(format!("invalid path parameters for `{pattern}`"));
+                    return Err(Rejection::not_found(err).context(&req).into());
+                }
+                handler(req).await
+            }
+        });
+        app = app.route(&path, method_router);
     }
+    app
 }
@@ -1,3 +1,9 @@
 mod cluster;
 
+#[cfg(feature = "tls")]
+mod tls;
+
 pub use cluster::Cluster;
+
+#[cfg(feature = "tls")]
+pub(crate) use tls::TlsConnectInfo;
@@ -8,6 +8,7 @@ mod request;
 mod response;
 
 pub use application::Cluster;
+pub use middleware::{MiddlewareAdapter, MiddlewareService};
 pub use request::Extractor;
 pub use response::{AxumRejection, AxumResponse};
 
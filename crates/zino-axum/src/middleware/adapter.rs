@@ -0,0 +1,114 @@
+use crate::AxumRejection;
+use axum::{
+    body::Body,
+    http::{HeaderMap, HeaderValue, Request},
+    response::{IntoResponse, Response},
+};
+use futures::future::BoxFuture;
+use std::{
+    convert::Infallible,
+    mem,
+    sync::Arc,
+    task::{Context, Poll},
+};
+use tower::{Layer, Service};
+use zino_middleware::Middleware;
+
+/// An adapter which applies a framework-neutral middleware to the `axum` routes.
+pub struct MiddlewareAdapter<M> {
+    /// The middleware.
+    middleware: Arc<M>,
+}
+
+impl<M> MiddlewareAdapter<M> {
+    /// Creates a new instance.
+    #[inline]
+    pub fn new(middleware: M) -> Self {
+        Self {
+            middleware: Arc::new(middleware),
+        }
+    }
+}
+
+impl<M> Clone for MiddlewareAdapter<M> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            middleware: self.middleware.clone(),
+        }
+    }
+}
+
+impl<S, M> Layer<S> for MiddlewareAdapter<M> {
+    type Service = MiddlewareService<S, M>;
+
+    #[inline]
+    fn layer(&self, inner: S) -> Self::Service {
+        MiddlewareService {
+            inner,
+            middleware: self.middleware.clone(),
+        }
+    }
+}
+
+/// A service which runs a framework-neutral middleware.
+pub struct MiddlewareService<S, M> {
+    /// The inner service.
+    inner: S,
+    /// The middleware.
+    middleware: Arc<M>,
+}
+
+impl<S: Clone, M> Clone for MiddlewareService<S, M> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            middleware: self.middleware.clone(),
+        }
+    }
+}
+
+impl<S, M> Service<Request<Body>> for MiddlewareService<S, M>
+where
+    S: Service<Request<Body>, Response = Response, Error = Infallible> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    M: Middleware + 'static,
+{
+    type Response = Response;
+    type Error = Infallible;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    #[inline]
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<Body>) -> Self::Future {
+        let mut req = crate::Request::from(req);
+        match self.middleware.before_request(&mut req) {
+            Ok(state) => {
+                let middleware = self.middleware.clone();
+                let inner = self.inner.clone();
+                let mut inner = mem::replace(&mut self.inner, inner);
+                Box::pin(async move {
+                    let mut res = inner.call(req.into()).await?;
+                    let mut headers = HeaderMap::default();
+                    middleware.after_response(state, &mut headers);
+                    for (name, value) in headers {
+                        if let Some(header_name) = name
+                            && let Ok(header_value) = HeaderValue::try_from(value)
+                        {
+                            res.headers_mut().insert(header_name, header_value);
+                        }
+                    }
+                    Ok(res)
+                })
+            }
+            Err(rejection) => {
+                let res = AxumRejection::from(rejection).into_response();
+                Box::pin(async move { Ok(res) })
+            }
+        }
+    }
+}
@@ -11,5 +11,13 @@ pub(crate) async fn request_context(req: crate::Request, next: Next) -> Response
         Span::current().record("context.request_id", ctx.request_id().to_string());
         req.extensions_mut().insert(Arc::new(ctx));
     }
-    next.run(req).await
+
+    // Routes the reads following a write in the request to the writer
+    #[cfg(feature = "orm")]
+    let res = zino_orm::ReadYourWrites::new(next.run(req)).await;
+
+    #[cfg(not(feature = "orm"))]
+    let res = next.run(req).await;
+
+    res
 }
@@ -1,9 +1,12 @@
+mod adapter;
 mod context;
 mod cors;
 mod etag;
 mod static_pages;
 mod tracing;
 
+pub use self::adapter::{MiddlewareAdapter, MiddlewareService};
+
 pub(crate) use self::context::request_context;
 pub(crate) use self::cors::CORS_MIDDLEWARE;
 pub(crate) use self::etag::extract_etag;
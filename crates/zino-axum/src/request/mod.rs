@@ -13,6 +13,12 @@ use std::{
 };
 use zino_core::{error::Error, extension::HeaderMapExt, state::Data};
 use zino_http::request::{Context, RequestContext};
+use zino_router::{RouteRequest, Sendable};
+
+#[cfg(feature = "tls")]
+use crate::application::TlsConnectInfo;
+#[cfg(feature = "tls")]
+use zino_core::application::ClientCertificate;
 
 /// An HTTP request extractor.
 pub struct Extractor<T>(T);
@@ -47,6 +53,10 @@ impl From<Extractor<Request>> for Request {
     }
 }
 
+impl RouteRequest for Extractor<Request> {
+    type Flavor = Sendable;
+}
+
 impl RequestContext for Extractor<Request> {
     type Method = Method;
     type Uri = Uri;
@@ -93,11 +103,30 @@ impl RequestContext for Extractor<Request> {
 
     #[inline]
     fn client_ip(&self) -> Option<IpAddr> {
-        self.headers().get_client_ip().or_else(|| {
-            self.extensions()
-                .get::<ConnectInfo<SocketAddr>>()
-                .map(|socket| socket.ip())
-        })
+        self.headers().get_client_ip().or_else(|| self.peer_ip())
+    }
+
+    #[inline]
+    fn peer_ip(&self) -> Option<IpAddr> {
+        let extensions = self.extensions();
+        let socket = extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|info| info.0);
+        #[cfg(feature = "tls")]
+        let socket = socket.or_else(|| {
+            extensions
+                .get::<ConnectInfo<TlsConnectInfo>>()
+                .map(|info| info.remote_addr)
+        });
+        socket.map(|socket| socket.ip())
+    }
+
+    #[cfg(feature = "tls")]
+    #[inline]
+    fn client_certificate(&self) -> Option<Arc<ClientCertificate>> {
+        self.extensions()
+            .get::<ConnectInfo<TlsConnectInfo>>()
+            .and_then(|info| info.client_certificate.clone())
     }
 
     #[inline]
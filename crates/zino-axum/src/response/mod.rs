@@ -6,6 +6,8 @@ use axum::{
     },
     response::IntoResponse,
 };
+use futures::TryStreamExt;
+use std::io;
 use zino_http::response::{Rejection, Response, ResponseCode};
 
 /// An HTTP response for `axum`.
@@ -46,11 +48,17 @@ impl IntoResponse for AxumRejection {
 pub(crate) fn build_http_response<S: ResponseCode>(
     mut response: Response<S>,
 ) -> axum::response::Response {
-    let mut res = match response.read_bytes() {
-        Ok(data) => axum::response::Response::builder()
+    let result = if let Some(stream) = response.take_stream_data() {
+        let stream = stream.map_err(|err| io::Error::other(err.to_string()));
+        Ok(Body::from_stream(stream))
+    } else {
+        response.read_bytes().map(Body::from)
+    };
+    let mut res = match result {
+        Ok(body) => axum::response::Response::builder()
             .status(response.status_code())
             .header(header::CONTENT_TYPE, response.content_type())
-            .body(Body::from(data))
+            .body(body)
             .unwrap_or_default(),
         Err(err) => axum::response::Response::builder()
             .status(S::INTERNAL_SERVER_ERROR.status_code())
@@ -0,0 +1,436 @@
+use super::{echarts, svg, vega};
+use serde::{Deserialize, Serialize};
+use zino_core::{JsonValue, Map, extension::JsonValueExt};
+
+#[cfg(feature = "connector-arrow")]
+use zino_connector::DataFrameExecutor;
+#[cfg(feature = "connector-arrow")]
+use zino_core::error::Error;
+
+/// Kind of a chart.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[non_exhaustive]
+pub enum ChartKind {
+    /// Line chart.
+    #[default]
+    Line,
+    /// Bar chart.
+    Bar,
+    /// Pie chart.
+    Pie,
+    /// Scatter chart.
+    Scatter,
+    /// Heatmap.
+    Heatmap,
+}
+
+impl ChartKind {
+    /// Parses the chart kind.
+    pub fn parse(kind: &str) -> Option<Self> {
+        match kind {
+            "line" => Some(Self::Line),
+            "bar" => Some(Self::Bar),
+            "pie" => Some(Self::Pie),
+            "scatter" => Some(Self::Scatter),
+            "heatmap" => Some(Self::Heatmap),
+            _ => None,
+        }
+    }
+
+    /// Returns the chart kind as a string slice.
+    #[inline]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Line => "line",
+            Self::Bar => "bar",
+            Self::Pie => "pie",
+            Self::Scatter => "scatter",
+            Self::Heatmap => "heatmap",
+        }
+    }
+}
+
+/// A chart specification built from the data rows.
+///
+/// The rows are usually the results of `Schema::aggregate` or a `DataFrame`.
+/// The meanings of the fields depend on the chart kind:
+///
+/// | Kind      | `x_field`       | `y_field`              | `value_field` |
+/// |-----------|-----------------|------------------------|---------------|
+/// | `line`    | category        | one series per field   |               |
+/// | `bar`     | category        | one series per field   |               |
+/// | `pie`     | slice name      | slice value            |               |
+/// | `scatter` | numeric x value | numeric y value        |               |
+/// | `heatmap` | x category      | y category             | cell value    |
+///
+/// For the rows in the long format, the `series_field` can be used to split
+/// the values of the first `y_field` into one series per distinct value.
+#[derive(Debug, Clone, Default)]
+pub struct Chart {
+    /// Kind of the chart.
+    kind: ChartKind,
+    /// Title.
+    title: Option<String>,
+    /// Data rows.
+    rows: Vec<Map>,
+    /// Field for the x axis.
+    x_field: String,
+    /// Fields for the y axis.
+    y_fields: Vec<String>,
+    /// Field to split the series.
+    series_field: Option<String>,
+    /// Field for the values of a heatmap.
+    value_field: Option<String>,
+    /// Width in pixels.
+    width: u32,
+    /// Height in pixels.
+    height: u32,
+}
+
+impl Chart {
+    /// Creates a new instance with the chart kind and data rows.
+    #[inline]
+    pub fn new(kind: ChartKind, rows: Vec<Map>) -> Self {
+        Self {
+            kind,
+            rows,
+            width: 640,
+            height: 400,
+            ..Self::default()
+        }
+    }
+
+    /// Creates a new instance with the rows of a `DataFrame`.
+    #[cfg(feature = "connector-arrow")]
+    pub async fn try_from_data_frame(
+        kind: ChartKind,
+        data_frame: impl DataFrameExecutor,
+    ) -> Result<Self, Error> {
+        let rows = data_frame.query_as::<Map>().await?;
+        Ok(Self::new(kind, rows))
+    }
+
+    /// Sets the title.
+    #[inline]
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Sets the field for the x axis.
+    #[inline]
+    pub fn with_x_field(mut self, field: impl Into<String>) -> Self {
+        self.x_field = field.into();
+        self
+    }
+
+    /// Adds a field for the y axis.
+    #[inline]
+    pub fn with_y_field(mut self, field: impl Into<String>) -> Self {
+        self.y_fields.push(field.into());
+        self
+    }
+
+    /// Sets the field to split the series.
+    #[inline]
+    pub fn with_series_field(mut self, field: impl Into<String>) -> Self {
+        self.series_field = Some(field.into());
+        self
+    }
+
+    /// Sets the field for the values of a heatmap.
+    #[inline]
+    pub fn with_value_field(mut self, field: impl Into<String>) -> Self {
+        self.value_field = Some(field.into());
+        self
+    }
+
+    /// Sets the size in pixels.
+    #[inline]
+    pub fn with_size(mut self, width: u32, height: u32) -> Self {
+        self.width = width.max(1);
+        self.height = height.max(1);
+        self
+    }
+
+    /// Returns the chart kind.
+    #[inline]
+    pub fn kind(&self) -> ChartKind {
+        self.kind
+    }
+
+    /// Returns the title.
+    #[inline]
+    pub fn title(&self) -> Option<&str> {
+        self.title.as_deref()
+    }
+
+    /// Returns the data rows.
+    #[inline]
+    pub fn rows(&self) -> &[Map] {
+        &self.rows
+    }
+
+    /// Returns the width in pixels.
+    #[inline]
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Returns the height in pixels.
+    #[inline]
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Generates the option of [ECharts](https://echarts.apache.org).
+    #[inline]
+    pub fn to_echarts(&self) -> Map {
+        echarts::build_option(self)
+    }
+
+    /// Generates the specification of [Vega-Lite](https://vega.github.io/vega-lite).
+    #[inline]
+    pub fn to_vega_lite(&self) -> Map {
+        vega::build_spec(self)
+    }
+
+    /// Renders the chart as a static SVG document.
+    #[inline]
+    pub fn render_svg(&self) -> String {
+        svg::render(self)
+    }
+
+    /// Returns the field for the x axis.
+    #[inline]
+    pub fn x_field(&self) -> &str {
+        &self.x_field
+    }
+
+    /// Returns the fields for the y axis.
+    #[inline]
+    pub fn y_fields(&self) -> &[String] {
+        &self.y_fields
+    }
+
+    /// Returns the field to split the series.
+    #[inline]
+    pub fn series_field(&self) -> Option<&str> {
+        self.series_field.as_deref()
+    }
+
+    /// Returns the field for the values of a heatmap.
+    #[inline]
+    pub fn value_field(&self) -> Option<&str> {
+        self.value_field.as_deref()
+    }
+
+    /// Returns the distinct values of the field in order of appearance.
+    pub(crate) fn distinct_values(&self, field: &str) -> Vec<String> {
+        let mut values = Vec::new();
+        for row in &self.rows {
+            if let Some(value) = row.get(field).map(format_label)
+                && !values.contains(&value)
+            {
+                values.push(value);
+            }
+        }
+        values
+    }
+
+    /// Returns the distinct values of the x field.
+    #[inline]
+    pub(crate) fn categories(&self) -> Vec<String> {
+        self.distinct_values(&self.x_field)
+    }
+
+    /// Returns the named series whose values are aligned with the categories.
+    pub(crate) fn series(&self) -> Vec<(String, Vec<Option<f64>>)> {
+        let categories = self.categories();
+        let position = |row: &Map| {
+            let label = row.get(&self.x_field).map(format_label)?;
+            categories.iter().position(|category| category == &label)
+        };
+        if let Some(series_field) = self.series_field.as_deref() {
+            let Some(y_field) = self.y_fields.first() else {
+                return Vec::new();
+            };
+            self.distinct_values(series_field)
+                .into_iter()
+                .map(|name| {
+                    let mut values = vec![None; categories.len()];
+                    for row in &self.rows {
+                        let matched = row
+                            .get(series_field)
+                            .is_some_and(|value| format_label(value) == name);
+                        if matched && let Some(index) = position(row) {
+                            values[index] = row.get(y_field).and_then(parse_number);
+                        }
+                    }
+                    (name, values)
+                })
+                .collect()
+        } else {
+            self.y_fields
+                .iter()
+                .map(|y_field| {
+                    let mut values = vec![None; categories.len()];
+                    for row in &self.rows {
+                        if let Some(index) = position(row) {
+                            values[index] = row.get(y_field).and_then(parse_number);
+                        }
+                    }
+                    (y_field.to_owned(), values)
+                })
+                .collect()
+        }
+    }
+
+    /// Returns the numeric points for the y field.
+    pub(crate) fn points(&self, y_field: &str) -> Vec<(f64, f64)> {
+        self.rows
+            .iter()
+            .filter_map(|row| {
+                let x = row.get(&self.x_field).and_then(parse_number)?;
+                let y = row.get(y_field).and_then(parse_number)?;
+                Some((x, y))
+            })
+            .collect()
+    }
+
+    /// Returns the categories and cells of a heatmap.
+    pub(crate) fn cells(&self) -> HeatmapCells {
+        let x_categories = self.categories();
+        let (Some(y_field), Some(value_field)) =
+            (self.y_fields.first(), self.value_field.as_deref())
+        else {
+            return HeatmapCells {
+                x_categories,
+                ..HeatmapCells::default()
+            };
+        };
+        let y_categories = self.distinct_values(y_field);
+        let cells = self
+            .rows
+            .iter()
+            .filter_map(|row| {
+                let x = row.get(&self.x_field).map(format_label)?;
+                let y = row.get(y_field).map(format_label)?;
+                let value = row.get(value_field).and_then(parse_number)?;
+                let x_index = x_categories.iter().position(|s| s == &x)?;
+                let y_index = y_categories.iter().position(|s| s == &y)?;
+                Some((x_index, y_index, value))
+            })
+            .collect();
+        HeatmapCells {
+            x_categories,
+            y_categories,
+            cells,
+        }
+    }
+
+    /// Returns the named values of a pie chart.
+    pub(crate) fn slices(&self) -> Vec<(String, f64)> {
+        let Some(y_field) = self.y_fields.first() else {
+            return Vec::new();
+        };
+        self.rows
+            .iter()
+            .filter_map(|row| {
+                let name = row.get(&self.x_field).map(format_label)?;
+                let value = row.get(y_field).and_then(parse_number)?;
+                Some((name, value))
+            })
+            .collect()
+    }
+}
+
+/// Categories and cells of a heatmap.
+#[derive(Debug, Default)]
+pub(crate) struct HeatmapCells {
+    /// Categories of the x axis.
+    pub(crate) x_categories: Vec<String>,
+    /// Categories of the y axis.
+    pub(crate) y_categories: Vec<String>,
+    /// Cells as the indexes of the x and y categories with the values.
+    pub(crate) cells: Vec<(usize, usize, f64)>,
+}
+
+/// Formats the JSON value as a label.
+pub(crate) fn format_label(value: &JsonValue) -> String {
+    value.to_string_unquoted()
+}
+
+/// Parses the JSON value as a number, where numeric strings are also supported
+/// since the aggregated decimals may be decoded as strings.
+pub(crate) fn parse_number(value: &JsonValue) -> Option<f64> {
+    match value {
+        JsonValue::Number(n) => n.as_f64(),
+        JsonValue::String(s) => s.trim().parse().ok(),
+        JsonValue::Bool(b) => Some(if *b { 1.0 } else { 0.0 }),
+        _ => None,
+    }
+}
+
+/// Converts an optional number to a JSON value.
+pub(crate) fn number_value(value: Option<f64>) -> JsonValue {
+    value
+        .and_then(serde_json::Number::from_f64)
+        .map(JsonValue::Number)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Chart, ChartKind};
+    use zino_core::{JsonValue, Map, extension::JsonObjectExt};
+
+    #[test]
+    fn it_builds_charts_from_aggregates() {
+        let rows = [
+            ("2025-01-01", "paid", 3),
+            ("2025-01-01", "refunded", 1),
+            ("2025-01-02", "paid", 5),
+        ]
+        .into_iter()
+        .map(|(date, status, count)| {
+            let mut row = Map::new();
+            row.upsert("date", date);
+            row.upsert("status", status);
+            row.upsert("count", count.to_string());
+            row
+        })
+        .collect::<Vec<_>>();
+        let chart = Chart::new(ChartKind::Bar, rows)
+            .with_title("Orders <daily>")
+            .with_x_field("date")
+            .with_y_field("count")
+            .with_series_field("status");
+
+        let option = chart.to_echarts();
+        let series = option
+            .get_array("series")
+            .expect("field `series` should be an array");
+        assert_eq!(series.len(), 2);
+        assert_eq!(
+            series[1].get("data"),
+            Some(&JsonValue::from(vec![
+                JsonValue::from(1.0),
+                JsonValue::Null
+            ]))
+        );
+
+        let spec = chart.to_vega_lite();
+        assert_eq!(spec.get_str("mark"), Some("bar"));
+        let encoding = spec
+            .get_object("encoding")
+            .expect("field `encoding` should be an object");
+        assert!(encoding.contains_key("xOffset"));
+
+        let svg = chart.render_svg();
+        assert!(svg.starts_with("<svg") && svg.ends_with("</svg>"));
+        assert!(svg.contains("Orders &lt;daily&gt;"));
+        assert_eq!(svg.matches("<rect").count(), 1 + 3 + 2);
+    }
+}
@@ -0,0 +1,126 @@
+use super::{
+    Chart, ChartKind,
+    chart::{HeatmapCells, number_value},
+};
+use zino_core::{JsonValue, Map, extension::JsonObjectExt};
+
+/// Builds the ECharts option for the chart.
+pub(crate) fn build_option(chart: &Chart) -> Map {
+    let mut option = Map::new();
+    if let Some(title) = chart.title() {
+        option.upsert("title", Map::from_entry("text", title));
+    }
+    match chart.kind() {
+        ChartKind::Line | ChartKind::Bar => {
+            let series_type = chart.kind().as_str();
+            let series = chart.series();
+            let names = series
+                .iter()
+                .map(|(name, _)| name.as_str())
+                .collect::<Vec<_>>();
+            let series = series
+                .iter()
+                .map(|(name, values)| {
+                    let data = values.iter().map(|&v| number_value(v)).collect::<Vec<_>>();
+                    let mut map = Map::new();
+                    map.upsert("name", name.as_str());
+                    map.upsert("type", series_type);
+                    map.upsert("data", data);
+                    JsonValue::Object(map)
+                })
+                .collect::<Vec<_>>();
+
+            let mut x_axis = Map::new();
+            x_axis.upsert("type", "category");
+            x_axis.upsert("data", chart.categories());
+            option.upsert("tooltip", Map::from_entry("trigger", "axis"));
+            option.upsert("legend", Map::from_entry("data", names));
+            option.upsert("xAxis", x_axis);
+            option.upsert("yAxis", Map::from_entry("type", "value"));
+            option.upsert("series", series);
+        }
+        ChartKind::Pie => {
+            let data = chart
+                .slices()
+                .into_iter()
+                .map(|(name, value)| {
+                    let mut map = Map::new();
+                    map.upsert("name", name);
+                    map.upsert("value", number_value(Some(value)));
+                    JsonValue::Object(map)
+                })
+                .collect::<Vec<_>>();
+
+            let mut series = Map::new();
+            series.upsert("type", "pie");
+            series.upsert("radius", "60%");
+            series.upsert("data", data);
+            option.upsert("tooltip", Map::from_entry("trigger", "item"));
+            option.upsert("legend", Map::from_entry("orient", "vertical"));
+            option.upsert("series", vec![series]);
+        }
+        ChartKind::Scatter => {
+            let series = chart
+                .y_fields()
+                .iter()
+                .map(|y_field| {
+                    let data = chart
+                        .points(y_field)
+                        .into_iter()
+                        .map(|(x, y)| vec![number_value(Some(x)), number_value(Some(y))])
+                        .collect::<Vec<_>>();
+                    let mut map = Map::new();
+                    map.upsert("name", y_field.as_str());
+                    map.upsert("type", "scatter");
+                    map.upsert("data", data);
+                    JsonValue::Object(map)
+                })
+                .collect::<Vec<_>>();
+            option.upsert("tooltip", Map::from_entry("trigger", "item"));
+            option.upsert("xAxis", Map::from_entry("type", "value"));
+            option.upsert("yAxis", Map::from_entry("type", "value"));
+            option.upsert("series", series);
+        }
+        ChartKind::Heatmap => {
+            let HeatmapCells {
+                x_categories,
+                y_categories,
+                cells,
+            } = chart.cells();
+            let (min, max) = cells
+                .iter()
+                .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), cell| {
+                    (min.min(cell.2), max.max(cell.2))
+                });
+            let data = cells
+                .into_iter()
+                .map(|(x, y, value)| vec![x.into(), y.into(), number_value(Some(value))])
+                .collect::<Vec<Vec<JsonValue>>>();
+
+            let mut x_axis = Map::new();
+            x_axis.upsert("type", "category");
+            x_axis.upsert("data", x_categories);
+
+            let mut y_axis = Map::new();
+            y_axis.upsert("type", "category");
+            y_axis.upsert("data", y_categories);
+
+            let mut visual_map = Map::new();
+            visual_map.upsert("min", number_value(Some(min).filter(|v| v.is_finite())));
+            visual_map.upsert("max", number_value(Some(max).filter(|v| v.is_finite())));
+            visual_map.upsert("calculable", true);
+            visual_map.upsert("orient", "horizontal");
+            visual_map.upsert("left", "center");
+
+            let mut series = Map::new();
+            series.upsert("type", "heatmap");
+            series.upsert("data", data);
+            option.upsert("tooltip", Map::from_entry("position", "top"));
+            option.upsert("xAxis", x_axis);
+            option.upsert("yAxis", y_axis);
+            option.upsert("visualMap", visual_map);
+            option.upsert("series", vec![series]);
+        }
+    }
+    option
+}
@@ -1,3 +1,13 @@
+#![cfg_attr(docsrs, feature(doc_cfg))]
 #![doc = include_str!("../README.md")]
 #![doc(html_favicon_url = "https://zino.cc/assets/zino-logo.png")]
 #![doc(html_logo_url = "https://zino.cc/assets/zino-logo.svg")]
+
+mod chart;
+
+/// Supported outputs.
+mod echarts;
+mod svg;
+mod vega;
+
+pub use chart::{Chart, ChartKind};
@@ -0,0 +1,621 @@
+use super::{Chart, ChartKind, chart::HeatmapCells};
+use std::f64::consts::PI;
+
+/// Default color palette, which is the same as ECharts.
+const PALETTE: [&str; 9] = [
+    "#5470c6", "#91cc75", "#fac858", "#ee6666", "#73c0de", "#3ba272", "#fc8452", "#9a60b4",
+    "#ea7ccc",
+];
+
+/// Color of the axes.
+const AXIS_COLOR: &str = "#6e7079";
+
+/// Color of the grid lines.
+const GRID_COLOR: &str = "#e0e6f1";
+
+/// Color range of the heatmap cells.
+const HEATMAP_COLORS: [(f64, f64, f64); 2] = [(224.0, 243.0, 248.0), (215.0, 48.0, 39.0)];
+
+/// Approximate number of the ticks on a value axis.
+const TICK_COUNT: usize = 5;
+
+/// Renders the chart as a static SVG document.
+pub(crate) fn render(chart: &Chart) -> String {
+    let width = f64::from(chart.width());
+    let height = f64::from(chart.height());
+    let mut svg = SvgWriter::new(width, height);
+    let mut top = 20.0;
+    if let Some(title) = chart.title() {
+        svg.text(
+            width / 2.0,
+            26.0,
+            title,
+            "middle",
+            "font-size=\"16\" font-weight=\"bold\"",
+        );
+        top = 44.0;
+    }
+
+    let area = Area {
+        left: 56.0,
+        top,
+        right: width - 24.0,
+        bottom: height - 56.0,
+    };
+    match chart.kind() {
+        ChartKind::Line | ChartKind::Bar => render_category_chart(&mut svg, chart, &area),
+        ChartKind::Pie => render_pie_chart(&mut svg, chart, &area),
+        ChartKind::Scatter => render_scatter_chart(&mut svg, chart, &area),
+        ChartKind::Heatmap => render_heatmap(&mut svg, chart, &area),
+    }
+    svg.finish()
+}
+
+/// Renders a line chart or a bar chart.
+fn render_category_chart(svg: &mut SvgWriter, chart: &Chart, area: &Area) {
+    let is_bar = chart.kind() == ChartKind::Bar;
+    let categories = chart.categories();
+    let series = chart.series();
+    let values = series
+        .iter()
+        .flat_map(|(_, values)| values.iter().flatten());
+    let (mut min, mut max) = bounds(values.copied());
+    if is_bar {
+        min = min.min(0.0);
+        max = max.max(0.0);
+    }
+
+    let y_scale = render_value_axis(svg, area, min, max);
+    let band = area.width() / categories.len().max(1) as f64;
+    for (index, category) in categories.iter().enumerate() {
+        let x = area.left + band * (index as f64 + 0.5);
+        svg.text(x, area.bottom + 16.0, category, "middle", "");
+    }
+    svg.line(area.left, area.bottom, area.right, area.bottom, AXIS_COLOR);
+
+    let num_series = series.len().max(1) as f64;
+    for (series_index, (_, values)) in series.iter().enumerate() {
+        let color = PALETTE[series_index % PALETTE.len()];
+        if is_bar {
+            let group_width = band * 0.7;
+            let bar_width = group_width / num_series;
+            for (index, value) in values.iter().enumerate() {
+                if let Some(value) = value {
+                    let x = area.left + band * index as f64 + (band - group_width) / 2.0;
+                    let y0 = y_scale.map(value.max(0.0));
+                    let y1 = y_scale.map(value.min(0.0));
+                    let x = x + bar_width * series_index as f64;
+                    svg.rect(x, y0, bar_width, y1 - y0, color);
+                }
+            }
+        } else {
+            let mut segment = Vec::new();
+            for (index, value) in values.iter().enumerate() {
+                if let Some(value) = value {
+                    let x = area.left + band * (index as f64 + 0.5);
+                    segment.push((x, y_scale.map(*value)));
+                } else {
+                    svg.polyline(&segment, color);
+                    segment.clear();
+                }
+            }
+            svg.polyline(&segment, color);
+            for (index, value) in values.iter().enumerate() {
+                if let Some(value) = value {
+                    let x = area.left + band * (index as f64 + 0.5);
+                    svg.circle(x, y_scale.map(*value), 3.0, color);
+                }
+            }
+        }
+    }
+    if series.len() > 1 {
+        let names = series.iter().map(|(name, _)| name.as_str());
+        render_legend(svg, area, names);
+    }
+}
+
+/// Renders a pie chart.
+fn render_pie_chart(svg: &mut SvgWriter, chart: &Chart, area: &Area) {
+    let slices = chart
+        .slices()
+        .into_iter()
+        .filter(|(_, value)| *value > 0.0)
+        .collect::<Vec<_>>();
+    let total = slices.iter().map(|(_, value)| value).sum::<f64>();
+    let cx = (area.left + area.right) / 2.0;
+    let cy = (area.top + area.bottom) / 2.0;
+    let radius = area.width().min(area.height()) / 2.0 * 0.9;
+    let mut start_angle = -PI / 2.0;
+    for (index, (_, value)) in slices.iter().enumerate() {
+        let color = PALETTE[index % PALETTE.len()];
+        let fraction = value / total;
+        if fraction >= 0.9999 {
+            svg.circle(cx, cy, radius, color);
+            break;
+        }
+
+        let end_angle = start_angle + fraction * 2.0 * PI;
+        let (x0, y0) = (
+            cx + radius * start_angle.cos(),
+            cy + radius * start_angle.sin(),
+        );
+        let (x1, y1) = (cx + radius * end_angle.cos(), cy + radius * end_angle.sin());
+        let large_arc = if fraction > 0.5 { 1 } else { 0 };
+        let path = format!(
+            "M {cx:.1} {cy:.1} L {x0:.1} {y0:.1} A {radius:.1} {radius:.1} 0 {large_arc} 1 {x1:.1} {y1:.1} Z"
+        );
+        svg.path(&path, color);
+        start_angle = end_angle;
+    }
+
+    let names = slices
+        .iter()
+        .map(|(name, value)| format!("{name} ({:.1}%)", value / total * 100.0))
+        .collect::<Vec<_>>();
+    render_legend(svg, area, names.iter().map(|s| s.as_str()));
+}
+
+/// Renders a scatter chart.
+fn render_scatter_chart(svg: &mut SvgWriter, chart: &Chart, area: &Area) {
+    let series = chart
+        .y_fields()
+        .iter()
+        .map(|y_field| (y_field.as_str(), chart.points(y_field)))
+        .collect::<Vec<_>>();
+    let points = series.iter().flat_map(|(_, points)| points.iter());
+    let (x_min, x_max) = bounds(points.clone().map(|point| point.0));
+    let (y_min, y_max) = bounds(points.map(|point| point.1));
+
+    let y_scale = render_value_axis(svg, area, y_min, y_max);
+    let (x_min, x_max, x_step) = nice_ticks(x_min, x_max);
+    let x_scale = Scale::new((x_min, x_max), (area.left, area.right));
+    let mut x = x_min;
+    while x <= x_max + x_step / 2.0 {
+        let position = x_scale.map(x);
+        svg.line(
+            position,
+            area.bottom,
+            position,
+            area.bottom + 4.0,
+            AXIS_COLOR,
+        );
+        svg.text(
+            position,
+            area.bottom + 16.0,
+            &format_number(x),
+            "middle",
+            "",
+        );
+        x += x_step;
+    }
+    svg.line(area.left, area.bottom, area.right, area.bottom, AXIS_COLOR);
+
+    for (index, (_, points)) in series.iter().enumerate() {
+        let color = PALETTE[index % PALETTE.len()];
+        for &(x, y) in points {
+            svg.circle(x_scale.map(x), y_scale.map(y), 4.0, color);
+        }
+    }
+    if series.len() > 1 {
+        render_legend(svg, area, series.iter().map(|(name, _)| *name));
+    }
+}
+
+/// Renders a heatmap.
+fn render_heatmap(svg: &mut SvgWriter, chart: &Chart, area: &Area) {
+    let HeatmapCells {
+        x_categories,
+        y_categories,
+        cells,
+    } = chart.cells();
+    let (min, max) = bounds(cells.iter().map(|cell| cell.2));
+    let cell_width = area.width() / x_categories.len().max(1) as f64;
+    let cell_height = area.height() / y_categories.len().max(1) as f64;
+    for (x, y, value) in cells {
+        let ratio = if max > min {
+            (value - min) / (max - min)
+        } else {
+            1.0
+        };
+        let [(r0, g0, b0), (r1, g1, b1)] = HEATMAP_COLORS;
+        let color = format!(
+            "rgb({:.0},{:.0},{:.0})",
+            r0 + (r1 - r0) * ratio,
+            g0 + (g1 - g0) * ratio,
+            b0 + (b1 - b0) * ratio
+        );
+        let left = area.left + cell_width * x as f64;
+        let top = area.bottom - cell_height * (y + 1) as f64;
+        svg.rect(left, top, cell_width, cell_height, &color);
+    }
+    for (index, category) in x_categories.iter().enumerate() {
+        let x = area.left + cell_width * (index as f64 + 0.5);
+        svg.text(x, area.bottom + 16.0, category, "middle", "");
+    }
+    for (index, category) in y_categories.iter().enumerate() {
+        let y = area.bottom - cell_height * (index as f64 + 0.5) + 4.0;
+        svg.text(area.left - 6.0, y, category, "end", "");
+    }
+}
+
+/// Renders a value axis on the left side with the grid lines and returns the scale.
+fn render_value_axis(svg: &mut SvgWriter, area: &Area, min: f64, max: f64) -> Scale {
+    let (min, max, step) = nice_ticks(min, max);
+    let scale = Scale::new((min, max), (area.bottom, area.top));
+    let mut value = min;
+    while value <= max + step / 2.0 {
+        let y = scale.map(value);
+        svg.line(area.left, y, area.right, y, GRID_COLOR);
+        svg.text(area.left - 6.0, y + 4.0, &format_number(value), "end", "");
+        value += step;
+    }
+    svg.line(area.left, area.top, area.left, area.bottom, AXIS_COLOR);
+    scale
+}
+
+/// Renders the legend below the plot area.
+fn render_legend<'a>(svg: &mut SvgWriter, area: &Area, names: impl Iterator<Item = &'a str>) {
+    let y = area.bottom + 36.0;
+    let mut x = area.left;
+    for (index, name) in names.enumerate() {
+        let color = PALETTE[index % PALETTE.len()];
+        svg.rect(x, y - 9.0, 10.0, 10.0, color);
+        svg.text(x + 14.0, y, name, "start", "");
+        x += 24.0 + name.chars().count() as f64 * 7.0;
+    }
+}
+
+/// Returns the minimum and maximum of the values.
+fn bounds(values: impl Iterator<Item = f64>) -> (f64, f64) {
+    let (min, max) = values
+        .filter(|value| value.is_finite())
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), value| {
+            (min.min(value), max.max(value))
+        });
+    if min > max { (0.0, 1.0) } else { (min, max) }
+}
+
+/// Extends the range to the nice numbers and returns the step of the ticks.
+fn nice_ticks(mut min: f64, mut max: f64) -> (f64, f64, f64) {
+    if min == max {
+        if min > 0.0 {
+            min = 0.0;
+        } else if max < 0.0 {
+            max = 0.0;
+        } else {
+            max = 1.0;
+        }
+    }
+
+    let raw_step = (max - min) / TICK_COUNT as f64;
+    let magnitude = 10_f64.powf(raw_step.log10().floor());
+    let normalized = raw_step / magnitude;
+    let step = if normalized <= 1.0 {
+        1.0
+    } else if normalized <= 2.0 {
+        2.0
+    } else if normalized <= 5.0 {
+        5.0
+    } else {
+        10.0
+    } * magnitude;
+    (
+        (min / step).floor() * step,
+        (max / step).ceil() * step,
+        step,
+    )
+}
+
+/// Formats the number for the tick labels.
+fn format_number(value: f64) -> String {
+    if (value - value.round()).abs() < 1e-9 {
+        format!("{value:.0}")
+    } else {
+        let s = format!("{value:.4}");
+        s.trim_end_matches('0').trim_end_matches('.').to_owned()
+    }
+}
+
+/// Escapes the special characters in XML.
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Plot area.
+struct Area {
+    /// Left coordinate.
+    left: f64,
+    /// Top coordinate.
+    top: f64,
+    /// Right coordinate.
+    right: f64,
+    /// Bottom coordinate.
+    bottom: f64,
+}
+
+impl Area {
+    /// Returns the width.
+    #[inline]
+    fn width(&self) -> f64 {
+        (self.right - self.left).max(1.0)
+    }
+
+    /// Returns the height.
+    #[inline]
+    fn height(&self) -> f64 {
+        (self.bottom - self.top).max(1.0)
+    }
+}
+
+/// Linear scale from the domain to the range.
+struct Scale {
+    /// Domain of the values.
+    domain: (f64, f64),
+    /// Range of the coordinates.
+    range: (f64, f64),
+}
+
+impl Scale {
+    /// Creates a new instance.
+    #[inline]
+    fn new(domain: (f64, f64), range: (f64, f64)) -> Self {
+        Self { domain, range }
+    }
+
+    /// Maps the value to the coordinate.
+    #[inline]
+    fn map(&self, value: f64) -> f64 {
+        let (d0, d1) = self.domain;
+        let (r0, r1) = self.range;
+        if d1 > d0 {
+            r0 + (value - d0) / (d1 - d0) * (r1 - r0)
+        } else {
+            r0
+        }
+    }
+}
+
+/// A writer for the SVG elements.
+struct SvgWriter {
+    /// Buffer.
+    buffer: String,
+}
+
+impl SvgWriter {
+    /// Creates a new instance with the size.
+    fn new(width: f64, height: f64) -> Self {
+        let buffer = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" \
+                viewBox=\"0 0 {width} {height}\" font-family=\"sans-serif\" font-size=\"12\">\
+                <rect width=\"100%\" height=\"100%\" fill=\"white\"/>"
+        );
+        Self { buffer }
+    }
+
+    /// Writes a line.
+    fn line(&mut self, x1: f64, y1: f64, x2: f64, y2: f64, color: &str) {
+        self.buffer.push_str(&format!(
+            "<line x1=\"{x1:.1}\" y1=\"{y1:.1}\" x2=\"{x2:.1}\" y2=\"{y2:.1}\" stroke=\"{color}\"/>"
+        ));
+    }
+
+    /// Writes a rectangle.
+    fn rect(&mut self, x: f64, y: f64, width: f64, height: f64, color: &str) {
+        self.buffer.push_str(&format!(
+            "<rect x=\"{x:.1}\" y=\"{y:.1}\" width=\"{width:.1}\" height=\"{height:.1}\" \
+                fill=\"{color}\"/>"
+        ));
+    }
+
+    /// Writes a circle.
+    fn circle(&mut self, cx: f64, cy: f64, radius: f64, color: &str) {
+        self.buffer.push_str(&format!(
+            "<circle cx=\"{cx:.1}\" cy=\"{cy:.1}\" r=\"{radius:.1}\" fill=\"{color}\"/>"
+        ));
+    }
+
+    /// Writes a polyline if it has at least two points.
+    fn polyline(&mut self, points: &[(f64, f64)], color: &str) {
+        if points.len() > 1 {
+            let points = points
+                .iter()
+                .map(|(x, y)| format!("{x:.1},{y:.1}"))
+                .collect::<Vec<_>>()
+                .join(" ");
+            self.buffer.push_str(&format!(
+                "<polyline points=\"{points}\" fill=\"none\" stroke=\"{color}\" stroke-width=\"2\"/>"
+            ));
+        }
+    }
+
+    /// Writes a path.
+    fn path(&mut self, data: &str, color: &str) {
+        self.buffer.push_str(&format!(
+            "<path d=\"{data}\" fill=\"{color}\" stroke=\"white\"/>"
+        ));
+    }
+
+    /// Writes a text with the anchor and extra attributes.
+    fn text(&mut self, x: f64, y: f64, text: &str, anchor: &str, attrs: &str) {
+        let text = escape_xml(text);
+        self.buffer.push_str(&format!(
+            "<text x=\"{x:.1}\" y=\"{y:.1}\" text-anchor=\"{anchor}\" fill=\"#333\" {attrs}>{text}</text>"
+        ));
+    }
+
+    /// Finishes the document.
+    fn finish(mut self) -> String {
+        self.buffer.push_str("</svg>");
+        self.buffer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Chart, ChartKind};
+    use serde_json::json;
+    use zino_core::{JsonValue, Map};
+
+    /// Creates the data rows from a JSON array.
+    fn rows(value: JsonValue) -> Vec<Map> {
+        value
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|row| row.as_object().cloned())
+            .collect()
+    }
+
+    #[test]
+    fn it_renders_line_charts() {
+        let data = rows(json!([
+            { "month": "Jan", "sales": 10, "cost": 4 },
+            { "month": "Feb", "cost": 6 },
+            { "month": "Mar", "sales": 30, "cost": 8 },
+        ]));
+        let svg = Chart::new(ChartKind::Line, data)
+            .with_title("Sales & cost")
+            .with_x_field("month")
+            .with_y_field("sales")
+            .with_y_field("cost")
+            .render_svg();
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"640\""));
+        assert!(svg.ends_with("</svg>"));
+        assert!(svg.contains(">Sales &amp; cost</text>"));
+
+        // The missing value splits the `sales` line into single points
+        assert_eq!(svg.matches("<polyline").count(), 1);
+        assert_eq!(svg.matches("<circle").count(), 2 + 3);
+        for label in ["Jan", "Feb", "Mar", "0", "10", "20", "30", "sales", "cost"] {
+            assert!(
+                svg.contains(&format!(">{label}</text>")),
+                "missing `{label}`"
+            );
+        }
+
+        // Background and legend
+        assert_eq!(svg.matches("<rect").count(), 1 + 2);
+    }
+
+    #[test]
+    fn it_renders_bar_charts() {
+        let data = rows(json!([
+            { "name": "a", "value": -5 },
+            { "name": "b", "value": "10" },
+        ]));
+        let svg = Chart::new(ChartKind::Bar, data)
+            .with_x_field("name")
+            .with_y_field("value")
+            .render_svg();
+        assert_eq!(svg.matches("<rect").count(), 1 + 2);
+        assert!(!svg.contains("height=\"-"));
+
+        // The bars start from the zero line
+        assert!(svg.contains("<rect x=\"98.0\" y=\"236.0\" width=\"196.0\" height=\"108.0\""));
+        assert!(svg.contains("<rect x=\"378.0\" y=\"20.0\" width=\"196.0\" height=\"216.0\""));
+        assert!(svg.contains(">-5</text>"));
+    }
+
+    #[test]
+    fn it_renders_pie_charts() {
+        let data = rows(json!([
+            { "browser": "Chrome", "share": 1 },
+            { "browser": "Safari", "share": 1 },
+            { "browser": "Firefox", "share": 2 },
+            { "browser": "Opera", "share": 0 },
+        ]));
+        let svg = Chart::new(ChartKind::Pie, data)
+            .with_x_field("browser")
+            .with_y_field("share")
+            .render_svg();
+        assert_eq!(svg.matches("<path").count(), 3);
+        assert_eq!(svg.matches(" 0 0 1 ").count(), 3);
+        assert!(svg.contains(">Chrome (25.0%)</text>"));
+        assert!(svg.contains(">Firefox (50.0%)</text>"));
+        assert!(!svg.contains("Opera"));
+
+        // A single slice is rendered as a full circle
+        let data = rows(json!([{ "browser": "Chrome", "share": 3 }]));
+        let svg = Chart::new(ChartKind::Pie, data)
+            .with_x_field("browser")
+            .with_y_field("share")
+            .render_svg();
+        assert_eq!(svg.matches("<path").count(), 0);
+        assert_eq!(svg.matches("<circle").count(), 1);
+        assert!(svg.contains(">Chrome (100.0%)</text>"));
+    }
+
+    #[test]
+    fn it_renders_scatter_charts() {
+        let data = rows(json!([
+            { "x": 1, "y1": 2, "y2": 3 },
+            { "x": 2, "y1": 4 },
+            { "x": "n/a", "y1": 5 },
+        ]));
+        let svg = Chart::new(ChartKind::Scatter, data)
+            .with_x_field("x")
+            .with_y_field("y1")
+            .with_y_field("y2")
+            .render_svg();
+        assert_eq!(svg.matches("<circle").count(), 3);
+        for label in ["1", "1.2", "1.4", "1.6", "1.8", "2", "y1", "y2"] {
+            assert!(
+                svg.contains(&format!(">{label}</text>")),
+                "missing `{label}`"
+            );
+        }
+        assert_eq!(svg.matches("<rect").count(), 1 + 2);
+    }
+
+    #[test]
+    fn it_renders_heatmaps() {
+        let data = rows(json!([
+            { "day": "Mon", "hour": "9", "count": 1 },
+            { "day": "Mon", "hour": "10", "count": 5 },
+            { "day": "Tue", "hour": "9", "count": 3 },
+        ]));
+        let chart = Chart::new(ChartKind::Heatmap, data)
+            .with_x_field("day")
+            .with_y_field("hour");
+        let svg = chart.clone().with_value_field("count").render_svg();
+        assert_eq!(svg.matches("<rect").count(), 1 + 3);
+        assert!(svg.contains("fill=\"rgb(224,243,248)\""));
+        assert!(svg.contains("fill=\"rgb(215,48,39)\""));
+        for label in ["Mon", "Tue", "9", "10"] {
+            assert!(
+                svg.contains(&format!(">{label}</text>")),
+                "missing `{label}`"
+            );
+        }
+
+        // No cells are rendered without the value field
+        let svg = chart.render_svg();
+        assert_eq!(svg.matches("<rect").count(), 1);
+        assert!(svg.contains(">Mon</text>"));
+    }
+
+    #[test]
+    fn it_computes_nice_ticks() {
+        assert_eq!(super::nice_ticks(4.0, 30.0), (0.0, 30.0, 10.0));
+        assert_eq!(super::nice_ticks(-5.0, 10.0), (-5.0, 10.0, 5.0));
+        assert_eq!(super::nice_ticks(3.0, 3.0), (0.0, 3.0, 1.0));
+        assert_eq!(super::nice_ticks(0.0, 0.0), (0.0, 1.0, 0.2));
+        assert_eq!(super::format_number(2.0), "2");
+        assert_eq!(super::format_number(1.2000000000000002), "1.2");
+        assert_eq!(
+            super::escape_xml("<a & 'b'>"),
+            "&lt;a &amp; &apos;b&apos;&gt;"
+        );
+    }
+}
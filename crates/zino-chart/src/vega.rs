@@ -0,0 +1,261 @@
+use super::{Chart, ChartKind};
+use zino_core::{JsonValue, Map, extension::JsonObjectExt};
+
+/// Schema URL of Vega-Lite.
+const SCHEMA_URL: &str = "https://vega.github.io/schema/vega-lite/v5.json";
+
+/// Field name of the folded series.
+const SERIES_FIELD: &str = "series";
+
+/// Field name of the folded values.
+const VALUE_FIELD: &str = "value";
+
+/// Builds the Vega-Lite specification for the chart.
+pub(crate) fn build_spec(chart: &Chart) -> Map {
+    let mut spec = Map::new();
+    spec.upsert("$schema", SCHEMA_URL);
+    if let Some(title) = chart.title() {
+        spec.upsert("title", title);
+    }
+    spec.upsert("width", chart.width());
+    spec.upsert("height", chart.height());
+    spec.upsert("data", Map::from_entry("values", chart.rows().to_vec()));
+
+    let x_field = chart.x_field();
+    let y_fields = chart.y_fields();
+    let y_field = y_fields.first().map(|s| s.as_str()).unwrap_or_default();
+    let mut encoding = Map::new();
+    match chart.kind() {
+        ChartKind::Line | ChartKind::Bar => {
+            let (series_field, value_field) = if let Some(series_field) = chart.series_field() {
+                (Some(series_field), y_field)
+            } else if y_fields.len() > 1 {
+                let mut fold = Map::new();
+                fold.upsert("fold", y_fields);
+                fold.upsert("as", vec![SERIES_FIELD, VALUE_FIELD]);
+                spec.upsert("transform", vec![fold]);
+                (Some(SERIES_FIELD), VALUE_FIELD)
+            } else {
+                (None, y_field)
+            };
+            if chart.kind() == ChartKind::Line {
+                let mut mark = Map::from_entry("type", "line");
+                mark.upsert("point", true);
+                spec.upsert("mark", mark);
+            } else {
+                spec.upsert("mark", "bar");
+            }
+            encoding.upsert("x", field_def(x_field, "ordinal"));
+            encoding.upsert("y", field_def(value_field, "quantitative"));
+            if let Some(series_field) = series_field {
+                encoding.upsert("color", field_def(series_field, "nominal"));
+                if chart.kind() == ChartKind::Bar {
+                    encoding.upsert("xOffset", field_def(series_field, "nominal"));
+                }
+            }
+        }
+        ChartKind::Pie => {
+            spec.upsert("mark", "arc");
+            encoding.upsert("theta", field_def(y_field, "quantitative"));
+            encoding.upsert("color", field_def(x_field, "nominal"));
+        }
+        ChartKind::Scatter => {
+            spec.upsert("mark", "point");
+            encoding.upsert("x", field_def(x_field, "quantitative"));
+            encoding.upsert("y", field_def(y_field, "quantitative"));
+            if let Some(series_field) = chart.series_field() {
+                encoding.upsert("color", field_def(series_field, "nominal"));
+            }
+        }
+        ChartKind::Heatmap => {
+            spec.upsert("mark", "rect");
+            encoding.upsert("x", field_def(x_field, "nominal"));
+            encoding.upsert("y", field_def(y_field, "nominal"));
+            if let Some(value_field) = chart.value_field() {
+                encoding.upsert("color", field_def(value_field, "quantitative"));
+            }
+        }
+    }
+    spec.upsert("encoding", encoding);
+    spec
+}
+
+/// Creates a field definition of the encoding channel.
+fn field_def(field: &str, data_type: &str) -> JsonValue {
+    let mut map = Map::new();
+    map.upsert("field", field);
+    map.upsert("type", data_type);
+    map.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Chart, ChartKind};
+    use serde_json::json;
+    use zino_core::{JsonValue, Map, extension::JsonObjectExt};
+
+    /// Creates the data rows from a JSON array.
+    fn rows(value: JsonValue) -> Vec<Map> {
+        value
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|row| row.as_object().cloned())
+            .collect()
+    }
+
+    #[test]
+    fn it_builds_line_specs() {
+        let data = rows(json!([
+            { "month": "Jan", "sales": 10, "cost": 4 },
+            { "month": "Feb", "sales": 20, "cost": 6 },
+        ]));
+        let spec = Chart::new(ChartKind::Line, data.clone())
+            .with_title("Sales")
+            .with_x_field("month")
+            .with_y_field("sales")
+            .with_size(800, 300)
+            .to_vega_lite();
+        assert_eq!(
+            spec.get_str("$schema"),
+            Some("https://vega.github.io/schema/vega-lite/v5.json")
+        );
+        assert_eq!(spec.get_str("title"), Some("Sales"));
+        assert_eq!(spec.get_u32("width"), Some(800));
+        assert_eq!(spec.get_u32("height"), Some(300));
+        assert_eq!(spec.get("data"), Some(&json!({ "values": data })));
+        assert_eq!(
+            spec.get("mark"),
+            Some(&json!({ "type": "line", "point": true }))
+        );
+        assert_eq!(
+            spec.get("encoding"),
+            Some(&json!({
+                "x": { "field": "month", "type": "ordinal" },
+                "y": { "field": "sales", "type": "quantitative" },
+            }))
+        );
+        assert!(!spec.contains_key("transform"));
+
+        // Multiple y fields are folded into the series
+        let spec = Chart::new(ChartKind::Line, data)
+            .with_x_field("month")
+            .with_y_field("sales")
+            .with_y_field("cost")
+            .to_vega_lite();
+        assert!(!spec.contains_key("title"));
+        assert_eq!(
+            spec.get("transform"),
+            Some(&json!([{ "fold": ["sales", "cost"], "as": ["series", "value"] }]))
+        );
+        assert_eq!(
+            spec.get("encoding"),
+            Some(&json!({
+                "x": { "field": "month", "type": "ordinal" },
+                "y": { "field": "value", "type": "quantitative" },
+                "color": { "field": "series", "type": "nominal" },
+            }))
+        );
+    }
+
+    #[test]
+    fn it_builds_bar_specs() {
+        let data = rows(json!([
+            { "date": "2025-01-01", "status": "paid", "count": 3 },
+            { "date": "2025-01-01", "status": "refunded", "count": 1 },
+        ]));
+        let spec = Chart::new(ChartKind::Bar, data)
+            .with_x_field("date")
+            .with_y_field("count")
+            .with_series_field("status")
+            .to_vega_lite();
+        assert_eq!(spec.get_str("mark"), Some("bar"));
+        assert!(!spec.contains_key("transform"));
+        assert_eq!(
+            spec.get("encoding"),
+            Some(&json!({
+                "x": { "field": "date", "type": "ordinal" },
+                "y": { "field": "count", "type": "quantitative" },
+                "color": { "field": "status", "type": "nominal" },
+                "xOffset": { "field": "status", "type": "nominal" },
+            }))
+        );
+    }
+
+    #[test]
+    fn it_builds_pie_specs() {
+        let data = rows(json!([
+            { "browser": "Chrome", "share": 65 },
+            { "browser": "Safari", "share": 20 },
+        ]));
+        let spec = Chart::new(ChartKind::Pie, data)
+            .with_x_field("browser")
+            .with_y_field("share")
+            .to_vega_lite();
+        assert_eq!(spec.get_str("mark"), Some("arc"));
+        assert_eq!(
+            spec.get("encoding"),
+            Some(&json!({
+                "theta": { "field": "share", "type": "quantitative" },
+                "color": { "field": "browser", "type": "nominal" },
+            }))
+        );
+    }
+
+    #[test]
+    fn it_builds_scatter_specs() {
+        let data = rows(json!([
+            { "height": 170, "weight": 65, "gender": "male" },
+            { "height": 160, "weight": 50, "gender": "female" },
+        ]));
+        let chart = Chart::new(ChartKind::Scatter, data)
+            .with_x_field("height")
+            .with_y_field("weight");
+        let spec = chart.to_vega_lite();
+        assert_eq!(spec.get_str("mark"), Some("point"));
+        assert_eq!(
+            spec.get("encoding"),
+            Some(&json!({
+                "x": { "field": "height", "type": "quantitative" },
+                "y": { "field": "weight", "type": "quantitative" },
+            }))
+        );
+
+        let spec = chart.with_series_field("gender").to_vega_lite();
+        let encoding = spec
+            .get_object("encoding")
+            .expect("field `encoding` should be an object");
+        assert_eq!(
+            encoding.get("color"),
+            Some(&json!({ "field": "gender", "type": "nominal" }))
+        );
+    }
+
+    #[test]
+    fn it_builds_heatmap_specs() {
+        let data = rows(json!([
+            { "day": "Mon", "hour": "9", "count": 1 },
+            { "day": "Tue", "hour": "10", "count": 5 },
+        ]));
+        let chart = Chart::new(ChartKind::Heatmap, data)
+            .with_x_field("day")
+            .with_y_field("hour");
+        let spec = chart.clone().with_value_field("count").to_vega_lite();
+        assert_eq!(spec.get_str("mark"), Some("rect"));
+        assert_eq!(
+            spec.get("encoding"),
+            Some(&json!({
+                "x": { "field": "day", "type": "nominal" },
+                "y": { "field": "hour", "type": "nominal" },
+                "color": { "field": "count", "type": "quantitative" },
+            }))
+        );
+
+        // The color channel is omitted without the value field
+        let spec = chart.to_vega_lite();
+        let encoding = spec
+            .get_object("encoding")
+            .expect("field `encoding` should be an object");
+        assert!(!encoding.contains_key("color"));
+    }
+}
@@ -47,6 +47,7 @@ use std::{
     env, fs,
     path::{Component, Path, PathBuf},
     thread,
+    time::Duration,
 };
 use toml::value::Table;
 
@@ -56,6 +57,7 @@ mod app_type;
 mod plugin;
 mod secret_key;
 mod server_tag;
+mod shutdown;
 mod static_record;
 
 #[cfg(feature = "http-client")]
@@ -70,6 +72,9 @@ mod preferences;
 #[cfg(feature = "sentry")]
 mod sentry_client;
 
+#[cfg(feature = "tls-server")]
+mod tls_config;
+
 #[cfg(feature = "tracing-subscriber")]
 mod tracing_subscriber;
 
@@ -78,6 +83,9 @@ pub(crate) use secret_key::SECRET_KEY;
 #[cfg(feature = "preferences")]
 pub use preferences::Preferences;
 
+#[cfg(feature = "tls-server")]
+pub use tls_config::{ClientCertificate, TlsConfig};
+
 #[cfg(feature = "http-client")]
 use crate::{error::Error, extension::HeaderMapExt, trace::TraceContext};
 
@@ -86,6 +94,7 @@ pub use app_code::ApplicationCode;
 pub use app_type::AppType;
 pub use plugin::Plugin;
 pub use server_tag::ServerTag;
+pub use shutdown::{Shutdown, ShutdownNotified};
 pub use static_record::StaticRecord;
 
 /// Application interfaces.
@@ -307,10 +316,39 @@ pub trait Application {
     #[inline]
     async fn load() {}
 
-    /// Handles the graceful shutdown.
+    /// Handles the graceful shutdown after the servers have been stopped.
     #[inline]
     async fn shutdown() {}
 
+    /// Returns the timeout for draining the in-flight requests and jobs
+    /// on the graceful shutdown. The default value is `30s`.
+    fn shutdown_timeout() -> Duration {
+        SHARED_APP_STATE
+            .get_config("server")
+            .and_then(|config| config.get_duration("shutdown-timeout"))
+            .unwrap_or(Duration::from_secs(30))
+    }
+
+    /// Returns the TLS config for the listener bound to the address.
+    /// It is loaded from the `tls` table of the listener config.
+    #[cfg(feature = "tls-server")]
+    fn tls_config(addr: std::net::SocketAddr) -> Option<TlsConfig> {
+        let config = SHARED_APP_STATE
+            .get_listener_config(addr)?
+            .get_table("tls")?;
+        let tls_config = TlsConfig::with_config(config)
+            .unwrap_or_else(|err| panic!("fail to configure TLS for `{addr}`: {err}"));
+        Some(tls_config)
+    }
+
+    /// Tears down the application: runs the [`shutdown()`](Self::shutdown) handler,
+    /// then executes the hooks of [`Shutdown`] such as the plugin unloaders.
+    async fn teardown() {
+        Self::shutdown().await;
+        Shutdown::run_hooks().await;
+        tracing::warn!(app_name = Self::name(), "shutdown completed");
+    }
+
     /// Makes an HTTP request to the provided URL.
     #[cfg(feature = "http-client")]
     async fn fetch(url: &str, options: Option<&Map>) -> Result<reqwest::Response, Error> {
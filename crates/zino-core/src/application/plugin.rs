@@ -1,3 +1,4 @@
+use super::Shutdown;
 use crate::{
     BoxFuture,
     error::Error,
@@ -13,6 +14,8 @@ pub struct Plugin {
     name: &'static str,
     /// Plugin loader.
     loader: Option<BoxFuture<'static, Result<(), Error>>>,
+    /// Plugin unloader.
+    unloader: Option<BoxFuture<'static, Result<(), Error>>>,
     /// Running environments.
     environments: SmallVec<[Env; 2]>,
     /// Dependencies.
@@ -26,6 +29,7 @@ impl Plugin {
         Self {
             name,
             loader: None,
+            unloader: None,
             environments: SmallVec::new(),
             dependencies: SmallVec::new(),
         }
@@ -37,6 +41,7 @@ impl Plugin {
         Self {
             name,
             loader: Some(loader),
+            unloader: None,
             environments: SmallVec::new(),
             dependencies: SmallVec::new(),
         }
@@ -48,6 +53,13 @@ impl Plugin {
         self.loader = Some(loader);
     }
 
+    /// Sets an asynchronous unloader for the plugin.
+    /// It will be executed on the graceful shutdown if the plugin has been loaded.
+    #[inline]
+    pub fn set_unloader(&mut self, unloader: BoxFuture<'static, Result<(), Error>>) {
+        self.unloader = Some(unloader);
+    }
+
     /// Enables the running environment [`Env::Dev`].
     #[inline]
     pub fn enable_dev(&mut self) {
@@ -113,13 +125,14 @@ impl Plugin {
         }
     }
 
-    /// Loads the plugin.
-    #[inline]
+    /// Loads the plugin and registers the unloader as a shutdown hook.
     pub async fn load(self) -> Result<(), Error> {
         if let Some(loader) = self.loader {
-            loader.await
-        } else {
-            Ok(())
+            loader.await?;
+        }
+        if let Some(unloader) = self.unloader {
+            Shutdown::add_hook(self.name, unloader);
         }
+        Ok(())
     }
 }
@@ -0,0 +1,287 @@
+use crate::{BoxFuture, error::Error};
+use parking_lot::Mutex;
+use std::{
+    fmt,
+    future::Future,
+    pin::Pin,
+    sync::atomic::{
+        AtomicBool, AtomicU64,
+        Ordering::{Relaxed, SeqCst},
+    },
+    task::{Context, Poll, Waker},
+};
+
+/// Coordinator of the graceful shutdown.
+///
+/// The shutdown is triggered by the `Ctrl+C` or terminate signal,
+/// or programmatically via [`Shutdown::trigger()`]. After the servers have been stopped,
+/// the registered hooks are executed in the reverse order of the registration.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Shutdown;
+
+impl Shutdown {
+    /// Triggers the graceful shutdown and wakes up all the waiters.
+    #[inline]
+    pub fn trigger() {
+        SHUTDOWN_STATE.trigger();
+    }
+
+    /// Returns `true` if the graceful shutdown has been triggered.
+    #[inline]
+    pub fn is_triggered() -> bool {
+        SHUTDOWN_STATE.is_triggered()
+    }
+
+    /// Returns a future which resolves once the graceful shutdown has been triggered.
+    #[inline]
+    pub fn wait() -> ShutdownNotified {
+        SHUTDOWN_STATE.wait()
+    }
+
+    /// Registers a hook executed on the graceful shutdown.
+    #[inline]
+    pub fn add_hook(name: &'static str, hook: BoxFuture<'static, Result<(), Error>>) {
+        SHUTDOWN_STATE.add_hook(name, hook);
+    }
+
+    /// Executes the registered hooks in the reverse order of the registration.
+    #[inline]
+    pub async fn run_hooks() {
+        SHUTDOWN_STATE.run_hooks().await;
+    }
+
+    /// Listens for the `Ctrl+C` or terminate signal and triggers the graceful shutdown.
+    #[cfg(feature = "runtime-tokio")]
+    pub async fn listen() {
+        use tokio::signal;
+
+        let ctrl_c = async {
+            if let Err(err) = signal::ctrl_c().await {
+                tracing::error!("fail to install the `Ctrl+C` handler: {err}");
+            }
+        };
+        #[cfg(unix)]
+        let terminate = async {
+            signal::unix::signal(signal::unix::SignalKind::terminate())
+                .expect("fail to install the terminate signal handler")
+                .recv()
+                .await;
+        };
+        #[cfg(not(unix))]
+        let terminate = std::future::pending::<()>();
+        let signal_received = tokio::select! {
+            _ = ctrl_c => true,
+            _ = terminate => true,
+            _ = Self::wait() => false,
+        };
+        if signal_received {
+            tracing::warn!("signal received, starting graceful shutdown");
+        }
+        Self::trigger();
+    }
+}
+
+/// A future which resolves once the graceful shutdown has been triggered.
+///
+/// The waker is deregistered when the future is dropped.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct ShutdownNotified {
+    /// Shutdown state.
+    state: &'static ShutdownState,
+    /// Key of the registered waker.
+    key: Option<u64>,
+}
+
+impl fmt::Debug for ShutdownNotified {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ShutdownNotified")
+            .field("key", &self.key)
+            .finish()
+    }
+}
+
+impl Future for ShutdownNotified {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let state = self.state;
+        if state.is_triggered() {
+            return Poll::Ready(());
+        }
+
+        let mut wakers = state.wakers.lock();
+
+        // Checks the flag again to avoid missing the wakeup.
+        if state.is_triggered() {
+            return Poll::Ready(());
+        }
+        if let Some(key) = self.key
+            && let Some((_, waker)) = wakers.iter_mut().find(|(k, _)| *k == key)
+        {
+            if !waker.will_wake(cx.waker()) {
+                waker.clone_from(cx.waker());
+            }
+        } else {
+            let key = state.next_key.fetch_add(1, Relaxed);
+            wakers.push((key, cx.waker().clone()));
+            drop(wakers);
+            self.key = Some(key);
+        }
+        Poll::Pending
+    }
+}
+
+impl Drop for ShutdownNotified {
+    fn drop(&mut self) {
+        if let Some(key) = self.key.take() {
+            self.state.wakers.lock().retain(|(k, _)| *k != key);
+        }
+    }
+}
+
+/// A named hook executed on the graceful shutdown.
+type ShutdownHook = (&'static str, BoxFuture<'static, Result<(), Error>>);
+
+/// State of the graceful shutdown.
+struct ShutdownState {
+    /// Flag to indicate whether the graceful shutdown has been triggered.
+    triggered: AtomicBool,
+    /// Key for the next registered waker.
+    next_key: AtomicU64,
+    /// Wakers of the pending waiters.
+    wakers: Mutex<Vec<(u64, Waker)>>,
+    /// Hooks executed on the graceful shutdown.
+    hooks: Mutex<Vec<ShutdownHook>>,
+}
+
+impl ShutdownState {
+    /// Creates a new instance.
+    const fn new() -> Self {
+        Self {
+            triggered: AtomicBool::new(false),
+            next_key: AtomicU64::new(0),
+            wakers: Mutex::new(Vec::new()),
+            hooks: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Triggers the graceful shutdown and wakes up all the waiters.
+    fn trigger(&self) {
+        if !self.triggered.swap(true, SeqCst) {
+            let wakers = std::mem::take(&mut *self.wakers.lock());
+            for (_, waker) in wakers {
+                waker.wake();
+            }
+        }
+    }
+
+    /// Returns `true` if the graceful shutdown has been triggered.
+    #[inline]
+    fn is_triggered(&self) -> bool {
+        self.triggered.load(SeqCst)
+    }
+
+    /// Returns a future which resolves once the graceful shutdown has been triggered.
+    #[inline]
+    fn wait(&'static self) -> ShutdownNotified {
+        ShutdownNotified {
+            state: self,
+            key: None,
+        }
+    }
+
+    /// Registers a hook executed on the graceful shutdown.
+    #[inline]
+    fn add_hook(&self, name: &'static str, hook: BoxFuture<'static, Result<(), Error>>) {
+        self.hooks.lock().push((name, hook));
+    }
+
+    /// Executes the registered hooks in the reverse order of the registration.
+    async fn run_hooks(&self) {
+        let hooks = self.hooks.lock().drain(..).rev().collect::<Vec<_>>();
+        for (name, hook) in hooks {
+            if let Err(err) = hook.await {
+                tracing::error!("fail to run the shutdown hook `{name}`: {err}");
+            } else {
+                tracing::info!("run the shutdown hook `{name}`");
+            }
+        }
+    }
+}
+
+/// Shared state of the graceful shutdown.
+static SHUTDOWN_STATE: ShutdownState = ShutdownState::new();
+
+#[cfg(test)]
+mod tests {
+    use super::{SHUTDOWN_STATE, ShutdownState};
+    use crate::{application::Plugin, error::Error};
+    use futures::{executor::block_on, future::FutureExt};
+    use parking_lot::Mutex;
+    use std::{sync::Arc, thread};
+
+    #[test]
+    fn it_deregisters_dropped_waiters() {
+        static STATE: ShutdownState = ShutdownState::new();
+        for _ in 0..3 {
+            let mut notified = STATE.wait();
+            assert!((&mut notified).now_or_never().is_none());
+            assert!((&mut notified).now_or_never().is_none());
+            assert_eq!(STATE.wakers.lock().len(), 1);
+        }
+        assert!(STATE.wakers.lock().is_empty());
+        assert!(!STATE.is_triggered());
+    }
+
+    #[test]
+    fn it_shuts_down_gracefully() {
+        static STATE: ShutdownState = ShutdownState::new();
+        let executed_hooks = Arc::new(Mutex::new(Vec::new()));
+        for name in ["first", "second"] {
+            let executed_hooks = executed_hooks.clone();
+            let hook = async move {
+                executed_hooks.lock().push(name);
+                if name == "second" {
+                    Err(Error::new("fail to run the hook"))
+                } else {
+                    Ok(())
+                }
+            };
+            STATE.add_hook(name, Box::pin(hook));
+        }
+
+        let mut notified = STATE.wait();
+        assert!((&mut notified).now_or_never().is_none());
+
+        let waiter = thread::spawn(|| block_on(STATE.wait()));
+        STATE.trigger();
+        waiter.join().expect("fail to join the thread");
+        assert!(STATE.is_triggered());
+        assert!(STATE.wakers.lock().is_empty());
+        assert!(notified.now_or_never().is_some());
+
+        // Triggering the shutdown again is a no-op
+        STATE.trigger();
+        assert!(STATE.wait().now_or_never().is_some());
+
+        block_on(STATE.run_hooks());
+        assert_eq!(*executed_hooks.lock(), ["second", "first"]);
+
+        // The hooks are executed only once
+        block_on(STATE.run_hooks());
+        assert_eq!(executed_hooks.lock().len(), 2);
+    }
+
+    #[test]
+    fn it_registers_plugin_unloaders() {
+        let mut plugin = Plugin::new("shutdown-test");
+        plugin.set_unloader(Box::pin(async { Ok(()) }));
+        block_on(plugin.load()).expect("fail to load the plugin");
+
+        // The shared shutdown is not triggered by the registration
+        let hooks = SHUTDOWN_STATE.hooks.lock();
+        assert!(hooks.iter().any(|(name, _)| *name == "shutdown-test"));
+        assert!(!SHUTDOWN_STATE.is_triggered());
+    }
+}
@@ -1,5 +1,8 @@
 //! Base64 encoding and decoding.
-use base64::{DecodeError, Engine, engine::general_purpose::STANDARD_NO_PAD};
+use base64::{
+    DecodeError, Engine,
+    engine::general_purpose::{STANDARD_NO_PAD, URL_SAFE_NO_PAD},
+};
 
 /// Encodes the data as base64 string.
 #[inline]
@@ -13,6 +16,18 @@ pub fn decode(data: impl AsRef<[u8]>) -> Result<Vec<u8>, DecodeError> {
     STANDARD_NO_PAD.decode(data)
 }
 
+/// Encodes the data as URL-safe base64 string.
+#[inline]
+pub fn encode_url_safe(data: impl AsRef<[u8]>) -> String {
+    URL_SAFE_NO_PAD.encode(data)
+}
+
+/// Decodes the URL-safe base64-encoded data as `Vec<u8>`.
+#[inline]
+pub fn decode_url_safe(data: impl AsRef<[u8]>) -> Result<Vec<u8>, DecodeError> {
+    URL_SAFE_NO_PAD.decode(data)
+}
+
 /// Encodes the data as base64-encoded data URL string.
 pub fn encode_data_url(data: impl AsRef<[u8]>) -> String {
     fn inner(bytes: &[u8]) -> String {
@@ -8,6 +8,7 @@ use crate::{
 };
 use chrono::NaiveDateTime;
 use convert_case::{Case, Casing};
+use csv::{ByteRecord, Writer};
 use rust_decimal::Decimal;
 use serde_json::map::Entry;
 use std::{
@@ -273,6 +274,9 @@ pub trait JsonObjectExt {
     /// Serializes the map into a query string.
     fn to_query_string(&self) -> String;
 
+    /// Attempts to write the values of the fields as a CSV record without the headers.
+    fn to_csv_record(&self, fields: &[String], buffer: Vec<u8>) -> Result<Vec<u8>, csv::Error>;
+
     /// Consumes `self` and constructs an Avro record value.
     fn into_avro_record(self) -> Record;
 
@@ -863,6 +867,20 @@ impl JsonObjectExt for Map {
         serde_qs::to_string(&self).unwrap_or_default()
     }
 
+    fn to_csv_record(&self, fields: &[String], buffer: Vec<u8>) -> Result<Vec<u8>, csv::Error> {
+        let mut wtr = Writer::from_writer(buffer);
+        let num_fields = fields.len();
+        let buffer_size = num_fields * 8;
+        let mut record = ByteRecord::with_capacity(buffer_size, num_fields);
+        for field in fields {
+            let value = self.parse_string(field).unwrap_or("".into());
+            record.push_field(value.as_ref().as_bytes());
+        }
+        wtr.write_byte_record(&record)?;
+        wtr.flush()?;
+        wtr.into_inner().map_err(|err| err.into_error().into())
+    }
+
     fn into_avro_record(self) -> Record {
         let mut record = Record::with_capacity(self.len());
         for (field, value) in self.into_iter() {
@@ -254,7 +254,25 @@ impl<'a> Column<'a> {
     /// Returns `true` if the column supports fuzzy search.
     #[inline]
     pub fn fuzzy_search(&self) -> bool {
-        self.index_type() == Some("text") || self.has_attribute("fuzzy_search")
+        self.has_fulltext_index() || self.has_attribute("fuzzy_search")
+    }
+
+    /// Returns `true` if the column has a full-text search index.
+    #[inline]
+    pub fn has_fulltext_index(&self) -> bool {
+        self.index_type().is_some_and(|index_type| {
+            matches!(index_type, "text" | "fulltext")
+                || index_type.starts_with("text:")
+                || index_type.starts_with("fulltext:")
+        })
+    }
+
+    /// Returns the text search configuration of the full-text search index.
+    #[inline]
+    pub fn fulltext_language(&self) -> Option<&'a str> {
+        self.index_type()?
+            .strip_prefix("fulltext:")
+            .or_else(|| self.index_type()?.strip_prefix("text:"))
     }
 
     /// Returns the Avro schema.
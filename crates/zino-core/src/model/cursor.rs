@@ -0,0 +1,219 @@
+use super::QueryOrder;
+use crate::{JsonValue, Map, encoding::base64, error::Error, extension::JsonObjectExt};
+
+/// An opaque cursor for the keyset pagination.
+///
+/// The cursor records the values of the sort fields for a boundary model,
+/// in which the primary key is appended as a tiebreaker to make the order total.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use zino_core::model::{Query, QueryCursor};
+///
+/// let mut query = Query::default();
+/// query.order_desc("updated_at");
+/// query.order_by_tiebreaker("id");
+///
+/// let models = User::find::<Map>(&query).await?;
+/// if let Some(model) = models.last() {
+///     let cursor = QueryCursor::next(query.sort_order(), "id", model);
+///     query.set_cursor(cursor);
+///
+///     // `WHERE (updated_at, id) < ('2025-01-01T00:00:00Z', '...')`
+///     let models = User::find::<Map>(&query).await?;
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct QueryCursor {
+    /// The sort keys with the boundary values.
+    keys: Vec<(QueryOrder, JsonValue)>,
+    /// A flag to indicate whether the cursor points to the previous page.
+    backward: bool,
+}
+
+impl QueryCursor {
+    /// Creates a new instance for the page after the model.
+    #[inline]
+    pub fn next(sort_order: &[QueryOrder], primary_key_name: &str, model: &Map) -> Self {
+        Self::new(sort_order, primary_key_name, model, false)
+    }
+
+    /// Creates a new instance for the page before the model.
+    #[inline]
+    pub fn prev(sort_order: &[QueryOrder], primary_key_name: &str, model: &Map) -> Self {
+        Self::new(sort_order, primary_key_name, model, true)
+    }
+
+    /// Creates a new instance with the sort order and the boundary model.
+    fn new(sort_order: &[QueryOrder], primary_key_name: &str, model: &Map, backward: bool) -> Self {
+        let mut keys = sort_order
+            .iter()
+            .map(|order| {
+                let value = model.get(order.field()).cloned().unwrap_or_default();
+                (order.clone(), value)
+            })
+            .collect::<Vec<_>>();
+        if !sort_order
+            .iter()
+            .any(|order| order.field() == primary_key_name)
+        {
+            let descending = sort_order.last().is_some_and(|order| order.is_descending());
+            let order = QueryOrder::new(primary_key_name.to_owned(), descending);
+            let value = model.get(primary_key_name).cloned().unwrap_or_default();
+            keys.push((order, value));
+        }
+        Self { keys, backward }
+    }
+
+    /// Returns the sort keys with the boundary values.
+    #[inline]
+    pub fn keys(&self) -> &[(QueryOrder, JsonValue)] {
+        self.keys.as_slice()
+    }
+
+    /// Returns the sort order recorded in the cursor.
+    #[inline]
+    pub fn sort_order(&self) -> Vec<QueryOrder> {
+        self.keys.iter().map(|(order, _)| order.clone()).collect()
+    }
+
+    /// Returns `true` if the cursor points to the previous page.
+    #[inline]
+    pub fn is_backward(&self) -> bool {
+        self.backward
+    }
+
+    /// Returns `true` if the sort keys are the same as the sort order,
+    /// regardless of the placement of nulls.
+    pub fn matches(&self, sort_order: &[QueryOrder]) -> bool {
+        self.keys.len() == sort_order.len()
+            && self.keys.iter().zip(sort_order).all(|((key, _), order)| {
+                key.field() == order.field() && key.is_descending() == order.is_descending()
+            })
+    }
+
+    /// Encodes the cursor as an opaque string.
+    pub fn encode(&self) -> String {
+        let keys = self
+            .keys
+            .iter()
+            .map(|(order, value)| {
+                let direction = if order.is_descending() { "desc" } else { "asc" };
+                JsonValue::from(vec![order.field().into(), direction.into(), value.clone()])
+            })
+            .collect::<Vec<_>>();
+        let mut map = Map::new();
+        map.upsert("keys", keys);
+        if self.backward {
+            map.upsert("backward", true);
+        }
+        base64::encode_url_safe(JsonValue::from(map).to_string())
+    }
+
+    /// Decodes an opaque string as the cursor.
+    pub fn decode(cursor: &str) -> Result<Self, Error> {
+        let data = base64::decode_url_safe(cursor)?;
+        let map = serde_json::from_slice::<Map>(&data)?;
+        let Some(entries) = map.get_array("keys").filter(|v| !v.is_empty()) else {
+            return Err(Error::new("the cursor should have nonempty keys"));
+        };
+
+        let mut keys = Vec::with_capacity(entries.len());
+        for entry in entries {
+            if let Some([field, direction, value]) = entry.as_array().map(|v| v.as_slice())
+                && let Some(field) = field.as_str().filter(|s| is_valid_field(s))
+            {
+                let descending = direction.as_str() == Some("desc");
+                keys.push((QueryOrder::new(field.to_owned(), descending), value.clone()));
+            } else {
+                return Err(Error::new("the cursor has an invalid sort key"));
+            }
+        }
+        Ok(Self {
+            keys,
+            backward: map.get_bool("backward").unwrap_or_default(),
+        })
+    }
+}
+
+/// Returns `true` if the field name only contains the characters `[a-zA-Z0-9_.]`.
+fn is_valid_field(field: &str) -> bool {
+    !field.is_empty()
+        && field
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{QueryCursor, QueryOrder};
+    use crate::{Map, extension::JsonObjectExt, model::Query};
+
+    #[test]
+    fn it_encodes_query_cursors() {
+        let sort_order = [QueryOrder::new("updated_at", true)];
+        let mut model = Map::new();
+        model.upsert("id", 42);
+        model.upsert("updated_at", "2025-01-01T00:00:00Z");
+
+        let cursor = QueryCursor::prev(&sort_order, "id", &model);
+        let encoded = cursor.encode();
+        assert!(!encoded.contains(['+', '/', '=']));
+
+        let decoded = QueryCursor::decode(&encoded).expect("fail to decode the cursor");
+        assert!(decoded.is_backward());
+        assert_eq!(decoded.keys().len(), 2);
+
+        let (order, value) = &decoded.keys()[1];
+        assert_eq!(order.field(), "id");
+        assert!(order.is_descending());
+        assert_eq!(value.as_i64(), Some(42));
+        assert!(QueryCursor::decode("invalid").is_err());
+
+        assert!(decoded.matches(&[
+            QueryOrder::new("updated_at", true),
+            QueryOrder::new("id", true)
+        ]));
+        assert!(!decoded.matches(&[QueryOrder::new("updated_at", true)]));
+        assert!(!decoded.matches(&[
+            QueryOrder::new("updated_at", false),
+            QueryOrder::new("id", true)
+        ]));
+    }
+
+    #[test]
+    fn it_rejects_invalid_sort_keys() {
+        let mut model = Map::new();
+        model.upsert("id", 1);
+
+        let sort_order = [QueryOrder::new(r#"id" = 1 OR "x"#, false)];
+        let encoded = QueryCursor::next(&sort_order, "id", &model).encode();
+        assert!(QueryCursor::decode(&encoded).is_err());
+
+        let sort_order = [QueryOrder::new("user.name", false)];
+        let encoded = QueryCursor::next(&sort_order, "id", &model).encode();
+        assert!(QueryCursor::decode(&encoded).is_ok());
+    }
+
+    #[test]
+    fn it_checks_the_sort_order_of_query_cursors() {
+        let sort_order = [QueryOrder::new("updated_at", true)];
+        let mut model = Map::new();
+        model.upsert("id", 42);
+        model.upsert("updated_at", "2025-01-01T00:00:00Z");
+        let cursor = QueryCursor::next(&sort_order, "id", &model).encode();
+
+        let mut data = Map::new();
+        data.upsert("order_by", "updated_at");
+        data.upsert("cursor", cursor.as_str());
+        let mut query = Query::default();
+        assert!(query.read_map(&data).is_success());
+        assert!(query.cursor().is_some());
+
+        data.upsert("order_by", "updated_at|asc");
+        let mut query = Query::default();
+        assert!(query.read_map(&data).contains_key("cursor"));
+        assert!(query.cursor().is_none());
+    }
+}
@@ -4,6 +4,7 @@ use serde::{Serialize, de::DeserializeOwned};
 
 mod column;
 mod context;
+mod cursor;
 mod hook;
 mod mutation;
 mod order;
@@ -16,6 +17,7 @@ pub use apache_avro::schema;
 
 pub use column::Column;
 pub use context::QueryContext;
+pub use cursor::QueryCursor;
 pub use hook::ModelHooks;
 pub use mutation::Mutation;
 pub use order::QueryOrder;
@@ -1,4 +1,4 @@
-use super::QueryOrder;
+use super::{QueryCursor, QueryOrder};
 use crate::{
     JsonValue, Map, SharedString,
     extension::{JsonObjectExt, JsonValueExt},
@@ -18,6 +18,8 @@ pub struct Query {
     offset: usize,
     /// Limit.
     limit: usize,
+    /// Cursor for the keyset pagination.
+    cursor: Option<QueryCursor>,
     /// Extra flags.
     extra: Map,
 }
@@ -33,6 +35,7 @@ impl Query {
             sort_order: Vec::new(),
             offset: 0,
             limit: 0,
+            cursor: None,
             extra: Map::new(),
         }
     }
@@ -48,6 +51,7 @@ impl Query {
     pub fn read_map(&mut self, data: &Map) -> Validation {
         let mut validation = Validation::new();
         let mut pagination_current_page = None;
+        let mut pagination_cursor = None;
         let filters = &mut self.filters;
         let extra = &mut self.extra;
         for (key, value) in data.iter().filter(|(_, v)| !v.is_ignorable()) {
@@ -97,6 +101,14 @@ impl Query {
                         }
                     }
                 }
+                "cursor" => {
+                    if let Some(cursor) = value.as_str() {
+                        match QueryCursor::decode(cursor) {
+                            Ok(cursor) => pagination_cursor = Some(cursor),
+                            Err(err) => validation.record_fail("cursor", err),
+                        }
+                    }
+                }
                 "populate" | "translate" | "show_deleted" | "validate_only" | "no_check" => {
                     if let Some(result) = value.parse_bool() {
                         match result {
@@ -136,6 +148,18 @@ impl Query {
         if let Some(current_page) = pagination_current_page {
             self.offset = self.limit * current_page.saturating_sub(1);
         }
+        if let Some(cursor) = pagination_cursor {
+            // The tiebreaker of the cursor is checked when the query is executed
+            let mut sort_order = self.sort_order.clone();
+            if let Some((order, _)) = cursor.keys().get(sort_order.len()) {
+                sort_order.push(order.clone());
+            }
+            if cursor.matches(&sort_order) {
+                self.set_cursor(cursor);
+            } else {
+                validation.record("cursor", "it does not match the sort order");
+            }
+        }
         validation
     }
 
@@ -275,6 +299,37 @@ impl Query {
         self.sort_order.push(QueryOrder::new(field, true));
     }
 
+    /// Appends a unique field as the tiebreaker of the sort order if it is absent,
+    /// which follows the direction of the last query order.
+    pub fn order_by_tiebreaker(&mut self, field: impl Into<SharedString>) {
+        let field = field.into();
+        if !self
+            .sort_order
+            .iter()
+            .any(|order| order.field() == field.as_ref())
+        {
+            let descending = self
+                .sort_order
+                .last()
+                .is_some_and(|order| order.is_descending());
+            self.sort_order.push(QueryOrder::new(field, descending));
+        }
+    }
+
+    /// Sets the cursor for the keyset pagination and resets the offset.
+    /// The sort keys of the cursor should match the sort order of the query.
+    #[inline]
+    pub fn set_cursor(&mut self, cursor: QueryCursor) {
+        self.offset = 0;
+        self.cursor = Some(cursor);
+    }
+
+    /// Removes the cursor for the keyset pagination.
+    #[inline]
+    pub fn remove_cursor(&mut self) -> Option<QueryCursor> {
+        self.cursor.take()
+    }
+
     /// Sets the query offset.
     #[inline]
     pub fn set_offset(&mut self, offset: usize) {
@@ -323,6 +378,12 @@ impl Query {
         self.limit
     }
 
+    /// Returns a reference to the cursor for the keyset pagination.
+    #[inline]
+    pub fn cursor(&self) -> Option<&QueryCursor> {
+        self.cursor.as_ref()
+    }
+
     /// Returns a reference to the extra flags.
     #[inline]
     pub fn extra(&self) -> &Map {
@@ -375,6 +436,7 @@ impl Default for Query {
             sort_order: Vec::new(),
             offset: 0,
             limit: 10,
+            cursor: None,
             extra: Map::new(),
         }
     }
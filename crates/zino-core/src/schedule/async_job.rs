@@ -1,10 +1,18 @@
 //! Scheduler for sync and async cron jobs.
 
-use super::{AsyncScheduler, DEFAULT_TICK_INTERVAL, JobContext};
-use crate::{BoxFuture, Uuid, datetime::DateTime, extension::TomlTableExt};
+use super::{
+    AsyncScheduler, CatchUp, DEFAULT_LEASE_TTL, DEFAULT_TICK_INTERVAL, JobContext, JobState,
+    JobStore, RetryPolicy, policy::ConcurrencySlot,
+};
+use crate::{
+    BoxFuture, Uuid, application::Shutdown, datetime::DateTime, error::Error,
+    extension::TomlTableExt,
+};
 use chrono::Local;
 use cron::Schedule;
-use std::{io, str::FromStr, time::Duration};
+use futures::future::{self, Either, FutureExt};
+use futures_timer::Delay;
+use std::{io, mem, str::FromStr, sync::Arc, time::Duration};
 use toml::Table;
 
 /// A function pointer of the async cron job.
@@ -18,6 +26,8 @@ pub struct AsyncJob {
     schedule: Schedule,
     /// Cron job to run.
     run: AsyncCronJob,
+    /// Flag to indicate whether the job state has been restored from the store.
+    restored: bool,
 }
 
 impl AsyncJob {
@@ -36,6 +46,7 @@ impl AsyncJob {
             context,
             schedule,
             run: exec,
+            restored: false,
         }
     }
 
@@ -62,10 +73,27 @@ impl AsyncJob {
         {
             context.set_remaining_ticks(ticks);
         }
+        if let Some(catch_up) = config.get_str("catch-up").and_then(CatchUp::parse) {
+            context.set_catch_up(catch_up);
+        }
+        if config.contains_key("max-retries") {
+            context.set_retry_policy(RetryPolicy::with_config(config));
+        }
+        if let Some(timeout) = config.get_duration("timeout") {
+            context.set_timeout(timeout);
+        }
+        if let Some(max_concurrency) = config
+            .get_bool("no-overlap")
+            .and_then(|b| b.then_some(1))
+            .or_else(|| config.get_usize("max-concurrency"))
+        {
+            context.set_max_concurrency(max_concurrency);
+        }
         Self {
             context,
             schedule,
             run: exec,
+            restored: false,
         }
     }
 
@@ -97,6 +125,44 @@ impl AsyncJob {
         self
     }
 
+    /// Sets the policy for catching up the missed runs.
+    #[inline]
+    pub fn catch_up(mut self, catch_up: CatchUp) -> Self {
+        self.context.set_catch_up(catch_up);
+        self
+    }
+
+    /// Sets the policy for retrying the failed job.
+    #[inline]
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.context.set_retry_policy(policy);
+        self
+    }
+
+    /// Sets the timeout for the job execution.
+    /// The execution will be cancelled and recorded as failed if it exceeds the timeout.
+    #[inline]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.context.set_timeout(timeout);
+        self
+    }
+
+    /// Sets the maximum number of concurrent executions for the job.
+    /// The execution will be skipped if the limit has been reached.
+    #[inline]
+    pub fn max_concurrency(mut self, max_concurrency: usize) -> Self {
+        self.context.set_max_concurrency(max_concurrency);
+        self
+    }
+
+    /// Sets the maximum number of concurrent executions as `1`
+    /// to ensure that the executions of the job do not overlap.
+    #[inline]
+    pub fn no_overlap(mut self) -> Self {
+        self.context.set_max_concurrency(1);
+        self
+    }
+
     /// Enables the flag to indicate whether the job is disabled.
     #[inline]
     pub fn disable(mut self, disabled: bool) -> Self {
@@ -130,20 +196,24 @@ impl AsyncJob {
         let ctx = &mut self.context;
         let run = self.run;
         if ctx.is_immediate() && !ctx.is_disabled() && !ctx.is_fused() {
-            ctx.start();
-            ctx.set_next_tick(upcoming);
-            run(ctx).await;
-            ctx.finish();
+            Self::run_once(ctx, run, upcoming).await;
         } else if let Some(last_tick) = ctx.last_tick().map(|dt| dt.into()) {
-            for event in self.schedule.after(&last_tick) {
-                if event > now || ctx.is_fused() {
+            if ctx.is_disabled() {
+                return;
+            }
+
+            let (num_due, num_runs) = ctx.catch_up().plan(&self.schedule, &last_tick, &now);
+            if num_runs < num_due {
+                ctx.set_last_tick(now.into());
+            }
+            for _ in 0..num_runs {
+                if ctx.is_fused() || !Self::run_once(ctx, run, upcoming).await {
                     break;
                 }
-                if !ctx.is_disabled() {
-                    ctx.start();
-                    ctx.set_next_tick(upcoming);
-                    run(ctx).await;
-                    ctx.finish();
+
+                // The retry of a failed run takes precedence over the remaining missed runs.
+                if ctx.retry_count() > 0 {
+                    break;
                 }
             }
         } else {
@@ -151,15 +221,67 @@ impl AsyncJob {
         }
     }
 
+    /// Executes the missed runs asynchronously with the job store.
+    ///
+    /// The job state is restored from the store before the first tick,
+    /// and the lease of the job should be acquired before running the missed runs
+    /// so that only one owner runs each tick.
+    pub async fn tick_with_store(
+        &mut self,
+        store: &dyn JobStore,
+        owner: &str,
+        lease_ttl: Duration,
+    ) -> Result<(), Error> {
+        let job_key = self.job_key().to_owned();
+        if !self.restored {
+            if let Some(state) = store.load(&job_key).await? {
+                state.restore(&mut self.context);
+            }
+            self.restored = true;
+        }
+        if !self.is_due() || !store.acquire_lease(&job_key, owner, lease_ttl).await? {
+            return Ok(());
+        }
+
+        // The job may have been executed by another owner.
+        if let Some(state) = store.load(&job_key).await? {
+            state.restore(&mut self.context);
+        }
+        self.tick().await;
+
+        let state = JobState::from_context(&self.context);
+        let result = store.save(&job_key, &state).await;
+        store.release_lease(&job_key, owner).await?;
+        result
+    }
+
     /// Executes the job manually.
     pub async fn execute(&mut self) {
         let upcoming = self.upcoming();
-        let ctx = &mut self.context;
-        let run = self.run;
+        Self::run_once(&mut self.context, self.run, upcoming).await;
+    }
+
+    /// Runs the job once with the execution policies.
+    /// It returns `false` if the execution is skipped.
+    async fn run_once(ctx: &mut JobContext, run: AsyncCronJob, upcoming: Option<DateTime>) -> bool {
+        let Some(_slot) = ConcurrencySlot::acquire(ctx.job_key(), ctx.max_concurrency()) else {
+            ctx.skip();
+            return false;
+        };
         ctx.start();
         ctx.set_next_tick(upcoming);
-        run(ctx).await;
+        if let Some(timeout) = ctx.timeout() {
+            // The timer does not depend on the async runtime.
+            let execution = future::select(run(ctx), Delay::new(timeout));
+            if matches!(execution.await, Either::Right(_)) {
+                let message = format!("job execution timed out after {timeout:?}");
+                ctx.record_error(Error::new(message));
+            }
+        } else {
+            run(ctx).await;
+        }
         ctx.finish();
+        true
     }
 
     /// Returns a reference to the job context.
@@ -179,20 +301,85 @@ impl AsyncJob {
     pub fn upcoming(&self) -> Option<DateTime> {
         self.schedule.upcoming(Local).next().map(|dt| dt.into())
     }
+
+    /// Returns the key to identify the job in a job store,
+    /// *i.e.* the job name or the cron expression.
+    #[inline]
+    pub fn job_key(&self) -> &str {
+        self.context.job_key()
+    }
+
+    /// Returns `true` if the job is due to run or the last tick should be initialized.
+    fn is_due(&self) -> bool {
+        let ctx = &self.context;
+        if ctx.is_disabled() || ctx.is_fused() {
+            false
+        } else if ctx.is_immediate() {
+            true
+        } else if let Some(last_tick) = ctx.last_tick().map(|dt| dt.into()) {
+            self.schedule
+                .after(&last_tick)
+                .next()
+                .is_some_and(|event| event <= Local::now())
+        } else {
+            true
+        }
+    }
 }
 
 /// A type contains and executes the async scheduled jobs.
-#[derive(Default)]
+///
+/// When there is a Tokio runtime, the due jobs are spawned as tasks so that
+/// a long-running job does not stall the ticks of the others. They are detached
+/// from the scheduler until finished, and are waited for on the graceful shutdown.
 pub struct AsyncJobScheduler {
     /// A list of async jobs.
     jobs: Vec<AsyncJob>,
+    /// Jobs running in the background.
+    running_jobs: Vec<(Uuid, BoxFuture<'static, Option<AsyncJob>>)>,
+    /// IDs of the running jobs to be removed once finished.
+    removed_jobs: Vec<Uuid>,
+    /// An optional job store.
+    store: Option<Arc<dyn JobStore>>,
+    /// Owner of the job leases.
+    owner: String,
+    /// Time-to-live for the job leases.
+    lease_ttl: Duration,
 }
 
 impl AsyncJobScheduler {
     /// Creates a new instance.
     #[inline]
     pub fn new() -> Self {
-        Self { jobs: Vec::new() }
+        Self {
+            jobs: Vec::new(),
+            running_jobs: Vec::new(),
+            removed_jobs: Vec::new(),
+            store: None,
+            owner: String::new(),
+            lease_ttl: DEFAULT_LEASE_TTL,
+        }
+    }
+
+    /// Creates a new instance with the job store.
+    #[inline]
+    pub fn with_store(store: impl JobStore + 'static) -> Self {
+        Self {
+            jobs: Vec::new(),
+            running_jobs: Vec::new(),
+            removed_jobs: Vec::new(),
+            store: Some(Arc::new(store)),
+            owner: Uuid::now_v7().to_string(),
+            lease_ttl: DEFAULT_LEASE_TTL,
+        }
+    }
+
+    /// Sets the time-to-live for the job leases.
+    /// It should be longer than the execution time of the jobs.
+    #[inline]
+    pub fn lease_ttl(mut self, ttl: Duration) -> Self {
+        self.lease_ttl = ttl;
+        self
     }
 
     /// Adds an async job to the scheduler and returns the job ID.
@@ -204,6 +391,7 @@ impl AsyncJobScheduler {
     }
 
     /// Removes an async job by ID from the scheduler.
+    /// A running job will be removed once finished.
     pub fn remove(&mut self, job_id: Uuid) -> bool {
         let position = self
             .jobs
@@ -212,12 +400,18 @@ impl AsyncJobScheduler {
         if let Some(index) = position {
             self.jobs.remove(index);
             true
+        } else if self.running_jobs.iter().any(|(id, _)| *id == job_id) {
+            if !self.removed_jobs.contains(&job_id) {
+                self.removed_jobs.push(job_id);
+            }
+            true
         } else {
             false
         }
     }
 
     /// Returns a reference to the job with the ID.
+    /// The jobs running in the background are not accessible until finished.
     #[inline]
     pub fn get(&self, job_id: Uuid) -> Option<&AsyncJob> {
         self.jobs
@@ -226,6 +420,7 @@ impl AsyncJobScheduler {
     }
 
     /// Returns a mutable reference to the job with the ID.
+    /// The jobs running in the background are not accessible until finished.
     #[inline]
     pub fn get_mut(&mut self, job_id: Uuid) -> Option<&mut AsyncJob> {
         self.jobs
@@ -264,34 +459,129 @@ impl AsyncJobScheduler {
 
     /// Increments time for the scheduler and executes any pending jobs asynchronously.
     /// It is recommended to sleep for at least 500 milliseconds between invocations of this method.
+    ///
+    /// The due jobs are spawned as Tokio tasks if possible, and are ticked concurrently
+    /// otherwise. The executions of the jobs with the same key are limited
+    /// by the concurrency policy. Once the graceful shutdown has been triggered,
+    /// it waits for the running jobs instead of executing the pending ones.
     pub async fn tick(&mut self) {
-        let mut fused_jobs = Vec::new();
-        for job in &mut self.jobs {
-            job.tick().await;
+        if Shutdown::is_triggered() {
+            let running_jobs = mem::take(&mut self.running_jobs);
+            let finished_jobs = future::join_all(running_jobs.into_iter().map(|(_, job)| job));
+            for job in finished_jobs.await.into_iter().flatten() {
+                self.restore_job(job);
+            }
+        } else {
+            self.collect_finished_jobs();
+
+            let mut pending_jobs = Vec::new();
+            for job in mem::take(&mut self.jobs) {
+                if !job.is_due() {
+                    self.jobs.push(job);
+                } else if let Some(job) = self.spawn_job(job) {
+                    pending_jobs.push(job);
+                }
+            }
+
+            let store = self.store.as_deref();
+            let owner = self.owner.as_str();
+            let lease_ttl = self.lease_ttl;
+            let ticks = pending_jobs
+                .iter_mut()
+                .map(|job| tick_job(job, store, owner, lease_ttl));
+            future::join_all(ticks).await;
+            self.jobs.append(&mut pending_jobs);
+        }
+        self.jobs.retain(|job| !job.context().is_fused());
+    }
+
+    /// Spawns a task to tick the job if there is a Tokio runtime,
+    /// and returns the job back if it can not be spawned.
+    fn spawn_job(&mut self, job: AsyncJob) -> Option<AsyncJob> {
+        #[cfg(feature = "runtime-tokio")]
+        if let Ok(handle) = tokio::runtime::Handle::try_current() {
+            let mut job = job;
+            let job_id = job.context().job_id();
+            let store = self.store.clone();
+            let owner = self.owner.clone();
+            let lease_ttl = self.lease_ttl;
+            let task = handle.spawn(async move {
+                tick_job(&mut job, store.as_deref(), &owner, lease_ttl).await;
+                job
+            });
+            let running_job = task.map(move |result| {
+                result
+                    .inspect_err(|err| tracing::error!(%job_id, "fail to run the job: {err}"))
+                    .ok()
+            });
+            self.running_jobs.push((job_id, running_job.boxed()));
+            return None;
+        }
+        Some(job)
+    }
 
-            let ctx = job.context();
-            if ctx.is_fused() {
-                fused_jobs.push(ctx.job_id());
+    /// Collects the running jobs which have been finished.
+    fn collect_finished_jobs(&mut self) {
+        let mut index = 0;
+        while index < self.running_jobs.len() {
+            if let Some(result) = (&mut self.running_jobs[index].1).now_or_never() {
+                let (job_id, _) = self.running_jobs.swap_remove(index);
+                if let Some(job) = result {
+                    self.restore_job(job);
+                } else {
+                    self.removed_jobs.retain(|&id| id != job_id);
+                }
+            } else {
+                index += 1;
             }
         }
-        for job_id in fused_jobs {
-            self.remove(job_id);
+    }
+
+    /// Restores a finished job to the scheduler unless it has been removed.
+    fn restore_job(&mut self, job: AsyncJob) {
+        let job_id = job.context().job_id();
+        if let Some(index) = self.removed_jobs.iter().position(|&id| id == job_id) {
+            self.removed_jobs.swap_remove(index);
+        } else {
+            self.jobs.push(job);
         }
     }
 
     /// Executes all the jobs manually.
     #[inline]
     pub async fn execute(&mut self) {
-        for job in &mut self.jobs {
-            job.execute().await;
+        future::join_all(self.jobs.iter_mut().map(|job| job.execute())).await;
+    }
+}
+
+/// Ticks the job with an optional job store.
+async fn tick_job(
+    job: &mut AsyncJob,
+    store: Option<&dyn JobStore>,
+    owner: &str,
+    lease_ttl: Duration,
+) {
+    if let Some(store) = store {
+        if let Err(err) = job.tick_with_store(store, owner, lease_ttl).await {
+            let job_key = job.job_key();
+            tracing::error!(job_key, "fail to tick the job with the store: {err}");
         }
+    } else {
+        job.tick().await;
+    }
+}
+
+impl Default for AsyncJobScheduler {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 impl AsyncScheduler for AsyncJobScheduler {
     #[inline]
     fn is_ready(&self) -> bool {
-        !self.jobs.is_empty()
+        !self.jobs.is_empty() || !self.running_jobs.is_empty()
     }
 
     #[inline]
@@ -1,9 +1,56 @@
+use super::{CatchUp, RetryPolicy};
 use crate::{Uuid, datetime::DateTime, error::Error};
 use std::{
     any::Any,
+    collections::VecDeque,
     time::{Duration, Instant},
 };
 
+/// A record of the job execution.
+#[derive(Debug, Clone)]
+pub struct JobRun {
+    /// The start time.
+    start_time: DateTime,
+    /// The execution time.
+    execution_time: Duration,
+    /// The retry attempt, which is `0` for a scheduled run.
+    attempt: usize,
+    /// The error message.
+    error: Option<String>,
+}
+
+impl JobRun {
+    /// Returns the start time.
+    #[inline]
+    pub fn start_time(&self) -> DateTime {
+        self.start_time
+    }
+
+    /// Returns the execution time.
+    #[inline]
+    pub fn execution_time(&self) -> Duration {
+        self.execution_time
+    }
+
+    /// Returns the retry attempt, which is `0` for a scheduled run.
+    #[inline]
+    pub fn attempt(&self) -> usize {
+        self.attempt
+    }
+
+    /// Returns the error message if the execution failed.
+    #[inline]
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    /// Returns `true` if the execution succeeded.
+    #[inline]
+    pub fn is_success(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
 /// Data associated with a job.
 #[derive(Debug)]
 pub struct JobContext {
@@ -21,6 +68,24 @@ pub struct JobContext {
     immediate: bool,
     /// Remaining ticks.
     remaining_ticks: Option<usize>,
+    /// Policy for catching up the missed runs.
+    catch_up: CatchUp,
+    /// Policy for retrying the failed job.
+    retry_policy: RetryPolicy,
+    /// Timeout for the job execution.
+    timeout: Option<Duration>,
+    /// Maximum number of concurrent executions.
+    max_concurrency: Option<usize>,
+    /// Number of retries for the current run.
+    retry_count: usize,
+    /// Number of executions.
+    num_runs: usize,
+    /// Number of failed executions.
+    num_failures: usize,
+    /// Number of consecutive failures.
+    consecutive_failures: usize,
+    /// Recent runs of the job.
+    run_history: VecDeque<JobRun>,
     /// Last time when running the job.
     last_tick: Option<DateTime>,
     /// Next time when running the job.
@@ -43,6 +108,15 @@ impl JobContext {
             disabled: false,
             immediate: false,
             remaining_ticks: None,
+            catch_up: CatchUp::default(),
+            retry_policy: RetryPolicy::default(),
+            timeout: None,
+            max_concurrency: None,
+            retry_count: 0,
+            num_runs: 0,
+            num_failures: 0,
+            consecutive_failures: 0,
+            run_history: VecDeque::new(),
             last_tick: None,
             next_tick: None,
             execution_error: None,
@@ -54,12 +128,42 @@ impl JobContext {
     #[inline]
     pub fn start(&mut self) {
         self.start_time = Instant::now();
+        self.execution_error = None;
     }
 
     /// Finishes the job.
+    ///
+    /// If the execution failed and the retry policy allows,
+    /// a retry will be scheduled after the backoff delay.
     pub fn finish(&mut self) {
-        if let Some(ticks) = self.remaining_ticks {
-            self.remaining_ticks = Some(ticks.saturating_sub(1));
+        let execution_time = self.start_time.elapsed();
+        let failed = self.execution_error.is_some();
+        let retrying = failed && self.retry_count < self.retry_policy.max_retries();
+        let attempt = self.retry_count;
+        self.num_runs += 1;
+        if failed {
+            self.num_failures += 1;
+            self.consecutive_failures += 1;
+        } else {
+            self.consecutive_failures = 0;
+        }
+        if self.run_history.len() >= MAX_RUN_HISTORY {
+            self.run_history.pop_front();
+        }
+        self.run_history.push_back(JobRun {
+            start_time: DateTime::now() - execution_time,
+            execution_time,
+            attempt,
+            error: self.execution_error.as_ref().map(|err| err.to_string()),
+        });
+        if retrying {
+            self.retry_count += 1;
+            self.retry_after(self.retry_policy.delay(self.retry_count));
+        } else {
+            self.retry_count = 0;
+            if let Some(ticks) = self.remaining_ticks {
+                self.remaining_ticks = Some(ticks.saturating_sub(1));
+            }
         }
 
         let job_id = self.job_id.to_string();
@@ -67,7 +171,6 @@ impl JobContext {
         let remaining_ticks = self.remaining_ticks;
         let last_tick = self.last_tick.map(|dt| dt.to_string());
         let next_tick = self.next_tick.map(|dt| dt.to_string());
-        let execution_time = self.start_time.elapsed();
         let execution_time_millis = execution_time.as_millis();
         if let Some(error) = self.execution_error.as_ref() {
             tracing::error!(
@@ -77,6 +180,8 @@ impl JobContext {
                 last_tick,
                 next_tick,
                 execution_time_millis,
+                attempt,
+                retrying,
                 "{error}"
             );
         } else {
@@ -87,25 +192,64 @@ impl JobContext {
                 last_tick,
                 next_tick,
                 execution_time_millis,
+                attempt,
             );
         }
         #[cfg(feature = "metrics")]
-        if let Some(name) = job_name {
+        {
+            let (label_key, label_value) = self.metric_label();
+            let status = if failed { "failure" } else { "success" };
             metrics::histogram!(
                 "zino_job_execution_duration_seconds",
-                "job_name" => name,
+                label_key => label_value.clone(),
             )
             .record(execution_time.as_secs_f64());
-        } else {
-            metrics::histogram!(
-                "zino_job_execution_duration_seconds",
-                "job_id" => job_id,
+            metrics::counter!(
+                "zino_job_executions_total",
+                label_key => label_value.clone(),
+                "status" => status,
             )
-            .record(execution_time.as_secs_f64());
+            .increment(1);
+            metrics::gauge!(
+                "zino_job_consecutive_failures",
+                label_key => label_value.clone(),
+            )
+            .set(self.consecutive_failures as f64);
+            if retrying {
+                metrics::counter!("zino_job_retries_total", label_key => label_value).increment(1);
+            }
         }
         self.set_last_tick(DateTime::now());
     }
 
+    /// Skips the job execution since the concurrency limit has been reached.
+    pub(super) fn skip(&self) {
+        let job_id = self.job_id.to_string();
+        let job_name = self.job_name;
+        let max_concurrency = self.max_concurrency;
+        tracing::warn!(
+            job_id,
+            job_name,
+            max_concurrency,
+            "job execution is skipped because the concurrency limit has been reached"
+        );
+        #[cfg(feature = "metrics")]
+        {
+            let (label_key, label_value) = self.metric_label();
+            metrics::counter!("zino_job_skipped_total", label_key => label_value).increment(1);
+        }
+    }
+
+    /// Returns the label to identify the job in metrics.
+    #[cfg(feature = "metrics")]
+    fn metric_label(&self) -> (&'static str, String) {
+        if let Some(name) = self.job_name {
+            ("job_name", name.to_owned())
+        } else {
+            ("job_id", self.job_id.to_string())
+        }
+    }
+
     /// Records an error occurred in the job execution.
     #[inline]
     pub fn record_error(&mut self, error: impl Into<Error>) {
@@ -136,6 +280,31 @@ impl JobContext {
         self.remaining_ticks = Some(ticks);
     }
 
+    /// Sets the policy for catching up the missed runs.
+    #[inline]
+    pub fn set_catch_up(&mut self, catch_up: CatchUp) {
+        self.catch_up = catch_up;
+    }
+
+    /// Sets the policy for retrying the failed job.
+    #[inline]
+    pub fn set_retry_policy(&mut self, policy: RetryPolicy) {
+        self.retry_policy = policy;
+    }
+
+    /// Sets the timeout for the job execution.
+    #[inline]
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = Some(timeout);
+    }
+
+    /// Sets the maximum number of concurrent executions.
+    /// A value of `1` prevents the executions of the job from overlapping.
+    #[inline]
+    pub fn set_max_concurrency(&mut self, max_concurrency: usize) {
+        self.max_concurrency = Some(max_concurrency);
+    }
+
     /// Sets the last tick.
     #[inline]
     pub fn set_last_tick(&mut self, last_tick: DateTime) {
@@ -202,6 +371,12 @@ impl JobContext {
         self.job_name
     }
 
+    /// Returns the key to identify the job, *i.e.* the job name or the cron expression.
+    #[inline]
+    pub fn job_key(&self) -> &str {
+        self.job_name.unwrap_or(&self.source)
+    }
+
     /// Returns a reference to the source, *i.e.* the cron expression for a cron job.
     #[inline]
     pub fn source(&self) -> &str {
@@ -232,6 +407,66 @@ impl JobContext {
         self.remaining_ticks == Some(0)
     }
 
+    /// Returns the remaining ticks.
+    #[inline]
+    pub fn remaining_ticks(&self) -> Option<usize> {
+        self.remaining_ticks
+    }
+
+    /// Returns the policy for catching up the missed runs.
+    #[inline]
+    pub fn catch_up(&self) -> CatchUp {
+        self.catch_up
+    }
+
+    /// Returns the policy for retrying the failed job.
+    #[inline]
+    pub fn retry_policy(&self) -> &RetryPolicy {
+        &self.retry_policy
+    }
+
+    /// Returns the timeout for the job execution.
+    #[inline]
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    /// Returns the maximum number of concurrent executions.
+    #[inline]
+    pub fn max_concurrency(&self) -> Option<usize> {
+        self.max_concurrency
+    }
+
+    /// Returns the number of retries for the current run.
+    #[inline]
+    pub fn retry_count(&self) -> usize {
+        self.retry_count
+    }
+
+    /// Returns the number of executions.
+    #[inline]
+    pub fn num_runs(&self) -> usize {
+        self.num_runs
+    }
+
+    /// Returns the number of failed executions.
+    #[inline]
+    pub fn num_failures(&self) -> usize {
+        self.num_failures
+    }
+
+    /// Returns the number of consecutive failures.
+    #[inline]
+    pub fn consecutive_failures(&self) -> usize {
+        self.consecutive_failures
+    }
+
+    /// Returns the recent runs of the job in chronological order.
+    #[inline]
+    pub fn run_history(&self) -> &VecDeque<JobRun> {
+        &self.run_history
+    }
+
     /// Returns the last tick.
     #[inline]
     pub fn last_tick(&self) -> Option<DateTime> {
@@ -257,3 +492,6 @@ impl Default for JobContext {
         Self::new()
     }
 }
+
+/// Maximum number of the runs kept in the history.
+const MAX_RUN_HISTORY: usize = 10;
@@ -1,10 +1,14 @@
 //! Scheduler for sync and async cron jobs.
 
-use super::{DEFAULT_TICK_INTERVAL, JobContext, Scheduler};
-use crate::{Uuid, datetime::DateTime, extension::TomlTableExt};
+use super::{
+    CatchUp, DEFAULT_LEASE_TTL, DEFAULT_TICK_INTERVAL, JobContext, JobState, JobStore, RetryPolicy,
+    Scheduler, policy::ConcurrencySlot,
+};
+use crate::{Uuid, datetime::DateTime, error::Error, extension::TomlTableExt};
 use chrono::Local;
 use cron::Schedule;
-use std::{str::FromStr, time::Duration};
+use futures::executor;
+use std::{str::FromStr, sync::Arc, time::Duration};
 use toml::Table;
 
 /// A function pointer of the cron job.
@@ -18,6 +22,8 @@ pub struct Job {
     schedule: Schedule,
     /// Cron job to run.
     run: CronJob,
+    /// Flag to indicate whether the job state has been restored from the store.
+    restored: bool,
 }
 
 impl Job {
@@ -36,6 +42,7 @@ impl Job {
             context,
             schedule,
             run: exec,
+            restored: false,
         }
     }
 
@@ -62,10 +69,27 @@ impl Job {
         {
             context.set_remaining_ticks(ticks);
         }
+        if let Some(catch_up) = config.get_str("catch-up").and_then(CatchUp::parse) {
+            context.set_catch_up(catch_up);
+        }
+        if config.contains_key("max-retries") {
+            context.set_retry_policy(RetryPolicy::with_config(config));
+        }
+        if let Some(timeout) = config.get_duration("timeout") {
+            context.set_timeout(timeout);
+        }
+        if let Some(max_concurrency) = config
+            .get_bool("no-overlap")
+            .and_then(|b| b.then_some(1))
+            .or_else(|| config.get_usize("max-concurrency"))
+        {
+            context.set_max_concurrency(max_concurrency);
+        }
         Self {
             context,
             schedule,
             run: exec,
+            restored: false,
         }
     }
 
@@ -97,6 +121,46 @@ impl Job {
         self
     }
 
+    /// Sets the policy for catching up the missed runs.
+    #[inline]
+    pub fn catch_up(mut self, catch_up: CatchUp) -> Self {
+        self.context.set_catch_up(catch_up);
+        self
+    }
+
+    /// Sets the policy for retrying the failed job.
+    #[inline]
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.context.set_retry_policy(policy);
+        self
+    }
+
+    /// Sets the timeout for the job execution.
+    ///
+    /// Since a sync job can not be interrupted, the execution will be recorded as failed
+    /// after it completes if the timeout has been exceeded.
+    #[inline]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.context.set_timeout(timeout);
+        self
+    }
+
+    /// Sets the maximum number of concurrent executions for the job.
+    /// The execution will be skipped if the limit has been reached.
+    #[inline]
+    pub fn max_concurrency(mut self, max_concurrency: usize) -> Self {
+        self.context.set_max_concurrency(max_concurrency);
+        self
+    }
+
+    /// Sets the maximum number of concurrent executions as `1`
+    /// to ensure that the executions of the job do not overlap.
+    #[inline]
+    pub fn no_overlap(mut self) -> Self {
+        self.context.set_max_concurrency(1);
+        self
+    }
+
     /// Enables the flag to indicate whether the job is disabled.
     #[inline]
     pub fn disable(mut self, disabled: bool) -> Self {
@@ -130,20 +194,24 @@ impl Job {
         let ctx = &mut self.context;
         let run = self.run;
         if ctx.is_immediate() && !ctx.is_disabled() && !ctx.is_fused() {
-            ctx.start();
-            ctx.set_next_tick(upcoming);
-            run(ctx);
-            ctx.finish();
+            Self::run_once(ctx, run, upcoming);
         } else if let Some(last_tick) = ctx.last_tick().map(|dt| dt.into()) {
-            for event in self.schedule.after(&last_tick) {
-                if event > now || ctx.is_fused() {
+            if ctx.is_disabled() {
+                return;
+            }
+
+            let (num_due, num_runs) = ctx.catch_up().plan(&self.schedule, &last_tick, &now);
+            if num_runs < num_due {
+                ctx.set_last_tick(now.into());
+            }
+            for _ in 0..num_runs {
+                if ctx.is_fused() || !Self::run_once(ctx, run, upcoming) {
                     break;
                 }
-                if !ctx.is_disabled() {
-                    ctx.start();
-                    ctx.set_next_tick(upcoming);
-                    run(ctx);
-                    ctx.finish();
+
+                // The retry of a failed run takes precedence over the remaining missed runs.
+                if ctx.retry_count() > 0 {
+                    break;
                 }
             }
         } else {
@@ -151,15 +219,65 @@ impl Job {
         }
     }
 
+    /// Executes the missed runs with the job store.
+    ///
+    /// The job state is restored from the store before the first tick,
+    /// and the lease of the job should be acquired before running the missed runs
+    /// so that only one owner runs each tick.
+    pub fn tick_with_store(
+        &mut self,
+        store: &dyn JobStore,
+        owner: &str,
+        lease_ttl: Duration,
+    ) -> Result<(), Error> {
+        let job_key = self.job_key().to_owned();
+        if !self.restored {
+            if let Some(state) = executor::block_on(store.load(&job_key))? {
+                state.restore(&mut self.context);
+            }
+            self.restored = true;
+        }
+        if !self.is_due() || !executor::block_on(store.acquire_lease(&job_key, owner, lease_ttl))? {
+            return Ok(());
+        }
+
+        // The job may have been executed by another owner.
+        if let Some(state) = executor::block_on(store.load(&job_key))? {
+            state.restore(&mut self.context);
+        }
+        self.tick();
+
+        let state = JobState::from_context(&self.context);
+        let result = executor::block_on(store.save(&job_key, &state));
+        executor::block_on(store.release_lease(&job_key, owner))?;
+        result
+    }
+
     /// Executes the job manually.
     pub fn execute(&mut self) {
         let upcoming = self.upcoming();
-        let ctx = &mut self.context;
-        let run = self.run;
+        Self::run_once(&mut self.context, self.run, upcoming);
+    }
+
+    /// Runs the job once with the execution policies.
+    /// It returns `false` if the execution is skipped.
+    fn run_once(ctx: &mut JobContext, run: CronJob, upcoming: Option<DateTime>) -> bool {
+        let Some(_slot) = ConcurrencySlot::acquire(ctx.job_key(), ctx.max_concurrency()) else {
+            ctx.skip();
+            return false;
+        };
         ctx.start();
         ctx.set_next_tick(upcoming);
         run(ctx);
+        if let Some(timeout) = ctx.timeout()
+            && ctx.start_time().elapsed() > timeout
+            && ctx.execution_error().is_none()
+        {
+            let message = format!("job execution exceeded the timeout of {timeout:?}");
+            ctx.record_error(Error::new(message));
+        }
         ctx.finish();
+        true
     }
 
     /// Returns a reference to the job context.
@@ -179,20 +297,84 @@ impl Job {
     pub fn upcoming(&self) -> Option<DateTime> {
         self.schedule.upcoming(Local).next().map(|dt| dt.into())
     }
+
+    /// Returns the key to identify the job in a job store,
+    /// *i.e.* the job name or the cron expression.
+    #[inline]
+    pub fn job_key(&self) -> &str {
+        self.context.job_key()
+    }
+
+    /// Returns `true` if the job is due to run or the last tick should be initialized.
+    fn is_due(&self) -> bool {
+        let ctx = &self.context;
+        if ctx.is_disabled() || ctx.is_fused() {
+            false
+        } else if ctx.is_immediate() {
+            true
+        } else if let Some(last_tick) = ctx.last_tick().map(|dt| dt.into()) {
+            self.schedule
+                .after(&last_tick)
+                .next()
+                .is_some_and(|event| event <= Local::now())
+        } else {
+            true
+        }
+    }
 }
 
 /// A type contains and executes the scheduled jobs.
-#[derive(Default)]
+///
+/// The scheduler can be backed by a [`JobStore`] to coordinate the jobs across
+/// multiple instances. Since the store is async, the futures are blocked on
+/// within the Tokio runtime where the scheduler has been created if there is one.
 pub struct JobScheduler {
     /// A list of jobs.
     jobs: Vec<Job>,
+    /// An optional job store.
+    store: Option<Arc<dyn JobStore>>,
+    /// Owner of the job leases.
+    owner: String,
+    /// Time-to-live for the job leases.
+    lease_ttl: Duration,
+    /// Handle of the Tokio runtime to access the job store.
+    #[cfg(feature = "runtime-tokio")]
+    runtime_handle: Option<tokio::runtime::Handle>,
 }
 
 impl JobScheduler {
     /// Creates a new instance.
     #[inline]
     pub fn new() -> Self {
-        Self { jobs: Vec::new() }
+        Self {
+            jobs: Vec::new(),
+            store: None,
+            owner: String::new(),
+            lease_ttl: DEFAULT_LEASE_TTL,
+            #[cfg(feature = "runtime-tokio")]
+            runtime_handle: None,
+        }
+    }
+
+    /// Creates a new instance with the job store.
+    #[inline]
+    pub fn with_store(store: impl JobStore + 'static) -> Self {
+        Self {
+            jobs: Vec::new(),
+            store: Some(Arc::new(store)),
+            owner: Uuid::now_v7().to_string(),
+            lease_ttl: DEFAULT_LEASE_TTL,
+            #[cfg(feature = "runtime-tokio")]
+            runtime_handle: tokio::runtime::Handle::try_current().ok(),
+        }
+    }
+
+    /// Sets the time-to-live for the job leases.
+    /// It should be longer than the execution time of the jobs.
+    #[inline]
+    pub fn lease_ttl(mut self, ttl: Duration) -> Self {
+        self.lease_ttl = ttl;
+        self
     }
 
     /// Adds a job to the scheduler and returns the job ID.
@@ -265,9 +447,19 @@ impl JobScheduler {
     /// Increments time for the scheduler and executes any pending jobs.
     /// It is recommended to sleep for at least 500 milliseconds between invocations of this method.
     pub fn tick(&mut self) {
+        #[cfg(feature = "runtime-tokio")]
+        let _guard = self.runtime_handle.as_ref().map(|handle| handle.enter());
+
         let mut fused_jobs = Vec::new();
         for job in &mut self.jobs {
-            job.tick();
+            if let Some(store) = self.store.as_deref() {
+                if let Err(err) = job.tick_with_store(store, &self.owner, self.lease_ttl) {
+                    let job_key = job.job_key();
+                    tracing::error!(job_key, "fail to tick the job with the store: {err}");
+                }
+            } else {
+                job.tick();
+            }
 
             let ctx = job.context();
             if ctx.is_fused() {
@@ -288,6 +480,13 @@ impl JobScheduler {
     }
 }
 
+impl Default for JobScheduler {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Scheduler for JobScheduler {
     #[inline]
     fn is_ready(&self) -> bool {
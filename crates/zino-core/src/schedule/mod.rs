@@ -1,14 +1,93 @@
 //! Scheduler for sync and async cron jobs.
+//!
+//! Both the sync and async job schedulers can be backed by a [`JobStore`] to persist
+//! the job states and coordinate the job executions across multiple instances, in which
+//! a lease-based lock ensures that only one instance runs each tick.
+//!
+//! The execution policies of a job can be specified in the configuration:
+//!
+//! ```toml
+//! [[jobs]]
+//! cron = "0 0 * * * *"
+//! max-retries = 3
+//! backoff = "1s"
+//! max-backoff = "5m"
+//! jitter = true
+//! timeout = "10m"
+//! no-overlap = true
+//! ```
 
+use chrono::{DateTime, Local};
+use cron::Schedule;
 use std::{future::Future, io, time::Duration};
 
 mod async_job;
 mod context;
 mod job;
+mod policy;
+mod store;
 
 pub use async_job::{AsyncCronJob, AsyncJob, AsyncJobScheduler};
-pub use context::JobContext;
+pub use context::{JobContext, JobRun};
 pub use job::{CronJob, Job, JobScheduler};
+pub use policy::RetryPolicy;
+pub use store::{JobState, JobStore, MemoryJobStore};
+
+/// Policies for catching up the runs missed during the downtime.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CatchUp {
+    /// Skips the missed runs and waits for the next scheduled time.
+    Skip,
+    /// Runs the job once for all the missed runs.
+    RunOnce,
+    /// Runs the job for each of the missed runs.
+    #[default]
+    RunAll,
+}
+
+impl CatchUp {
+    /// Parses the catch-up policy.
+    pub fn parse(policy: &str) -> Option<Self> {
+        match policy {
+            "skip" => Some(Self::Skip),
+            "run-once" | "run_once" => Some(Self::RunOnce),
+            "run-all" | "run_all" => Some(Self::RunAll),
+            _ => None,
+        }
+    }
+
+    /// Plans the runs for the scheduled events between the last tick and now.
+    /// It returns the number of due events and the number of runs to be executed.
+    ///
+    /// A due event is considered to be missed if it is earlier than
+    /// the misfire grace period before now.
+    fn plan(
+        self,
+        schedule: &Schedule,
+        last_tick: &DateTime<Local>,
+        now: &DateTime<Local>,
+    ) -> (usize, usize) {
+        let mut num_due = 0;
+        let mut latest_event = None;
+        for event in schedule.after(last_tick) {
+            if &event > now {
+                break;
+            }
+            num_due += 1;
+            latest_event = Some(event);
+        }
+
+        let num_runs = match self {
+            Self::Skip => latest_event
+                .and_then(|event| (*now - event).to_std().ok())
+                .filter(|&lateness| lateness <= MISFIRE_GRACE_PERIOD)
+                .map_or(0, |_| 1),
+            Self::RunOnce => num_due.min(1),
+            Self::RunAll => num_due,
+        };
+        (num_due, num_runs)
+    }
+}
 
 /// An interface for scheduling sync jobs.
 pub trait Scheduler {
@@ -62,9 +141,253 @@ impl AsyncScheduler for apalis::prelude::Monitor {
 
     #[inline]
     async fn run(self) -> io::Result<()> {
-        self.run().await
+        // Stops the workers gracefully once the shutdown has been triggered.
+        let signal = async {
+            crate::application::Shutdown::wait().await;
+            Ok(())
+        };
+        self.run_with_signal(signal).await
     }
 }
 
 /// The default tick interval.
 const DEFAULT_TICK_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Default time-to-live for the job leases.
+const DEFAULT_LEASE_TTL: Duration = Duration::from_secs(300);
+
+/// The grace period for a scheduled event to be not considered as missed.
+const MISFIRE_GRACE_PERIOD: Duration = Duration::from_secs(5);
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        AsyncJob, AsyncJobScheduler, CatchUp, Job, JobContext, JobScheduler, JobStore,
+        MemoryJobStore, RetryPolicy,
+    };
+    use crate::{BoxFuture, datetime::DateTime, error::Error};
+    use chrono::{Duration, Local};
+    use cron::Schedule;
+    use futures::{executor::block_on, future};
+    use std::{
+        future::Future,
+        pin::Pin,
+        str::FromStr,
+        sync::{
+            Arc,
+            atomic::{AtomicUsize, Ordering::Relaxed},
+        },
+        task::{Context, Poll},
+    };
+
+    /// A future which yields to the executor once.
+    struct YieldNow(bool);
+
+    impl Future for YieldNow {
+        type Output = ();
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+            if self.0 {
+                Poll::Ready(())
+            } else {
+                self.0 = true;
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+        }
+    }
+
+    #[test]
+    fn it_plans_missed_runs() {
+        let schedule =
+            Schedule::from_str("0 * * * * *").expect("fail to parse the cron expression");
+        let now = Local::now();
+        let last_tick = now - Duration::minutes(10);
+        assert_eq!(CatchUp::RunAll.plan(&schedule, &last_tick, &now).1, 10);
+        assert_eq!(CatchUp::RunOnce.plan(&schedule, &last_tick, &now).1, 1);
+
+        let (num_due, num_runs) = CatchUp::Skip.plan(&schedule, &last_tick, &now);
+        assert_eq!(num_due, 10);
+        assert!(num_runs <= 1);
+
+        let last_tick = now - Duration::seconds(1);
+        let (num_due, num_runs) = CatchUp::Skip.plan(&schedule, &last_tick, &now);
+        assert_eq!(num_due, num_runs);
+    }
+
+    #[test]
+    fn it_computes_retry_delays() {
+        let policy = RetryPolicy::new(5)
+            .backoff(std::time::Duration::from_secs(1))
+            .max_backoff(std::time::Duration::from_secs(10))
+            .jitter(false);
+        assert_eq!(policy.delay(1).as_secs(), 1);
+        assert_eq!(policy.delay(3).as_secs(), 4);
+        assert_eq!(policy.delay(5).as_secs(), 10);
+        assert_eq!(policy.delay(100).as_secs(), 10);
+
+        let delay = policy.jitter(true).delay(4);
+        assert!(delay.as_secs_f64() >= 4.0 && delay.as_secs_f64() <= 8.0);
+    }
+
+    #[test]
+    fn it_coordinates_sync_jobs_with_store() {
+        static NUM_RUNS: AtomicUsize = AtomicUsize::new(0);
+
+        fn count(_ctx: &mut JobContext) {
+            NUM_RUNS.fetch_add(1, Relaxed);
+        }
+
+        let store = Arc::new(MemoryJobStore::new());
+        let mut schedulers = [
+            JobScheduler::with_store(store.clone()),
+            JobScheduler::with_store(store.clone()),
+        ];
+        for scheduler in &mut schedulers {
+            scheduler.add(
+                Job::new("0 0 * * * *", count)
+                    .name("counter")
+                    .once()
+                    .immediate(true),
+            );
+        }
+        for scheduler in &mut schedulers {
+            scheduler.tick();
+            scheduler.tick();
+        }
+        assert_eq!(NUM_RUNS.load(Relaxed), 1);
+
+        // The lease held by another owner blocks the execution.
+        let lease_ttl = std::time::Duration::from_secs(60);
+        let mut scheduler = JobScheduler::with_store(store.clone());
+        let job_id = scheduler.add(
+            Job::new("0 0 * * * *", count)
+                .name("blocked")
+                .immediate(true),
+        );
+        assert!(
+            block_on(store.acquire_lease("blocked", "another-owner", lease_ttl))
+                .expect("fail to acquire the lease")
+        );
+        scheduler.tick();
+        assert_eq!(NUM_RUNS.load(Relaxed), 1);
+
+        block_on(store.release_lease("blocked", "another-owner"))
+            .expect("fail to release the lease");
+        scheduler.tick();
+        assert_eq!(NUM_RUNS.load(Relaxed), 2);
+        assert!(scheduler.get(job_id).is_some());
+    }
+
+    #[test]
+    fn it_limits_concurrent_executions() {
+        fn run(_ctx: &mut JobContext) -> BoxFuture<'_> {
+            Box::pin(YieldNow(false))
+        }
+
+        let mut scheduler = AsyncJobScheduler::new();
+        let job_ids = (0..2)
+            .map(|_| {
+                let job = AsyncJob::new("0 0 * * * *", run)
+                    .name("overlapping")
+                    .no_overlap()
+                    .immediate(true);
+                scheduler.add(job)
+            })
+            .collect::<Vec<_>>();
+        block_on(scheduler.tick());
+
+        let num_runs = job_ids
+            .into_iter()
+            .filter_map(|job_id| scheduler.get(job_id))
+            .map(|job| job.context().num_runs())
+            .sum::<usize>();
+        assert_eq!(num_runs, 1);
+    }
+
+    #[test]
+    fn it_prefers_retries_over_missed_runs() {
+        fn fail(ctx: &mut JobContext) -> BoxFuture<'_> {
+            Box::pin(async move {
+                ctx.record_error(Error::new("job failed"));
+            })
+        }
+
+        let backoff = std::time::Duration::from_secs(3600);
+        let policy = RetryPolicy::new(3)
+            .backoff(backoff)
+            .max_backoff(backoff)
+            .jitter(false);
+        let mut job = AsyncJob::new("0 * * * * *", fail)
+            .catch_up(CatchUp::RunAll)
+            .retry_policy(policy);
+        let last_tick = DateTime::now() - std::time::Duration::from_secs(300);
+        job.context_mut().set_last_tick(last_tick);
+        block_on(job.tick());
+
+        let ctx = job.context();
+        assert_eq!(ctx.num_runs(), 1);
+        assert_eq!(ctx.retry_count(), 1);
+        assert!(
+            ctx.next_tick()
+                .and_then(|dt| dt.span_after_now())
+                .is_some_and(|span| span > backoff / 2)
+        );
+    }
+
+    #[test]
+    fn it_cancels_timed_out_jobs() {
+        fn hang(_ctx: &mut JobContext) -> BoxFuture<'_> {
+            Box::pin(future::pending())
+        }
+
+        let mut job =
+            AsyncJob::new("0 0 * * * *", hang).timeout(std::time::Duration::from_millis(10));
+        block_on(job.execute());
+
+        let ctx = job.context();
+        assert_eq!(ctx.num_failures(), 1);
+        assert!(
+            ctx.run_history()
+                .back()
+                .and_then(|run| run.error())
+                .is_some_and(|error| error.contains("timed out"))
+        );
+    }
+
+    #[cfg(feature = "runtime-tokio")]
+    #[test]
+    fn it_detaches_long_running_jobs() {
+        static NUM_RUNS: AtomicUsize = AtomicUsize::new(0);
+
+        fn hang(_ctx: &mut JobContext) -> BoxFuture<'_> {
+            Box::pin(future::pending())
+        }
+
+        fn count(_ctx: &mut JobContext) -> BoxFuture<'_> {
+            Box::pin(async {
+                NUM_RUNS.fetch_add(1, Relaxed);
+            })
+        }
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .expect("fail to build the runtime");
+        let mut scheduler = AsyncJobScheduler::new();
+        let hung_job_id = scheduler.add(AsyncJob::new("0 0 * * * *", hang).immediate(true));
+        let job_id = scheduler.add(AsyncJob::new("0 0 * * * *", count).once().immediate(true));
+        runtime.block_on(async {
+            scheduler.tick().await;
+            assert!(scheduler.get(hung_job_id).is_none());
+            assert!(scheduler.remove(hung_job_id));
+
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            scheduler.tick().await;
+        });
+        assert_eq!(NUM_RUNS.load(Relaxed), 1);
+        assert!(scheduler.get(job_id).is_none());
+        assert!(scheduler.get(hung_job_id).is_none());
+        assert!(super::AsyncScheduler::is_ready(&scheduler));
+    }
+}
@@ -0,0 +1,148 @@
+use crate::{LazyLock, extension::TomlTableExt};
+use parking_lot::Mutex;
+use rand::RngExt;
+use std::{collections::HashMap, time::Duration};
+use toml::Table;
+
+/// Policy for retrying a failed job.
+///
+/// The delay before each retry grows exponentially from the initial backoff,
+/// and is capped at the maximum backoff. If the jitter is enabled,
+/// the delay is randomized between the half and the full of the exponential backoff.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Maximum number of retries.
+    max_retries: usize,
+    /// Initial backoff.
+    backoff: Duration,
+    /// Maximum backoff.
+    max_backoff: Duration,
+    /// Flag to indicate whether the jitter is enabled.
+    jitter: bool,
+}
+
+impl RetryPolicy {
+    /// Creates a new instance with the maximum number of retries.
+    #[inline]
+    pub fn new(max_retries: usize) -> Self {
+        Self {
+            max_retries,
+            backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(300),
+            jitter: true,
+        }
+    }
+
+    /// Creates a new instance with the configuration.
+    pub fn with_config(config: &Table) -> Self {
+        let mut policy = Self::new(config.get_usize("max-retries").unwrap_or_default());
+        if let Some(backoff) = config.get_duration("backoff") {
+            policy.backoff = backoff;
+        }
+        if let Some(max_backoff) = config.get_duration("max-backoff") {
+            policy.max_backoff = max_backoff;
+        }
+        if let Some(jitter) = config.get_bool("jitter") {
+            policy.jitter = jitter;
+        }
+        policy
+    }
+
+    /// Sets the initial backoff.
+    #[inline]
+    pub fn backoff(mut self, backoff: Duration) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Sets the maximum backoff.
+    #[inline]
+    pub fn max_backoff(mut self, max_backoff: Duration) -> Self {
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    /// Enables the flag to indicate whether the jitter is enabled.
+    #[inline]
+    pub fn jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Returns the maximum number of retries.
+    #[inline]
+    pub fn max_retries(&self) -> usize {
+        self.max_retries
+    }
+
+    /// Returns the delay before the retry attempt, which starts from `1`.
+    pub fn delay(&self, attempt: usize) -> Duration {
+        let exponent = u32::try_from(attempt.saturating_sub(1)).unwrap_or(u32::MAX);
+        let delay = 2u32
+            .checked_pow(exponent)
+            .and_then(|factor| self.backoff.checked_mul(factor))
+            .unwrap_or(self.max_backoff)
+            .min(self.max_backoff);
+        if self.jitter && !delay.is_zero() {
+            delay.mul_f64(rand::rng().random_range(0.5..=1.0))
+        } else {
+            delay
+        }
+    }
+}
+
+impl Default for RetryPolicy {
+    #[inline]
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+/// A slot for running a job within the concurrency limit,
+/// which is released when dropped.
+///
+/// The slots are shared by the jobs with the same key in the process,
+/// so the limit also applies to the executions across the schedulers.
+pub(super) struct ConcurrencySlot {
+    /// The job key.
+    job_key: Option<String>,
+}
+
+impl ConcurrencySlot {
+    /// Attempts to acquire a slot for the job.
+    /// It returns `None` if the concurrency limit has been reached.
+    pub(super) fn acquire(job_key: &str, max_concurrency: Option<usize>) -> Option<Self> {
+        let Some(max_concurrency) = max_concurrency else {
+            return Some(Self { job_key: None });
+        };
+
+        let mut running_jobs = RUNNING_JOBS.lock();
+        let num_running = running_jobs.entry(job_key.to_owned()).or_default();
+        if *num_running >= max_concurrency {
+            None
+        } else {
+            *num_running += 1;
+            Some(Self {
+                job_key: Some(job_key.to_owned()),
+            })
+        }
+    }
+}
+
+impl Drop for ConcurrencySlot {
+    fn drop(&mut self) {
+        if let Some(job_key) = self.job_key.as_deref() {
+            let mut running_jobs = RUNNING_JOBS.lock();
+            if let Some(num_running) = running_jobs.get_mut(job_key) {
+                *num_running = num_running.saturating_sub(1);
+                if *num_running == 0 {
+                    running_jobs.remove(job_key);
+                }
+            }
+        }
+    }
+}
+
+/// Number of the running executions for each job.
+static RUNNING_JOBS: LazyLock<Mutex<HashMap<String, usize>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
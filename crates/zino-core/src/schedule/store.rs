@@ -0,0 +1,215 @@
+use super::JobContext;
+use crate::{BoxFuture, datetime::DateTime, error::Error};
+use parking_lot::Mutex;
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+/// Persistent state of a job.
+#[derive(Debug, Clone, Default)]
+pub struct JobState {
+    /// Last time when running the job.
+    last_tick: Option<DateTime>,
+    /// Remaining ticks.
+    remaining_ticks: Option<usize>,
+    /// The error message of the last execution.
+    last_error: Option<String>,
+}
+
+impl JobState {
+    /// Creates a new instance.
+    #[inline]
+    pub fn new(
+        last_tick: Option<DateTime>,
+        remaining_ticks: Option<usize>,
+        last_error: Option<String>,
+    ) -> Self {
+        Self {
+            last_tick,
+            remaining_ticks,
+            last_error,
+        }
+    }
+
+    /// Creates a new instance from the job context.
+    #[inline]
+    pub fn from_context(ctx: &JobContext) -> Self {
+        Self {
+            last_tick: ctx.last_tick(),
+            remaining_ticks: ctx.remaining_ticks(),
+            last_error: ctx.execution_error().map(|err| err.to_string()),
+        }
+    }
+
+    /// Restores the job context with the state.
+    pub fn restore(&self, ctx: &mut JobContext) {
+        if let Some(last_tick) = self.last_tick {
+            ctx.set_last_tick(last_tick);
+        }
+        if let Some(ticks) = self.remaining_ticks {
+            ctx.set_remaining_ticks(ticks);
+        }
+        if let Some(message) = self.last_error.as_deref() {
+            ctx.record_error(Error::new(message.to_owned()));
+        }
+    }
+
+    /// Returns the last tick.
+    #[inline]
+    pub fn last_tick(&self) -> Option<DateTime> {
+        self.last_tick
+    }
+
+    /// Returns the remaining ticks.
+    #[inline]
+    pub fn remaining_ticks(&self) -> Option<usize> {
+        self.remaining_ticks
+    }
+
+    /// Returns the error message of the last execution.
+    #[inline]
+    pub fn last_error(&self) -> Option<&str> {
+        self.last_error.as_deref()
+    }
+}
+
+/// An interface for persisting the job states and coordinating the job executions
+/// across multiple instances.
+///
+/// A job is identified by the key which is stable across restarts,
+/// *i.e.* the job name or the cron expression.
+pub trait JobStore: Send + Sync {
+    /// Loads the state of a job.
+    fn load<'a>(&'a self, job_key: &'a str) -> BoxFuture<'a, Result<Option<JobState>, Error>>;
+
+    /// Saves the state of a job.
+    fn save<'a>(
+        &'a self,
+        job_key: &'a str,
+        state: &'a JobState,
+    ) -> BoxFuture<'a, Result<(), Error>>;
+
+    /// Attempts to acquire the lease of a job for the owner.
+    /// It returns `true` if the lease is acquired or renewed,
+    /// and `false` if the lease is held by another owner.
+    fn acquire_lease<'a>(
+        &'a self,
+        job_key: &'a str,
+        owner: &'a str,
+        ttl: Duration,
+    ) -> BoxFuture<'a, Result<bool, Error>>;
+
+    /// Releases the lease of a job held by the owner.
+    fn release_lease<'a>(
+        &'a self,
+        job_key: &'a str,
+        owner: &'a str,
+    ) -> BoxFuture<'a, Result<(), Error>>;
+}
+
+impl<T: JobStore + ?Sized> JobStore for Arc<T> {
+    #[inline]
+    fn load<'a>(&'a self, job_key: &'a str) -> BoxFuture<'a, Result<Option<JobState>, Error>> {
+        (**self).load(job_key)
+    }
+
+    #[inline]
+    fn save<'a>(
+        &'a self,
+        job_key: &'a str,
+        state: &'a JobState,
+    ) -> BoxFuture<'a, Result<(), Error>> {
+        (**self).save(job_key, state)
+    }
+
+    #[inline]
+    fn acquire_lease<'a>(
+        &'a self,
+        job_key: &'a str,
+        owner: &'a str,
+        ttl: Duration,
+    ) -> BoxFuture<'a, Result<bool, Error>> {
+        (**self).acquire_lease(job_key, owner, ttl)
+    }
+
+    #[inline]
+    fn release_lease<'a>(
+        &'a self,
+        job_key: &'a str,
+        owner: &'a str,
+    ) -> BoxFuture<'a, Result<(), Error>> {
+        (**self).release_lease(job_key, owner)
+    }
+}
+
+/// An in-memory job store.
+///
+/// It is suitable for a single instance, in which the job states
+/// will be lost on restart.
+#[derive(Debug, Default)]
+pub struct MemoryJobStore {
+    /// Job states.
+    states: Mutex<HashMap<String, JobState>>,
+    /// Leases with the owner and the expiry time.
+    leases: Mutex<HashMap<String, (String, DateTime)>>,
+}
+
+impl MemoryJobStore {
+    /// Creates a new instance.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl JobStore for MemoryJobStore {
+    fn load<'a>(&'a self, job_key: &'a str) -> BoxFuture<'a, Result<Option<JobState>, Error>> {
+        Box::pin(async move { Ok(self.states.lock().get(job_key).cloned()) })
+    }
+
+    fn save<'a>(
+        &'a self,
+        job_key: &'a str,
+        state: &'a JobState,
+    ) -> BoxFuture<'a, Result<(), Error>> {
+        Box::pin(async move {
+            self.states.lock().insert(job_key.to_owned(), state.clone());
+            Ok(())
+        })
+    }
+
+    fn acquire_lease<'a>(
+        &'a self,
+        job_key: &'a str,
+        owner: &'a str,
+        ttl: Duration,
+    ) -> BoxFuture<'a, Result<bool, Error>> {
+        Box::pin(async move {
+            let now = DateTime::now();
+            let mut leases = self.leases.lock();
+            if let Some((lease_owner, expiry)) = leases.get(job_key)
+                && lease_owner != owner
+                && *expiry > now
+            {
+                return Ok(false);
+            }
+            leases.insert(job_key.to_owned(), (owner.to_owned(), now + ttl));
+            Ok(true)
+        })
+    }
+
+    fn release_lease<'a>(
+        &'a self,
+        job_key: &'a str,
+        owner: &'a str,
+    ) -> BoxFuture<'a, Result<(), Error>> {
+        Box::pin(async move {
+            let mut leases = self.leases.lock();
+            if leases
+                .get(job_key)
+                .is_some_and(|(lease_owner, _)| lease_owner == owner)
+            {
+                leases.remove(job_key);
+            }
+            Ok(())
+        })
+    }
+}
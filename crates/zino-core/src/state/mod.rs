@@ -285,6 +285,29 @@ impl<T> State<T> {
 
         listeners
     }
+
+    /// Gets the config of the listener bound to the address.
+    /// It is the `[debug]`, `[main]` or one of the `[[standby]]` tables.
+    pub fn get_listener_config(&self, addr: SocketAddr) -> Option<&Table> {
+        let config = self.config();
+        let standbys = config
+            .get_array("standby")
+            .into_iter()
+            .flatten()
+            .filter_map(|v| v.as_table());
+        config
+            .get_table("debug")
+            .into_iter()
+            .chain(config.get_table("main"))
+            .chain(standbys)
+            .find(|server| {
+                let host = server
+                    .get_str("host")
+                    .and_then(|s| s.parse::<IpAddr>().ok());
+                let port = server.get_u16("port");
+                host == Some(addr.ip()) && port == Some(addr.port())
+            })
+    }
 }
 
 impl State {
@@ -23,15 +23,23 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
 
     // Parsing field attributes
     let mut decode_model_fields = Vec::new();
+    let mut relation_setters = Vec::new();
     'outer: for field in parser::parse_struct_fields(input.data) {
         let type_name = parser::get_type_name(&field.ty);
         if let Some(ident) = field.ident {
             let name = ident.to_string().trim_start_matches("r#").to_owned();
             for attr in field.attrs.iter() {
                 let arguments = parser::parse_schema_attr(attr);
-                for (key, _value) in arguments.into_iter() {
+                for (key, value) in arguments.into_iter() {
                     match key.as_str() {
                         "ignore" | "write_only" => continue 'outer,
+                        "relation" => {
+                            let relation_name = value.unwrap_or_else(|| name.clone());
+                            relation_setters.push(quote! {
+                                #relation_name => self.#ident = value.deserialize()?,
+                            });
+                            continue 'outer;
+                        }
                         _ => (),
                     }
                 }
@@ -103,6 +111,25 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
             }
         }
     }
+    let quote_attach_relation = if relation_setters.is_empty() {
+        quote! {}
+    } else {
+        quote! {
+            fn attach_relation(
+                &mut self,
+                name: &str,
+                value: zino_core::JsonValue,
+            ) -> Result<(), Self::Error> {
+                use zino_core::extension::JsonValueExt;
+
+                match name {
+                    #(#relation_setters)*
+                    _ => (),
+                }
+                Ok(())
+            }
+        }
+    };
     quote! {
         impl zino_orm::DecodeRow<zino_orm::DatabaseRow> for #name {
             type Error = zino_core::error::Error;
@@ -114,6 +141,18 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
                 #(#decode_model_fields)*
                 Ok(model)
             }
+
+            #[inline]
+            fn decode_cached(value: zino_core::JsonValue) -> Option<Self> {
+                zino_orm::QueryCache::decode(value)
+            }
+
+            #[inline]
+            fn encode_cached(&self) -> Option<zino_core::JsonValue> {
+                zino_orm::QueryCache::encode(self)
+            }
+
+            #quote_attach_relation
         }
     }
 }
@@ -217,7 +217,7 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
                                 }
                             }
                         }
-                        "ignore" | "read_only" | "generated" | "reserved" => {
+                        "ignore" | "read_only" | "generated" | "reserved" | "relation" => {
                             enable_setter = false;
                         }
                         "inherent" => {
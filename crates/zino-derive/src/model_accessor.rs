@@ -11,11 +11,14 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
 
     // Parsing struct attributes
     let mut auto_rename = false;
+    let mut history_enabled = false;
     let mut composite_constraints = Vec::new();
     for attr in input.attrs.iter() {
         for (key, value) in parser::parse_schema_attr(attr).into_iter() {
             if key == "auto_rename" {
                 auto_rename = true;
+            } else if key == "history" {
+                history_enabled = true;
             } else if key == "unique_on"
                 && let Some(value) = value
             {
@@ -790,6 +793,11 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
 
             #(#column_methods)*
 
+            #[inline]
+            fn history_enabled() -> bool {
+                #history_enabled
+            }
+
             fn snapshot(&self) -> ZinoMap {
                 let mut snapshot = ZinoMap::new();
                 snapshot.upsert(Self::PRIMARY_KEY_NAME, self.primary_key_value());
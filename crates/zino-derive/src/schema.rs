@@ -10,12 +10,13 @@ const INTEGER_TYPES: [&str; 10] = [
 ];
 
 // Special attributes
-const SPECIAL_ATTRIBUTES: [&str; 9] = [
+const SPECIAL_ATTRIBUTES: [&str; 10] = [
     "ignore",
     "type_name",
     "not_null",
     "default_value",
     "index_type",
+    "fulltext",
     "reference",
     "comment",
     "less_than",
@@ -36,8 +37,17 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
     let mut writer_name = String::from("main");
     let mut table_name = None;
     let mut model_comment = None;
+    let mut query_cache_ttl = None;
+    let mut relations = Vec::new();
+    let mut relation_loaders = Vec::new();
     for attr in input.attrs.iter() {
-        for (key, value) in parser::parse_schema_attr(attr).into_iter() {
+        let arguments = parser::parse_schema_attr(attr);
+        if let Some((relation, loader)) = parse_relation(&model_name, &arguments) {
+            relations.push(relation);
+            relation_loaders.push(loader);
+            continue;
+        }
+        for (key, value) in arguments.into_iter() {
             if let Some(value) = value {
                 match key.as_str() {
                     "model_name" => {
@@ -55,6 +65,13 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
                     "comment" => {
                         model_comment = Some(value);
                     }
+                    "query_cache_ttl" => {
+                        let ttl =
+                            zino_core::datetime::parse_duration(&value).unwrap_or_else(|err| {
+                                panic!("invalid value `{value}` for the `query_cache_ttl`: {err}")
+                            });
+                        query_cache_ttl = Some(ttl.as_millis() as u64);
+                    }
                     _ => (),
                 }
             }
@@ -110,7 +127,7 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
                             });
                         }
                         match key {
-                            "ignore" => continue 'outer,
+                            "ignore" | "relation" => continue 'outer,
                             "type_name" => {
                                 if let Some(value) = value {
                                     type_name = value;
@@ -150,6 +167,13 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
                             "index_type" => {
                                 index_type = value;
                             }
+                            "fulltext" => {
+                                index_type = if let Some(language) = value {
+                                    Some(format!("fulltext:{language}"))
+                                } else {
+                                    Some("fulltext".to_owned())
+                                };
+                            }
                             "reference" => {
                                 reference = value;
                             }
@@ -272,7 +296,35 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
     let num_read_only_fields = read_only_fields.len();
     let num_write_only_fields = write_only_fields.len();
     let quote_table_name = parser::quote_option_string(table_name);
+    let quote_query_cache_ttl = if let Some(millis) = query_cache_ttl {
+        quote! { Some(std::time::Duration::from_millis(#millis)) }
+    } else {
+        quote! { None }
+    };
     let quote_model_comment = parser::quote_option_string(model_comment);
+    let quote_relations = if relations.is_empty() {
+        quote! {}
+    } else {
+        let schema_relations = format_ident!("{}_RELATIONS", model_name_upper_snake);
+        let num_relations = relations.len();
+        quote! {
+            #[inline]
+            fn relations() -> &'static [zino_orm::Relation] {
+                static #schema_relations: [zino_orm::Relation; #num_relations] = [#(#relations),*];
+                #schema_relations.as_slice()
+            }
+
+            async fn load_relation(
+                relation: &zino_orm::Relation,
+                keys: Vec<zino_core::JsonValue>,
+            ) -> Result<Vec<(zino_core::JsonValue, zino_core::Map)>, ZinoError> {
+                match relation.name() {
+                    #(#relation_loaders)*
+                    name => Err(ZinoError::new(format!("the `{name}` relation is not defined"))),
+                }
+            }
+        }
+    };
     let quote_equality = if let Some(field) = equality_field {
         let schema_equality = format_ident!("{}", field);
         quote! {
@@ -331,6 +383,7 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
             const READER_NAME: &'static str = #reader_name;
             const WRITER_NAME: &'static str = #writer_name;
             const TABLE_NAME: Option<&'static str> = #quote_table_name;
+            const QUERY_CACHE_TTL: Option<std::time::Duration> = #quote_query_cache_ttl;
 
             #[inline]
             fn primary_key(&self) -> &Self::PrimaryKey {
@@ -377,16 +430,14 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
                 use zino_orm::PoolManager;
 
                 if let Some(reader) = #schema_reader.get() {
-                    if reader.is_available()
-                        || reader.is_retryable() && reader.check_availability().await
+                    // Routes the reads across the replicas on each acquisition
+                    let reader = Self::init_reader().unwrap_or(*reader);
+                    if !reader.is_available()
+                        && !(reader.is_retryable() && reader.check_availability().await)
                     {
-                        Ok(*reader)
-                    } else if let Ok(connection_pool) = Self::init_reader() {
                         reader.increment_missed_count();
-                        Ok(connection_pool)
-                    } else {
-                        Ok(*reader)
                     }
+                    Ok(reader)
                 } else {
                     let model_name = Self::MODEL_NAME;
                     let connection_pool = Self::init_reader()?;
@@ -420,8 +471,9 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
 
             async fn acquire_writer() -> Result<&'static ConnectionPool, ZinoError> {
                 use zino_core::{bail, error::Error, warn};
-                use zino_orm::PoolManager;
+                use zino_orm::{GlobalPool, PoolManager};
 
+                GlobalPool::record_write(Self::WRITER_NAME);
                 if let Some(writer) = #schema_writer.get() {
                     if writer.is_available()
                         || writer.is_retryable() && writer.check_availability().await
@@ -462,6 +514,8 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
                 }
             }
 
+            #quote_relations
+
             #[inline]
             fn table_name() -> &'static str {
                 Self::TABLE_NAME.unwrap_or_else(|| {
@@ -487,3 +541,70 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
         impl Eq for #name {}
     }
 }
+
+/// Parses the relation declared by the struct attribute, and returns
+/// the constructor of the relation and the match arm of the loader.
+fn parse_relation(
+    model_name: &str,
+    arguments: &[(String, Option<String>)],
+) -> Option<(TokenStream, TokenStream)> {
+    let mut kind = None;
+    let mut related_model = None;
+    let mut relation_name = None;
+    let mut foreign_key = None;
+    let mut related_key = None;
+    let mut through = None;
+    for (key, value) in arguments {
+        let Some(value) = value.to_owned() else {
+            continue;
+        };
+        match key.as_str() {
+            "belongs_to" | "has_many" | "many_to_many" => {
+                kind = Some(key.as_str());
+                related_model = Some(value);
+            }
+            "relation" => relation_name = Some(value),
+            "foreign_key" => foreign_key = Some(value),
+            "related_key" => related_key = Some(value),
+            "through" => through = Some(value),
+            _ => (),
+        }
+    }
+
+    let kind = kind?;
+    let related_model = related_model?;
+    let related_model_snake = related_model.to_case(Case::Snake);
+    let model_ident = format_ident!("{}", related_model);
+    let name = relation_name.unwrap_or_else(|| related_model_snake.clone());
+    let relation = match kind {
+        "belongs_to" => {
+            let foreign_key = foreign_key.unwrap_or_else(|| format!("{name}_id"));
+            quote! { zino_orm::Relation::belongs_to(#name, #foreign_key) }
+        }
+        "has_many" => {
+            let foreign_key =
+                foreign_key.unwrap_or_else(|| format!("{}_id", model_name.to_case(Case::Snake)));
+            quote! { zino_orm::Relation::has_many(#name, #foreign_key) }
+        }
+        _ => {
+            let foreign_key =
+                foreign_key.unwrap_or_else(|| format!("{}_id", model_name.to_case(Case::Snake)));
+            let related_key = related_key.unwrap_or_else(|| format!("{related_model_snake}_id"));
+            quote! { zino_orm::Relation::many_to_many(#name, #foreign_key, #related_key) }
+        }
+    };
+    let loader = if kind == "many_to_many" {
+        let through = through.unwrap_or_else(|| {
+            panic!("the `through` attribute should be specified for the `{name}` relation")
+        });
+        let through_ident = format_ident!("{}", through);
+        quote! {
+            #name => relation.load_through::<#model_ident, #through_ident>(keys).await,
+        }
+    } else {
+        quote! {
+            #name => relation.load::<#model_ident>(keys).await,
+        }
+    };
+    Some((relation, loader))
+}
@@ -0,0 +1,222 @@
+use super::CacheBackend;
+use opendal::{ErrorKind::NotFound, Operator};
+use parking_lot::Mutex;
+use std::{collections::HashMap, time::Duration};
+use toml::{Table, Value};
+use zino_core::{
+    BoxFuture, JsonValue, Map, bail,
+    datetime::DateTime,
+    error::Error,
+    extension::{JsonObjectExt, TomlTableExt},
+};
+use zino_storage::GlobalAccessor;
+
+/// A cache backend built on the top of the storage accessors in [`GlobalAccessor`],
+/// which can be shared by multiple instances, such as `redis`, `memcached`,
+/// `moka` and `dashmap`.
+///
+/// The entries are serialized as JSON objects with the value and the expiration time,
+/// and the expired entries are removed lazily when they are read.
+/// For the services supporting the `default-ttl`, such as `redis` and `memcached`,
+/// the time-to-live of an entry is also applied by the store itself.
+///
+/// ```toml
+/// [cache]
+/// backend = "accessor"
+/// accessor = "redis"
+/// time-to-live = "30m"
+///
+/// [[accessor]]
+/// scheme = "redis"
+/// endpoint = "tcp://127.0.0.1:6379"
+/// default-ttl = "1h"
+/// ```
+#[derive(Debug)]
+pub struct AccessorBackend {
+    /// Backend name.
+    name: String,
+    /// Storage operator.
+    operator: &'static Operator,
+    /// Prefix of the keys.
+    prefix: String,
+    /// Default time-to-live for the entries.
+    time_to_live: Option<Duration>,
+    /// Config of the storage accessor.
+    accessor_config: Option<&'static Table>,
+    /// Operators writing the entries with the time-to-live in seconds.
+    ttl_operators: Mutex<HashMap<u64, &'static Operator>>,
+}
+
+impl AccessorBackend {
+    /// Creates a new instance with the operator.
+    #[inline]
+    pub fn new(name: impl Into<String>, operator: &'static Operator) -> Self {
+        let name = name.into();
+        let prefix = format!("cache/{name}/");
+        Self {
+            name,
+            operator,
+            prefix,
+            time_to_live: None,
+            accessor_config: None,
+            ttl_operators: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Attempts to create a new instance with the configuration.
+    pub fn try_with_config(name: impl Into<String>, config: &Table) -> Result<Self, Error> {
+        let Some(accessor) = config.get_str("accessor") else {
+            bail!("field `accessor` should be specified for the cache backend");
+        };
+        let Some(operator) = GlobalAccessor::get(accessor) else {
+            bail!("storage accessor `{}` does not exist", accessor);
+        };
+
+        let mut backend = Self::new(name, operator);
+        if let Some(prefix) = config.get_str("prefix") {
+            backend.prefix = prefix.to_owned();
+        }
+        backend.time_to_live = config.get_duration("time-to-live");
+        backend.accessor_config = GlobalAccessor::get_config(accessor);
+        Ok(backend)
+    }
+
+    /// Sets the prefix of the keys.
+    #[inline]
+    pub fn prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+
+    /// Sets the default time-to-live for the entries.
+    #[inline]
+    pub fn time_to_live(mut self, ttl: Duration) -> Self {
+        self.time_to_live = Some(ttl);
+        self
+    }
+
+    /// Returns the storage path for the key.
+    #[inline]
+    fn path(&self, key: &str) -> String {
+        [self.prefix.as_str(), key].concat()
+    }
+
+    /// Returns an operator which writes the entries with the time-to-live.
+    ///
+    /// An operator is built for each distinct time-to-live in seconds,
+    /// if the service supports the `default-ttl`. Otherwise, the expiration time
+    /// stored in the entry is the only one to be checked.
+    fn ttl_operator(&self, ttl: Duration) -> Result<&'static Operator, Error> {
+        let Some(config) = self.accessor_config else {
+            return Ok(self.operator);
+        };
+        let Some(scheme) = config
+            .get_str("scheme")
+            .filter(|&scheme| matches!(scheme, "memcached" | "redis"))
+        else {
+            return Ok(self.operator);
+        };
+
+        let secs = ttl.as_secs() + u64::from(ttl.subsec_nanos() > 0);
+        let mut ttl_operators = self.ttl_operators.lock();
+        if let Some(&operator) = ttl_operators.get(&secs) {
+            return Ok(operator);
+        }
+        if ttl_operators.len() >= MAX_TTL_OPERATORS {
+            return Ok(self.operator);
+        }
+
+        let mut config = config.clone();
+        config.insert("default-ttl".to_owned(), Value::from(format!("{secs}s")));
+        let operator = GlobalAccessor::try_new_operator(scheme, &config)?;
+        let operator = Box::leak(Box::new(operator));
+        ttl_operators.insert(secs, operator);
+        Ok(operator)
+    }
+}
+
+impl CacheBackend for AccessorBackend {
+    #[inline]
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn get<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<Option<JsonValue>, Error>> {
+        Box::pin(async move {
+            let path = self.path(key);
+            let buffer = match self.operator.read(&path).await {
+                Ok(buffer) => buffer,
+                Err(err) if err.kind() == NotFound => return Ok(None),
+                Err(err) => return Err(err.into()),
+            };
+            let mut entry = serde_json::from_slice::<Map>(&buffer.to_vec())?;
+            if entry
+                .get_i64("expires_at")
+                .is_some_and(|expires_at| expires_at <= DateTime::now().timestamp_millis())
+            {
+                self.operator.delete(&path).await?;
+                return Ok(None);
+            }
+            Ok(entry.remove("value"))
+        })
+    }
+
+    fn put<'a>(
+        &'a self,
+        key: &'a str,
+        value: JsonValue,
+        ttl: Option<Duration>,
+    ) -> BoxFuture<'a, Result<(), Error>> {
+        Box::pin(async move {
+            let mut entry = Map::new();
+            entry.upsert("value", value);
+
+            let ttl = ttl.or(self.time_to_live);
+            let operator = if let Some(ttl) = ttl {
+                let expires_at = DateTime::now() + ttl;
+                entry.upsert("expires_at", expires_at.timestamp_millis());
+                self.ttl_operator(ttl)?
+            } else {
+                self.operator
+            };
+
+            let bytes = serde_json::to_vec(&entry)?;
+            operator.write(&self.path(key), bytes).await?;
+            Ok(())
+        })
+    }
+
+    #[inline]
+    fn remove<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<(), Error>> {
+        Box::pin(async move {
+            self.operator.delete(&self.path(key)).await?;
+            Ok(())
+        })
+    }
+
+    #[inline]
+    fn contains<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<bool, Error>> {
+        Box::pin(async move { Ok(self.get(key).await?.is_some()) })
+    }
+
+    /// Clears all the entries with the prefix.
+    /// It returns an error if the service does not support listing the entries.
+    fn clear(&self) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(async move {
+            let entries = self
+                .operator
+                .list_with(&self.prefix)
+                .recursive(true)
+                .await?;
+            for entry in entries {
+                if entry.metadata().is_file() {
+                    self.operator.delete(entry.path()).await?;
+                }
+            }
+            Ok(())
+        })
+    }
+}
+
+/// Maximum number of the operators for distinct time-to-live values.
+const MAX_TTL_OPERATORS: usize = 16;
@@ -0,0 +1,100 @@
+use super::{CacheRegion, loader};
+use std::{future::Future, time::Duration};
+use zino_core::{BoxFuture, JsonValue, error::Error};
+
+/// An interface for the cache storage,
+/// which can be an in-process LRU cache or a shared store across multiple instances.
+pub trait CacheBackend: Send + Sync {
+    /// Returns the name of the backend.
+    fn name(&self) -> &str;
+
+    /// Returns a cloned value of the key or `None` if it is not present or has expired.
+    fn get<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<Option<JsonValue>, Error>>;
+
+    /// Puts a key-value pair with an optional time-to-live.
+    /// If the time-to-live is `None`, the default one of the backend will be used.
+    fn put<'a>(
+        &'a self,
+        key: &'a str,
+        value: JsonValue,
+        ttl: Option<Duration>,
+    ) -> BoxFuture<'a, Result<(), Error>>;
+
+    /// Removes the entry of the key.
+    fn remove<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<(), Error>>;
+
+    /// Returns a bool indicating whether the given key is present and has not expired.
+    fn contains<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<bool, Error>>;
+
+    /// Clears all the entries.
+    fn clear(&self) -> BoxFuture<'_, Result<(), Error>>;
+}
+
+impl dyn CacheBackend {
+    /// Returns a cloned value of the key, or loads the value with the loader
+    /// and puts it into the backend if it is not present.
+    ///
+    /// Concurrent misses for the same key in the current process are coalesced
+    /// so that only one loader is executed and the others wait for its value.
+    /// If the loader fails, the waiting callers will execute their own loaders.
+    pub async fn get_or_insert_with<F, Fut, E>(&self, key: &str, loader: F) -> Result<JsonValue, E>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<JsonValue, E>>,
+        E: From<Error>,
+    {
+        let flight_key = [self.name(), ":", key].concat();
+        let getter = || async { self.get(key).await.map_err(E::from) };
+        let setter = |value| async { self.put(key, value, None).await.map_err(E::from) };
+        loader::get_or_load(flight_key, getter, loader, setter).await
+    }
+}
+
+impl CacheBackend for CacheRegion {
+    #[inline]
+    fn name(&self) -> &str {
+        self.name()
+    }
+
+    #[inline]
+    fn get<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<Option<JsonValue>, Error>> {
+        Box::pin(async move { Ok(self.get(key)) })
+    }
+
+    fn put<'a>(
+        &'a self,
+        key: &'a str,
+        value: JsonValue,
+        ttl: Option<Duration>,
+    ) -> BoxFuture<'a, Result<(), Error>> {
+        Box::pin(async move {
+            if let Some(ttl) = ttl {
+                self.put_with_ttl(key, value, ttl);
+            } else {
+                self.put(key, value);
+            }
+            Ok(())
+        })
+    }
+
+    #[inline]
+    fn remove<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<(), Error>> {
+        Box::pin(async move {
+            self.pop(key);
+            Ok(())
+        })
+    }
+
+    #[inline]
+    fn contains<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<bool, Error>> {
+        Box::pin(async move { Ok(self.contains(key)) })
+    }
+
+    #[inline]
+    fn clear(&self) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(async move {
+            self.clear();
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,151 @@
+use parking_lot::Mutex;
+use std::{
+    collections::HashMap,
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll, Waker},
+};
+use zino_core::{JsonValue, LazyLock};
+
+/// Returns a cloned value of the key with the getter, or loads the value with the loader
+/// and stores it with the setter if it is not present.
+///
+/// Concurrent misses for the same key in the current process are coalesced
+/// so that only one loader is executed and the others wait for its value.
+/// If the loader fails, the waiting callers will execute their own loaders.
+pub(super) async fn get_or_load<G, GFut, F, Fut, S, SFut, E>(
+    key: String,
+    getter: G,
+    loader: F,
+    setter: S,
+) -> Result<JsonValue, E>
+where
+    G: Fn() -> GFut,
+    GFut: Future<Output = Result<Option<JsonValue>, E>>,
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Result<JsonValue, E>>,
+    S: FnOnce(JsonValue) -> SFut,
+    SFut: Future<Output = Result<(), E>>,
+{
+    let flight = loop {
+        if let Some(value) = getter().await? {
+            return Ok(value);
+        }
+
+        let flight = {
+            let mut flights = FLIGHTS.lock();
+            if let Some(flight) = flights.get(&key) {
+                flight.clone()
+            } else {
+                let flight = Arc::new(Flight::default());
+                flights.insert(key.clone(), flight.clone());
+                break flight;
+            }
+        };
+        if let Some(value) = flight.wait().await {
+            return Ok(value);
+        }
+    };
+
+    let guard = LoadGuard { key: &key, flight };
+    if let Some(value) = getter().await? {
+        // The value has been loaded since the last miss.
+        guard.complete(value.clone());
+        return Ok(value);
+    }
+
+    let value = loader().await?;
+    setter(value.clone()).await?;
+    guard.complete(value.clone());
+    Ok(value)
+}
+
+/// A pending load for a missed key, which is shared by the concurrent callers.
+#[derive(Debug, Default)]
+struct Flight {
+    /// State of the load.
+    state: Mutex<FlightState>,
+}
+
+impl Flight {
+    /// Waits for the load to finish. It returns `None` if the load failed or was cancelled.
+    #[inline]
+    fn wait(self: Arc<Self>) -> FlightWait {
+        FlightWait { flight: self }
+    }
+
+    /// Finishes the load and wakes the waiting callers.
+    fn finish(&self, value: Option<JsonValue>) {
+        let mut state = self.state.lock();
+        if !state.finished {
+            state.value = value;
+            state.finished = true;
+            for waker in state.wakers.drain(..) {
+                waker.wake();
+            }
+        }
+    }
+}
+
+/// State of a pending load.
+#[derive(Debug, Default)]
+struct FlightState {
+    /// The loaded value.
+    value: Option<JsonValue>,
+    /// Flag to indicate whether the load has finished.
+    finished: bool,
+    /// Wakers of the waiting callers.
+    wakers: Vec<Waker>,
+}
+
+/// A future which resolves to the loaded value of a pending load.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+struct FlightWait {
+    /// The pending load.
+    flight: Arc<Flight>,
+}
+
+impl Future for FlightWait {
+    type Output = Option<JsonValue>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.flight.state.lock();
+        if state.finished {
+            Poll::Ready(state.value.clone())
+        } else {
+            if !state.wakers.iter().any(|waker| waker.will_wake(cx.waker())) {
+                state.wakers.push(cx.waker().clone());
+            }
+            Poll::Pending
+        }
+    }
+}
+
+/// A guard for the leading caller of a pending load, which removes the load
+/// and wakes the waiting callers when dropped.
+struct LoadGuard<'a> {
+    /// The key.
+    key: &'a str,
+    /// The pending load.
+    flight: Arc<Flight>,
+}
+
+impl LoadGuard<'_> {
+    /// Completes the load with the value.
+    #[inline]
+    fn complete(self, value: JsonValue) {
+        self.flight.finish(Some(value));
+    }
+}
+
+impl Drop for LoadGuard<'_> {
+    fn drop(&mut self) {
+        FLIGHTS.lock().remove(self.key);
+        self.flight.finish(None);
+    }
+}
+
+/// Pending loads for the missed keys, which are prefixed with the names of the regions.
+static FLIGHTS: LazyLock<Mutex<HashMap<String, Arc<Flight>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
@@ -1,25 +1,127 @@
 //! Global cache for the application.
+//!
+//! The global cache consists of a default region and multiple named regions,
+//! each of which has its own backend, capacity and expiration policies.
+//! The in-process LRU cache is used as the backend by default,
+//! and a region can be switched to a shared store with `backend = "accessor"`.
+//! The associated functions of [`GlobalCache`] operate on the configured backend
+//! of the default region, and the backends of the named regions are available
+//! via [`GlobalCache::region_backend()`]:
+//!
+//! ```toml
+//! [cache]
+//! capacity = 10000
+//! time-to-live = "30m"
+//!
+//! [[cache.regions]]
+//! name = "tokens"
+//! capacity = 1000
+//! time-to-live = "1h"
+//! time-to-idle = "10m"
+//!
+//! [[cache.regions]]
+//! name = "responses"
+//! backend = "accessor"
+//! accessor = "redis"
+//! time-to-live = "5m"
+//! ```
 
-use lru::LruCache;
-use parking_lot::RwLock;
-use std::num::NonZeroUsize;
-use zino_core::{JsonValue, LazyLock, state::State};
+use futures::executor;
+use std::{collections::HashMap, future::Future, num::NonZeroUsize, time::Duration};
+use toml::Table;
+use zino_core::{
+    JsonValue, LazyLock, bail, error::Error, extension::TomlTableExt, state::State, warn,
+};
 
-/// Global cache built on the top of [`LruCache`].
+mod backend;
+mod loader;
+mod region;
+
+#[cfg(feature = "cache-accessor")]
+mod accessor;
+
+pub use backend::CacheBackend;
+pub use region::{CacheRegion, CacheStats};
+
+#[cfg(feature = "cache-accessor")]
+pub use accessor::AccessorBackend;
+
+/// Global cache built on the top of [`CacheRegion`]s and [`CacheBackend`]s.
+///
+/// The associated functions operate on the configured backend of the default region.
+/// If the default region uses a shared store, the sync functions block the current thread
+/// until the operations complete, so the async variants should be used in async contexts.
+/// The LRU-specific functions always operate on the in-process LRU cache of the default region.
 #[derive(Debug, Clone, Copy, Default)]
 pub struct GlobalCache;
 
 impl GlobalCache {
+    /// Returns a reference to the default region.
+    #[inline]
+    pub fn default_region() -> &'static CacheRegion {
+        &DEFAULT_REGION
+    }
+
+    /// Returns a reference to the named region.
+    /// It returns `None` if the region does not exist or uses another backend.
+    #[inline]
+    pub fn region(name: &str) -> Option<&'static CacheRegion> {
+        CACHE_REGIONS.get(name)
+    }
+
+    /// Returns a reference to the backend of the default region.
+    /// It returns an error if the backend is misconfigured.
+    #[inline]
+    pub fn backend() -> Result<&'static dyn CacheBackend, Error> {
+        Ok(Self::store()?.backend())
+    }
+
+    /// Returns a reference to the backend of the named region.
+    /// It returns an error if the region does not exist or the backend is misconfigured.
+    #[inline]
+    pub fn region_backend(name: &str) -> Result<&'static dyn CacheBackend, Error> {
+        if let Some(backend) = CACHE_BACKENDS.get(name) {
+            backend.clone().map_err(Error::new)
+        } else {
+            Err(warn!("cache region `{}` does not exist", name))
+        }
+    }
+
     /// Puts a key-value pair into the global cache.
-    /// If the key already exists in the cache, then it updates the key’s value and
-    /// returns the old value. Otherwise, `None` is returned.
+    /// If the key already exists in the in-process LRU cache, then it updates the key’s value
+    /// and returns the old value. Otherwise, `None` is returned.
     #[inline]
     pub fn put(key: impl Into<String>, value: impl Into<JsonValue>) -> Option<JsonValue> {
-        let mut cache = GLOBAL_CACHE.write();
-        cache.put(key.into(), value.into())
+        Self::store().ok()?.put(key.into(), value.into(), None)
     }
 
-    /// Pushes a key-value pair into the global cache. If an entry with the key already
+    /// Puts a key-value pair into the global cache asynchronously.
+    #[inline]
+    pub async fn put_async(key: &str, value: impl Into<JsonValue>) -> Result<(), Error> {
+        Self::backend()?.put(key, value.into(), None).await
+    }
+
+    /// Puts a key-value pair into the global cache with the time-to-live.
+    #[inline]
+    pub fn put_with_ttl(
+        key: impl Into<String>,
+        value: impl Into<JsonValue>,
+        ttl: Duration,
+    ) -> Option<JsonValue> {
+        Self::store().ok()?.put(key.into(), value.into(), Some(ttl))
+    }
+
+    /// Puts a key-value pair into the global cache with the time-to-live asynchronously.
+    #[inline]
+    pub async fn put_with_ttl_async(
+        key: &str,
+        value: impl Into<JsonValue>,
+        ttl: Duration,
+    ) -> Result<(), Error> {
+        Self::backend()?.put(key, value.into(), Some(ttl)).await
+    }
+
+    /// Pushes a key-value pair into the in-process LRU cache. If an entry with the key already
     /// exists in the cache or another cache entry is removed (due to the LRU’s capacity),
     /// then it returns the old entry’s key-value pair. Otherwise, returns `None`.
     #[inline]
@@ -27,16 +129,20 @@ impl GlobalCache {
         key: impl Into<String>,
         value: impl Into<JsonValue>,
     ) -> Option<(String, JsonValue)> {
-        let mut cache = GLOBAL_CACHE.write();
-        cache.push(key.into(), value.into())
+        DEFAULT_REGION.push(key, value)
     }
 
     /// Returns a cloned value of the key in the global cache or `None`
     /// if it is not present in the cache. Moves the key to the head of the LRU list if it exists.
     #[inline]
     pub fn get(key: &str) -> Option<JsonValue> {
-        let mut cache = GLOBAL_CACHE.write();
-        cache.get(key).cloned()
+        Self::store().ok()?.get(key)
+    }
+
+    /// Returns a cloned value of the key in the global cache asynchronously.
+    #[inline]
+    pub async fn get_async(key: &str) -> Result<Option<JsonValue>, Error> {
+        Self::backend()?.get(key).await
     }
 
     /// Returns a cloned value of the key in the global cache or `None`
@@ -44,106 +150,558 @@ impl GlobalCache {
     /// so the key’s position will be unchanged.
     #[inline]
     pub fn peek(key: &str) -> Option<JsonValue> {
-        let cache = GLOBAL_CACHE.read();
-        cache.peek(key).cloned()
+        Self::store().ok()?.peek(key)
+    }
+
+    /// Returns a cloned value of the key in the global cache asynchronously
+    /// without updating the LRU list.
+    #[inline]
+    pub async fn peek_async(key: &str) -> Result<Option<JsonValue>, Error> {
+        Self::store()?.peek_async(key).await
     }
 
     /// Returns a bool indicating whether the given key is in the global cache.
     /// Does not update the LRU list.
     #[inline]
     pub fn contains(key: &str) -> bool {
-        let cache = GLOBAL_CACHE.read();
-        cache.contains(key)
+        Self::store().is_ok_and(|store| store.contains(key))
+    }
+
+    /// Returns a bool indicating whether the given key is in the global cache asynchronously.
+    #[inline]
+    pub async fn contains_async(key: &str) -> Result<bool, Error> {
+        Self::backend()?.contains(key).await
+    }
+
+    /// Returns a cloned value of the key in the global cache, or loads the value with the loader
+    /// and puts it into the global cache if it is not present.
+    /// Concurrent misses for the same key are coalesced.
+    #[inline]
+    pub async fn get_or_insert_with<F, Fut, E>(key: &str, loader: F) -> Result<JsonValue, E>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<JsonValue, E>>,
+        E: From<Error>,
+    {
+        match Self::store()? {
+            CacheStore::Local(region) => region.get_or_insert_with(key, loader).await,
+            CacheStore::Shared(backend) => backend.get_or_insert_with(key, loader).await,
+        }
     }
 
     /// Removes and returns the value corresponding to the key from the global cache or
     /// `None` if it does not exist.
     #[inline]
     pub fn pop(key: &str) -> Option<JsonValue> {
-        let mut cache = GLOBAL_CACHE.write();
-        cache.pop(key)
+        Self::store().ok()?.pop(key)
     }
 
-    /// Removes and returns the key-value pair from the global cache or
+    /// Removes and returns the value corresponding to the key from the global cache
+    /// asynchronously.
+    #[inline]
+    pub async fn pop_async(key: &str) -> Result<Option<JsonValue>, Error> {
+        Self::store()?.pop_async(key).await
+    }
+
+    /// Removes and returns the key-value pair from the in-process LRU cache or
     /// `None` if it does not exist.
     #[inline]
     pub fn pop_entry(key: &str) -> Option<(String, JsonValue)> {
-        let mut cache = GLOBAL_CACHE.write();
-        cache.pop_entry(key)
+        DEFAULT_REGION.pop_entry(key)
     }
 
     /// Removes and returns the key-value pair corresponding to the least recently used item
-    /// or `None` if the global cache is empty.
+    /// or `None` if the in-process LRU cache is empty.
     #[inline]
     pub fn pop_lru() -> Option<(String, JsonValue)> {
-        let mut cache = GLOBAL_CACHE.write();
-        cache.pop_lru()
+        DEFAULT_REGION.pop_lru()
+    }
+
+    /// Removes the expired entries from the in-process LRU cache and returns the number of them.
+    #[inline]
+    pub fn remove_expired() -> usize {
+        DEFAULT_REGION.remove_expired()
     }
 
     /// Marks the key as the most recently used one.
     #[inline]
     pub fn promote(key: &str) -> bool {
-        let mut cache = GLOBAL_CACHE.write();
-        cache.promote(key)
+        DEFAULT_REGION.promote(key)
     }
 
     /// Marks the key as the least recently used one.
     #[inline]
     pub fn demote(key: &str) -> bool {
-        let mut cache = GLOBAL_CACHE.write();
-        cache.demote(key)
+        DEFAULT_REGION.demote(key)
     }
 
-    /// Returns the number of key-value pairs that are currently in the global cache.
+    /// Returns the number of key-value pairs that are currently in the in-process LRU cache.
     #[inline]
     pub fn len() -> usize {
-        let cache = GLOBAL_CACHE.read();
-        cache.len()
+        DEFAULT_REGION.len()
     }
 
-    /// Returns a bool indicating whether the global cache is empty or not.
+    /// Returns a bool indicating whether the in-process LRU cache is empty or not.
     #[inline]
     pub fn is_empty() -> bool {
-        let cache = GLOBAL_CACHE.read();
-        cache.is_empty()
+        DEFAULT_REGION.is_empty()
     }
 
-    /// Returns the maximum number of key-value pairs the global cache can hold.
+    /// Returns the maximum number of key-value pairs the in-process LRU cache can hold.
     #[inline]
     pub fn cap() -> NonZeroUsize {
-        let cache = GLOBAL_CACHE.read();
-        cache.cap()
+        DEFAULT_REGION.cap()
     }
 
-    /// Resizes the global cache. If the new capacity is smaller than the size of
+    /// Resizes the in-process LRU cache. If the new capacity is smaller than the size of
     /// the current cache any entries past the new capacity are discarded.
     #[inline]
     pub fn resize(cap: NonZeroUsize) {
-        let mut cache = GLOBAL_CACHE.write();
-        cache.resize(cap)
+        DEFAULT_REGION.resize(cap)
+    }
+
+    /// Returns the statistics of the in-process LRU cache.
+    #[inline]
+    pub fn stats() -> CacheStats {
+        DEFAULT_REGION.stats()
     }
 
     /// Clears the contents of the global cache.
+    #[inline]
     pub fn clear() {
-        let mut cache = GLOBAL_CACHE.write();
-        cache.clear()
+        if let Ok(store) = Self::store() {
+            store.clear();
+        }
+    }
+
+    /// Clears the contents of the global cache asynchronously.
+    #[inline]
+    pub async fn clear_async() -> Result<(), Error> {
+        Self::backend()?.clear().await
+    }
+
+    /// Returns the store of the default region.
+    #[inline]
+    fn store() -> Result<CacheStore, Error> {
+        DEFAULT_STORE.clone().map_err(Error::new)
     }
 }
 
-/// Global cache.
-static GLOBAL_CACHE: LazyLock<RwLock<LruCache<String, JsonValue>>> = LazyLock::new(|| {
-    let capacity = if let Some(cache) = State::shared().get_config("cache") {
-        cache
-            .get("capacity")
-            .expect("field `capacity` is missing")
-            .as_integer()
-            .expect("field `capacity` should be an integer")
-            .try_into()
-            .expect("field `capacity` should be a positive integer")
+/// Store of a cache region.
+#[derive(Clone, Copy)]
+enum CacheStore {
+    /// In-process LRU cache.
+    Local(&'static CacheRegion),
+    /// Shared store across multiple instances.
+    Shared(&'static dyn CacheBackend),
+}
+
+impl CacheStore {
+    /// Returns the backend of the store.
+    #[inline]
+    fn backend(self) -> &'static dyn CacheBackend {
+        match self {
+            Self::Local(region) => region,
+            Self::Shared(backend) => backend,
+        }
+    }
+
+    /// Puts a key-value pair with an optional time-to-live and returns the old value
+    /// if it is in the in-process LRU cache.
+    fn put(self, key: String, value: JsonValue, ttl: Option<Duration>) -> Option<JsonValue> {
+        match self {
+            Self::Local(region) => match ttl {
+                Some(ttl) => region.put_with_ttl(key, value, ttl),
+                None => region.put(key, value),
+            },
+            Self::Shared(backend) => {
+                if let Err(err) = executor::block_on(backend.put(&key, value, ttl)) {
+                    tracing::error!("fail to put the cache entry `{key}`: {err}");
+                }
+                None
+            }
+        }
+    }
+
+    /// Returns a cloned value of the key.
+    fn get(self, key: &str) -> Option<JsonValue> {
+        match self {
+            Self::Local(region) => region.get(key),
+            Self::Shared(backend) => executor::block_on(backend.get(key))
+                .inspect_err(|err| tracing::error!("fail to get the cache entry `{key}`: {err}"))
+                .ok()
+                .flatten(),
+        }
+    }
+
+    /// Returns a cloned value of the key without updating the LRU list.
+    fn peek(self, key: &str) -> Option<JsonValue> {
+        match self {
+            Self::Local(region) => region.peek(key),
+            Self::Shared(_) => self.get(key),
+        }
+    }
+
+    /// Returns a cloned value of the key asynchronously without updating the LRU list.
+    async fn peek_async(self, key: &str) -> Result<Option<JsonValue>, Error> {
+        match self {
+            Self::Local(region) => Ok(region.peek(key)),
+            Self::Shared(backend) => backend.get(key).await,
+        }
+    }
+
+    /// Returns a bool indicating whether the given key is present.
+    fn contains(self, key: &str) -> bool {
+        match self {
+            Self::Local(region) => region.contains(key),
+            Self::Shared(backend) => executor::block_on(backend.contains(key))
+                .inspect_err(|err| tracing::error!("fail to check the cache entry `{key}`: {err}"))
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Removes and returns the value corresponding to the key.
+    fn pop(self, key: &str) -> Option<JsonValue> {
+        match self {
+            Self::Local(region) => region.pop(key),
+            Self::Shared(backend) => {
+                let value = self.get(key)?;
+                if let Err(err) = executor::block_on(backend.remove(key)) {
+                    tracing::error!("fail to remove the cache entry `{key}`: {err}");
+                }
+                Some(value)
+            }
+        }
+    }
+
+    /// Removes and returns the value corresponding to the key asynchronously.
+    async fn pop_async(self, key: &str) -> Result<Option<JsonValue>, Error> {
+        match self {
+            Self::Local(region) => Ok(region.pop(key)),
+            Self::Shared(backend) => {
+                let value = backend.get(key).await?;
+                if value.is_some() {
+                    backend.remove(key).await?;
+                }
+                Ok(value)
+            }
+        }
+    }
+
+    /// Clears all the entries.
+    fn clear(self) {
+        match self {
+            Self::Local(region) => region.clear(),
+            Self::Shared(backend) => {
+                if let Err(err) = executor::block_on(backend.clear()) {
+                    tracing::error!("fail to clear the cache entries: {err}");
+                }
+            }
+        }
+    }
+}
+
+/// Creates a new backend for the region if it does not use the in-process LRU cache.
+fn new_backend(name: &str, config: &Table) -> Result<Option<&'static dyn CacheBackend>, Error> {
+    match config.get_str("backend").unwrap_or("lru") {
+        "lru" => Ok(None),
+        #[cfg(feature = "cache-accessor")]
+        "accessor" => {
+            let backend = AccessorBackend::try_with_config(name, config)?;
+            Ok(Some(Box::leak(Box::new(backend))))
+        }
+        backend => bail!(
+            "cache backend `{}` for the region `{}` is unsupported",
+            backend,
+            name
+        ),
+    }
+}
+
+/// Default region of the global cache.
+static DEFAULT_REGION: LazyLock<CacheRegion> = LazyLock::new(|| {
+    if let Some(config) = State::shared().get_config("cache") {
+        CacheRegion::with_config("default", config)
     } else {
-        10000
+        CacheRegion::new(
+            "default",
+            NonZeroUsize::new(10000).unwrap_or(NonZeroUsize::MIN),
+        )
+    }
+});
+
+/// Named regions of the global cache using the in-process LRU cache.
+static CACHE_REGIONS: LazyLock<HashMap<String, CacheRegion>> = LazyLock::new(|| {
+    let mut regions = HashMap::new();
+    if let Some(configs) = State::shared()
+        .get_config("cache")
+        .and_then(|config| config.get_array("regions"))
+    {
+        for config in configs.iter().filter_map(|v| v.as_table()) {
+            let name = config
+                .get_str("name")
+                .expect("field `name` should be specified for the cache region");
+            if config
+                .get_str("backend")
+                .is_none_or(|backend| backend == "lru")
+            {
+                regions.insert(name.to_owned(), CacheRegion::with_config(name, config));
+            }
+        }
+    }
+    regions
+});
+
+/// Store of the default region or the error message if it is misconfigured.
+static DEFAULT_STORE: LazyLock<Result<CacheStore, String>> = LazyLock::new(|| {
+    let backend = if let Some(config) = State::shared().get_config("cache") {
+        new_backend("default", config).map_err(|err| {
+            tracing::error!("fail to create the backend for the default cache region: {err}");
+            err.to_string()
+        })?
+    } else {
+        None
     };
-    RwLock::new(LruCache::new(
-        NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::MIN),
-    ))
+    Ok(backend.map_or(CacheStore::Local(&DEFAULT_REGION), CacheStore::Shared))
 });
+
+/// Backends of the named regions or the error messages if they are misconfigured.
+type RegionBackends = HashMap<String, Result<&'static dyn CacheBackend, String>>;
+
+/// Backends of the named regions.
+static CACHE_BACKENDS: LazyLock<RegionBackends> = LazyLock::new(|| {
+    let mut backends = HashMap::new();
+    if let Some(configs) = State::shared()
+        .get_config("cache")
+        .and_then(|config| config.get_array("regions"))
+    {
+        for config in configs.iter().filter_map(|v| v.as_table()) {
+            let name = config
+                .get_str("name")
+                .expect("field `name` should be specified for the cache region");
+            let backend = match new_backend(name, config) {
+                Ok(Some(backend)) => Ok(backend),
+                Ok(None) => match CACHE_REGIONS.get(name) {
+                    Some(region) => Ok(region as &'static dyn CacheBackend),
+                    None => continue,
+                },
+                Err(err) => {
+                    tracing::error!(
+                        "fail to create the backend for the cache region `{name}`: {err}"
+                    );
+                    Err(err.to_string())
+                }
+            };
+            backends.insert(name.to_owned(), backend);
+        }
+    }
+    backends
+});
+
+#[cfg(test)]
+mod tests {
+    use super::{CacheBackend, CacheRegion, CacheStore};
+    use futures::{executor::block_on, future};
+    use parking_lot::Mutex;
+    use std::{
+        collections::HashMap,
+        num::NonZeroUsize,
+        sync::atomic::{AtomicUsize, Ordering::Relaxed},
+        time::Duration,
+    };
+    use toml::Table;
+    use zino_core::{BoxFuture, JsonValue, error::Error};
+
+    #[test]
+    fn it_creates_backends() {
+        let config = "backend = 'lru'"
+            .parse::<Table>()
+            .expect("fail to parse the config");
+        let backend = super::new_backend("tokens", &config).expect("fail to create the backend");
+        assert!(backend.is_none());
+
+        let config = "backend = 'unknown'"
+            .parse::<Table>()
+            .expect("fail to parse the config");
+        assert!(super::new_backend("tokens", &config).is_err());
+    }
+
+    #[test]
+    fn it_coalesces_concurrent_misses() {
+        let region = CacheRegion::new("coalesced", NonZeroUsize::MIN.saturating_add(9));
+        let loads = AtomicUsize::new(0);
+        let loader = || async {
+            loads.fetch_add(1, Relaxed);
+            // Yields once so that the other callers miss the key in the meantime
+            let mut yielded = false;
+            future::poll_fn(|cx| {
+                if yielded {
+                    return std::task::Poll::Ready(());
+                }
+                yielded = true;
+                cx.waker().wake_by_ref();
+                std::task::Poll::Pending
+            })
+            .await;
+            Ok::<_, Error>(JsonValue::from("value"))
+        };
+
+        let backend: &dyn CacheBackend = &region;
+        let values = block_on(future::join3(
+            region.get_or_insert_with("key", loader),
+            region.get_or_insert_with("key", loader),
+            backend.get_or_insert_with("key", loader),
+        ));
+        assert_eq!(values.0.expect("fail to load the value"), "value");
+        assert_eq!(values.1.expect("fail to load the value"), "value");
+        assert_eq!(values.2.expect("fail to load the value"), "value");
+        assert_eq!(loads.load(Relaxed), 1);
+
+        let value =
+            block_on(region.get_or_insert_with("key", loader)).expect("fail to get the value");
+        assert_eq!(value, "value");
+        assert_eq!(loads.load(Relaxed), 1);
+    }
+
+    #[test]
+    fn it_puts_entries_with_ttl() {
+        let region = CacheRegion::new("ttl", NonZeroUsize::MIN.saturating_add(9));
+        let backend: &dyn CacheBackend = &region;
+        block_on(backend.put("alive", "value".into(), None)).expect("fail to put the entry");
+        block_on(backend.put("expired", "value".into(), Some(Duration::ZERO)))
+            .expect("fail to put the entry");
+        assert!(block_on(backend.contains("alive")).expect("fail to check the entry"));
+        assert!(!block_on(backend.contains("expired")).expect("fail to check the entry"));
+        let value = block_on(backend.get("expired")).expect("fail to get the entry");
+        assert_eq!(value, None);
+
+        block_on(backend.remove("alive")).expect("fail to remove the entry");
+        assert!(region.is_empty());
+    }
+
+    #[test]
+    fn it_routes_to_the_local_region() {
+        let region = Box::leak(Box::new(CacheRegion::new(
+            "local",
+            NonZeroUsize::MIN.saturating_add(9),
+        )));
+        let store = CacheStore::Local(region);
+        assert_eq!(store.put("key".to_owned(), "value".into(), None), None);
+        assert_eq!(
+            store.put("key".to_owned(), "updated".into(), None),
+            Some("value".into())
+        );
+        assert_eq!(store.peek("key"), Some("updated".into()));
+        assert!(store.contains("key"));
+        assert_eq!(region.len(), 1);
+
+        assert_eq!(store.pop("key"), Some("updated".into()));
+        assert!(region.is_empty());
+    }
+
+    #[test]
+    fn it_routes_to_the_shared_backend() {
+        #[derive(Default)]
+        struct SharedBackend {
+            entries: Mutex<HashMap<String, JsonValue>>,
+            calls: AtomicUsize,
+        }
+
+        impl CacheBackend for SharedBackend {
+            fn name(&self) -> &str {
+                "shared"
+            }
+
+            fn get<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<Option<JsonValue>, Error>> {
+                self.calls.fetch_add(1, Relaxed);
+                Box::pin(async move { Ok(self.entries.lock().get(key).cloned()) })
+            }
+
+            fn put<'a>(
+                &'a self,
+                key: &'a str,
+                value: JsonValue,
+                _ttl: Option<Duration>,
+            ) -> BoxFuture<'a, Result<(), Error>> {
+                self.calls.fetch_add(1, Relaxed);
+                Box::pin(async move {
+                    self.entries.lock().insert(key.to_owned(), value);
+                    Ok(())
+                })
+            }
+
+            fn remove<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<(), Error>> {
+                self.calls.fetch_add(1, Relaxed);
+                Box::pin(async move {
+                    self.entries.lock().remove(key);
+                    Ok(())
+                })
+            }
+
+            fn contains<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<bool, Error>> {
+                self.calls.fetch_add(1, Relaxed);
+                Box::pin(async move { Ok(self.entries.lock().contains_key(key)) })
+            }
+
+            fn clear(&self) -> BoxFuture<'_, Result<(), Error>> {
+                self.calls.fetch_add(1, Relaxed);
+                Box::pin(async move {
+                    self.entries.lock().clear();
+                    Ok(())
+                })
+            }
+        }
+
+        let backend: &'static SharedBackend = Box::leak(Box::default());
+        let store = CacheStore::Shared(backend);
+        assert_eq!(store.put("key".to_owned(), "value".into(), None), None);
+        assert_eq!(store.get("key"), Some("value".into()));
+        assert_eq!(store.peek("key"), Some("value".into()));
+        assert!(store.contains("key"));
+        assert_eq!(backend.calls.load(Relaxed), 4);
+
+        let value = block_on(store.peek_async("key")).expect("fail to peek the entry");
+        assert_eq!(value, Some("value".into()));
+        let value = block_on(store.pop_async("key")).expect("fail to pop the entry");
+        assert_eq!(value, Some("value".into()));
+        assert!(backend.entries.lock().is_empty());
+        assert_eq!(store.pop("key"), None);
+
+        store.put("key".to_owned(), "value".into(), None);
+        store.clear();
+        assert!(backend.entries.lock().is_empty());
+        assert_eq!(store.backend().name(), "shared");
+    }
+
+    #[cfg(feature = "cache-accessor")]
+    #[test]
+    fn it_routes_to_the_accessor_backend() {
+        use super::AccessorBackend;
+        use zino_storage::GlobalAccessor;
+
+        let operator = GlobalAccessor::try_new_operator("memory", &Table::new())
+            .expect("fail to create the memory operator");
+        let operator = &*Box::leak(Box::new(operator));
+        let backend = AccessorBackend::new("shared", operator);
+        let store = CacheStore::Shared(Box::leak(Box::new(backend)));
+        assert_eq!(store.put("key".to_owned(), "value".into(), None), None);
+        assert!(
+            block_on(operator.exists("cache/shared/key")).expect("fail to check the entry"),
+            "the entry should be written to the accessor"
+        );
+        assert_eq!(store.get("key"), Some("value".into()));
+        assert_eq!(store.peek("key"), Some("value".into()));
+        assert!(store.contains("key"));
+
+        let backend = store.backend();
+        let value = block_on(backend.get("key")).expect("fail to get the entry");
+        assert_eq!(value, Some("value".into()));
+
+        assert_eq!(store.pop("key"), Some("value".into()));
+        assert!(!block_on(operator.exists("cache/shared/key")).expect("fail to check the entry"));
+        assert_eq!(store.get("key"), None);
+
+        store.put("expired".to_owned(), "value".into(), Some(Duration::ZERO));
+        assert!(!store.contains("expired"));
+    }
+}
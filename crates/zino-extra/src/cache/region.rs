@@ -0,0 +1,670 @@
+use super::loader;
+use lru::LruCache;
+use parking_lot::RwLock;
+use std::{
+    future::Future,
+    num::NonZeroUsize,
+    sync::atomic::{AtomicU64, Ordering::Relaxed},
+    time::{Duration, Instant},
+};
+use toml::Table;
+use zino_core::{JsonValue, extension::TomlTableExt};
+
+/// A named region of the cache with its own capacity and expiration policies.
+///
+/// The entries are evicted in the LRU order when the number of entries exceeds the capacity,
+/// or the total size of the entries exceeds the maximum bytes.
+/// The size of an entry is measured by the length of its key and its serialized value.
+#[derive(Debug)]
+pub struct CacheRegion {
+    /// Region name.
+    name: String,
+    /// Cache state.
+    state: RwLock<RegionState>,
+    /// Maximum bytes of the entries.
+    max_bytes: Option<usize>,
+    /// Default time-to-live for the entries.
+    time_to_live: Option<Duration>,
+    /// Default time-to-idle for the entries.
+    time_to_idle: Option<Duration>,
+    /// Cache statistics.
+    stats: RegionStats,
+}
+
+impl CacheRegion {
+    /// Creates a new instance with the capacity.
+    #[inline]
+    pub fn new(name: impl Into<String>, capacity: NonZeroUsize) -> Self {
+        Self {
+            name: name.into(),
+            state: RwLock::new(RegionState {
+                entries: LruCache::unbounded(),
+                capacity,
+                total_bytes: 0,
+            }),
+            max_bytes: None,
+            time_to_live: None,
+            time_to_idle: None,
+            stats: RegionStats::default(),
+        }
+    }
+
+    /// Creates a new instance with the configuration.
+    pub fn with_config(name: impl Into<String>, config: &Table) -> Self {
+        let capacity = config.get_usize("capacity").unwrap_or(10000);
+        let mut region = Self::new(
+            name,
+            NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::MIN),
+        );
+        region.max_bytes = config.get_usize("max-bytes");
+        region.time_to_live = config.get_duration("time-to-live");
+        region.time_to_idle = config.get_duration("time-to-idle");
+        region
+    }
+
+    /// Sets the maximum bytes of the entries.
+    #[inline]
+    pub fn max_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_bytes = Some(max_bytes);
+        self
+    }
+
+    /// Sets the default time-to-live for the entries.
+    #[inline]
+    pub fn time_to_live(mut self, ttl: Duration) -> Self {
+        self.time_to_live = Some(ttl);
+        self
+    }
+
+    /// Sets the default time-to-idle for the entries.
+    #[inline]
+    pub fn time_to_idle(mut self, tti: Duration) -> Self {
+        self.time_to_idle = Some(tti);
+        self
+    }
+
+    /// Returns the region name.
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Puts a key-value pair into the region with the default expiration policies.
+    /// If the key already exists in the region, then it updates the key’s value and
+    /// returns the old value. Otherwise, `None` is returned.
+    #[inline]
+    pub fn put(&self, key: impl Into<String>, value: impl Into<JsonValue>) -> Option<JsonValue> {
+        self.put_with_expiry(key, value, self.time_to_live, self.time_to_idle)
+    }
+
+    /// Puts a key-value pair into the region with the time-to-live.
+    #[inline]
+    pub fn put_with_ttl(
+        &self,
+        key: impl Into<String>,
+        value: impl Into<JsonValue>,
+        ttl: Duration,
+    ) -> Option<JsonValue> {
+        self.put_with_expiry(key, value, Some(ttl), self.time_to_idle)
+    }
+
+    /// Puts a key-value pair into the region with the time-to-live and the time-to-idle.
+    /// The entry expires when either of them elapses.
+    pub fn put_with_expiry(
+        &self,
+        key: impl Into<String>,
+        value: impl Into<JsonValue>,
+        ttl: Option<Duration>,
+        tti: Option<Duration>,
+    ) -> Option<JsonValue> {
+        let entry = self.new_entry(key.into(), value.into(), ttl, tti);
+        let mut state = self.state.write();
+        let old_entry = state.insert(entry);
+        self.evict(&mut state, |_| ());
+        old_entry.map(|(_, entry)| entry.value)
+    }
+
+    /// Pushes a key-value pair into the region. If an entry with the key already
+    /// exists in the region or another entry is evicted, then it returns the old entry’s
+    /// key-value pair. Otherwise, returns `None`.
+    pub fn push(
+        &self,
+        key: impl Into<String>,
+        value: impl Into<JsonValue>,
+    ) -> Option<(String, JsonValue)> {
+        let entry = self.new_entry(
+            key.into(),
+            value.into(),
+            self.time_to_live,
+            self.time_to_idle,
+        );
+        let mut state = self.state.write();
+        let mut old_entry = state.insert(entry).map(|(key, entry)| (key, entry.value));
+        self.evict(&mut state, |evicted| {
+            if old_entry.is_none() {
+                old_entry = Some(evicted);
+            }
+        });
+        old_entry
+    }
+
+    /// Returns a cloned value of the key in the region or `None`
+    /// if it is not present or has expired. Moves the key to the head of the LRU list if it exists.
+    pub fn get(&self, key: &str) -> Option<JsonValue> {
+        let now = Instant::now();
+        let mut state = self.state.write();
+        let value = match state.entries.get_mut(key) {
+            Some(entry) if entry.is_expired(now) => {
+                state.remove(key);
+                self.stats.record_expiration(&self.name);
+                None
+            }
+            Some(entry) => {
+                entry.last_accessed = now;
+                Some(entry.value.clone())
+            }
+            None => None,
+        };
+        if value.is_some() {
+            self.stats.record_hit(&self.name);
+        } else {
+            self.stats.record_miss(&self.name);
+        }
+        value
+    }
+
+    /// Returns a cloned value of the key in the region or `None`
+    /// if it is not present or has expired. It does not update the LRU list
+    /// so the key’s position will be unchanged.
+    #[inline]
+    pub fn peek(&self, key: &str) -> Option<JsonValue> {
+        let now = Instant::now();
+        let state = self.state.read();
+        state
+            .entries
+            .peek(key)
+            .filter(|entry| !entry.is_expired(now))
+            .map(|entry| entry.value.clone())
+    }
+
+    /// Returns a bool indicating whether the given key is in the region and has not expired.
+    /// Does not update the LRU list.
+    #[inline]
+    pub fn contains(&self, key: &str) -> bool {
+        let now = Instant::now();
+        let state = self.state.read();
+        state
+            .entries
+            .peek(key)
+            .is_some_and(|entry| !entry.is_expired(now))
+    }
+
+    /// Returns a cloned value of the key in the region, or loads the value with the loader
+    /// and puts it into the region if it is not present.
+    ///
+    /// Concurrent misses for the same key are coalesced so that only one loader is executed
+    /// and the others wait for its value. If the loader fails, the waiting callers
+    /// will execute their own loaders.
+    pub async fn get_or_insert_with<F, Fut, E>(&self, key: &str, loader: F) -> Result<JsonValue, E>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<JsonValue, E>>,
+    {
+        let flight_key = [self.name(), ":", key].concat();
+        let getter = || async { Ok(self.get(key)) };
+        let setter = |value| async {
+            self.put(key, value);
+            Ok(())
+        };
+        loader::get_or_load(flight_key, getter, loader, setter).await
+    }
+
+    /// Removes and returns the value corresponding to the key from the region or
+    /// `None` if it does not exist.
+    #[inline]
+    pub fn pop(&self, key: &str) -> Option<JsonValue> {
+        self.pop_entry(key).map(|(_, value)| value)
+    }
+
+    /// Removes and returns the key-value pair from the region or
+    /// `None` if it does not exist.
+    #[inline]
+    pub fn pop_entry(&self, key: &str) -> Option<(String, JsonValue)> {
+        let mut state = self.state.write();
+        state.remove(key).map(|(key, entry)| (key, entry.value))
+    }
+
+    /// Removes and returns the key-value pair corresponding to the least recently used item
+    /// or `None` if the region is empty.
+    #[inline]
+    pub fn pop_lru(&self) -> Option<(String, JsonValue)> {
+        let mut state = self.state.write();
+        state.pop_lru().map(|(key, entry)| (key, entry.value))
+    }
+
+    /// Marks the key as the most recently used one.
+    #[inline]
+    pub fn promote(&self, key: &str) -> bool {
+        let mut state = self.state.write();
+        state.entries.promote(key)
+    }
+
+    /// Marks the key as the least recently used one.
+    #[inline]
+    pub fn demote(&self, key: &str) -> bool {
+        let mut state = self.state.write();
+        state.entries.demote(key)
+    }
+
+    /// Removes the expired entries and returns the number of them.
+    pub fn remove_expired(&self) -> usize {
+        let now = Instant::now();
+        let mut state = self.state.write();
+        let expired_keys = state
+            .entries
+            .iter()
+            .filter(|(_, entry)| entry.is_expired(now))
+            .map(|(key, _)| key.clone())
+            .collect::<Vec<_>>();
+        for key in expired_keys.iter() {
+            state.remove(key);
+            self.stats.record_expiration(&self.name);
+        }
+        expired_keys.len()
+    }
+
+    /// Returns the number of key-value pairs that are currently in the region.
+    #[inline]
+    pub fn len(&self) -> usize {
+        let state = self.state.read();
+        state.entries.len()
+    }
+
+    /// Returns a bool indicating whether the region is empty or not.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        let state = self.state.read();
+        state.entries.is_empty()
+    }
+
+    /// Returns the total size of the entries in bytes.
+    /// It is always `0` if the maximum bytes are not specified.
+    #[inline]
+    pub fn size_in_bytes(&self) -> usize {
+        let state = self.state.read();
+        state.total_bytes
+    }
+
+    /// Returns the maximum number of key-value pairs the region can hold.
+    #[inline]
+    pub fn cap(&self) -> NonZeroUsize {
+        let state = self.state.read();
+        state.capacity
+    }
+
+    /// Resizes the region. If the new capacity is smaller than the size of
+    /// the current region any entries past the new capacity are discarded.
+    #[inline]
+    pub fn resize(&self, cap: NonZeroUsize) {
+        let mut state = self.state.write();
+        state.capacity = cap;
+        self.evict(&mut state, |_| ());
+    }
+
+    /// Clears the contents of the region.
+    #[inline]
+    pub fn clear(&self) {
+        let mut state = self.state.write();
+        state.entries.clear();
+        state.total_bytes = 0;
+    }
+
+    /// Returns the statistics of the region.
+    #[inline]
+    pub fn stats(&self) -> CacheStats {
+        self.stats.snapshot()
+    }
+
+    /// Creates a new entry.
+    fn new_entry(
+        &self,
+        key: String,
+        value: JsonValue,
+        ttl: Option<Duration>,
+        tti: Option<Duration>,
+    ) -> (String, CacheEntry) {
+        let now = Instant::now();
+        let size = if self.max_bytes.is_some() {
+            key.len() + value.to_string().len()
+        } else {
+            0
+        };
+        let entry = CacheEntry {
+            value,
+            size,
+            expires_at: ttl.and_then(|ttl| now.checked_add(ttl)),
+            time_to_idle: tti,
+            last_accessed: now,
+        };
+        (key, entry)
+    }
+
+    /// Evicts the least recently used entries until the limits are satisfied.
+    fn evict(&self, state: &mut RegionState, mut on_evict: impl FnMut((String, JsonValue))) {
+        while state.entries.len() > state.capacity.get()
+            || self
+                .max_bytes
+                .is_some_and(|max_bytes| state.total_bytes > max_bytes)
+        {
+            let Some((key, entry)) = state.pop_lru() else {
+                break;
+            };
+            self.stats.record_eviction(&self.name);
+            on_evict((key, entry.value));
+        }
+    }
+}
+
+/// Statistics of a cache region.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Number of hits.
+    hits: u64,
+    /// Number of misses.
+    misses: u64,
+    /// Number of evictions.
+    evictions: u64,
+    /// Number of expirations.
+    expirations: u64,
+}
+
+impl CacheStats {
+    /// Returns the number of hits.
+    #[inline]
+    pub fn hits(&self) -> u64 {
+        self.hits
+    }
+
+    /// Returns the number of misses.
+    #[inline]
+    pub fn misses(&self) -> u64 {
+        self.misses
+    }
+
+    /// Returns the number of evictions.
+    #[inline]
+    pub fn evictions(&self) -> u64 {
+        self.evictions
+    }
+
+    /// Returns the number of expirations.
+    #[inline]
+    pub fn expirations(&self) -> u64 {
+        self.expirations
+    }
+
+    /// Returns the ratio of hits to the total lookups.
+    #[inline]
+    pub fn hit_ratio(&self) -> f64 {
+        let lookups = self.hits + self.misses;
+        if lookups == 0 {
+            0.0
+        } else {
+            self.hits as f64 / lookups as f64
+        }
+    }
+}
+
+/// Counters for the cache statistics.
+#[derive(Debug, Default)]
+struct RegionStats {
+    /// Number of hits.
+    hits: AtomicU64,
+    /// Number of misses.
+    misses: AtomicU64,
+    /// Number of evictions.
+    evictions: AtomicU64,
+    /// Number of expirations.
+    expirations: AtomicU64,
+}
+
+impl RegionStats {
+    /// Records a hit.
+    fn record_hit(&self, _region: &str) {
+        self.hits.fetch_add(1, Relaxed);
+        #[cfg(feature = "metrics")]
+        metrics::counter!("zino_cache_hits_total", "region" => _region.to_owned()).increment(1);
+    }
+
+    /// Records a miss.
+    fn record_miss(&self, _region: &str) {
+        self.misses.fetch_add(1, Relaxed);
+        #[cfg(feature = "metrics")]
+        metrics::counter!("zino_cache_misses_total", "region" => _region.to_owned()).increment(1);
+    }
+
+    /// Records an eviction.
+    fn record_eviction(&self, _region: &str) {
+        self.evictions.fetch_add(1, Relaxed);
+        #[cfg(feature = "metrics")]
+        metrics::counter!("zino_cache_evictions_total", "region" => _region.to_owned())
+            .increment(1);
+    }
+
+    /// Records an expiration.
+    fn record_expiration(&self, _region: &str) {
+        self.expirations.fetch_add(1, Relaxed);
+        #[cfg(feature = "metrics")]
+        metrics::counter!("zino_cache_expirations_total", "region" => _region.to_owned())
+            .increment(1);
+    }
+
+    /// Returns a snapshot of the statistics.
+    fn snapshot(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Relaxed),
+            misses: self.misses.load(Relaxed),
+            evictions: self.evictions.load(Relaxed),
+            expirations: self.expirations.load(Relaxed),
+        }
+    }
+}
+
+/// State of a cache region.
+#[derive(Debug)]
+struct RegionState {
+    /// Cache entries in the LRU order.
+    entries: LruCache<String, CacheEntry>,
+    /// Maximum number of entries.
+    capacity: NonZeroUsize,
+    /// Total size of the entries in bytes.
+    total_bytes: usize,
+}
+
+impl RegionState {
+    /// Inserts an entry and returns the old entry with the same key.
+    fn insert(&mut self, (key, entry): (String, CacheEntry)) -> Option<(String, CacheEntry)> {
+        self.total_bytes += entry.size;
+        let old_entry = self.entries.push(key, entry);
+        if let Some((_, entry)) = old_entry.as_ref() {
+            self.total_bytes = self.total_bytes.saturating_sub(entry.size);
+        }
+        old_entry
+    }
+
+    /// Removes an entry with the key.
+    fn remove(&mut self, key: &str) -> Option<(String, CacheEntry)> {
+        let old_entry = self.entries.pop_entry(key);
+        if let Some((_, entry)) = old_entry.as_ref() {
+            self.total_bytes = self.total_bytes.saturating_sub(entry.size);
+        }
+        old_entry
+    }
+
+    /// Removes the least recently used entry.
+    fn pop_lru(&mut self) -> Option<(String, CacheEntry)> {
+        let old_entry = self.entries.pop_lru();
+        if let Some((_, entry)) = old_entry.as_ref() {
+            self.total_bytes = self.total_bytes.saturating_sub(entry.size);
+        }
+        old_entry
+    }
+}
+
+/// An entry in the cache region.
+#[derive(Debug)]
+struct CacheEntry {
+    /// The cached value.
+    value: JsonValue,
+    /// Size of the entry in bytes.
+    size: usize,
+    /// The time when the entry expires.
+    expires_at: Option<Instant>,
+    /// The time-to-idle.
+    time_to_idle: Option<Duration>,
+    /// The time when the entry was last accessed.
+    last_accessed: Instant,
+}
+
+impl CacheEntry {
+    /// Returns `true` if the entry has expired.
+    fn is_expired(&self, now: Instant) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+            || self
+                .time_to_idle
+                .is_some_and(|tti| now.duration_since(self.last_accessed) >= tti)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CacheRegion;
+    use std::{num::NonZeroUsize, thread, time::Duration};
+    use toml::Table;
+
+    #[test]
+    fn it_configures_regions() {
+        let config = r#"
+            capacity = 100
+            max-bytes = 1024
+            time-to-live = "1h"
+            time-to-idle = "10m"
+        "#
+        .parse::<Table>()
+        .expect("fail to parse the value");
+        let region = CacheRegion::with_config("tokens", &config);
+        assert_eq!(region.name(), "tokens");
+        assert_eq!(region.cap().get(), 100);
+        assert_eq!(region.max_bytes, Some(1024));
+        assert_eq!(region.time_to_live, Some(Duration::from_secs(3600)));
+        assert_eq!(region.time_to_idle, Some(Duration::from_secs(600)));
+
+        let region = CacheRegion::with_config("default", &Table::new());
+        assert_eq!(region.cap().get(), 10000);
+        assert_eq!(region.max_bytes, None);
+    }
+
+    #[test]
+    fn it_expires_entries() {
+        let region = CacheRegion::new(
+            "expiry",
+            NonZeroUsize::new(10).expect("capacity should be nonzero"),
+        )
+        .time_to_live(Duration::from_secs(3600));
+        region.put("alive", "value");
+        region.put_with_ttl("expired", "value", Duration::ZERO);
+        region.put_with_expiry("idle", "value", None, Some(Duration::from_millis(200)));
+        assert!(region.contains("alive"));
+        assert!(!region.contains("expired"));
+        assert_eq!(region.peek("expired"), None);
+        assert_eq!(region.get("idle").expect("the entry should exist"), "value");
+
+        // Accessing the entry resets its idle timer
+        thread::sleep(Duration::from_millis(120));
+        assert_eq!(region.get("idle").expect("the entry should exist"), "value");
+        thread::sleep(Duration::from_millis(120));
+        assert!(region.contains("idle"));
+        thread::sleep(Duration::from_millis(120));
+        assert!(!region.contains("idle"));
+
+        assert_eq!(region.get("expired"), None);
+        assert_eq!(region.len(), 2);
+        assert_eq!(region.remove_expired(), 1);
+        assert_eq!(region.len(), 1);
+        assert_eq!(region.stats().expirations(), 2);
+    }
+
+    #[test]
+    fn it_evicts_entries_in_lru_order() {
+        let region = CacheRegion::new(
+            "lru",
+            NonZeroUsize::new(2).expect("capacity should be nonzero"),
+        );
+        assert_eq!(region.push("a", 1), None);
+        assert_eq!(region.push("b", 2), None);
+        assert_eq!(region.get("a").expect("the entry should exist"), 1);
+        assert_eq!(region.push("c", 3), Some(("b".to_owned(), 2.into())));
+        assert_eq!(region.push("a", 4), Some(("a".to_owned(), 1.into())));
+        assert!(!region.contains("b"));
+
+        region.resize(NonZeroUsize::MIN);
+        assert_eq!(region.len(), 1);
+        assert!(region.contains("a"));
+        assert_eq!(region.stats().evictions(), 2);
+    }
+
+    #[test]
+    fn it_evicts_entries_by_size() {
+        let region = CacheRegion::new(
+            "bytes",
+            NonZeroUsize::new(100).expect("capacity should be nonzero"),
+        )
+        .max_bytes(16);
+        region.put("a", "12345");
+        region.put("b", "12345");
+        assert_eq!(region.size_in_bytes(), 16);
+
+        // Each entry takes 8 bytes, including the quotes of the serialized value
+        region.put("c", "12345");
+        assert_eq!(region.len(), 2);
+        assert_eq!(region.size_in_bytes(), 16);
+        assert!(!region.contains("a"));
+
+        region.put("b", "1");
+        assert_eq!(region.size_in_bytes(), 12);
+        region.put("d", "1234567890123456");
+        assert!(region.is_empty());
+        assert_eq!(region.size_in_bytes(), 0);
+        assert_eq!(region.stats().evictions(), 4);
+
+        region.put("a", "1");
+        assert_eq!(region.pop("a").expect("the entry should exist"), "1");
+        assert_eq!(region.size_in_bytes(), 0);
+    }
+
+    #[test]
+    fn it_records_stats() {
+        let region = CacheRegion::new(
+            "stats",
+            NonZeroUsize::new(10).expect("capacity should be nonzero"),
+        );
+        assert_eq!(region.stats().hit_ratio(), 0.0);
+
+        region.put("key", "value");
+        assert!(region.get("key").is_some());
+        assert!(region.get("key").is_some());
+        assert!(region.get("unknown").is_none());
+        assert!(region.peek("unknown").is_none());
+
+        let stats = region.stats();
+        assert_eq!(stats.hits(), 2);
+        assert_eq!(stats.misses(), 1);
+        assert_eq!(stats.evictions(), 0);
+        assert_eq!(stats.expirations(), 0);
+        assert!((stats.hit_ratio() - 2.0 / 3.0).abs() < f64::EPSILON);
+
+        region.clear();
+        assert!(region.is_empty());
+        assert_eq!(region.stats(), stats);
+    }
+}
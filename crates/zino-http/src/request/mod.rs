@@ -44,6 +44,9 @@ use unic_langid::LanguageIdentifier;
 #[cfg(feature = "i18n")]
 use zino_core::i18n::{Intl, IntlError};
 
+#[cfg(feature = "tls")]
+use zino_core::application::ClientCertificate;
+
 mod context;
 
 pub use context::Context;
@@ -76,6 +79,17 @@ pub trait RequestContext {
     /// Returns the client's remote IP.
     fn client_ip(&self) -> Option<IpAddr>;
 
+    /// Returns the IP of the socket peer, which may be a proxy in front of the client.
+    /// Unlike [`client_ip()`](Self::client_ip), it can not be spoofed by the request headers.
+    fn peer_ip(&self) -> Option<IpAddr>;
+
+    /// Returns the client certificate verified in the TLS handshake.
+    #[cfg(feature = "tls")]
+    #[inline]
+    fn client_certificate(&self) -> Option<Arc<ClientCertificate>> {
+        None
+    }
+
     /// Gets the request context.
     fn get_context(&self) -> Option<Arc<Context>>;
 
@@ -45,11 +45,14 @@ use crate::{
 };
 use bytes::Bytes;
 use etag::EntityTag;
+use futures::{Stream, StreamExt, stream::BoxStream};
 use http::{HeaderMap, HeaderName};
 use serde::Serialize;
 use std::{
+    fmt,
     marker::PhantomData,
     mem,
+    sync::{Arc, Mutex},
     time::{Duration, Instant},
 };
 use zino_core::{
@@ -83,6 +86,20 @@ pub type StatusCode = http::StatusCode;
 /// A function pointer of transforming the response data.
 pub type DataTransformer = fn(data: &JsonValue) -> Result<Bytes, Error>;
 
+/// A stream of the chunks of the response body.
+pub type BodyStream = BoxStream<'static, Result<Bytes, Error>>;
+
+/// A shared slot for the streaming body, which can be taken only once.
+#[derive(Clone, Default)]
+struct StreamData(Arc<Mutex<Option<BodyStream>>>);
+
+impl fmt::Debug for StreamData {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("StreamData")
+    }
+}
+
 /// An HTTP response.
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "snake_case")]
@@ -125,6 +142,9 @@ pub struct Response<S: ResponseCode> {
     /// Bytes data.
     #[serde(skip)]
     bytes_data: Bytes,
+    /// Stream data.
+    #[serde(skip)]
+    stream_data: Option<StreamData>,
     /// Transformer of the response data.
     #[serde(skip)]
     data_transformer: Option<DataTransformer>,
@@ -163,6 +183,7 @@ impl<S: ResponseCode> Response<S> {
             request_id: Uuid::nil(),
             json_data: JsonValue::Null,
             bytes_data: Bytes::new(),
+            stream_data: None,
             data_transformer: None,
             content_type: None,
             trace_context: None,
@@ -195,6 +216,7 @@ impl<S: ResponseCode> Response<S> {
             request_id: ctx.request_id(),
             json_data: JsonValue::Null,
             bytes_data: Bytes::new(),
+            stream_data: None,
             data_transformer: None,
             content_type: None,
             trace_context: None,
@@ -335,6 +357,7 @@ impl<S: ResponseCode> Response<S> {
     pub fn set_json_data(&mut self, data: impl Into<JsonValue>) {
         self.json_data = data.into();
         self.bytes_data = Bytes::new();
+        self.stream_data = None;
     }
 
     /// Sets the bytes data.
@@ -342,6 +365,27 @@ impl<S: ResponseCode> Response<S> {
     pub fn set_bytes_data(&mut self, data: impl Into<Bytes>) {
         self.json_data = JsonValue::Null;
         self.bytes_data = data.into();
+        self.stream_data = None;
+    }
+
+    /// Sets the stream data, which is sent as the response body chunk by chunk
+    /// without being buffered. The `x-etag` header is not generated for the stream.
+    pub fn set_stream_data<T, D>(&mut self, stream: T)
+    where
+        T: Stream<Item = Result<D, Error>> + Send + 'static,
+        D: Into<Bytes>,
+    {
+        let stream = stream.map(|result| result.map(|data| data.into())).boxed();
+        self.json_data = JsonValue::Null;
+        self.bytes_data = Bytes::new();
+        self.stream_data = Some(StreamData(Arc::new(Mutex::new(Some(stream)))));
+    }
+
+    /// Takes the stream data if it has been set.
+    pub fn take_stream_data(&mut self) -> Option<BodyStream> {
+        let stream_data = self.stream_data.take()?;
+        let mut stream = stream_data.0.lock().ok()?;
+        stream.take()
     }
 
     /// Sets the response data for the validation.
@@ -563,7 +607,7 @@ impl<S: ResponseCode> Response<S> {
     #[inline]
     pub fn content_type(&self) -> &str {
         self.content_type.as_deref().unwrap_or_else(|| {
-            if !self.bytes_data.is_empty() {
+            if !self.bytes_data.is_empty() || self.stream_data.is_some() {
                 "application/octet-stream"
             } else if self.is_success() {
                 "application/json; charset=utf-8"
@@ -31,6 +31,10 @@ enum RejectionKind {
     MethodNotAllowed(Error),
     /// 409 Conflict
     Conflict(Error),
+    /// 413 Payload Too Large
+    PayloadTooLarge(Error),
+    /// 429 Too Many Requests
+    TooManyRequests(Error),
     /// 500 Internal Server Error
     InternalServerError(Error),
     /// 503 Service Unavailable
@@ -98,6 +102,26 @@ impl Rejection {
         }
     }
 
+    /// Creates a `413 Payload Too Large` rejection.
+    #[inline]
+    pub fn payload_too_large(err: impl Into<Error>) -> Self {
+        Self {
+            kind: PayloadTooLarge(err.into()),
+            context: None,
+            trace_context: None,
+        }
+    }
+
+    /// Creates a `429 Too Many Requests` rejection.
+    #[inline]
+    pub fn too_many_requests(err: impl Into<Error>) -> Self {
+        Self {
+            kind: TooManyRequests(err.into()),
+            context: None,
+            trace_context: None,
+        }
+    }
+
     /// Creates a `500 Internal Server Error` rejection.
     #[inline]
     pub fn internal_server_error(err: impl Into<Error>) -> Self {
@@ -139,6 +163,10 @@ impl Rejection {
                 Rejection::method_not_allowed(err)
             } else if message.starts_with("409 Conflict") {
                 Rejection::conflict(err)
+            } else if message.starts_with("413 Payload Too Large") {
+                Rejection::payload_too_large(err)
+            } else if message.starts_with("429 Too Many Requests") {
+                Rejection::too_many_requests(err)
             } else if message.starts_with("503 Service Unavailable") {
                 Rejection::service_unavailable(err)
             } else {
@@ -172,10 +200,33 @@ impl Rejection {
             NotFound(_) => 404,
             MethodNotAllowed(_) => 405,
             Conflict(_) => 409,
+            PayloadTooLarge(_) => 413,
+            TooManyRequests(_) => 429,
             InternalServerError(_) => 500,
             ServiceUnavailable(_) => 503,
         }
     }
+
+    /// Converts `self` into an error.
+    /// The validation of a `400 Bad Request` rejection is kept as the error context.
+    pub fn into_error(self) -> Error {
+        match self.kind {
+            BadRequest(validation) => {
+                let mut err = Error::new(validation.to_string());
+                err.set_context(validation);
+                err
+            }
+            Unauthorized(err) => err,
+            Forbidden(err) => err,
+            NotFound(err) => err,
+            MethodNotAllowed(err) => err,
+            Conflict(err) => err,
+            PayloadTooLarge(err) => err,
+            TooManyRequests(err) => err,
+            InternalServerError(err) => err,
+            ServiceUnavailable(err) => err,
+        }
+    }
 }
 
 macro_rules! impl_from_rejection {
@@ -213,6 +264,16 @@ macro_rules! impl_from_rejection {
                         res.set_error_message(err);
                         res
                     }
+                    PayloadTooLarge(err) => {
+                        let mut res = Response::new(<$Ty>::PAYLOAD_TOO_LARGE);
+                        res.set_error_message(err);
+                        res
+                    }
+                    TooManyRequests(err) => {
+                        let mut res = Response::new(<$Ty>::TOO_MANY_REQUESTS);
+                        res.set_error_message(err);
+                        res
+                    }
                     InternalServerError(err) => {
                         let mut res = Response::new(<$Ty>::INTERNAL_SERVER_ERROR);
                         res.set_error_message(err);
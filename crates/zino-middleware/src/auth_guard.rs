@@ -0,0 +1,154 @@
+use super::Middleware;
+use hmac::Hmac;
+use zino_auth::{AccessKeyId, SecretAccessKey};
+use zino_core::{crypto::Digest, error::Error, warn};
+use zino_http::{request::RequestContext, response::Rejection};
+
+#[cfg(feature = "jwt")]
+use std::{error, marker::PhantomData, str::FromStr};
+
+#[cfg(feature = "jwt")]
+use zino_auth::{JwtClaims, JwtHmacKey, UserSession};
+
+/// A guard which verifies the request signature parsed by
+/// [`parse_authentication()`](RequestContext::parse_authentication).
+///
+/// On success, the `AccessKeyId` is inserted into the request data.
+#[derive(Debug, Clone, Copy)]
+pub struct SignatureGuard {
+    /// A function to get the secret access key for the access key ID.
+    secret_key_fn: fn(&AccessKeyId) -> Option<SecretAccessKey>,
+}
+
+impl SignatureGuard {
+    /// Creates a new instance with the secret access keys derived from the app secret.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            secret_key_fn: |access_key_id| Some(SecretAccessKey::new(access_key_id)),
+        }
+    }
+
+    /// Creates a new instance with a function to get the secret access key.
+    #[inline]
+    pub fn with_secret_key_fn(secret_key_fn: fn(&AccessKeyId) -> Option<SecretAccessKey>) -> Self {
+        Self { secret_key_fn }
+    }
+}
+
+impl Default for SignatureGuard {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Middleware for SignatureGuard {
+    type State = ();
+
+    fn before_request<Ctx: RequestContext>(&self, ctx: &mut Ctx) -> Result<(), Rejection> {
+        let authentication = ctx.parse_authentication()?;
+        let access_key_id = AccessKeyId::from(authentication.access_key_id());
+        if access_key_id.as_str().is_empty() {
+            let err = warn!("access key ID should be nonempty");
+            return Err(Rejection::unauthorized(err).context(ctx));
+        }
+
+        let Some(secret_access_key) = (self.secret_key_fn)(&access_key_id) else {
+            let err = warn!("access key ID `{}` is invalid", access_key_id);
+            return Err(Rejection::unauthorized(err).context(ctx));
+        };
+        let validation = authentication.validate_with::<Hmac<Digest>>(&secret_access_key);
+        if !validation.is_success() {
+            return Err(Rejection::bad_request(validation).context(ctx));
+        }
+        ctx.set_data(access_key_id);
+        Ok(())
+    }
+}
+
+/// A guard which verifies the JWT parsed by
+/// [`parse_jwt_claims()`](RequestContext::parse_jwt_claims).
+///
+/// On success, the `UserSession<U, String, T>` constructed from the claims
+/// is inserted into the request data.
+#[cfg(feature = "jwt")]
+#[derive(Debug)]
+pub struct JwtGuard<U, T = U> {
+    /// Optional HMAC key, which defaults to the shared key.
+    key: Option<JwtHmacKey>,
+    /// Roles of which the user should have at least one.
+    roles: Vec<String>,
+    /// Phantom types of the user session.
+    session: PhantomData<fn() -> (U, T)>,
+}
+
+#[cfg(feature = "jwt")]
+impl<U, T> JwtGuard<U, T> {
+    /// Creates a new instance with the shared HMAC key.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            key: None,
+            roles: Vec::new(),
+            session: PhantomData,
+        }
+    }
+
+    /// Creates a new instance with the HMAC key.
+    #[inline]
+    pub fn with_key(key: JwtHmacKey) -> Self {
+        Self {
+            key: Some(key),
+            roles: Vec::new(),
+            session: PhantomData,
+        }
+    }
+
+    /// Requires the user to have the role.
+    /// If multiple roles are required, the user should have at least one of them.
+    #[inline]
+    pub fn require_role(mut self, role: impl Into<String>) -> Self {
+        self.roles.push(role.into());
+        self
+    }
+}
+
+#[cfg(feature = "jwt")]
+impl<U, T> Default for JwtGuard<U, T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "jwt")]
+impl<U, T> Middleware for JwtGuard<U, T>
+where
+    U: FromStr + Clone + Send + Sync + 'static,
+    T: FromStr + Clone + Send + Sync + 'static,
+    <U as FromStr>::Err: error::Error + Send + 'static,
+{
+    type State = ();
+
+    fn before_request<Ctx: RequestContext>(&self, ctx: &mut Ctx) -> Result<(), Rejection> {
+        let claims = match &self.key {
+            Some(key) => ctx.parse_jwt_claims(key)?,
+            None => ctx.parse_jwt_claims(JwtClaims::shared_key())?,
+        };
+        let session = match UserSession::<U, String, T>::try_from_jwt_claims(claims) {
+            Ok(session) => session,
+            Err(err) => {
+                tracing::warn!("invalid JWT claims: {err}");
+                let err = Error::new("invalid JWT claims");
+                return Err(Rejection::unauthorized(err).context(ctx));
+            }
+        };
+        if !self.roles.is_empty() && !self.roles.iter().any(|role| session.has_role(role)) {
+            let err = warn!("one of the roles `{}` is required", self.roles.join(", "));
+            return Err(Rejection::forbidden(err).context(ctx));
+        }
+        ctx.set_data(session);
+        Ok(())
+    }
+}
@@ -0,0 +1,55 @@
+use super::Middleware;
+use toml::Table;
+use zino_core::{error::Error, extension::TomlTableExt, warn};
+use zino_http::{request::RequestContext, response::Rejection};
+
+/// A middleware which rejects the request with a body larger than the limit.
+///
+/// The size is determined by the `content-length` header,
+/// so the streaming bodies should be limited by the server as well.
+#[derive(Debug, Clone, Copy)]
+pub struct BodyLimit {
+    /// Maximum number of bytes.
+    max_bytes: usize,
+}
+
+impl BodyLimit {
+    /// Creates a new instance with the maximum number of bytes.
+    #[inline]
+    pub fn new(max_bytes: usize) -> Self {
+        Self { max_bytes }
+    }
+
+    /// Creates a new instance with the configuration.
+    pub fn with_config(config: &Table) -> Self {
+        let max_bytes = config.get_usize("max-bytes").unwrap_or(128 * 1024 * 1024); // 128MB
+        Self::new(max_bytes)
+    }
+
+    /// Returns the maximum number of bytes.
+    #[inline]
+    pub fn max_bytes(&self) -> usize {
+        self.max_bytes
+    }
+}
+
+impl Middleware for BodyLimit {
+    type State = ();
+
+    fn before_request<Ctx: RequestContext>(&self, ctx: &mut Ctx) -> Result<(), Rejection> {
+        let Some(content_length) = ctx.get_header("content-length") else {
+            return Ok(());
+        };
+        match content_length.parse::<usize>() {
+            Ok(length) if length > self.max_bytes => {
+                let err = warn!(
+                    "request body of {} bytes exceeds the limit of {} bytes",
+                    length, self.max_bytes
+                );
+                Err(Rejection::payload_too_large(err).context(ctx))
+            }
+            Ok(_) => Ok(()),
+            Err(err) => Err(Rejection::from_validation_entry("content-length", err).context(ctx)),
+        }
+    }
+}
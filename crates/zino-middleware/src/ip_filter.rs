@@ -0,0 +1,118 @@
+use super::{Middleware, TrustedProxies, trusted_proxies::IpNetwork};
+use std::net::IpAddr;
+use toml::Table;
+use zino_core::{error::Error, extension::TomlTableExt, warn};
+use zino_http::{request::RequestContext, response::Rejection};
+
+/// A middleware which filters the requests by the client IP.
+///
+/// A request is rejected if the client IP matches the deny list,
+/// or if the allow list is nonempty and the client IP does not match it.
+/// The client IP is resolved by the [`TrustedProxies`].
+#[derive(Debug, Clone, Default)]
+pub struct IpFilter {
+    /// Allowed networks.
+    allow_list: Vec<IpNetwork>,
+    /// Denied networks.
+    deny_list: Vec<IpNetwork>,
+    /// Trusted proxies.
+    trusted_proxies: TrustedProxies,
+}
+
+impl IpFilter {
+    /// Creates a new instance which allows all the requests.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new instance with the configuration.
+    pub fn with_config(config: &Table) -> Self {
+        let mut filter = Self::new().trusted_proxies(TrustedProxies::with_config(config));
+        if let Some(networks) = config.get_str_array("allow") {
+            for network in networks {
+                filter = filter.allow(network);
+            }
+        }
+        if let Some(networks) = config.get_str_array("deny") {
+            for network in networks {
+                filter = filter.deny(network);
+            }
+        }
+        filter
+    }
+
+    /// Allows an IP address or a network in the CIDR notation.
+    pub fn allow(mut self, network: &str) -> Self {
+        match network.parse() {
+            Ok(network) => self.allow_list.push(network),
+            Err(err) => tracing::error!("fail to parse the network `{network}`: {err}"),
+        }
+        self
+    }
+
+    /// Denies an IP address or a network in the CIDR notation.
+    pub fn deny(mut self, network: &str) -> Self {
+        match network.parse() {
+            Ok(network) => self.deny_list.push(network),
+            Err(err) => tracing::error!("fail to parse the network `{network}`: {err}"),
+        }
+        self
+    }
+
+    /// Sets the trusted proxies for resolving the client IP.
+    #[inline]
+    pub fn trusted_proxies(mut self, trusted_proxies: TrustedProxies) -> Self {
+        self.trusted_proxies = trusted_proxies;
+        self
+    }
+
+    /// Returns `true` if the IP address is permitted.
+    pub fn is_permitted(&self, ip: IpAddr) -> bool {
+        if self.deny_list.iter().any(|network| network.contains(ip)) {
+            return false;
+        }
+        self.allow_list.is_empty() || self.allow_list.iter().any(|network| network.contains(ip))
+    }
+}
+
+impl Middleware for IpFilter {
+    type State = ();
+
+    fn before_request<Ctx: RequestContext>(&self, ctx: &mut Ctx) -> Result<(), Rejection> {
+        match self.trusted_proxies.client_ip(ctx) {
+            Some(ip) if self.is_permitted(ip) => Ok(()),
+            Some(ip) => {
+                let err = warn!("client IP `{}` is not permitted", ip);
+                Err(Rejection::forbidden(err).context(ctx))
+            }
+            None if self.allow_list.is_empty() => Ok(()),
+            None => {
+                let err = warn!("client IP can not be determined");
+                Err(Rejection::forbidden(err).context(ctx))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::IpFilter;
+
+    #[test]
+    fn it_filters_ip_addresses() {
+        let filter = IpFilter::new()
+            .allow("10.0.0.0/8")
+            .allow("::1")
+            .deny("10.1.0.0/16");
+        assert!(filter.is_permitted("10.2.3.4".parse().expect("fail to parse the value")));
+        assert!(filter.is_permitted("::1".parse().expect("fail to parse the value")));
+        assert!(filter.is_permitted("::ffff:10.0.0.1".parse().expect("fail to parse the value")));
+        assert!(!filter.is_permitted("10.1.2.3".parse().expect("fail to parse the value")));
+        assert!(!filter.is_permitted("192.168.0.1".parse().expect("fail to parse the value")));
+
+        let filter = IpFilter::new().deny("0.0.0.0/0");
+        assert!(!filter.is_permitted("127.0.0.1".parse().expect("fail to parse the value")));
+        assert!(filter.is_permitted("fe80::1".parse().expect("fail to parse the value")));
+    }
+}
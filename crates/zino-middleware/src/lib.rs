@@ -1,3 +1,48 @@
+#![cfg_attr(docsrs, feature(doc_cfg))]
 #![doc = include_str!("../README.md")]
 #![doc(html_favicon_url = "https://zino.cc/assets/zino-logo.png")]
 #![doc(html_logo_url = "https://zino.cc/assets/zino-logo.svg")]
+
+use http::HeaderMap;
+use zino_http::{request::RequestContext, response::Rejection};
+
+mod body_limit;
+mod ip_filter;
+mod rate_limit;
+mod request_id;
+mod trusted_proxies;
+
+#[cfg(feature = "auth")]
+mod auth_guard;
+
+pub use body_limit::BodyLimit;
+pub use ip_filter::IpFilter;
+pub use rate_limit::{ClientIp, RateLimitKey, RateLimitQuota, RateLimiter};
+pub use request_id::RequestId;
+pub use trusted_proxies::TrustedProxies;
+
+#[cfg(feature = "auth")]
+pub use auth_guard::SignatureGuard;
+
+#[cfg(feature = "jwt")]
+pub use auth_guard::JwtGuard;
+
+/// A framework-neutral middleware over the request context.
+///
+/// The integration crates provide thin adapters which run
+/// [`before_request()`](Middleware::before_request) ahead of the handler
+/// and apply [`after_response()`](Middleware::after_response) to the response headers.
+pub trait Middleware: Send + Sync {
+    /// Per-request state passed from the request phase to the response phase.
+    type State: Send + 'static;
+
+    /// Inspects the request before it reaches the handler.
+    /// Returning a rejection short-circuits the request.
+    fn before_request<Ctx: RequestContext>(&self, ctx: &mut Ctx) -> Result<Self::State, Rejection>;
+
+    /// Modifies the response headers after the handler has been executed.
+    #[inline]
+    fn after_response(&self, state: Self::State, headers: &mut HeaderMap<String>) {
+        let _ = (state, headers);
+    }
+}
@@ -0,0 +1,304 @@
+use super::{Middleware, TrustedProxies};
+use http::{HeaderMap, HeaderName};
+use parking_lot::Mutex;
+use std::{
+    collections::HashMap,
+    marker::PhantomData,
+    time::{Duration, Instant},
+};
+use toml::Table;
+use zino_core::{error::Error, extension::TomlTableExt, warn};
+use zino_http::{request::RequestContext, response::Rejection};
+
+#[cfg(feature = "auth")]
+use std::fmt::Display;
+
+#[cfg(feature = "auth")]
+use zino_auth::UserSession;
+
+/// Key of the rate limiting.
+pub trait RateLimitKey: 'static {
+    /// Extracts the key from the request context.
+    /// The client IP should be resolved by the trusted proxies.
+    fn extract_key<Ctx: RequestContext>(
+        ctx: &Ctx,
+        trusted_proxies: &TrustedProxies,
+    ) -> Option<String>;
+}
+
+/// Rate limiting keyed on the client IP.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ClientIp;
+
+impl RateLimitKey for ClientIp {
+    #[inline]
+    fn extract_key<Ctx: RequestContext>(
+        ctx: &Ctx,
+        trusted_proxies: &TrustedProxies,
+    ) -> Option<String> {
+        trusted_proxies.client_ip(ctx).map(|ip| format!("ip:{ip}"))
+    }
+}
+
+/// Rate limiting keyed on the user ID of the `UserSession` in the request data.
+/// It falls back to the client IP if the user session does not exist.
+#[cfg(feature = "auth")]
+impl<U, R, T> RateLimitKey for UserSession<U, R, T>
+where
+    U: Display + Clone + Send + Sync + 'static,
+    R: Clone + Send + Sync + 'static,
+    T: Clone + Send + Sync + 'static,
+{
+    #[inline]
+    fn extract_key<Ctx: RequestContext>(
+        ctx: &Ctx,
+        trusted_proxies: &TrustedProxies,
+    ) -> Option<String> {
+        ctx.get_data::<Self>()
+            .map(|session| format!("user:{}", session.user_id()))
+            .or_else(|| ClientIp::extract_key(ctx, trusted_proxies))
+    }
+}
+
+/// A token-bucket rate limiter.
+///
+/// Each key owns a bucket which is refilled at a constant rate.
+/// A request consumes one token and it is rejected if the bucket is empty.
+/// Once the number of buckets reaches `max_keys`, the full buckets are evicted first,
+/// and then the least recently used bucket is evicted for a new key.
+#[derive(Debug)]
+pub struct RateLimiter<K = ClientIp> {
+    /// Number of tokens refilled in the period.
+    capacity: usize,
+    /// Maximum number of tokens in a bucket.
+    burst: usize,
+    /// Time period to refill the capacity.
+    period: Duration,
+    /// Maximum number of buckets.
+    max_keys: usize,
+    /// Token buckets.
+    buckets: Mutex<HashMap<String, TokenBucket>>,
+    /// Trusted proxies.
+    trusted_proxies: TrustedProxies,
+    /// Phantom type of the key.
+    key: PhantomData<fn() -> K>,
+}
+
+/// A bucket of tokens.
+#[derive(Debug, Clone, Copy)]
+struct TokenBucket {
+    /// Number of the available tokens.
+    tokens: f64,
+    /// Last time the bucket was refilled.
+    updated_at: Instant,
+}
+
+/// Quota of the rate limiting for a request.
+#[derive(Debug, Clone, Copy)]
+pub struct RateLimitQuota {
+    /// Maximum number of requests in the burst.
+    limit: usize,
+    /// Number of remaining requests.
+    remaining: usize,
+    /// Time until the bucket is full.
+    reset: Duration,
+}
+
+impl<K: RateLimitKey> RateLimiter<K> {
+    /// Creates a new instance which allows the number of requests in the time period.
+    #[inline]
+    pub fn new(capacity: usize, period: Duration) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            capacity,
+            burst: capacity,
+            period: period.max(Duration::from_millis(1)),
+            max_keys: 10000,
+            buckets: Mutex::new(HashMap::new()),
+            trusted_proxies: TrustedProxies::new(),
+            key: PhantomData,
+        }
+    }
+
+    /// Creates a new instance with the configuration.
+    pub fn with_config(config: &Table) -> Self {
+        let capacity = config.get_usize("capacity").unwrap_or(100);
+        let period = config
+            .get_duration("period")
+            .unwrap_or(Duration::from_secs(60));
+        let mut rate_limiter =
+            Self::new(capacity, period).trusted_proxies(TrustedProxies::with_config(config));
+        if let Some(burst) = config.get_usize("burst") {
+            rate_limiter = rate_limiter.burst(burst);
+        }
+        if let Some(max_keys) = config.get_usize("max-keys") {
+            rate_limiter = rate_limiter.max_keys(max_keys);
+        }
+        rate_limiter
+    }
+
+    /// Sets the maximum number of tokens in a bucket.
+    #[inline]
+    pub fn burst(mut self, burst: usize) -> Self {
+        self.burst = burst.max(1);
+        self
+    }
+
+    /// Sets the maximum number of buckets.
+    #[inline]
+    pub fn max_keys(mut self, max_keys: usize) -> Self {
+        self.max_keys = max_keys.max(1);
+        self
+    }
+
+    /// Sets the trusted proxies for resolving the client IP.
+    #[inline]
+    pub fn trusted_proxies(mut self, trusted_proxies: TrustedProxies) -> Self {
+        self.trusted_proxies = trusted_proxies;
+        self
+    }
+
+    /// Attempts to acquire a token for the key.
+    pub fn acquire(&self, key: &str) -> Result<RateLimitQuota, Duration> {
+        let now = Instant::now();
+        let rate = self.capacity as f64 / self.period.as_secs_f64();
+        let burst = self.burst as f64;
+        let refill = |bucket: &TokenBucket| {
+            let elapsed = now.saturating_duration_since(bucket.updated_at);
+            (bucket.tokens + elapsed.as_secs_f64() * rate).min(burst)
+        };
+
+        let mut buckets = self.buckets.lock();
+        if buckets.len() >= self.max_keys && !buckets.contains_key(key) {
+            buckets.retain(|_, bucket| refill(bucket) < burst);
+            if buckets.len() >= self.max_keys {
+                let oldest_key = buckets
+                    .iter()
+                    .min_by_key(|(_, bucket)| bucket.updated_at)
+                    .map(|(key, _)| key.clone());
+                if let Some(key) = oldest_key {
+                    buckets.remove(&key);
+                }
+            }
+        }
+
+        let bucket = buckets.entry(key.to_owned()).or_insert(TokenBucket {
+            tokens: burst,
+            updated_at: now,
+        });
+        bucket.tokens = refill(bucket);
+        bucket.updated_at = now;
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(RateLimitQuota {
+                limit: self.burst,
+                remaining: bucket.tokens as usize,
+                reset: Duration::from_secs_f64((burst - bucket.tokens) / rate),
+            })
+        } else {
+            Err(Duration::from_secs_f64((1.0 - bucket.tokens) / rate))
+        }
+    }
+}
+
+impl<K: RateLimitKey> Middleware for RateLimiter<K> {
+    type State = Option<RateLimitQuota>;
+
+    fn before_request<Ctx: RequestContext>(&self, ctx: &mut Ctx) -> Result<Self::State, Rejection> {
+        let Some(key) = K::extract_key(ctx, &self.trusted_proxies) else {
+            return Ok(None);
+        };
+        match self.acquire(&key) {
+            Ok(quota) => Ok(Some(quota)),
+            Err(retry_after) => {
+                let err = warn!(
+                    "rate limit is exceeded, retry after {} seconds",
+                    retry_after.as_secs().max(1)
+                );
+                Err(Rejection::too_many_requests(err).context(ctx))
+            }
+        }
+    }
+
+    fn after_response(&self, state: Self::State, headers: &mut HeaderMap<String>) {
+        if let Some(quota) = state {
+            let reset = quota.reset.as_secs_f64().ceil() as u64;
+            headers.insert(
+                HeaderName::from_static("x-ratelimit-limit"),
+                quota.limit.to_string(),
+            );
+            headers.insert(
+                HeaderName::from_static("x-ratelimit-remaining"),
+                quota.remaining.to_string(),
+            );
+            headers.insert(
+                HeaderName::from_static("x-ratelimit-reset"),
+                reset.to_string(),
+            );
+        }
+    }
+}
+
+impl RateLimitQuota {
+    /// Returns the maximum number of requests in the burst.
+    #[inline]
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Returns the number of remaining requests.
+    #[inline]
+    pub fn remaining(&self) -> usize {
+        self.remaining
+    }
+
+    /// Returns the time until the bucket is full.
+    #[inline]
+    pub fn reset(&self) -> Duration {
+        self.reset
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RateLimiter;
+    use std::time::Duration;
+
+    #[test]
+    fn it_limits_the_request_rate() {
+        let rate_limiter = RateLimiter::<super::ClientIp>::new(2, Duration::from_secs(60))
+            .burst(3)
+            .max_keys(3);
+        for remaining in [2, 1, 0] {
+            let quota = rate_limiter
+                .acquire("ip:127.0.0.1")
+                .expect("the quota should be available");
+            assert_eq!(quota.remaining(), remaining);
+            assert_eq!(quota.limit(), 3);
+        }
+
+        let retry_after = rate_limiter
+            .acquire("ip:127.0.0.1")
+            .expect_err("the quota should be exhausted");
+        assert!(retry_after > Duration::from_secs(29));
+        assert!(retry_after <= Duration::from_secs(30));
+        assert!(rate_limiter.acquire("ip:::1").is_ok());
+        assert!(rate_limiter.acquire("user:1").is_ok());
+        assert!(rate_limiter.acquire("ip:127.0.0.1").is_err());
+    }
+
+    #[test]
+    fn it_evicts_the_oldest_bucket() {
+        let rate_limiter = RateLimiter::<super::ClientIp>::new(1, Duration::from_secs(60))
+            .burst(2)
+            .max_keys(2);
+        for key in ["ip:10.0.0.1", "ip:10.0.0.2", "ip:10.0.0.3", "ip:10.0.0.4"] {
+            assert!(rate_limiter.acquire(key).is_ok());
+            assert!(rate_limiter.buckets.lock().len() <= 2);
+        }
+
+        let buckets = rate_limiter.buckets.lock();
+        assert!(buckets.contains_key("ip:10.0.0.3"));
+        assert!(buckets.contains_key("ip:10.0.0.4"));
+    }
+}
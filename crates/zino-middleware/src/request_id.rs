@@ -0,0 +1,81 @@
+use super::Middleware;
+use http::{HeaderMap, HeaderName};
+use zino_core::Uuid;
+use zino_http::{request::RequestContext, response::Rejection};
+
+/// A middleware which propagates the request ID to the response.
+///
+/// The request ID is taken from the request header if it is a valid value.
+/// Otherwise, it falls back to the ID in the request context or a newly generated one.
+#[derive(Debug, Clone)]
+pub struct RequestId {
+    /// Header name.
+    header_name: HeaderName,
+    /// Maximum length of the request ID.
+    max_length: usize,
+}
+
+impl RequestId {
+    /// Creates a new instance with the `x-request-id` header.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            header_name: HeaderName::from_static("x-request-id"),
+            max_length: 128,
+        }
+    }
+
+    /// Sets the header name, which should be in lower case.
+    ///
+    /// # Panics
+    ///
+    /// It will panic if the header name is invalid.
+    #[inline]
+    pub fn header_name(mut self, header_name: &'static str) -> Self {
+        self.header_name = HeaderName::from_static(header_name);
+        self
+    }
+
+    /// Sets the maximum length of the request ID in the header.
+    #[inline]
+    pub fn max_length(mut self, max_length: usize) -> Self {
+        self.max_length = max_length;
+        self
+    }
+
+    /// Returns `true` if the request ID is valid.
+    fn is_valid(&self, request_id: &str) -> bool {
+        !request_id.is_empty()
+            && request_id.len() <= self.max_length
+            && request_id
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || b"-_.:".contains(&b))
+    }
+}
+
+impl Default for RequestId {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Middleware for RequestId {
+    type State = String;
+
+    fn before_request<Ctx: RequestContext>(&self, ctx: &mut Ctx) -> Result<String, Rejection> {
+        let request_id = ctx
+            .get_header(self.header_name.as_str())
+            .filter(|s| self.is_valid(s))
+            .map(|s| s.to_owned())
+            .or_else(|| ctx.get_context().map(|ctx| ctx.request_id().to_string()))
+            .unwrap_or_else(|| Uuid::now_v7().to_string());
+        tracing::Span::current().record("context.request_id", request_id.as_str());
+        Ok(request_id)
+    }
+
+    #[inline]
+    fn after_response(&self, request_id: String, headers: &mut HeaderMap<String>) {
+        headers.insert(self.header_name.clone(), request_id);
+    }
+}
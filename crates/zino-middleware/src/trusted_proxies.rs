@@ -0,0 +1,186 @@
+use std::net::IpAddr;
+use toml::Table;
+use zino_core::{error::Error, extension::TomlTableExt};
+use zino_http::request::RequestContext;
+
+/// Trusted proxies for resolving the client IP.
+///
+/// The client IP is the socket peer address unless the peer is a trusted proxy.
+/// In that case, the `X-Forwarded-For` header is walked from right to left
+/// and the first address which is not a trusted proxy is taken as the client IP,
+/// falling back to the `X-Real-IP` header.
+#[derive(Debug, Clone, Default)]
+pub struct TrustedProxies {
+    /// Networks of the trusted proxies.
+    networks: Vec<IpNetwork>,
+}
+
+impl TrustedProxies {
+    /// Creates a new instance which trusts no proxies.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new instance with the `trusted-proxies` of the configuration.
+    pub fn with_config(config: &Table) -> Self {
+        let mut trusted_proxies = Self::new();
+        if let Some(networks) = config.get_str_array("trusted-proxies") {
+            for network in networks {
+                trusted_proxies = trusted_proxies.trust(network);
+            }
+        }
+        trusted_proxies
+    }
+
+    /// Trusts an IP address or a network in the CIDR notation.
+    pub fn trust(mut self, network: &str) -> Self {
+        match network.parse() {
+            Ok(network) => self.networks.push(network),
+            Err(err) => tracing::error!("fail to parse the network `{network}`: {err}"),
+        }
+        self
+    }
+
+    /// Returns `true` if the IP address is a trusted proxy.
+    #[inline]
+    pub fn is_trusted(&self, ip: IpAddr) -> bool {
+        self.networks.iter().any(|network| network.contains(ip))
+    }
+
+    /// Resolves the client IP of the request.
+    pub fn client_ip<Ctx: RequestContext>(&self, ctx: &Ctx) -> Option<IpAddr> {
+        let peer_ip = ctx.peer_ip()?;
+        let forwarded_for = ctx.get_header("x-forwarded-for");
+        let real_ip = ctx.get_header("x-real-ip");
+        Some(self.resolve(peer_ip, forwarded_for, real_ip))
+    }
+
+    /// Resolves the client IP from the socket peer and the forwarded headers.
+    fn resolve(
+        &self,
+        peer_ip: IpAddr,
+        forwarded_for: Option<&str>,
+        real_ip: Option<&str>,
+    ) -> IpAddr {
+        if !self.is_trusted(peer_ip) {
+            return peer_ip;
+        }
+
+        let mut client_ip = peer_ip;
+        if let Some(forwarded_for) = forwarded_for {
+            for ip in forwarded_for.rsplit(',') {
+                let Ok(ip) = ip.trim().parse() else {
+                    // The remaining addresses can not be trusted.
+                    return client_ip;
+                };
+                client_ip = ip;
+                if !self.is_trusted(ip) {
+                    return ip;
+                }
+            }
+        } else if let Some(ip) = real_ip.and_then(|s| s.trim().parse().ok()) {
+            client_ip = ip;
+        }
+        client_ip
+    }
+}
+
+/// An IP network in the CIDR notation.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct IpNetwork {
+    /// Network address.
+    addr: IpAddr,
+    /// Length of the prefix.
+    prefix_len: u8,
+}
+
+impl IpNetwork {
+    /// Returns `true` if the network contains the IP address.
+    pub(crate) fn contains(&self, ip: IpAddr) -> bool {
+        let ip = match ip {
+            IpAddr::V6(ip) => ip
+                .to_ipv4_mapped()
+                .map(IpAddr::V4)
+                .unwrap_or(IpAddr::V6(ip)),
+            ip => ip,
+        };
+        match (self.addr, ip) {
+            (IpAddr::V4(addr), IpAddr::V4(ip)) => {
+                let mask = u32::MAX.checked_shl(32 - u32::from(self.prefix_len));
+                let mask = mask.unwrap_or_default();
+                u32::from(addr) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(addr), IpAddr::V6(ip)) => {
+                let mask = u128::MAX.checked_shl(128 - u32::from(self.prefix_len));
+                let mask = mask.unwrap_or_default();
+                u128::from(addr) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+impl std::str::FromStr for IpNetwork {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (addr, prefix_len) = match s.split_once('/') {
+            Some((addr, prefix_len)) => (addr.parse::<IpAddr>()?, Some(prefix_len.parse::<u8>()?)),
+            None => (s.parse::<IpAddr>()?, None),
+        };
+        let max_prefix_len = if addr.is_ipv4() { 32 } else { 128 };
+        let prefix_len = prefix_len.unwrap_or(max_prefix_len);
+        if prefix_len > max_prefix_len {
+            return Err(Error::new(format!(
+                "prefix length `{prefix_len}` should be no more than {max_prefix_len}"
+            )));
+        }
+        Ok(Self { addr, prefix_len })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TrustedProxies;
+    use std::net::IpAddr;
+
+    #[test]
+    fn it_resolves_client_ip() {
+        let ip = |s: &str| s.parse::<IpAddr>().expect("fail to parse the value");
+        let spoofed = Some("1.2.3.4, 5.6.7.8");
+
+        // The forwarded headers are ignored if the peer is not a trusted proxy.
+        let trusted_proxies = TrustedProxies::new();
+        assert_eq!(
+            trusted_proxies.resolve(ip("8.8.8.8"), spoofed, Some("1.2.3.4")),
+            ip("8.8.8.8")
+        );
+
+        let trusted_proxies = TrustedProxies::new().trust("10.0.0.0/8");
+        assert_eq!(
+            trusted_proxies.resolve(ip("8.8.8.8"), spoofed, None),
+            ip("8.8.8.8")
+        );
+        assert_eq!(
+            trusted_proxies.resolve(ip("10.0.0.1"), Some("1.2.3.4, 5.6.7.8, 10.0.0.2"), None),
+            ip("5.6.7.8")
+        );
+        assert_eq!(
+            trusted_proxies.resolve(ip("10.0.0.1"), Some("10.0.0.3, 10.0.0.2"), None),
+            ip("10.0.0.3")
+        );
+        assert_eq!(
+            trusted_proxies.resolve(ip("10.0.0.1"), Some("1.2.3.4, unknown, 10.0.0.2"), None),
+            ip("10.0.0.2")
+        );
+        assert_eq!(
+            trusted_proxies.resolve(ip("10.0.0.1"), None, Some("5.6.7.8")),
+            ip("5.6.7.8")
+        );
+        assert_eq!(
+            trusted_proxies.resolve(ip("10.0.0.1"), None, None),
+            ip("10.0.0.1")
+        );
+    }
+}
@@ -12,6 +12,9 @@ use zino_core::{
 };
 use zino_derive::{DecodeRow, Entity, ModelAccessor, Schema};
 
+#[cfg(feature = "embedding")]
+use zino_ai::ModelEmbedding;
+
 #[cfg(feature = "tags")]
 use crate::tag::Tag;
 
@@ -31,7 +34,7 @@ pub struct Dataset {
     // Basic fields.
     #[schema(read_only)]
     id: Uuid,
-    #[schema(not_null)]
+    #[schema(not_null, embedding)]
     name: String,
     #[cfg(feature = "namespace")]
     #[schema(default_value = "Dataset::model_namespace", index_type = "hash")]
@@ -41,6 +44,7 @@ pub struct Dataset {
     visibility: String,
     #[schema(default_value = "Active", index_type = "hash")]
     status: String,
+    #[schema(embedding)]
     description: String,
 
     // Info fields.
@@ -124,6 +128,9 @@ impl Model for Dataset {
 }
 
 impl ModelHooks for Dataset {
+    #[cfg(feature = "embedding")]
+    type Data = ModelEmbedding;
+    #[cfg(not(feature = "embedding"))]
     type Data = ();
     #[cfg(feature = "maintainer-id")]
     type Extension = UserSession<Uuid, String>;
@@ -148,4 +155,7 @@ impl ModelHooks for Dataset {
         }
         Ok(())
     }
+
+    #[cfg(feature = "embedding")]
+    embedding_hooks!();
 }
@@ -4,6 +4,42 @@
 #![doc(html_logo_url = "https://zino.cc/assets/zino-logo.svg")]
 #![allow(async_fn_in_trait)]
 
+/// Implements the model hooks which keep the vector index of the semantic search
+/// in sync with the table, where the `Data` type of the hooks should be `ModelEmbedding`.
+#[cfg(feature = "embedding")]
+macro_rules! embedding_hooks {
+    () => {
+        #[inline]
+        async fn before_save(&mut self) -> Result<Self::Data, zino_core::error::Error> {
+            zino_ai::SemanticSearch::prepare_embedding(self).await
+        }
+
+        #[inline]
+        async fn after_save(
+            ctx: &zino_core::model::QueryContext,
+            data: Self::Data,
+        ) -> Result<(), zino_core::error::Error> {
+            <Self as zino_ai::SemanticSearch>::index_saved_embedding(ctx, data)
+        }
+
+        async fn after_delete(
+            self,
+            ctx: &zino_core::model::QueryContext,
+            _data: Self::Data,
+        ) -> Result<(), zino_core::error::Error> {
+            let query = ctx.query();
+            let query_id = ctx.query_id().to_string();
+            if ctx.is_success() {
+                zino_ai::SemanticSearch::remove_deleted_embedding(&self, ctx);
+                tracing::warn!(query, query_id, "a model was deleted from the table");
+            } else {
+                tracing::error!(query, query_id, "fail to delete a model from the table");
+            }
+            Ok(())
+        }
+    };
+}
+
 pub mod group;
 pub mod policy;
 pub mod resource;
@@ -12,6 +12,9 @@ use zino_core::{
 };
 use zino_derive::{DecodeRow, Entity, ModelAccessor, Schema};
 
+#[cfg(feature = "embedding")]
+use zino_ai::ModelEmbedding;
+
 #[cfg(feature = "tags")]
 use crate::tag::Tag;
 
@@ -28,7 +31,7 @@ pub struct Message {
     // Basic fields.
     #[schema(read_only)]
     id: Uuid,
-    #[schema(not_null)]
+    #[schema(not_null, embedding)]
     name: String,
     #[cfg(feature = "namespace")]
     #[schema(default_value = "Message::model_namespace", index_type = "hash")]
@@ -38,6 +41,7 @@ pub struct Message {
     visibility: String,
     #[schema(default_value = "Active", index_type = "hash")]
     status: String,
+    #[schema(embedding)]
     description: String,
 
     // Info fields.
@@ -48,6 +52,7 @@ pub struct Message {
     #[schema(reference = "Group")]
     consumer_id: Option<Uuid>, // group.id
 
+    #[schema(embedding)]
     message: String,
     #[cfg(feature = "tags")]
     #[schema(reference = "Tag", index_type = "gin")]
@@ -123,6 +128,9 @@ impl Model for Message {
 }
 
 impl ModelHooks for Message {
+    #[cfg(feature = "embedding")]
+    type Data = ModelEmbedding;
+    #[cfg(not(feature = "embedding"))]
     type Data = ();
     #[cfg(feature = "maintainer-id")]
     type Extension = UserSession<Uuid, String>;
@@ -147,4 +155,7 @@ impl ModelHooks for Message {
         }
         Ok(())
     }
+
+    #[cfg(feature = "embedding")]
+    embedding_hooks!();
 }
@@ -11,6 +11,9 @@ use zino_core::{
 };
 use zino_derive::{DecodeRow, Entity, ModelAccessor, Schema};
 
+#[cfg(feature = "embedding")]
+use zino_ai::ModelEmbedding;
+
 #[cfg(feature = "tags")]
 use crate::tag::Tag;
 
@@ -30,7 +33,7 @@ pub struct Resource {
     // Basic fields.
     #[schema(read_only)]
     id: Uuid,
-    #[schema(not_null)]
+    #[schema(not_null, embedding)]
     name: String,
     #[cfg(feature = "namespace")]
     #[schema(default_value = "Resource::model_namespace", index_type = "hash")]
@@ -40,6 +43,7 @@ pub struct Resource {
     visibility: String,
     #[schema(default_value = "Active", index_type = "hash")]
     status: String,
+    #[schema(embedding)]
     description: String,
 
     // Info fields.
@@ -123,6 +127,9 @@ impl Model for Resource {
 }
 
 impl ModelHooks for Resource {
+    #[cfg(feature = "embedding")]
+    type Data = ModelEmbedding;
+    #[cfg(not(feature = "embedding"))]
     type Data = ();
     #[cfg(feature = "maintainer-id")]
     type Extension = UserSession<Uuid, String>;
@@ -147,4 +154,7 @@ impl ModelHooks for Resource {
         }
         Ok(())
     }
+
+    #[cfg(feature = "embedding")]
+    embedding_hooks!();
 }
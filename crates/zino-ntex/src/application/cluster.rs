@@ -1,19 +1,28 @@
-use crate::RouterConfigure;
+use crate::{Request, RouterConfigure};
+use futures::future;
 use ntex::{
+    http::Method,
     rt::{self, DefaultRuntime, System},
-    time,
+    time::{self, Seconds},
     web::{
-        self, App, HttpServer,
+        self, App, HttpServer, ServiceConfig,
         middleware::Compress,
         types::{FormConfig, JsonConfig, PayloadConfig},
     },
 };
 use ntex_files::{Files, NamedFile};
+use std::{pin::pin, sync::Arc};
 use zino_core::{
-    application::{AppType, Application, Plugin, ServerTag},
+    application::{AppType, Application, Plugin, ServerTag, Shutdown},
+    error::Error,
     extension::TomlTableExt,
     schedule::AsyncScheduler,
 };
+use zino_http::{request::RequestContext, response::Rejection};
+use zino_router::{ParamType, RouteTable};
+
+/// Framework-neutral routes.
+type NeutralRouter = zino_router::Router<crate::Request, crate::Result>;
 
 /// An HTTP server cluster.
 #[derive(Default)]
@@ -24,6 +33,26 @@ pub struct Cluster {
     default_routes: Vec<RouterConfigure>,
     /// Tagged routes.
     tagged_routes: Vec<(ServerTag, Vec<RouterConfigure>)>,
+    /// Default framework-neutral routes.
+    default_routers: Vec<NeutralRouter>,
+    /// Tagged framework-neutral routes.
+    tagged_routers: Vec<(ServerTag, NeutralRouter)>,
+}
+
+impl Cluster {
+    /// Registers the framework-neutral routes.
+    pub fn register_router(mut self, router: NeutralRouter) -> Self {
+        RouteTable::record(&router);
+        self.default_routers.push(router);
+        self
+    }
+
+    /// Registers the framework-neutral routes with a server tag.
+    pub fn register_router_with(mut self, server_tag: ServerTag, router: NeutralRouter) -> Self {
+        RouteTable::record(&router);
+        self.tagged_routers.push((server_tag, router));
+        self
+    }
 }
 
 impl Application for Cluster {
@@ -53,32 +82,56 @@ impl Application for Cluster {
         let app_env = Self::env();
         System::new("prelude", DefaultRuntime).block_on(async {
             #[cfg(feature = "orm")]
-            zino_orm::GlobalPool::connect_all().await;
+            {
+                zino_orm::GlobalPool::connect_all().await;
+                zino_orm::GlobalPool::migrate_all().await;
+                Shutdown::add_hook(
+                    "orm",
+                    Box::pin(async {
+                        zino_orm::GlobalPool::close_all().await;
+                        Ok(())
+                    }),
+                );
+            }
             Self::load().await;
             app_env.load_plugins(self.custom_plugins).await;
         });
         System::new("main", DefaultRuntime).block_on(async {
+            rt::spawn(Shutdown::listen());
+
+            #[cfg(feature = "orm")]
+            rt::spawn(zino_orm::GlobalPool::monitor_replicas());
+
             // Fixed: Move scheduler startup inside main block_on to ensure correct runtime context
             // https://github.com/ntex-rs/ntex/issues/335#issuecomment-2071498572
-            if scheduler.is_ready() {
+            let scheduler_task = scheduler.is_ready().then(|| {
                 rt::spawn(Box::pin(async move {
-                    loop {
+                    // Stops the scheduler after the current jobs on the graceful shutdown.
+                    while !Shutdown::is_triggered() {
                         scheduler.tick().await;
 
                         // Cannot use `std::thread::sleep` because it blocks the Tokio runtime.
                         if let Some(duration) = scheduler.time_till_next_job() {
-                            time::sleep(duration).await;
+                            let sleep = pin!(time::sleep(duration));
+                            future::select(sleep, Shutdown::wait()).await;
                         }
                     }
-                }));
-            }
+                    // Waits for the jobs running in the background.
+                    scheduler.tick().await;
+                }))
+            });
 
             let default_routes = self.default_routes.leak() as &'static [_];
             let tagged_routes = self.tagged_routes.leak() as &'static [_];
+            let default_routers = self.default_routers.leak() as &'static [_];
+            let tagged_routers = self.tagged_routers.leak() as &'static [_];
             let app_state = Self::shared_state();
             let app_name = Self::name();
             let app_version = Self::version();
             let app_domain = Self::domain();
+            let shutdown_timeout = Self::shutdown_timeout();
+            let shutdown_timeout_secs =
+                u16::try_from(shutdown_timeout.as_secs()).unwrap_or(u16::MAX);
             let listeners = app_state.listeners();
             let servers = listeners.into_iter().map(|listener| {
                 let server_tag = listener.0;
@@ -116,7 +169,15 @@ impl Application for Cluster {
                 }
 
                 let public_dir = Self::parse_path(public_dir);
-                HttpServer::new(async move || {
+
+                // TLS config
+                #[cfg(feature = "tls")]
+                let tls_server_config = Self::tls_config(addr).map(|tls_config| {
+                    tls_config.build_server_config().unwrap_or_else(|err| {
+                        panic!("fail to build the TLS server config for {addr}: {err}")
+                    })
+                });
+                let server = HttpServer::new(async move || {
                     let mut app = App::new();
                     if public_dir.exists() {
                         let index_file = public_dir.join("index.html");
@@ -150,26 +211,140 @@ impl Application for Cluster {
                             }
                         }
                     }
+                    for router in default_routers {
+                        app = app.configure(|cfg| configure_routes(cfg, router));
+                    }
+                    for (tag, router) in tagged_routers {
+                        if tag == &server_tag || server_tag.is_debug() {
+                            app = app.configure(|cfg| configure_routes(cfg, router));
+                        }
+                    }
 
                     app.state(FormConfig::default().limit(body_limit))
                         .state(JsonConfig::default().limit(body_limit))
                         .state(PayloadConfig::default().limit(body_limit))
                         .middleware(Compress::default())
                 })
-                .stop_runtime()
                 .disable_signals()
+                .shutdown_timeout(Seconds(shutdown_timeout_secs))
                 .server_hostname(app_domain)
                 .backlog(backlog)
-                .maxconn(max_connections)
-                .bind(addr)
-                .unwrap_or_else(|err| panic!("fail to create an HTTP server: {err}"))
-                .run()
+                .maxconn(max_connections);
+                #[cfg(feature = "tls")]
+                let server = if let Some(server_config) = tls_server_config {
+                    tracing::info!("TLS is enabled for `{addr}`");
+                    server.bind_rustls(addr, server_config)
+                } else {
+                    server.bind(addr)
+                };
+                #[cfg(not(feature = "tls"))]
+                let server = server.bind(addr);
+                let server = server
+                    .unwrap_or_else(|err| panic!("fail to create an HTTP server: {err}"))
+                    .run();
+                let server_handle = server.clone();
+                rt::spawn(async move {
+                    Shutdown::wait().await;
+                    server_handle.stop(true).await;
+                });
+                server
             });
-            for result in futures::future::join_all(servers).await {
+            for result in future::join_all(servers).await {
                 if let Err(err) = result {
                     tracing::error!("ntex server error: {err}");
                 }
             }
+            if let Some(task) = scheduler_task
+                && time::timeout(shutdown_timeout, task).await.is_err()
+            {
+                tracing::warn!("timed out to wait for the async scheduler");
+            }
+            Self::teardown().await;
+        });
+    }
+}
+
+/// Configures the framework-neutral routes for the ntex service.
+fn configure_routes(cfg: &mut ServiceConfig, router: &NeutralRouter) {
+    for route in router.routes().iter().cloned() {
+        let Ok(method) = Method::from_bytes(route.method().as_str().as_bytes()) else {
+            tracing::warn!("HTTP method `{}` is not supported by ntex", route.method());
+            continue;
+        };
+        let path = route.pattern().format_with(|name, ty| {
+            if ty == ParamType::Wildcard {
+                format!("{{{name}}}*")
+            } else {
+                format!("{{{name}}}")
+            }
+        });
+        let pattern = Arc::new(route.pattern().clone());
+        let handler = route.into_handler();
+        let route_handler = move |req: Request| {
+            let pattern = pattern.clone();
+            let handler = handler.clone();
+            async move {
+                // Typed captures are checked here since ntex does not know the types.
+                if pattern.is_typed() && pattern.matches(req.request_path()).is_none() {
+                    let err = Error::new(format!("invalid path parameters for `{pattern}`"));
+                    return Err(Rejection::not_found(err).context(&req).into());
+                }
+                handler(req).await
+            }
+        };
+        cfg.route(&path, web::method(method).to(route_handler));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{NeutralRouter, configure_routes};
+    use crate::{Request, Response};
+    use ntex::{
+        http::StatusCode,
+        rt::{DefaultRuntime, System},
+        web::{App, test},
+    };
+
+    #[test]
+    fn it_configures_neutral_routes() {
+        let router = NeutralRouter::new()
+            .get("/users/{id:u64}", |req: Request| async move {
+                let mut res = Response::new(StatusCode::OK);
+                res.set_bytes_data(
+                    req.match_info()
+                        .get("id")
+                        .expect("the path parameter should exist")
+                        .to_owned(),
+                );
+                Ok(res.into())
+            })
+            .get("/files/{*path}", |req: Request| async move {
+                let mut res = Response::new(StatusCode::OK);
+                res.set_bytes_data(
+                    req.match_info()
+                        .get("path")
+                        .expect("the path parameter should exist")
+                        .to_owned(),
+                );
+                Ok(res.into())
+            });
+        System::new("test", DefaultRuntime).block_on(async move {
+            let app = App::new().configure(|cfg| configure_routes(cfg, &router));
+            let app = test::init_service(app).await;
+
+            let req = test::TestRequest::get().uri("/users/42").to_request();
+            let res = test::call_service(&app, req).await;
+            assert_eq!(res.status(), StatusCode::OK);
+            assert_eq!(test::read_body(res).await, "42");
+
+            let req = test::TestRequest::get().uri("/users/alice").to_request();
+            let res = test::call_service(&app, req).await;
+            assert_eq!(res.status(), StatusCode::NOT_FOUND);
+
+            let req = test::TestRequest::get().uri("/files/a/b.txt").to_request();
+            let res = test::call_service(&app, req).await;
+            assert_eq!(test::read_body(res).await, "a/b.txt");
         });
     }
 }
@@ -3,10 +3,12 @@
 #![doc(html_logo_url = "https://zino.cc/assets/zino-logo.svg")]
 
 mod application;
+mod middleware;
 mod request;
 mod response;
 
 pub use application::Cluster;
+pub use middleware::{MiddlewareAdapter, MiddlewareService};
 pub use request::Extractor;
 pub use response::{NtexRejection, NtexResponse};
 
@@ -0,0 +1,82 @@
+use crate::NtexRejection;
+use ntex::{
+    SharedCfg,
+    http::header::{HeaderName, HeaderValue},
+    service::{Middleware as NtexMiddleware, Service, ServiceCtx},
+    web::{Error, ErrorRenderer, WebRequest, WebResponse},
+};
+use std::sync::Arc;
+use zino_middleware::Middleware;
+
+/// An adapter which applies a framework-neutral middleware to the `ntex` services.
+pub struct MiddlewareAdapter<M> {
+    /// The middleware.
+    middleware: Arc<M>,
+}
+
+impl<M> MiddlewareAdapter<M> {
+    /// Creates a new instance.
+    #[inline]
+    pub fn new(middleware: M) -> Self {
+        Self {
+            middleware: Arc::new(middleware),
+        }
+    }
+}
+
+impl<S, M> NtexMiddleware<S, SharedCfg> for MiddlewareAdapter<M> {
+    type Service = MiddlewareService<S, M>;
+
+    #[inline]
+    fn create(&self, service: S, _cfg: SharedCfg) -> Self::Service {
+        MiddlewareService {
+            service,
+            middleware: self.middleware.clone(),
+        }
+    }
+}
+
+/// A service which runs a framework-neutral middleware.
+pub struct MiddlewareService<S, M> {
+    /// The inner service.
+    service: S,
+    /// The middleware.
+    middleware: Arc<M>,
+}
+
+impl<S, M, Err> Service<WebRequest<Err>> for MiddlewareService<S, M>
+where
+    S: Service<WebRequest<Err>, Response = WebResponse, Error = Error>,
+    M: Middleware + 'static,
+    Err: ErrorRenderer,
+{
+    type Response = WebResponse;
+    type Error = Error;
+
+    ntex::forward_ready!(service);
+
+    async fn call(
+        &self,
+        req: WebRequest<Err>,
+        ctx: ServiceCtx<'_, Self>,
+    ) -> Result<Self::Response, Self::Error> {
+        let mut req = crate::Request::from(req);
+        let state = match self.middleware.before_request(&mut req) {
+            Ok(state) => state,
+            Err(rejection) => return Err(NtexRejection::from(rejection).into()),
+        };
+
+        let req = WebRequest::try_from(req)?;
+        let mut res = ctx.call(&self.service, req).await?;
+        let mut headers = Default::default();
+        self.middleware.after_response(state, &mut headers);
+        for (name, value) in headers {
+            if let Some(Ok(header_name)) = name.map(|name| HeaderName::try_from(name.as_str()))
+                && let Ok(header_value) = HeaderValue::try_from(value)
+            {
+                res.headers_mut().insert(header_name, header_value);
+            }
+        }
+        Ok(res)
+    }
+}
@@ -0,0 +1,3 @@
+mod adapter;
+
+pub use self::adapter::{MiddlewareAdapter, MiddlewareService};
@@ -20,6 +20,12 @@ use zino_http::{
     request::{Context, RequestContext},
     response::Rejection,
 };
+use zino_router::{RouteRequest, ThreadLocal};
+
+#[cfg(feature = "tls")]
+use ntex::tls::rustls::PeerCertChain;
+#[cfg(feature = "tls")]
+use zino_core::application::ClientCertificate;
 
 /// An HTTP request extractor.
 pub struct Extractor<T>(T, Payload);
@@ -40,6 +46,10 @@ impl<T> DerefMut for Extractor<T> {
     }
 }
 
+impl RouteRequest for Extractor<HttpRequest> {
+    type Flavor = ThreadLocal;
+}
+
 impl RequestContext for Extractor<HttpRequest> {
     type Method = Method;
     type Uri = Uri;
@@ -79,6 +89,21 @@ impl RequestContext for Extractor<HttpRequest> {
         self.connection_info().remote().and_then(|s| s.parse().ok())
     }
 
+    #[inline]
+    fn peer_ip(&self) -> Option<IpAddr> {
+        self.peer_addr().map(|socket| socket.ip())
+    }
+
+    #[cfg(feature = "tls")]
+    fn client_certificate(&self) -> Option<Arc<ClientCertificate>> {
+        let io = self.head().io.as_ref()?;
+        let cert_chain = io.query::<PeerCertChain<'static>>();
+        cert_chain
+            .as_ref()
+            .and_then(|cert_chain| ClientCertificate::from_peer_certificates(&cert_chain.0))
+            .map(Arc::new)
+    }
+
     #[inline]
     fn get_context(&self) -> Option<Arc<Context>> {
         let extensions = self.extensions();
@@ -1,12 +1,14 @@
+use futures::{StreamExt, TryStreamExt};
 use ntex::{
     http::{
         ResponseError, StatusCode,
-        body::Body,
+        body::{Body, BodyStream},
         header::{self, HeaderName, HeaderValue},
     },
+    util::Bytes,
     web::{HttpRequest, HttpResponse, Responder, WebResponseError},
 };
-use std::{error, fmt};
+use std::{error, fmt, io};
 use zino_http::{
     response::{Rejection, Response, ResponseCode},
     timing::TimingMetric,
@@ -119,13 +121,21 @@ impl WebResponseError for NtexRejection {
 
 /// Build http response from `zino_core::response::Response`.
 fn build_http_response<S: ResponseCode>(response: &mut Response<S>) -> HttpResponse {
-    match response.read_bytes() {
-        Ok(data) => {
+    let result = if let Some(stream) = response.take_stream_data() {
+        let stream = stream
+            .map_ok(|data| Bytes::from(data.to_vec()))
+            .map_err(|err| io::Error::other(err.to_string()))
+            .boxed_local();
+        Ok(Body::from_message(BodyStream::new(stream)))
+    } else {
+        response.read_bytes().map(|data| Body::from(data.to_vec()))
+    };
+    match result {
+        Ok(body) => {
             let status_code = response
                 .status_code()
                 .try_into()
                 .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
-            let body = Body::from(data.to_vec());
             let mut res = HttpResponse::with_body(status_code, body);
             if let Ok(header_value) = HeaderValue::try_from(response.content_type()) {
                 res.headers_mut().insert(header::CONTENT_TYPE, header_value);
@@ -32,9 +32,11 @@ use zino_core::{
     application::{Agent, Application},
     extension::TomlTableExt,
 };
+use zino_router::RouteTable;
 
 mod model;
 mod parser;
+mod route;
 
 pub use model::translate_model_entry;
 
@@ -95,10 +97,26 @@ fn openapi_info(title: &str, version: &str) -> Info {
 }
 
 /// Returns the default OpenAPI paths.
+/// The routes registered by [`zino_router`] are merged unless they have been defined.
 fn default_paths() -> Paths {
+    let mut paths = OPENAPI_PATHS.clone();
+    for route_info in RouteTable::routes() {
+        let path = route::parse_route_path(&route_info);
+        let http_method = parser::parse_http_method(route_info.method().as_str());
+        if let Some(item) = paths.get_mut(&path) {
+            if !route::has_operation(item, &http_method) {
+                let operation = route::parse_route_operation(&route_info);
+                item.merge_operations(PathItem::new(http_method, operation));
+            }
+        } else {
+            let operation = route::parse_route_operation(&route_info);
+            paths.insert(path, PathItem::new(http_method, operation));
+        }
+    }
+
     let mut paths_builder = PathsBuilder::new();
-    for (path, item) in OPENAPI_PATHS.iter() {
-        paths_builder = paths_builder.path(path, item.to_owned());
+    for (path, item) in paths {
+        paths_builder = paths_builder.path(path, item);
     }
     paths_builder.build()
 }
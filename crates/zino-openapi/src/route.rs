@@ -0,0 +1,104 @@
+use utoipa::openapi::{
+    Deprecated, Required,
+    path::{
+        HttpMethod, Operation, OperationBuilder, Parameter, ParameterBuilder, ParameterIn, PathItem,
+    },
+    schema::{KnownFormat, ObjectBuilder, Ref, SchemaFormat, Type},
+};
+use zino_router::{ParamType, RouteInfo};
+
+/// Returns the OpenAPI path of the route.
+pub(super) fn parse_route_path(route: &RouteInfo) -> String {
+    route.pattern().format_with(|name, _| format!("{{{name}}}"))
+}
+
+/// Parses the operation of the route.
+pub(super) fn parse_route_operation(route: &RouteInfo) -> Operation {
+    let metadata = route.metadata();
+    let mut operation_builder = OperationBuilder::new()
+        .response("default", Ref::from_response_name("default"))
+        .response("error", Ref::from_response_name("4XX"));
+    let tags = metadata.tags();
+    if !tags.is_empty() {
+        let tags = tags.iter().map(|tag| tag.to_string()).collect::<Vec<_>>();
+        operation_builder = operation_builder.tags(Some(tags));
+    } else if let Some(tag) = route
+        .pattern()
+        .to_path()
+        .split('/')
+        .find(|s| !s.is_empty() && !s.starts_with('{'))
+    {
+        operation_builder = operation_builder.tag(tag);
+    }
+    if let Some(summary) = metadata.summary() {
+        operation_builder = operation_builder.summary(Some(summary));
+    }
+
+    let roles = metadata.roles();
+    if !roles.is_empty() {
+        let roles = roles
+            .iter()
+            .map(|role| format!("`{role}`"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let description = if let Some(description) = metadata.description() {
+            format!("{description}\n\nRequired roles: {roles}")
+        } else {
+            format!("Required roles: {roles}")
+        };
+        operation_builder = operation_builder.description(Some(description));
+    } else if let Some(description) = metadata.description() {
+        operation_builder = operation_builder.description(Some(description));
+    }
+    if let Some(name) = metadata.name() {
+        operation_builder = operation_builder.operation_id(Some(name));
+    }
+    if metadata.is_deprecated() {
+        operation_builder = operation_builder.deprecated(Some(Deprecated::True));
+    }
+    for parameter in parse_route_parameters(route).into_iter() {
+        operation_builder = operation_builder.parameter(parameter);
+    }
+    operation_builder.build()
+}
+
+/// Returns `true` if the path item has an operation for the HTTP method.
+pub(super) fn has_operation(item: &PathItem, method: &HttpMethod) -> bool {
+    match method {
+        HttpMethod::Get => item.get.is_some(),
+        HttpMethod::Post => item.post.is_some(),
+        HttpMethod::Put => item.put.is_some(),
+        HttpMethod::Delete => item.delete.is_some(),
+        HttpMethod::Options => item.options.is_some(),
+        HttpMethod::Head => item.head.is_some(),
+        HttpMethod::Patch => item.patch.is_some(),
+        HttpMethod::Trace => item.trace.is_some(),
+    }
+}
+
+/// Parses the typed path parameters of the route.
+fn parse_route_parameters(route: &RouteInfo) -> Vec<Parameter> {
+    let mut parameters = Vec::new();
+    for (name, ty) in route.pattern().params() {
+        let schema = match ty {
+            ParamType::Integer => ObjectBuilder::new()
+                .schema_type(Type::Integer)
+                .format(Some(SchemaFormat::KnownFormat(KnownFormat::Int64))),
+            ParamType::UnsignedInteger => ObjectBuilder::new()
+                .schema_type(Type::Integer)
+                .format(Some(SchemaFormat::KnownFormat(KnownFormat::UInt64))),
+            ParamType::Uuid => ObjectBuilder::new()
+                .schema_type(Type::String)
+                .format(Some(SchemaFormat::KnownFormat(KnownFormat::Uuid))),
+            _ => ObjectBuilder::new().schema_type(Type::String),
+        };
+        let parameter = ParameterBuilder::new()
+            .name(name)
+            .schema(Some(schema.build()))
+            .parameter_in(ParameterIn::Path)
+            .required(Required::True)
+            .build();
+        parameters.push(parameter);
+    }
+    parameters
+}
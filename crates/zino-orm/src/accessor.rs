@@ -1,4 +1,4 @@
-use super::{IntoSqlValue, ModelHelper, Schema};
+use super::{IntoSqlValue, ModelHelper, Schema, history};
 use std::fmt::Display;
 use zino_core::{
     JsonValue, Map, bail,
@@ -86,6 +86,12 @@ where
         0
     }
 
+    /// Returns `true` if the revision history is enabled for the model.
+    #[inline]
+    fn history_enabled() -> bool {
+        false
+    }
+
     /// Returns a snapshot of the model.
     fn snapshot(&self) -> Map {
         let mut snapshot = Map::new();
@@ -378,7 +384,7 @@ where
 
         let query = model.current_version_query();
         let mut mutation = model.soft_delete_mutation();
-        let ctx = Self::update_one(&query, &mut mutation).await?;
+        let ctx = history::update_one::<K, Self>(id, &query, &mut mutation, "soft_delete").await?;
         Self::after_soft_delete(&ctx, model_data).await?;
         Ok(())
     }
@@ -390,7 +396,7 @@ where
 
         let query = model.current_version_query();
         let mut mutation = model.lock_mutation();
-        let ctx = Self::update_one(&query, &mut mutation).await?;
+        let ctx = history::update_one::<K, Self>(id, &query, &mut mutation, "lock").await?;
         Self::after_lock(&ctx, model_data).await?;
         Ok(())
     }
@@ -402,7 +408,7 @@ where
 
         let query = model.current_version_query();
         let mut mutation = model.archive_mutation();
-        let ctx = Self::update_one(&query, &mut mutation).await?;
+        let ctx = history::update_one::<K, Self>(id, &query, &mut mutation, "archive").await?;
         Self::after_archive(&ctx, model_data).await?;
         Ok(())
     }
@@ -450,7 +456,7 @@ where
         let mut mutation = model.next_version_mutation(data);
 
         let model_data = model.before_update().await?;
-        let ctx = Self::update_one(&query, &mut mutation).await?;
+        let ctx = history::update_one::<K, Self>(id, &query, &mut mutation, "update").await?;
         if ctx.rows_affected() != Some(1) {
             bail!(
                 "404 Not Found: there is no version `{}` for the model `{}`",
@@ -462,6 +468,103 @@ where
         Ok((validation, model))
     }
 
+    /// Lists the revisions of a model of the primary key in the descending order of versions.
+    async fn list_revisions(id: &K) -> Result<Vec<Map>, Error> {
+        if !Self::history_enabled() {
+            bail!(
+                "404 Not Found: there is no revision history for the model `{}`",
+                Self::model_name()
+            );
+        }
+        history::list_revisions::<Self>(id, None).await
+    }
+
+    /// Computes the diff between two versions of a model of the primary key.
+    async fn diff_versions(id: &K, from_version: u64, to_version: u64) -> Result<Map, Error> {
+        if !Self::history_enabled() {
+            bail!(
+                "404 Not Found: there is no revision history for the model `{}`",
+                Self::model_name()
+            );
+        }
+
+        let mut snapshots = Vec::with_capacity(2);
+        for version in [from_version, to_version] {
+            let snapshot = history::fetch_version::<Self>(id, version)
+                .await?
+                .ok_or_else(|| {
+                    warn!(
+                        "404 Not Found: there is no version `{}` for the model `{}`",
+                        version, id
+                    )
+                })?;
+            snapshots.push(snapshot);
+        }
+        Ok(history::diff_snapshots(&snapshots[0], &snapshots[1]))
+    }
+
+    /// Restores a model of the primary key to the specific version
+    /// and returns the restored model.
+    async fn restore_by_id(id: &K, version: u64) -> Result<Self, Error> {
+        if !Self::history_enabled() {
+            bail!(
+                "404 Not Found: there is no revision history for the model `{}`",
+                Self::model_name()
+            );
+        }
+
+        let mut model = Self::try_get_model(id).await?;
+        if model.version() == version {
+            bail!(
+                "409 Conflict: the model `{}` is already at version `{}`",
+                id,
+                version
+            );
+        } else if model.is_archived() {
+            bail!("403 Forbidden: archived model `{}` can not be restored", id);
+        }
+
+        let mut data = history::fetch_version::<Self>(id, version)
+            .await?
+            .ok_or_else(|| {
+                warn!(
+                    "404 Not Found: there is no version `{}` for the model `{}`",
+                    version, id
+                )
+            })?;
+        let primary_key_name = Self::primary_key_name();
+        data.retain(|key, _value| {
+            key != primary_key_name && !matches!(key.as_str(), "updated_at" | "version")
+        });
+
+        let query = model.current_version_query();
+        let mut mutation = model.next_version_mutation(&mut data);
+
+        let model_data = model.before_update().await?;
+        let ctx = history::update_one::<K, Self>(id, &query, &mut mutation, "restore").await?;
+        if ctx.rows_affected() != Some(1) {
+            bail!(
+                "404 Not Found: there is no version `{}` for the model `{}`",
+                model.version(),
+                id,
+            );
+        }
+        Self::after_update(&ctx, model_data).await?;
+
+        // Returns the restored row from the writer instead of the prior model.
+        let mut data = history::fetch_current_row::<Self>(id)
+            .await?
+            .ok_or_else(|| warn!("404 Not Found: cannot find the model `{}`", id))?;
+        Self::after_decode(&mut data).await?;
+        Self::try_from_map(data).map_err(|err| {
+            warn!(
+                "fail to decode the value as a model `{}`: {}",
+                Self::model_name(),
+                err
+            )
+        })
+    }
+
     /// Generates random associations for the model.
     async fn random_associations() -> Result<Map, Error> {
         let mut associations = Map::new();
@@ -1,6 +1,5 @@
 use self::Aggregation::*;
-use super::{Entity, query::QueryExt};
-use zino_core::model::Query;
+use super::{Dialect, Entity};
 
 /// SQL aggregate expressions.
 ///
@@ -48,10 +47,11 @@ pub enum Aggregation<E: Entity> {
 impl<E: Entity> Aggregation<E> {
     /// Returns the SQL expression.
     pub(super) fn expr(&self) -> String {
+        let dialect = E::dialect();
         match self {
             Count(col, distinct) => {
                 let col_name = E::format_column(col);
-                let field = Query::format_field(&col_name);
+                let field = dialect.format_field(&col_name);
                 if *distinct {
                     format!("count(distinct {field})")
                 } else {
@@ -60,44 +60,40 @@ impl<E: Entity> Aggregation<E> {
             }
             Sum(col) => {
                 let col_name = E::format_column(col);
-                let field = Query::format_field(&col_name);
+                let field = dialect.format_field(&col_name);
                 format!("sum({field})")
             }
             Avg(col) => {
                 let col_name = E::format_column(col);
-                let field = Query::format_field(&col_name);
+                let field = dialect.format_field(&col_name);
                 format!("avg({field})")
             }
             Min(col) => {
                 let col_name = E::format_column(col);
-                let field = Query::format_field(&col_name);
+                let field = dialect.format_field(&col_name);
                 format!("min({field})")
             }
             Max(col) => {
                 let col_name = E::format_column(col);
-                let field = Query::format_field(&col_name);
+                let field = dialect.format_field(&col_name);
                 format!("max({field})")
             }
             Stddev(col) => {
                 let col_name = E::format_column(col);
-                let field = Query::format_field(&col_name);
+                let field = dialect.format_field(&col_name);
                 format!("stddev({field})")
             }
             Variance(col) => {
                 let col_name = E::format_column(col);
-                let field = Query::format_field(&col_name);
+                let field = dialect.format_field(&col_name);
                 format!("variance({field})")
             }
             JsonArrayagg(col) => {
                 let col_name = E::format_column(col);
-                let field = Query::format_field(&col_name);
-                if cfg!(any(
-                    feature = "orm-mariadb",
-                    feature = "orm-mysql",
-                    feature = "orm-tidb"
-                )) {
+                let field = dialect.format_field(&col_name);
+                if dialect.is_mysql_compatible() {
                     format!("json_arrayagg({field})")
-                } else if cfg!(feature = "orm-postgres") {
+                } else if dialect == Dialect::Postgres {
                     format!("jsonb_agg({field})")
                 } else {
                     format!("json_group_array({field})")
@@ -106,15 +102,11 @@ impl<E: Entity> Aggregation<E> {
             JsonObjectagg(key_col, val_col) => {
                 let key_col_name = E::format_column(key_col);
                 let val_col_name = E::format_column(val_col);
-                let key_field = Query::format_field(&key_col_name);
-                let val_field = Query::format_field(&val_col_name);
-                if cfg!(any(
-                    feature = "orm-mariadb",
-                    feature = "orm-mysql",
-                    feature = "orm-tidb"
-                )) {
+                let key_field = dialect.format_field(&key_col_name);
+                let val_field = dialect.format_field(&val_col_name);
+                if dialect.is_mysql_compatible() {
                     format!("json_objectagg({key_field}, {val_field})")
-                } else if cfg!(feature = "orm-postgres") {
+                } else if dialect == Dialect::Postgres {
                     format!("jsonb_object_agg({key_field}, {val_field})")
                 } else {
                     format!("json_group_object({key_field}, {val_field})")
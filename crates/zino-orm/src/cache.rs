@@ -0,0 +1,198 @@
+//! Query result cache for the models.
+//!
+//! When the query cache is enabled for a model via `#[schema(query_cache_ttl = "30s")]`,
+//! the results of [`Schema::find_by_id`] and [`Schema::count`] are cached in process,
+//! keyed on the formatted SQL and arguments of the [`QueryContext`].
+//! All the entries of a model are invalidated whenever the writes through [`Schema`]
+//! or [`Transaction`](crate::Transaction) helpers are executed for the table.
+//!
+//! Only the decoded types supporting [`DecodeRow::decode_cached`], such as `Map`
+//! and the types deriving `DecodeRow`, can be restored from the cache.
+//! The writes executed by raw SQL outside of the model
+//! should be followed by [`QueryCache::invalidate`] explicitly.
+
+use super::Schema;
+use serde::{Serialize, de::DeserializeOwned};
+use std::{
+    collections::HashMap,
+    sync::RwLock,
+    time::{Duration, Instant},
+};
+use zino_core::{JsonValue, LazyLock, model::QueryContext};
+
+/// Maximum number of the cached entries for a table.
+const MAX_ENTRIES: usize = 10000;
+
+/// In-process query cache for the models.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct QueryCache;
+
+impl QueryCache {
+    /// Invalidates all the cached entries of the model.
+    #[inline]
+    pub fn invalidate<M: Schema>() {
+        Self::invalidate_table(M::table_name());
+    }
+
+    /// Invalidates all the cached entries of the table.
+    pub fn invalidate_table(table_name: &str) {
+        if let Ok(mut tables) = QUERY_CACHE.write()
+            && let Some(table) = tables.get_mut(table_name)
+        {
+            table.generation += 1;
+            table.entries.clear();
+        }
+    }
+
+    /// Clears the cached entries of all the tables.
+    pub fn clear() {
+        if let Ok(mut tables) = QUERY_CACHE.write() {
+            for table in tables.values_mut() {
+                table.generation += 1;
+                table.entries.clear();
+            }
+        }
+    }
+
+    /// Returns the number of cached entries of the model.
+    pub fn len<M: Schema>() -> usize {
+        QUERY_CACHE
+            .read()
+            .ok()
+            .and_then(|tables| tables.get(M::table_name()).map(|t| t.entries.len()))
+            .unwrap_or_default()
+    }
+
+    /// Encodes the data as a value to be stored in the cache.
+    /// It is used by `#[derive(DecodeRow)]` to implement
+    /// [`DecodeRow::encode_cached`](crate::DecodeRow::encode_cached).
+    #[inline]
+    pub fn encode<T: Serialize>(data: &T) -> Option<JsonValue> {
+        serde_json::to_value(data).ok()
+    }
+
+    /// Decodes the data from a value in the cache.
+    /// It is used by `#[derive(DecodeRow)]` to implement
+    /// [`DecodeRow::decode_cached`](crate::DecodeRow::decode_cached).
+    #[inline]
+    pub fn decode<T: DeserializeOwned>(value: JsonValue) -> Option<T> {
+        serde_json::from_value(value).ok()
+    }
+}
+
+/// Cached entries of a table.
+#[derive(Debug, Default)]
+struct TableCache {
+    /// Generation of the entries, which is incremented on each invalidation.
+    generation: u64,
+    /// Cached values with the expiration time.
+    entries: HashMap<String, (JsonValue, Instant)>,
+}
+
+/// Returns the cache key for the query context.
+pub(super) fn cache_key(ctx: &QueryContext) -> String {
+    if let Some(arguments) = ctx.format_arguments() {
+        format!("{} -- [{arguments}]", ctx.query())
+    } else {
+        ctx.query().to_owned()
+    }
+}
+
+/// Returns the current generation of the table,
+/// which should be obtained before the query is executed.
+pub(super) fn generation(table_name: &'static str) -> u64 {
+    if let Some(generation) = QUERY_CACHE
+        .read()
+        .ok()
+        .and_then(|tables| tables.get(table_name).map(|t| t.generation))
+    {
+        return generation;
+    }
+    QUERY_CACHE
+        .write()
+        .map(|mut tables| tables.entry(table_name).or_default().generation)
+        .unwrap_or_default()
+}
+
+/// Returns a cloned value of the key if it has not expired.
+pub(super) fn get(table_name: &str, key: &str) -> Option<JsonValue> {
+    let tables = QUERY_CACHE.read().ok()?;
+    let (value, expires_at) = tables.get(table_name)?.entries.get(key)?;
+    (*expires_at > Instant::now()).then(|| value.clone())
+}
+
+/// Puts a value into the cache if the table has not been invalidated
+/// since the given generation was obtained.
+pub(super) fn put(
+    table_name: &'static str,
+    generation: u64,
+    key: String,
+    value: JsonValue,
+    ttl: Duration,
+) {
+    if let Ok(mut tables) = QUERY_CACHE.write() {
+        let table = tables.entry(table_name).or_default();
+        if table.generation != generation {
+            return;
+        }
+
+        let now = Instant::now();
+        if table.entries.len() >= MAX_ENTRIES {
+            table.entries.retain(|_, (_, expires_at)| *expires_at > now);
+        }
+        if table.entries.len() < MAX_ENTRIES {
+            table.entries.insert(key, (value, now + ttl));
+        }
+    }
+}
+
+/// Global query cache keyed by the table name.
+static QUERY_CACHE: LazyLock<RwLock<HashMap<&'static str, TableCache>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+
+#[cfg(test)]
+mod tests {
+    use super::QueryCache;
+    use crate::fixture::Group;
+    use std::time::Duration;
+
+    #[test]
+    fn it_encodes_cached_models() {
+        let group = Group {
+            id: 1,
+            name: "admin".to_owned(),
+            parent_id: Some(2),
+            ..Group::default()
+        };
+        let value = QueryCache::encode(&group).expect("the model should be encoded");
+        let cached = QueryCache::decode::<Group>(value).expect("the model should be decoded");
+        assert_eq!(cached.name, "admin");
+        assert_eq!(cached.parent_id, Some(2));
+        assert!(QueryCache::decode::<Group>("admin".into()).is_none());
+    }
+
+    #[test]
+    fn it_discards_stale_results() {
+        let table_name = "test_query_cache";
+        let ttl = Duration::from_secs(60);
+        let generation = super::generation(table_name);
+        super::put(table_name, generation, "a".to_owned(), 1.into(), ttl);
+        assert_eq!(super::get(table_name, "a"), Some(1.into()));
+
+        // A read started before the invalidation should not populate the cache.
+        super::QueryCache::invalidate_table(table_name);
+        assert_eq!(super::get(table_name, "a"), None);
+        super::put(table_name, generation, "a".to_owned(), 1.into(), ttl);
+        assert_eq!(super::get(table_name, "a"), None);
+
+        let generation = super::generation(table_name);
+        super::put(
+            table_name,
+            generation,
+            "b".to_owned(),
+            2.into(),
+            Duration::ZERO,
+        );
+        assert_eq!(super::get(table_name, "b"), None);
+    }
+}
@@ -1,45 +1,66 @@
-use super::query::QueryExt;
+use super::{
+    Dialect,
+    driver::{DriverExt, with_driver},
+};
 use convert_case::{Case, Casing};
 use std::borrow::Cow;
-use zino_core::{
-    JsonValue,
-    extension::JsonObjectExt,
-    model::{Column, Query},
-};
+use zino_core::{JsonValue, extension::JsonObjectExt, model::Column};
 
 /// Encoding a column to be sent to the database.
-pub trait EncodeColumn<DB> {
-    /// Returns the corresponding column type in the database.
-    fn column_type(&self) -> &str;
+pub trait EncodeColumn {
+    /// Returns the corresponding column type in the database of the SQL dialect.
+    fn column_type(&self, dialect: Dialect) -> &str;
 
     /// Encodes a json value as a column value represented by a str.
-    fn encode_value<'a>(&self, value: Option<&'a JsonValue>) -> Cow<'a, str>;
+    fn encode_value<'a>(&self, dialect: Dialect, value: Option<&'a JsonValue>) -> Cow<'a, str>;
 
     /// Formats a string value for the column.
-    fn format_value<'a>(&self, value: &'a str) -> Cow<'a, str>;
+    fn format_value<'a>(&self, dialect: Dialect, value: &'a str) -> Cow<'a, str>;
 
     /// Formats a column filter.
-    fn format_filter(&self, key: &str, value: &JsonValue) -> String;
+    fn format_filter(&self, dialect: Dialect, key: &str, value: &JsonValue) -> String;
+}
+
+impl EncodeColumn for Column<'_> {
+    #[inline]
+    fn column_type(&self, dialect: Dialect) -> &str {
+        with_driver!(dialect, D => D::column_type(self, dialect))
+    }
+
+    #[inline]
+    fn encode_value<'a>(&self, dialect: Dialect, value: Option<&'a JsonValue>) -> Cow<'a, str> {
+        with_driver!(dialect, D => D::encode_value(self, dialect, value))
+    }
+
+    #[inline]
+    fn format_value<'a>(&self, dialect: Dialect, value: &'a str) -> Cow<'a, str> {
+        with_driver!(dialect, D => D::format_value(self, dialect, value))
+    }
+
+    #[inline]
+    fn format_filter(&self, dialect: Dialect, key: &str, value: &JsonValue) -> String {
+        with_driver!(dialect, D => D::format_filter(self, dialect, key, value))
+    }
 }
 
 /// Extension trait for [`Column`].
 pub(super) trait ColumnExt {
     /// Returns `true` if it is compatible with the given data type.
-    fn is_compatible(&self, data_type: &str) -> bool;
+    fn is_compatible(&self, dialect: Dialect, data_type: &str) -> bool;
 
     /// Returns the type annotation.
-    fn type_annotation(&self) -> &'static str;
+    fn type_annotation(&self, dialect: Dialect) -> &'static str;
 
     /// Returns the field definition.
-    fn field_definition(&self, primary_key_name: &str) -> String;
+    fn field_definition(&self, dialect: Dialect, primary_key_name: &str) -> String;
 
     /// Returns the constraints.
-    fn constraints(&self) -> Vec<String>;
+    fn constraints(&self, dialect: Dialect) -> Vec<String>;
 }
 
 impl ColumnExt for Column<'_> {
-    fn is_compatible(&self, data_type: &str) -> bool {
-        let column_type = self.column_type();
+    fn is_compatible(&self, dialect: Dialect, data_type: &str) -> bool {
+        let column_type = self.column_type(dialect);
         if column_type.eq_ignore_ascii_case(data_type) {
             return true;
         }
@@ -52,7 +73,7 @@ impl ColumnExt for Column<'_> {
             "BIGINT UNSIGNED" | "BIGSERIAL" => data_type == "BIGINT",
             "TEXT" => data_type == "VARCHAR",
             _ => {
-                if cfg!(feature = "orm-postgres") && column_type.ends_with("[]") {
+                if dialect == Dialect::Postgres && column_type.ends_with("[]") {
                     data_type == "ARRAY"
                 } else if column_type.starts_with("TIMESTAMP") {
                     data_type.starts_with("TIMESTAMP")
@@ -68,9 +89,9 @@ impl ColumnExt for Column<'_> {
         }
     }
 
-    fn type_annotation(&self) -> &'static str {
-        if cfg!(feature = "orm-postgres") {
-            match self.column_type() {
+    fn type_annotation(&self, dialect: Dialect) -> &'static str {
+        if dialect == Dialect::Postgres {
+            match self.column_type(dialect) {
                 "UUID" => "::UUID",
                 "BIGINT" | "BIGSERIAL" => "::BIGINT",
                 "INT" | "SERIAL" => "::INT",
@@ -82,24 +103,20 @@ impl ColumnExt for Column<'_> {
         }
     }
 
-    fn field_definition(&self, primary_key_name: &str) -> String {
+    fn field_definition(&self, dialect: Dialect, primary_key_name: &str) -> String {
         let column_name = self
             .extra()
             .get_str("column_name")
             .unwrap_or_else(|| self.name());
-        let column_field = Query::format_field(column_name);
-        let column_type = self.column_type();
+        let column_field = dialect.format_field(column_name);
+        let column_type = self.column_type(dialect);
         let mut definition = format!("{column_field} {column_type}");
         if column_name == primary_key_name {
             definition += " PRIMARY KEY";
         }
         if let Some(value) = self.default_value() {
             if self.auto_increment() {
-                definition += if cfg!(any(
-                    feature = "orm-mariadb",
-                    feature = "orm-mysql",
-                    feature = "orm-tidb"
-                )) {
+                definition += if dialect.is_mysql_compatible() {
                     " AUTO_INCREMENT"
                 } else {
                     // PostgreSQL does not support `AUTO INCREMENT` and SQLite does not need it.
@@ -107,14 +124,14 @@ impl ColumnExt for Column<'_> {
                 };
             } else if self.auto_random() {
                 // Only TiDB supports this feature.
-                definition += if cfg!(feature = "orm-tidb") {
+                definition += if dialect == Dialect::TiDb {
                     " AUTO_RANDOM"
                 } else {
                     ""
                 };
             } else {
-                let value = self.format_value(value);
-                if cfg!(feature = "orm-sqlite") && value.contains('(') {
+                let value = self.format_value(dialect, value);
+                if dialect == Dialect::Sqlite && value.contains('(') {
                     definition = format!("{definition} DEFAULT ({value})");
                 } else {
                     definition = format!("{definition} DEFAULT {value}");
@@ -123,18 +140,15 @@ impl ColumnExt for Column<'_> {
         } else if self.is_not_null() {
             definition += " NOT NULL";
         }
-        if cfg!(any(
-            feature = "orm-mariadb",
-            feature = "orm-mysql",
-            feature = "orm-tidb"
-        )) && let Some(comment) = self.comment()
+        if dialect.is_mysql_compatible()
+            && let Some(comment) = self.comment()
         {
             definition = format!("{definition} COMMENT '{comment}'");
         }
         definition
     }
 
-    fn constraints(&self) -> Vec<String> {
+    fn constraints(&self, dialect: Dialect) -> Vec<String> {
         let mut constraints = Vec::new();
         let extra = self.extra();
         let column_name = self
@@ -145,9 +159,9 @@ impl ColumnExt for Column<'_> {
             .reference()
             .filter(|_| extra.contains_key("foreign_key"))
         {
-            let column_field = Query::format_field(column_name);
-            let parent_table = Query::format_field(reference.name());
-            let parent_column_field = Query::format_field(reference.column_name());
+            let column_field = dialect.format_field(column_name);
+            let parent_table = dialect.format_field(reference.name());
+            let parent_column_field = dialect.format_field(reference.column_name());
             let mut constraint = format!(
                 "FOREIGN KEY ({column_field}) REFERENCES {parent_table}({parent_column_field})"
             );
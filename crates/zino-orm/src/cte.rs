@@ -0,0 +1,248 @@
+use super::{Schema, query::QueryExt};
+use zino_core::{JsonValue, Map, extension::JsonObjectExt, model::Query};
+
+/// A common table expression (CTE) which can be referenced by name in the query.
+///
+/// # Examples
+/// ```rust,ignore
+/// use crate::model::{Group, GroupColumn};
+/// use zino_orm::{QueryBuilder, Schema};
+///
+/// // Fetches the group and all of its descendants in a single round trip
+/// let anchor = QueryBuilder::<Group>::new().primary_key(group_id);
+/// let recursive = QueryBuilder::<Group>::new().and_ne(GroupColumn::Status, "Deleted");
+/// let query = QueryBuilder::<Group>::new()
+///     .recursive_cte(
+///         "group_tree",
+///         anchor,
+///         recursive,
+///         (GroupColumn::ParentId, GroupColumn::Id),
+///     )
+///     .table_name("group_tree".to_owned())
+///     .build();
+/// let groups: Vec<Group> = Group::find(&query).await?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct CommonTableExpr {
+    /// The CTE name.
+    name: String,
+    /// The query expression.
+    expr: String,
+    /// A flag to indicate whether the CTE is recursive.
+    recursive: bool,
+}
+
+impl CommonTableExpr {
+    /// Creates a new instance for the rows selected by the query.
+    pub fn new<M: Schema>(name: impl Into<String>, query: &Query) -> Self {
+        let table_name = query.format_table_name::<M>();
+        let projection = query.format_table_fields::<M>();
+        let filters = query.format_filters::<M>();
+        let sort = query.format_sort::<M>();
+        let pagination = query.format_pagination();
+        let expr = format!("SELECT {projection} FROM {table_name} {filters} {sort} {pagination}");
+        Self {
+            name: name.into(),
+            expr,
+            recursive: false,
+        }
+    }
+
+    /// Creates a recursive CTE which starts with the rows selected by the `anchor` query,
+    /// and appends the rows selected by the `recursive` query repeatedly
+    /// whose `column` is equal to the `cte_column` of the rows produced in the last iteration.
+    ///
+    /// The `column` and the top-level filters of the `recursive` query are qualified
+    /// with the model name if they are not, since the CTE may have the same columns.
+    /// The sort order and the pagination of both queries are ignored.
+    /// The hierarchy should be acyclic since the rows are combined with `UNION ALL`.
+    pub fn recursive<M: Schema>(
+        name: impl Into<String>,
+        anchor: &Query,
+        recursive: &Query,
+        column: &str,
+        cte_column: &str,
+    ) -> Self {
+        let name = name.into();
+        let anchor_expr = {
+            let table_name = anchor.format_table_name::<M>();
+            let projection = anchor.format_table_fields::<M>();
+            let filters = anchor.format_filters::<M>();
+            format!("SELECT {projection} FROM {table_name} {filters}")
+        };
+        let recursive_expr = {
+            let dialect = M::dialect();
+            let model_name = M::model_name();
+            let mut recursive = recursive.clone();
+            let unqualified_keys = recursive
+                .filters()
+                .keys()
+                .filter(|key| !key.starts_with('$') && !key.contains('.'))
+                .cloned()
+                .collect::<Vec<_>>();
+            for key in unqualified_keys {
+                if let Some(value) = recursive.remove_filter(&key) {
+                    recursive.add_filter([model_name, ".", &key].concat(), value);
+                }
+            }
+
+            let table_name = recursive.format_table_name::<M>();
+            let projection = recursive.format_table_fields::<M>();
+            let projection = if projection == "*" {
+                let model_name = dialect.escape_table_name(model_name);
+                format!("{model_name}.*").into()
+            } else {
+                projection
+            };
+            let filters = recursive.format_filters::<M>();
+            let cte_name = dialect.escape_table_name(&name);
+            let column = if column.contains('.') {
+                column.to_owned()
+            } else {
+                [model_name, ".", column].concat()
+            };
+            let column = dialect.format_field(&column);
+            let cte_column = dialect.format_field(cte_column);
+            format!(
+                "SELECT {projection} FROM {table_name} \
+                    INNER JOIN {cte_name} ON {column} = {cte_name}.{cte_column} {filters}"
+            )
+        };
+        Self {
+            name,
+            expr: format!("{anchor_expr} UNION ALL {recursive_expr}"),
+            recursive: true,
+        }
+    }
+
+    /// Returns the CTE name.
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the query expression.
+    #[inline]
+    pub fn expr(&self) -> &str {
+        &self.expr
+    }
+
+    /// Returns `true` if the CTE is recursive.
+    #[inline]
+    pub fn is_recursive(&self) -> bool {
+        self.recursive
+    }
+
+    /// Attaches the CTE to the query so that it can be referenced by name,
+    /// usually as the table name of the query.
+    pub fn attach_to(self, query: &mut Query) {
+        let mut ctes = query.extra().get_array("cte").cloned().unwrap_or_default();
+        let mut cte = Map::new();
+        cte.upsert("name", self.name);
+        cte.upsert("expr", self.expr);
+        cte.upsert("recursive", self.recursive);
+        ctes.push(cte.into());
+        query.set_extra_flag("cte", JsonValue::Array(ctes));
+    }
+}
+
+/// Formats the `WITH` clause for the CTEs attached to the query.
+pub(super) fn format_with_clause<M: Schema>(query: &Query) -> String {
+    let Some(ctes) = query.extra().get_array("cte") else {
+        return String::new();
+    };
+
+    let mut recursive = false;
+    let mut exprs = Vec::with_capacity(ctes.len());
+    for cte in ctes.iter().filter_map(|v| v.as_object()) {
+        if let Some(name) = cte.get_str("name")
+            && let Some(expr) = cte.get_str("expr")
+        {
+            let name = M::dialect().escape_table_name(name);
+            exprs.push(format!("{name} AS ({expr})"));
+            recursive |= cte.get_bool("recursive") == Some(true);
+        }
+    }
+    if exprs.is_empty() {
+        String::new()
+    } else if recursive {
+        format!("WITH RECURSIVE {} ", exprs.join(", "))
+    } else {
+        format!("WITH {} ", exprs.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CommonTableExpr;
+    use crate::{Schema, fixture::Group};
+    use zino_core::{Map, extension::JsonObjectExt, model::Query};
+
+    #[test]
+    fn it_formats_with_clause() {
+        let dialect = Group::dialect();
+        let mut query = Query::default();
+        assert_eq!(super::format_with_clause::<Group>(&query), "");
+
+        let mut active_groups = Query::default();
+        active_groups.add_filter("status", "Active");
+        CommonTableExpr::new::<Group>("active_groups", &active_groups).attach_to(&mut query);
+        let with_clause = super::format_with_clause::<Group>(&query);
+        let cte_name = dialect.escape_table_name("active_groups");
+        let table_name = dialect.escape_table_name("test_group");
+        assert!(
+            with_clause.starts_with(&format!("WITH {cte_name} AS (SELECT * FROM {table_name}"))
+        );
+
+        let anchor = Query::default();
+        let recursive = Query::default();
+        CommonTableExpr::recursive::<Group>("group_tree", &anchor, &recursive, "parent_id", "id")
+            .attach_to(&mut query);
+        let with_clause = super::format_with_clause::<Group>(&query);
+        let tree_name = dialect.escape_table_name("group_tree");
+        assert!(with_clause.starts_with(&format!("WITH RECURSIVE {cte_name} AS (")));
+        assert!(with_clause.contains(&format!("), {tree_name} AS (")));
+    }
+
+    #[test]
+    fn it_formats_tree_query() {
+        let mut roots = Query::default();
+        roots.add_filter("parent_id", "null");
+
+        let mut anchor = Query::default();
+        anchor.set_extra_flag("table_name", "test_group_tree_roots");
+
+        let mut recursive = Query::default();
+        recursive.add_filter("status", Map::from_entry("$ne", "Deleted"));
+
+        let mut query = Query::default();
+        query.set_extra_flag("table_name", "test_group_tree");
+        CommonTableExpr::new::<Group>("test_group_tree_roots", &roots).attach_to(&mut query);
+        CommonTableExpr::recursive::<Group>(
+            "test_group_tree",
+            &anchor,
+            &recursive,
+            "parent_id",
+            "id",
+        )
+        .attach_to(&mut query);
+
+        let dialect = Group::dialect();
+        let with_clause = super::format_with_clause::<Group>(&query);
+        let roots_name = dialect.escape_table_name("test_group_tree_roots");
+        let tree_name = dialect.escape_table_name("test_group_tree");
+        let model_name = dialect.escape_table_name("group");
+        assert!(with_clause.contains(&format!(
+            "SELECT * FROM {roots_name} AS {model_name}  UNION ALL SELECT {model_name}.* "
+        )));
+
+        // The columns existing in both relations should be qualified.
+        let parent_id = dialect.format_field("group.parent_id");
+        let status = dialect.format_field("group.status");
+        let cte_column = dialect.format_field("id");
+        assert!(with_clause.contains(&format!(
+            "INNER JOIN {tree_name} ON {parent_id} = {tree_name}.{cte_column}"
+        )));
+        assert!(with_clause.contains(&format!("WHERE {status} <> 'Deleted'")));
+    }
+}
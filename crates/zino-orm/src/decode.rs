@@ -1,14 +1,44 @@
-use super::{DatabaseDriver, DatabaseRow};
-use sqlx::{Database, Decode, Row, ValueRef};
-use zino_core::{Decimal, Uuid, error::Error, warn};
+use super::{DatabaseRow, driver::dispatch};
+use sqlx::{ColumnIndex, Database, Decode, Row, Type, ValueRef};
+use zino_core::{Decimal, JsonValue, Uuid, error::Error, extension::JsonValueExt, warn};
+
+/// Defines the traits for the values decoded by all the enabled drivers.
+macro_rules! define_decode_traits {
+    ($($db:ty),+) => {
+        /// A type that can be decoded from the rows of all the enabled database drivers.
+        pub trait DecodeValue<'r>: $(Decode<'r, $db> +)+ Sized {}
+
+        impl<'r, T> DecodeValue<'r> for T where T: $(Decode<'r, $db> +)+ Sized {}
+
+        /// A type that can be decoded as a column value of all the enabled database drivers.
+        pub trait DecodeColumn: for<'r> DecodeValue<'r> $(+ Type<$db>)+ {}
+
+        impl<T> DecodeColumn for T where T: for<'r> DecodeValue<'r> $(+ Type<$db>)+ {}
+    };
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(all(
+        any(feature = "orm-mariadb", feature = "orm-mysql", feature = "orm-tidb"),
+        feature = "orm-postgres"
+    ))] {
+        define_decode_traits!(sqlx::MySql, sqlx::Postgres, sqlx::Sqlite);
+    } else if #[cfg(any(feature = "orm-mariadb", feature = "orm-mysql", feature = "orm-tidb"))] {
+        define_decode_traits!(sqlx::MySql, sqlx::Sqlite);
+    } else if #[cfg(feature = "orm-postgres")] {
+        define_decode_traits!(sqlx::Postgres, sqlx::Sqlite);
+    } else {
+        define_decode_traits!(sqlx::Sqlite);
+    }
+}
 
 /// Decodes a single value as `T` for the field in a row.
 #[inline]
 pub fn decode<'r, T>(row: &'r DatabaseRow, field: &str) -> Result<T, Error>
 where
-    T: Decode<'r, DatabaseDriver>,
+    T: DecodeValue<'r>,
 {
-    row.try_get_unchecked(field)
+    dispatch!(row, DatabaseRow(row) => row.try_get_unchecked(field))
         .map_err(|err| warn!("fail to decode the `{}` field: {}", field, err))
 }
 
@@ -17,128 +47,109 @@ where
 #[inline]
 pub fn decode_optional<'r, T>(row: &'r DatabaseRow, field: &str) -> Result<Option<T>, Error>
 where
-    T: Decode<'r, DatabaseDriver>,
+    T: DecodeValue<'r>,
 {
-    match row.try_get_raw(field) {
-        Ok(value) => {
-            if value.is_null() {
-                Ok(None)
-            } else {
-                let value = decode_raw(field, value)?;
-                Ok(Some(value))
-            }
-        }
-        Err(err) => {
-            if let sqlx::Error::ColumnNotFound(_) = err {
-                Ok(None)
-            } else {
-                Err(warn!("fail to get the `{}` field: {}", field, err))
-            }
-        }
-    }
+    dispatch!(row, DatabaseRow(row) => decode_optional_value(row, field))
 }
 
 /// Decodes a single value as `Decimal` for the field in a row.
-#[cfg(any(
-    feature = "orm-mariadb",
-    feature = "orm-mysql",
-    feature = "orm-postgres",
-    feature = "orm-tidb"
-))]
 #[inline]
 pub fn decode_decimal(row: &DatabaseRow, field: &str) -> Result<Decimal, Error> {
-    match row.try_get_raw(field) {
-        Ok(value) => {
-            if value.is_null() {
-                warn!("decode the NULL as a zero value");
-                Ok(Decimal::ZERO)
-            } else {
-                let value = decode_raw(field, value)?;
-                Ok(value)
-            }
+    match row {
+        #[cfg(any(feature = "orm-mariadb", feature = "orm-mysql", feature = "orm-tidb"))]
+        DatabaseRow::MySql(row) | DatabaseRow::MariaDb(row) | DatabaseRow::TiDb(row) => {
+            decode_optional_value(row, field).map(Option::unwrap_or_default)
         }
-        Err(err) => {
-            if let sqlx::Error::ColumnNotFound(_) = err {
-                Ok(Decimal::ZERO)
-            } else {
-                Err(warn!("fail to get the `{}` field: {}", field, err))
-            }
+        #[cfg(feature = "orm-postgres")]
+        DatabaseRow::Postgres(row) => {
+            decode_optional_value(row, field).map(Option::unwrap_or_default)
+        }
+        DatabaseRow::Sqlite(row) => {
+            let Some(value) = decode_optional_value::<_, String>(row, field)? else {
+                return Ok(Decimal::ZERO);
+            };
+            value
+                .parse()
+                .map_err(|err| warn!("fail to decode the `{}` field: {}", field, err))
         }
     }
 }
 
-/// Decodes a single value as `Decimal` for the field in a row.
-#[cfg(not(any(
-    feature = "orm-mariadb",
-    feature = "orm-mysql",
-    feature = "orm-postgres",
-    feature = "orm-tidb"
-)))]
+/// Decodes a single value as `Uuid` for the field in a row.
 #[inline]
-pub fn decode_decimal(row: &DatabaseRow, field: &str) -> Result<Decimal, Error> {
+pub fn decode_uuid(row: &DatabaseRow, field: &str) -> Result<Uuid, Error> {
+    #[cfg(feature = "orm-postgres")]
+    if let DatabaseRow::Postgres(row) = row {
+        return decode_optional_value(row, field).map(Option::unwrap_or_default);
+    }
+
     let Some(value) = decode_optional::<String>(row, field)? else {
-        return Ok(Decimal::ZERO);
+        return Ok(Uuid::nil());
     };
     value
         .parse()
         .map_err(|err| warn!("fail to decode the `{}` field: {}", field, err))
 }
 
-/// Decodes a single value as `Uuid` for the field in a row.
-#[cfg(feature = "orm-postgres")]
-#[inline]
-pub fn decode_uuid(row: &DatabaseRow, field: &str) -> Result<Uuid, Error> {
-    match row.try_get_raw(field) {
-        Ok(value) => {
-            if value.is_null() {
-                Ok(Uuid::nil())
+/// Decodes a single value as `Vec<T>` for the field in a row.
+///
+/// The array is decoded natively for PostgreSQL, from a JSON string for MariaDB,
+/// and from a JSON array for the other dialects.
+pub fn decode_array<T>(row: &DatabaseRow, field: &str) -> Result<Vec<T>, Error>
+where
+    T: DecodeColumn + std::str::FromStr + serde::de::DeserializeOwned,
+    <T as std::str::FromStr>::Err: std::error::Error + Send + 'static,
+{
+    match row {
+        #[cfg(feature = "orm-postgres")]
+        DatabaseRow::Postgres(row) => {
+            decode_optional_value(row, field).map(Option::unwrap_or_default)
+        }
+        #[cfg(any(feature = "orm-mariadb", feature = "orm-mysql", feature = "orm-tidb"))]
+        DatabaseRow::MariaDb(row) => {
+            let Some(value) = decode_optional_value::<_, String>(row, field)? else {
+                return Ok(Vec::new());
+            };
+            if value.starts_with('[') && value.ends_with(']') {
+                serde_json::from_str(&value)
+                    .map_err(|err| warn!("fail to decode the `{}` field: {}", field, err))
             } else {
-                let id = decode_raw(field, value)?;
-                Ok(id)
+                zino_core::bail!("invalid array data for the `{}` field", field);
             }
         }
-        Err(err) => {
-            if let sqlx::Error::ColumnNotFound(_) = err {
-                Ok(Uuid::nil())
+        _ => {
+            let Some(value) = decode_optional::<JsonValue>(row, field)? else {
+                return Ok(Vec::new());
+            };
+            if let Some(result) = value.parse_array() {
+                result.map_err(|err| warn!("fail to decode the `{}` field: {}", field, err))
             } else {
-                Err(warn!("fail to get the `{}` field: {}", field, err))
+                Ok(Vec::new())
             }
         }
     }
 }
 
-/// Decodes a single value as `Uuid` for the field in a row.
-#[cfg(not(feature = "orm-postgres"))]
-#[inline]
-pub fn decode_uuid(row: &DatabaseRow, field: &str) -> Result<Uuid, Error> {
-    let Some(value) = decode_optional::<String>(row, field)? else {
-        return Ok(Uuid::nil());
-    };
-    value
-        .parse()
-        .map_err(|err| warn!("fail to decode the `{}` field: {}", field, err))
-}
-
-/// Decodes a single value as `Vec<T>` for the field in a row.
-#[cfg(feature = "orm-postgres")]
-#[inline]
-pub fn decode_array<'r, T>(row: &'r DatabaseRow, field: &str) -> Result<Vec<T>, Error>
+/// Decodes a single value as `T` for the field in a row of the driver,
+/// returning `None` if it was not found or is `NULL`.
+fn decode_optional_value<'r, R, T>(row: &'r R, field: &str) -> Result<Option<T>, Error>
 where
-    T: for<'a> Decode<'a, DatabaseDriver> + sqlx::Type<DatabaseDriver>,
+    R: Row,
+    T: Decode<'r, R::Database>,
+    for<'a> &'a str: ColumnIndex<R>,
 {
     match row.try_get_raw(field) {
         Ok(value) => {
             if value.is_null() {
-                warn!("decode the NULL as an empty array");
-                Ok(Vec::new())
+                Ok(None)
             } else {
-                let vec = decode_raw(field, value)?;
-                Ok(vec)
+                let value = decode_raw::<T, R::Database>(field, value)?;
+                Ok(Some(value))
             }
         }
         Err(err) => {
             if let sqlx::Error::ColumnNotFound(_) = err {
-                Ok(Vec::new())
+                Ok(None)
             } else {
                 Err(warn!("fail to get the `{}` field: {}", field, err))
             }
@@ -146,52 +157,15 @@ where
     }
 }
 
-/// Decodes a single value as `Vec<T>` for the field in a row.
-#[cfg(feature = "orm-mariadb")]
-#[inline]
-pub fn decode_array<'r, T>(row: &'r DatabaseRow, field: &str) -> Result<Vec<T>, Error>
-where
-    T: Decode<'r, DatabaseDriver> + serde::de::DeserializeOwned,
-{
-    let Some(value) = decode_optional::<String>(row, field)? else {
-        return Ok(Vec::new());
-    };
-    if value.starts_with('[') && value.ends_with(']') {
-        serde_json::from_str(&value)
-            .map_err(|err| warn!("fail to decode the `{}` field: {}", field, err))
-    } else {
-        zino_core::bail!("invalid array data for the `{}` field", field);
-    }
-}
-
-/// Decodes a single value as `Vec<T>` for the field in a row.
-#[cfg(not(any(feature = "orm-mariadb", feature = "orm-postgres")))]
-#[inline]
-pub fn decode_array<'r, T>(row: &'r DatabaseRow, field: &str) -> Result<Vec<T>, Error>
-where
-    T: Decode<'r, DatabaseDriver> + std::str::FromStr,
-    <T as std::str::FromStr>::Err: std::error::Error + Send + 'static,
-{
-    use zino_core::{JsonValue, extension::JsonValueExt};
-
-    let Some(value) = decode_optional::<JsonValue>(row, field)? else {
-        return Ok(Vec::new());
-    };
-    if let Some(result) = value.parse_array() {
-        result.map_err(|err| warn!("fail to decode the `{}` field: {}", field, err))
-    } else {
-        Ok(Vec::new())
-    }
-}
-
 /// Decodes a raw value at the index.
 #[inline]
-pub(super) fn decode_raw<'r, T>(
+pub(super) fn decode_raw<'r, T, DB>(
     field: &str,
-    value: <DatabaseDriver as Database>::ValueRef<'r>,
+    value: <DB as Database>::ValueRef<'r>,
 ) -> Result<T, sqlx::Error>
 where
-    T: Decode<'r, DatabaseDriver>,
+    T: Decode<'r, DB>,
+    DB: Database,
 {
     T::decode(value).map_err(|source| {
         tracing::error!("fail to decode the `{}` field", field);
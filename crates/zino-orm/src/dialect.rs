@@ -0,0 +1,224 @@
+//! SQL dialects of the database services.
+//!
+//! Each connection pool records the dialect of its database service, which is determined by
+//! the name of the configuration array, and the pool is created with the driver of the dialect,
+//! so that a binary built with the `orm-mysql` and `orm-postgres` features
+//! can talk to MySQL, TiDB and PostgreSQL services at the same time:
+//!
+//! ```toml
+//! [database]
+//! type = "mysql"
+//!
+//! [[mysql]]
+//! name = "main"
+//! host = "10.0.0.1"
+//! database = "data_cube"
+//!
+//! [[tidb]]
+//! name = "analytics"
+//! host = "10.0.0.2"
+//! port = 4000
+//! database = "data_lake"
+//!
+//! [[postgres]]
+//! name = "billing"
+//! host = "10.0.0.3"
+//! database = "billing"
+//! ```
+//!
+//! The services whose drivers are not enabled by the crate features are ignored.
+//!
+//! The SQL statements of a model, including the identifier quoting, the placeholders
+//! and the upsert syntax, are formatted in the dialect of the connection pool
+//! that the model is bound to, which is resolved by [`Schema::dialect()`](crate::Schema::dialect).
+
+use std::borrow::Cow;
+use zino_core::SharedString;
+
+/// SQL dialects of the database services.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dialect {
+    /// MySQL.
+    MySql,
+    /// MariaDB.
+    MariaDb,
+    /// TiDB.
+    TiDb,
+    /// PostgreSQL.
+    Postgres,
+    /// SQLite.
+    Sqlite,
+}
+
+impl Dialect {
+    /// A list of all the dialects.
+    pub const ALL: [Self; 5] = [
+        Self::MySql,
+        Self::MariaDb,
+        Self::TiDb,
+        Self::Postgres,
+        Self::Sqlite,
+    ];
+
+    /// Parses the dialect from a driver name.
+    pub fn parse(driver: &str) -> Option<Self> {
+        match driver {
+            "mysql" => Some(Self::MySql),
+            "mariadb" => Some(Self::MariaDb),
+            "tidb" => Some(Self::TiDb),
+            "postgres" | "postgresql" => Some(Self::Postgres),
+            "sqlite" => Some(Self::Sqlite),
+            _ => None,
+        }
+    }
+
+    /// Returns the driver name.
+    #[inline]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::MySql => "mysql",
+            Self::MariaDb => "mariadb",
+            Self::TiDb => "tidb",
+            Self::Postgres => "postgres",
+            Self::Sqlite => "sqlite",
+        }
+    }
+
+    /// Returns `true` if the dialect belongs to the MySQL family.
+    #[inline]
+    pub fn is_mysql_compatible(&self) -> bool {
+        matches!(self, Self::MySql | Self::MariaDb | Self::TiDb)
+    }
+
+    /// Returns `true` if the driver of the dialect is enabled.
+    #[inline]
+    pub fn is_supported(&self) -> bool {
+        match self {
+            Self::MySql | Self::MariaDb | Self::TiDb => cfg!(any(
+                feature = "orm-mariadb",
+                feature = "orm-mysql",
+                feature = "orm-tidb"
+            )),
+            Self::Postgres => cfg!(feature = "orm-postgres"),
+            Self::Sqlite => true,
+        }
+    }
+
+    /// Returns a placeholder for the n-th parameter.
+    #[inline]
+    pub fn placeholder(&self, n: usize) -> SharedString {
+        if *self == Self::Postgres {
+            if n == 1 {
+                "$1".into()
+            } else {
+                format!("${n}").into()
+            }
+        } else {
+            "?".into()
+        }
+    }
+
+    /// Returns the quote character for the identifiers.
+    #[inline]
+    pub fn identifier_quote(&self) -> &'static str {
+        if *self == Self::Postgres { "\"" } else { "`" }
+    }
+
+    /// Formats a field for the query.
+    pub fn format_field<'a>(&self, field: &'a str) -> Cow<'a, str> {
+        let quote = self.identifier_quote();
+        if field.contains(quote) {
+            field.into()
+        } else if field.contains('.') {
+            field
+                .split('.')
+                .map(|s| [quote, s, quote].concat())
+                .collect::<Vec<_>>()
+                .join(".")
+                .into()
+        } else {
+            [quote, field, quote].concat().into()
+        }
+    }
+
+    /// Escapes the table name.
+    #[inline]
+    pub fn escape_table_name(&self, table_name: &str) -> String {
+        self.format_field(table_name).into_owned()
+    }
+
+    /// Formats a JSON filter to check whether the document in the field
+    /// contains the encoded JSON value.
+    pub fn format_json_filter(&self, field: &str, value: &str) -> String {
+        let field = self.format_field(field);
+        match self {
+            Self::Postgres => format!(r#"{field} @> {value}::jsonb"#),
+            Self::Sqlite => format!(r#"json_patch({field}, {value}) = json({field})"#),
+            _ => format!(r#"json_contains({field}, {value})"#),
+        }
+    }
+
+    /// Formats the statement to insert a row into the table,
+    /// or update it if the primary key already exists.
+    pub fn format_upsert(
+        &self,
+        table_name: &str,
+        fields: &str,
+        values: &str,
+        mutations: &str,
+        primary_key_name: &str,
+    ) -> String {
+        if self.is_mysql_compatible() {
+            format!(
+                "INSERT INTO {table_name} ({fields}) VALUES ({values}) \
+                    ON DUPLICATE KEY UPDATE {mutations};"
+            )
+        } else {
+            // Both PostgreQL and SQLite (3.35+) support this syntax.
+            format!(
+                "INSERT INTO {table_name} ({fields}) VALUES ({values}) \
+                    ON CONFLICT ({primary_key_name}) DO UPDATE SET {mutations} \
+                        RETURNING {primary_key_name};"
+            )
+        }
+    }
+}
+
+impl Default for Dialect {
+    /// Returns the dialect of the primary driver enabled by the crate features,
+    /// which is used by the models without a connection pool.
+    #[inline]
+    fn default() -> Self {
+        Self::parse(super::DRIVER_NAME).unwrap_or(Self::Sqlite)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Dialect;
+
+    #[test]
+    fn it_formats_statements_in_dialects() {
+        assert_eq!(Dialect::Postgres.placeholder(2), "$2");
+        assert_eq!(Dialect::MySql.placeholder(2), "?");
+        assert_eq!(
+            Dialect::Postgres.format_field("user.name"),
+            r#""user"."name""#
+        );
+        assert_eq!(Dialect::Sqlite.format_field("user.name"), "`user`.`name`");
+        assert_eq!(
+            Dialect::Postgres.escape_table_name("audit.log"),
+            r#""audit"."log""#
+        );
+        assert_eq!(
+            Dialect::MariaDb.format_upsert("`tag`", "`id`, `name`", "1, 'a'", "`name` = 'a'", "id"),
+            "INSERT INTO `tag` (`id`, `name`) VALUES (1, 'a') \
+                ON DUPLICATE KEY UPDATE `name` = 'a';"
+        );
+        assert_eq!(
+            Dialect::Sqlite.format_upsert("`tag`", "`id`, `name`", "1, 'a'", "`name` = 'a'", "id"),
+            "INSERT INTO `tag` (`id`, `name`) VALUES (1, 'a') \
+                ON CONFLICT (id) DO UPDATE SET `name` = 'a' RETURNING id;"
+        );
+    }
+}
@@ -0,0 +1,502 @@
+//! Database drivers selected by the SQL dialects at runtime.
+//!
+//! The drivers are enabled by the crate features: `orm-mysql`, `orm-mariadb` and `orm-tidb`
+//! enable the MySQL driver, `orm-postgres` enables the PostgreSQL driver,
+//! and the SQLite driver is always enabled. A connection pool is created with the driver
+//! for the dialect of its configuration array, so that the models bound to different
+//! database services can be used in the same binary.
+
+use super::{DecodeRow, Dialect, Schema, executor::Executor};
+use futures::{Stream, StreamExt, stream::BoxStream};
+use std::borrow::Cow;
+use zino_core::{
+    JsonValue, Map, Record,
+    error::Error,
+    extension::{JsonObjectExt, JsonValueExt},
+    model::{Column, Query},
+};
+
+/// Dispatches the expression to the inner value of each variant.
+macro_rules! dispatch {
+    ($value:expr, $ty:ident($inner:ident) => $body:expr) => {
+        match $value {
+            #[cfg(any(feature = "orm-mariadb", feature = "orm-mysql", feature = "orm-tidb"))]
+            $ty::MySql($inner) | $ty::MariaDb($inner) | $ty::TiDb($inner) => $body,
+            #[cfg(feature = "orm-postgres")]
+            $ty::Postgres($inner) => $body,
+            $ty::Sqlite($inner) => $body,
+        }
+    };
+}
+
+/// Maps each variant to the variant of the same dialect in the target type.
+macro_rules! map_variant {
+    ($value:expr, $ty:ident($inner:ident) => $target:ident($body:expr)) => {
+        match $value {
+            #[cfg(any(feature = "orm-mariadb", feature = "orm-mysql", feature = "orm-tidb"))]
+            $ty::MySql($inner) => $target::MySql($body),
+            #[cfg(any(feature = "orm-mariadb", feature = "orm-mysql", feature = "orm-tidb"))]
+            $ty::MariaDb($inner) => $target::MariaDb($body),
+            #[cfg(any(feature = "orm-mariadb", feature = "orm-mysql", feature = "orm-tidb"))]
+            $ty::TiDb($inner) => $target::TiDb($body),
+            #[cfg(feature = "orm-postgres")]
+            $ty::Postgres($inner) => $target::Postgres($body),
+            $ty::Sqlite($inner) => $target::Sqlite($body),
+        }
+    };
+}
+
+/// Evaluates the expression with the database driver for the SQL dialect.
+/// The SQLite driver is used for the dialects whose drivers are not enabled.
+macro_rules! with_driver {
+    ($dialect:expr, $driver:ident => $body:expr) => {
+        match $dialect {
+            #[cfg(any(feature = "orm-mariadb", feature = "orm-mysql", feature = "orm-tidb"))]
+            $crate::Dialect::MySql | $crate::Dialect::MariaDb | $crate::Dialect::TiDb => {
+                type $driver = sqlx::MySql;
+                $body
+            }
+            #[cfg(not(any(feature = "orm-mariadb", feature = "orm-mysql", feature = "orm-tidb")))]
+            $crate::Dialect::MySql | $crate::Dialect::MariaDb | $crate::Dialect::TiDb => {
+                type $driver = sqlx::Sqlite;
+                $body
+            }
+            #[cfg(feature = "orm-postgres")]
+            $crate::Dialect::Postgres => {
+                type $driver = sqlx::Postgres;
+                $body
+            }
+            #[cfg(not(feature = "orm-postgres"))]
+            $crate::Dialect::Postgres => {
+                type $driver = sqlx::Sqlite;
+                $body
+            }
+            $crate::Dialect::Sqlite => {
+                type $driver = sqlx::Sqlite;
+                $body
+            }
+        }
+    };
+}
+
+pub(super) use {dispatch, with_driver};
+
+/// Implements the `dialect()` method for the types with a variant for each dialect.
+macro_rules! impl_dialect {
+    ($ty:ident) => {
+        impl $ty {
+            /// Returns the SQL dialect.
+            #[inline]
+            pub fn dialect(&self) -> Dialect {
+                match self {
+                    #[cfg(any(
+                        feature = "orm-mariadb",
+                        feature = "orm-mysql",
+                        feature = "orm-tidb"
+                    ))]
+                    Self::MySql(_) => Dialect::MySql,
+                    #[cfg(any(
+                        feature = "orm-mariadb",
+                        feature = "orm-mysql",
+                        feature = "orm-tidb"
+                    ))]
+                    Self::MariaDb(_) => Dialect::MariaDb,
+                    #[cfg(any(
+                        feature = "orm-mariadb",
+                        feature = "orm-mysql",
+                        feature = "orm-tidb"
+                    ))]
+                    Self::TiDb(_) => Dialect::TiDb,
+                    #[cfg(feature = "orm-postgres")]
+                    Self::Postgres(_) => Dialect::Postgres,
+                    Self::Sqlite(_) => Dialect::Sqlite,
+                }
+            }
+        }
+    };
+}
+
+/// Database pool of the driver for the SQL dialect.
+#[derive(Debug, Clone)]
+pub enum DatabasePool {
+    /// MySQL database pool.
+    #[cfg(any(feature = "orm-mariadb", feature = "orm-mysql", feature = "orm-tidb"))]
+    MySql(sqlx::MySqlPool),
+    /// MariaDB database pool.
+    #[cfg(any(feature = "orm-mariadb", feature = "orm-mysql", feature = "orm-tidb"))]
+    MariaDb(sqlx::MySqlPool),
+    /// TiDB database pool.
+    #[cfg(any(feature = "orm-mariadb", feature = "orm-mysql", feature = "orm-tidb"))]
+    TiDb(sqlx::MySqlPool),
+    /// PostgreSQL database pool.
+    #[cfg(feature = "orm-postgres")]
+    Postgres(sqlx::PgPool),
+    /// SQLite database pool.
+    Sqlite(sqlx::SqlitePool),
+}
+
+impl_dialect!(DatabasePool);
+
+impl DatabasePool {
+    /// Retrieves a connection from the pool.
+    pub async fn acquire(&self) -> Result<DatabaseConnection, sqlx::Error> {
+        let connection = map_variant!(self, DatabasePool(pool) => DatabaseConnection(
+            pool.acquire().await?
+        ));
+        Ok(connection)
+    }
+
+    /// Retrieves a connection and immediately begins a new transaction.
+    pub async fn begin(&self) -> Result<DatabaseTransaction, sqlx::Error> {
+        let transaction = map_variant!(self, DatabasePool(pool) => DatabaseTransaction(
+            pool.begin().await?
+        ));
+        Ok(transaction)
+    }
+
+    /// Retrieves a connection and immediately begins a new transaction
+    /// using the custom statement.
+    pub async fn begin_with(
+        &self,
+        statement: impl Into<Cow<'static, str>>,
+    ) -> Result<DatabaseTransaction, sqlx::Error> {
+        let statement = statement.into();
+        let transaction = map_variant!(self, DatabasePool(pool) => DatabaseTransaction(
+            pool.begin_with(statement).await?
+        ));
+        Ok(transaction)
+    }
+
+    /// Shuts down the pool.
+    pub async fn close(&self) {
+        dispatch!(self, DatabasePool(pool) => pool.close().await)
+    }
+
+    /// Returns `true` if the pool has been closed.
+    #[inline]
+    pub fn is_closed(&self) -> bool {
+        dispatch!(self, DatabasePool(pool) => pool.is_closed())
+    }
+}
+
+/// Database connection retrieved from a [`DatabasePool`].
+#[derive(Debug)]
+pub enum DatabaseConnection {
+    /// MySQL database connection.
+    #[cfg(any(feature = "orm-mariadb", feature = "orm-mysql", feature = "orm-tidb"))]
+    MySql(sqlx::pool::PoolConnection<sqlx::MySql>),
+    /// MariaDB database connection.
+    #[cfg(any(feature = "orm-mariadb", feature = "orm-mysql", feature = "orm-tidb"))]
+    MariaDb(sqlx::pool::PoolConnection<sqlx::MySql>),
+    /// TiDB database connection.
+    #[cfg(any(feature = "orm-mariadb", feature = "orm-mysql", feature = "orm-tidb"))]
+    TiDb(sqlx::pool::PoolConnection<sqlx::MySql>),
+    /// PostgreSQL database connection.
+    #[cfg(feature = "orm-postgres")]
+    Postgres(sqlx::pool::PoolConnection<sqlx::Postgres>),
+    /// SQLite database connection.
+    Sqlite(sqlx::pool::PoolConnection<sqlx::Sqlite>),
+}
+
+impl_dialect!(DatabaseConnection);
+
+/// An in-progress database transaction began from a [`DatabasePool`].
+pub enum DatabaseTransaction {
+    /// MySQL database transaction.
+    #[cfg(any(feature = "orm-mariadb", feature = "orm-mysql", feature = "orm-tidb"))]
+    MySql(sqlx::Transaction<'static, sqlx::MySql>),
+    /// MariaDB database transaction.
+    #[cfg(any(feature = "orm-mariadb", feature = "orm-mysql", feature = "orm-tidb"))]
+    MariaDb(sqlx::Transaction<'static, sqlx::MySql>),
+    /// TiDB database transaction.
+    #[cfg(any(feature = "orm-mariadb", feature = "orm-mysql", feature = "orm-tidb"))]
+    TiDb(sqlx::Transaction<'static, sqlx::MySql>),
+    /// PostgreSQL database transaction.
+    #[cfg(feature = "orm-postgres")]
+    Postgres(sqlx::Transaction<'static, sqlx::Postgres>),
+    /// SQLite database transaction.
+    Sqlite(sqlx::Transaction<'static, sqlx::Sqlite>),
+}
+
+impl_dialect!(DatabaseTransaction);
+
+impl DatabaseTransaction {
+    /// Commits the transaction.
+    pub async fn commit(self) -> Result<(), sqlx::Error> {
+        dispatch!(self, DatabaseTransaction(transaction) => transaction.commit().await)
+    }
+
+    /// Aborts the transaction.
+    pub async fn rollback(self) -> Result<(), sqlx::Error> {
+        dispatch!(self, DatabaseTransaction(transaction) => transaction.rollback().await)
+    }
+}
+
+/// A single row from the database of the driver for the SQL dialect.
+pub enum DatabaseRow {
+    /// A row from the MySQL database.
+    #[cfg(any(feature = "orm-mariadb", feature = "orm-mysql", feature = "orm-tidb"))]
+    MySql(sqlx::mysql::MySqlRow),
+    /// A row from the MariaDB database.
+    #[cfg(any(feature = "orm-mariadb", feature = "orm-mysql", feature = "orm-tidb"))]
+    MariaDb(sqlx::mysql::MySqlRow),
+    /// A row from the TiDB database.
+    #[cfg(any(feature = "orm-mariadb", feature = "orm-mysql", feature = "orm-tidb"))]
+    TiDb(sqlx::mysql::MySqlRow),
+    /// A row from the PostgreSQL database.
+    #[cfg(feature = "orm-postgres")]
+    Postgres(sqlx::postgres::PgRow),
+    /// A row from the SQLite database.
+    Sqlite(sqlx::sqlite::SqliteRow),
+}
+
+impl_dialect!(DatabaseRow);
+
+impl DecodeRow<DatabaseRow> for Map {
+    type Error = Error;
+
+    #[inline]
+    fn decode_row(row: &DatabaseRow) -> Result<Self, Self::Error> {
+        let dialect = row.dialect();
+        dispatch!(row, DatabaseRow(row) => row.decode_map(dialect))
+    }
+
+    #[inline]
+    fn decode_cached(value: JsonValue) -> Option<Self> {
+        value.into_map_opt()
+    }
+
+    #[inline]
+    fn encode_cached(&self) -> Option<JsonValue> {
+        Some(self.clone().into())
+    }
+
+    #[inline]
+    fn attach_relation(&mut self, name: &str, value: JsonValue) -> Result<(), Self::Error> {
+        self.upsert(name, value);
+        Ok(())
+    }
+}
+
+impl DecodeRow<DatabaseRow> for Record {
+    type Error = Error;
+
+    #[inline]
+    fn decode_row(row: &DatabaseRow) -> Result<Self, Self::Error> {
+        let dialect = row.dialect();
+        dispatch!(row, DatabaseRow(row) => row.decode_record(dialect))
+    }
+}
+
+/// The result of a query executed against the database.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct QueryResult {
+    /// The number of rows affected.
+    rows_affected: u64,
+    /// The last inserted ID.
+    last_insert_id: Option<i64>,
+}
+
+impl QueryResult {
+    /// Creates a new instance.
+    #[inline]
+    pub fn new(rows_affected: u64, last_insert_id: Option<i64>) -> Self {
+        Self {
+            rows_affected,
+            last_insert_id,
+        }
+    }
+
+    /// Returns the number of rows affected.
+    #[inline]
+    pub fn rows_affected(&self) -> u64 {
+        self.rows_affected
+    }
+
+    /// Returns the last inserted ID.
+    /// It is always `None` for PostgreSQL, which should use the `RETURNING` clause instead.
+    #[inline]
+    pub fn last_insert_id(&self) -> Option<i64> {
+        self.last_insert_id
+    }
+}
+
+#[cfg(any(feature = "orm-mariadb", feature = "orm-mysql", feature = "orm-tidb"))]
+impl From<sqlx::mysql::MySqlQueryResult> for QueryResult {
+    #[inline]
+    fn from(result: sqlx::mysql::MySqlQueryResult) -> Self {
+        Self::new(
+            result.rows_affected(),
+            result.last_insert_id().try_into().ok(),
+        )
+    }
+}
+
+#[cfg(feature = "orm-postgres")]
+impl From<sqlx::postgres::PgQueryResult> for QueryResult {
+    #[inline]
+    fn from(result: sqlx::postgres::PgQueryResult) -> Self {
+        Self::new(result.rows_affected(), None)
+    }
+}
+
+impl From<sqlx::sqlite::SqliteQueryResult> for QueryResult {
+    #[inline]
+    fn from(result: sqlx::sqlite::SqliteQueryResult) -> Self {
+        Self::new(result.rows_affected(), Some(result.last_insert_rowid()))
+    }
+}
+
+/// Extension trait for the database drivers,
+/// which formats the SQL of the columns and queries in their dialects.
+pub(super) trait DriverExt {
+    /// Returns the corresponding column type.
+    fn column_type<'a>(col: &'a Column<'_>, dialect: Dialect) -> &'a str;
+
+    /// Encodes a json value as a column value represented by a str.
+    fn encode_value<'a>(
+        col: &Column<'_>,
+        dialect: Dialect,
+        value: Option<&'a JsonValue>,
+    ) -> Cow<'a, str>;
+
+    /// Formats a string value for the column.
+    fn format_value<'a>(col: &Column<'_>, dialect: Dialect, value: &'a str) -> Cow<'a, str>;
+
+    /// Formats a column filter.
+    fn format_filter(col: &Column<'_>, dialect: Dialect, field: &str, value: &JsonValue) -> String;
+
+    /// Formats the table name of the query.
+    fn format_table_name<M: Schema>(query: &Query) -> String {
+        let dialect = M::dialect();
+        let table_name = query
+            .extra()
+            .get_str("table_name")
+            .unwrap_or_else(|| M::table_name());
+        let table_name = dialect.escape_table_name(table_name);
+        let model_name = dialect.format_field(M::model_name());
+        format!("{table_name} AS {model_name}")
+    }
+
+    /// Parses text search filter.
+    fn parse_text_search<M: Schema>(filter: &Map) -> Option<String>;
+
+    /// Formats the relevance of the text search, where a larger value is more relevant.
+    fn format_text_search_rank<M: Schema>(filter: &Map) -> Option<String>;
+
+    /// Formats the snippet of the field with the matches of the text search highlighted.
+    fn format_text_search_highlight<M: Schema>(filter: &Map, field: &str) -> Option<String>;
+}
+
+/// Extension trait for the rows of the database drivers.
+pub(super) trait RowExt: Sized {
+    /// Converts `self` into a database row for the SQL dialect.
+    fn into_database_row(self, dialect: Dialect) -> DatabaseRow;
+
+    /// Decodes the row as a map.
+    fn decode_map(&self, dialect: Dialect) -> Result<Map, Error>;
+
+    /// Decodes the row as a record.
+    fn decode_record(&self, dialect: Dialect) -> Result<Record, Error>;
+}
+
+/// Implements the `Executor` trait for the types with a variant for each dialect.
+macro_rules! impl_database_executor {
+    ($ty:ident, $inner:ident => $executor:expr) => {
+        type Row = DatabaseRow;
+        type QueryResult = QueryResult;
+
+        async fn execute(self, sql: &str) -> Result<Self::QueryResult, Error> {
+            dispatch!(self, $ty($inner) => $executor.execute(sql).await.map(QueryResult::from))
+        }
+
+        async fn execute_with<T: ToString>(
+            self,
+            sql: &str,
+            arguments: &[T],
+        ) -> Result<Self::QueryResult, Error> {
+            dispatch!(self, $ty($inner) => {
+                $executor.execute_with(sql, arguments).await.map(QueryResult::from)
+            })
+        }
+
+        async fn fetch(self, sql: &str) -> Result<Vec<Self::Row>, Error> {
+            let dialect = self.dialect();
+            dispatch!(self, $ty($inner) => {
+                let rows = $executor.fetch(sql).await?;
+                Ok(rows.into_iter().map(|row| row.into_database_row(dialect)).collect())
+            })
+        }
+
+        async fn fetch_with<T: ToString>(
+            self,
+            sql: &str,
+            arguments: &[T],
+        ) -> Result<Vec<Self::Row>, Error> {
+            let dialect = self.dialect();
+            dispatch!(self, $ty($inner) => {
+                let rows = $executor.fetch_with(sql, arguments).await?;
+                Ok(rows.into_iter().map(|row| row.into_database_row(dialect)).collect())
+            })
+        }
+
+        fn fetch_stream(self, sql: String) -> impl Stream<Item = Result<Self::Row, Error>> + Send {
+            self.fetch_stream_with(sql, Vec::new())
+        }
+
+        fn fetch_stream_with(
+            self,
+            sql: String,
+            arguments: Vec<String>,
+        ) -> impl Stream<Item = Result<Self::Row, Error>> + Send {
+            let dialect = self.dialect();
+            let stream: BoxStream<'_, Result<Self::Row, Error>> = dispatch!(self, $ty($inner) => {
+                $executor
+                    .fetch_stream_with(sql, arguments)
+                    .map(move |result| result.map(|row| row.into_database_row(dialect)))
+                    .boxed()
+            });
+            stream
+        }
+
+        async fn fetch_one(self, sql: &str) -> Result<Self::Row, Error> {
+            let dialect = self.dialect();
+            dispatch!(self, $ty($inner) => {
+                let row = $executor.fetch_one(sql).await?;
+                Ok(row.into_database_row(dialect))
+            })
+        }
+
+        async fn fetch_optional(self, sql: &str) -> Result<Option<Self::Row>, Error> {
+            let dialect = self.dialect();
+            dispatch!(self, $ty($inner) => {
+                let row = $executor.fetch_optional(sql).await?;
+                Ok(row.map(|row| row.into_database_row(dialect)))
+            })
+        }
+
+        async fn fetch_optional_with<T: ToString>(
+            self,
+            sql: &str,
+            arguments: &[T],
+        ) -> Result<Option<Self::Row>, Error> {
+            let dialect = self.dialect();
+            dispatch!(self, $ty($inner) => {
+                let row = $executor.fetch_optional_with(sql, arguments).await?;
+                Ok(row.map(|row| row.into_database_row(dialect)))
+            })
+        }
+    };
+}
+
+impl Executor for &DatabasePool {
+    impl_database_executor!(DatabasePool, pool => pool);
+}
+
+impl Executor for &mut DatabaseConnection {
+    impl_database_executor!(DatabaseConnection, connection => &mut **connection);
+}
+
+impl Executor for &mut DatabaseTransaction {
+    impl_database_executor!(DatabaseTransaction, transaction => &mut **transaction);
+}
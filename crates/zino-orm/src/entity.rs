@@ -1,15 +1,12 @@
-use super::{Aggregation, IntoSqlValue, query::QueryExt};
+use super::{Aggregation, Dialect, IntoSqlValue, Schema, query::QueryExt};
 use std::{
     fmt::{self, Display},
     marker::PhantomData,
 };
-use zino_core::{
-    JsonValue,
-    model::{Model, Query},
-};
+use zino_core::{JsonValue, model::Query};
 
 /// An interface for the model entity.
-pub trait Entity: Model {
+pub trait Entity: Schema {
     /// The column type.
     type Column: ModelColumn<Self>;
 
@@ -64,7 +61,8 @@ impl<E: Entity> DerivedColumn<E> {
     #[inline]
     pub fn coalesce<V: IntoSqlValue>(col: E::Column, value: V) -> Self {
         let col_name = E::format_column(&col);
-        let field = Query::format_field(&col_name);
+        let dialect = E::dialect();
+        let field = dialect.format_field(&col_name);
         Self::coalesce_field(&field, value.into_sql_value())
     }
 
@@ -105,8 +103,9 @@ impl<E: Entity> DerivedColumn<E> {
     #[inline]
     pub fn year(col: E::Column) -> Self {
         let col_name = E::format_column(&col);
-        let field = Query::format_field(&col_name);
-        let expr = if cfg!(feature = "orm-sqlite") {
+        let dialect = E::dialect();
+        let field = dialect.format_field(&col_name);
+        let expr = if dialect == Dialect::Sqlite {
             format!("strftime('%Y', {field}, 'localtime')")
         } else {
             format!("year({field})")
@@ -118,14 +117,11 @@ impl<E: Entity> DerivedColumn<E> {
     #[inline]
     pub fn year_month(col: E::Column) -> Self {
         let col_name = E::format_column(&col);
-        let field = Query::format_field(&col_name);
-        let expr = if cfg!(any(
-            feature = "orm-mariadb",
-            feature = "orm-mysql",
-            feature = "orm-tidb"
-        )) {
+        let dialect = E::dialect();
+        let field = dialect.format_field(&col_name);
+        let expr = if dialect.is_mysql_compatible() {
             format!("date_format({field}, '%Y-%m')")
-        } else if cfg!(feature = "orm-postgres") {
+        } else if dialect == Dialect::Postgres {
             format!("to_char({field}, 'YYYY-MM')")
         } else {
             format!("strftime('%Y-%m', {field}, 'localtime')")
@@ -137,8 +133,9 @@ impl<E: Entity> DerivedColumn<E> {
     #[inline]
     pub fn date(col: E::Column) -> Self {
         let col_name = E::format_column(&col);
-        let field = Query::format_field(&col_name);
-        let expr = if cfg!(feature = "orm-sqlite") {
+        let dialect = E::dialect();
+        let field = dialect.format_field(&col_name);
+        let expr = if dialect == Dialect::Sqlite {
             format!("strftime('%Y-%m-%d', {field}, 'localtime')")
         } else {
             format!("date({field})")
@@ -150,14 +147,11 @@ impl<E: Entity> DerivedColumn<E> {
     #[inline]
     pub fn format_date_time(col: E::Column) -> Self {
         let col_name = E::format_column(&col);
-        let field = Query::format_field(&col_name);
-        let expr = if cfg!(any(
-            feature = "orm-mariadb",
-            feature = "orm-mysql",
-            feature = "orm-tidb"
-        )) {
+        let dialect = E::dialect();
+        let field = dialect.format_field(&col_name);
+        let expr = if dialect.is_mysql_compatible() {
             format!("date_format({field}, '%Y-%m-%d %H:%i:%s')")
-        } else if cfg!(feature = "orm-postgres") {
+        } else if dialect == Dialect::Postgres {
             format!("to_char({field}, 'YYYY-MM-DD HH24:MI:SS')")
         } else {
             format!("strftime('%Y-%m-%d %H:%M:%S', {field}, 'localtime')")
@@ -169,8 +163,9 @@ impl<E: Entity> DerivedColumn<E> {
     #[inline]
     pub fn json_extract(col: E::Column, path: &str) -> Self {
         let col_name = E::format_column(&col);
-        let field = Query::format_field(&col_name);
-        let expr = if cfg!(feature = "orm-postgres") {
+        let dialect = E::dialect();
+        let field = dialect.format_field(&col_name);
+        let expr = if dialect == Dialect::Postgres {
             let path = path.strip_prefix("$.").unwrap_or(path).replace('.', ", ");
             format!(r#"({field} #>> '{{{path}}}')"#)
         } else {
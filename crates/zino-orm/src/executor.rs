@@ -1,3 +1,4 @@
+use futures::Stream;
 use zino_core::error::Error;
 
 /// Executing queries against the database.
@@ -28,6 +29,19 @@ pub trait Executor {
         arguments: &[T],
     ) -> Result<Vec<Self::Row>, Error>;
 
+    /// Executes the query and returns a stream of the generated results.
+    ///
+    /// The rows are fetched lazily while the stream is polled,
+    /// so the maximum row limit does not apply.
+    fn fetch_stream(self, sql: String) -> impl Stream<Item = Result<Self::Row, Error>> + Send;
+
+    /// Executes the query with arguments and returns a stream of the generated results.
+    fn fetch_stream_with(
+        self,
+        sql: String,
+        arguments: Vec<String>,
+    ) -> impl Stream<Item = Result<Self::Row, Error>> + Send;
+
     /// Executes the query and returns exactly one row.
     async fn fetch_one(self, sql: &str) -> Result<Self::Row, Error>;
 
@@ -44,9 +58,9 @@ pub trait Executor {
 
 #[cfg(feature = "orm-sqlx")]
 macro_rules! impl_sqlx_executor {
-    () => {
-        type Row = super::DatabaseRow;
-        type QueryResult = <super::DatabaseDriver as sqlx::Database>::QueryResult;
+    ($db:ty) => {
+        type Row = <$db as sqlx::Database>::Row;
+        type QueryResult = <$db as sqlx::Database>::QueryResult;
 
         async fn execute(self, sql: &str) -> Result<Self::QueryResult, Error> {
             match sqlx::query(sql).execute(self).await {
@@ -157,6 +171,50 @@ macro_rules! impl_sqlx_executor {
             Ok(rows)
         }
 
+        fn fetch_stream(
+            self,
+            sql: String,
+        ) -> impl futures::Stream<Item = Result<Self::Row, Error>> + Send {
+            self.fetch_stream_with(sql, Vec::new())
+        }
+
+        fn fetch_stream_with(
+            self,
+            sql: String,
+            arguments: Vec<String>,
+        ) -> impl futures::Stream<Item = Result<Self::Row, Error>> + Send {
+            use futures::{SinkExt, StreamExt, channel::mpsc, stream};
+
+            // The producer owns the SQL and drives the row stream borrowing from it,
+            // while the rows are delivered to the consumer through a bounded channel.
+            let (mut sender, receiver) = mpsc::channel(1);
+            let producer = async move {
+                let mut query = sqlx::query(&sql);
+                for arg in arguments {
+                    query = query.bind(arg);
+                }
+
+                let mut stream = query.fetch(self);
+                while let Some(result) = stream.next().await {
+                    let result = match result {
+                        Ok(row) => Ok(row),
+                        Err(err) => {
+                            if matches!(err, sqlx::error::Error::PoolTimedOut) {
+                                super::GlobalPool::connect_all().await;
+                            }
+                            Err(Error::from(err))
+                        }
+                    };
+                    let failed = result.is_err();
+                    if sender.send(result).await.is_err() || failed {
+                        break;
+                    }
+                }
+            };
+            let producer = stream::once(producer).filter_map(|()| async { None });
+            stream::select(receiver, producer)
+        }
+
         async fn fetch_one(self, sql: &str) -> Result<Self::Row, Error> {
             match sqlx::query(sql).fetch_one(self).await {
                 Ok(row) => Ok(row),
@@ -203,12 +261,32 @@ macro_rules! impl_sqlx_executor {
     };
 }
 
+#[cfg(any(feature = "orm-mariadb", feature = "orm-mysql", feature = "orm-tidb"))]
+impl Executor for &sqlx::Pool<sqlx::MySql> {
+    impl_sqlx_executor!(sqlx::MySql);
+}
+
+#[cfg(any(feature = "orm-mariadb", feature = "orm-mysql", feature = "orm-tidb"))]
+impl Executor for &mut sqlx::MySqlConnection {
+    impl_sqlx_executor!(sqlx::MySql);
+}
+
+#[cfg(feature = "orm-postgres")]
+impl Executor for &sqlx::Pool<sqlx::Postgres> {
+    impl_sqlx_executor!(sqlx::Postgres);
+}
+
+#[cfg(feature = "orm-postgres")]
+impl Executor for &mut sqlx::PgConnection {
+    impl_sqlx_executor!(sqlx::Postgres);
+}
+
 #[cfg(feature = "orm-sqlx")]
-impl Executor for &sqlx::Pool<super::DatabaseDriver> {
-    impl_sqlx_executor!();
+impl Executor for &sqlx::Pool<sqlx::Sqlite> {
+    impl_sqlx_executor!(sqlx::Sqlite);
 }
 
 #[cfg(feature = "orm-sqlx")]
-impl Executor for &mut super::DatabaseConnection {
-    impl_sqlx_executor!();
+impl Executor for &mut sqlx::SqliteConnection {
+    impl_sqlx_executor!(sqlx::Sqlite);
 }
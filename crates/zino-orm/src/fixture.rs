@@ -0,0 +1,155 @@
+//! A model for testing the SQL statements without a database connection.
+
+use super::{ConnectionPool, Dialect, Relation, Schema};
+use serde::{Deserialize, Serialize};
+use std::{cell::Cell, sync::OnceLock};
+use zino_core::{
+    JsonValue, LazyLock, Map, bail,
+    error::Error,
+    model::{Column, Model, ModelHooks},
+};
+
+/// A group which can be nested in a parent group.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub(crate) struct Group {
+    pub(crate) id: i64,
+    pub(crate) name: String,
+    pub(crate) description: String,
+    pub(crate) status: String,
+    pub(crate) parent_id: Option<i64>,
+    pub(crate) owner_id: Option<i64>,
+    pub(crate) version: u64,
+}
+
+impl Model for Group {
+    const MODEL_NAME: &'static str = "group";
+}
+
+impl ModelHooks for Group {
+    type Data = Map;
+    type Extension = Map;
+}
+
+impl Schema for Group {
+    type PrimaryKey = i64;
+
+    const TABLE_NAME: Option<&'static str> = Some("test_group");
+
+    #[inline]
+    fn primary_key(&self) -> &Self::PrimaryKey {
+        &self.id
+    }
+
+    #[inline]
+    fn schema() -> &'static apache_avro::Schema {
+        &GROUP_SCHEMA
+    }
+
+    #[inline]
+    fn columns() -> &'static [Column<'static>] {
+        GROUP_COLUMNS.as_slice()
+    }
+
+    #[inline]
+    fn fields() -> &'static [&'static str] {
+        &[
+            "id",
+            "name",
+            "description",
+            "status",
+            "parent_id",
+            "owner_id",
+            "version",
+        ]
+    }
+
+    #[inline]
+    fn read_only_fields() -> &'static [&'static str] {
+        &["id"]
+    }
+
+    #[inline]
+    fn write_only_fields() -> &'static [&'static str] {
+        &[]
+    }
+
+    #[inline]
+    fn dialect() -> Dialect {
+        DIALECT.get()
+    }
+
+    #[inline]
+    fn relations() -> &'static [Relation] {
+        static GROUP_RELATIONS: [Relation; 2] = [
+            Relation::belongs_to("parent", "parent_id"),
+            Relation::has_many("children", "parent_id"),
+        ];
+        GROUP_RELATIONS.as_slice()
+    }
+
+    async fn load_relation(
+        relation: &Relation,
+        keys: Vec<JsonValue>,
+    ) -> Result<Vec<(JsonValue, Map)>, Error> {
+        relation.load::<Self>(keys).await
+    }
+
+    async fn acquire_reader() -> Result<&'static ConnectionPool, Error> {
+        if let Some(connection_pool) = CONNECTION_POOL.get() {
+            return Ok(connection_pool);
+        }
+        bail!(
+            "there is no connection pool for the model `{}`",
+            Self::MODEL_NAME
+        );
+    }
+
+    async fn acquire_writer() -> Result<&'static ConnectionPool, Error> {
+        bail!(
+            "there is no connection pool for the model `{}`",
+            Self::MODEL_NAME
+        );
+    }
+}
+
+thread_local! {
+    /// SQL dialect of the `Group` model for the current test.
+    static DIALECT: Cell<Dialect> = Cell::new(Dialect::default());
+}
+
+/// Sets the SQL dialect of the `Group` model for the current test.
+pub(crate) fn set_dialect(dialect: Dialect) {
+    DIALECT.set(dialect);
+}
+
+/// Connection pool of the `Group` model for the tests with a database.
+static CONNECTION_POOL: OnceLock<ConnectionPool> = OnceLock::new();
+
+/// Sets the connection pool of the `Group` model.
+/// It should be called by only one test since the pool can not be replaced.
+#[cfg(feature = "orm-sqlx")]
+pub(crate) fn set_connection_pool(connection_pool: ConnectionPool) {
+    if CONNECTION_POOL.set(connection_pool).is_err() {
+        panic!("the connection pool of the `Group` model has been set");
+    }
+}
+
+/// Avro schema of the `Group` model.
+static GROUP_SCHEMA: apache_avro::Schema = apache_avro::Schema::Null;
+
+/// Columns of the `Group` model.
+static GROUP_COLUMNS: LazyLock<Vec<Column<'static>>> = LazyLock::new(|| {
+    let mut id = Column::new("id", "i64", true);
+    id.set_extra_attribute("primary_key", true);
+    let mut description = Column::new("description", "String", true);
+    description.set_index_type("fulltext");
+    vec![
+        id,
+        Column::new("name", "String", true),
+        description,
+        Column::new("status", "String", true),
+        Column::new("parent_id", "Option<i64>", false),
+        Column::new("owner_id", "Option<i64>", false),
+        Column::new("version", "u64", true),
+    ]
+});
@@ -0,0 +1,394 @@
+//! Revision history for the models.
+//!
+//! When the revision history is enabled for a model via `#[schema(history)]`,
+//! each write through [`ModelAccessor`](crate::ModelAccessor) stores the prior snapshot
+//! in a companion `<table>_history` table, together with the diff,
+//! the acting user and the query ID of the mutation.
+//!
+//! The acting user is provided by wrapping the request future in [`ActingUser`],
+//! which is usually done in the middleware where the user session is initialized:
+//!
+//! ```rust,ignore
+//! use zino_orm::ActingUser;
+//!
+//! pub async fn init_user_session(mut req: Request, next: Next) -> Result<Response> {
+//!     let claims = req.parse_jwt_claims(JwtClaims::shared_key())?;
+//!     let session = UserSession::<i64>::try_from_jwt_claims(claims).extract(&req)?;
+//!     let user_id = session.user_id().to_string();
+//!     req.set_data(session);
+//!     Ok(ActingUser::new(user_id, next.run(req.into())).await)
+//! }
+//! ```
+
+use super::{
+    DecodeRow, Dialect, Executor, ModelAccessor, Schema, TransactionOptions, TransactionScope,
+    column::ColumnExt,
+};
+use std::{
+    cell::RefCell,
+    collections::HashSet,
+    fmt::Display,
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+};
+use zino_core::{
+    JsonValue, LazyLock, Map,
+    error::Error,
+    extension::{JsonObjectExt, JsonValueExt},
+    model::{Mutation, Query, QueryContext},
+};
+
+/// A future which records the acting user in the revision history for the writes within it.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct ActingUser<F> {
+    /// The inner future.
+    future: Pin<Box<F>>,
+    /// The user ID.
+    user_id: Option<Arc<str>>,
+}
+
+impl<F: Future> ActingUser<F> {
+    /// Creates a new instance.
+    #[inline]
+    pub fn new(user_id: impl Into<Option<String>>, future: F) -> Self {
+        Self {
+            future: Box::pin(future),
+            user_id: user_id.into().map(Arc::from),
+        }
+    }
+}
+
+impl<F: Future> Future for ActingUser<F> {
+    type Output = F::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let user_id = self.user_id.clone();
+        let _guard = ActingUserGuard(CURRENT_USER.replace(user_id));
+        self.future.as_mut().poll(cx)
+    }
+}
+
+/// A guard which restores the previous acting user when dropped.
+struct ActingUserGuard(Option<Arc<str>>);
+
+impl Drop for ActingUserGuard {
+    #[inline]
+    fn drop(&mut self) {
+        CURRENT_USER.set(self.0.take());
+    }
+}
+
+/// Returns the acting user for the current future.
+#[inline]
+fn acting_user() -> Option<Arc<str>> {
+    CURRENT_USER.with_borrow(|user_id| user_id.clone())
+}
+
+/// Returns the name of the history table for the model.
+#[inline]
+fn history_table_name<M: Schema>() -> String {
+    [M::table_name(), "_history"].concat()
+}
+
+/// Creates the history table for the model if it does not exist.
+async fn create_history_table<M: Schema>() -> Result<(), Error> {
+    let table_name = M::table_name();
+    if HISTORY_TABLES
+        .lock()
+        .is_ok_and(|tables| tables.contains(table_name))
+    {
+        return Ok(());
+    }
+
+    let connection_pool = M::acquire_writer().await?;
+    if connection_pool.auto_migration() {
+        let dialect = connection_pool.dialect();
+        let history_table_name = dialect.escape_table_name(&history_table_name::<M>());
+        let (text_type, timestamp_type) = if dialect.is_mysql_compatible() {
+            let timestamp_type = "DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6)";
+            ("LONGTEXT", timestamp_type)
+        } else if dialect == Dialect::Postgres {
+            ("TEXT", "TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP")
+        } else {
+            ("TEXT", "DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP")
+        };
+        let sql = format!(
+            "CREATE TABLE IF NOT EXISTS {history_table_name} (\n  \
+                model_id VARCHAR(255) NOT NULL,\n  \
+                version BIGINT NOT NULL,\n  \
+                action VARCHAR(64) NOT NULL,\n  \
+                snapshot {text_type} NOT NULL,\n  \
+                diff {text_type} NOT NULL,\n  \
+                actor VARCHAR(255),\n  \
+                query_id VARCHAR(64) NOT NULL,\n  \
+                created_at {timestamp_type},\n  \
+                PRIMARY KEY (model_id, version)\n);"
+        );
+        if let Err(err) = connection_pool.pool().execute(&sql).await {
+            tracing::error!(table_name, "fail to execute `{sql}`");
+            return Err(err);
+        }
+    }
+    if let Ok(mut tables) = HISTORY_TABLES.lock() {
+        tables.insert(table_name);
+    }
+    Ok(())
+}
+
+/// Formats the SQL to select the current row of a model by the primary key.
+fn format_current_row_query<M: Schema>() -> String {
+    let dialect = M::dialect();
+    let table_name = dialect.escape_table_name(M::table_name());
+    let primary_key_name = dialect.format_field(M::primary_key_name());
+    let placeholder = dialect.placeholder(1);
+    if dialect == Dialect::Postgres {
+        let type_annotation = M::primary_key_column().type_annotation(dialect);
+        format!(
+            "SELECT * FROM {table_name} \
+                WHERE {primary_key_name} = ({placeholder}){type_annotation};"
+        )
+    } else {
+        format!("SELECT * FROM {table_name} WHERE {primary_key_name} = {placeholder};")
+    }
+}
+
+/// Fetches the current row of a model from the writer,
+/// which is not affected by the replication lag.
+pub(crate) async fn fetch_current_row<M: Schema>(
+    primary_key: &M::PrimaryKey,
+) -> Result<Option<Map>, Error> {
+    let sql = format_current_row_query::<M>();
+    let pool = M::acquire_writer().await?.pool();
+    pool.fetch_optional_with(&sql, &[primary_key])
+        .await?
+        .map(|row| Map::decode_row(&row))
+        .transpose()
+}
+
+/// Fetches the current row of a model inside of the transaction.
+async fn fetch_current_row_in<M: Schema>(
+    tx: &mut TransactionScope<'_>,
+    primary_key: &M::PrimaryKey,
+) -> Result<Option<Map>, Error> {
+    let sql = format_current_row_query::<M>();
+    tx.connection()
+        .fetch_optional_with(&sql, &[primary_key])
+        .await?
+        .map(|row| Map::decode_row(&row))
+        .transpose()
+}
+
+/// Fetches the snapshot of a model at the specific version,
+/// which is either the current row or a revision in the history table.
+pub(crate) async fn fetch_version<M: Schema>(
+    primary_key: &M::PrimaryKey,
+    version: u64,
+) -> Result<Option<Map>, Error> {
+    if let Some(current) = fetch_current_row::<M>(primary_key).await?
+        && current.get_u64("version") == Some(version)
+    {
+        return Ok(Some(current));
+    }
+
+    let mut revisions = list_revisions::<M>(primary_key, Some(version)).await?;
+    Ok(revisions
+        .pop()
+        .and_then(|mut revision| revision.remove("snapshot"))
+        .and_then(|snapshot| snapshot.into_map_opt()))
+}
+
+/// Updates at most one model selected by the query. If the revision history is enabled,
+/// a revision with the prior snapshot is recorded in the same transaction.
+pub(crate) async fn update_one<K, M>(
+    primary_key: &K,
+    query: &Query,
+    mutation: &mut Mutation,
+    action: &str,
+) -> Result<QueryContext, Error>
+where
+    K: Default + Display + PartialEq,
+    M: ModelAccessor<K>,
+{
+    if !M::history_enabled() {
+        return M::update_one(query, mutation).await;
+    }
+
+    // The history table should be created outside of the transaction
+    // since a DDL statement causes an implicit commit in MySQL.
+    create_history_table::<M>().await?;
+
+    let connection_pool = M::acquire_writer().await?;
+    let mut tx = TransactionScope::begin(connection_pool, &TransactionOptions::new()).await?;
+    match update_one_in::<M>(&mut tx, primary_key, query, mutation, action).await {
+        Ok(ctx) => {
+            tx.commit().await?;
+            Ok(ctx)
+        }
+        Err(err) => {
+            if let Err(rollback_err) = tx.rollback().await {
+                let model_name = M::model_name();
+                tracing::error!(
+                    model_name,
+                    "fail to roll back the transaction: {rollback_err}"
+                );
+            }
+            Err(err)
+        }
+    }
+}
+
+/// Updates at most one model selected by the query,
+/// and records a revision with the prior snapshot inside of the transaction.
+async fn update_one_in<M: Schema>(
+    tx: &mut TransactionScope<'_>,
+    primary_key: &M::PrimaryKey,
+    query: &Query,
+    mutation: &mut Mutation,
+    action: &str,
+) -> Result<QueryContext, Error> {
+    let snapshot = fetch_current_row_in::<M>(tx, primary_key).await?;
+    let ctx = M::update_one_in(query, mutation, tx).await?;
+    let Some(snapshot) = snapshot.filter(|_| ctx.rows_affected() == Some(1)) else {
+        return Ok(ctx);
+    };
+
+    let current = fetch_current_row_in::<M>(tx, primary_key)
+        .await?
+        .unwrap_or_default();
+    let diff = diff_snapshots(&snapshot, &current);
+    let version = snapshot.get_u64("version").unwrap_or_default();
+    let mut arguments = vec![
+        primary_key.to_string(),
+        action.to_owned(),
+        JsonValue::from(snapshot).to_string(),
+        JsonValue::from(diff).to_string(),
+        ctx.query_id().to_string(),
+    ];
+    let dialect = M::dialect();
+    let actor = if let Some(user_id) = acting_user() {
+        arguments.push(user_id.to_string());
+        dialect.placeholder(arguments.len())
+    } else {
+        "NULL".into()
+    };
+    let table_name = dialect.escape_table_name(&history_table_name::<M>());
+    let sql = format!(
+        "INSERT INTO {table_name} \
+            (model_id, version, action, snapshot, diff, query_id, actor) \
+            VALUES ({}, {version}, {}, {}, {}, {}, {actor});",
+        dialect.placeholder(1),
+        dialect.placeholder(2),
+        dialect.placeholder(3),
+        dialect.placeholder(4),
+        dialect.placeholder(5),
+    );
+    if let Err(err) = tx.connection().execute_with(&sql, &arguments).await {
+        let model_name = M::model_name();
+        tracing::error!(model_name, "fail to record a revision: {err}");
+        return Err(err);
+    }
+    Ok(ctx)
+}
+
+/// Lists the revisions of a model in the descending order of versions.
+pub(crate) async fn list_revisions<M: Schema>(
+    primary_key: &M::PrimaryKey,
+    version: Option<u64>,
+) -> Result<Vec<Map>, Error> {
+    create_history_table::<M>().await?;
+
+    let dialect = M::dialect();
+    let table_name = dialect.escape_table_name(&history_table_name::<M>());
+    let version_filter = version
+        .map(|version| format!(" AND version = {version}"))
+        .unwrap_or_default();
+    let placeholder = dialect.placeholder(1);
+    let sql = format!(
+        "SELECT * FROM {table_name} \
+            WHERE model_id = {placeholder}{version_filter} ORDER BY version DESC;"
+    );
+    let pool = M::acquire_reader().await?.pool();
+    let rows = pool.fetch_with(&sql, &[primary_key]).await?;
+    let mut revisions = Vec::with_capacity(rows.len());
+    for row in rows {
+        let mut revision = Map::decode_row(&row)?;
+        for key in ["snapshot", "diff"] {
+            if let Some(value) = revision.get_str(key) {
+                let value = serde_json::from_str::<JsonValue>(value)?;
+                revision.upsert(key, value);
+            }
+        }
+        revisions.push(revision);
+    }
+    Ok(revisions)
+}
+
+/// Computes the diff between two snapshots of a model.
+///
+/// Each changed field is mapped to an object with the `old` and `new` values.
+pub(crate) fn diff_snapshots(old: &Map, new: &Map) -> Map {
+    let mut diff = Map::new();
+    for (key, old_value) in old {
+        let new_value = new.get(key).unwrap_or(&JsonValue::Null);
+        if old_value != new_value {
+            let mut change = Map::new();
+            change.upsert("old", old_value.clone());
+            change.upsert("new", new_value.clone());
+            diff.upsert(key, change);
+        }
+    }
+    for (key, new_value) in new {
+        if !old.contains_key(key) && !new_value.is_null() {
+            let mut change = Map::new();
+            change.upsert("old", JsonValue::Null);
+            change.upsert("new", new_value.clone());
+            diff.upsert(key, change);
+        }
+    }
+    diff
+}
+
+thread_local! {
+    /// The acting user for the future being polled on the current thread.
+    static CURRENT_USER: RefCell<Option<Arc<str>>> = const { RefCell::new(None) };
+}
+
+/// Tables for which the history table has been created.
+static HISTORY_TABLES: LazyLock<Mutex<HashSet<&'static str>>> =
+    LazyLock::new(|| Mutex::new(HashSet::new()));
+
+#[cfg(test)]
+mod tests {
+    use zino_core::{Map, extension::JsonObjectExt};
+
+    #[test]
+    fn it_diffs_model_snapshots() {
+        let mut old = Map::new();
+        old.upsert("id", 1);
+        old.upsert("name", "alice");
+        old.upsert("status", "Active");
+        old.upsert("version", 3);
+
+        let mut new = old.clone();
+        new.upsert("status", "Locked");
+        new.upsert("version", 4);
+        new.upsert("description", "locked by admin");
+
+        let diff = super::diff_snapshots(&old, &new);
+        assert_eq!(diff.len(), 3);
+        assert!(!diff.contains_key("name"));
+
+        let change = diff
+            .get_object("status")
+            .expect("the status change should exist");
+        assert_eq!(change.get_str("old"), Some("Active"));
+        assert_eq!(change.get_str("new"), Some("Locked"));
+
+        let change = diff
+            .get_object("description")
+            .expect("the description change should exist");
+        assert!(change.get("old").is_some_and(|v| v.is_null()));
+    }
+}
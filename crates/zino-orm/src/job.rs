@@ -0,0 +1,235 @@
+use super::{ConnectionPool, DecodeRow, Executor};
+use std::{
+    sync::atomic::{AtomicBool, Ordering::Relaxed},
+    time::Duration,
+};
+use zino_core::{
+    BoxFuture, Map,
+    datetime::DateTime,
+    error::Error,
+    extension::JsonObjectExt,
+    schedule::{JobState, JobStore},
+};
+
+/// A job store backed by the database.
+///
+/// The job states and leases are persisted in the table `{table-prefix}job_states`,
+/// which is created automatically if it does not exist.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use zino_core::schedule::{AsyncJobScheduler, JobScheduler};
+/// use zino_orm::{DatabaseJobStore, GlobalPool};
+///
+/// let connection_pool = GlobalPool::get("main").expect("connection pool should exist");
+/// let mut scheduler = AsyncJobScheduler::with_store(DatabaseJobStore::new(connection_pool));
+/// let mut sync_scheduler = JobScheduler::with_store(DatabaseJobStore::new(connection_pool));
+/// ```
+#[derive(Debug)]
+pub struct DatabaseJobStore {
+    /// Connection pool.
+    connection_pool: &'static ConnectionPool,
+    /// Flag to indicate whether the table has been created.
+    table_created: AtomicBool,
+}
+
+impl DatabaseJobStore {
+    /// Creates a new instance for the connection pool.
+    #[inline]
+    pub fn new(connection_pool: &'static ConnectionPool) -> Self {
+        Self {
+            connection_pool,
+            table_created: AtomicBool::new(false),
+        }
+    }
+
+    /// Creates the table if it does not exist.
+    async fn create_table(&self) -> Result<(), Error> {
+        if self.table_created.load(Relaxed) {
+            return Ok(());
+        }
+
+        let dialect = self.connection_pool.dialect();
+
+        let table_name = dialect.escape_table_name(&job_table_name());
+        let sql = format!(
+            "CREATE TABLE IF NOT EXISTS {table_name} (\n  \
+                job_key VARCHAR(255) PRIMARY KEY,\n  \
+                last_tick BIGINT,\n  \
+                remaining_ticks BIGINT,\n  \
+                last_error TEXT,\n  \
+                lease_owner VARCHAR(255),\n  \
+                lease_expires_at BIGINT,\n  \
+                updated_at BIGINT\n);"
+        );
+        self.connection_pool.pool().execute(&sql).await?;
+        self.table_created.store(true, Relaxed);
+        Ok(())
+    }
+
+    /// Inserts a row for the job if it does not exist.
+    async fn insert_job(&self, job_key: &str) -> Result<(), Error> {
+        self.create_table().await?;
+
+        let dialect = self.connection_pool.dialect();
+
+        let table_name = dialect.escape_table_name(&job_table_name());
+        let placeholder = dialect.placeholder(1);
+        let sql = if dialect.is_mysql_compatible() {
+            format!("INSERT IGNORE INTO {table_name} (job_key) VALUES ({placeholder});")
+        } else {
+            format!(
+                "INSERT INTO {table_name} (job_key) VALUES ({placeholder}) \
+                    ON CONFLICT (job_key) DO NOTHING;"
+            )
+        };
+        self.connection_pool
+            .pool()
+            .execute_with(&sql, &[job_key])
+            .await?;
+        Ok(())
+    }
+}
+
+impl JobStore for DatabaseJobStore {
+    fn load<'a>(&'a self, job_key: &'a str) -> BoxFuture<'a, Result<Option<JobState>, Error>> {
+        Box::pin(async move {
+            self.create_table().await?;
+
+            let dialect = self.connection_pool.dialect();
+
+            let table_name = dialect.escape_table_name(&job_table_name());
+            let placeholder = dialect.placeholder(1);
+            let sql = format!(
+                "SELECT last_tick, remaining_ticks, last_error FROM {table_name} \
+                    WHERE job_key = {placeholder};"
+            );
+            let pool = self.connection_pool.pool();
+            let Some(row) = pool.fetch_optional_with(&sql, &[job_key]).await? else {
+                return Ok(None);
+            };
+
+            let data = Map::decode_row(&row)?;
+            let last_tick = data
+                .get_i64("last_tick")
+                .map(DateTime::from_timestamp_millis);
+            let remaining_ticks = data.get_usize("remaining_ticks");
+            let last_error = data.get_str("last_error").map(|s| s.to_owned());
+            Ok(Some(JobState::new(last_tick, remaining_ticks, last_error)))
+        })
+    }
+
+    fn save<'a>(
+        &'a self,
+        job_key: &'a str,
+        state: &'a JobState,
+    ) -> BoxFuture<'a, Result<(), Error>> {
+        Box::pin(async move {
+            self.insert_job(job_key).await?;
+
+            let dialect = self.connection_pool.dialect();
+
+            let table_name = dialect.escape_table_name(&job_table_name());
+            let last_tick = state
+                .last_tick()
+                .map(|dt| dt.timestamp_millis().to_string())
+                .unwrap_or_else(|| "NULL".to_owned());
+            let remaining_ticks = state
+                .remaining_ticks()
+                .map(|ticks| ticks.to_string())
+                .unwrap_or_else(|| "NULL".to_owned());
+            let updated_at = DateTime::now().timestamp_millis();
+            let mut arguments = Vec::with_capacity(2);
+            let last_error = if let Some(message) = state.last_error() {
+                arguments.push(message);
+                dialect.placeholder(arguments.len())
+            } else {
+                "NULL".into()
+            };
+            arguments.push(job_key);
+
+            let placeholder = dialect.placeholder(arguments.len());
+            let sql = format!(
+                "UPDATE {table_name} SET last_tick = {last_tick}, \
+                    remaining_ticks = {remaining_ticks}, last_error = {last_error}, \
+                    updated_at = {updated_at} WHERE job_key = {placeholder};"
+            );
+            self.connection_pool
+                .pool()
+                .execute_with(&sql, &arguments)
+                .await?;
+            Ok(())
+        })
+    }
+
+    fn acquire_lease<'a>(
+        &'a self,
+        job_key: &'a str,
+        owner: &'a str,
+        ttl: Duration,
+    ) -> BoxFuture<'a, Result<bool, Error>> {
+        Box::pin(async move {
+            self.insert_job(job_key).await?;
+
+            let dialect = self.connection_pool.dialect();
+
+            let table_name = dialect.escape_table_name(&job_table_name());
+            let now = DateTime::now();
+            let expires_at = (now + ttl).timestamp_millis();
+            let now = now.timestamp_millis();
+            let sql = format!(
+                "UPDATE {table_name} SET lease_owner = {}, lease_expires_at = {expires_at} \
+                    WHERE job_key = {} AND (lease_owner IS NULL OR lease_owner = {} \
+                    OR lease_expires_at < {now});",
+                dialect.placeholder(1),
+                dialect.placeholder(2),
+                dialect.placeholder(3),
+            );
+            let pool = self.connection_pool.pool();
+            let query_result = pool.execute_with(&sql, &[owner, job_key, owner]).await?;
+            if query_result.rows_affected() == 1 {
+                return Ok(true);
+            }
+
+            // MySQL reports zero affected rows if the lease is renewed with the same values,
+            // so the lease owner is read back to check whether the lease is held.
+            let sql = format!(
+                "SELECT lease_owner FROM {table_name} WHERE job_key = {};",
+                dialect.placeholder(1),
+            );
+            let Some(row) = pool.fetch_optional_with(&sql, &[job_key]).await? else {
+                return Ok(false);
+            };
+            let data = Map::decode_row(&row)?;
+            Ok(data.get_str("lease_owner") == Some(owner))
+        })
+    }
+
+    fn release_lease<'a>(
+        &'a self,
+        job_key: &'a str,
+        owner: &'a str,
+    ) -> BoxFuture<'a, Result<(), Error>> {
+        Box::pin(async move {
+            let dialect = self.connection_pool.dialect();
+            let table_name = dialect.escape_table_name(&job_table_name());
+            let sql = format!(
+                "UPDATE {table_name} SET lease_owner = NULL, lease_expires_at = NULL \
+                    WHERE job_key = {} AND lease_owner = {};",
+                dialect.placeholder(1),
+                dialect.placeholder(2),
+            );
+            self.connection_pool
+                .pool()
+                .execute_with(&sql, &[job_key, owner])
+                .await?;
+            Ok(())
+        })
+    }
+}
+
+/// Returns the name of the table for the job states.
+fn job_table_name() -> String {
+    [*super::TABLE_PREFIX, "job_states"].concat()
+}
@@ -1,5 +1,4 @@
-use super::{Entity, ModelColumn, Schema, query::QueryExt};
-use zino_core::model::Query;
+use super::{Entity, ModelColumn, Schema};
 
 /// Variants for `JOIN` types.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -224,8 +223,9 @@ impl JoinOn {
     /// Formats the join table.
     #[inline]
     fn format_join_table<M: Schema>() -> String {
-        let table_name = Query::escape_table_name(M::table_name());
-        let model_name = Query::escape_table_name(M::model_name());
+        let dialect = M::dialect();
+        let table_name = dialect.escape_table_name(M::table_name());
+        let model_name = dialect.escape_table_name(M::model_name());
         format!("{table_name} AS {model_name}")
     }
 
@@ -239,8 +239,8 @@ impl JoinOn {
     {
         let left_col = left_col.into_column_expr();
         let right_col = right_col.into_column_expr();
-        let left_col_field = Query::format_field(&left_col);
-        let right_col_field = Query::format_field(&right_col);
+        let left_col_field = E1::dialect().format_field(&left_col);
+        let right_col_field = E2::dialect().format_field(&right_col);
         let condition = format!("{left_col_field} {operator} {right_col_field}");
         self.conditions.push(condition);
         self
@@ -9,20 +9,31 @@ use std::sync::{
     OnceLock,
     atomic::{AtomicBool, AtomicUsize, Ordering::Relaxed},
 };
-use zino_core::{LazyLock, extension::TomlTableExt, state::State};
+use zino_core::{LazyLock, application::Shutdown, extension::TomlTableExt, state::State};
 
 mod accessor;
 mod aggregate;
+mod cache;
 mod column;
+mod cte;
+mod dialect;
+mod driver;
 mod entity;
 mod executor;
+#[cfg(test)]
+mod fixture;
 mod helper;
+mod history;
+mod job;
 mod join;
 mod manager;
+mod migration;
 mod mutation;
 mod pool;
 mod primary_key;
 mod query;
+mod relation;
+mod replica;
 mod row;
 mod schema;
 mod transaction;
@@ -31,19 +42,28 @@ mod window;
 
 pub use accessor::ModelAccessor;
 pub use aggregate::Aggregation;
+pub use cache::QueryCache;
 pub use column::EncodeColumn;
+pub use cte::CommonTableExpr;
+pub use dialect::Dialect;
+pub use driver::{DatabaseConnection, DatabasePool, DatabaseRow, DatabaseTransaction, QueryResult};
 pub use entity::{DerivedColumn, Entity, ModelColumn};
 pub use executor::Executor;
 pub use helper::ModelHelper;
+pub use history::ActingUser;
+pub use job::DatabaseJobStore;
 pub use join::JoinOn;
 pub use manager::PoolManager;
+pub use migration::{Migration, MigrationMode, Migrator};
 pub use mutation::MutationBuilder;
 pub use pool::ConnectionPool;
 pub use primary_key::PrimaryKey;
 pub use query::QueryBuilder;
+pub use relation::{Relation, RelationKind};
+pub use replica::{LoadBalancing, ReadYourWrites};
 pub use row::DecodeRow;
 pub use schema::Schema;
-pub use transaction::Transaction;
+pub use transaction::{IsolationLevel, Transaction, TransactionOptions};
 pub use value::IntoSqlValue;
 pub use window::Window;
 
@@ -53,70 +73,32 @@ mod decode;
 mod scalar;
 
 #[cfg(feature = "orm-sqlx")]
-pub use decode::{decode, decode_array, decode_decimal, decode_optional, decode_uuid};
+pub use decode::{
+    DecodeColumn, DecodeValue, decode, decode_array, decode_decimal, decode_optional, decode_uuid,
+};
 #[cfg(feature = "orm-sqlx")]
 pub use scalar::ScalarQuery;
-
-cfg_if::cfg_if! {
-    if #[cfg(any(feature = "orm-mariadb", feature = "orm-mysql", feature = "orm-tidb"))] {
-        mod mysql;
-
-        /// Driver name.
-        static DRIVER_NAME: &str = if cfg!(feature = "orm-mariadb") {
-            "mariadb"
-        } else if cfg!(feature = "orm-tidb") {
-            "tidb"
-        } else {
-            "mysql"
-        };
-
-        /// MySQL database driver.
-        pub type DatabaseDriver = sqlx::MySql;
-
-        /// MySQL database pool.
-        pub type DatabasePool = sqlx::MySqlPool;
-
-        /// MySQL database connection.
-        pub type DatabaseConnection = sqlx::MySqlConnection;
-
-        /// A single row from the MySQL database.
-        pub type DatabaseRow = sqlx::mysql::MySqlRow;
-    } else if #[cfg(feature = "orm-postgres")] {
-        mod postgres;
-
-        /// Driver name.
-        static DRIVER_NAME: &str = "postgres";
-
-        /// PostgreSQL database driver.
-        pub type DatabaseDriver = sqlx::Postgres;
-
-        /// PostgreSQL database pool.
-        pub type DatabasePool = sqlx::PgPool;
-
-        /// PostgreSQL database connection.
-        pub type DatabaseConnection = sqlx::PgConnection;
-
-        /// A single row from the PostgreSQL database.
-        pub type DatabaseRow = sqlx::postgres::PgRow;
-    } else {
-        mod sqlite;
-
-        /// Driver name.
-        static DRIVER_NAME: &str = "sqlite";
-
-        /// SQLite database driver.
-        pub type DatabaseDriver = sqlx::Sqlite;
-
-        /// SQLite database pool.
-        pub type DatabasePool = sqlx::SqlitePool;
-
-        /// SQLite database connection.
-        pub type DatabaseConnection = sqlx::SqliteConnection;
-
-        /// A single row from the SQLite database.
-        pub type DatabaseRow = sqlx::sqlite::SqliteRow;
-    }
-}
+#[cfg(feature = "orm-sqlx")]
+pub use transaction::TransactionScope;
+
+#[cfg(any(feature = "orm-mariadb", feature = "orm-mysql", feature = "orm-tidb"))]
+mod mysql;
+#[cfg(feature = "orm-postgres")]
+mod postgres;
+mod sqlite;
+
+/// Driver name of the default dialect.
+static DRIVER_NAME: &str = if cfg!(feature = "orm-mariadb") {
+    "mariadb"
+} else if cfg!(feature = "orm-tidb") {
+    "tidb"
+} else if cfg!(feature = "orm-mysql") {
+    "mysql"
+} else if cfg!(feature = "orm-postgres") {
+    "postgres"
+} else {
+    "sqlite"
+};
 
 /// A list of database connection pools.
 #[derive(Debug)]
@@ -124,9 +106,14 @@ struct ConnectionPools(SmallVec<[ConnectionPool; 4]>);
 
 impl ConnectionPools {
     /// Returns a connection pool with the specific name.
+    /// The read replicas are excluded.
     pub(crate) fn get_pool(&self, name: &str) -> Option<&ConnectionPool> {
         let mut pool = None;
-        for cp in self.0.iter().filter(|cp| cp.name() == name) {
+        for cp in self
+            .0
+            .iter()
+            .filter(|cp| cp.name() == name && !cp.is_replica())
+        {
             if cp.is_available() {
                 return Some(cp);
             } else {
@@ -135,6 +122,32 @@ impl ConnectionPools {
         }
         pool
     }
+
+    /// Returns a connection pool for reads with the specific name.
+    ///
+    /// The read replicas are selected by the load-balancing strategy,
+    /// and it falls back to the writer if there are no eligible replicas.
+    pub(crate) fn get_reader(&self, name: &str) -> Option<&ConnectionPool> {
+        if replica::has_written(name) {
+            return self.get_pool(name);
+        }
+
+        let max_replication_lag = replica::max_replication_lag();
+        let replicas = self
+            .0
+            .iter()
+            .filter(|cp| cp.name() == name && cp.is_replica())
+            .collect::<SmallVec<[_; 4]>>();
+        let candidates = replicas
+            .iter()
+            .copied()
+            .filter(|cp| cp.is_available() && cp.replication_lag() <= max_replication_lag)
+            .collect::<SmallVec<[_; 4]>>();
+        replica::load_balancing()
+            .select(&candidates)
+            .or_else(|| self.get_pool(name))
+            .or_else(|| replicas.first().copied())
+    }
 }
 
 /// Global access to the shared connection pools.
@@ -148,6 +161,19 @@ impl GlobalPool {
         SHARED_CONNECTION_POOLS.get_pool(name)
     }
 
+    /// Gets the connection pool for reads on the specific service,
+    /// which may be one of the read replicas.
+    #[inline]
+    pub fn get_reader(name: &str) -> Option<&'static ConnectionPool> {
+        SHARED_CONNECTION_POOLS.get_reader(name)
+    }
+
+    /// Records a write on the specific service for the "read-your-writes" consistency.
+    #[inline]
+    pub fn record_write(name: &'static str) {
+        replica::record_write(name);
+    }
+
     /// Iterates over the shared connection pools and
     /// attempts to establish a database connection for each of them.
     #[inline]
@@ -157,6 +183,57 @@ impl GlobalPool {
         }
     }
 
+    /// Iterates over the read replicas to refresh the availability, latency and replication lag.
+    #[inline]
+    pub async fn check_replicas() {
+        for cp in SHARED_CONNECTION_POOLS
+            .0
+            .iter()
+            .filter(|cp| cp.is_replica())
+        {
+            cp.check_availability().await;
+        }
+    }
+
+    /// Refreshes the read replicas every `replica-check-interval`
+    /// until the graceful shutdown is triggered.
+    /// It returns immediately if there are no read replicas.
+    pub async fn monitor_replicas() {
+        if !SHARED_CONNECTION_POOLS.0.iter().any(|cp| cp.is_replica()) {
+            return;
+        }
+
+        let period = replica::check_interval();
+        while !Shutdown::is_triggered() {
+            tokio::select! {
+                _ = tokio::time::sleep(period) => Self::check_replicas().await,
+                _ = Shutdown::wait() => {},
+            }
+        }
+    }
+
+    /// Iterates over the shared connection pools and runs the versioned migrations
+    /// for each of them according to the migration mode.
+    pub async fn migrate_all() {
+        for cp in SHARED_CONNECTION_POOLS.0.iter() {
+            if cp.is_replica() || cp.migration_mode() == MigrationMode::Disabled {
+                continue;
+            }
+
+            let name = cp.name();
+            match Migrator::new(cp).load_migrations() {
+                Ok(migrator) => {
+                    if let Err(err) = migrator.run().await {
+                        tracing::error!("fail to run migrations for the `{name}` service: {err}");
+                    }
+                }
+                Err(err) => {
+                    tracing::error!("fail to load migrations for the `{name}` service: {err}");
+                }
+            }
+        }
+    }
+
     /// Shuts down the shared connection pools to ensure all connections are gracefully closed.
     #[inline]
     pub async fn close_all() {
@@ -171,6 +248,7 @@ static SHARED_CONNECTION_POOLS: LazyLock<ConnectionPools> = LazyLock::new(|| {
     let config = State::shared().config();
     let mut database_type = DRIVER_NAME;
     let mut disable_auto_migration = false;
+    let mut migration_mode = MigrationMode::Disabled;
     if let Some(database) = config.get_table("database") {
         if let Some(driver) = database.get_str("type") {
             database_type = driver;
@@ -186,38 +264,65 @@ static SHARED_CONNECTION_POOLS: LazyLock<ConnectionPools> = LazyLock::new(|| {
         if let Some(auto_migration) = database.get_bool("auto-migration") {
             disable_auto_migration = !auto_migration;
         }
+        if let Some(mode) = database.get_str("migration").and_then(MigrationMode::parse) {
+            migration_mode = mode;
+        }
         if let Some(debug_only) = database.get_bool("debug-only") {
             DEBUG_ONLY.store(debug_only, Relaxed);
         }
+        if let Some(strategy) = database
+            .get_str("load-balancing")
+            .and_then(LoadBalancing::parse)
+        {
+            replica::set_load_balancing(strategy);
+        }
+        if let Some(lag) = database.get_duration("max-replication-lag") {
+            replica::set_max_replication_lag(lag);
+        }
+        if let Some(interval) = database.get_duration("replica-check-interval") {
+            replica::set_check_interval(interval);
+        }
+        if let Some(read_your_writes) = database.get_bool("read-your-writes") {
+            replica::set_read_your_writes(read_your_writes);
+        }
     }
 
     // Database connection pools.
-    let databases = config.get_array(database_type).unwrap_or_else(|| {
-        panic!(
-            "field `{database_type}` should be an array of tables; \
-                please use `[[{database_type}]]` to configure a list of database services"
-        )
-    });
-    let pools = databases
-        .iter()
-        .filter_map(|v| v.as_table())
-        .map(|config| {
-            let connection_pool = ConnectionPool::with_config(config);
+    let mut pools = SmallVec::new();
+    for dialect in Dialect::ALL {
+        let driver = dialect.as_str();
+        let Some(databases) = config.get_array(driver) else {
+            continue;
+        };
+        if !dialect.is_supported() {
+            tracing::error!(
+                "database services in `[[{driver}]]` are ignored since the driver is not enabled"
+            );
+            continue;
+        }
+        pools.extend(databases.iter().filter_map(|v| v.as_table()).map(|config| {
+            let mut connection_pool = ConnectionPool::with_dialect_config(dialect, config);
             if disable_auto_migration {
                 connection_pool.disable_auto_migration();
             }
+            if !config.contains_key("migration") {
+                connection_pool.set_migration_mode(migration_mode);
+            }
             connection_pool
-        })
-        .collect();
-    let driver = DRIVER_NAME;
-    if database_type == driver {
-        tracing::warn!(driver, "connect to database services lazily");
-    } else {
-        tracing::error!(
-            driver,
-            "invalid database type `{database_type}` for the driver `{driver}`"
+        }));
+    }
+    if pools.is_empty() {
+        panic!(
+            "field `{database_type}` should be an array of tables; \
+                please use `[[{database_type}]]` to configure a list of database services"
         );
     }
+
+    if Dialect::parse(database_type).is_some_and(|dialect| dialect.is_supported()) {
+        tracing::warn!(database_type, "connect to database services lazily");
+    } else {
+        tracing::error!("invalid database type `{database_type}` for the enabled drivers");
+    }
     ConnectionPools(pools)
 });
 
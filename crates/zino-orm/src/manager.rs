@@ -1,12 +1,23 @@
-use super::{DatabasePool, pool::ConnectionPool};
-use std::time::Duration;
+use super::{DatabasePool, Dialect, migration::MigrationMode, pool::ConnectionPool};
+use std::time::{Duration, Instant};
 use toml::value::Table;
 use zino_core::extension::TomlTableExt;
 
 /// A manager of the connection pool.
 pub trait PoolManager {
-    /// Connects lazily to the database according to the config.
-    fn with_config(config: &'static Table) -> Self;
+    /// Connects lazily to the database according to the config,
+    /// using the driver for the SQL dialect.
+    fn with_dialect_config(dialect: Dialect, config: &'static Table) -> Self;
+
+    /// Connects lazily to the database according to the config,
+    /// using the driver for the default dialect.
+    #[inline]
+    fn with_config(config: &'static Table) -> Self
+    where
+        Self: Sized,
+    {
+        Self::with_dialect_config(Dialect::default(), config)
+    }
 
     /// Checks the availability of the connection pool.
     async fn check_availability(&self) -> bool;
@@ -17,95 +28,85 @@ pub trait PoolManager {
 
 #[cfg(feature = "orm-sqlx")]
 impl PoolManager for ConnectionPool<DatabasePool> {
-    fn with_config(config: &'static Table) -> Self {
-        use sqlx::{Connection, Executor, pool::PoolOptions};
-
+    fn with_dialect_config(dialect: Dialect, config: &'static Table) -> Self {
         let name = config.get_str("name").unwrap_or("main");
-
-        // Connect options.
         let database = config
             .get_str("database")
             .expect("field `database` should be a str");
-        let mut connect_options = new_connect_options(database, config);
-        if let Some(statement_cache_capacity) = config.get_usize("statement-cache-capacity") {
-            connect_options = connect_options.statement_cache_capacity(statement_cache_capacity);
+        let replica = config.get_str("role") == Some("replica");
+        let pool = match dialect {
+            #[cfg(any(feature = "orm-mariadb", feature = "orm-mysql", feature = "orm-tidb"))]
+            Dialect::MySql | Dialect::MariaDb | Dialect::TiDb => {
+                let connect_options = new_mysql_connect_options(database, config);
+                let pool =
+                    new_pool_options(name, dialect, config).connect_lazy_with(connect_options);
+                match dialect {
+                    Dialect::MariaDb => DatabasePool::MariaDb(pool),
+                    Dialect::TiDb => DatabasePool::TiDb(pool),
+                    _ => DatabasePool::MySql(pool),
+                }
+            }
+            #[cfg(feature = "orm-postgres")]
+            Dialect::Postgres => {
+                let connect_options = new_postgres_connect_options(database, config);
+                let pool =
+                    new_pool_options(name, dialect, config).connect_lazy_with(connect_options);
+                DatabasePool::Postgres(pool)
+            }
+            Dialect::Sqlite => {
+                let connect_options = new_sqlite_connect_options(database, config);
+                let pool =
+                    new_pool_options(name, dialect, config).connect_lazy_with(connect_options);
+                DatabasePool::Sqlite(pool)
+            }
+            #[allow(unreachable_patterns)]
+            _ => panic!(
+                "the driver for the `{}` dialect is not enabled",
+                dialect.as_str()
+            ),
+        };
+        let mut connection_pool = Self::new(name, database, pool);
+        connection_pool.set_dialect(dialect);
+        if replica {
+            connection_pool.set_replica();
         }
-
-        // Pool options.
-        let max_connections = config.get_u32("max-connections").unwrap_or(16);
-        let min_connections = config.get_u32("min-connections").unwrap_or(1);
-        let max_lifetime = config
-            .get_duration("max-lifetime")
-            .unwrap_or_else(|| Duration::from_secs(24 * 60 * 60));
-        let idle_timeout = config
-            .get_duration("idle-timeout")
-            .unwrap_or_else(|| Duration::from_secs(60 * 60));
-        let acquire_timeout = config
-            .get_duration("acquire-timeout")
-            .unwrap_or_else(|| Duration::from_secs(60));
-        let health_check_interval = config.get_u64("health-check-interval").unwrap_or(60);
-        let pool = PoolOptions::<super::DatabaseDriver>::new()
-            .max_connections(max_connections)
-            .min_connections(min_connections)
-            .max_lifetime(max_lifetime)
-            .idle_timeout(idle_timeout)
-            .acquire_timeout(acquire_timeout)
-            .test_before_acquire(false)
-            .before_acquire(move |conn, meta| {
-                Box::pin(async move {
-                    if meta.idle_for.as_secs() > health_check_interval
-                        && let Some(cp) = super::GlobalPool::get(name)
-                    {
-                        if let Err(err) = conn.ping().await {
-                            let name = cp.name();
-                            cp.store_availability(false);
-                            tracing::error!(
-                                "fail to ping the database for the `{name}` service: {err}"
-                            );
-                            return Err(err);
-                        } else {
-                            cp.store_availability(true);
-                        }
-                    }
-                    Ok(true)
-                })
-            })
-            .after_connect(|conn, _meta| {
-                Box::pin(async move {
-                    if let Some(time_zone) = super::TIME_ZONE.get() {
-                        if cfg!(any(
-                            feature = "orm-mariadb",
-                            feature = "orm-mysql",
-                            feature = "orm-tidb"
-                        )) {
-                            let sql = format!("SET time_zone = '{time_zone}';");
-                            conn.execute(sql.as_str()).await?;
-                        } else if cfg!(feature = "orm-postgres") {
-                            let sql = format!("SET TIME ZONE '{time_zone}';");
-                            conn.execute(sql.as_str()).await?;
-                        }
-                    }
-                    Ok(())
-                })
-            })
-            .connect_lazy_with(connect_options);
-        let connection_pool = Self::new(name, database, pool);
         if config.get_bool("auto-migration").is_some_and(|b| !b) {
             connection_pool.disable_auto_migration();
         }
+        if let Some(mode) = config.get_str("migration").and_then(MigrationMode::parse) {
+            connection_pool.set_migration_mode(mode);
+        }
         connection_pool
     }
 
     async fn check_availability(&self) -> bool {
         let name = self.name();
-        if let Err(err) = self.pool().acquire().await {
-            tracing::error!("fail to acquire a connection for the `{name}` service: {err}");
-            self.store_availability(false);
-            false
-        } else {
-            tracing::info!("acquire a connection for the `{name}` service sucessfully");
-            self.store_availability(true);
-            true
+        let start_time = Instant::now();
+        match self.pool().acquire().await {
+            Ok(mut conn) => {
+                self.record_latency(start_time.elapsed());
+                if self.is_replica() {
+                    match fetch_replication_lag(&mut conn, self.dialect()).await {
+                        Ok(Some(lag)) => self.store_replication_lag(lag),
+                        Ok(None) => {
+                            tracing::error!("the replication of the `{name}` service has stopped");
+                            self.store_availability(false);
+                            return false;
+                        }
+                        Err(err) => tracing::warn!(
+                            "fail to check the replication lag for the `{name}` service: {err}"
+                        ),
+                    }
+                }
+                tracing::info!("acquire a connection for the `{name}` service sucessfully");
+                self.store_availability(true);
+                true
+            }
+            Err(err) => {
+                tracing::error!("fail to acquire a connection for the `{name}` service: {err}");
+                self.store_availability(false);
+                false
+            }
         }
     }
 
@@ -116,78 +117,287 @@ impl PoolManager for ConnectionPool<DatabasePool> {
     }
 }
 
-cfg_if::cfg_if! {
-    if #[cfg(any(feature = "orm-mariadb", feature = "orm-mysql", feature = "orm-tidb"))] {
-        use sqlx::mysql::{MySqlConnectOptions, MySqlSslMode};
-        use zino_core::state::State;
-
-        /// Options and flags which can be used to configure a MySQL connection.
-        fn new_connect_options(database: &'static str, config: &'static Table) -> MySqlConnectOptions {
-            let username = config
-                .get_str("username")
-                .expect("field `username` should be a str");
-            let password =
-                State::decrypt_password(config).expect("field `password` should be a str");
-
-            let mut connect_options = MySqlConnectOptions::new()
-                .database(database)
-                .username(username)
-                .password(password.as_ref());
-            if let Some(host) = config.get_str("host") {
-                connect_options = connect_options.host(host);
-            }
-            if let Some(port) = config.get_u16("port") {
-                connect_options = connect_options.port(port);
-            }
-            if let Some(ssl_mode) = config.get_str("ssl-mode").and_then(|s| s.parse().ok()) {
-                connect_options = connect_options.ssl_mode(ssl_mode);
-            } else {
-                connect_options = connect_options.ssl_mode(MySqlSslMode::Disabled);
-            }
-            connect_options
-        }
-    } else if #[cfg(feature = "orm-postgres")] {
-        use sqlx::postgres::{PgConnectOptions, PgSslMode};
-        use zino_core::state::State;
-
-        /// Options and flags which can be used to configure a PostgreSQL connection.
-        fn new_connect_options(database: &'static str, config: &'static Table) -> PgConnectOptions {
-            let username = config
-                .get_str("username")
-                .expect("field `username` should be a str");
-            let password =
-                State::decrypt_password(config).expect("field `password` should be a str");
-
-            let mut connect_options = PgConnectOptions::new()
-                .database(database)
-                .username(username)
-                .password(password.as_ref());
-            if let Some(host) = config.get_str("host") {
-                connect_options = connect_options.host(host);
-            }
-            if let Some(port) = config.get_u16("port") {
-                connect_options = connect_options.port(port);
-            }
-            if let Some(ssl_mode) = config.get_str("ssl-mode").and_then(|s| s.parse().ok()) {
-                connect_options = connect_options.ssl_mode(ssl_mode);
-            } else {
-                connect_options = connect_options.ssl_mode(PgSslMode::Disable);
-            }
-            connect_options
-        }
+/// Fetches the replication lag of a read replica.
+///
+/// It returns `None` if the replica is not replicating from the source.
+/// The query is executed with `sqlx` directly to bypass the reconnection of the `Executor`,
+/// which calls `GlobalPool::connect_all()` and then this function again.
+#[cfg(feature = "orm-sqlx")]
+async fn fetch_replication_lag(
+    conn: &mut super::DatabaseConnection,
+    dialect: Dialect,
+) -> Result<Option<Duration>, zino_core::error::Error> {
+    if dialect == Dialect::TiDb {
+        Ok(Some(Duration::ZERO))
+    } else if dialect.is_mysql_compatible() {
+        let sql = "SHOW REPLICA STATUS;";
+        let status = fetch_status(conn, sql).await?;
+        Ok(status.and_then(|status| parse_mysql_replication_lag(&status)))
+    } else if dialect == Dialect::Postgres {
+        let sql = "
+            SELECT CASE
+                WHEN NOT pg_is_in_recovery() OR pg_last_wal_receive_lsn() = pg_last_wal_replay_lsn()
+                    THEN 0
+                ELSE EXTRACT(EPOCH FROM now() - pg_last_xact_replay_timestamp())
+            END::float8 AS replication_lag;
+        ";
+        let status = fetch_status(conn, sql).await?;
+        Ok(status.and_then(|status| parse_postgres_replication_lag(&status)))
     } else {
-        use sqlx::sqlite::SqliteConnectOptions;
-        use zino_core::application::{Agent, Application};
-
-        /// Options and flags which can be used to configure a SQLite connection.
-        fn new_connect_options(database: &'static str, config: &'static Table) -> SqliteConnectOptions {
-            let mut connect_options = SqliteConnectOptions::new().create_if_missing(true);
-            if let Some(read_only) = config.get_bool("read-only") {
-                connect_options = connect_options.read_only(read_only);
-            }
+        Ok(Some(Duration::ZERO))
+    }
+}
+
+/// Fetches the status of the database server as a map.
+#[cfg(feature = "orm-sqlx")]
+async fn fetch_status(
+    conn: &mut super::DatabaseConnection,
+    sql: &str,
+) -> Result<Option<zino_core::Map>, zino_core::error::Error> {
+    use super::{
+        DatabaseConnection,
+        driver::{RowExt, dispatch},
+    };
+
+    let dialect = conn.dialect();
+    dispatch!(conn, DatabaseConnection(conn) => {
+        sqlx::query(sql)
+            .fetch_optional(&mut **conn)
+            .await?
+            .map(|row| row.decode_map(dialect))
+            .transpose()
+    })
+}
+
+/// Parses the replication lag from the output of `SHOW REPLICA STATUS` in MySQL.
+/// A `NULL` value of `Seconds_Behind_Source` means that the replication has stopped.
+#[cfg(feature = "orm-sqlx")]
+fn parse_mysql_replication_lag(status: &zino_core::Map) -> Option<Duration> {
+    use zino_core::extension::JsonObjectExt;
+
+    status
+        .get_u64("Seconds_Behind_Source")
+        .or_else(|| status.get_u64("Seconds_Behind_Master"))
+        .map(Duration::from_secs)
+}
+
+/// Parses the replication lag from the status of a PostgreSQL standby server.
+/// A `NULL` value means that no transaction has been replayed since the recovery.
+#[cfg(feature = "orm-sqlx")]
+fn parse_postgres_replication_lag(status: &zino_core::Map) -> Option<Duration> {
+    use zino_core::extension::JsonObjectExt;
 
-            let database_path = Agent::parse_path(database);
-            connect_options.filename(database_path)
+    status
+        .get_f64("replication_lag")
+        .filter(|secs| secs.is_finite())
+        .map(|secs| Duration::from_secs_f64(secs.max(0.0)))
+}
+
+/// Options and flags which can be used to configure a connection pool.
+#[cfg(feature = "orm-sqlx")]
+fn new_pool_options<DB>(
+    name: &'static str,
+    dialect: Dialect,
+    config: &'static Table,
+) -> sqlx::pool::PoolOptions<DB>
+where
+    DB: sqlx::Database,
+    for<'c> &'c mut DB::Connection: sqlx::Executor<'c, Database = DB>,
+{
+    use sqlx::{Connection, Executor};
+
+    let max_connections = config.get_u32("max-connections").unwrap_or(16);
+    let min_connections = config.get_u32("min-connections").unwrap_or(1);
+    let max_lifetime = config
+        .get_duration("max-lifetime")
+        .unwrap_or_else(|| Duration::from_secs(24 * 60 * 60));
+    let idle_timeout = config
+        .get_duration("idle-timeout")
+        .unwrap_or_else(|| Duration::from_secs(60 * 60));
+    let acquire_timeout = config
+        .get_duration("acquire-timeout")
+        .unwrap_or_else(|| Duration::from_secs(60));
+    let health_check_interval = config.get_u64("health-check-interval").unwrap_or(60);
+    let replica = config.get_str("role") == Some("replica");
+    let time_zone_statement = super::TIME_ZONE.get().and_then(|time_zone| {
+        if dialect.is_mysql_compatible() {
+            Some(format!("SET time_zone = '{time_zone}';"))
+        } else if dialect == Dialect::Postgres {
+            Some(format!("SET TIME ZONE '{time_zone}';"))
+        } else {
+            None
         }
+    });
+    sqlx::pool::PoolOptions::<DB>::new()
+        .max_connections(max_connections)
+        .min_connections(min_connections)
+        .max_lifetime(max_lifetime)
+        .idle_timeout(idle_timeout)
+        .acquire_timeout(acquire_timeout)
+        .test_before_acquire(false)
+        .before_acquire(move |conn, meta| {
+            Box::pin(async move {
+                // The read replicas are checked by `GlobalPool::check_replicas()`
+                if !replica
+                    && meta.idle_for.as_secs() > health_check_interval
+                    && let Some(cp) = super::GlobalPool::get(name)
+                {
+                    let start_time = Instant::now();
+                    if let Err(err) = conn.ping().await {
+                        let name = cp.name();
+                        cp.store_availability(false);
+                        tracing::error!(
+                            "fail to ping the database for the `{name}` service: {err}"
+                        );
+                        return Err(err);
+                    } else {
+                        cp.record_latency(start_time.elapsed());
+                        cp.store_availability(true);
+                    }
+                }
+                Ok(true)
+            })
+        })
+        .after_connect(move |conn, _meta| {
+            let time_zone_statement = time_zone_statement.clone();
+            Box::pin(async move {
+                if let Some(sql) = time_zone_statement {
+                    conn.execute(sql.as_str()).await?;
+                }
+                Ok(())
+            })
+        })
+}
+
+/// Options and flags which can be used to configure a MySQL connection.
+#[cfg(any(feature = "orm-mariadb", feature = "orm-mysql", feature = "orm-tidb"))]
+fn new_mysql_connect_options(
+    database: &'static str,
+    config: &'static Table,
+) -> sqlx::mysql::MySqlConnectOptions {
+    use sqlx::mysql::{MySqlConnectOptions, MySqlSslMode};
+    use zino_core::state::State;
+
+    let username = config
+        .get_str("username")
+        .expect("field `username` should be a str");
+    let password = State::decrypt_password(config).expect("field `password` should be a str");
+
+    let mut connect_options = MySqlConnectOptions::new()
+        .database(database)
+        .username(username)
+        .password(password.as_ref());
+    if let Some(host) = config.get_str("host") {
+        connect_options = connect_options.host(host);
+    }
+    if let Some(port) = config.get_u16("port") {
+        connect_options = connect_options.port(port);
+    }
+    if let Some(ssl_mode) = config.get_str("ssl-mode").and_then(|s| s.parse().ok()) {
+        connect_options = connect_options.ssl_mode(ssl_mode);
+    } else {
+        connect_options = connect_options.ssl_mode(MySqlSslMode::Disabled);
+    }
+    if let Some(capacity) = config.get_usize("statement-cache-capacity") {
+        connect_options = connect_options.statement_cache_capacity(capacity);
+    }
+    connect_options
+}
+
+/// Options and flags which can be used to configure a PostgreSQL connection.
+#[cfg(feature = "orm-postgres")]
+fn new_postgres_connect_options(
+    database: &'static str,
+    config: &'static Table,
+) -> sqlx::postgres::PgConnectOptions {
+    use sqlx::postgres::{PgConnectOptions, PgSslMode};
+    use zino_core::state::State;
+
+    let username = config
+        .get_str("username")
+        .expect("field `username` should be a str");
+    let password = State::decrypt_password(config).expect("field `password` should be a str");
+
+    let mut connect_options = PgConnectOptions::new()
+        .database(database)
+        .username(username)
+        .password(password.as_ref());
+    if let Some(host) = config.get_str("host") {
+        connect_options = connect_options.host(host);
+    }
+    if let Some(port) = config.get_u16("port") {
+        connect_options = connect_options.port(port);
+    }
+    if let Some(ssl_mode) = config.get_str("ssl-mode").and_then(|s| s.parse().ok()) {
+        connect_options = connect_options.ssl_mode(ssl_mode);
+    } else {
+        connect_options = connect_options.ssl_mode(PgSslMode::Disable);
+    }
+    if let Some(capacity) = config.get_usize("statement-cache-capacity") {
+        connect_options = connect_options.statement_cache_capacity(capacity);
+    }
+    connect_options
+}
+
+/// Options and flags which can be used to configure a SQLite connection.
+#[cfg(feature = "orm-sqlx")]
+fn new_sqlite_connect_options(
+    database: &'static str,
+    config: &'static Table,
+) -> sqlx::sqlite::SqliteConnectOptions {
+    use sqlx::sqlite::SqliteConnectOptions;
+    use zino_core::application::{Agent, Application};
+
+    let mut connect_options = SqliteConnectOptions::new().create_if_missing(true);
+    if let Some(read_only) = config.get_bool("read-only") {
+        connect_options = connect_options.read_only(read_only);
+    }
+    if let Some(capacity) = config.get_usize("statement-cache-capacity") {
+        connect_options = connect_options.statement_cache_capacity(capacity);
+    }
+
+    let database_path = Agent::parse_path(database);
+    connect_options.filename(database_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use zino_core::{JsonValue, Map, extension::JsonObjectExt};
+
+    #[test]
+    fn it_parses_mysql_replication_lag() {
+        let mut status = Map::new();
+        status.upsert("Seconds_Behind_Source", 3);
+        assert_eq!(
+            super::parse_mysql_replication_lag(&status),
+            Some(Duration::from_secs(3))
+        );
+
+        let mut status = Map::new();
+        status.upsert("Seconds_Behind_Master", 0);
+        assert_eq!(
+            super::parse_mysql_replication_lag(&status),
+            Some(Duration::ZERO)
+        );
+
+        // The replication has stopped.
+        let mut status = Map::new();
+        status.upsert("Seconds_Behind_Source", JsonValue::Null);
+        assert_eq!(super::parse_mysql_replication_lag(&status), None);
+    }
+
+    #[test]
+    fn it_parses_postgres_replication_lag() {
+        let mut status = Map::new();
+        status.upsert("replication_lag", 1.5);
+        assert_eq!(
+            super::parse_postgres_replication_lag(&status),
+            Some(Duration::from_millis(1500))
+        );
+
+        let mut status = Map::new();
+        status.upsert("replication_lag", JsonValue::Null);
+        assert_eq!(super::parse_postgres_replication_lag(&status), None);
     }
 }
@@ -0,0 +1,1158 @@
+//! Versioned and reversible schema migrations.
+//!
+//! A migration is a pair of ordered `up` and `down` SQL statements identified by a version.
+//! Migrations can be generated from the differences between [`Schema::columns()`]
+//! and the table definitions in the database, written to the files
+//! `{version}_{name}.up.sql` and `{version}_{name}.down.sql`,
+//! and applied or rolled back by a [`Migrator`].
+//!
+//! ```rust,ignore
+//! use zino_orm::{GlobalPool, Migration, Migrator, Schema};
+//!
+//! let mut migration = Migration::new("sync_users_and_tags");
+//! migration.merge(User::generate_migration().await?);
+//! migration.merge(Tag::generate_migration().await?);
+//!
+//! let connection_pool = GlobalPool::get("main").ok_or_else(|| warn!("no pool"))?;
+//! let mut migrator = Migrator::new(connection_pool).load_migrations()?;
+//! migrator.write_migration(migration)?;
+//!
+//! let statements = migrator.dry_run().await?;
+//! let versions = migrator.apply().await?;
+//! let versions = migrator.rollback(1).await?;
+//! ```
+//!
+//! # Configuration
+//!
+//! Migrations can also be applied when the application starts
+//! by setting the `migration` field in the `[database]` table or in a `[[database-type]]` table,
+//! which disables the implicit `auto-migration` for the connection pool.
+//!
+//! ```toml
+//! [database]
+//! type = "postgres"
+//! migration = "apply" # supported values: "apply" | "dry-run" | "disabled"
+//! migration-dir = "./migrations"
+//! ```
+
+use super::{
+    ConnectionPool, DecodeRow, Dialect, EncodeColumn, Executor, Schema, column::ColumnExt,
+    query::QueryExt,
+};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use zino_core::{
+    Map,
+    application::{Agent, Application},
+    bail,
+    datetime::DateTime,
+    encoding::hex,
+    error::Error,
+    extension::{JsonObjectExt, TomlTableExt},
+    model::{Column, Query},
+    state::State,
+    warn,
+};
+
+/// A versioned schema migration.
+#[derive(Debug, Clone, Default)]
+pub struct Migration {
+    /// Version.
+    version: String,
+    /// Name.
+    name: String,
+    /// Statements to apply the migration.
+    up: Vec<String>,
+    /// Statements to revert the migration.
+    down: Vec<String>,
+}
+
+impl Migration {
+    /// Creates a new instance with a version derived from the current time.
+    #[inline]
+    pub fn new(name: impl Into<String>) -> Self {
+        let version = DateTime::now().format("%Y%m%d%H%M%S");
+        Self::with_version(version, name)
+    }
+
+    /// Creates a new instance with the specific version.
+    #[inline]
+    pub fn with_version(version: impl Into<String>, name: impl Into<String>) -> Self {
+        Self {
+            version: version.into(),
+            name: name.into(),
+            up: Vec::new(),
+            down: Vec::new(),
+        }
+    }
+
+    /// Adds a pair of statements to apply and revert the migration.
+    /// The `down` statements are executed in the reversed order.
+    #[inline]
+    pub fn add_statements(&mut self, up: impl Into<String>, down: impl Into<String>) {
+        self.up.push(up.into());
+        self.down.insert(0, down.into());
+    }
+
+    /// Adds a statement to apply the migration.
+    #[inline]
+    pub fn add_up_statement(&mut self, up: impl Into<String>) {
+        self.up.push(up.into());
+    }
+
+    /// Adds a statement to revert the migration.
+    #[inline]
+    pub fn add_down_statement(&mut self, down: impl Into<String>) {
+        self.down.insert(0, down.into());
+    }
+
+    /// Merges the statements of another migration.
+    #[inline]
+    pub fn merge(&mut self, mut other: Migration) {
+        self.up.append(&mut other.up);
+        other.down.append(&mut self.down);
+        self.down = other.down;
+    }
+
+    /// Returns the version.
+    #[inline]
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    /// Returns the name.
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the statements to apply the migration.
+    #[inline]
+    pub fn up_statements(&self) -> &[String] {
+        &self.up
+    }
+
+    /// Returns the statements to revert the migration.
+    #[inline]
+    pub fn down_statements(&self) -> &[String] {
+        &self.down
+    }
+
+    /// Returns `true` if the migration does not contain any statements to apply.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.up.is_empty()
+    }
+
+    /// Returns the file stem of the migration.
+    #[inline]
+    pub fn file_stem(&self) -> String {
+        format!("{}_{}", self.version, self.name)
+    }
+
+    /// Returns the SQL to apply the migration.
+    #[inline]
+    pub fn up_sql(&self) -> String {
+        format_statements(&self.up)
+    }
+
+    /// Returns the SQL to revert the migration.
+    #[inline]
+    pub fn down_sql(&self) -> String {
+        format_statements(&self.down)
+    }
+
+    /// Returns the checksum of the SQL to apply the migration.
+    #[inline]
+    pub fn checksum(&self) -> String {
+        hex::encode(zino_core::crypto::checksum(self.up_sql().as_bytes()))
+    }
+
+    /// Parses a migration from the SQL files.
+    pub fn parse(file_stem: &str, up_sql: &str, down_sql: &str) -> Result<Self, Error> {
+        let Some((version, name)) = file_stem.split_once('_') else {
+            bail!("invalid file name for the migration `{}`", file_stem);
+        };
+        if version.is_empty() || !version.bytes().all(|b| b.is_ascii_digit()) {
+            bail!("invalid version for the migration `{}`", file_stem);
+        }
+        Ok(Self {
+            version: version.to_owned(),
+            name: name.to_owned(),
+            up: parse_statements(up_sql),
+            down: parse_statements(down_sql),
+        })
+    }
+}
+
+/// Mode of running migrations when the connection pool starts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MigrationMode {
+    /// Migrations are not run on startup.
+    #[default]
+    Disabled,
+    /// Pending migrations are applied on startup.
+    Apply,
+    /// Pending migrations are logged on startup without being applied.
+    DryRun,
+}
+
+impl MigrationMode {
+    /// Parses the migration mode from a str.
+    #[inline]
+    pub fn parse(mode: &str) -> Option<Self> {
+        match mode {
+            "disabled" | "off" => Some(Self::Disabled),
+            "apply" | "on" => Some(Self::Apply),
+            "dry-run" | "dry_run" => Some(Self::DryRun),
+            _ => None,
+        }
+    }
+}
+
+/// A migrator which applies and reverts the migrations for a connection pool.
+#[derive(Debug)]
+pub struct Migrator {
+    /// Connection pool.
+    connection_pool: &'static ConnectionPool,
+    /// Directory of the migration files.
+    dir: PathBuf,
+    /// Migrations sorted by the version.
+    migrations: Vec<Migration>,
+}
+
+impl Migrator {
+    /// Creates a new instance for the connection pool.
+    /// The migration files are located in the directory `{migration-dir}/{driver-name}`.
+    pub fn new(connection_pool: &'static ConnectionPool) -> Self {
+        let migration_dir = State::shared()
+            .get_config("database")
+            .and_then(|config| config.get_str("migration-dir"))
+            .unwrap_or("./migrations");
+        let dir = Agent::parse_path(migration_dir).join(connection_pool.dialect().as_str());
+        Self {
+            connection_pool,
+            dir,
+            migrations: Vec::new(),
+        }
+    }
+
+    /// Sets the directory of the migration files.
+    #[inline]
+    pub fn with_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.dir = dir.into();
+        self
+    }
+
+    /// Returns the directory of the migration files.
+    #[inline]
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Returns a reference to the migrations.
+    #[inline]
+    pub fn migrations(&self) -> &[Migration] {
+        &self.migrations
+    }
+
+    /// Adds a migration.
+    pub fn add_migration(&mut self, migration: Migration) {
+        let version = migration.version();
+        match self
+            .migrations
+            .binary_search_by(|m| m.version().cmp(version))
+        {
+            Ok(index) => self.migrations[index] = migration,
+            Err(index) => self.migrations.insert(index, migration),
+        }
+    }
+
+    /// Loads the migrations from the `*.up.sql` and `*.down.sql` files in the directory.
+    pub fn load_migrations(mut self) -> Result<Self, Error> {
+        let dir = &self.dir;
+        if !dir.exists() {
+            return Ok(self);
+        }
+
+        let mut migrations = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            let Some(file_stem) = path
+                .file_name()
+                .and_then(|s| s.to_str())
+                .and_then(|s| s.strip_suffix(".up.sql"))
+            else {
+                continue;
+            };
+
+            let up_sql = fs::read_to_string(&path)?;
+            let down_path = dir.join(format!("{file_stem}.down.sql"));
+            let down_sql = if down_path.exists() {
+                fs::read_to_string(down_path)?
+            } else {
+                String::new()
+            };
+            migrations.push(Migration::parse(file_stem, &up_sql, &down_sql)?);
+        }
+        for migration in migrations {
+            self.add_migration(migration);
+        }
+        Ok(self)
+    }
+
+    /// Writes the migration to the files in the directory and adds it to the migrator.
+    /// Returns the paths of the `up` and `down` files.
+    pub fn write_migration(&mut self, migration: Migration) -> Result<(PathBuf, PathBuf), Error> {
+        if migration.is_empty() {
+            bail!("migration `{}` should be nonempty", migration.file_stem());
+        }
+
+        let dir = &self.dir;
+        if !dir.exists() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let file_stem = migration.file_stem();
+        let up_path = dir.join(format!("{file_stem}.up.sql"));
+        let down_path = dir.join(format!("{file_stem}.down.sql"));
+        if up_path.exists() {
+            bail!("migration file `{}` already exists", up_path.display());
+        }
+        fs::write(&up_path, migration.up_sql())?;
+        fs::write(&down_path, migration.down_sql())?;
+        tracing::info!(file_stem, "migration files have been written");
+        self.add_migration(migration);
+        Ok((up_path, down_path))
+    }
+
+    /// Returns the versions of the applied migrations and their checksums.
+    pub async fn applied_migrations(&self) -> Result<Vec<(String, String)>, Error> {
+        self.create_bookkeeping_table().await?;
+
+        let dialect = self.connection_pool.dialect();
+        let table_name = dialect.escape_table_name(&bookkeeping_table_name());
+        let sql = format!("SELECT version, checksum FROM {table_name} ORDER BY version ASC;");
+        let pool = self.connection_pool.pool();
+        let mut applied_migrations = Vec::new();
+        for row in pool.fetch(&sql).await? {
+            let map = Map::decode_row(&row)?;
+            if let Some(version) = map.get_str("version") {
+                let checksum = map.get_str("checksum").unwrap_or_default();
+                applied_migrations.push((version.to_owned(), checksum.to_owned()));
+            }
+        }
+        Ok(applied_migrations)
+    }
+
+    /// Returns the migrations which have not been applied.
+    pub async fn pending_migrations(&self) -> Result<Vec<&Migration>, Error> {
+        let applied_migrations = self.applied_migrations().await?;
+        let mut pending_migrations = Vec::new();
+        for migration in &self.migrations {
+            let version = migration.version();
+            if let Some((_, checksum)) = applied_migrations.iter().find(|(v, _)| v == version) {
+                if !checksum.is_empty() && checksum != &migration.checksum() {
+                    let file_stem = migration.file_stem();
+                    tracing::warn!(
+                        file_stem,
+                        "checksum of the migration differs from the applied one"
+                    );
+                }
+            } else {
+                pending_migrations.push(migration);
+            }
+        }
+        Ok(pending_migrations)
+    }
+
+    /// Returns the statements of the pending migrations without executing them.
+    pub async fn dry_run(&self) -> Result<Vec<String>, Error> {
+        let mut statements = Vec::new();
+        for migration in self.pending_migrations().await? {
+            let file_stem = migration.file_stem();
+            statements.push(format!("-- {file_stem}"));
+            statements.extend_from_slice(migration.up_statements());
+        }
+        Ok(statements)
+    }
+
+    /// Applies the pending migrations in order and returns the versions applied.
+    ///
+    /// Each migration is executed inside of a transaction. Note that MySQL commits
+    /// the DDL statements implicitly, so a failed migration may be partially applied.
+    pub async fn apply(&self) -> Result<Vec<String>, Error> {
+        let dialect = self.connection_pool.dialect();
+        let table_name = dialect.escape_table_name(&bookkeeping_table_name());
+        let pending_migrations = self.pending_migrations().await?;
+        let mut versions = Vec::with_capacity(pending_migrations.len());
+        for migration in pending_migrations {
+            let version = migration.version();
+            let name = Query::escape_string(migration.name());
+            let checksum = Query::escape_string(migration.checksum());
+            let sql = format!(
+                "INSERT INTO {table_name} (version, name, checksum) \
+                    VALUES ('{version}', {name}, {checksum});"
+            );
+            let mut statements = migration.up_statements().to_vec();
+            statements.push(sql);
+            self.execute_statements(&statements).await.map_err(|err| {
+                let file_stem = migration.file_stem();
+                warn!("fail to apply the migration `{}`: {}", file_stem, err)
+            })?;
+
+            let file_stem = migration.file_stem();
+            tracing::warn!(file_stem, "migration has been applied");
+            versions.push(version.to_owned());
+        }
+        Ok(versions)
+    }
+
+    /// Reverts the last `steps` applied migrations and returns the versions reverted.
+    pub async fn rollback(&self, steps: usize) -> Result<Vec<String>, Error> {
+        let dialect = self.connection_pool.dialect();
+        let table_name = dialect.escape_table_name(&bookkeeping_table_name());
+        let applied_migrations = self.applied_migrations().await?;
+        let mut versions = Vec::with_capacity(steps);
+        for (version, _) in applied_migrations.iter().rev().take(steps) {
+            let Some(migration) = self.migrations.iter().find(|m| m.version() == version) else {
+                bail!("migration of the version `{}` can not be found", version);
+            };
+            let file_stem = migration.file_stem();
+            if migration.down_statements().is_empty() {
+                bail!("migration `{}` is irreversible", file_stem);
+            }
+
+            let sql = format!("DELETE FROM {table_name} WHERE version = '{version}';");
+            let mut statements = migration.down_statements().to_vec();
+            statements.push(sql);
+            self.execute_statements(&statements)
+                .await
+                .map_err(|err| warn!("fail to revert the migration `{}`: {}", file_stem, err))?;
+            tracing::warn!(file_stem, "migration has been reverted");
+            versions.push(version.to_owned());
+        }
+        Ok(versions)
+    }
+
+    /// Runs the migrations according to the migration mode of the connection pool.
+    pub async fn run(&self) -> Result<(), Error> {
+        let name = self.connection_pool.name();
+        match self.connection_pool.migration_mode() {
+            MigrationMode::Apply => {
+                let versions = self.apply().await?;
+                let num_migrations = versions.len();
+                tracing::info!(name, num_migrations, "pending migrations have been applied");
+            }
+            MigrationMode::DryRun => {
+                for statement in self.dry_run().await? {
+                    tracing::info!(name, "{statement}");
+                }
+            }
+            _ => (),
+        }
+        Ok(())
+    }
+
+    /// Creates the bookkeeping table if it does not exist.
+    async fn create_bookkeeping_table(&self) -> Result<(), Error> {
+        let dialect = self.connection_pool.dialect();
+        let table_name = dialect.escape_table_name(&bookkeeping_table_name());
+        let timestamp_type = match dialect {
+            Dialect::Postgres => "TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP",
+            Dialect::Sqlite => "DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP",
+            _ => "DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6)",
+        };
+        let sql = format!(
+            "CREATE TABLE IF NOT EXISTS {table_name} (\n  \
+                version VARCHAR(64) PRIMARY KEY,\n  \
+                name VARCHAR(255) NOT NULL,\n  \
+                checksum VARCHAR(64) NOT NULL,\n  \
+                applied_at {timestamp_type}\n);"
+        );
+        self.connection_pool.pool().execute(&sql).await?;
+        Ok(())
+    }
+
+    /// Executes the statements inside of a transaction.
+    async fn execute_statements(&self, statements: &[String]) -> Result<(), Error> {
+        let mut transaction = self.connection_pool.pool().begin().await?;
+        let connection = &mut transaction;
+        for statement in statements {
+            connection.execute(statement).await?;
+        }
+        transaction.commit().await?;
+        Ok(())
+    }
+}
+
+/// Column definition in the database.
+#[derive(Debug)]
+struct ColumnDefinition {
+    /// Column name.
+    name: String,
+    /// Data type.
+    data_type: String,
+    /// Full column type.
+    column_type: String,
+    /// Default value.
+    default_value: Option<String>,
+    /// A flag for the `NOT NULL` constraint.
+    not_null: bool,
+}
+
+impl ColumnDefinition {
+    /// Parses the column definition from a row of the table info.
+    fn parse(map: &Map) -> Option<Self> {
+        let get_str = |key: &str| {
+            map.get_str(key)
+                .or_else(|| map.get_str(&key.to_ascii_uppercase()))
+        };
+        let name = get_str("column_name")?.to_owned();
+        let data_type = get_str("data_type").unwrap_or_default().to_owned();
+        let column_type = if let Some(column_type) = get_str("column_type") {
+            column_type.to_owned()
+        } else if let Some(udt_name) = get_str("udt_name").filter(|_| data_type == "ARRAY") {
+            format!("{}[]", udt_name.trim_start_matches('_'))
+        } else if let Some(Ok(length)) = map.parse_u64("character_maximum_length") {
+            format!("{data_type}({length})")
+        } else {
+            data_type.clone()
+        };
+        let default_value = get_str("column_default").map(|s| s.to_owned());
+        let not_null = if let Some(is_nullable) = get_str("is_nullable") {
+            is_nullable.eq_ignore_ascii_case("NO")
+        } else {
+            get_str("is_not_null") == Some("1")
+                || map.parse_u64("is_not_null").and_then(|r| r.ok()) == Some(1)
+        };
+        Some(Self {
+            name,
+            data_type,
+            column_type,
+            default_value,
+            not_null,
+        })
+    }
+
+    /// Returns the SQL definition of the column in the dialect.
+    fn definition(&self, dialect: Dialect) -> String {
+        let field = dialect.format_field(&self.name);
+        let column_type = &self.column_type;
+        let mut definition = format!("{field} {column_type}");
+        if let Some(value) = self.default_value.as_ref() {
+            definition = format!("{definition} DEFAULT {value}");
+        } else if self.not_null {
+            definition += " NOT NULL";
+        }
+        definition
+    }
+}
+
+/// Fetches the table info for the column definitions.
+pub(super) async fn fetch_table_info(
+    connection_pool: &ConnectionPool,
+    table_name: &str,
+) -> Result<Vec<Map>, Error> {
+    let dialect = connection_pool.dialect();
+    let sql = if dialect.is_mysql_compatible() {
+        let table_schema = connection_pool.database();
+        format!(
+            "SELECT column_name, data_type, column_type, column_default, is_nullable \
+                FROM information_schema.columns \
+                    WHERE table_schema = '{table_schema}' AND table_name = '{table_name}';"
+        )
+    } else if dialect == Dialect::Postgres {
+        format!(
+            "SELECT column_name, data_type, udt_name, character_maximum_length, \
+                    column_default, is_nullable \
+                FROM information_schema.columns \
+                    WHERE table_schema = 'public' AND table_name = '{table_name}';"
+        )
+    } else {
+        format!(
+            "SELECT p.name AS column_name, p.type AS data_type, \
+                    p.dflt_value AS column_default, p.[notnull] AS is_not_null \
+                FROM sqlite_master m LEFT OUTER JOIN pragma_table_info((m.name)) p
+                    ON m.name <> p.name WHERE m.name = '{table_name}';"
+        )
+    };
+    let pool = connection_pool.pool();
+    let rows = pool.fetch(&sql).await?;
+    let mut data = Vec::with_capacity(rows.len());
+    for row in rows {
+        data.push(Map::decode_row(&row)?);
+    }
+    Ok(data)
+}
+
+/// Generates a migration for the model by comparing the columns with the table info.
+pub(super) async fn generate_migration<M: Schema>() -> Result<Migration, Error> {
+    let (mut migration, recreated) = generate_table_migration::<M>().await?;
+    let connection_pool = M::init_writer()?;
+    let dialect = connection_pool.dialect();
+    if dialect != Dialect::Sqlite {
+        return Ok(migration);
+    }
+
+    // The FTS5 table has to be rebuilt when the full-text columns are changed,
+    // or when the triggers have been dropped together with the rebuilt table.
+    let full_table_name = M::table_name();
+    let table_name = full_table_name
+        .rsplit_once('.')
+        .map(|(_, suffix)| suffix)
+        .unwrap_or(full_table_name);
+    let table_name_escaped = dialect.escape_table_name(full_table_name);
+    let text_search_columns = M::columns()
+        .iter()
+        .filter(|col| col.has_fulltext_index())
+        .map(|col| col.name())
+        .collect::<Vec<_>>();
+    let fts_columns = fetch_fts_columns(connection_pool, table_name).await?;
+    if !recreated && fts_columns == text_search_columns {
+        return Ok(migration);
+    }
+    if !fts_columns.is_empty() {
+        migration.up.extend(format_fts_drop_statements(table_name));
+    }
+    migration.up.extend(format_fts_statements(
+        table_name,
+        &table_name_escaped,
+        &text_search_columns,
+    ));
+    if !text_search_columns.is_empty() {
+        migration
+            .down
+            .splice(0..0, format_fts_drop_statements(table_name));
+    }
+    migration.down.extend(format_fts_statements(
+        table_name,
+        &table_name_escaped,
+        &fts_columns,
+    ));
+    Ok(migration)
+}
+
+/// Generates a migration for the table of the model,
+/// and returns a flag indicating whether the table is created or rebuilt.
+async fn generate_table_migration<M: Schema>() -> Result<(Migration, bool), Error> {
+    let connection_pool = M::init_writer()?;
+    let dialect = connection_pool.dialect();
+    let full_table_name = M::table_name();
+    let table_name = full_table_name
+        .rsplit_once('.')
+        .map(|(_, suffix)| suffix)
+        .unwrap_or(full_table_name);
+    let table_name_escaped = dialect.escape_table_name(full_table_name);
+    let table_info = fetch_table_info(connection_pool, table_name).await?;
+    let definitions = table_info
+        .iter()
+        .filter_map(ColumnDefinition::parse)
+        .collect::<Vec<_>>();
+
+    let model_name = M::model_name();
+    let migration_name = ["sync_", table_name].concat();
+    let mut migration = Migration::new(migration_name);
+    let primary_key_name = M::primary_key_name();
+    let columns = M::columns();
+    if definitions.is_empty() {
+        let mut definitions = columns
+            .iter()
+            .map(|col| col.field_definition(dialect, primary_key_name))
+            .collect::<Vec<_>>();
+        for col in columns {
+            definitions.append(&mut col.constraints(dialect));
+        }
+
+        let definitions = definitions.join(",\n  ");
+        migration.add_statements(
+            format!("CREATE TABLE IF NOT EXISTS {table_name_escaped} (\n  {definitions}\n);"),
+            format!("DROP TABLE IF EXISTS {table_name_escaped};"),
+        );
+        return Ok((migration, true));
+    }
+
+    let is_sqlite = dialect == Dialect::Sqlite;
+    let mut requires_rebuild = false;
+    let mut renamed_columns = Vec::new();
+    for col in columns {
+        let column_name = column_name(col);
+        let column_field = dialect.format_field(column_name);
+        let definition = definitions.iter().find(|d| d.name == column_name);
+        let Some(definition) = definition else {
+            let renamed_definition = col
+                .extra()
+                .get_str("renamed_from")
+                .and_then(|name| definitions.iter().find(|d| d.name == name));
+            if let Some(d) = renamed_definition {
+                let previous_field = dialect.format_field(&d.name);
+                migration.add_statements(
+                    format!(
+                        "ALTER TABLE {table_name_escaped} \
+                            RENAME COLUMN {previous_field} TO {column_field};"
+                    ),
+                    format!(
+                        "ALTER TABLE {table_name_escaped} \
+                            RENAME COLUMN {column_field} TO {previous_field};"
+                    ),
+                );
+                renamed_columns.push(d.name.as_str());
+            } else {
+                let column_definition = col.field_definition(dialect, primary_key_name);
+                migration.add_statements(
+                    format!("ALTER TABLE {table_name_escaped} ADD COLUMN {column_definition};"),
+                    format!("ALTER TABLE {table_name_escaped} DROP COLUMN {column_field};"),
+                );
+            }
+            continue;
+        };
+
+        let column_type = col.column_type(dialect);
+        let type_changed = !col.is_compatible(dialect, &definition.data_type);
+        let not_null_changed =
+            col.is_not_null() != definition.not_null && column_name != primary_key_name;
+        if !type_changed && !not_null_changed {
+            continue;
+        }
+        tracing::info!(
+            model_name,
+            table_name,
+            column_name,
+            column_type,
+            data_type = definition.data_type.as_str(),
+            "column `{column_name}` should be altered",
+        );
+        if is_sqlite {
+            requires_rebuild = true;
+        } else if dialect == Dialect::Postgres {
+            if type_changed {
+                let previous_type = &definition.column_type;
+                migration.add_statements(
+                    format!(
+                        "ALTER TABLE {table_name_escaped} ALTER COLUMN {column_field} \
+                            TYPE {column_type} USING {column_field}::{column_type};"
+                    ),
+                    format!(
+                        "ALTER TABLE {table_name_escaped} ALTER COLUMN {column_field} \
+                            TYPE {previous_type} USING {column_field}::{previous_type};"
+                    ),
+                );
+            }
+            if not_null_changed {
+                let (up_action, down_action) = if col.is_not_null() {
+                    ("SET", "DROP")
+                } else {
+                    ("DROP", "SET")
+                };
+                migration.add_statements(
+                    format!(
+                        "ALTER TABLE {table_name_escaped} \
+                            ALTER COLUMN {column_field} {up_action} NOT NULL;"
+                    ),
+                    format!(
+                        "ALTER TABLE {table_name_escaped} \
+                            ALTER COLUMN {column_field} {down_action} NOT NULL;"
+                    ),
+                );
+            }
+        } else {
+            let column_definition = col.field_definition(dialect, primary_key_name);
+            let previous_definition = definition.definition(dialect);
+            migration.add_statements(
+                format!("ALTER TABLE {table_name_escaped} MODIFY COLUMN {column_definition};"),
+                format!("ALTER TABLE {table_name_escaped} MODIFY COLUMN {previous_definition};"),
+            );
+        }
+    }
+
+    let dropped_definitions = definitions
+        .iter()
+        .filter(|d| {
+            !renamed_columns.contains(&d.name.as_str())
+                && !columns.iter().any(|col| column_name(col) == d.name)
+        })
+        .collect::<Vec<_>>();
+    if is_sqlite && requires_rebuild {
+        // SQLite does not support altering column types or constraints,
+        // so the table has to be rebuilt with the data copied.
+        let sql = format!(
+            "SELECT sql FROM sqlite_master WHERE type = 'table' AND name = '{table_name}';"
+        );
+        let previous_table_sql = connection_pool
+            .pool()
+            .fetch_optional(&sql)
+            .await?
+            .map(|row| Map::decode_row(&row))
+            .transpose()?
+            .and_then(|map| map.get_str("sql").map(|s| s.to_owned()));
+        let Some(previous_table_sql) = previous_table_sql else {
+            bail!("fail to get the table definition of `{}`", table_name);
+        };
+
+        let mut column_definitions = columns
+            .iter()
+            .map(|col| col.field_definition(dialect, primary_key_name))
+            .collect::<Vec<_>>();
+        for col in columns {
+            column_definitions.append(&mut col.constraints(dialect));
+        }
+
+        let mut target_fields = Vec::new();
+        let mut source_fields = Vec::new();
+        for col in columns {
+            let column_name = column_name(col);
+            let source_name = if definitions.iter().any(|d| d.name == column_name) {
+                Some(column_name)
+            } else {
+                col.extra()
+                    .get_str("renamed_from")
+                    .filter(|&name| definitions.iter().any(|d| d.name == name))
+            };
+            if let Some(source_name) = source_name {
+                target_fields.push(dialect.format_field(column_name).into_owned());
+                source_fields.push(dialect.format_field(source_name).into_owned());
+            }
+        }
+
+        let definitions = column_definitions.join(",\n  ");
+        let target_fields = target_fields.join(", ");
+        let source_fields = source_fields.join(", ");
+        let backup_table_name = dialect.escape_table_name(&[table_name, "__backup"].concat());
+
+        let mut migration = Migration::with_version(migration.version, migration.name);
+        migration.add_up_statement(format!(
+            "ALTER TABLE {table_name_escaped} RENAME TO {backup_table_name};"
+        ));
+        migration.add_up_statement(format!(
+            "CREATE TABLE {table_name_escaped} (\n  {definitions}\n);"
+        ));
+        migration.add_up_statement(format!(
+            "INSERT INTO {table_name_escaped} ({target_fields}) \
+                SELECT {source_fields} FROM {backup_table_name};"
+        ));
+        migration.add_up_statement(format!("DROP TABLE {backup_table_name};"));
+
+        migration.add_down_statement(format!("DROP TABLE {backup_table_name};"));
+        migration.add_down_statement(format!(
+            "INSERT INTO {table_name_escaped} ({source_fields}) \
+                SELECT {target_fields} FROM {backup_table_name};"
+        ));
+        migration.add_down_statement([previous_table_sql.trim_end_matches(';'), ";"].concat());
+        migration.add_down_statement(format!(
+            "ALTER TABLE {table_name_escaped} RENAME TO {backup_table_name};"
+        ));
+        return Ok((migration, true));
+    }
+    for d in dropped_definitions {
+        let column_field = dialect.format_field(&d.name);
+        let previous_definition = if d.not_null && d.default_value.is_none() {
+            // Restores the column without the `NOT NULL` constraint
+            // since the existing rows have no values for it.
+            let column_type = &d.column_type;
+            format!("{column_field} {column_type}")
+        } else {
+            d.definition(dialect)
+        };
+        migration.add_statements(
+            format!("ALTER TABLE {table_name_escaped} DROP COLUMN {column_field};"),
+            format!("ALTER TABLE {table_name_escaped} ADD COLUMN {previous_definition};"),
+        );
+    }
+    Ok((migration, false))
+}
+
+/// Fetches the columns of the external content FTS5 table for the full-text search in SQLite.
+pub(super) async fn fetch_fts_columns(
+    connection_pool: &ConnectionPool,
+    table_name: &str,
+) -> Result<Vec<String>, Error> {
+    let sql = format!("SELECT name FROM pragma_table_info('{table_name}_fts');");
+    let rows = connection_pool.pool().fetch(&sql).await?;
+    let mut columns = Vec::with_capacity(rows.len());
+    for row in rows {
+        if let Some(name) = Map::decode_row(&row)?.get_str("name") {
+            columns.push(name.to_owned());
+        }
+    }
+    Ok(columns)
+}
+
+/// Formats the statements to create an external content FTS5 table for the full-text search
+/// in SQLite, which is kept in sync with the content table by the triggers.
+pub(super) fn format_fts_statements<T: AsRef<str>>(
+    table_name: &str,
+    table_name_escaped: &str,
+    columns: &[T],
+) -> Vec<String> {
+    if columns.is_empty() {
+        return Vec::new();
+    }
+
+    let fts_table = format!("{table_name}_fts");
+    let columns = columns.iter().map(|col| col.as_ref()).collect::<Vec<_>>();
+    let new_values = columns
+        .iter()
+        .map(|col| format!("new.{col}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let old_values = columns
+        .iter()
+        .map(|col| format!("old.{col}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let columns = columns.join(", ");
+    vec![
+        format!(
+            "CREATE VIRTUAL TABLE IF NOT EXISTS {fts_table} \
+                USING fts5({columns}, content='{table_name}', content_rowid='rowid');"
+        ),
+        format!(
+            "CREATE TRIGGER IF NOT EXISTS {fts_table}_insert \
+                AFTER INSERT ON {table_name_escaped} BEGIN \
+                    INSERT INTO {fts_table}(rowid, {columns}) \
+                        VALUES (new.rowid, {new_values}); \
+                END;"
+        ),
+        format!(
+            "CREATE TRIGGER IF NOT EXISTS {fts_table}_delete \
+                AFTER DELETE ON {table_name_escaped} BEGIN \
+                    INSERT INTO {fts_table}({fts_table}, rowid, {columns}) \
+                        VALUES ('delete', old.rowid, {old_values}); \
+                END;"
+        ),
+        format!(
+            "CREATE TRIGGER IF NOT EXISTS {fts_table}_update \
+                AFTER UPDATE ON {table_name_escaped} BEGIN \
+                    INSERT INTO {fts_table}({fts_table}, rowid, {columns}) \
+                        VALUES ('delete', old.rowid, {old_values}); \
+                    INSERT INTO {fts_table}(rowid, {columns}) \
+                        VALUES (new.rowid, {new_values}); \
+                END;"
+        ),
+        format!("INSERT INTO {fts_table}({fts_table}) VALUES ('rebuild');"),
+    ]
+}
+
+/// Formats the statements to drop the FTS5 table and its triggers in SQLite.
+pub(super) fn format_fts_drop_statements(table_name: &str) -> Vec<String> {
+    let fts_table = format!("{table_name}_fts");
+    vec![
+        format!("DROP TRIGGER IF EXISTS {fts_table}_insert;"),
+        format!("DROP TRIGGER IF EXISTS {fts_table}_delete;"),
+        format!("DROP TRIGGER IF EXISTS {fts_table}_update;"),
+        format!("DROP TABLE IF EXISTS {fts_table};"),
+    ]
+}
+
+/// Returns the name of the bookkeeping table.
+fn bookkeeping_table_name() -> String {
+    [*super::TABLE_PREFIX, "schema_migrations"].concat()
+}
+
+/// Returns the column name in the database.
+fn column_name<'a>(col: &'a Column<'static>) -> &'a str {
+    col.extra()
+        .get_str("column_name")
+        .unwrap_or_else(|| col.name())
+}
+
+/// Formats the statements as the content of a SQL file.
+fn format_statements(statements: &[String]) -> String {
+    let mut sql = statements
+        .iter()
+        .map(|s| {
+            let s = s.trim();
+            if s.ends_with(';') {
+                s.to_owned()
+            } else {
+                [s, ";"].concat()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    sql.push('\n');
+    sql
+}
+
+/// Parses the statements from the content of a SQL file.
+/// A statement is terminated by a line ending with `;`, and the `--` comment lines are ignored.
+fn parse_statements(sql: &str) -> Vec<String> {
+    let mut statements = Vec::new();
+    let mut statement = String::new();
+    for line in sql.lines() {
+        let line = line.trim_end();
+        if line.trim_start().starts_with("--") || (line.is_empty() && statement.is_empty()) {
+            continue;
+        }
+        if !statement.is_empty() {
+            statement.push('\n');
+        }
+        statement.push_str(line);
+        if line.ends_with(';') {
+            statements.push(statement.trim().to_owned());
+            statement.clear();
+        }
+    }
+    if !statement.trim().is_empty() {
+        statements.push(statement.trim().to_owned());
+    }
+    statements
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Migration;
+
+    #[test]
+    fn it_formats_fts_statements() {
+        let statements = super::format_fts_statements("post", "`post`", &["title", "content"]);
+        assert_eq!(statements.len(), 5);
+        assert_eq!(
+            statements[0],
+            "CREATE VIRTUAL TABLE IF NOT EXISTS post_fts \
+                USING fts5(title, content, content='post', content_rowid='rowid');"
+        );
+        assert_eq!(
+            statements[3],
+            "CREATE TRIGGER IF NOT EXISTS post_fts_update AFTER UPDATE ON `post` BEGIN \
+                INSERT INTO post_fts(post_fts, rowid, title, content) \
+                    VALUES ('delete', old.rowid, old.title, old.content); \
+                INSERT INTO post_fts(rowid, title, content) \
+                    VALUES (new.rowid, new.title, new.content); END;"
+        );
+        assert_eq!(
+            statements[4],
+            "INSERT INTO post_fts(post_fts) VALUES ('rebuild');"
+        );
+        assert!(super::format_fts_statements::<&str>("post", "`post`", &[]).is_empty());
+        assert_eq!(
+            super::format_fts_drop_statements("post"),
+            [
+                "DROP TRIGGER IF EXISTS post_fts_insert;",
+                "DROP TRIGGER IF EXISTS post_fts_delete;",
+                "DROP TRIGGER IF EXISTS post_fts_update;",
+                "DROP TABLE IF EXISTS post_fts;",
+            ]
+        );
+    }
+
+    #[cfg(feature = "orm-sqlx")]
+    #[tokio::test]
+    async fn it_rebuilds_fts_tables() {
+        use super::{ConnectionPool, Executor};
+        use crate::{DatabasePool, Dialect};
+        use sqlx::sqlite::SqlitePoolOptions;
+
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("fail to connect the database");
+        pool.execute("CREATE TABLE post (id INTEGER PRIMARY KEY, title TEXT, content TEXT);")
+            .await
+            .expect("fail to execute the statement");
+        pool.execute("INSERT INTO post (id, title, content) VALUES (1, 'rust', 'async io');")
+            .await
+            .expect("fail to execute the statement");
+
+        let mut connection_pool =
+            ConnectionPool::new("main", "test", DatabasePool::Sqlite(pool.clone()));
+        connection_pool.set_dialect(Dialect::Sqlite);
+        assert!(
+            super::fetch_fts_columns(&connection_pool, "post")
+                .await
+                .expect("fail to fetch the full-text columns")
+                .is_empty()
+        );
+        for sql in super::format_fts_statements("post", "`post`", &["content"]) {
+            pool.execute(&sql)
+                .await
+                .expect("fail to execute the statement");
+        }
+        assert_eq!(
+            super::fetch_fts_columns(&connection_pool, "post")
+                .await
+                .expect("fail to fetch the full-text columns"),
+            ["content"]
+        );
+
+        // The full-text columns have been changed
+        let statements = super::format_fts_drop_statements("post").into_iter().chain(
+            super::format_fts_statements("post", "`post`", &["title", "content"]),
+        );
+        for sql in statements {
+            pool.execute(&sql)
+                .await
+                .expect("fail to execute the statement");
+        }
+        assert_eq!(
+            super::fetch_fts_columns(&connection_pool, "post")
+                .await
+                .expect("fail to fetch the full-text columns"),
+            ["title", "content"]
+        );
+        pool.execute("UPDATE post SET title = 'zino' WHERE id = 1;")
+            .await
+            .expect("fail to execute the statement");
+        let rows = pool
+            .fetch("SELECT rowid FROM post_fts WHERE post_fts MATCH 'title : zino';")
+            .await
+            .expect("fail to fetch the rows");
+        assert_eq!(rows.len(), 1);
+        let rows = pool
+            .fetch("SELECT rowid FROM post_fts WHERE post_fts MATCH 'title : rust';")
+            .await
+            .expect("fail to fetch the rows");
+        assert!(rows.is_empty());
+    }
+
+    #[test]
+    fn it_parses_migrations() {
+        let up_sql = "-- add columns\n\
+            ALTER TABLE \"user\" ADD COLUMN \"age\" INT;\n\n\
+            ALTER TABLE \"user\"\n  ADD COLUMN \"bio\" TEXT;\n";
+        let down_sql = "ALTER TABLE \"user\" DROP COLUMN \"bio\";\n\
+            ALTER TABLE \"user\" DROP COLUMN \"age\";\n";
+        let migration = Migration::parse("20260101000000_sync_user", up_sql, down_sql)
+            .expect("fail to parse the migration");
+        assert_eq!(migration.version(), "20260101000000");
+        assert_eq!(migration.name(), "sync_user");
+        assert_eq!(migration.up_statements().len(), 2);
+        assert_eq!(
+            migration.up_statements()[1],
+            "ALTER TABLE \"user\"\n  ADD COLUMN \"bio\" TEXT;"
+        );
+        assert_eq!(migration.down_statements().len(), 2);
+        assert!(Migration::parse("sync_user", up_sql, down_sql).is_err());
+    }
+
+    #[test]
+    fn it_merges_migrations() {
+        let mut migration = Migration::with_version("1", "first");
+        migration.add_statements("CREATE TABLE a (id INT);", "DROP TABLE a;");
+        migration.add_statements("CREATE TABLE b (id INT);", "DROP TABLE b;");
+
+        let mut other = Migration::with_version("2", "second");
+        other.add_statements("CREATE TABLE c (id INT);", "DROP TABLE c;");
+        migration.merge(other);
+        assert_eq!(
+            migration.up_statements(),
+            [
+                "CREATE TABLE a (id INT);",
+                "CREATE TABLE b (id INT);",
+                "CREATE TABLE c (id INT);"
+            ]
+        );
+        assert_eq!(
+            migration.down_statements(),
+            ["DROP TABLE c;", "DROP TABLE b;", "DROP TABLE a;"]
+        );
+
+        let parsed = Migration::parse("1_first", &migration.up_sql(), &migration.down_sql())
+            .expect("fail to parse the migration");
+        assert_eq!(parsed.up_statements(), migration.up_statements());
+        assert_eq!(parsed.down_statements(), migration.down_statements());
+    }
+}
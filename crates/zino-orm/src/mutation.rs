@@ -1,11 +1,11 @@
 /// Generates SQL `SET` expressions.
-use super::{DatabaseDriver, EncodeColumn, Entity, IntoSqlValue, Schema, query::QueryExt};
+use super::{Dialect, EncodeColumn, Entity, IntoSqlValue, Schema};
 use std::marker::PhantomData;
 use zino_core::{
     JsonValue, Map,
     datetime::DateTime,
     extension::{JsonObjectExt, JsonValueExt},
-    model::{Mutation, Query},
+    model::Mutation,
 };
 
 /// A mutation builder for the model entity.
@@ -214,13 +214,14 @@ impl<E: Entity> Default for MutationBuilder<E> {
 }
 
 /// Extension trait for [`Mutation`](crate::model::Mutation).
-pub(super) trait MutationExt<DB> {
+pub(super) trait MutationExt {
     /// Formats the updates to generate SQL `SET` expression.
     fn format_updates<M: Schema>(&self) -> String;
 }
 
-impl MutationExt<DatabaseDriver> for Mutation {
+impl MutationExt for Mutation {
     fn format_updates<M: Schema>(&self) -> String {
+        let dialect = M::dialect();
         let updates = self.updates();
         if updates.is_empty() {
             return String::new();
@@ -237,8 +238,8 @@ impl MutationExt<DatabaseDriver> for Mutation {
                             if (permissive || fields.contains(key))
                                 && let Some(col) = M::get_writable_column(key)
                             {
-                                let key = Query::format_field(key);
-                                let value = col.encode_value(Some(value));
+                                let key = dialect.format_field(key);
+                                let value = col.encode_value(dialect, Some(value));
                                 let mutation = format!(r#"{key} = {value} + {key}"#);
                                 mutations.push(mutation);
                             }
@@ -251,8 +252,8 @@ impl MutationExt<DatabaseDriver> for Mutation {
                             if (permissive || fields.contains(key))
                                 && let Some(col) = M::get_writable_column(key)
                             {
-                                let key = Query::format_field(key);
-                                let value = col.encode_value(Some(value));
+                                let key = dialect.format_field(key);
+                                let value = col.encode_value(dialect, Some(value));
                                 let mutation = format!(r#"{key} = {value} * {key}"#);
                                 mutations.push(mutation);
                             }
@@ -265,9 +266,9 @@ impl MutationExt<DatabaseDriver> for Mutation {
                             if (permissive || fields.contains(key))
                                 && let Some(col) = M::get_writable_column(key)
                             {
-                                let key = Query::format_field(key);
-                                let value = col.encode_value(Some(value));
-                                let mutation = if cfg!(feature = "orm-sqlite") {
+                                let key = dialect.format_field(key);
+                                let value = col.encode_value(dialect, Some(value));
+                                let mutation = if dialect == Dialect::Sqlite {
                                     format!(r#"{key} = MIN({value}, {key})"#)
                                 } else {
                                     format!(r#"{key} = LEAST({value}, {key})"#)
@@ -283,9 +284,9 @@ impl MutationExt<DatabaseDriver> for Mutation {
                             if (permissive || fields.contains(key))
                                 && let Some(col) = M::get_writable_column(key)
                             {
-                                let key = Query::format_field(key);
-                                let value = col.encode_value(Some(value));
-                                let mutation = if cfg!(feature = "orm-sqlite") {
+                                let key = dialect.format_field(key);
+                                let value = col.encode_value(dialect, Some(value));
+                                let mutation = if dialect == Dialect::Sqlite {
                                     format!(r#"{key} = MAX({value}, {key})"#)
                                 } else {
                                     format!(r#"{key} = GREATEST({value}, {key})"#)
@@ -299,13 +300,13 @@ impl MutationExt<DatabaseDriver> for Mutation {
                     if (permissive || fields.contains(key))
                         && let Some(col) = M::get_writable_column(key)
                     {
-                        let key = Query::format_field(key);
+                        let key = dialect.format_field(key);
                         let mutation = if let Some(subquery) =
                             value.as_object().and_then(|m| m.get_str("$subquery"))
                         {
                             format!(r#"{key} = {subquery}"#)
                         } else {
-                            let value = col.encode_value(Some(value));
+                            let value = col.encode_value(dialect, Some(value));
                             format!(r#"{key} = {value}"#)
                         };
                         mutations.push(mutation);
@@ -1,23 +1,27 @@
-use super::{DatabaseDriver, DatabaseRow, DecodeRow, EncodeColumn, Schema, query::QueryExt};
+use super::{
+    DatabaseRow, Dialect, Schema,
+    decode::decode_raw,
+    driver::{DriverExt, RowExt},
+    query::QueryExt,
+    value::parse_utc_timestamp,
+};
 use chrono::NaiveDateTime;
+use sqlx::{Column as _, MySql, Row, TypeInfo, ValueRef, types::Decimal};
 use std::borrow::Cow;
 use zino_core::{
-    AvroValue, JsonValue, Map, Record, SharedString, Uuid,
+    AvroValue, JsonValue, Map, Record, Uuid,
     datetime::{Date, DateTime, Time},
     error::Error,
     extension::{JsonObjectExt, JsonValueExt},
-    model::{Column, Query, QueryOrder},
+    model::{Column, Query},
 };
 
-#[cfg(feature = "orm-sqlx")]
-use sqlx::{Column as _, Row, TypeInfo, ValueRef, types::Decimal};
-
-impl EncodeColumn<DatabaseDriver> for Column<'_> {
-    fn column_type(&self) -> &str {
-        if let Some(column_type) = self.extra().get_str("column_type") {
+impl DriverExt for MySql {
+    fn column_type<'a>(col: &'a Column<'_>, dialect: Dialect) -> &'a str {
+        if let Some(column_type) = col.extra().get_str("column_type") {
             return column_type;
         }
-        match self.type_name() {
+        match col.type_name() {
             "bool" => "BOOLEAN",
             "u64" | "usize" | "Option<u64>" => "BIGINT UNSIGNED",
             "i64" | "isize" | "Option<i64>" => "BIGINT",
@@ -30,19 +34,16 @@ impl EncodeColumn<DatabaseDriver> for Column<'_> {
             "f64" => "DOUBLE",
             "f32" => "FLOAT",
             "Decimal" => "NUMERIC",
-            "String" | "Option<String>" => {
-                if self.default_value().or(self.index_type()).is_some() {
-                    "VARCHAR(255)"
-                } else {
-                    "TEXT"
-                }
+            "String" | "Option<String>" if col.default_value().or(col.index_type()).is_some() => {
+                "VARCHAR(255)"
             }
+            "String" | "Option<String>" => "TEXT",
             "Date" | "NaiveDate" => "DATE",
             "Time" | "NaiveTime" => "TIME",
             "DateTime" => "TIMESTAMP(6)",
             "NaiveDateTime" => "DATETIME(6)",
             "Uuid" | "Option<Uuid>" => {
-                if cfg!(feature = "orm-mariadb") {
+                if dialect == Dialect::MariaDb {
                     "UUID"
                 } else {
                     "CHAR(36)"
@@ -55,7 +56,11 @@ impl EncodeColumn<DatabaseDriver> for Column<'_> {
         }
     }
 
-    fn encode_value<'a>(&self, value: Option<&'a JsonValue>) -> Cow<'a, str> {
+    fn encode_value<'a>(
+        col: &Column<'_>,
+        dialect: Dialect,
+        value: Option<&'a JsonValue>,
+    ) -> Cow<'a, str> {
         if let Some(value) = value {
             match value {
                 JsonValue::Null => "NULL".into(),
@@ -66,8 +71,8 @@ impl EncodeColumn<DatabaseDriver> for Column<'_> {
                 JsonValue::Number(n) => n.to_string().into(),
                 JsonValue::String(s) => {
                     if s.is_empty() {
-                        if let Some(value) = self.default_value() {
-                            self.format_value(value).into_owned().into()
+                        if let Some(value) = col.default_value() {
+                            Self::format_value(col, dialect, value).into_owned().into()
                         } else {
                             "''".into()
                         }
@@ -76,7 +81,7 @@ impl EncodeColumn<DatabaseDriver> for Column<'_> {
                     } else if s == "not_null" {
                         "NOT NULL".into()
                     } else {
-                        self.format_value(s)
+                        Self::format_value(col, dialect, s)
                     }
                 }
                 JsonValue::Array(vec) => {
@@ -84,22 +89,22 @@ impl EncodeColumn<DatabaseDriver> for Column<'_> {
                         .iter()
                         .map(|v| match v {
                             JsonValue::String(v) => Query::escape_string(v),
-                            _ => self.encode_value(Some(v)).into_owned(),
+                            _ => Self::encode_value(col, dialect, Some(v)).into_owned(),
                         })
                         .collect::<Vec<_>>();
                     format!(r#"json_array({})"#, values.join(",")).into()
                 }
                 JsonValue::Object(_) => Query::escape_string(value).into(),
             }
-        } else if self.default_value().is_some() {
+        } else if col.default_value().is_some() {
             "DEFAULT".into()
         } else {
             "NULL".into()
         }
     }
 
-    fn format_value<'a>(&self, value: &'a str) -> Cow<'a, str> {
-        match self.type_name() {
+    fn format_value<'a>(col: &Column<'_>, dialect: Dialect, value: &'a str) -> Cow<'a, str> {
+        match col.type_name() {
             "bool" => {
                 let value = if value == "true" { "TRUE" } else { "FALSE" };
                 value.into()
@@ -131,7 +136,15 @@ impl EncodeColumn<DatabaseDriver> for Column<'_> {
                 "today" => "curdate()".into(),
                 "tomorrow" => "curdate() + INTERVAL 1 DAY".into(),
                 "yesterday" => "curdate() - INTERVAL 1 DAY".into(),
-                _ => Query::escape_string(value).into(),
+                _ => {
+                    if col.type_name() == "DateTime"
+                        && let Some(timestamp) = parse_utc_timestamp(value)
+                    {
+                        Query::escape_string(timestamp).into()
+                    } else {
+                        Query::escape_string(value).into()
+                    }
+                }
             },
             "Date" | "NaiveDate" => match value {
                 "epoch" => "'1970-01-01'".into(),
@@ -145,8 +158,7 @@ impl EncodeColumn<DatabaseDriver> for Column<'_> {
                 "midnight" => "'00:00:00'".into(),
                 _ => Query::escape_string(value).into(),
             },
-            #[cfg(feature = "orm-mariadb")]
-            "Uuid" | "Option<Uuid>" => format!("'{value}'").into(),
+            "Uuid" | "Option<Uuid>" if dialect == Dialect::MariaDb => format!("'{value}'").into(),
             "Vec<u8>" => format!("'{value}'").into(),
             "Vec<String>" | "Vec<Uuid>" | "Vec<u64>" | "Vec<i64>" | "Vec<u32>" | "Vec<i32>"
             | "Vec<f64>" | "Vec<f32>" => {
@@ -165,12 +177,12 @@ impl EncodeColumn<DatabaseDriver> for Column<'_> {
         }
     }
 
-    fn format_filter(&self, field: &str, value: &JsonValue) -> String {
-        let type_name = self.type_name();
-        let field = Query::format_field(field);
+    fn format_filter(col: &Column<'_>, dialect: Dialect, field: &str, value: &JsonValue) -> String {
+        let type_name = col.type_name();
+        let field = dialect.format_field(field);
         if let Some(filter) = value.as_object() {
             if type_name == "Map" {
-                let value = self.encode_value(Some(value));
+                let value = Self::encode_value(col, dialect, Some(value));
                 return format!(r#"json_contains({field}, {value})"#);
             } else {
                 let mut conditions = Vec::with_capacity(filter.len());
@@ -209,7 +221,7 @@ impl EncodeColumn<DatabaseDriver> for Column<'_> {
                             } else {
                                 let value = values
                                     .iter()
-                                    .map(|v| self.encode_value(Some(v)))
+                                    .map(|v| Self::encode_value(col, dialect, Some(v)))
                                     .collect::<Vec<_>>()
                                     .join(", ");
                                 let condition = format!(r#"{field} {operator} ({value})"#);
@@ -219,20 +231,20 @@ impl EncodeColumn<DatabaseDriver> for Column<'_> {
                     } else if operator == "BETWEEN" {
                         if let Some(values) = value.as_array() {
                             if let [min_value, max_value] = values.as_slice() {
-                                let min_value = self.encode_value(Some(min_value));
-                                let max_value = self.encode_value(Some(max_value));
-                                let condition =
-                                    format!(r#"({field} BETWEEN {min_value} AND {max_value})"#);
-                                conditions.push(condition);
-                            }
-                        } else if let Some(values) = value.parse_str_array() {
-                            if let [min_value, max_value] = values.as_slice() {
-                                let min_value = self.format_value(min_value);
-                                let max_value = self.format_value(max_value);
+                                let min_value = Self::encode_value(col, dialect, Some(min_value));
+                                let max_value = Self::encode_value(col, dialect, Some(max_value));
                                 let condition =
                                     format!(r#"({field} BETWEEN {min_value} AND {max_value})"#);
                                 conditions.push(condition);
                             }
+                        } else if let Some(values) = value.parse_str_array()
+                            && let [min_value, max_value] = values.as_slice()
+                        {
+                            let min_value = Self::format_value(col, dialect, min_value);
+                            let max_value = Self::format_value(col, dialect, max_value);
+                            let condition =
+                                format!(r#"({field} BETWEEN {min_value} AND {max_value})"#);
+                            conditions.push(condition);
                         }
                     } else if operator == "json_length" {
                         if let Some(Ok(length)) = value.parse_usize() {
@@ -240,7 +252,7 @@ impl EncodeColumn<DatabaseDriver> for Column<'_> {
                             conditions.push(condition);
                         }
                     } else {
-                        let value = self.encode_value(Some(value));
+                        let value = Self::encode_value(col, dialect, Some(value));
                         let condition = format!(r#"{field} {operator} {value}"#);
                         conditions.push(condition);
                     }
@@ -253,8 +265,8 @@ impl EncodeColumn<DatabaseDriver> for Column<'_> {
             }
         } else if value.is_null() {
             return format!(r#"{field} IS NULL"#);
-        } else if self.has_attribute("exact_filter") {
-            let value = self.encode_value(Some(value));
+        } else if col.has_attribute("exact_filter") {
+            let value = Self::encode_value(col, dialect, Some(value));
             return format!(r#"{field} = {value}"#);
         } else if let Some(value) = value.as_str() {
             if value == "null" {
@@ -262,17 +274,17 @@ impl EncodeColumn<DatabaseDriver> for Column<'_> {
             } else if value == "not_null" {
                 return format!(r#"{field} IS NOT NULL"#);
             } else if let Some((min_value, max_value)) =
-                value.split_once(',').filter(|_| self.is_temporal_type())
+                value.split_once(',').filter(|_| col.is_temporal_type())
             {
-                let min_value = self.format_value(min_value);
-                let max_value = self.format_value(max_value);
+                let min_value = Self::format_value(col, dialect, min_value);
+                let max_value = Self::format_value(col, dialect, max_value);
                 return format!(r#"{field} >= {min_value} AND {field} < {max_value}"#);
             }
         }
 
         match type_name {
             "bool" => {
-                let value = self.encode_value(Some(value));
+                let value = Self::encode_value(col, dialect, Some(value));
                 if value == "TRUE" {
                     format!(r#"{field} IS TRUE"#)
                 } else {
@@ -288,11 +300,11 @@ impl EncodeColumn<DatabaseDriver> for Column<'_> {
                         let value = value.split(',').collect::<Vec<_>>().join(",");
                         format!(r#"{field} IN ({value})"#)
                     } else {
-                        let value = self.format_value(value);
+                        let value = Self::format_value(col, dialect, value);
                         format!(r#"{field} = {value}"#)
                     }
                 } else {
-                    let value = self.encode_value(Some(value));
+                    let value = Self::encode_value(col, dialect, Some(value));
                     format!(r#"{field} = {value}"#)
                 }
             }
@@ -303,7 +315,7 @@ impl EncodeColumn<DatabaseDriver> for Column<'_> {
                         format!(r#"({field} = '') IS NOT FALSE"#)
                     } else if value == "nonempty" {
                         format!(r#"({field} = '') IS FALSE"#)
-                    } else if self.fuzzy_search() {
+                    } else if col.fuzzy_search() {
                         if value.contains(',') {
                             let exprs = value
                                 .split(',')
@@ -329,14 +341,14 @@ impl EncodeColumn<DatabaseDriver> for Column<'_> {
                         format!(r#"{field} = {value}"#)
                     }
                 } else {
-                    let value = self.encode_value(Some(value));
+                    let value = Self::encode_value(col, dialect, Some(value));
                     format!(r#"{field} = {value}"#)
                 }
             }
             "DateTime" | "NaiveDateTime" => {
                 if let Some(value) = value.as_str() {
                     let length = value.len();
-                    let value = self.format_value(value);
+                    let value = Self::format_value(col, dialect, value);
                     match length {
                         4 => format!(r#"date_format({field}, '%Y') = {value}"#),
                         7 => format!(r#"date_format({field}, '%Y-%m') = {value}"#),
@@ -344,28 +356,28 @@ impl EncodeColumn<DatabaseDriver> for Column<'_> {
                         _ => format!(r#"{field} = {value}"#),
                     }
                 } else {
-                    let value = self.encode_value(Some(value));
+                    let value = Self::encode_value(col, dialect, Some(value));
                     format!(r#"{field} = {value}"#)
                 }
             }
             "Date" | "NaiveDate" => {
                 if let Some(value) = value.as_str() {
                     let length = value.len();
-                    let value = self.format_value(value);
+                    let value = Self::format_value(col, dialect, value);
                     match length {
                         4 => format!(r#"date_format({field}, '%Y') = {value}"#),
                         7 => format!(r#"date_format({field}, '%Y-%m') = {value}"#),
                         _ => format!(r#"{field} = {value}"#),
                     }
                 } else {
-                    let value = self.encode_value(Some(value));
+                    let value = Self::encode_value(col, dialect, Some(value));
                     format!(r#"{field} = {value}"#)
                 }
             }
             "Time" | "NaiveTime" => {
                 if let Some(value) = value.as_str() {
                     let length = value.len();
-                    let value = self.format_value(value);
+                    let value = Self::format_value(col, dialect, value);
                     match length {
                         2 => format!(r#"date_format({field}, '%H') = {value}"#),
                         5 => format!(r#"date_format({field}, '%H:%i') = {value}"#),
@@ -373,7 +385,7 @@ impl EncodeColumn<DatabaseDriver> for Column<'_> {
                         _ => format!(r#"{field} = {value}"#),
                     }
                 } else {
-                    let value = self.encode_value(Some(value));
+                    let value = Self::encode_value(col, dialect, Some(value));
                     format!(r#"{field} = {value}"#)
                 }
             }
@@ -391,7 +403,7 @@ impl EncodeColumn<DatabaseDriver> for Column<'_> {
                         format!(r#"{field} = {value}"#)
                     }
                 } else {
-                    let value = self.encode_value(Some(value));
+                    let value = Self::encode_value(col, dialect, Some(value));
                     format!(r#"{field} = {value}"#)
                 }
             }
@@ -405,71 +417,119 @@ impl EncodeColumn<DatabaseDriver> for Column<'_> {
                             .split(',')
                             .map(|v| {
                                 let s = v.replace(';', ",");
-                                let value = self.format_value(&s);
+                                let value = Self::format_value(col, dialect, &s);
                                 format!(r#"json_contains({field}, {value})"#)
                             })
                             .collect::<Vec<_>>();
                         format!("({})", exprs.join(" OR "))
                     } else {
-                        let value = self.format_value(value);
+                        let value = Self::format_value(col, dialect, value);
                         format!(r#"json_overlaps({field}, {value})"#)
                     }
                 } else {
-                    let value = self.encode_value(Some(value));
+                    let value = Self::encode_value(col, dialect, Some(value));
                     format!(r#"json_overlaps({field}, {value})"#)
                 }
             }
             "Map" => {
-                let value = self.encode_value(Some(value));
+                let value = Self::encode_value(col, dialect, Some(value));
                 format!(r#"json_contains({field}, {value})"#)
             }
             _ => {
-                let value = self.encode_value(Some(value));
+                let value = Self::encode_value(col, dialect, Some(value));
                 format!(r#"{field} = {value}"#)
             }
         }
     }
+
+    fn parse_text_search<M: Schema>(filter: &Map) -> Option<String> {
+        let fields = filter.parse_str_array("$fields")?;
+        filter.parse_string("$search").map(|search| {
+            let fields = fields.join(", ");
+            let search = Query::escape_string(search.as_ref());
+            format!("match({fields}) against({search})")
+        })
+    }
+
+    #[inline]
+    fn format_text_search_rank<M: Schema>(filter: &Map) -> Option<String> {
+        Self::parse_text_search::<M>(filter)
+    }
+
+    fn format_text_search_highlight<M: Schema>(filter: &Map, field: &str) -> Option<String> {
+        // There is no built-in function to generate snippets,
+        // so the search terms are wrapped with tags by a regular expression.
+        let search = filter.parse_string("$search")?;
+        let terms = search
+            .split_whitespace()
+            .map(|term| {
+                term.chars()
+                    .filter(|c| c.is_alphanumeric())
+                    .collect::<String>()
+            })
+            .filter(|term| !term.is_empty())
+            .collect::<Vec<_>>();
+        if terms.is_empty() {
+            return None;
+        }
+
+        let field = M::dialect().format_field(field);
+        let pattern = format!("(?i)({})", terms.join("|"));
+        let replacement = if M::dialect() == Dialect::MariaDb {
+            r"<mark>\\1</mark>"
+        } else {
+            "<mark>$1</mark>"
+        };
+        Some(format!(
+            "regexp_replace({field}, '{pattern}', '{replacement}')"
+        ))
+    }
 }
 
-#[cfg(feature = "orm-sqlx")]
-impl DecodeRow<DatabaseRow> for Map {
-    type Error = Error;
+impl RowExt for sqlx::mysql::MySqlRow {
+    #[inline]
+    fn into_database_row(self, dialect: Dialect) -> DatabaseRow {
+        match dialect {
+            Dialect::MariaDb => DatabaseRow::MariaDb(self),
+            Dialect::TiDb => DatabaseRow::TiDb(self),
+            _ => DatabaseRow::MySql(self),
+        }
+    }
 
-    fn decode_row(row: &DatabaseRow) -> Result<Self, Self::Error> {
+    fn decode_map(&self, dialect: Dialect) -> Result<Map, Error> {
         let mut map = Map::new();
-        for col in row.columns() {
+        for col in self.columns() {
             let field = col.name();
             let index = col.ordinal();
-            let raw_value = row.try_get_raw(index)?;
+            let raw_value = self.try_get_raw(index)?;
             let value = if raw_value.is_null() {
                 JsonValue::Null
             } else {
-                use super::decode::decode_raw;
                 match col.type_info().name() {
-                    "BOOLEAN" => decode_raw::<bool>(field, raw_value)?.into(),
-                    "TINYINT" => decode_raw::<i8>(field, raw_value)?.into(),
-                    "TINYINT UNSIGNED" => decode_raw::<u8>(field, raw_value)?.into(),
-                    "SMALLINT" => decode_raw::<i16>(field, raw_value)?.into(),
-                    "SMALLINT UNSIGNED" => decode_raw::<u16>(field, raw_value)?.into(),
-                    "INT" => decode_raw::<i32>(field, raw_value)?.into(),
-                    "INT UNSIGNED" => decode_raw::<u32>(field, raw_value)?.into(),
-                    "BIGINT" => decode_raw::<i64>(field, raw_value)?.into(),
-                    "BIGINT UNSIGNED" => decode_raw::<u64>(field, raw_value)?.into(),
-                    "FLOAT" => decode_raw::<f32>(field, raw_value)?.into(),
-                    "DOUBLE" => decode_raw::<f64>(field, raw_value)?.into(),
+                    "BOOLEAN" => decode_raw::<bool, MySql>(field, raw_value)?.into(),
+                    "TINYINT" => decode_raw::<i8, MySql>(field, raw_value)?.into(),
+                    "TINYINT UNSIGNED" => decode_raw::<u8, MySql>(field, raw_value)?.into(),
+                    "SMALLINT" => decode_raw::<i16, MySql>(field, raw_value)?.into(),
+                    "SMALLINT UNSIGNED" => decode_raw::<u16, MySql>(field, raw_value)?.into(),
+                    "INT" => decode_raw::<i32, MySql>(field, raw_value)?.into(),
+                    "INT UNSIGNED" => decode_raw::<u32, MySql>(field, raw_value)?.into(),
+                    "BIGINT" => decode_raw::<i64, MySql>(field, raw_value)?.into(),
+                    "BIGINT UNSIGNED" => decode_raw::<u64, MySql>(field, raw_value)?.into(),
+                    "FLOAT" => decode_raw::<f32, MySql>(field, raw_value)?.into(),
+                    "DOUBLE" => decode_raw::<f64, MySql>(field, raw_value)?.into(),
                     "NUMERIC" => {
-                        let value = decode_raw::<Decimal>(field, raw_value)?;
+                        let value = decode_raw::<Decimal, MySql>(field, raw_value)?;
                         serde_json::to_value(value)?
                     }
-                    "TIMESTAMP" => decode_raw::<DateTime>(field, raw_value)?.into(),
-                    "DATETIME" => decode_raw::<NaiveDateTime>(field, raw_value)?
+                    "TIMESTAMP" => decode_raw::<DateTime, MySql>(field, raw_value)?.into(),
+                    "DATETIME" => decode_raw::<NaiveDateTime, MySql>(field, raw_value)?
                         .to_string()
                         .into(),
-                    "DATE" => decode_raw::<Date>(field, raw_value)?.into(),
-                    "TIME" => decode_raw::<Time>(field, raw_value)?.into(),
+                    "DATE" => decode_raw::<Date, MySql>(field, raw_value)?.into(),
+                    "TIME" => decode_raw::<Time, MySql>(field, raw_value)?.into(),
                     "BYTE" | "BINARY" | "VARBINARY" | "BLOB" => {
-                        let bytes = decode_raw::<Vec<u8>>(field, raw_value)?;
-                        if cfg!(feature = "orm-mariadb")
+                        let bytes = decode_raw::<Vec<u8>, MySql>(field, raw_value)?;
+                        if dialect == Dialect::MariaDb
                             && (bytes.starts_with(b"[") && bytes.ends_with(b"]")
                                 || bytes.starts_with(b"{") && bytes.ends_with(b"}"))
                         {
@@ -485,11 +545,10 @@ impl DecodeRow<DatabaseRow> for Map {
                             bytes.into()
                         }
                     }
-                    "JSON" => decode_raw::<JsonValue>(field, raw_value)?,
-                    #[cfg(feature = "orm-mariadb")]
-                    "TEXT" | "LONGTEXT" => {
+                    "JSON" => decode_raw::<JsonValue, MySql>(field, raw_value)?,
+                    "TEXT" | "LONGTEXT" if dialect == Dialect::MariaDb => {
                         // In MariaDB, JSON is just an alias for LONGTEXT.
-                        let value = decode_raw::<String>(field, raw_value)?;
+                        let value = decode_raw::<String, MySql>(field, raw_value)?;
                         if value.starts_with('[') && value.ends_with(']')
                             || value.starts_with('{') && value.ends_with('}')
                         {
@@ -499,7 +558,7 @@ impl DecodeRow<DatabaseRow> for Map {
                             value.into()
                         }
                     }
-                    _ => decode_raw::<String>(field, raw_value)?.into(),
+                    _ => decode_raw::<String, MySql>(field, raw_value)?.into(),
                 }
             };
             if !value.is_ignorable() {
@@ -508,47 +567,49 @@ impl DecodeRow<DatabaseRow> for Map {
         }
         Ok(map)
     }
-}
 
-#[cfg(feature = "orm-sqlx")]
-impl DecodeRow<DatabaseRow> for Record {
-    type Error = Error;
-
-    fn decode_row(row: &DatabaseRow) -> Result<Self, Self::Error> {
-        let columns = row.columns();
+    fn decode_record(&self, dialect: Dialect) -> Result<Record, Error> {
+        let columns = self.columns();
         let mut record = Record::with_capacity(columns.len());
         for col in columns {
             let field = col.name();
             let index = col.ordinal();
-            let raw_value = row.try_get_raw(index)?;
+            let raw_value = self.try_get_raw(index)?;
             let value = if raw_value.is_null() {
                 AvroValue::Null
             } else {
-                use super::decode::decode_raw;
                 match col.type_info().name() {
-                    "BOOLEAN" => decode_raw::<bool>(field, raw_value)?.into(),
-                    "TINYINT" => i32::from(decode_raw::<i8>(field, raw_value)?).into(),
-                    "TINYINT UNSIGNED" => i32::from(decode_raw::<u8>(field, raw_value)?).into(),
-                    "SMALLINT" => i32::from(decode_raw::<i16>(field, raw_value)?).into(),
-                    "SMALLINT UNSIGNED" => i32::from(decode_raw::<u16>(field, raw_value)?).into(),
-                    "INT" => decode_raw::<i32>(field, raw_value)?.into(),
-                    "INT UNSIGNED" => i32::try_from(decode_raw::<u32>(field, raw_value)?)?.into(),
-                    "BIGINT" => decode_raw::<i64>(field, raw_value)?.into(),
+                    "BOOLEAN" => decode_raw::<bool, MySql>(field, raw_value)?.into(),
+                    "TINYINT" => i32::from(decode_raw::<i8, MySql>(field, raw_value)?).into(),
+                    "TINYINT UNSIGNED" => {
+                        i32::from(decode_raw::<u8, MySql>(field, raw_value)?).into()
+                    }
+                    "SMALLINT" => i32::from(decode_raw::<i16, MySql>(field, raw_value)?).into(),
+                    "SMALLINT UNSIGNED" => {
+                        i32::from(decode_raw::<u16, MySql>(field, raw_value)?).into()
+                    }
+                    "INT" => decode_raw::<i32, MySql>(field, raw_value)?.into(),
+                    "INT UNSIGNED" => {
+                        i32::try_from(decode_raw::<u32, MySql>(field, raw_value)?)?.into()
+                    }
+                    "BIGINT" => decode_raw::<i64, MySql>(field, raw_value)?.into(),
                     "BIGINT UNSIGNED" => {
-                        i64::try_from(decode_raw::<u64>(field, raw_value)?)?.into()
+                        i64::try_from(decode_raw::<u64, MySql>(field, raw_value)?)?.into()
                     }
-                    "FLOAT" => decode_raw::<f32>(field, raw_value)?.into(),
-                    "DOUBLE" => decode_raw::<f64>(field, raw_value)?.into(),
-                    "NUMERIC" => decode_raw::<Decimal>(field, raw_value)?.to_string().into(),
-                    "TIMESTAMP" => decode_raw::<DateTime>(field, raw_value)?.into(),
-                    "DATETIME" => decode_raw::<NaiveDateTime>(field, raw_value)?
+                    "FLOAT" => decode_raw::<f32, MySql>(field, raw_value)?.into(),
+                    "DOUBLE" => decode_raw::<f64, MySql>(field, raw_value)?.into(),
+                    "NUMERIC" => decode_raw::<Decimal, MySql>(field, raw_value)?
+                        .to_string()
+                        .into(),
+                    "TIMESTAMP" => decode_raw::<DateTime, MySql>(field, raw_value)?.into(),
+                    "DATETIME" => decode_raw::<NaiveDateTime, MySql>(field, raw_value)?
                         .to_string()
                         .into(),
-                    "DATE" => decode_raw::<Date>(field, raw_value)?.into(),
-                    "TIME" => decode_raw::<Time>(field, raw_value)?.into(),
+                    "DATE" => decode_raw::<Date, MySql>(field, raw_value)?.into(),
+                    "TIME" => decode_raw::<Time, MySql>(field, raw_value)?.into(),
                     "BYTE" | "BINARY" | "VARBINARY" | "BLOB" => {
-                        let bytes = decode_raw::<Vec<u8>>(field, raw_value)?;
-                        if cfg!(feature = "orm-mariadb")
+                        let bytes = decode_raw::<Vec<u8>, MySql>(field, raw_value)?;
+                        if dialect == Dialect::MariaDb
                             && (bytes.starts_with(b"[") && bytes.ends_with(b"]")
                                 || bytes.starts_with(b"{") && bytes.ends_with(b"}"))
                         {
@@ -565,10 +626,9 @@ impl DecodeRow<DatabaseRow> for Record {
                             bytes.into()
                         }
                     }
-                    "JSON" => decode_raw::<JsonValue>(field, raw_value)?.into(),
-                    #[cfg(feature = "orm-mariadb")]
-                    "TEXT" | "LONGTEXT" => {
-                        let value = decode_raw::<String>(field, raw_value)?;
+                    "JSON" => decode_raw::<JsonValue, MySql>(field, raw_value)?.into(),
+                    "TEXT" | "LONGTEXT" if dialect == Dialect::MariaDb => {
+                        let value = decode_raw::<String, MySql>(field, raw_value)?;
                         if value.starts_with('[') && value.ends_with(']')
                             || value.starts_with('{') && value.ends_with('}')
                         {
@@ -579,7 +639,7 @@ impl DecodeRow<DatabaseRow> for Record {
                             value.into()
                         }
                     }
-                    _ => decode_raw::<String>(field, raw_value)?.into(),
+                    _ => decode_raw::<String, MySql>(field, raw_value)?.into(),
                 }
             };
             record.push((field.to_owned(), value));
@@ -587,137 +647,3 @@ impl DecodeRow<DatabaseRow> for Record {
         Ok(record)
     }
 }
-
-#[cfg(feature = "orm-sqlx")]
-impl QueryExt<DatabaseDriver> for Query {
-    type QueryResult = sqlx::mysql::MySqlQueryResult;
-
-    #[inline]
-    fn parse_query_result(query_result: Self::QueryResult) -> (Option<i64>, u64) {
-        let last_insert_id = query_result.last_insert_id();
-        let rows_affected = query_result.rows_affected();
-        (last_insert_id.try_into().ok(), rows_affected)
-    }
-
-    #[inline]
-    fn query_fields(&self) -> &[String] {
-        self.fields()
-    }
-
-    #[inline]
-    fn query_filters(&self) -> &Map {
-        self.filters()
-    }
-
-    #[inline]
-    fn query_order(&self) -> &[QueryOrder] {
-        self.sort_order()
-    }
-
-    #[inline]
-    fn query_offset(&self) -> usize {
-        self.offset()
-    }
-
-    #[inline]
-    fn query_limit(&self) -> usize {
-        self.limit()
-    }
-
-    #[inline]
-    fn placeholder(_n: usize) -> SharedString {
-        "?".into()
-    }
-
-    #[inline]
-    fn prepare_query<'a>(
-        query: &'a str,
-        params: Option<&'a Map>,
-    ) -> (Cow<'a, str>, Vec<&'a JsonValue>) {
-        crate::query::prepare_sql_query(query, params, '?')
-    }
-
-    fn format_field(field: &str) -> Cow<'_, str> {
-        if field.contains('`') {
-            field.into()
-        } else if field.contains('.') {
-            field
-                .split('.')
-                .map(|s| ["`", s, "`"].concat())
-                .collect::<Vec<_>>()
-                .join(".")
-                .into()
-        } else {
-            ["`", field, "`"].concat().into()
-        }
-    }
-
-    fn format_table_fields<M: Schema>(&self) -> Cow<'_, str> {
-        let model_name = M::model_name();
-        let fields = self.query_fields();
-        if fields.is_empty() {
-            "*".into()
-        } else {
-            fields
-                .iter()
-                .map(|field| {
-                    if let Some((alias, expr)) = field.split_once(':') {
-                        let alias = Self::format_field(alias.trim());
-                        format!(r#"{expr} AS {alias}"#)
-                    } else if field.contains('`') {
-                        field.into()
-                    } else if field.contains('.') {
-                        field
-                            .split('.')
-                            .map(|s| ["`", s, "`"].concat())
-                            .collect::<Vec<_>>()
-                            .join(".")
-                    } else {
-                        format!(r#"`{model_name}`.`{field}`"#)
-                    }
-                })
-                .collect::<Vec<_>>()
-                .join(", ")
-                .into()
-        }
-    }
-
-    fn format_table_name<M: Schema>(&self) -> String {
-        let table_name = self
-            .extra()
-            .get_str("table_name")
-            .unwrap_or_else(|| M::table_name());
-        let model_name = M::model_name();
-        if table_name.contains('.') {
-            let table_name = table_name
-                .split('.')
-                .map(|s| ["`", s, "`"].concat())
-                .collect::<Vec<_>>()
-                .join(".");
-            format!(r#"{table_name} AS `{model_name}`"#)
-        } else {
-            format!(r#"`{table_name}` AS `{model_name}`"#)
-        }
-    }
-
-    fn escape_table_name(table_name: &str) -> String {
-        if table_name.contains('.') {
-            table_name
-                .split('.')
-                .map(|s| ["`", s, "`"].concat())
-                .collect::<Vec<_>>()
-                .join(".")
-        } else {
-            ["`", table_name, "`"].concat()
-        }
-    }
-
-    fn parse_text_search(filter: &Map) -> Option<String> {
-        let fields = filter.parse_str_array("$fields")?;
-        filter.parse_string("$search").map(|search| {
-            let fields = fields.join(", ");
-            let search = Query::escape_string(search.as_ref());
-            format!("match({fields}) against({search})")
-        })
-    }
-}
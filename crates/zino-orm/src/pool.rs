@@ -1,5 +1,8 @@
-use super::DatabasePool;
-use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering::Relaxed};
+use super::{DatabasePool, Dialect, migration::MigrationMode};
+use std::{
+    sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering::Relaxed},
+    time::Duration,
+};
 
 /// A database connection pool with metadata.
 #[derive(Debug)]
@@ -8,6 +11,8 @@ pub struct ConnectionPool<P = DatabasePool> {
     name: &'static str,
     /// Database.
     database: &'static str,
+    /// SQL dialect.
+    dialect: Dialect,
     /// Pool.
     pool: P,
     /// Availability.
@@ -16,6 +21,14 @@ pub struct ConnectionPool<P = DatabasePool> {
     missed_count: AtomicUsize,
     /// Auto migration.
     auto_migration: AtomicBool,
+    /// Migration mode.
+    migration_mode: MigrationMode,
+    /// A flag to indicate whether it is a read replica.
+    replica: bool,
+    /// Smoothed latency in microseconds.
+    latency: AtomicU64,
+    /// Replication lag in milliseconds.
+    replication_lag: AtomicU64,
 }
 
 impl<P> ConnectionPool<P> {
@@ -25,10 +38,15 @@ impl<P> ConnectionPool<P> {
         Self {
             name,
             database,
+            dialect: Dialect::default(),
             pool,
             available: AtomicBool::new(true),
             missed_count: AtomicUsize::new(0),
             auto_migration: AtomicBool::new(true),
+            migration_mode: MigrationMode::Disabled,
+            replica: false,
+            latency: AtomicU64::new(0),
+            replication_lag: AtomicU64::new(0),
         }
     }
 
@@ -49,12 +67,55 @@ impl<P> ConnectionPool<P> {
         }
     }
 
+    /// Sets the SQL dialect of the database service.
+    #[inline]
+    pub fn set_dialect(&mut self, dialect: Dialect) {
+        self.dialect = dialect;
+    }
+
     /// Disables auto migration.
     #[inline]
     pub fn disable_auto_migration(&self) {
         self.auto_migration.store(false, Relaxed);
     }
 
+    /// Sets the mode of running versioned migrations on startup.
+    /// Auto migration will be disabled if the versioned migrations are enabled.
+    #[inline]
+    pub fn set_migration_mode(&mut self, mode: MigrationMode) {
+        if mode != MigrationMode::Disabled {
+            self.disable_auto_migration();
+        }
+        self.migration_mode = mode;
+    }
+
+    /// Marks the connection pool as a read replica.
+    /// Auto migration will be disabled since the replica is read-only.
+    #[inline]
+    pub fn set_replica(&mut self) {
+        self.disable_auto_migration();
+        self.replica = true;
+    }
+
+    /// Records a sample of the latency, which is smoothed by an exponential moving average.
+    pub fn record_latency(&self, latency: Duration) {
+        let sample = u64::try_from(latency.as_micros()).unwrap_or(u64::MAX);
+        let _ = self.latency.fetch_update(Relaxed, Relaxed, |value| {
+            if value == 0 {
+                Some(sample)
+            } else {
+                Some(value - value / 8 + sample / 8)
+            }
+        });
+    }
+
+    /// Stores the replication lag of the read replica.
+    #[inline]
+    pub fn store_replication_lag(&self, lag: Duration) {
+        let lag = u64::try_from(lag.as_millis()).unwrap_or(u64::MAX);
+        self.replication_lag.store(lag, Relaxed);
+    }
+
     /// Returns the number of missed count.
     #[inline]
     pub fn missed_count(&self) -> usize {
@@ -86,6 +147,30 @@ impl<P> ConnectionPool<P> {
         self.auto_migration.load(Relaxed)
     }
 
+    /// Returns the mode of running versioned migrations on startup.
+    #[inline]
+    pub fn migration_mode(&self) -> MigrationMode {
+        self.migration_mode
+    }
+
+    /// Returns `true` if the connection pool is a read replica.
+    #[inline]
+    pub fn is_replica(&self) -> bool {
+        self.replica
+    }
+
+    /// Returns the smoothed latency.
+    #[inline]
+    pub fn latency(&self) -> Duration {
+        Duration::from_micros(self.latency.load(Relaxed))
+    }
+
+    /// Returns the replication lag of the read replica.
+    #[inline]
+    pub fn replication_lag(&self) -> Duration {
+        Duration::from_millis(self.replication_lag.load(Relaxed))
+    }
+
     /// Returns the name.
     #[inline]
     pub fn name(&self) -> &'static str {
@@ -98,6 +183,12 @@ impl<P> ConnectionPool<P> {
         self.database
     }
 
+    /// Returns the SQL dialect.
+    #[inline]
+    pub fn dialect(&self) -> Dialect {
+        self.dialect
+    }
+
     /// Returns a reference to the pool.
     #[inline]
     pub fn pool(&self) -> &P {
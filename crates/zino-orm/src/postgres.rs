@@ -1,33 +1,36 @@
-use super::{DatabaseDriver, DatabaseRow, DecodeRow, EncodeColumn, Schema, query::QueryExt};
+use super::{
+    DatabaseRow, Dialect, Schema,
+    decode::decode_raw,
+    driver::{DriverExt, RowExt},
+    query::QueryExt,
+};
 use chrono::NaiveDateTime;
+use sqlx::{Column as _, Postgres, Row, TypeInfo, ValueRef, types::Decimal};
 use std::borrow::Cow;
 use zino_core::{
-    AvroValue, JsonValue, Map, Record, SharedString, Uuid,
+    AvroValue, JsonValue, Map, Record, Uuid,
     datetime::{Date, DateTime, Time},
     error::Error,
     extension::{JsonObjectExt, JsonValueExt},
-    model::{Column, Query, QueryOrder},
+    model::{Column, Query},
 };
 
-#[cfg(feature = "orm-sqlx")]
-use sqlx::{Column as _, Row, TypeInfo, ValueRef, types::Decimal};
-
-impl EncodeColumn<DatabaseDriver> for Column<'_> {
-    fn column_type(&self) -> &str {
-        if let Some(column_type) = self.extra().get_str("column_type") {
+impl DriverExt for Postgres {
+    fn column_type<'a>(col: &'a Column<'_>, _dialect: Dialect) -> &'a str {
+        if let Some(column_type) = col.extra().get_str("column_type") {
             return column_type;
         }
-        match self.type_name() {
+        match col.type_name() {
             "bool" => "BOOLEAN",
             "u64" | "i64" | "usize" | "isize" | "Option<u64>" | "Option<i64>" => {
-                if self.auto_increment() {
+                if col.auto_increment() {
                     "BIGSERIAL"
                 } else {
                     "BIGINT"
                 }
             }
             "u32" | "i32" | "Option<u32>" | "Option<i32>" => {
-                if self.auto_increment() {
+                if col.auto_increment() {
                     "SERIAL"
                 } else {
                     "INT"
@@ -54,7 +57,11 @@ impl EncodeColumn<DatabaseDriver> for Column<'_> {
         }
     }
 
-    fn encode_value<'a>(&self, value: Option<&'a JsonValue>) -> Cow<'a, str> {
+    fn encode_value<'a>(
+        col: &Column<'_>,
+        dialect: Dialect,
+        value: Option<&'a JsonValue>,
+    ) -> Cow<'a, str> {
         if let Some(value) = value {
             match value {
                 JsonValue::Null => "NULL".into(),
@@ -65,8 +72,8 @@ impl EncodeColumn<DatabaseDriver> for Column<'_> {
                 JsonValue::Number(n) => n.to_string().into(),
                 JsonValue::String(s) => {
                     if s.is_empty() {
-                        if let Some(value) = self.default_value() {
-                            self.format_value(value).into_owned().into()
+                        if let Some(value) = col.default_value() {
+                            Self::format_value(col, dialect, value).into_owned().into()
                         } else {
                             "''".into()
                         }
@@ -75,7 +82,7 @@ impl EncodeColumn<DatabaseDriver> for Column<'_> {
                     } else if s == "not_null" {
                         "NOT NULL".into()
                     } else {
-                        self.format_value(s)
+                        Self::format_value(col, dialect, s)
                     }
                 }
                 JsonValue::Array(vec) => {
@@ -83,24 +90,32 @@ impl EncodeColumn<DatabaseDriver> for Column<'_> {
                         .iter()
                         .map(|v| match v {
                             JsonValue::String(v) => Query::escape_string(v),
-                            _ => self.encode_value(Some(v)).into_owned(),
+                            _ => Self::encode_value(col, dialect, Some(v)).into_owned(),
                         })
                         .collect::<Vec<_>>();
-                    format!("ARRAY[{}]::{}", values.join(","), self.column_type()).into()
-                }
-                JsonValue::Object(_) => {
-                    format!("{}::{}", Query::escape_string(value), self.column_type()).into()
+                    format!(
+                        "ARRAY[{}]::{}",
+                        values.join(","),
+                        Self::column_type(col, dialect)
+                    )
+                    .into()
                 }
+                JsonValue::Object(_) => format!(
+                    "{}::{}",
+                    Query::escape_string(value),
+                    Self::column_type(col, dialect)
+                )
+                .into(),
             }
-        } else if self.default_value().is_some() {
+        } else if col.default_value().is_some() {
             "DEFAULT".into()
         } else {
             "NULL".into()
         }
     }
 
-    fn format_value<'a>(&self, value: &'a str) -> Cow<'a, str> {
-        match self.type_name() {
+    fn format_value<'a>(col: &Column<'_>, dialect: Dialect, value: &'a str) -> Cow<'a, str> {
+        match col.type_name() {
             "bool" => {
                 let value = if value == "true" { "TRUE" } else { "FALSE" };
                 value.into()
@@ -150,7 +165,7 @@ impl EncodeColumn<DatabaseDriver> for Column<'_> {
             "Vec<u8>" => format!(r"'\x{value}'").into(),
             "Vec<Uuid>" | "Vec<String>" | "Vec<u64>" | "Vec<i64>" | "Vec<u32>" | "Vec<i32>"
             | "Vec<f64>" | "Vec<f32>" => {
-                let column_type = self.column_type();
+                let column_type = Self::column_type(col, dialect);
                 if value.contains(',') {
                     let values = value
                         .split(',')
@@ -170,12 +185,12 @@ impl EncodeColumn<DatabaseDriver> for Column<'_> {
         }
     }
 
-    fn format_filter(&self, field: &str, value: &JsonValue) -> String {
-        let type_name = self.type_name();
-        let field = Query::format_field(field);
+    fn format_filter(col: &Column<'_>, dialect: Dialect, field: &str, value: &JsonValue) -> String {
+        let type_name = col.type_name();
+        let field = dialect.format_field(field);
         if let Some(filter) = value.as_object() {
             if type_name == "Map" {
-                let value = self.encode_value(Some(value));
+                let value = Self::encode_value(col, dialect, Some(value));
                 return format!(r#"{field} @> {value}"#);
             } else {
                 let mut conditions = Vec::with_capacity(filter.len());
@@ -214,7 +229,7 @@ impl EncodeColumn<DatabaseDriver> for Column<'_> {
                             } else {
                                 let value = values
                                     .iter()
-                                    .map(|v| self.encode_value(Some(v)))
+                                    .map(|v| Self::encode_value(col, dialect, Some(v)))
                                     .collect::<Vec<_>>()
                                     .join(", ");
                                 let condition = format!(r#"{field} {operator} ({value})"#);
@@ -224,20 +239,20 @@ impl EncodeColumn<DatabaseDriver> for Column<'_> {
                     } else if operator == "BETWEEN" {
                         if let Some(values) = value.as_array() {
                             if let [min_value, max_value] = values.as_slice() {
-                                let min_value = self.encode_value(Some(min_value));
-                                let max_value = self.encode_value(Some(max_value));
-                                let condition =
-                                    format!(r#"({field} BETWEEN {min_value} AND {max_value})"#);
-                                conditions.push(condition);
-                            }
-                        } else if let Some(values) = value.parse_str_array() {
-                            if let [min_value, max_value] = values.as_slice() {
-                                let min_value = self.format_value(min_value);
-                                let max_value = self.format_value(max_value);
+                                let min_value = Self::encode_value(col, dialect, Some(min_value));
+                                let max_value = Self::encode_value(col, dialect, Some(max_value));
                                 let condition =
                                     format!(r#"({field} BETWEEN {min_value} AND {max_value})"#);
                                 conditions.push(condition);
                             }
+                        } else if let Some(values) = value.parse_str_array()
+                            && let [min_value, max_value] = values.as_slice()
+                        {
+                            let min_value = Self::format_value(col, dialect, min_value);
+                            let max_value = Self::format_value(col, dialect, max_value);
+                            let condition =
+                                format!(r#"({field} BETWEEN {min_value} AND {max_value})"#);
+                            conditions.push(condition);
                         }
                     } else if operator == "array_length" {
                         if let Some(Ok(length)) = value.parse_usize() {
@@ -245,7 +260,7 @@ impl EncodeColumn<DatabaseDriver> for Column<'_> {
                             conditions.push(condition);
                         }
                     } else {
-                        let value = self.encode_value(Some(value));
+                        let value = Self::encode_value(col, dialect, Some(value));
                         let condition = format!(r#"{field} {operator} {value}"#);
                         conditions.push(condition);
                     }
@@ -258,8 +273,8 @@ impl EncodeColumn<DatabaseDriver> for Column<'_> {
             }
         } else if value.is_null() {
             return format!(r#"{field} IS NULL"#);
-        } else if self.has_attribute("exact_filter") {
-            let value = self.encode_value(Some(value));
+        } else if col.has_attribute("exact_filter") {
+            let value = Self::encode_value(col, dialect, Some(value));
             return format!(r#"{field} = {value}"#);
         } else if let Some(value) = value.as_str() {
             if value == "null" {
@@ -267,17 +282,17 @@ impl EncodeColumn<DatabaseDriver> for Column<'_> {
             } else if value == "not_null" {
                 return format!(r#"{field} IS NOT NULL"#);
             } else if let Some((min_value, max_value)) =
-                value.split_once(',').filter(|_| self.is_temporal_type())
+                value.split_once(',').filter(|_| col.is_temporal_type())
             {
-                let min_value = self.format_value(min_value);
-                let max_value = self.format_value(max_value);
+                let min_value = Self::format_value(col, dialect, min_value);
+                let max_value = Self::format_value(col, dialect, max_value);
                 return format!(r#"{field} >= {min_value} AND {field} < {max_value}"#);
             }
         }
 
         match type_name {
             "bool" => {
-                let value = self.encode_value(Some(value));
+                let value = Self::encode_value(col, dialect, Some(value));
                 if value == "TRUE" {
                     format!(r#"{field} IS TRUE"#)
                 } else {
@@ -293,11 +308,11 @@ impl EncodeColumn<DatabaseDriver> for Column<'_> {
                         let value = value.split(',').collect::<Vec<_>>().join(",");
                         format!(r#"{field} IN ({value})"#)
                     } else {
-                        let value = self.format_value(value);
+                        let value = Self::format_value(col, dialect, value);
                         format!(r#"{field} = {value}"#)
                     }
                 } else {
-                    let value = self.encode_value(Some(value));
+                    let value = Self::encode_value(col, dialect, Some(value));
                     format!(r#"{field} = {value}"#)
                 }
             }
@@ -308,7 +323,7 @@ impl EncodeColumn<DatabaseDriver> for Column<'_> {
                         format!(r#"({field} = '') IS NOT FALSE"#)
                     } else if value == "nonempty" {
                         format!(r#"({field} = '') IS FALSE"#)
-                    } else if self.fuzzy_search() {
+                    } else if col.fuzzy_search() {
                         if value.contains(',') {
                             let exprs = value
                                 .split(',')
@@ -341,14 +356,14 @@ impl EncodeColumn<DatabaseDriver> for Column<'_> {
                         }
                     }
                 } else {
-                    let value = self.encode_value(Some(value));
+                    let value = Self::encode_value(col, dialect, Some(value));
                     format!(r#"{field} = {value}"#)
                 }
             }
             "DateTime" | "NaiveDateTime" => {
                 if let Some(value) = value.as_str() {
                     let length = value.len();
-                    let value = self.format_value(value);
+                    let value = Self::format_value(col, dialect, value);
                     match length {
                         4 => format!(r#"to_char({field}, 'YYYY') = {value}"#),
                         7 => format!(r#"to_char({field}, 'YYYY-MM') = {value}"#),
@@ -356,28 +371,28 @@ impl EncodeColumn<DatabaseDriver> for Column<'_> {
                         _ => format!(r#"{field} = {value}"#),
                     }
                 } else {
-                    let value = self.encode_value(Some(value));
+                    let value = Self::encode_value(col, dialect, Some(value));
                     format!(r#"{field} = {value}"#)
                 }
             }
             "Date" | "NaiveDate" => {
                 if let Some(value) = value.as_str() {
                     let length = value.len();
-                    let value = self.format_value(value);
+                    let value = Self::format_value(col, dialect, value);
                     match length {
                         4 => format!(r#"to_char({field}, 'YYYY') = {value}"#),
                         7 => format!(r#"to_char({field}, 'YYYY-MM') = {value}"#),
                         _ => format!(r#"{field} = {value}"#),
                     }
                 } else {
-                    let value = self.encode_value(Some(value));
+                    let value = Self::encode_value(col, dialect, Some(value));
                     format!(r#"{field} = {value}"#)
                 }
             }
             "Time" | "NaiveTime" => {
                 if let Some(value) = value.as_str() {
                     let length = value.len();
-                    let value = self.format_value(value);
+                    let value = Self::format_value(col, dialect, value);
                     match length {
                         2 => format!(r#"to_char({field}, 'HH24') = {value}"#),
                         5 => format!(r#"to_char({field}, 'HH24:MI') = {value}"#),
@@ -385,7 +400,7 @@ impl EncodeColumn<DatabaseDriver> for Column<'_> {
                         _ => format!(r#"{field} = {value}"#),
                     }
                 } else {
-                    let value = self.encode_value(Some(value));
+                    let value = Self::encode_value(col, dialect, Some(value));
                     format!(r#"{field} = {value}"#)
                 }
             }
@@ -403,7 +418,7 @@ impl EncodeColumn<DatabaseDriver> for Column<'_> {
                         format!(r#"{field} = {value}"#)
                     }
                 } else {
-                    let value = self.encode_value(Some(value));
+                    let value = Self::encode_value(col, dialect, Some(value));
                     format!(r#"{field} = {value}"#)
                 }
             }
@@ -417,17 +432,17 @@ impl EncodeColumn<DatabaseDriver> for Column<'_> {
                             .split(',')
                             .map(|v| {
                                 let s = v.replace(';', ",");
-                                let value = self.format_value(&s);
+                                let value = Self::format_value(col, dialect, &s);
                                 format!(r#"{field} @> {value}"#)
                             })
                             .collect::<Vec<_>>();
                         format!("({})", exprs.join(" OR "))
                     } else {
-                        let value = self.format_value(value);
+                        let value = Self::format_value(col, dialect, value);
                         format!(r#"{field} && {value}"#)
                     }
                 } else {
-                    let value = self.encode_value(Some(value));
+                    let value = Self::encode_value(col, dialect, Some(value));
                     format!(r#"{field} && {value}"#)
                 }
             }
@@ -437,66 +452,88 @@ impl EncodeColumn<DatabaseDriver> for Column<'_> {
                     let value = Query::escape_string(value);
                     format!(r#"{field} @? {value}"#)
                 } else {
-                    let value = self.encode_value(Some(value));
+                    let value = Self::encode_value(col, dialect, Some(value));
                     format!(r#"{field} @> {value}"#)
                 }
             }
             _ => {
-                let value = self.encode_value(Some(value));
+                let value = Self::encode_value(col, dialect, Some(value));
                 format!(r#"{field} = {value}"#)
             }
         }
     }
+
+    fn parse_text_search<M: Schema>(filter: &Map) -> Option<String> {
+        let (_, document, text_query) = parse_text_search_parts(filter)?;
+        Some(format!("{document} @@ {text_query}"))
+    }
+
+    fn format_text_search_rank<M: Schema>(filter: &Map) -> Option<String> {
+        let (_, document, text_query) = parse_text_search_parts(filter)?;
+        Some(format!("ts_rank({document}, {text_query})"))
+    }
+
+    fn format_text_search_highlight<M: Schema>(filter: &Map, field: &str) -> Option<String> {
+        let (lang, _, text_query) = parse_text_search_parts(filter)?;
+        let field = M::dialect().format_field(field);
+        Some(format!(
+            "ts_headline({lang}, coalesce({field}, ''), {text_query}, \
+                'StartSel=<mark>, StopSel=</mark>')"
+        ))
+    }
 }
 
-#[cfg(feature = "orm-sqlx")]
-impl DecodeRow<DatabaseRow> for Map {
-    type Error = Error;
+impl RowExt for sqlx::postgres::PgRow {
+    #[inline]
+    fn into_database_row(self, _dialect: Dialect) -> DatabaseRow {
+        DatabaseRow::Postgres(self)
+    }
 
-    fn decode_row(row: &DatabaseRow) -> Result<Self, Self::Error> {
+    fn decode_map(&self, _dialect: Dialect) -> Result<Map, Error> {
         let mut map = Map::new();
-        for col in row.columns() {
+        for col in self.columns() {
             let field = col.name();
             let index = col.ordinal();
-            let raw_value = row.try_get_raw(index)?;
+            let raw_value = self.try_get_raw(index)?;
             let value = if raw_value.is_null() {
                 JsonValue::Null
             } else {
-                use super::decode::decode_raw;
                 match col.type_info().name() {
-                    "BOOL" => decode_raw::<bool>(field, raw_value)?.into(),
-                    "INT2" => decode_raw::<i16>(field, raw_value)?.into(),
-                    "INT4" => decode_raw::<i32>(field, raw_value)?.into(),
-                    "INT8" => decode_raw::<i64>(field, raw_value)?.into(),
-                    "FLOAT4" => decode_raw::<f32>(field, raw_value)?.into(),
-                    "FLOAT8" => decode_raw::<f64>(field, raw_value)?.into(),
+                    "BOOL" => decode_raw::<bool, Postgres>(field, raw_value)?.into(),
+                    "INT2" => decode_raw::<i16, Postgres>(field, raw_value)?.into(),
+                    "INT4" => decode_raw::<i32, Postgres>(field, raw_value)?.into(),
+                    "INT8" => decode_raw::<i64, Postgres>(field, raw_value)?.into(),
+                    "FLOAT4" => decode_raw::<f32, Postgres>(field, raw_value)?.into(),
+                    "FLOAT8" => decode_raw::<f64, Postgres>(field, raw_value)?.into(),
                     "NUMERIC" => {
-                        let value = decode_raw::<Decimal>(field, raw_value)?;
+                        let value = decode_raw::<Decimal, Postgres>(field, raw_value)?;
                         serde_json::to_value(value)?
                     }
-                    "TIMESTAMPTZ" => decode_raw::<DateTime>(field, raw_value)?.into(),
-                    "TIMESTAMP" => decode_raw::<NaiveDateTime>(field, raw_value)?
+                    "TIMESTAMPTZ" => decode_raw::<DateTime, Postgres>(field, raw_value)?.into(),
+                    "TIMESTAMP" => decode_raw::<NaiveDateTime, Postgres>(field, raw_value)?
                         .to_string()
                         .into(),
-                    "DATE" => decode_raw::<Date>(field, raw_value)?.into(),
-                    "TIME" => decode_raw::<Time>(field, raw_value)?.into(),
-                    "UUID" => decode_raw::<Uuid>(field, raw_value)?.to_string().into(),
-                    "BYTEA" => decode_raw::<Vec<u8>>(field, raw_value)?.into(),
-                    "INT4[]" => decode_raw::<Vec<i32>>(field, raw_value)?.into(),
-                    "INT8[]" => decode_raw::<Vec<i64>>(field, raw_value)?.into(),
-                    "FLOAT4[]" => decode_raw::<Vec<f32>>(field, raw_value)?.into(),
-                    "FLOAT8[]" => decode_raw::<Vec<f64>>(field, raw_value)?.into(),
-                    "TEXT[]" => decode_raw::<Vec<String>>(field, raw_value)?.into(),
+                    "DATE" => decode_raw::<Date, Postgres>(field, raw_value)?.into(),
+                    "TIME" => decode_raw::<Time, Postgres>(field, raw_value)?.into(),
+                    "UUID" => decode_raw::<Uuid, Postgres>(field, raw_value)?
+                        .to_string()
+                        .into(),
+                    "BYTEA" => decode_raw::<Vec<u8>, Postgres>(field, raw_value)?.into(),
+                    "INT4[]" => decode_raw::<Vec<i32>, Postgres>(field, raw_value)?.into(),
+                    "INT8[]" => decode_raw::<Vec<i64>, Postgres>(field, raw_value)?.into(),
+                    "FLOAT4[]" => decode_raw::<Vec<f32>, Postgres>(field, raw_value)?.into(),
+                    "FLOAT8[]" => decode_raw::<Vec<f64>, Postgres>(field, raw_value)?.into(),
+                    "TEXT[]" => decode_raw::<Vec<String>, Postgres>(field, raw_value)?.into(),
                     "UUID[]" => {
-                        let values = decode_raw::<Vec<Uuid>>(field, raw_value)?;
+                        let values = decode_raw::<Vec<Uuid>, Postgres>(field, raw_value)?;
                         values
                             .iter()
                             .map(|v| v.to_string())
                             .collect::<Vec<_>>()
                             .into()
                     }
-                    "JSONB" | "JSON" => decode_raw::<JsonValue>(field, raw_value)?,
-                    _ => decode_raw::<String>(field, raw_value)?.into(),
+                    "JSONB" | "JSON" => decode_raw::<JsonValue, Postgres>(field, raw_value)?,
+                    _ => decode_raw::<String, Postgres>(field, raw_value)?.into(),
                 }
             };
             if !value.is_ignorable() {
@@ -505,70 +542,66 @@ impl DecodeRow<DatabaseRow> for Map {
         }
         Ok(map)
     }
-}
-
-#[cfg(feature = "orm-sqlx")]
-impl DecodeRow<DatabaseRow> for Record {
-    type Error = Error;
 
-    fn decode_row(row: &DatabaseRow) -> Result<Self, Self::Error> {
-        let columns = row.columns();
+    fn decode_record(&self, _dialect: Dialect) -> Result<Record, Error> {
+        let columns = self.columns();
         let mut record = Record::with_capacity(columns.len());
         for col in columns {
             let field = col.name();
             let index = col.ordinal();
-            let raw_value = row.try_get_raw(index)?;
+            let raw_value = self.try_get_raw(index)?;
             let value = if raw_value.is_null() {
                 AvroValue::Null
             } else {
-                use super::decode::decode_raw;
                 match col.type_info().name() {
-                    "BOOL" => decode_raw::<bool>(field, raw_value)?.into(),
-                    "INT4" => decode_raw::<i32>(field, raw_value)?.into(),
-                    "INT8" => decode_raw::<i64>(field, raw_value)?.into(),
-                    "FLOAT4" => decode_raw::<f32>(field, raw_value)?.into(),
-                    "FLOAT8" => decode_raw::<f64>(field, raw_value)?.into(),
-                    "NUMERIC" => decode_raw::<Decimal>(field, raw_value)?.to_string().into(),
-                    "TIMESTAMPTZ" => decode_raw::<DateTime>(field, raw_value)?.into(),
-                    "TIMESTAMP" => decode_raw::<NaiveDateTime>(field, raw_value)?
+                    "BOOL" => decode_raw::<bool, Postgres>(field, raw_value)?.into(),
+                    "INT4" => decode_raw::<i32, Postgres>(field, raw_value)?.into(),
+                    "INT8" => decode_raw::<i64, Postgres>(field, raw_value)?.into(),
+                    "FLOAT4" => decode_raw::<f32, Postgres>(field, raw_value)?.into(),
+                    "FLOAT8" => decode_raw::<f64, Postgres>(field, raw_value)?.into(),
+                    "NUMERIC" => decode_raw::<Decimal, Postgres>(field, raw_value)?
+                        .to_string()
+                        .into(),
+                    "TIMESTAMPTZ" => decode_raw::<DateTime, Postgres>(field, raw_value)?.into(),
+                    "TIMESTAMP" => decode_raw::<NaiveDateTime, Postgres>(field, raw_value)?
                         .to_string()
                         .into(),
-                    "DATE" => decode_raw::<Date>(field, raw_value)?.into(),
-                    "TIME" => decode_raw::<Time>(field, raw_value)?.into(),
-                    "UUID" => decode_raw::<Uuid>(field, raw_value)?.into(),
-                    "BYTEA" => decode_raw::<Vec<u8>>(field, raw_value)?.into(),
+                    "DATE" => decode_raw::<Date, Postgres>(field, raw_value)?.into(),
+                    "TIME" => decode_raw::<Time, Postgres>(field, raw_value)?.into(),
+                    "UUID" => decode_raw::<Uuid, Postgres>(field, raw_value)?.into(),
+                    "BYTEA" => decode_raw::<Vec<u8>, Postgres>(field, raw_value)?.into(),
                     "INT4[]" => {
-                        let values = decode_raw::<Vec<i32>>(field, raw_value)?;
+                        let values = decode_raw::<Vec<i32>, Postgres>(field, raw_value)?;
                         let vec = values.into_iter().map(AvroValue::Int).collect();
                         AvroValue::Array(vec)
                     }
                     "INT8[]" => {
-                        let values = decode_raw::<Vec<i64>>(field, raw_value)?;
+                        let values = decode_raw::<Vec<i64>, Postgres>(field, raw_value)?;
                         let vec = values.into_iter().map(AvroValue::Long).collect();
                         AvroValue::Array(vec)
                     }
                     "FLOAT4[]" => {
-                        let values = decode_raw::<Vec<f32>>(field, raw_value)?;
+                        let values = decode_raw::<Vec<f32>, Postgres>(field, raw_value)?;
                         let vec = values.into_iter().map(AvroValue::Float).collect();
                         AvroValue::Array(vec)
                     }
                     "FLOAT8[]" => {
-                        let values = decode_raw::<Vec<f64>>(field, raw_value)?;
+                        let values = decode_raw::<Vec<f64>, Postgres>(field, raw_value)?;
                         let vec = values.into_iter().map(AvroValue::Double).collect();
                         AvroValue::Array(vec)
                     }
                     "TEXT[]" => {
-                        let values = decode_raw::<Vec<String>>(field, raw_value)?;
+                        let values = decode_raw::<Vec<String>, Postgres>(field, raw_value)?;
                         let vec = values.into_iter().map(AvroValue::String).collect();
                         AvroValue::Array(vec)
                     }
                     "UUID[]" => {
-                        let values = decode_raw::<Vec<Uuid>>(field, raw_value)?;
+                        let values = decode_raw::<Vec<Uuid>, Postgres>(field, raw_value)?;
                         let vec = values.into_iter().map(AvroValue::Uuid).collect();
                         AvroValue::Array(vec)
                     }
-                    "JSONB" | "JSON" => decode_raw::<JsonValue>(field, raw_value)?.into(),
-                    _ => decode_raw::<String>(field, raw_value)?.into(),
+                    "JSONB" | "JSON" => decode_raw::<JsonValue, Postgres>(field, raw_value)?.into(),
+                    _ => decode_raw::<String, Postgres>(field, raw_value)?.into(),
                 }
             };
             record.push((field.to_owned(), value));
@@ -577,141 +610,22 @@ impl DecodeRow<DatabaseRow> for Record {
     }
 }
 
-#[cfg(feature = "orm-sqlx")]
-impl QueryExt<DatabaseDriver> for Query {
-    type QueryResult = sqlx::postgres::PgQueryResult;
-
-    #[inline]
-    fn parse_query_result(query_result: Self::QueryResult) -> (Option<i64>, u64) {
-        let rows_affected = query_result.rows_affected();
-        (None, rows_affected)
-    }
-
-    #[inline]
-    fn query_fields(&self) -> &[String] {
-        self.fields()
-    }
-
-    #[inline]
-    fn query_filters(&self) -> &Map {
-        self.filters()
-    }
-
-    #[inline]
-    fn query_order(&self) -> &[QueryOrder] {
-        self.sort_order()
-    }
-
-    #[inline]
-    fn query_offset(&self) -> usize {
-        self.offset()
-    }
-
-    #[inline]
-    fn query_limit(&self) -> usize {
-        self.limit()
-    }
-
-    #[inline]
-    fn placeholder(n: usize) -> SharedString {
-        if n == 1 {
-            "$1".into()
-        } else {
-            format!("${n}").into()
-        }
-    }
-
-    #[inline]
-    fn prepare_query<'a>(
-        query: &'a str,
-        params: Option<&'a Map>,
-    ) -> (Cow<'a, str>, Vec<&'a JsonValue>) {
-        crate::query::prepare_sql_query(query, params, '$')
-    }
-
-    fn format_field(field: &str) -> Cow<'_, str> {
-        if field.contains('"') {
-            field.into()
-        } else if field.contains('.') {
-            field
-                .split('.')
-                .map(|s| ["\"", s, "\""].concat())
-                .collect::<Vec<_>>()
-                .join(".")
-                .into()
-        } else {
-            ["\"", field, "\""].concat().into()
-        }
-    }
-
-    fn format_table_fields<M: Schema>(&self) -> Cow<'_, str> {
-        let model_name = M::model_name();
-        let fields = self.query_fields();
-        if fields.is_empty() {
-            "*".into()
-        } else {
-            fields
-                .iter()
-                .map(|field| {
-                    if let Some((alias, expr)) = field.split_once(':') {
-                        let alias = Self::format_field(alias.trim());
-                        format!(r#"{expr} AS {alias}"#)
-                    } else if field.contains('"') {
-                        field.into()
-                    } else if field.contains('.') {
-                        field
-                            .split('.')
-                            .map(|s| ["\"", s, "\""].concat())
-                            .collect::<Vec<_>>()
-                            .join(".")
-                    } else {
-                        format!(r#""{model_name}"."{field}""#)
-                    }
-                })
-                .collect::<Vec<_>>()
-                .join(", ")
-                .into()
-        }
-    }
-
-    fn format_table_name<M: Schema>(&self) -> String {
-        let table_name = self
-            .extra()
-            .get_str("table_name")
-            .unwrap_or_else(|| M::table_name());
-        let model_name = M::model_name();
-        if table_name.contains('.') {
-            let table_name = table_name
-                .split('.')
-                .map(|s| ["\"", s, "\""].concat())
-                .collect::<Vec<_>>()
-                .join(".");
-            format!(r#"{table_name} AS "{model_name}""#)
-        } else {
-            format!(r#""{table_name}" AS "{model_name}""#)
-        }
-    }
-
-    fn escape_table_name(table_name: &str) -> String {
-        if table_name.contains('.') {
-            table_name
-                .split('.')
-                .map(|s| ["\"", s, "\""].concat())
-                .collect::<Vec<_>>()
-                .join(".")
-        } else {
-            ["\"", table_name, "\""].concat()
-        }
-    }
-
-    fn parse_text_search(filter: &Map) -> Option<String> {
-        let fields = filter.parse_str_array("$fields")?;
-        filter.parse_string("$search").map(|search| {
-            let text = fields.join(" || ' ' || ");
-            let lang = filter
-                .parse_string("$language")
-                .unwrap_or_else(|| "english".into());
-            format!("to_tsvector('{lang}', {text}) @@ websearch_to_tsquery('{lang}', '{search}')")
-        })
-    }
+/// Parses the text search configuration, the document and the query of the text search filter.
+/// The document matches the expression of the GIN index created for the `fulltext` columns.
+fn parse_text_search_parts(filter: &Map) -> Option<(String, String, String)> {
+    let fields = filter.parse_str_array("$fields")?;
+    let search = filter.parse_string("$search")?;
+    let lang = filter
+        .parse_string("$language")
+        .unwrap_or_else(|| "english".into());
+    let lang = Query::escape_string(lang);
+    let text = fields
+        .into_iter()
+        .map(|field| format!("coalesce({field}, '')"))
+        .collect::<Vec<_>>()
+        .join(" || ' ' || ");
+    let document = format!("to_tsvector({lang}, {text})");
+    let search = Query::escape_string(search);
+    let text_query = format!("websearch_to_tsquery({lang}, {search})");
+    Some((lang, document, text_query))
 }
@@ -75,7 +75,7 @@
 //! | `$or`      | `OR`                | `OR`             | `OR`                  |
 //! | `$not`     | `NOT`               | `NOT`            | `NOT`                 |
 //! | `$rand`    | `rand()`            | `random()`       | `abs(random())`       |
-//! | `$text`    | `match() against()` | `to_tsvector()`  | `MATCH`               |
+//! | `$text`    | `match() against()` | `to_tsvector()`  | `MATCH` (FTS5)        |
 //! | `$eq`      | `=`                 | `=`              | `=`                   |
 //! | `$ne`      | `<>`                | `<>`             | `<>`                  |
 //! | `$lt`      | `<`                 | `<`              | `<`                   |
@@ -96,13 +96,18 @@
 //! [`TypeORM`]: https://typeorm.io/
 //! [`PostgREST`]: https://postgrest.org/
 
-use super::{Aggregation, EncodeColumn, Entity, IntoSqlValue, ModelColumn, Schema, Window};
+use super::{
+    Aggregation, CommonTableExpr, Dialect, EncodeColumn, Entity, IntoSqlValue, ModelColumn, Schema,
+    Window,
+    driver::{DriverExt, with_driver},
+};
 use regex::{Captures, Regex};
 use std::{borrow::Cow, fmt::Display, marker::PhantomData};
 use zino_core::{
-    JsonValue, LazyLock, Map, SharedString,
+    JsonValue, LazyLock, Map, bail,
+    error::Error,
     extension::{JsonObjectExt, JsonValueExt},
-    model::{Query, QueryOrder},
+    model::{Query, QueryCursor, QueryOrder},
 };
 
 /// A query builder for the model entity.
@@ -139,12 +144,20 @@ pub struct QueryBuilder<E: Entity> {
     logical_and: Vec<Map>,
     /// The logical `OR` conditions.
     logical_or: Vec<Map>,
+    /// The full-text search condition.
+    text_search: Map,
+    /// The common table expressions.
+    ctes: Vec<CommonTableExpr>,
     /// Sort order.
     sort_order: Vec<QueryOrder>,
     /// Offset.
     offset: usize,
     /// Limit.
     limit: usize,
+    /// Keyset pagination.
+    keyset: bool,
+    /// Cursor for the keyset pagination.
+    cursor: Option<QueryCursor>,
     /// Extra flags.
     extra: Map,
     /// The phantom data.
@@ -162,9 +175,13 @@ impl<E: Entity> QueryBuilder<E> {
             filters: Map::new(),
             logical_and: Vec::new(),
             logical_or: Vec::new(),
+            text_search: Map::new(),
+            ctes: Vec::new(),
             sort_order: Vec::new(),
             offset: 0,
             limit: 0,
+            keyset: false,
+            cursor: None,
             extra: Map::new(),
             phantom: PhantomData,
         }
@@ -207,7 +224,7 @@ impl<E: Entity> QueryBuilder<E> {
         F: AsRef<str>,
     {
         let col_name = col.into_column_expr();
-        let field = Query::format_field(&col_name);
+        let field = E::dialect().format_field(&col_name);
         let field_alias = [alias.as_ref(), ":", &field].concat();
         self.fields.push(field_alias);
         self
@@ -303,6 +320,66 @@ impl<E: Entity> QueryBuilder<E> {
         self
     }
 
+    /// Adds a full-text search condition for the columns with a `fulltext` index.
+    ///
+    /// # Examples
+    /// ```rust,ignore
+    /// use crate::model::{Post, PostColumn};
+    /// use zino_orm::{QueryBuilder, Schema};
+    ///
+    /// let query = QueryBuilder::<Post>::new()
+    ///     .search([PostColumn::Title, PostColumn::Content], "rust async")
+    ///     .order_by_relevance()
+    ///     .highlight(PostColumn::Content)
+    ///     .limit(10)
+    ///     .build();
+    /// let posts: Vec<Map> = Post::find(&query).await?;
+    /// ```
+    pub fn search<C, V>(mut self, cols: V, text: &str) -> Self
+    where
+        C: ModelColumn<E>,
+        V: Into<Vec<C>>,
+    {
+        let fields = cols
+            .into()
+            .into_iter()
+            .map(|col| col.into_column_expr())
+            .collect::<Vec<_>>();
+        self.text_search.upsert("$fields", fields);
+        self.text_search.upsert("$search", text);
+        self
+    }
+
+    /// Sets the text search configuration of the full-text search.
+    /// It is only supported by PostgreSQL.
+    #[inline]
+    pub fn search_language(mut self, language: &str) -> Self {
+        self.text_search.upsert("$language", language);
+        self
+    }
+
+    /// Orders the results of the full-text search by relevance,
+    /// and adds the `search_rank` field to the projection.
+    #[inline]
+    pub fn order_by_relevance(mut self) -> Self {
+        self.text_search.upsert("$rank", true);
+        self.sort_order
+            .insert(0, QueryOrder::new("search_rank".to_owned(), true));
+        self
+    }
+
+    /// Highlights the matches of the full-text search for the column,
+    /// and adds the `{column}_highlight` field to the projection.
+    pub fn highlight<C: ModelColumn<E>>(mut self, col: C) -> Self {
+        let field = col.into_column_expr();
+        if let Some(JsonValue::Array(fields)) = self.text_search.get_mut("$highlight") {
+            fields.push(field.into());
+        } else {
+            self.text_search.upsert("$highlight", vec![field]);
+        }
+        self
+    }
+
     /// Adds a logical `AND` condition by merging the other query builder.
     pub fn and<M: Entity>(mut self, mut other: QueryBuilder<M>) -> Self {
         let mut logical_and = other.logical_and;
@@ -531,7 +608,7 @@ impl<E: Entity> QueryBuilder<E> {
             .into_iter()
             .map(|col| {
                 let col_name = col.into_column_expr();
-                Query::format_field(&col_name).into_owned()
+                E::dialect().format_field(&col_name).into_owned()
             })
             .collect::<Vec<_>>()
             .join(", ");
@@ -553,7 +630,7 @@ impl<E: Entity> QueryBuilder<E> {
             .into_iter()
             .map(|col| {
                 let col_name = col.into_column_expr();
-                Query::format_field(&col_name).into_owned()
+                E::dialect().format_field(&col_name).into_owned()
             })
             .collect::<Vec<_>>()
             .join(", ");
@@ -898,7 +975,7 @@ impl<E: Entity> QueryBuilder<E> {
             .into_iter()
             .map(|col| {
                 let col_name = col.into_column_expr();
-                Query::format_field(&col_name).into_owned()
+                E::dialect().format_field(&col_name).into_owned()
             })
             .collect::<Vec<_>>()
             .join(", ");
@@ -920,7 +997,7 @@ impl<E: Entity> QueryBuilder<E> {
             .into_iter()
             .map(|col| {
                 let col_name = col.into_column_expr();
-                Query::format_field(&col_name).into_owned()
+                E::dialect().format_field(&col_name).into_owned()
             })
             .collect::<Vec<_>>()
             .join(", ");
@@ -1093,6 +1170,30 @@ impl<E: Entity> QueryBuilder<E> {
         self
     }
 
+    /// Enables the keyset pagination with an optional cursor returned from the previous page.
+    /// The primary key will be appended as a tiebreaker of the sort order.
+    ///
+    /// # Examples
+    /// ```rust,ignore
+    /// use crate::model::{User, UserColumn};
+    /// use zino_core::model::QueryCursor;
+    /// use zino_orm::{QueryBuilder, Schema};
+    ///
+    /// let cursor = QueryCursor::decode(next_cursor)?;
+    /// let query = QueryBuilder::<User>::new()
+    ///     .order_desc(UserColumn::UpdatedAt)
+    ///     .keyset(Some(cursor))
+    ///     .limit(100)
+    ///     .build();
+    /// let users: Vec<User> = User::find(&query).await?;
+    /// ```
+    #[inline]
+    pub fn keyset(mut self, cursor: Option<QueryCursor>) -> Self {
+        self.keyset = true;
+        self.cursor = cursor;
+        self
+    }
+
     /// Enables the model translation.
     #[inline]
     pub fn translate(mut self) -> Self {
@@ -1100,6 +1201,101 @@ impl<E: Entity> QueryBuilder<E> {
         self
     }
 
+    /// Adds a named common table expression composed from the query builder,
+    /// which can be referenced by [`table_name`](Self::table_name).
+    ///
+    /// # Examples
+    /// ```rust,ignore
+    /// use crate::model::{Tag, TagColumn};
+    /// use zino_orm::{QueryBuilder, Schema};
+    ///
+    /// let recent_tags = QueryBuilder::<Tag>::new()
+    ///     .order_desc(TagColumn::UpdatedAt)
+    ///     .limit(100);
+    /// let query = QueryBuilder::<Tag>::new()
+    ///     .cte("recent_tags", recent_tags)
+    ///     .table_name("recent_tags".to_owned())
+    ///     .and_eq(TagColumn::Category, "Rustacean")
+    ///     .build();
+    /// let tags: Vec<Tag> = Tag::find(&query).await?;
+    /// ```
+    pub fn cte<M: Entity + Schema>(mut self, name: &str, subquery: QueryBuilder<M>) -> Self {
+        let cte = CommonTableExpr::new::<M>(name, &subquery.build());
+        self.ctes.push(cte);
+        self
+    }
+
+    /// Adds a named recursive common table expression which starts with the rows selected by
+    /// the `anchor` query and appends the rows selected by the `recursive` query repeatedly,
+    /// where the left column of `on` is equal to the right column of the previous rows.
+    ///
+    /// # Examples
+    /// ```rust,ignore
+    /// use crate::model::{Resource, ResourceColumn};
+    /// use zino_orm::{QueryBuilder, Schema};
+    ///
+    /// let anchor = QueryBuilder::<Resource>::new().and_null(ResourceColumn::ParentId);
+    /// let recursive = QueryBuilder::<Resource>::new()
+    ///     .and_ne(ResourceColumn::Status, "Deleted");
+    /// let query = QueryBuilder::<Resource>::new()
+    ///     .recursive_cte(
+    ///         "resource_tree",
+    ///         anchor,
+    ///         recursive,
+    ///         (ResourceColumn::ParentId, ResourceColumn::Id),
+    ///     )
+    ///     .table_name("resource_tree".to_owned())
+    ///     .build();
+    /// let resources: Vec<Resource> = Resource::find(&query).await?;
+    /// ```
+    pub fn recursive_cte<M, L, R>(
+        mut self,
+        name: &str,
+        anchor: QueryBuilder<M>,
+        recursive: QueryBuilder<M>,
+        on: (L, R),
+    ) -> Self
+    where
+        M: Entity + Schema,
+        L: ModelColumn<M>,
+        R: ModelColumn<M>,
+    {
+        let column = on.0.into_column_expr();
+        let cte = CommonTableExpr::recursive::<M>(
+            name,
+            &anchor.build(),
+            &recursive.build(),
+            &column,
+            on.1.as_ref(),
+        );
+        self.ctes.push(cte);
+        self
+    }
+
+    /// Eager loads the relation declared by `#[derive(Schema)]`.
+    /// The join key of the relation is added to the projection if it has been omitted.
+    ///
+    /// # Examples
+    /// ```rust,ignore
+    /// use crate::model::{Post, PostColumn};
+    /// use zino_orm::{QueryBuilder, Schema};
+    ///
+    /// let query = QueryBuilder::<Post>::new()
+    ///     .and_eq(PostColumn::Status, "Published")
+    ///     .with("author")
+    ///     .with("comments")
+    ///     .build();
+    /// let posts: Vec<PostWithComments> = Post::find(&query).await?;
+    /// ```
+    pub fn with(mut self, relation: &str) -> Self {
+        if let Some(JsonValue::Array(relations)) = self.extra.get_mut("with") {
+            relations.push(relation.into());
+        } else {
+            self.extra.upsert("with", vec![relation]);
+        }
+        self
+    }
+
     /// Builds the model query.
     pub fn build(mut self) -> Query {
         let mut filters = self.filters;
@@ -1107,6 +1303,8 @@ impl<E: Entity> QueryBuilder<E> {
         let having_conditions = self.having_conditions;
         let logical_and = self.logical_and;
         let logical_or = self.logical_or;
+        let text_search = self.text_search;
+        let ctes = self.ctes;
         if !group_by_fields.is_empty() {
             filters.upsert("$group", group_by_fields);
         }
@@ -1119,13 +1317,25 @@ impl<E: Entity> QueryBuilder<E> {
         if !logical_or.is_empty() {
             filters.upsert("$or", logical_or);
         }
+        if text_search.contains_key("$search") {
+            filters.upsert("$text", text_search);
+        }
 
         let mut query = Query::new(filters);
         query.set_fields(self.fields);
         query.set_order(self.sort_order);
         query.set_offset(self.offset);
         query.set_limit(self.limit);
+        if self.keyset {
+            query.order_by_tiebreaker(E::PRIMARY_KEY.into_column_expr());
+        }
+        if let Some(cursor) = self.cursor {
+            query.set_cursor(cursor);
+        }
         query.append_extra_flags(&mut self.extra);
+        for cte in ctes {
+            cte.attach_to(&mut query);
+        }
         query
     }
 
@@ -1177,7 +1387,7 @@ impl<E: Entity + Schema> QueryBuilder<E> {
         let table_name = query.format_table_name::<E>();
         let projection = query.format_table_fields::<E>();
         let filters = query.format_filters::<E>();
-        let sort = query.format_sort();
+        let sort = query.format_sort::<E>();
         let pagination = query.format_pagination();
         format!("(SELECT {projection} FROM {table_name} {filters} {sort} {pagination})")
     }
@@ -1191,13 +1401,7 @@ impl<E: Entity> Default for QueryBuilder<E> {
 }
 
 /// Extension trait for [`Query`](crate::model::Query).
-pub(super) trait QueryExt<DB> {
-    /// Query result type.
-    type QueryResult;
-
-    /// Parses the query result to get `last_insert_id` and `rows_affected`.
-    fn parse_query_result(query_result: Self::QueryResult) -> (Option<i64>, u64);
-
+pub(super) trait QueryExt {
     /// Returns a reference to the projection fields.
     fn query_fields(&self) -> &[String];
 
@@ -1213,29 +1417,71 @@ pub(super) trait QueryExt<DB> {
     /// Returns the query limit.
     fn query_limit(&self) -> usize;
 
-    /// Returns a placeholder for the n-th parameter.
-    fn placeholder(n: usize) -> SharedString;
+    /// Returns a reference to the cursor for the keyset pagination.
+    fn query_cursor(&self) -> Option<&QueryCursor>;
 
-    /// Prepares the SQL query for binding parameters.
+    /// Returns a reference to the extra flags.
+    fn query_extra(&self) -> &Map;
+
+    /// Prepares the SQL query for binding parameters in the dialect.
+    #[inline]
     fn prepare_query<'a>(
+        dialect: Dialect,
         query: &'a str,
         params: Option<&'a Map>,
-    ) -> (Cow<'a, str>, Vec<&'a JsonValue>);
-
-    /// Formats a field for the query.
-    fn format_field(field: &str) -> Cow<'_, str>;
+    ) -> (Cow<'a, str>, Vec<&'a JsonValue>) {
+        let placeholder = if dialect == Dialect::Postgres {
+            '$'
+        } else {
+            '?'
+        };
+        prepare_sql_query(query, params, placeholder)
+    }
 
-    /// Formats table fields.
-    fn format_table_fields<M: Schema>(&self) -> Cow<'_, str>;
+    /// Formats table fields in the dialect of the model.
+    fn format_table_fields<M: Schema>(&self) -> Cow<'_, str> {
+        let dialect = M::dialect();
+        let model_name = M::model_name();
+        let fields = self.query_fields();
+        let projection: Cow<'_, str> = if fields.is_empty() {
+            "*".into()
+        } else {
+            fields
+                .iter()
+                .map(|field| {
+                    if let Some((alias, expr)) = field.split_once(':') {
+                        let alias = dialect.format_field(alias.trim());
+                        format!(r#"{expr} AS {alias}"#)
+                    } else if field.contains(dialect.identifier_quote()) || field.contains('.') {
+                        dialect.format_field(field).into_owned()
+                    } else {
+                        dialect
+                            .format_field(&[model_name, ".", field].concat())
+                            .into_owned()
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(", ")
+                .into()
+        };
+        if let Some(text_search_fields) = self.format_text_search_fields::<M>() {
+            format!("{projection}, {text_search_fields}").into()
+        } else {
+            projection
+        }
+    }
 
-    /// Formats the table name.
+    /// Formats the table name in the dialect of the model.
     fn format_table_name<M: Schema>(&self) -> String;
 
-    /// Escapes the table name.
-    fn escape_table_name(table_name: &str) -> String;
-
     /// Parses text search filter.
-    fn parse_text_search(filter: &Map) -> Option<String>;
+    fn parse_text_search<M: Schema>(filter: &Map) -> Option<String>;
+
+    /// Formats the relevance of the text search, where a larger value is more relevant.
+    fn format_text_search_rank<M: Schema>(filter: &Map) -> Option<String>;
+
+    /// Formats the snippet of the field with the matches of the text search highlighted.
+    fn format_text_search_highlight<M: Schema>(filter: &Map, field: &str) -> Option<String>;
 
     /// Escapes a string.
     #[inline]
@@ -1243,8 +1489,8 @@ pub(super) trait QueryExt<DB> {
         format!("'{}'", value.to_string().replace('\'', "''"))
     }
 
-    /// Formats projection fields.
-    fn format_projection(&self) -> Cow<'_, str> {
+    /// Formats projection fields in the dialect.
+    fn format_projection(&self, dialect: Dialect) -> Cow<'_, str> {
         let fields = self.query_fields();
         if fields.is_empty() {
             "*".into()
@@ -1253,10 +1499,10 @@ pub(super) trait QueryExt<DB> {
                 .iter()
                 .map(|field| {
                     if let Some((alias, expr)) = field.split_once(':') {
-                        let alias = Self::format_field(alias.trim());
+                        let alias = dialect.format_field(alias.trim());
                         format!(r#"{expr} AS {alias}"#).into()
                     } else {
-                        Self::format_field(field)
+                        dialect.format_field(field)
                     }
                 })
                 .collect::<Vec<_>>()
@@ -1265,15 +1511,48 @@ pub(super) trait QueryExt<DB> {
         }
     }
 
+    /// Formats the `search_rank` and `{field}_highlight` fields for the text search.
+    fn format_text_search_fields<M: Schema>(&self) -> Option<String> {
+        let dialect = M::dialect();
+        let filter = self.query_filters().get_object("$text")?;
+        let mut fields = Vec::new();
+        if filter.get_bool("$rank") == Some(true)
+            && let Some(rank) = Self::format_text_search_rank::<M>(filter)
+        {
+            let alias = dialect.format_field("search_rank");
+            fields.push(format!("{rank} AS {alias}"));
+        }
+        if let Some(highlight_fields) = filter.parse_str_array("$highlight") {
+            for field in highlight_fields {
+                if let Some(snippet) = Self::format_text_search_highlight::<M>(filter, field) {
+                    let name = field.rsplit_once('.').map_or(field, |(_, name)| name);
+                    let alias = dialect
+                        .format_field(&format!("{name}_highlight"))
+                        .into_owned();
+                    fields.push(format!("{snippet} AS {alias}"));
+                }
+            }
+        }
+        (!fields.is_empty()).then(|| fields.join(", "))
+    }
+
     /// Formats the query filters to generate SQL `WHERE` expression.
     fn format_filters<M: Schema>(&self) -> String {
+        let dialect = M::dialect();
         let filters = self.query_filters();
-        if filters.is_empty() {
+        let keyset_condition = self
+            .query_cursor()
+            .map(|cursor| Self::format_keyset_condition::<M>(cursor, self.query_order()))
+            .filter(|condition| !condition.is_empty());
+        if filters.is_empty() && keyset_condition.is_none() {
             return String::new();
         }
 
         let mut expression = String::new();
-        let mut logical_and_conditions = Vec::with_capacity(filters.len());
+        let mut logical_and_conditions = Vec::with_capacity(filters.len() + 1);
+        if let Some(condition) = keyset_condition {
+            logical_and_conditions.push(condition);
+        }
         for (key, value) in filters {
             match key.as_str() {
                 "$and" => {
@@ -1296,23 +1575,21 @@ pub(super) trait QueryExt<DB> {
                 }
                 "$rand" => {
                     if let Some(Ok(value)) = value.parse_f64() {
-                        let condition = if cfg!(any(
-                            feature = "orm-mariadb",
-                            feature = "orm-mysql",
-                            feature = "orm-tidb"
-                        )) {
-                            format!("rand() < {value}")
-                        } else if cfg!(feature = "orm-postgres") {
-                            format!("random() < {value}")
-                        } else {
-                            let value = (value * i64::MAX as f64) as i64;
-                            format!("abs(random()) < {value}")
+                        let condition = match dialect {
+                            Dialect::Postgres => format!("random() < {value}"),
+                            Dialect::Sqlite => {
+                                let value = (value * i64::MAX as f64) as i64;
+                                format!("abs(random()) < {value}")
+                            }
+                            _ => format!("rand() < {value}"),
                         };
                         logical_and_conditions.push(condition);
                     }
                 }
                 "$text" => {
-                    if let Some(condition) = value.as_object().and_then(Self::parse_text_search) {
+                    if let Some(condition) =
+                        value.as_object().and_then(Self::parse_text_search::<M>)
+                    {
                         logical_and_conditions.push(condition);
                     }
                 }
@@ -1321,10 +1598,10 @@ pub(super) trait QueryExt<DB> {
                         let condition = if let Some(subquery) =
                             value.as_object().and_then(|m| m.get_str("$subquery"))
                         {
-                            let key = Self::format_field(key);
+                            let key = dialect.format_field(key);
                             format!(r#"{key} = {subquery}"#)
                         } else {
-                            col.format_filter(key, value)
+                            col.format_filter(dialect, key, value)
                         };
                         if !condition.is_empty() {
                             logical_and_conditions.push(condition);
@@ -1344,7 +1621,7 @@ pub(super) trait QueryExt<DB> {
         if let Some(groups) = filters.parse_str_array("$group") {
             let groups = groups
                 .into_iter()
-                .map(Self::format_field)
+                .map(|field| dialect.format_field(field))
                 .collect::<Vec<_>>()
                 .join(", ");
             expression += &format!(" GROUP BY {groups}");
@@ -1356,8 +1633,122 @@ pub(super) trait QueryExt<DB> {
         expression
     }
 
+    /// Checks the cursor for the keyset pagination.
+    /// The sort keys should be the columns of the model and match the sort order of the query.
+    fn check_cursor<M: Schema>(&self) -> Result<(), Error> {
+        let Some(cursor) = self.query_cursor() else {
+            return Ok(());
+        };
+        if !cursor.matches(self.query_order()) {
+            bail!("the cursor does not match the sort order of the query");
+        }
+        for (order, _) in cursor.keys() {
+            let field = order.field();
+            if M::get_column(field).is_none() {
+                bail!("the cursor has an invalid sort key `{}`", field);
+            }
+        }
+        Ok(())
+    }
+
+    /// Formats the keyset condition for the cursor.
+    ///
+    /// If all the sort keys have the same direction and there are no nullable values, a row
+    /// value comparison `(a, b) > (x, y)` is generated, which is supported by MySQL,
+    /// PostgreSQL and SQLite. Otherwise, it is expanded as `a > x OR (a = x AND b < y)`,
+    /// where the nulls are compared according to their placement in the sort order.
+    fn format_keyset_condition<M: Schema>(
+        cursor: &QueryCursor,
+        sort_order: &[QueryOrder],
+    ) -> String {
+        let dialect = M::dialect();
+        let backward = cursor.is_backward();
+        let keys = cursor.keys();
+        let nulls_largest = dialect == Dialect::Postgres;
+        let mut nullable = false;
+        let mut fields = Vec::with_capacity(keys.len());
+        let mut values = Vec::with_capacity(keys.len());
+        let mut operators = Vec::with_capacity(keys.len());
+        let mut nulls_after = Vec::with_capacity(keys.len());
+        for (index, (order, value)) in keys.iter().enumerate() {
+            let field = order.field();
+            let col = M::get_column(field);
+            let encoded_value = if value.is_null() {
+                "NULL".into()
+            } else if let Some(col) = col {
+                col.encode_value(dialect, Some(value))
+            } else if let Some(s) = value.as_str() {
+                Self::escape_string(s).into()
+            } else {
+                value.to_string().into()
+            };
+            let ascending = !(order.is_descending() ^ backward);
+            let operator = if ascending { ">" } else { "<" };
+
+            // Whether the nulls appear after the non-null values in the scan order
+            let order = sort_order.get(index).unwrap_or(order);
+            let nullable_key = value.is_null() || col.is_none_or(|col| !col.is_not_null());
+            let nulls_last = nullable_key
+                && if order.nulls_first() || order.nulls_last() {
+                    order.nulls_last() ^ backward
+                } else {
+                    nulls_largest == ascending
+                };
+            nullable |= nullable_key;
+            fields.push(dialect.format_field(field));
+            values.push(encoded_value);
+            operators.push(operator);
+            nulls_after.push(nulls_last);
+        }
+        if !nullable
+            && let Some(operator) = operators.first()
+            && operators.iter().all(|op| op == operator)
+        {
+            if fields.len() == 1 {
+                format!("{} {operator} {}", fields[0], values[0])
+            } else {
+                format!("({}) {operator} ({})", fields.join(", "), values.join(", "))
+            }
+        } else {
+            let mut conditions = Vec::with_capacity(fields.len());
+            for (index, operator) in operators.iter().enumerate() {
+                let field = &fields[index];
+                let value = &values[index];
+                let condition = if keys[index].1.is_null() {
+                    if nulls_after[index] {
+                        // There are no rows after the nulls
+                        continue;
+                    } else {
+                        format!("{field} IS NOT NULL")
+                    }
+                } else if nulls_after[index] {
+                    format!("({field} {operator} {value} OR {field} IS NULL)")
+                } else {
+                    format!("{field} {operator} {value}")
+                };
+                let mut condition_group = (0..index)
+                    .map(|i| {
+                        if keys[i].1.is_null() {
+                            format!("{} IS NULL", fields[i])
+                        } else {
+                            format!("{} = {}", fields[i], values[i])
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                condition_group.push(condition);
+                conditions.push(Self::join_conditions(condition_group, " AND "));
+            }
+            if conditions.is_empty() {
+                "1 = 0".to_owned()
+            } else {
+                format!("({})", conditions.join(" OR "))
+            }
+        }
+    }
+
     // Formats the filters with a logic operator.
     fn format_logical_filters<M: Schema>(filters: &[JsonValue], operator: &str) -> String {
+        let dialect = M::dialect();
         let mut conditions = Vec::with_capacity(filters.len());
         for filter in filters {
             if let JsonValue::Object(filter) = filter {
@@ -1393,10 +1784,10 @@ pub(super) trait QueryExt<DB> {
                                 let condition = if let Some(subquery) =
                                     value.as_object().and_then(|m| m.get_str("$subquery"))
                                 {
-                                    let key = Self::format_field(key);
+                                    let key = dialect.format_field(key);
                                     format!(r#"{key} = {subquery}"#)
                                 } else {
-                                    col.format_filter(key, value)
+                                    col.format_filter(dialect, key, value)
                                 };
                                 if !condition.is_empty() {
                                     logical_and_conditions.push(condition);
@@ -1421,13 +1812,14 @@ pub(super) trait QueryExt<DB> {
 
     /// Formats a query filter.
     fn format_query_filter<M: Schema>(key: &str, value: &JsonValue) -> String {
+        let dialect = M::dialect();
         let json_field = key.split_once('.').and_then(|(key, path)| {
             M::get_column(key)
                 .filter(|col| col.type_name() == "Map")
                 .map(|col| {
                     let key = [M::model_name(), ".", col.name()].concat();
-                    let field = Self::format_field(&key);
-                    if cfg!(feature = "orm-postgres") {
+                    let field = dialect.format_field(&key);
+                    if dialect == Dialect::Postgres {
                         let path = path.replace('.', ", ");
                         format!(r#"({field} #> '{{{path}}}')"#)
                     } else {
@@ -1438,7 +1830,7 @@ pub(super) trait QueryExt<DB> {
         let requires_json_value = json_field.is_some();
         let field = json_field
             .map(|s| s.into())
-            .unwrap_or_else(|| Self::format_field(key));
+            .unwrap_or_else(|| dialect.format_field(key));
         if let Some(filter) = value.as_object() {
             let mut conditions = Vec::with_capacity(filter.len());
             for (name, value) in filter {
@@ -1458,7 +1850,7 @@ pub(super) trait QueryExt<DB> {
                 {
                     format!(r#"{field} {operator} {subquery}"#)
                 } else if requires_json_value {
-                    Self::format_json_filter(&field, operator, value)
+                    Self::format_json_filter(dialect, &field, operator, value)
                 } else if let Some(s) = value.as_str() {
                     if name == "$subquery" {
                         format!(r#"{field} {operator} {s}"#)
@@ -1473,7 +1865,7 @@ pub(super) trait QueryExt<DB> {
             }
             Self::join_conditions(conditions, " AND ")
         } else if requires_json_value {
-            Self::format_json_filter(&field, "=", value)
+            Self::format_json_filter(dialect, &field, "=", value)
         } else if let Some(s) = value.as_str() {
             let value = Self::escape_string(s);
             format!(r#"{field} = {value}"#)
@@ -1482,20 +1874,26 @@ pub(super) trait QueryExt<DB> {
         }
     }
 
-    /// Formats the filter for a JSON field.
-    fn format_json_filter(field: &str, operator: &str, value: &JsonValue) -> String {
+    /// Formats the filter for a JSON field in the dialect.
+    fn format_json_filter(
+        dialect: Dialect,
+        field: &str,
+        operator: &str,
+        value: &JsonValue,
+    ) -> String {
+        let is_postgres = dialect == Dialect::Postgres;
         match value {
             JsonValue::Null => format!(r#"{field} IS NULL"#),
             JsonValue::Bool(b) => {
                 let value = if *b { "TRUE" } else { "FALSE" };
-                if cfg!(feature = "orm-postgres") {
+                if is_postgres {
                     format!(r#"({field})::boolean IS {value}"#)
                 } else {
                     format!(r#"{field} = {value}"#)
                 }
             }
             JsonValue::Number(n) => {
-                if cfg!(feature = "orm-postgres") {
+                if is_postgres {
                     format!(r#"{field} {operator} '{n}'"#)
                 } else {
                     format!(r#"{field} {operator} {n}"#)
@@ -1507,19 +1905,19 @@ pub(super) trait QueryExt<DB> {
                 } else if s == "not_null" {
                     format!(r#"{field} IS NOT NULL"#)
                 } else if s == "true" || s == "false" {
-                    if cfg!(feature = "orm-postgres") {
+                    if is_postgres {
                         format!(r#"({field})::boolean IS {s}"#)
                     } else {
                         format!(r#"{field} = {s}"#)
                     }
                 } else if let Ok(n) = s.parse::<serde_json::Number>() {
-                    if cfg!(feature = "orm-postgres") {
+                    if is_postgres {
                         format!(r#"{field} {operator} '{n}'"#)
                     } else {
                         format!(r#"{field} {operator} {n}"#)
                     }
                 } else {
-                    let value = if cfg!(feature = "orm-postgres") {
+                    let value = if is_postgres {
                         Self::escape_string(value)
                     } else {
                         Self::escape_string(s)
@@ -1535,23 +1933,33 @@ pub(super) trait QueryExt<DB> {
     }
 
     /// Formats the query sort to generate SQL `ORDER BY` expression.
-    fn format_sort(&self) -> String {
+    fn format_sort<M: Schema>(&self) -> String {
+        let dialect = M::dialect();
         let sort_order = self.query_order();
         if sort_order.is_empty() {
             String::new()
         } else {
+            // The sort order is reversed when paginating backward with a cursor
+            let backward = self
+                .query_cursor()
+                .is_some_and(|cursor| cursor.is_backward());
             let sort_order = sort_order
                 .iter()
                 .map(|order| {
-                    let sort_field = Self::format_field(order.field());
-                    let mut expr = if order.is_descending() {
+                    let sort_field = dialect.format_field(order.field());
+                    let mut expr = if order.is_descending() ^ backward {
                         format!("{sort_field} DESC")
                     } else {
                         format!("{sort_field} ASC")
                     };
-                    if order.nulls_first() {
+                    let (nulls_first, nulls_last) = if backward {
+                        (order.nulls_last(), order.nulls_first())
+                    } else {
+                        (order.nulls_first(), order.nulls_last())
+                    };
+                    if nulls_first {
                         expr.push_str(" NULLS FIRST");
-                    } else if order.nulls_last() {
+                    } else if nulls_last {
                         expr.push_str(" NULLS LAST");
                     }
                     expr
@@ -1568,6 +1976,10 @@ pub(super) trait QueryExt<DB> {
             return String::new();
         }
 
+        if self.query_cursor().is_some() {
+            return format!("LIMIT {limit}");
+        }
+
         let offset = self.query_offset();
         format!("LIMIT {limit} OFFSET {offset}")
     }
@@ -1582,6 +1994,63 @@ pub(super) trait QueryExt<DB> {
     }
 }
 
+impl QueryExt for Query {
+    #[inline]
+    fn query_fields(&self) -> &[String] {
+        self.fields()
+    }
+
+    #[inline]
+    fn query_filters(&self) -> &Map {
+        self.filters()
+    }
+
+    #[inline]
+    fn query_order(&self) -> &[QueryOrder] {
+        self.sort_order()
+    }
+
+    #[inline]
+    fn query_offset(&self) -> usize {
+        self.offset()
+    }
+
+    #[inline]
+    fn query_limit(&self) -> usize {
+        self.limit()
+    }
+
+    #[inline]
+    fn query_cursor(&self) -> Option<&QueryCursor> {
+        self.cursor()
+    }
+
+    #[inline]
+    fn query_extra(&self) -> &Map {
+        self.extra()
+    }
+
+    #[inline]
+    fn format_table_name<M: Schema>(&self) -> String {
+        with_driver!(M::dialect(), D => D::format_table_name::<M>(self))
+    }
+
+    #[inline]
+    fn parse_text_search<M: Schema>(filter: &Map) -> Option<String> {
+        with_driver!(M::dialect(), D => D::parse_text_search::<M>(filter))
+    }
+
+    #[inline]
+    fn format_text_search_rank<M: Schema>(filter: &Map) -> Option<String> {
+        with_driver!(M::dialect(), D => D::format_text_search_rank::<M>(filter))
+    }
+
+    #[inline]
+    fn format_text_search_highlight<M: Schema>(filter: &Map, field: &str) -> Option<String> {
+        with_driver!(M::dialect(), D => D::format_text_search_highlight::<M>(filter, field))
+    }
+}
+
 /// Formats the query using interpolation of the parameters.
 ///
 /// The interpolation parameter is represented as `${param}`,
@@ -1646,7 +2115,234 @@ static STATEMENT_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
 
 #[cfg(test)]
 mod tests {
-    use zino_core::{Map, extension::JsonObjectExt};
+    use super::QueryExt;
+    use crate::{Dialect, fixture::Group};
+    use zino_core::{
+        Map,
+        extension::JsonObjectExt,
+        model::{Query, QueryCursor},
+    };
+
+    #[test]
+    fn it_checks_query_cursors() {
+        let mut query = Query::default();
+        query.order_by("status", false);
+        query.order_by_tiebreaker("id");
+
+        let mut model = Map::new();
+        model.upsert("id", 1);
+        model.upsert("status", "Active");
+        let cursor = QueryCursor::next(query.sort_order(), "id", &model);
+        query.set_cursor(cursor.clone());
+        assert!(query.check_cursor::<Group>().is_ok());
+
+        // The sort order has been changed
+        let mut other_query = Query::default();
+        other_query.order_by("name", false);
+        other_query.order_by_tiebreaker("id");
+        other_query.set_cursor(cursor);
+        assert!(other_query.check_cursor::<Group>().is_err());
+
+        // The sort key is not a column of the model
+        let mut query = Query::default();
+        query.order_by("unknown", false);
+        query.order_by_tiebreaker("id");
+        let cursor = QueryCursor::next(query.sort_order(), "id", &model);
+        query.set_cursor(cursor);
+        assert!(query.check_cursor::<Group>().is_err());
+    }
+
+    #[test]
+    fn it_formats_queries_in_dialects() {
+        let mut query = Query::default();
+        query.allow_fields(&["id", "name", "group.status"]);
+        query.add_filter("$rand", 0.5);
+        query.add_filter("$group", vec!["status"]);
+        query.order_by("name", false);
+
+        crate::fixture::set_dialect(Dialect::Postgres);
+        assert_eq!(
+            query.format_table_name::<Group>(),
+            r#""test_group" AS "group""#
+        );
+        assert_eq!(
+            query.format_table_fields::<Group>(),
+            r#""group"."id", "group"."name", "group"."status""#
+        );
+        assert_eq!(
+            query.format_filters::<Group>(),
+            r#"WHERE random() < 0.5 GROUP BY "status""#
+        );
+        assert_eq!(query.format_sort::<Group>(), r#"ORDER BY "name" ASC"#);
+
+        crate::fixture::set_dialect(Dialect::MySql);
+        assert_eq!(
+            query.format_table_name::<Group>(),
+            "`test_group` AS `group`"
+        );
+        assert_eq!(
+            query.format_table_fields::<Group>(),
+            "`group`.`id`, `group`.`name`, `group`.`status`"
+        );
+        assert_eq!(
+            query.format_filters::<Group>(),
+            "WHERE rand() < 0.5 GROUP BY `status`"
+        );
+        assert_eq!(query.format_sort::<Group>(), "ORDER BY `name` ASC");
+    }
+
+    #[cfg(feature = "orm-postgres")]
+    #[test]
+    fn it_formats_queries_for_driver_families() {
+        let mut query = Query::default();
+        query.add_filter("name", "alice");
+        query.add_filter("owner_id", Map::from_entry("$in", vec![1, 2]));
+        let sql = "SELECT * FROM test_group WHERE name = #{name} AND status = #{status};";
+        let mut params = Map::new();
+        params.upsert("name", "alice");
+        params.upsert("status", "Active");
+
+        crate::fixture::set_dialect(Dialect::Postgres);
+        assert_eq!(
+            query.format_table_name::<Group>(),
+            r#""test_group" AS "group""#
+        );
+        assert_eq!(
+            query.format_filters::<Group>(),
+            r#"WHERE "name" = 'alice' AND "owner_id" IN (1, 2)"#
+        );
+        let (sql_query, values) = Query::prepare_query(Dialect::Postgres, sql, Some(&params));
+        assert_eq!(
+            sql_query,
+            "SELECT * FROM test_group WHERE name = $1 AND status = $2;"
+        );
+        assert_eq!(values.len(), 2);
+
+        crate::fixture::set_dialect(Dialect::Sqlite);
+        assert_eq!(
+            query.format_table_name::<Group>(),
+            "`test_group` AS `group`"
+        );
+        assert_eq!(
+            query.format_filters::<Group>(),
+            "WHERE `name` = 'alice' AND `owner_id` IN (1, 2)"
+        );
+        let (sql_query, values) = Query::prepare_query(Dialect::Sqlite, sql, Some(&params));
+        assert_eq!(
+            sql_query,
+            "SELECT * FROM test_group WHERE name = ? AND status = ?;"
+        );
+        assert_eq!(values.len(), 2);
+    }
+
+    #[test]
+    fn it_formats_keyset_conditions() {
+        crate::fixture::set_dialect(Dialect::Sqlite);
+        let (name, owner_id, id) = ("`name`", "`owner_id`", "`id`");
+
+        let mut query = Query::default();
+        query.order_by("name", false);
+        query.order_by_tiebreaker("id");
+
+        let mut model = Map::new();
+        model.upsert("id", 3);
+        model.upsert("name", "alice");
+        model.upsert("owner_id", 7);
+        let cursor = QueryCursor::next(query.sort_order(), "id", &model);
+        assert_eq!(
+            Query::format_keyset_condition::<Group>(&cursor, query.sort_order()),
+            format!("({name}, {id}) > ('alice', 3)")
+        );
+
+        // The nulls are the smallest values in SQLite
+        let mut query = Query::default();
+        query.order_by("owner_id", false);
+        query.order_by_tiebreaker("id");
+        let cursor = QueryCursor::next(query.sort_order(), "id", &model);
+        assert_eq!(
+            Query::format_keyset_condition::<Group>(&cursor, query.sort_order()),
+            format!("({owner_id} > 7 OR ({owner_id} = 7 AND {id} > 3))")
+        );
+        let cursor = QueryCursor::prev(query.sort_order(), "id", &model);
+        assert_eq!(
+            Query::format_keyset_condition::<Group>(&cursor, query.sort_order()),
+            format!("(({owner_id} < 7 OR {owner_id} IS NULL) OR ({owner_id} = 7 AND {id} < 3))")
+        );
+
+        model.upsert("owner_id", None::<i64>);
+        let cursor = QueryCursor::next(query.sort_order(), "id", &model);
+        assert_eq!(
+            Query::format_keyset_condition::<Group>(&cursor, query.sort_order()),
+            format!("({owner_id} IS NOT NULL OR ({owner_id} IS NULL AND {id} > 3))")
+        );
+        let cursor = QueryCursor::prev(query.sort_order(), "id", &model);
+        assert_eq!(
+            Query::format_keyset_condition::<Group>(&cursor, query.sort_order()),
+            format!("(({owner_id} IS NULL AND {id} < 3))")
+        );
+
+        // The nulls are the largest values in PostgreSQL
+        crate::fixture::set_dialect(Dialect::Postgres);
+        let (owner_id, id) = (r#""owner_id""#, r#""id""#);
+        let cursor = QueryCursor::next(query.sort_order(), "id", &model);
+        assert_eq!(
+            Query::format_keyset_condition::<Group>(&cursor, query.sort_order()),
+            format!("(({owner_id} IS NULL AND {id} > 3))")
+        );
+
+        // The placement of nulls is explicit
+        let mut query = Query::default();
+        query.order_by_with_nulls("owner_id", true, false);
+        query.order_by_tiebreaker("id");
+        model.upsert("owner_id", 7);
+        let cursor = QueryCursor::next(query.sort_order(), "id", &model);
+        assert_eq!(
+            Query::format_keyset_condition::<Group>(&cursor, query.sort_order()),
+            format!("(({owner_id} < 7 OR {owner_id} IS NULL) OR ({owner_id} = 7 AND {id} < 3))")
+        );
+    }
+
+    #[cfg(not(any(
+        feature = "orm-mariadb",
+        feature = "orm-mysql",
+        feature = "orm-postgres",
+        feature = "orm-tidb"
+    )))]
+    #[test]
+    fn it_formats_text_search_in_sqlite() {
+        crate::fixture::set_dialect(Dialect::Sqlite);
+
+        let mut text_search = Map::new();
+        text_search.upsert("$fields", vec!["description"]);
+        text_search.upsert("$search", "rust \"async\"");
+        text_search.upsert("$rank", true);
+        text_search.upsert("$highlight", vec!["group.description"]);
+
+        let mut query = Query::default();
+        query.allow_fields(&["id", "name"]);
+        query.add_filter("$text", text_search);
+        query.order_by("search_rank", true);
+
+        let fts_table = "`test_group_fts`";
+        let search = r#"'{description} : ("rust" """async""")'"#;
+        let condition = format!("{fts_table} MATCH {search} AND rowid = `group`.rowid");
+        assert_eq!(
+            query.format_filters::<Group>(),
+            format!(
+                "WHERE rowid IN (SELECT rowid FROM {fts_table} WHERE {fts_table} MATCH {search})"
+            )
+        );
+        assert_eq!(
+            query.format_table_fields::<Group>(),
+            format!(
+                "`group`.`id`, `group`.`name`, \
+                    (SELECT -rank FROM {fts_table} WHERE {condition}) AS `search_rank`, \
+                    (SELECT snippet({fts_table}, 0, '<mark>', '</mark>', '...', 32) \
+                        FROM {fts_table} WHERE {condition}) AS `description_highlight`"
+            )
+        );
+        assert_eq!(query.format_sort::<Group>(), "ORDER BY `search_rank` DESC");
+    }
 
     #[test]
     fn it_formats_query_params() {
@@ -0,0 +1,321 @@
+use super::{DecodeRow, Executor, Schema, query::QueryExt};
+use zino_core::{JsonValue, Map, bail, error::Error, extension::JsonObjectExt};
+
+/// Kinds of the relationships between models.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RelationKind {
+    /// The model holds a foreign key referencing the related model.
+    BelongsTo,
+    /// The related models hold a foreign key referencing the model.
+    HasMany,
+    /// The models are associated through a join table.
+    ManyToMany,
+}
+
+/// A relationship between models, which can be eager loaded
+/// via [`QueryBuilder::with`](crate::QueryBuilder::with).
+///
+/// The relationships are declared by the `#[derive(Schema)]` macro:
+///
+/// ```rust,ignore
+/// #[derive(Schema)]
+/// #[schema(belongs_to = "User", relation = "author", foreign_key = "author_id")]
+/// #[schema(has_many = "Comment", relation = "comments", foreign_key = "post_id")]
+/// #[schema(many_to_many = "Tag", relation = "tags", through = "PostTag")]
+/// pub struct Post {
+///     id: Uuid,
+///     author_id: Uuid,
+///     // ...
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Relation {
+    /// Relation name.
+    name: &'static str,
+    /// Relation kind.
+    kind: RelationKind,
+    /// Foreign key referencing the primary key of the target model.
+    foreign_key: &'static str,
+    /// Column in the join table referencing the related model.
+    related_key: &'static str,
+}
+
+impl Relation {
+    /// Creates a belongs-to relation with the foreign key on the model.
+    #[inline]
+    pub const fn belongs_to(name: &'static str, foreign_key: &'static str) -> Self {
+        Self {
+            name,
+            kind: RelationKind::BelongsTo,
+            foreign_key,
+            related_key: "",
+        }
+    }
+
+    /// Creates a has-many relation with the foreign key on the related model.
+    #[inline]
+    pub const fn has_many(name: &'static str, foreign_key: &'static str) -> Self {
+        Self {
+            name,
+            kind: RelationKind::HasMany,
+            foreign_key,
+            related_key: "",
+        }
+    }
+
+    /// Creates a many-to-many relation, where the join table has a `foreign_key`
+    /// referencing the model and a `related_key` referencing the related model.
+    #[inline]
+    pub const fn many_to_many(
+        name: &'static str,
+        foreign_key: &'static str,
+        related_key: &'static str,
+    ) -> Self {
+        Self {
+            name,
+            kind: RelationKind::ManyToMany,
+            foreign_key,
+            related_key,
+        }
+    }
+
+    /// Returns the relation name.
+    #[inline]
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Returns the relation kind.
+    #[inline]
+    pub fn kind(&self) -> RelationKind {
+        self.kind
+    }
+
+    /// Returns the foreign key.
+    #[inline]
+    pub fn foreign_key(&self) -> &'static str {
+        self.foreign_key
+    }
+
+    /// Returns the column in the join table referencing the related model.
+    #[inline]
+    pub fn related_key(&self) -> &'static str {
+        self.related_key
+    }
+
+    /// Returns the column of the model `M` whose values are used to match the related models.
+    #[inline]
+    pub(crate) fn local_key<M: Schema>(&self) -> &'static str {
+        if self.kind == RelationKind::BelongsTo {
+            self.foreign_key
+        } else {
+            M::primary_key_name()
+        }
+    }
+
+    /// Loads the related models `R` for the keys with a batched select.
+    /// It returns a list of the related models paired with the matched keys.
+    pub async fn load<R: Schema>(
+        &self,
+        keys: Vec<JsonValue>,
+    ) -> Result<Vec<(JsonValue, Map)>, Error> {
+        match self.kind {
+            RelationKind::BelongsTo => fetch_by_keys::<R>(R::primary_key_name(), keys).await,
+            RelationKind::HasMany => fetch_by_keys::<R>(self.foreign_key, keys).await,
+            RelationKind::ManyToMany => {
+                bail!(
+                    "the join table should be specified for the `{}` relation",
+                    self.name
+                );
+            }
+        }
+    }
+
+    /// Loads the related models `R` through the join table of the model `T`.
+    /// It returns a list of the related models paired with the matched keys.
+    pub async fn load_through<R: Schema, T: Schema>(
+        &self,
+        keys: Vec<JsonValue>,
+    ) -> Result<Vec<(JsonValue, Map)>, Error> {
+        let related_key = self.related_key;
+        let links = fetch_by_keys::<T>(self.foreign_key, keys).await?;
+        let mut related_keys = Vec::new();
+        for (_, link) in links.iter() {
+            if let Some(key) = link.get(related_key)
+                && !related_keys.contains(key)
+            {
+                related_keys.push(key.to_owned());
+            }
+        }
+
+        let models = fetch_by_keys::<R>(R::primary_key_name(), related_keys).await?;
+        let mut associations = Vec::with_capacity(links.len());
+        for (key, link) in links {
+            if let Some(related_key) = link.get(related_key) {
+                associations.extend(
+                    models
+                        .iter()
+                        .filter(|(k, _)| k == related_key)
+                        .map(|(_, model)| (key.clone(), model.clone())),
+                );
+            }
+        }
+        Ok(associations)
+    }
+}
+
+/// Fetches the models whose `key` is one of the `values`,
+/// and returns them paired with the values of the `key`.
+async fn fetch_by_keys<M: Schema>(
+    key: &str,
+    values: Vec<JsonValue>,
+) -> Result<Vec<(JsonValue, Map)>, Error> {
+    if values.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut query = M::default_query();
+    query.add_filter(key, Map::from_entry("$in", values));
+    M::before_query(&query).await?;
+
+    let table_name = query.format_table_name::<M>();
+    let projection = query.format_table_fields::<M>();
+    let filters = query.format_filters::<M>();
+    let sql = format!("SELECT {projection} FROM {table_name} {filters};");
+    let mut ctx = M::before_scan(&sql).await?;
+    ctx.set_query(&sql);
+
+    let pool = M::acquire_reader().await?.pool();
+    let rows = pool.fetch(ctx.query()).await?;
+    let mut models = Vec::with_capacity(rows.len());
+    for row in rows {
+        let mut map = Map::decode_row(&row)?;
+        M::after_decode(&mut map).await?;
+        if let Some(value) = map.get(key).cloned() {
+            models.push((value, map));
+        }
+    }
+    ctx.set_query_result(u64::try_from(models.len())?, true);
+    M::after_scan(&ctx).await?;
+    M::after_query(&ctx).await?;
+    Ok(models)
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "orm-sqlx")]
+    #[tokio::test]
+    async fn it_eager_loads_relations() {
+        use crate::{ConnectionPool, DatabasePool, Dialect, Executor, Schema, fixture::Group};
+        use futures::TryStreamExt;
+        use serde::Deserialize;
+        use sqlx::sqlite::SqlitePoolOptions;
+        use std::pin::pin;
+        use zino_core::{Map, extension::JsonObjectExt, model::Query};
+
+        #[derive(Deserialize)]
+        struct GroupNode {
+            name: String,
+            children: Vec<Map>,
+        }
+
+        // The stream holds a connection while the relations are loaded
+        let pool = SqlitePoolOptions::new()
+            .max_connections(2)
+            .connect("sqlite:file:eager_loading?mode=memory&cache=shared")
+            .await
+            .expect("fail to connect the database");
+        pool.execute(
+            "CREATE TABLE test_group (id INTEGER PRIMARY KEY, name TEXT, description TEXT, \
+                status TEXT, parent_id INTEGER, owner_id INTEGER, version INTEGER);",
+        )
+        .await
+        .expect("fail to execute the statement");
+        pool.execute(
+            "INSERT INTO test_group (id, name, parent_id) \
+                VALUES (1, 'root', NULL), (2, 'left', 1), (3, 'right', 1);",
+        )
+        .await
+        .expect("fail to execute the statement");
+        let mut connection_pool = ConnectionPool::new("main", "test", DatabasePool::Sqlite(pool));
+        connection_pool.set_dialect(Dialect::Sqlite);
+        crate::fixture::set_connection_pool(connection_pool);
+        crate::fixture::set_dialect(Dialect::Sqlite);
+
+        // The join key `parent_id` is omitted by the projection
+        let mut query = Query::default();
+        query.allow_fields(&["id", "name"]);
+        query.order_asc("id");
+        query.set_extra_flag("with", vec!["parent", "children"]);
+
+        let groups = Group::find::<Map>(&query)
+            .await
+            .expect("fail to find the models");
+        assert_eq!(groups.len(), 3);
+        assert!(
+            groups[0]
+                .get("parent")
+                .is_some_and(|parent| parent.is_null())
+        );
+        assert_eq!(groups[0].get_array("children").map(|v| v.len()), Some(2));
+        assert_eq!(
+            groups[1]
+                .get_object("parent")
+                .and_then(|parent| parent.get_str("name")),
+            Some("root")
+        );
+        assert_eq!(groups[1].get_array("children").map(|v| v.len()), Some(0));
+
+        let nodes = Group::find_as::<GroupNode>(&query)
+            .await
+            .expect("fail to find the models");
+        assert_eq!(nodes[0].name, "root");
+        assert_eq!(nodes[0].children[1].get_str("name"), Some("right"));
+
+        let mut one_query = query.clone();
+        one_query.add_filter("id", 3);
+        let group = Group::find_one::<Map>(&one_query)
+            .await
+            .expect("fail to find the models")
+            .expect("the group should exist");
+        assert_eq!(
+            group
+                .get_object("parent")
+                .and_then(|parent| parent.get_i64("id")),
+            Some(1)
+        );
+
+        let stream = pin!(
+            Group::find_stream::<Map>(&query)
+                .await
+                .expect("fail to find the models")
+        );
+        let groups = stream
+            .try_collect::<Vec<_>>()
+            .await
+            .expect("fail to collect the stream");
+        assert_eq!(groups.len(), 3);
+        assert_eq!(groups[0].get_array("children").map(|v| v.len()), Some(2));
+        assert_eq!(
+            groups[2]
+                .get_object("parent")
+                .and_then(|parent| parent.get_str("name")),
+            Some("root")
+        );
+
+        let stream = pin!(
+            Group::find_stream_as::<GroupNode>(&query)
+                .await
+                .expect("fail to find the models")
+        );
+        let nodes = stream
+            .try_collect::<Vec<_>>()
+            .await
+            .expect("fail to collect the stream");
+        assert_eq!(nodes[0].children.len(), 2);
+
+        let mut query = Query::default();
+        query.set_extra_flag("with", vec!["owner"]);
+        assert!(Group::find::<Map>(&query).await.is_err());
+    }
+}
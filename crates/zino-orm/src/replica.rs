@@ -0,0 +1,328 @@
+//! Routing reads across the read replicas.
+//!
+//! A database service can have multiple read replicas, which are configured as
+//! the tables with the same `name` and `role = "replica"`:
+//!
+//! ```toml
+//! [database]
+//! load-balancing = "least-latency"
+//! max-replication-lag = "5s"
+//! replica-check-interval = "30s"
+//! read-your-writes = true
+//!
+//! [[postgres]]
+//! name = "main"
+//! host = "10.0.0.1"
+//! database = "data_cube"
+//!
+//! [[postgres]]
+//! name = "main"
+//! role = "replica"
+//! host = "10.0.0.2"
+//! database = "data_cube"
+//! ```
+//!
+//! Reads are dispatched to the available replicas whose replication lag does not exceed
+//! `max-replication-lag`, and fall back to the writer if there are no such replicas.
+//! The availability and the replication lag of the replicas are refreshed
+//! every `replica-check-interval` by [`GlobalPool::monitor_replicas()`](crate::GlobalPool::monitor_replicas).
+//! Within a [`ReadYourWrites`] scope, reads following a write are routed to the writer.
+//! The scope is bound to the task, so a spawned task should be wrapped with
+//! [`ReadYourWrites::inherit()`] to share the scope of its parent.
+
+use super::ConnectionPool;
+use smallvec::SmallVec;
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{
+        Arc, Mutex, OnceLock,
+        atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering::Relaxed},
+    },
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::task::futures::TaskLocalFuture;
+
+/// Load-balancing strategies for the read replicas.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LoadBalancing {
+    /// Selects the replicas in turn.
+    #[default]
+    RoundRobin,
+    /// Selects the replica with the least latency.
+    LeastLatency,
+}
+
+impl LoadBalancing {
+    /// Parses the load-balancing strategy.
+    pub fn parse(strategy: &str) -> Option<Self> {
+        match strategy {
+            "round-robin" | "round_robin" => Some(Self::RoundRobin),
+            "least-latency" | "least_latency" => Some(Self::LeastLatency),
+            _ => None,
+        }
+    }
+
+    /// Selects a connection pool from the candidates.
+    pub(crate) fn select<'a, P>(
+        self,
+        candidates: &[&'a ConnectionPool<P>],
+    ) -> Option<&'a ConnectionPool<P>> {
+        match self {
+            Self::RoundRobin => {
+                let num_candidates = candidates.len();
+                if num_candidates == 0 {
+                    return None;
+                }
+
+                let index = ROUND_ROBIN_COUNTER.fetch_add(1, Relaxed) % num_candidates;
+                candidates.get(index).copied()
+            }
+            Self::LeastLatency => candidates.iter().copied().min_by_key(|cp| cp.latency()),
+        }
+    }
+}
+
+/// A future which enables the "read-your-writes" consistency for the reads within it.
+///
+/// Once a write is performed on a database service inside the future,
+/// the subsequent reads on the same service will be routed to the writer
+/// instead of the read replicas.
+///
+/// The session is a task-local value which is not propagated to the spawned tasks.
+/// Use [`ReadYourWrites::inherit()`] to share it with a spawned task:
+///
+/// ```rust,ignore
+/// tokio::spawn(ReadYourWrites::inherit(async move {
+///     // The writes in the parent task are visible here.
+/// }));
+/// ```
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct ReadYourWrites<F> {
+    /// The inner future scoped with the session.
+    future: Pin<Box<TaskLocalFuture<Arc<Session>, F>>>,
+}
+
+impl<F: Future> ReadYourWrites<F> {
+    /// Creates a new instance with a new session.
+    #[inline]
+    pub fn new(future: F) -> Self {
+        Self::with_session(Arc::new(Session::default()), future)
+    }
+
+    /// Creates a new instance with the session of the current task,
+    /// or a new session if the current task is not in a session.
+    #[inline]
+    pub fn inherit(future: F) -> Self {
+        let session = CURRENT_SESSION.try_with(Arc::clone).unwrap_or_default();
+        Self::with_session(session, future)
+    }
+
+    /// Creates a new instance with the session.
+    #[inline]
+    fn with_session(session: Arc<Session>, future: F) -> Self {
+        Self {
+            future: Box::pin(CURRENT_SESSION.scope(session, future)),
+        }
+    }
+}
+
+impl<F: Future> Future for ReadYourWrites<F> {
+    type Output = F::Output;
+
+    #[inline]
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.future.as_mut().poll(cx)
+    }
+}
+
+/// Session state for the "read-your-writes" consistency.
+#[derive(Debug, Default)]
+struct Session {
+    /// Names of the database services that have been written.
+    written: Mutex<SmallVec<[&'static str; 2]>>,
+}
+
+/// Records a write on the database service for the current session.
+pub(crate) fn record_write(name: &'static str) {
+    if !READ_YOUR_WRITES.load(Relaxed) {
+        return;
+    }
+    let _ = CURRENT_SESSION.try_with(|session| {
+        if let Ok(mut written) = session.written.lock()
+            && !written.contains(&name)
+        {
+            written.push(name);
+        }
+    });
+}
+
+/// Returns `true` if the database service has been written in the current session.
+pub(crate) fn has_written(name: &str) -> bool {
+    READ_YOUR_WRITES.load(Relaxed)
+        && CURRENT_SESSION
+            .try_with(|session| {
+                session
+                    .written
+                    .lock()
+                    .is_ok_and(|written| written.contains(&name))
+            })
+            .unwrap_or_default()
+}
+
+/// Sets the load-balancing strategy. It can only be set once.
+#[inline]
+pub(crate) fn set_load_balancing(strategy: LoadBalancing) {
+    if LOAD_BALANCING.set(strategy).is_err() {
+        tracing::warn!("the load-balancing strategy for the read replicas has already been set");
+    }
+}
+
+/// Returns the load-balancing strategy.
+#[inline]
+pub(crate) fn load_balancing() -> LoadBalancing {
+    LOAD_BALANCING.get().copied().unwrap_or_default()
+}
+
+/// Sets the maximum replication lag.
+#[inline]
+pub(crate) fn set_max_replication_lag(lag: Duration) {
+    let lag = u64::try_from(lag.as_millis()).unwrap_or(u64::MAX);
+    MAX_REPLICATION_LAG.store(lag, Relaxed);
+}
+
+/// Returns the maximum replication lag.
+#[inline]
+pub(crate) fn max_replication_lag() -> Duration {
+    Duration::from_millis(MAX_REPLICATION_LAG.load(Relaxed))
+}
+
+/// Sets the interval for checking the read replicas.
+#[inline]
+pub(crate) fn set_check_interval(interval: Duration) {
+    let interval = u64::try_from(interval.as_millis()).unwrap_or(u64::MAX);
+    CHECK_INTERVAL.store(interval, Relaxed);
+}
+
+/// Returns the interval for checking the read replicas.
+#[inline]
+pub(crate) fn check_interval() -> Duration {
+    Duration::from_millis(CHECK_INTERVAL.load(Relaxed))
+}
+
+/// Enables or disables the "read-your-writes" consistency.
+#[inline]
+pub(crate) fn set_read_your_writes(enabled: bool) {
+    READ_YOUR_WRITES.store(enabled, Relaxed);
+}
+
+tokio::task_local! {
+    /// The session for the current task.
+    static CURRENT_SESSION: Arc<Session>;
+}
+
+/// Load-balancing strategy.
+static LOAD_BALANCING: OnceLock<LoadBalancing> = OnceLock::new();
+
+/// Counter for the round-robin strategy.
+static ROUND_ROBIN_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Maximum replication lag in milliseconds.
+static MAX_REPLICATION_LAG: AtomicU64 = AtomicU64::new(10_000);
+
+/// Interval for checking the read replicas in milliseconds.
+static CHECK_INTERVAL: AtomicU64 = AtomicU64::new(30_000);
+
+/// Read-your-writes consistency.
+static READ_YOUR_WRITES: AtomicBool = AtomicBool::new(true);
+
+#[cfg(test)]
+mod tests {
+    use super::{ConnectionPool, LoadBalancing, ReadYourWrites};
+    use futures::executor::block_on;
+    use std::time::Duration;
+
+    #[test]
+    fn it_parses_load_balancing() {
+        assert_eq!(
+            LoadBalancing::parse("round-robin"),
+            Some(LoadBalancing::RoundRobin)
+        );
+        assert_eq!(
+            LoadBalancing::parse("least_latency"),
+            Some(LoadBalancing::LeastLatency)
+        );
+        assert_eq!(LoadBalancing::parse("random"), None);
+    }
+
+    #[test]
+    fn it_selects_replicas() {
+        let fast = ConnectionPool::new("main", "data_cube", ());
+        let slow = ConnectionPool::new("main", "data_cube", ());
+        fast.record_latency(Duration::from_millis(2));
+        slow.record_latency(Duration::from_millis(20));
+
+        let candidates = [&slow, &fast];
+        let selected = LoadBalancing::LeastLatency.select(&candidates);
+        assert!(selected.is_some_and(|cp| std::ptr::eq(cp, &fast)));
+
+        let first = LoadBalancing::RoundRobin
+            .select(&candidates)
+            .expect("a replica should be selected");
+        let second = LoadBalancing::RoundRobin
+            .select(&candidates)
+            .expect("a replica should be selected");
+        assert!(!std::ptr::eq(first, second));
+
+        let candidates: [&ConnectionPool<()>; 0] = [];
+        assert!(LoadBalancing::RoundRobin.select(&candidates).is_none());
+        assert!(LoadBalancing::LeastLatency.select(&candidates).is_none());
+    }
+
+    #[test]
+    fn it_reads_your_writes() {
+        assert!(!super::has_written("main"));
+        block_on(ReadYourWrites::new(async {
+            assert!(!super::has_written("main"));
+            super::record_write("main");
+            assert!(super::has_written("main"));
+            assert!(!super::has_written("analytics"));
+        }));
+        assert!(!super::has_written("main"));
+
+        // Writes outside of a session are not recorded.
+        super::record_write("main");
+        assert!(!super::has_written("main"));
+    }
+
+    #[test]
+    fn it_ignores_the_second_load_balancing() {
+        super::set_load_balancing(LoadBalancing::LeastLatency);
+        super::set_load_balancing(LoadBalancing::RoundRobin);
+        assert_eq!(super::load_balancing(), LoadBalancing::LeastLatency);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn it_reads_your_writes_across_tasks() {
+        ReadYourWrites::new(async {
+            super::record_write("main");
+            for _ in 0..8 {
+                tokio::task::yield_now().await;
+                assert!(super::has_written("main"));
+            }
+
+            let inherited = tokio::spawn(ReadYourWrites::inherit(async {
+                let written = super::has_written("main");
+                super::record_write("analytics");
+                written
+            }));
+            assert!(inherited.await.expect("fail to join the task"));
+            assert!(super::has_written("analytics"));
+
+            let detached = tokio::spawn(async { super::has_written("main") });
+            assert!(!detached.await.expect("fail to join the task"));
+        })
+        .await;
+    }
+}
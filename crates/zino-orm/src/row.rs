@@ -1,3 +1,5 @@
+use zino_core::JsonValue;
+
 /// A collection of values that can be decoded from a single row.
 ///
 /// This trait can be derived by `zino_derive::DecodeRow`.
@@ -7,4 +9,25 @@ pub trait DecodeRow<Row>: Default + Sized {
 
     /// Decodes a row and attempts to create an instance of `Self`.
     fn decode_row(row: &Row) -> Result<Self, Self::Error>;
+
+    /// Attempts to restore an instance of `Self` from a value in the query cache.
+    /// It returns `None` if the type does not support the query cache.
+    #[inline]
+    fn decode_cached(_value: JsonValue) -> Option<Self> {
+        None
+    }
+
+    /// Encodes `self` as a value to be stored in the query cache.
+    /// It returns `None` if the type does not support the query cache.
+    #[inline]
+    fn encode_cached(&self) -> Option<JsonValue> {
+        None
+    }
+
+    /// Attaches the eager-loaded data of a relation to `self`.
+    /// The data is ignored if the type has no field for the relation.
+    #[inline]
+    fn attach_relation(&mut self, _name: &str, _value: JsonValue) -> Result<(), Self::Error> {
+        Ok(())
+    }
 }
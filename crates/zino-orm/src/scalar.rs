@@ -1,6 +1,9 @@
-use super::{DatabaseDriver, column::ColumnExt, query::QueryExt, schema::Schema};
+use super::{
+    DatabasePool, Dialect, column::ColumnExt, decode::DecodeColumn, driver::dispatch,
+    query::QueryExt, schema::Schema,
+};
 use futures::TryStreamExt;
-use sqlx::{Decode, Row, Type};
+use sqlx::Row;
 use std::{fmt::Display, sync::atomic::Ordering::Relaxed};
 use zino_core::{Map, error::Error, extension::JsonValueExt, model::Query};
 
@@ -13,20 +16,20 @@ where
     /// and decodes it as a single concrete type `T`.
     async fn find_scalar<T>(query: &Query) -> Result<T, Error>
     where
-        T: Send + Unpin + Type<DatabaseDriver> + for<'r> Decode<'r, DatabaseDriver>,
+        T: Send + Unpin + DecodeColumn,
     {
         Self::before_query(query).await?;
 
         let table_name = query.format_table_name::<Self>();
-        let projection = query.format_projection();
+        let projection = query.format_projection(Self::dialect());
         let filters = query.format_filters::<Self>();
-        let sort = query.format_sort();
+        let sort = query.format_sort::<Self>();
         let sql = format!("SELECT {projection} FROM {table_name} {filters} {sort} LIMIT 1;");
         let mut ctx = Self::before_scan(&sql).await?;
         ctx.set_query(sql);
 
         let pool = Self::acquire_reader().await?.pool();
-        let scalar = sqlx::query_scalar(ctx.query()).fetch_one(pool).await?;
+        let scalar = fetch_scalar(pool, ctx.query(), &[]).await?;
         ctx.set_query_result(1, true);
         Self::after_scan(&ctx).await?;
         Self::after_query(&ctx).await?;
@@ -37,40 +40,21 @@ where
     /// and decodes it as a `Vec<T>`.
     async fn find_scalars<T>(query: &Query) -> Result<Vec<T>, Error>
     where
-        T: Send + Unpin + Type<DatabaseDriver> + for<'r> Decode<'r, DatabaseDriver>,
+        T: Send + Unpin + DecodeColumn,
     {
         Self::before_query(query).await?;
 
         let table_name = query.format_table_name::<Self>();
-        let projection = query.format_projection();
+        let projection = query.format_projection(Self::dialect());
         let filters = query.format_filters::<Self>();
-        let sort = query.format_sort();
+        let sort = query.format_sort::<Self>();
         let pagination = query.format_pagination();
         let sql = format!("SELECT {projection} FROM {table_name} {filters} {sort} {pagination};");
         let mut ctx = Self::before_scan(&sql).await?;
         ctx.set_query(&sql);
 
         let pool = Self::acquire_reader().await?.pool();
-        let mut stream = sqlx::query(&sql).fetch(pool);
-        let mut max_rows = super::MAX_ROWS.load(Relaxed);
-        let estimated_rows = stream.size_hint().0;
-        if cfg!(debug_assertions) && estimated_rows > max_rows {
-            tracing::warn!(
-                "estimated number of rows {} exceeds the maximum row limit {}",
-                estimated_rows,
-                max_rows,
-            );
-        }
-
-        let mut data = Vec::with_capacity(estimated_rows.min(max_rows));
-        while let Some(row) = stream.try_next().await? {
-            if max_rows > 0 {
-                data.push(row.try_get_unchecked(0)?);
-                max_rows -= 1;
-            } else {
-                break;
-            }
-        }
+        let data = fetch_scalars(pool, &sql, &[]).await?;
         ctx.set_query_result(u64::try_from(data.len())?, true);
         Self::after_scan(&ctx).await?;
         Self::after_query(&ctx).await?;
@@ -81,14 +65,14 @@ where
     /// and decodes it as a `Vec<T>`.
     async fn find_distinct_scalars<T>(query: &Query) -> Result<Vec<T>, Error>
     where
-        T: Send + Unpin + Type<DatabaseDriver> + for<'r> Decode<'r, DatabaseDriver>,
+        T: Send + Unpin + DecodeColumn,
     {
         Self::before_query(query).await?;
 
         let table_name = query.format_table_name::<Self>();
-        let projection = query.format_projection();
+        let projection = query.format_projection(Self::dialect());
         let filters = query.format_filters::<Self>();
-        let sort = query.format_sort();
+        let sort = query.format_sort::<Self>();
         let pagination = query.format_pagination();
         let sql = format!(
             "SELECT DISTINCT {projection} FROM {table_name} \
@@ -98,26 +82,7 @@ where
         ctx.set_query(&sql);
 
         let pool = Self::acquire_reader().await?.pool();
-        let mut stream = sqlx::query(&sql).fetch(pool);
-        let mut max_rows = super::MAX_ROWS.load(Relaxed);
-        let estimated_rows = stream.size_hint().0;
-        if cfg!(debug_assertions) && estimated_rows > max_rows {
-            tracing::warn!(
-                "estimated number of rows {} exceeds the maximum row limit {}",
-                estimated_rows,
-                max_rows,
-            );
-        }
-
-        let mut data = Vec::with_capacity(estimated_rows.min(max_rows));
-        while let Some(row) = stream.try_next().await? {
-            if max_rows > 0 {
-                data.push(row.try_get_unchecked(0)?);
-                max_rows -= 1;
-            } else {
-                break;
-            }
-        }
+        let data = fetch_scalars(pool, &sql, &[]).await?;
         ctx.set_query_result(u64::try_from(data.len())?, true);
         Self::after_scan(&ctx).await?;
         Self::after_query(&ctx).await?;
@@ -127,21 +92,18 @@ where
     /// Executes the query in the table, and decodes it as a single concrete type `T`.
     async fn query_scalar<T>(query: &str, params: Option<&Map>) -> Result<T, Error>
     where
-        T: Send + Unpin + Type<DatabaseDriver> + for<'r> Decode<'r, DatabaseDriver>,
+        T: Send + Unpin + DecodeColumn,
     {
-        let (sql, values) = Query::prepare_query(query, params);
+        let (sql, values) = Query::prepare_query(Self::dialect(), query, params);
         let mut ctx = Self::before_scan(&sql).await?;
         ctx.set_query(sql);
 
-        let mut query = sqlx::query_scalar(ctx.query());
-        let mut arguments = Vec::with_capacity(values.len());
-        for value in values {
-            query = query.bind(value.to_string_unquoted());
-            arguments.push(value.to_string_unquoted());
-        }
-
+        let mut arguments = values
+            .into_iter()
+            .map(|value| value.to_string_unquoted())
+            .collect::<Vec<_>>();
         let pool = Self::acquire_reader().await?.pool();
-        let scalar = query.fetch_one(pool).await?;
+        let scalar = fetch_scalar(pool, ctx.query(), &arguments).await?;
         ctx.append_arguments(&mut arguments);
         ctx.set_query_result(1, true);
         Self::after_scan(&ctx).await?;
@@ -151,40 +113,18 @@ where
     /// Executes the query in the table, and decodes the scalar values as `Vec<T>`.
     async fn query_scalars<T>(query: &str, params: Option<&Map>) -> Result<Vec<T>, Error>
     where
-        T: Send + Unpin + Type<DatabaseDriver> + for<'r> Decode<'r, DatabaseDriver>,
+        T: Send + Unpin + DecodeColumn,
     {
-        let (sql, values) = Query::prepare_query(query, params);
+        let (sql, values) = Query::prepare_query(Self::dialect(), query, params);
         let mut ctx = Self::before_scan(&sql).await?;
         ctx.set_query(sql.as_ref());
 
-        let mut query = sqlx::query(&sql);
-        let mut arguments = Vec::with_capacity(values.len());
-        for value in values {
-            query = query.bind(value.to_string_unquoted());
-            arguments.push(value.to_string_unquoted());
-        }
-
+        let mut arguments = values
+            .into_iter()
+            .map(|value| value.to_string_unquoted())
+            .collect::<Vec<_>>();
         let pool = Self::acquire_reader().await?.pool();
-        let mut stream = query.fetch(pool);
-        let mut max_rows = super::MAX_ROWS.load(Relaxed);
-        let estimated_rows = stream.size_hint().0;
-        if cfg!(debug_assertions) && estimated_rows > max_rows {
-            tracing::warn!(
-                "estimated number of rows {} exceeds the maximum row limit {}",
-                estimated_rows,
-                max_rows,
-            );
-        }
-
-        let mut data = Vec::with_capacity(estimated_rows.min(max_rows));
-        while let Some(row) = stream.try_next().await? {
-            if max_rows > 0 {
-                data.push(row.try_get_unchecked(0)?);
-                max_rows -= 1;
-            } else {
-                break;
-            }
-        }
+        let data = fetch_scalars(pool, &sql, &arguments).await?;
         ctx.append_arguments(&mut arguments);
         ctx.set_query_result(u64::try_from(data.len())?, true);
         Self::after_scan(&ctx).await?;
@@ -196,14 +136,15 @@ where
     async fn find_scalar_by_id<C, T>(primary_key: &Self::PrimaryKey, column: C) -> Result<T, Error>
     where
         C: AsRef<str>,
-        T: Send + Unpin + Type<DatabaseDriver> + for<'r> Decode<'r, DatabaseDriver>,
+        T: Send + Unpin + DecodeColumn,
     {
+        let dialect = Self::dialect();
         let primary_key_name = Self::primary_key_name();
-        let table_name = Query::escape_table_name(Self::table_name());
-        let projection = Query::format_field(column.as_ref());
-        let placeholder = Query::placeholder(1);
-        let sql = if cfg!(feature = "orm-postgres") {
-            let type_annotation = Self::primary_key_column().type_annotation();
+        let table_name = dialect.escape_table_name(Self::table_name());
+        let projection = dialect.format_field(column.as_ref());
+        let placeholder = dialect.placeholder(1);
+        let sql = if dialect == Dialect::Postgres {
+            let type_annotation = Self::primary_key_column().type_annotation(dialect);
             format!(
                 "SELECT {projection} FROM {table_name} \
                     WHERE {primary_key_name} = ({placeholder}){type_annotation};"
@@ -217,8 +158,7 @@ where
         ctx.set_query(sql);
 
         let pool = Self::acquire_reader().await?.pool();
-        let query = sqlx::query_scalar(ctx.query()).bind(primary_key.to_string());
-        let scalar = query.fetch_one(pool).await?;
+        let scalar = fetch_scalar(pool, ctx.query(), &[primary_key.to_string()]).await?;
         ctx.set_query_result(1, true);
         Self::after_scan(&ctx).await?;
         Self::after_query(&ctx).await?;
@@ -228,20 +168,20 @@ where
     /// Finds a primary key selected by the query in the table.
     async fn find_primary_key(query: &Query) -> Result<K, Error>
     where
-        K: Send + Unpin + Type<DatabaseDriver> + for<'r> Decode<'r, DatabaseDriver>,
+        K: Send + Unpin + DecodeColumn,
     {
         Self::before_query(query).await?;
 
         let projection = Self::PRIMARY_KEY_NAME;
         let table_name = query.format_table_name::<Self>();
         let filters = query.format_filters::<Self>();
-        let sort = query.format_sort();
+        let sort = query.format_sort::<Self>();
         let sql = format!("SELECT {projection} FROM {table_name} {filters} {sort} LIMIT 1;");
         let mut ctx = Self::before_scan(&sql).await?;
         ctx.set_query(sql);
 
         let pool = Self::acquire_reader().await?.pool();
-        let scalar = sqlx::query_scalar(ctx.query()).fetch_one(pool).await?;
+        let scalar = fetch_scalar(pool, ctx.query(), &[]).await?;
         ctx.set_query_result(1, true);
         Self::after_scan(&ctx).await?;
         Self::after_query(&ctx).await?;
@@ -251,21 +191,65 @@ where
     /// Finds a list of primary keys selected by the query in the table.
     async fn find_primary_keys(query: &Query) -> Result<Vec<K>, Error>
     where
-        K: Send + Unpin + Type<DatabaseDriver> + for<'r> Decode<'r, DatabaseDriver>,
+        K: Send + Unpin + DecodeColumn,
     {
         Self::before_query(query).await?;
 
         let projection = Self::PRIMARY_KEY_NAME;
         let table_name = query.format_table_name::<Self>();
         let filters = query.format_filters::<Self>();
-        let sort = query.format_sort();
+        let sort = query.format_sort::<Self>();
         let pagination = query.format_pagination();
         let sql = format!("SELECT {projection} FROM {table_name} {filters} {sort} {pagination};");
         let mut ctx = Self::before_scan(&sql).await?;
         ctx.set_query(&sql);
 
         let pool = Self::acquire_reader().await?.pool();
-        let mut stream = sqlx::query(&sql).fetch(pool);
+        let data = fetch_scalars(pool, &sql, &[]).await?;
+        ctx.set_query_result(u64::try_from(data.len())?, true);
+        Self::after_scan(&ctx).await?;
+        Self::after_query(&ctx).await?;
+        Ok(data)
+    }
+}
+
+impl<M, K> ScalarQuery<K> for M
+where
+    M: Schema<PrimaryKey = K>,
+    K: Default + Display + PartialEq,
+{
+}
+
+/// Fetches a scalar value in the first column of the row with the pool.
+async fn fetch_scalar<T>(pool: &DatabasePool, sql: &str, arguments: &[String]) -> Result<T, Error>
+where
+    T: Send + Unpin + DecodeColumn,
+{
+    dispatch!(pool, DatabasePool(pool) => {
+        let mut query = sqlx::query_scalar(sql);
+        for argument in arguments {
+            query = query.bind(argument.as_str());
+        }
+        query.fetch_one(pool).await.map_err(Error::from)
+    })
+}
+
+/// Fetches a list of scalar values in the first column of the rows with the pool.
+async fn fetch_scalars<T>(
+    pool: &DatabasePool,
+    sql: &str,
+    arguments: &[String],
+) -> Result<Vec<T>, Error>
+where
+    T: Send + Unpin + DecodeColumn,
+{
+    dispatch!(pool, DatabasePool(pool) => {
+        let mut query = sqlx::query(sql);
+        for argument in arguments {
+            query = query.bind(argument.as_str());
+        }
+
+        let mut stream = query.fetch(pool);
         let mut max_rows = super::MAX_ROWS.load(Relaxed);
         let estimated_rows = stream.size_hint().0;
         if cfg!(debug_assertions) && estimated_rows > max_rows {
@@ -285,16 +269,6 @@ where
                 break;
             }
         }
-        ctx.set_query_result(u64::try_from(data.len())?, true);
-        Self::after_scan(&ctx).await?;
-        Self::after_query(&ctx).await?;
         Ok(data)
-    }
-}
-
-impl<M, K> ScalarQuery<K> for M
-where
-    M: Schema<PrimaryKey = K>,
-    K: Default + Display + PartialEq,
-{
+    })
 }
@@ -1,10 +1,13 @@
 use super::{
-    ConnectionPool, DatabaseRow, DecodeRow, EncodeColumn, Entity, Executor, GlobalPool,
-    IntoSqlValue, JoinOn, ModelHelper, PrimaryKey, QueryBuilder, column::ColumnExt,
-    mutation::MutationExt, query::QueryExt,
+    ConnectionPool, DatabaseRow, DecodeRow, Dialect, EncodeColumn, Entity, Executor, GlobalPool,
+    IntoSqlValue, JoinOn, Migration, ModelHelper, PrimaryKey, QueryBuilder, QueryCache,
+    QueryResult, Relation, RelationKind, TransactionScope, cache, column::ColumnExt, cte,
+    decode::decode, migration, mutation::MutationExt, query::QueryExt,
 };
+
+use futures::{Stream, StreamExt, stream};
 use serde::de::DeserializeOwned;
-use std::sync::atomic::Ordering::Relaxed;
+use std::{borrow::Cow, slice, sync::atomic::Ordering::Relaxed, time::Duration};
 use zino_core::{
     JsonValue, Map, bail,
     error::Error,
@@ -13,6 +16,9 @@ use zino_core::{
     warn,
 };
 
+/// Number of the rows in a batch for eager loading the relations of a stream.
+const EAGER_LOADING_BATCH_SIZE: usize = 100;
+
 /// Database schema.
 ///
 /// This trait can be derived by `zino_derive::Schema`.
@@ -28,6 +34,8 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
     const WRITER_NAME: &'static str = "main";
     /// Optional custom table name.
     const TABLE_NAME: Option<&'static str> = None;
+    /// Optional time-to-live for the query cache.
+    const QUERY_CACHE_TTL: Option<Duration> = None;
 
     /// Returns the primary key.
     fn primary_key(&self) -> &Self::PrimaryKey;
@@ -47,18 +55,45 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
     /// Returns a reference to the write-only column fields.
     fn write_only_fields() -> &'static [&'static str];
 
+    /// Returns the relationships of the model.
+    #[inline]
+    fn relations() -> &'static [Relation] {
+        &[]
+    }
+
+    /// Loads the related models of the relation for the keys,
+    /// which is implemented by `#[derive(Schema)]` for the declared relationships.
+    async fn load_relation(
+        relation: &Relation,
+        _keys: Vec<JsonValue>,
+    ) -> Result<Vec<(JsonValue, Map)>, Error> {
+        bail!(
+            "the `{}` relation is not supported for the model `{}`",
+            relation.name(),
+            Self::MODEL_NAME
+        );
+    }
+
     /// Retrieves a connection pool for the model reader.
     async fn acquire_reader() -> Result<&'static ConnectionPool, Error>;
 
     /// Retrieves a connection pool for the model writer.
     async fn acquire_writer() -> Result<&'static ConnectionPool, Error>;
 
+    /// Returns the SQL dialect of the database service for the model writer.
+    #[inline]
+    fn dialect() -> Dialect {
+        Self::init_writer()
+            .map(|cp| cp.dialect())
+            .unwrap_or_default()
+    }
+
     /// Returns the driver name.
     ///
     /// Supported drivers: `mariadb` | `mysql` | `postgres` | `sqlite` | `tidb`.
     #[inline]
     fn driver_name() -> &'static str {
-        super::DRIVER_NAME
+        Self::dialect().as_str()
     }
 
     /// Returns the prefix for the table name.
@@ -171,10 +206,10 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         mutation
     }
 
-    /// Initializes the model reader.
+    /// Initializes the model reader, which may be one of the read replicas.
     #[inline]
     fn init_reader() -> Result<&'static ConnectionPool, Error> {
-        GlobalPool::get(Self::READER_NAME)
+        GlobalPool::get_reader(Self::READER_NAME)
             .ok_or_else(|| warn!("connection to the database is unavailable"))
     }
 
@@ -195,14 +230,14 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
 
         let primary_key_name = Self::primary_key_name();
         let table_name = Self::table_name();
-        let table_name_escaped = Query::escape_table_name(table_name);
+        let table_name_escaped = Self::dialect().escape_table_name(table_name);
         let columns = Self::columns();
         let mut definitions = columns
             .iter()
-            .map(|col| col.field_definition(primary_key_name))
+            .map(|col| col.field_definition(Self::dialect(), primary_key_name))
             .collect::<Vec<_>>();
         for col in columns {
-            let mut constraints = col.constraints();
+            let mut constraints = col.constraints(Self::dialect());
             if !constraints.is_empty() {
                 definitions.append(&mut constraints);
             }
@@ -227,47 +262,17 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         }
 
         let mut table_name = Self::table_name();
-        let table_name_escaped = Query::escape_table_name(table_name);
+        let table_name_escaped = Self::dialect().escape_table_name(table_name);
         if let Some((_, suffix)) = table_name.rsplit_once('.') {
             table_name = suffix;
         }
 
-        let sql = if cfg!(any(
-            feature = "orm-mariadb",
-            feature = "orm-mysql",
-            feature = "orm-tidb"
-        )) {
-            let table_schema = connection_pool.database();
-            format!(
-                "SELECT column_name, data_type, column_default, is_nullable \
-                    FROM information_schema.columns \
-                        WHERE table_schema = '{table_schema}' AND table_name = '{table_name}';"
-            )
-        } else if cfg!(feature = "orm-postgres") {
-            format!(
-                "SELECT column_name, data_type, column_default, is_nullable \
-                    FROM information_schema.columns \
-                        WHERE table_schema = 'public' AND table_name = '{table_name}';"
-            )
-        } else {
-            format!(
-                "SELECT p.name AS column_name, p.type AS data_type, \
-                        p.dflt_value AS column_default, p.[notnull] AS is_not_null \
-                    FROM sqlite_master m LEFT OUTER JOIN pragma_table_info((m.name)) p
-                        ON m.name <> p.name WHERE m.name = '{table_name}';"
-            )
-        };
+        let data = migration::fetch_table_info(connection_pool, table_name).await?;
         let pool = connection_pool.pool();
-        let rows = pool.fetch(&sql).await?;
-        let mut data = Vec::with_capacity(rows.len());
-        for row in rows {
-            data.push(Map::decode_row(&row)?);
-        }
-
         let model_name = Self::model_name();
         let primary_key_name = Self::primary_key_name();
         for col in Self::columns() {
-            let column_type = col.column_type();
+            let column_type = col.column_type(Self::dialect());
             let column_name = col
                 .extra()
                 .get_str("column_name")
@@ -282,7 +287,7 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
                 let column_default = d
                     .get_str("column_default")
                     .or_else(|| d.get_str("COLUMN_DEFAULT"));
-                let is_not_null = if cfg!(any(feature = "orm-mysql", feature = "orm-postgres")) {
+                let is_not_null = if Self::dialect() != Dialect::Sqlite {
                     d.get_str("is_nullable")
                         .or_else(|| d.get_str("IS_NULLABLE"))
                         .unwrap_or("YES")
@@ -290,7 +295,7 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
                 } else {
                     d.get_str("is_not_null") == Some("1")
                 };
-                if !data_type.is_some_and(|t| col.is_compatible(t)) {
+                if !data_type.is_some_and(|t| col.is_compatible(Self::dialect(), t)) {
                     tracing::warn!(
                         model_name,
                         table_name,
@@ -313,7 +318,7 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
                     );
                 }
             } else {
-                let column_definition = col.field_definition(primary_key_name);
+                let column_definition = col.field_definition(Self::dialect(), primary_key_name);
                 let sql =
                     format!("ALTER TABLE {table_name_escaped} ADD COLUMN {column_definition};");
                 pool.execute(&sql).await?;
@@ -329,6 +334,17 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         Ok(())
     }
 
+    /// Generates a versioned migration for the model by comparing the columns
+    /// with the table definition in the database.
+    ///
+    /// The migration contains the statements to create the table, add, rename,
+    /// alter and drop columns, and the corresponding statements to revert them.
+    /// A column can be renamed by specifying the `renamed_from` attribute.
+    #[inline]
+    async fn generate_migration() -> Result<Migration, Error> {
+        migration::generate_migration::<Self>().await
+    }
+
     /// Creates indexes for the model.
     async fn create_indexes() -> Result<u64, Error> {
         let connection_pool = Self::init_writer()?;
@@ -337,7 +353,7 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         }
 
         let mut table_name = Self::table_name();
-        let table_name_escaped = Query::escape_table_name(table_name);
+        let table_name_escaped = Self::dialect().escape_table_name(table_name);
         if let Some((_, suffix)) = table_name.rsplit_once('.') {
             table_name = suffix;
         }
@@ -345,11 +361,7 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         let pool = connection_pool.pool();
         let columns = Self::columns();
         let mut rows = 0;
-        if cfg!(any(
-            feature = "orm-mariadb",
-            feature = "orm-mysql",
-            feature = "orm-tidb"
-        )) {
+        if Self::dialect().is_mysql_compatible() {
             let sql = format!("SHOW INDEXES FROM {table_name_escaped}");
             if pool.fetch(&sql).await?.len() > 1 {
                 return Ok(0);
@@ -359,7 +371,7 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
             for col in columns {
                 if let Some(index_type) = col.index_type() {
                     let column_name = col.name();
-                    if matches!(index_type, "fulltext" | "text") {
+                    if col.has_fulltext_index() {
                         text_search_columns.push(column_name);
                     } else if matches!(index_type, "unique" | "spatial") {
                         let index_type = index_type.to_uppercase();
@@ -386,16 +398,18 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
                 );
                 rows = pool.execute(&sql).await?.rows_affected().max(rows);
             }
-        } else if cfg!(feature = "orm-postgres") {
+        } else if Self::dialect() == Dialect::Postgres {
             let mut text_search_columns = Vec::new();
             let mut text_search_languages = Vec::new();
             for col in columns {
                 if let Some(index_type) = col.index_type() {
                     let column_name = col.name();
-                    if index_type.starts_with("text") {
-                        let language = index_type.strip_prefix("text:").unwrap_or("english");
+                    if col.has_fulltext_index() {
+                        let language = col.fulltext_language().unwrap_or("english");
                         let column = format!("coalesce({column_name}, '')");
-                        text_search_languages.push(language);
+                        if !text_search_languages.contains(&language) {
+                            text_search_languages.push(language);
+                        }
                         text_search_columns.push((language, column));
                     } else if index_type == "unique" {
                         let sql = format!(
@@ -428,8 +442,11 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
                 rows = pool.execute(&sql).await?.rows_affected().max(rows);
             }
         } else {
+            let mut text_search_columns = Vec::new();
             for col in columns {
-                if let Some(index_type) = col.index_type() {
+                if col.has_fulltext_index() {
+                    text_search_columns.push(col.name());
+                } else if let Some(index_type) = col.index_type() {
                     let column_name = col.name();
                     let index_type = if index_type == "unique" { "UNIQUE" } else { "" };
                     let sql = format!(
@@ -439,6 +456,28 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
                     rows = pool.execute(&sql).await?.rows_affected().max(rows);
                 }
             }
+            // The FTS5 table is rebuilt when the full-text columns have been changed
+            let fts_columns = migration::fetch_fts_columns(connection_pool, table_name).await?;
+            if fts_columns != text_search_columns {
+                let mut statements = Vec::new();
+                if !fts_columns.is_empty() {
+                    tracing::warn!(
+                        table_name,
+                        "full-text columns have been changed from `{}` to `{}`",
+                        fts_columns.join(", "),
+                        text_search_columns.join(", "),
+                    );
+                    statements.append(&mut migration::format_fts_drop_statements(table_name));
+                }
+                statements.append(&mut migration::format_fts_statements(
+                    table_name,
+                    &table_name_escaped,
+                    &text_search_columns,
+                ));
+                for sql in statements {
+                    rows = pool.execute(&sql).await?.rows_affected().max(rows);
+                }
+            }
         }
         Ok(rows)
     }
@@ -446,9 +485,9 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
     /// Prepares the SQL to insert the model into the table.
     async fn prepare_insert(self) -> Result<QueryContext, Error> {
         let table_name = if let Some(table) = self.before_prepare().await? {
-            Query::escape_table_name(&table)
+            Self::dialect().escape_table_name(&table)
         } else {
-            Query::escape_table_name(Self::table_name())
+            Self::dialect().escape_table_name(Self::table_name())
         };
         let map = self.into_map();
         let columns = Self::columns();
@@ -461,15 +500,15 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
                     None
                 } else {
                     let name = col.name();
-                    let field = Query::format_field(name);
+                    let field = Self::dialect().format_field(name);
                     fields.push(field);
-                    Some(col.encode_value(map.get(name)))
+                    Some(col.encode_value(Self::dialect(), map.get(name)))
                 }
             })
             .collect::<Vec<_>>()
             .join(", ");
         let fields = fields.join(", ");
-        let sql = if cfg!(feature = "orm-postgres") {
+        let sql = if Self::dialect() == Dialect::Postgres {
             let primary_key_name = Self::primary_key_name();
             format!(
                 "INSERT INTO {table_name} ({fields}) VALUES ({values}) \
@@ -487,37 +526,13 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
     }
 
     /// Inserts the model into the table.
-    async fn insert(mut self) -> Result<QueryContext, Error> {
-        let model_data = self.before_insert().await?;
-        let mut ctx = self.prepare_insert().await?;
-        if ctx.is_cancelled() {
-            return Ok(ctx);
-        }
+    async fn insert(self) -> Result<QueryContext, Error> {
+        insert_model(self, None).await
+    }
 
-        let pool = Self::acquire_writer().await?.pool();
-        let (last_insert_id, rows_affected) =
-            if cfg!(feature = "orm-postgres") && Self::primary_key_column().auto_increment() {
-                let primary_key = sqlx::query_scalar(ctx.query()).fetch_one(pool).await?;
-                (Some(primary_key), 1)
-            } else {
-                let query_result = pool.execute(ctx.query()).await?;
-                Query::parse_query_result(query_result)
-            };
-        let success = rows_affected == 1;
-        if let Some(last_insert_id) = last_insert_id {
-            ctx.set_last_insert_id(last_insert_id);
-        }
-        ctx.set_query_result(rows_affected, success);
-        Self::after_scan(&ctx).await?;
-        Self::after_insert(&ctx, model_data).await?;
-        if success {
-            Ok(ctx)
-        } else {
-            bail!(
-                "{} rows are affected while it is expected to affect 1 row",
-                rows_affected
-            );
-        }
+    /// Inserts the model into the table inside of the transaction.
+    async fn insert_in(self, tx: &mut TransactionScope<'_>) -> Result<QueryContext, Error> {
+        insert_model(self, Some(tx)).await
     }
 
     /// Prepares the SQL to insert many models into the table.
@@ -534,16 +549,16 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
             let map = model.into_map();
             let entries = columns
                 .iter()
-                .map(|col| col.encode_value(map.get(col.name())))
+                .map(|col| col.encode_value(Self::dialect(), map.get(col.name())))
                 .collect::<Vec<_>>()
                 .join(", ");
             values.push(format!("({entries})"));
         }
 
-        let table_name = Query::escape_table_name(Self::table_name());
+        let table_name = Self::dialect().escape_table_name(Self::table_name());
         let fields = Self::fields()
             .iter()
-            .map(|&field| Query::format_field(field))
+            .map(|&field| Self::dialect().format_field(field))
             .collect::<Vec<_>>()
             .join(", ");
         let values = values.join(", ");
@@ -566,6 +581,7 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         let pool = Self::acquire_writer().await?.pool();
         let query_result = pool.execute(ctx.query()).await?;
         ctx.set_query_result(query_result.rows_affected(), true);
+        QueryCache::invalidate::<Self>();
         Self::after_scan(&ctx).await?;
         Ok(ctx)
     }
@@ -583,7 +599,7 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
             bail!("a list of columns should be nonempty");
         }
 
-        let table_name = Query::escape_table_name(Self::table_name());
+        let table_name = Self::dialect().escape_table_name(Self::table_name());
         let fields = columns
             .iter()
             .map(|col| col.as_ref())
@@ -616,6 +632,7 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         let pool = Self::acquire_writer().await?.pool();
         let query_result = pool.execute(ctx.query()).await?;
         ctx.set_query_result(query_result.rows_affected(), true);
+        QueryCache::invalidate::<Self>();
         Self::after_scan(&ctx).await?;
         Ok(ctx)
     }
@@ -623,9 +640,9 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
     /// Prepares the SQL to update the model in the table.
     async fn prepare_update(self) -> Result<QueryContext, Error> {
         let table_name = if let Some(table) = self.before_prepare().await? {
-            Query::escape_table_name(&table)
+            Self::dialect().escape_table_name(&table)
         } else {
-            Query::escape_table_name(Self::table_name())
+            Self::dialect().escape_table_name(Self::table_name())
         };
         let primary_key = Query::escape_string(self.primary_key());
         let primary_key_name = Self::primary_key_name();
@@ -636,8 +653,8 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         for col in Self::columns() {
             let field = col.name();
             if !read_only_fields.contains(&field) {
-                let value = col.encode_value(map.get(field));
-                let field = Query::format_field(field);
+                let value = col.encode_value(Self::dialect(), map.get(field));
+                let field = Self::dialect().format_field(field);
                 mutations.push(format!("{field} = {value}"));
             }
         }
@@ -655,28 +672,13 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
     }
 
     /// Updates the model in the table.
-    async fn update(mut self) -> Result<QueryContext, Error> {
-        let model_data = self.before_update().await?;
-        let mut ctx = self.prepare_update().await?;
-        if ctx.is_cancelled() {
-            return Ok(ctx);
-        }
+    async fn update(self) -> Result<QueryContext, Error> {
+        update_model(self, None).await
+    }
 
-        let pool = Self::acquire_writer().await?.pool();
-        let query_result = pool.execute(ctx.query()).await?;
-        let rows_affected = query_result.rows_affected();
-        let success = rows_affected == 1;
-        ctx.set_query_result(rows_affected, success);
-        Self::after_scan(&ctx).await?;
-        Self::after_update(&ctx, model_data).await?;
-        if success {
-            Ok(ctx)
-        } else {
-            bail!(
-                "{} rows are affected while it is expected to affect 1 row",
-                rows_affected
-            );
-        }
+    /// Updates the model in the table inside of the transaction.
+    async fn update_in(self, tx: &mut TransactionScope<'_>) -> Result<QueryContext, Error> {
+        update_model(self, Some(tx)).await
     }
 
     /// Prepares the SQL to update the model for partial columns in the table.
@@ -685,9 +687,9 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         columns: &[C],
     ) -> Result<QueryContext, Error> {
         let table_name = if let Some(table) = self.before_prepare().await? {
-            Query::escape_table_name(&table)
+            Self::dialect().escape_table_name(&table)
         } else {
-            Query::escape_table_name(Self::table_name())
+            Self::dialect().escape_table_name(Self::table_name())
         };
         let primary_key = Query::escape_string(self.primary_key());
         let primary_key_name = Self::primary_key_name();
@@ -699,8 +701,8 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
             if !read_only_fields.contains(&field)
                 && let Some(col) = Self::columns().iter().find(|col| col.name() == field)
             {
-                let value = col.encode_value(map.get(field));
-                let field = Query::format_field(field);
+                let value = col.encode_value(Self::dialect(), map.get(field));
+                let field = Self::dialect().format_field(field);
                 mutations.push(format!("{field} = {value}"));
             }
         }
@@ -730,6 +732,7 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         let rows_affected = query_result.rows_affected();
         let success = rows_affected == 1;
         ctx.set_query_result(rows_affected, success);
+        QueryCache::invalidate::<Self>();
         Self::after_scan(&ctx).await?;
         Self::after_update(&ctx, model_data).await?;
         if success {
@@ -753,11 +756,7 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         let table_name = query.format_table_name::<Self>();
         let filters = query.format_filters::<Self>();
         let updates = mutation.format_updates::<Self>();
-        let sql = if cfg!(any(
-            feature = "orm-mariadb",
-            feature = "orm-mysql",
-            feature = "orm-tidb"
-        )) {
+        let sql = if Self::dialect().is_mysql_compatible() {
             // MySQL doesn't yet support 'LIMIT & IN/ALL/ANY/SOME subquery'
             // and self-referencing in UPDATE/DELETE
             format!(
@@ -766,7 +765,7 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
             )
         } else {
             // Both PostgreQL and SQLite support a `LIMIT` in subquery
-            let sort = query.format_sort();
+            let sort = query.format_sort::<Self>();
             format!(
                 "UPDATE {table_name} SET {updates} WHERE {primary_key_name} IN \
                     (SELECT {primary_key_name} FROM {table_name} {filters} {sort} LIMIT 1);"
@@ -782,26 +781,16 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
 
     /// Updates at most one model selected by the query in the table.
     async fn update_one(query: &Query, mutation: &mut Mutation) -> Result<QueryContext, Error> {
-        let mut ctx = Self::prepare_update_one(query, mutation).await?;
-        if ctx.is_cancelled() {
-            return Ok(ctx);
-        }
+        update_one_model::<Self>(query, mutation, None).await
+    }
 
-        let pool = Self::acquire_writer().await?.pool();
-        let query_result = pool.execute(ctx.query()).await?;
-        let rows_affected = query_result.rows_affected();
-        let success = rows_affected <= 1;
-        ctx.set_query_result(rows_affected, success);
-        Self::after_scan(&ctx).await?;
-        Self::after_mutation(&ctx).await?;
-        if success {
-            Ok(ctx)
-        } else {
-            bail!(
-                "{} rows are affected while it is expected to affect at most 1 row",
-                rows_affected
-            );
-        }
+    /// Updates at most one model selected by the query in the table inside of the transaction.
+    async fn update_one_in(
+        query: &Query,
+        mutation: &mut Mutation,
+        tx: &mut TransactionScope<'_>,
+    ) -> Result<QueryContext, Error> {
+        update_one_model::<Self>(query, mutation, Some(tx)).await
     }
 
     /// Prepares the SQL to update many models selected by the query in the table.
@@ -833,6 +822,7 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         let pool = Self::acquire_writer().await?.pool();
         let query_result = pool.execute(ctx.query()).await?;
         ctx.set_query_result(query_result.rows_affected(), true);
+        QueryCache::invalidate::<Self>();
         Self::after_scan(&ctx).await?;
         Self::after_mutation(&ctx).await?;
         Ok(ctx)
@@ -841,9 +831,9 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
     /// Prepares the SQL to update or insert the model into the table.
     async fn prepare_upsert(self) -> Result<QueryContext, Error> {
         let table_name = if let Some(table) = self.before_prepare().await? {
-            Query::escape_table_name(&table)
+            Self::dialect().escape_table_name(&table)
         } else {
-            Query::escape_table_name(Self::table_name())
+            Self::dialect().escape_table_name(Self::table_name())
         };
         let map = self.into_map();
         let num_fields = Self::fields().len();
@@ -854,8 +844,8 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         let mut mutations = Vec::with_capacity(num_writable_fields);
         for col in Self::columns() {
             let name = col.name();
-            let field = Query::format_field(name);
-            let value = col.encode_value(map.get(name));
+            let field = Self::dialect().format_field(name);
+            let value = col.encode_value(Self::dialect(), map.get(name));
             if !read_only_fields.contains(&name) {
                 mutations.push(format!("{field} = {value}"));
             }
@@ -866,25 +856,14 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         let fields = fields.join(", ");
         let values = values.join(", ");
         let mutations = mutations.join(", ");
-        let sql = if cfg!(any(
-            feature = "orm-mariadb",
-            feature = "orm-mysql",
-            feature = "orm-tidb"
-        )) {
-            format!(
-                "INSERT INTO {table_name} ({fields}) VALUES ({values}) \
-                    ON DUPLICATE KEY UPDATE {mutations};"
-            )
-        } else {
-            let primary_key_name = Self::primary_key_name();
-
-            // Both PostgreQL and SQLite (3.35+) support this syntax.
-            format!(
-                "INSERT INTO {table_name} ({fields}) VALUES ({values}) \
-                    ON CONFLICT ({primary_key_name}) DO UPDATE SET {mutations} \
-                        RETURNING {primary_key_name};"
-            )
-        };
+        let primary_key_name = Self::primary_key_name();
+        let sql = Self::dialect().format_upsert(
+            &table_name,
+            &fields,
+            &values,
+            &mutations,
+            primary_key_name,
+        );
         let mut ctx = Self::before_scan(&sql).await?;
         ctx.set_query(sql);
         if cfg!(debug_assertions) && super::DEBUG_ONLY.load(Relaxed) {
@@ -902,19 +881,22 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         }
 
         let pool = Self::acquire_writer().await?.pool();
-        let (last_insert_id, rows_affected) =
-            if cfg!(feature = "orm-postgres") && Self::primary_key_column().auto_increment() {
-                let primary_key = sqlx::query_scalar(ctx.query()).fetch_one(pool).await?;
-                (Some(primary_key), 1)
-            } else {
-                let query_result = pool.execute(ctx.query()).await?;
-                Query::parse_query_result(query_result)
-            };
+        let (last_insert_id, rows_affected) = if Self::dialect() == Dialect::Postgres
+            && Self::primary_key_column().auto_increment()
+        {
+            let row = pool.fetch_one(ctx.query()).await?;
+            let primary_key = decode(&row, Self::primary_key_name())?;
+            (Some(primary_key), 1)
+        } else {
+            let query_result = pool.execute(ctx.query()).await?;
+            (query_result.last_insert_id(), query_result.rows_affected())
+        };
         let success = rows_affected == 1;
         if let Some(last_insert_id) = last_insert_id {
             ctx.set_last_insert_id(last_insert_id);
         }
         ctx.set_query_result(rows_affected, success);
+        QueryCache::invalidate::<Self>();
         Self::after_scan(&ctx).await?;
         Self::after_upsert(&ctx, model_data).await?;
         if success {
@@ -930,14 +912,14 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
     /// Prepares the SQL to delete the model in the table.
     async fn prepare_delete(&self) -> Result<QueryContext, Error> {
         let table_name = if let Some(table) = self.before_prepare().await? {
-            Query::escape_table_name(&table)
+            Self::dialect().escape_table_name(&table)
         } else {
-            Query::escape_table_name(Self::table_name())
+            Self::dialect().escape_table_name(Self::table_name())
         };
         let primary_key_name = Self::primary_key_name();
-        let placeholder = Query::placeholder(1);
-        let sql = if cfg!(feature = "orm-postgres") {
-            let type_annotation = Self::primary_key_column().type_annotation();
+        let placeholder = Self::dialect().placeholder(1);
+        let sql = if Self::dialect() == Dialect::Postgres {
+            let type_annotation = Self::primary_key_column().type_annotation(Self::dialect());
             format!(
                 "DELETE FROM {table_name} \
                     WHERE {primary_key_name} = ({placeholder}){type_annotation};"
@@ -954,30 +936,13 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
     }
 
     /// Deletes the model in the table.
-    async fn delete(mut self) -> Result<QueryContext, Error> {
-        let model_data = self.before_delete().await?;
-        let mut ctx = self.prepare_delete().await?;
-        if ctx.is_cancelled() {
-            return Ok(ctx);
-        }
+    async fn delete(self) -> Result<QueryContext, Error> {
+        delete_model(self, None).await
+    }
 
-        let pool = Self::acquire_writer().await?.pool();
-        let primary_key = self.primary_key();
-        let query_result = pool.execute_with(ctx.query(), &[primary_key]).await?;
-        let rows_affected = query_result.rows_affected();
-        let success = rows_affected == 1;
-        ctx.add_argument(primary_key);
-        ctx.set_query_result(rows_affected, success);
-        Self::after_scan(&ctx).await?;
-        self.after_delete(&ctx, model_data).await?;
-        if success {
-            Ok(ctx)
-        } else {
-            bail!(
-                "{} rows are affected while it is expected to affect 1 row",
-                rows_affected
-            );
-        }
+    /// Deletes the model in the table inside of the transaction.
+    async fn delete_in(self, tx: &mut TransactionScope<'_>) -> Result<QueryContext, Error> {
+        delete_model(self, Some(tx)).await
     }
 
     /// Prepares the SQL to delete at most one model selected by the query in the table.
@@ -987,7 +952,7 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         let primary_key_name = Self::primary_key_name();
         let table_name = query.format_table_name::<Self>();
         let filters = query.format_filters::<Self>();
-        let sort = query.format_sort();
+        let sort = query.format_sort::<Self>();
         let sql = format!(
             "DELETE FROM {table_name} WHERE {primary_key_name} IN \
                 (SELECT {primary_key_name} FROM {table_name} {filters} {sort} LIMIT 1);"
@@ -1012,6 +977,7 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         let rows_affected = query_result.rows_affected();
         let success = rows_affected <= 1;
         ctx.set_query_result(rows_affected, success);
+        QueryCache::invalidate::<Self>();
         Self::after_scan(&ctx).await?;
         Self::after_query(&ctx).await?;
         if success {
@@ -1030,9 +996,9 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
 
         let table_name = query.format_table_name::<Self>();
         let filters = query.format_filters::<Self>();
-        let sort = query.format_sort();
+        let sort = query.format_sort::<Self>();
         let pagination = query.format_pagination();
-        let sql = if cfg!(feature = "orm-postgres") {
+        let sql = if Self::dialect() == Dialect::Postgres {
             let primary_key_name = Self::primary_key_name();
             format!(
                 "DELETE FROM {table_name} WHERE {primary_key_name} IN \
@@ -1059,6 +1025,7 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         let pool = Self::acquire_writer().await?.pool();
         let query_result = pool.execute(ctx.query()).await?;
         ctx.set_query_result(query_result.rows_affected(), true);
+        QueryCache::invalidate::<Self>();
         Self::after_scan(&ctx).await?;
         Self::after_query(&ctx).await?;
         Ok(ctx)
@@ -1077,7 +1044,7 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
             bail!("a list of columns should be nonempty");
         }
 
-        let table_name = Query::escape_table_name(Self::table_name());
+        let table_name = Self::dialect().escape_table_name(Self::table_name());
         let fields = columns
             .iter()
             .map(|col| col.as_ref())
@@ -1110,6 +1077,7 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         let pool = Self::acquire_writer().await?.pool();
         let query_result = pool.execute(ctx.query()).await?;
         ctx.set_query_result(query_result.rows_affected(), true);
+        QueryCache::invalidate::<Self>();
         Self::after_scan(&ctx).await?;
         Self::after_query(&ctx).await?;
         Ok(ctx)
@@ -1121,27 +1089,18 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
     where
         T: DecodeRow<DatabaseRow, Error = Error>,
     {
-        Self::before_query(query).await?;
-
-        let table_name = query.format_table_name::<Self>();
-        let projection = query.format_table_fields::<Self>();
-        let filters = query.format_filters::<Self>();
-        let sort = query.format_sort();
-        let pagination = query.format_pagination();
-        let sql = format!("SELECT {projection} FROM {table_name} {filters} {sort} {pagination};");
-        let mut ctx = Self::before_scan(&sql).await?;
-        ctx.set_query(&sql);
+        find_models::<Self, T>(query, None).await
+    }
 
-        let pool = Self::acquire_reader().await?.pool();
-        let rows = pool.fetch(ctx.query()).await?;
-        let mut data = Vec::with_capacity(rows.len());
-        for row in rows {
-            data.push(T::decode_row(&row)?);
-        }
-        ctx.set_query_result(u64::try_from(data.len())?, true);
-        Self::after_scan(&ctx).await?;
-        Self::after_query(&ctx).await?;
-        Ok(data)
+    /// Finds a list of models selected by the query in the table inside of the transaction,
+    /// and decodes it as `Vec<T>`.
+    ///
+    /// The query cache is bypassed so that the uncommitted writes are visible.
+    async fn find_in<T>(query: &Query, tx: &mut TransactionScope<'_>) -> Result<Vec<T>, Error>
+    where
+        T: DecodeRow<DatabaseRow, Error = Error>,
+    {
+        find_models::<Self, T>(query, Some(tx)).await
     }
 
     /// Finds a list of models selected by the query in the table,
@@ -1156,25 +1115,128 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         serde_json::from_value(data.into()).map_err(Error::from)
     }
 
+    /// Finds a list of models selected by the query in the table as a stream,
+    /// and decodes each row as an instance of type `T`.
+    ///
+    /// The rows are not buffered and the maximum row limit does not apply,
+    /// so it is suitable for exporting large tables. However, the page of a backward cursor
+    /// is buffered to restore the sort order, and the relations specified by
+    /// [`QueryBuilder::with`] are eager loaded for the rows in batches.
+    /// Since the stream is consumed lazily, the `after_scan` and `after_query` hooks
+    /// will not be invoked.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use futures::StreamExt;
+    /// use std::pin::pin;
+    ///
+    /// let query = Query::default();
+    /// let mut stream = pin!(Log::find_stream::<Map>(&query).await?);
+    /// while let Some(result) = stream.next().await {
+    ///     let log = result?;
+    /// }
+    /// ```
+    async fn find_stream<T>(
+        query: &Query,
+    ) -> Result<impl Stream<Item = Result<T, Error>> + use<Self, T>, Error>
+    where
+        T: DecodeRow<DatabaseRow, Error = Error>,
+    {
+        let query = prepare_eager_loading::<Self>(query)?;
+        Self::before_query(&query).await?;
+        query.check_cursor::<Self>()?;
+
+        let with_clause = cte::format_with_clause::<Self>(&query);
+        let table_name = query.format_table_name::<Self>();
+        let projection = query.format_table_fields::<Self>();
+        let filters = query.format_filters::<Self>();
+        let sort = query.format_sort::<Self>();
+        let pagination = query.format_pagination();
+        let sql = format!(
+            "{with_clause}SELECT {projection} FROM {table_name} {filters} {sort} {pagination};"
+        );
+        let mut ctx = Self::before_scan(&sql).await?;
+        ctx.set_query(sql);
+
+        let pool = Self::acquire_reader().await?.pool();
+        let rows = pool.fetch_stream(ctx.query().to_owned());
+        let is_backward = query.cursor().is_some_and(|cursor| cursor.is_backward());
+        let stream = if query.extra().contains_key("with") {
+            let query = query.into_owned();
+            rows.chunks(EAGER_LOADING_BATCH_SIZE)
+                .then(move |rows| {
+                    let query = query.clone();
+                    async move { decode_rows::<Self, T>(&query, rows).await }
+                })
+                .flat_map(|result| {
+                    let data = match result {
+                        Ok(data) => data.into_iter().map(Ok).collect(),
+                        Err(err) => vec![Err(err)],
+                    };
+                    stream::iter(data)
+                })
+                .left_stream()
+        } else {
+            rows.map(|result| result.and_then(|row| T::decode_row(&row)))
+                .right_stream()
+        };
+        if is_backward {
+            // Restores the sort order since it has been reversed for a backward cursor,
+            // which requires the page to be buffered
+            let mut data = stream.collect::<Vec<_>>().await;
+            data.reverse();
+            Ok(stream::iter(data).left_stream())
+        } else {
+            Ok(stream.right_stream())
+        }
+    }
+
+    /// Finds a list of models selected by the query in the table as a stream,
+    /// and parses each row as an instance of type `T`.
+    async fn find_stream_as<T: DeserializeOwned>(
+        query: &Query,
+    ) -> Result<impl Stream<Item = Result<T, Error>> + use<Self, T>, Error> {
+        let translate_enabled = query.translate_enabled();
+        let stream = Self::find_stream::<Map>(query)
+            .await?
+            .then(move |result| async move {
+                let mut model = result?;
+                translate_enabled.then(|| Self::translate_model(&mut model));
+                Self::after_decode(&mut model).await?;
+                serde_json::from_value(model.into()).map_err(Error::from)
+            });
+        Ok(stream)
+    }
+
     /// Finds one model selected by the query in the table,
     /// and decodes it as an instance of type `T`.
     async fn find_one<T>(query: &Query) -> Result<Option<T>, Error>
     where
         T: DecodeRow<DatabaseRow, Error = Error>,
     {
+        let query = &*prepare_eager_loading::<Self>(query)?;
         Self::before_query(query).await?;
+        query.check_cursor::<Self>()?;
 
+        let with_clause = cte::format_with_clause::<Self>(query);
         let table_name = query.format_table_name::<Self>();
         let projection = query.format_table_fields::<Self>();
         let filters = query.format_filters::<Self>();
-        let sort = query.format_sort();
-        let sql = format!("SELECT {projection} FROM {table_name} {filters} {sort} LIMIT 1;");
+        let sort = query.format_sort::<Self>();
+        let sql =
+            format!("{with_clause}SELECT {projection} FROM {table_name} {filters} {sort} LIMIT 1;");
         let mut ctx = Self::before_scan(&sql).await?;
         ctx.set_query(sql);
 
         let pool = Self::acquire_reader().await?.pool();
         let (num_rows, data) = if let Some(row) = pool.fetch_optional(ctx.query()).await? {
-            (1, Some(T::decode_row(&row)?))
+            let mut data = T::decode_row(&row)?;
+            if query.extra().contains_key("with") {
+                let model = Map::decode_row(&row)?;
+                Self::eager_load(query, &[model], slice::from_mut(&mut data)).await?;
+            }
+            (1, Some(data))
         } else {
             (0, None)
         };
@@ -1199,6 +1261,54 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         }
     }
 
+    /// Eager loads the relations specified by [`QueryBuilder::with`] for the models,
+    /// and attaches the related data to `data` which has been decoded from the same rows.
+    /// The related models are selected in a batch for each relation,
+    /// which solves the `N+1` problem.
+    async fn eager_load<T>(query: &Query, models: &[Map], data: &mut [T]) -> Result<(), Error>
+    where
+        T: DecodeRow<DatabaseRow, Error = Error>,
+    {
+        let Some(names) = query.extra().parse_str_array("with") else {
+            return Ok(());
+        };
+        for name in names {
+            let Some(relation) = Self::relations().iter().find(|r| r.name() == name) else {
+                bail!(
+                    "the `{}` relation is not defined for the model `{}`",
+                    name,
+                    Self::MODEL_NAME
+                );
+            };
+            let belongs_to = relation.kind() == RelationKind::BelongsTo;
+            let local_key = relation.local_key::<Self>();
+            let mut keys = Vec::new();
+            for model in models {
+                if let Some(key) = model.get(local_key).filter(|key| !key.is_null())
+                    && !keys.contains(key)
+                {
+                    keys.push(key.to_owned());
+                }
+            }
+
+            let associations = Self::load_relation(relation, keys).await?;
+            for (model, entry) in models.iter().zip(data.iter_mut()) {
+                let key = model.get(local_key);
+                let mut related = associations
+                    .iter()
+                    .filter(|(k, _)| Some(k) == key)
+                    .map(|(_, v)| JsonValue::from(v.to_owned()));
+                let value = if belongs_to {
+                    related.next().unwrap_or_default()
+                } else {
+                    related.collect::<Vec<_>>().into()
+                };
+                entry.attach_relation(name, value)?;
+            }
+        }
+        Ok(())
+    }
+
     /// Populates the related data in the corresponding `columns` for `Vec<Map>` using
     /// a merged select on the primary key, which solves the `N+1` problem.
     async fn populate<C: AsRef<str>>(
@@ -1328,7 +1438,7 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         }
 
         let table_name = query.format_table_name::<Self>();
-        let projection = query.format_projection();
+        let projection = query.format_projection(Self::dialect());
         let filters = query.format_filters::<Self>();
         let sql = format!("SELECT {projection} FROM {table_name} {filters};");
         let mut ctx = Self::before_scan(&sql).await?;
@@ -1390,6 +1500,7 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         T: DecodeRow<DatabaseRow, Error = Error>,
     {
         Self::before_query(query).await?;
+        query.check_cursor::<Self>()?;
 
         let join_conditions = joins
             .iter()
@@ -1401,13 +1512,15 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
             })
             .collect::<Vec<_>>()
             .join(" ");
+        let with_clause = cte::format_with_clause::<Self>(query);
         let table_name = query.format_table_name::<Self>();
         let projection = query.format_table_fields::<Self>();
         let filters = query.format_filters::<Self>();
-        let sort = query.format_sort();
+        let sort = query.format_sort::<Self>();
         let pagination = query.format_pagination();
         let sql = format!(
-            "SELECT {projection} FROM {table_name} {join_conditions} {filters} {sort} {pagination};"
+            "{with_clause}SELECT {projection} FROM {table_name} \
+                {join_conditions} {filters} {sort} {pagination};"
         );
         let mut ctx = Self::before_scan(&sql).await?;
         ctx.set_query(&sql);
@@ -1418,6 +1531,9 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         for row in rows {
             data.push(T::decode_row(&row)?);
         }
+        if query.cursor().is_some_and(|cursor| cursor.is_backward()) {
+            data.reverse();
+        }
         ctx.set_query_result(u64::try_from(data.len())?, true);
         Self::after_scan(&ctx).await?;
         Self::after_query(&ctx).await?;
@@ -1442,6 +1558,7 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
     /// Checks whether there is a model selected by the query in the table.
     async fn exists(query: &Query) -> Result<bool, Error> {
         Self::before_query(query).await?;
+        query.check_cursor::<Self>()?;
 
         let table_name = query.format_table_name::<Self>();
         let filters = query.format_filters::<Self>();
@@ -1461,19 +1578,35 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
     /// Counts the number of rows selected by the query in the table.
     async fn count(query: &Query) -> Result<u64, Error> {
         Self::before_count(query).await?;
+        query.check_cursor::<Self>()?;
 
+        let with_clause = cte::format_with_clause::<Self>(query);
         let table_name = query.format_table_name::<Self>();
         let filters = query.format_filters::<Self>();
-        let sql = format!("SELECT count(*) AS count FROM {table_name} {filters};");
+        let sql = format!("{with_clause}SELECT count(*) AS count FROM {table_name} {filters};");
         let mut ctx = Self::before_scan(&sql).await?;
         ctx.set_query(sql);
 
+        let table_name = Self::table_name();
+        let cache_key = Self::QUERY_CACHE_TTL.map(|_| cache::cache_key(&ctx));
+        if let Some(key) = cache_key.as_deref()
+            && let Some(count) = cache::get(table_name, key).and_then(|v| v.as_u64())
+        {
+            ctx.set_query_result(count, true);
+            Self::after_count(&ctx).await?;
+            return Ok(count);
+        }
+
+        let generation = cache::generation(table_name);
         let pool = Self::acquire_reader().await?.pool();
         let row = pool.fetch_one(ctx.query()).await?;
         let map = Map::decode_row(&row)?;
 
         // SQLite may return a string value for the count value.
         let count = map.parse_u64("count").transpose()?.unwrap_or_default();
+        if let Some((key, ttl)) = cache_key.zip(Self::QUERY_CACHE_TTL) {
+            cache::put(table_name, generation, key, count.into(), ttl);
+        }
         ctx.set_query_result(count, true);
         Self::after_scan(&ctx).await?;
         Self::after_count(&ctx).await?;
@@ -1495,7 +1628,7 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
             .iter()
             .map(|(col, distinct)| {
                 let col_name = col.as_ref();
-                let field = Query::format_field(col_name);
+                let field = Self::dialect().format_field(col_name);
                 if col_name != "*" {
                     if *distinct {
                         format!(r#"count(distinct {field}) AS {col_name}_distinct"#)
@@ -1538,12 +1671,15 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
     {
         Self::before_aggregate(query).await?;
 
+        let with_clause = cte::format_with_clause::<Self>(query);
         let table_name = query.format_table_name::<Self>();
         let projection = query.format_table_fields::<Self>();
         let filters = query.format_filters::<Self>();
-        let sort = query.format_sort();
+        let sort = query.format_sort::<Self>();
         let pagination = query.format_pagination();
-        let sql = format!("SELECT {projection} FROM {table_name} {filters} {sort} {pagination};");
+        let sql = format!(
+            "{with_clause}SELECT {projection} FROM {table_name} {filters} {sort} {pagination};"
+        );
         let mut ctx = Self::before_scan(&sql).await?;
         ctx.set_query(sql);
 
@@ -1568,7 +1704,7 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
 
     /// Executes the query in the table, and returns the total number of rows affected.
     async fn execute(query: &str, params: Option<&Map>) -> Result<QueryContext, Error> {
-        let (sql, values) = Query::prepare_query(query, params);
+        let (sql, values) = Query::prepare_query(Self::dialect(), query, params);
         let mut ctx = Self::before_scan(&sql).await?;
         ctx.set_query(sql);
         if cfg!(debug_assertions) && super::DEBUG_ONLY.load(Relaxed) {
@@ -1586,6 +1722,7 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         let query_result = pool.execute_with(ctx.query(), &arguments).await?;
         ctx.append_arguments(&mut arguments);
         ctx.set_query_result(query_result.rows_affected(), true);
+        QueryCache::invalidate::<Self>();
         Self::after_scan(&ctx).await?;
         Ok(ctx)
     }
@@ -1595,7 +1732,7 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
     where
         T: DecodeRow<DatabaseRow, Error = Error>,
     {
-        let (sql, values) = Query::prepare_query(query, params);
+        let (sql, values) = Query::prepare_query(Self::dialect(), query, params);
         let mut ctx = Self::before_scan(&sql).await?;
         ctx.set_query(sql);
 
@@ -1627,12 +1764,52 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         serde_json::from_value(data.into()).map_err(Error::from)
     }
 
+    /// Executes the query in the table, and decodes it as a stream of `T`.
+    ///
+    /// Since the stream is consumed lazily, the `after_scan` hook will not be invoked.
+    async fn query_stream<T>(
+        query: &str,
+        params: Option<&Map>,
+    ) -> Result<impl Stream<Item = Result<T, Error>> + use<Self, T>, Error>
+    where
+        T: DecodeRow<DatabaseRow, Error = Error>,
+    {
+        let (sql, values) = Query::prepare_query(Self::dialect(), query, params);
+        let mut ctx = Self::before_scan(&sql).await?;
+        ctx.set_query(sql);
+
+        let arguments = values
+            .iter()
+            .map(|v| v.to_string_unquoted())
+            .collect::<Vec<_>>();
+        let pool = Self::acquire_reader().await?.pool();
+        let stream = pool
+            .fetch_stream_with(ctx.query().to_owned(), arguments)
+            .map(|result| result.and_then(|row| T::decode_row(&row)));
+        Ok(stream)
+    }
+
+    /// Executes the query in the table, and parses it as a stream of `T`.
+    async fn query_stream_as<T: DeserializeOwned>(
+        query: &str,
+        params: Option<&Map>,
+    ) -> Result<impl Stream<Item = Result<T, Error>> + use<Self, T>, Error> {
+        let stream = Self::query_stream::<Map>(query, params)
+            .await?
+            .then(|result| async move {
+                let mut model = result?;
+                Self::after_decode(&mut model).await?;
+                serde_json::from_value(model.into()).map_err(Error::from)
+            });
+        Ok(stream)
+    }
+
     /// Executes the query in the table, and decodes it as an instance of type `T`.
     async fn query_one<T>(query: &str, params: Option<&Map>) -> Result<Option<T>, Error>
     where
         T: DecodeRow<DatabaseRow, Error = Error>,
     {
-        let (sql, values) = Query::prepare_query(query, params);
+        let (sql, values) = Query::prepare_query(Self::dialect(), query, params);
         let mut ctx = Self::before_scan(&sql).await?;
         ctx.set_query(sql);
 
@@ -1670,10 +1847,10 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
     /// Prepares the SQL to delete a model selected by the primary key in the table.
     async fn prepare_delete_by_id() -> Result<QueryContext, Error> {
         let primary_key_name = Self::primary_key_name();
-        let table_name = Query::escape_table_name(Self::table_name());
-        let placeholder = Query::placeholder(1);
-        let sql = if cfg!(feature = "orm-postgres") {
-            let type_annotation = Self::primary_key_column().type_annotation();
+        let table_name = Self::dialect().escape_table_name(Self::table_name());
+        let placeholder = Self::dialect().placeholder(1);
+        let sql = if Self::dialect() == Dialect::Postgres {
+            let type_annotation = Self::primary_key_column().type_annotation(Self::dialect());
             format!(
                 "DELETE FROM {table_name} \
                     WHERE {primary_key_name} = ({placeholder}){type_annotation};"
@@ -1702,6 +1879,7 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         let success = rows_affected == 1;
         ctx.add_argument(primary_key);
         ctx.set_query_result(rows_affected, success);
+        QueryCache::invalidate::<Self>();
         Self::after_scan(&ctx).await?;
         if success {
             Ok(ctx)
@@ -1716,20 +1894,16 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
     /// Prepares the SQL to update a model selected by the primary key in the table.
     async fn prepare_update_by_id(mutation: &mut Mutation) -> Result<QueryContext, Error> {
         let primary_key_name = Self::primary_key_name();
-        let table_name = Query::escape_table_name(Self::table_name());
+        let table_name = Self::dialect().escape_table_name(Self::table_name());
         let updates = mutation.format_updates::<Self>();
-        let placeholder = Query::placeholder(1);
-        let sql = if cfg!(any(
-            feature = "orm-mariadb",
-            feature = "orm-mysql",
-            feature = "orm-tidb"
-        )) {
+        let placeholder = Self::dialect().placeholder(1);
+        let sql = if Self::dialect().is_mysql_compatible() {
             format!(
                 "UPDATE {table_name} SET {updates} \
                     WHERE {primary_key_name} = {placeholder};"
             )
-        } else if cfg!(feature = "orm-postgres") {
-            let type_annotation = Self::primary_key_column().type_annotation();
+        } else if Self::dialect() == Dialect::Postgres {
+            let type_annotation = Self::primary_key_column().type_annotation(Self::dialect());
             format!(
                 "UPDATE {table_name} SET {updates} \
                     WHERE {primary_key_name} = ({placeholder}){type_annotation} RETURNING *;"
@@ -1763,20 +1937,14 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         }
 
         let pool = Self::acquire_writer().await?.pool();
-        let optional_row = if cfg!(any(
-            feature = "orm-mariadb",
-            feature = "orm-mysql",
-            feature = "orm-tidb"
-        )) {
-            use sqlx::Acquire;
-
+        let optional_row = if Self::dialect().is_mysql_compatible() {
             let mut transaction = pool.begin().await?;
-            let connection = transaction.acquire().await?;
+            let connection = &mut transaction;
             let query_result = connection.execute_with(ctx.query(), &[primary_key]).await?;
             let optional_row = if query_result.rows_affected() == 1 {
                 let primary_key_name = Self::primary_key_name();
-                let table_name = Query::escape_table_name(Self::table_name());
-                let placeholder = Query::placeholder(1);
+                let table_name = Self::dialect().escape_table_name(Self::table_name());
+                let placeholder = Self::dialect().placeholder(1);
                 let sql =
                     format!("SELECT * FROM {table_name} WHERE {primary_key_name} = {placeholder};");
                 connection.fetch_optional_with(&sql, &[primary_key]).await?
@@ -1796,6 +1964,7 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         };
         ctx.add_argument(primary_key);
         ctx.set_query_result(num_rows, true);
+        QueryCache::invalidate::<Self>();
         Self::after_scan(&ctx).await?;
         Self::after_query(&ctx).await?;
         Ok(data)
@@ -1810,10 +1979,10 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         let primary_key_name = Self::primary_key_name();
         let query = Self::default_query();
         let table_name = query.format_table_name::<Self>();
-        let projection = query.format_projection();
-        let placeholder = Query::placeholder(1);
-        let sql = if cfg!(feature = "orm-postgres") {
-            let type_annotation = Self::primary_key_column().type_annotation();
+        let projection = query.format_projection(Self::dialect());
+        let placeholder = Self::dialect().placeholder(1);
+        let sql = if Self::dialect() == Dialect::Postgres {
+            let type_annotation = Self::primary_key_column().type_annotation(Self::dialect());
             format!(
                 "SELECT {projection} FROM {table_name} \
                     WHERE {primary_key_name} = ({placeholder}){type_annotation};"
@@ -1825,7 +1994,19 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         };
         let mut ctx = Self::before_scan(&sql).await?;
         ctx.set_query(sql);
+        ctx.add_argument(primary_key);
 
+        let table_name = Self::table_name();
+        let cache_key = Self::QUERY_CACHE_TTL.map(|_| cache::cache_key(&ctx));
+        if let Some(key) = cache_key.as_deref()
+            && let Some(data) = cache::get(table_name, key).and_then(T::decode_cached)
+        {
+            ctx.set_query_result(1, true);
+            Self::after_query(&ctx).await?;
+            return Ok(Some(data));
+        }
+
+        let generation = cache::generation(table_name);
         let pool = Self::acquire_reader().await?.pool();
         let optional_row = pool
             .fetch_optional_with(ctx.query(), &[primary_key])
@@ -1835,7 +2016,11 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         } else {
             (0, None)
         };
-        ctx.add_argument(primary_key);
+        if let Some((key, ttl)) = cache_key.zip(Self::QUERY_CACHE_TTL)
+            && let Some(value) = data.as_ref().and_then(|data| data.encode_cached())
+        {
+            cache::put(table_name, generation, key, value, ttl);
+        }
         ctx.set_query_result(num_rows, true);
         Self::after_scan(&ctx).await?;
         Self::after_query(&ctx).await?;
@@ -1847,10 +2032,10 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         let primary_key_name = Self::primary_key_name();
         let query = Self::default_query();
         let table_name = query.format_table_name::<Self>();
-        let projection = query.format_projection();
-        let placeholder = Query::placeholder(1);
-        let sql = if cfg!(feature = "orm-postgres") {
-            let type_annotation = Self::primary_key_column().type_annotation();
+        let projection = query.format_projection(Self::dialect());
+        let placeholder = Self::dialect().placeholder(1);
+        let sql = if Self::dialect() == Dialect::Postgres {
+            let type_annotation = Self::primary_key_column().type_annotation(Self::dialect());
             format!(
                 "SELECT {projection} FROM {table_name} \
                     WHERE {primary_key_name} = ({placeholder}){type_annotation};"
@@ -1983,3 +2168,277 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         }
     }
 }
+
+/// Inserts the model into the table, inside of the transaction if it is provided.
+async fn insert_model<M: Schema>(
+    mut model: M,
+    mut tx: Option<&mut TransactionScope<'_>>,
+) -> Result<QueryContext, Error> {
+    let model_data = model.before_insert().await?;
+    let mut ctx = model.prepare_insert().await?;
+    if ctx.is_cancelled() {
+        return Ok(ctx);
+    }
+
+    let dialect = tx.as_deref().map_or_else(M::dialect, |tx| tx.dialect());
+    let (last_insert_id, rows_affected) = if dialect == Dialect::Postgres
+        && M::primary_key_column().auto_increment()
+    {
+        let row = if let Some(tx) = tx.as_deref_mut() {
+            tx.connection().fetch_one(ctx.query()).await?
+        } else {
+            M::acquire_writer()
+                .await?
+                .pool()
+                .fetch_one(ctx.query())
+                .await?
+        };
+        let primary_key = decode(&row, M::primary_key_name())?;
+        (Some(primary_key), 1)
+    } else {
+        let query_result = execute_scoped::<M, &str>(tx.as_deref_mut(), ctx.query(), &[]).await?;
+        (query_result.last_insert_id(), query_result.rows_affected())
+    };
+    let success = rows_affected == 1;
+    if let Some(last_insert_id) = last_insert_id {
+        ctx.set_last_insert_id(last_insert_id);
+    }
+    ctx.set_query_result(rows_affected, success);
+    invalidate_scoped::<M>(tx);
+    M::after_scan(&ctx).await?;
+    M::after_insert(&ctx, model_data).await?;
+    if success {
+        Ok(ctx)
+    } else {
+        bail!(
+            "{} rows are affected while it is expected to affect 1 row",
+            rows_affected
+        );
+    }
+}
+
+/// Updates the model in the table, inside of the transaction if it is provided.
+async fn update_model<M: Schema>(
+    mut model: M,
+    mut tx: Option<&mut TransactionScope<'_>>,
+) -> Result<QueryContext, Error> {
+    let model_data = model.before_update().await?;
+    let mut ctx = model.prepare_update().await?;
+    if ctx.is_cancelled() {
+        return Ok(ctx);
+    }
+
+    let query_result = execute_scoped::<M, &str>(tx.as_deref_mut(), ctx.query(), &[]).await?;
+    let rows_affected = query_result.rows_affected();
+    let success = rows_affected == 1;
+    ctx.set_query_result(rows_affected, success);
+    invalidate_scoped::<M>(tx);
+    M::after_scan(&ctx).await?;
+    M::after_update(&ctx, model_data).await?;
+    if success {
+        Ok(ctx)
+    } else {
+        bail!(
+            "{} rows are affected while it is expected to affect 1 row",
+            rows_affected
+        );
+    }
+}
+
+/// Updates at most one model selected by the query in the table,
+/// inside of the transaction if it is provided.
+async fn update_one_model<M: Schema>(
+    query: &Query,
+    mutation: &mut Mutation,
+    mut tx: Option<&mut TransactionScope<'_>>,
+) -> Result<QueryContext, Error> {
+    let mut ctx = M::prepare_update_one(query, mutation).await?;
+    if ctx.is_cancelled() {
+        return Ok(ctx);
+    }
+
+    let query_result = execute_scoped::<M, &str>(tx.as_deref_mut(), ctx.query(), &[]).await?;
+    let rows_affected = query_result.rows_affected();
+    let success = rows_affected <= 1;
+    ctx.set_query_result(rows_affected, success);
+    invalidate_scoped::<M>(tx);
+    M::after_scan(&ctx).await?;
+    M::after_mutation(&ctx).await?;
+    if success {
+        Ok(ctx)
+    } else {
+        bail!(
+            "{} rows are affected while it is expected to affect at most 1 row",
+            rows_affected
+        );
+    }
+}
+
+/// Deletes the model in the table, inside of the transaction if it is provided.
+async fn delete_model<M: Schema>(
+    mut model: M,
+    mut tx: Option<&mut TransactionScope<'_>>,
+) -> Result<QueryContext, Error> {
+    let model_data = model.before_delete().await?;
+    let mut ctx = model.prepare_delete().await?;
+    if ctx.is_cancelled() {
+        return Ok(ctx);
+    }
+
+    let primary_key = model.primary_key();
+    let query_result =
+        execute_scoped::<M, _>(tx.as_deref_mut(), ctx.query(), &[primary_key]).await?;
+    let rows_affected = query_result.rows_affected();
+    let success = rows_affected == 1;
+    ctx.add_argument(primary_key);
+    ctx.set_query_result(rows_affected, success);
+    invalidate_scoped::<M>(tx);
+    M::after_scan(&ctx).await?;
+    model.after_delete(&ctx, model_data).await?;
+    if success {
+        Ok(ctx)
+    } else {
+        bail!(
+            "{} rows are affected while it is expected to affect 1 row",
+            rows_affected
+        );
+    }
+}
+
+/// Finds a list of models selected by the query in the table,
+/// inside of the transaction if it is provided.
+async fn find_models<M, T>(
+    query: &Query,
+    tx: Option<&mut TransactionScope<'_>>,
+) -> Result<Vec<T>, Error>
+where
+    M: Schema,
+    T: DecodeRow<DatabaseRow, Error = Error>,
+{
+    let query = &*prepare_eager_loading::<M>(query)?;
+    M::before_query(query).await?;
+    query.check_cursor::<M>()?;
+
+    let with_clause = cte::format_with_clause::<M>(query);
+    let table_name = query.format_table_name::<M>();
+    let projection = query.format_table_fields::<M>();
+    let filters = query.format_filters::<M>();
+    let sort = query.format_sort::<M>();
+    let pagination = query.format_pagination();
+    let sql = format!(
+        "{with_clause}SELECT {projection} FROM {table_name} {filters} {sort} {pagination};"
+    );
+    let mut ctx = M::before_scan(&sql).await?;
+    ctx.set_query(&sql);
+
+    let rows = if let Some(tx) = tx {
+        tx.connection().fetch(ctx.query()).await?
+    } else {
+        M::acquire_reader().await?.pool().fetch(ctx.query()).await?
+    };
+    let mut data = Vec::with_capacity(rows.len());
+    for row in rows.iter() {
+        data.push(T::decode_row(row)?);
+    }
+    if query.extra().contains_key("with") {
+        let models = rows
+            .iter()
+            .map(Map::decode_row)
+            .collect::<Result<Vec<_>, _>>()?;
+        M::eager_load(query, &models, &mut data).await?;
+    }
+    if query.cursor().is_some_and(|cursor| cursor.is_backward()) {
+        // Restores the sort order since it has been reversed for a backward cursor
+        data.reverse();
+    }
+    ctx.set_query_result(u64::try_from(data.len())?, true);
+    M::after_scan(&ctx).await?;
+    M::after_query(&ctx).await?;
+    Ok(data)
+}
+
+/// Prepares the query for eager loading the relations specified by [`QueryBuilder::with`],
+/// where the local keys of the relations are added to the projection if they are omitted.
+fn prepare_eager_loading<M: Schema>(query: &Query) -> Result<Cow<'_, Query>, Error> {
+    let Some(names) = query.extra().parse_str_array("with") else {
+        return Ok(Cow::Borrowed(query));
+    };
+
+    let fields = query.fields();
+    let mut omitted_keys = Vec::new();
+    for name in names {
+        let Some(relation) = M::relations().iter().find(|r| r.name() == name) else {
+            bail!(
+                "the `{}` relation is not defined for the model `{}`",
+                name,
+                M::MODEL_NAME
+            );
+        };
+
+        let local_key = relation.local_key::<M>();
+        let projected = fields.is_empty()
+            || fields.iter().any(|field| {
+                field
+                    .rsplit_once('.')
+                    .map_or(field.as_str(), |(_, name)| name)
+                    == local_key
+            });
+        if !projected && !omitted_keys.contains(&local_key) {
+            omitted_keys.push(local_key);
+        }
+    }
+    if omitted_keys.is_empty() {
+        return Ok(Cow::Borrowed(query));
+    }
+
+    let mut query = query.clone();
+    let mut fields = fields.to_vec();
+    fields.extend(omitted_keys.into_iter().map(|key| key.to_owned()));
+    query.set_fields(fields);
+    Ok(Cow::Owned(query))
+}
+
+/// Decodes a batch of the rows as `Vec<T>`, and eager loads the relations for them.
+async fn decode_rows<M, T>(
+    query: &Query,
+    rows: Vec<Result<DatabaseRow, Error>>,
+) -> Result<Vec<T>, Error>
+where
+    M: Schema,
+    T: DecodeRow<DatabaseRow, Error = Error>,
+{
+    let mut models = Vec::with_capacity(rows.len());
+    let mut data = Vec::with_capacity(rows.len());
+    for row in rows {
+        let row = row?;
+        models.push(Map::decode_row(&row)?);
+        data.push(T::decode_row(&row)?);
+    }
+    M::eager_load(query, &models, &mut data).await?;
+    Ok(data)
+}
+
+/// Executes the query with arguments on the writer of the model,
+/// or inside of the transaction if it is provided.
+async fn execute_scoped<M: Schema, T: ToString>(
+    tx: Option<&mut TransactionScope<'_>>,
+    sql: &str,
+    arguments: &[T],
+) -> Result<QueryResult, Error> {
+    if let Some(tx) = tx {
+        tx.connection().execute_with(sql, arguments).await
+    } else {
+        let pool = M::acquire_writer().await?.pool();
+        pool.execute_with(sql, arguments).await
+    }
+}
+
+/// Invalidates the query cache of the model, which is deferred
+/// until the transaction has been committed if it is provided.
+fn invalidate_scoped<M: Schema>(tx: Option<&mut TransactionScope<'_>>) {
+    if let Some(tx) = tx {
+        tx.invalidate_on_commit::<M>();
+    } else {
+        QueryCache::invalidate::<M>();
+    }
+}
@@ -1,22 +1,26 @@
-use super::{DatabaseDriver, DatabaseRow, DecodeRow, EncodeColumn, Schema, query::QueryExt};
+use super::{
+    DatabaseRow, Dialect, Schema,
+    decode::decode_raw,
+    driver::{DriverExt, RowExt},
+    query::QueryExt,
+    value::parse_utc_timestamp,
+};
+use sqlx::{Column as _, Row, Sqlite, TypeInfo, ValueRef};
 use std::borrow::Cow;
 use zino_core::{
-    AvroValue, JsonValue, Map, Record, SharedString, Uuid,
+    AvroValue, JsonValue, Map, Record, Uuid,
     datetime::{Date, DateTime, Time},
     error::Error,
     extension::{JsonObjectExt, JsonValueExt},
-    model::{Column, Query, QueryOrder},
+    model::{Column, Query},
 };
 
-#[cfg(feature = "orm-sqlx")]
-use sqlx::{Column as _, Row, TypeInfo, ValueRef};
-
-impl EncodeColumn<DatabaseDriver> for Column<'_> {
-    fn column_type(&self) -> &str {
-        if let Some(column_type) = self.extra().get_str("column_type") {
+impl DriverExt for Sqlite {
+    fn column_type<'a>(col: &'a Column<'_>, _dialect: Dialect) -> &'a str {
+        if let Some(column_type) = col.extra().get_str("column_type") {
             return column_type;
         }
-        match self.type_name() {
+        match col.type_name() {
             "bool" => "BOOLEAN",
             "u64" | "i64" | "usize" | "isize" | "Option<u64>" | "Option<i64>" | "u32" | "i32"
             | "u16" | "i16" | "u8" | "i8" | "Option<u32>" | "Option<i32>" => "INTEGER",
@@ -29,7 +33,11 @@ impl EncodeColumn<DatabaseDriver> for Column<'_> {
         }
     }
 
-    fn encode_value<'a>(&self, value: Option<&'a JsonValue>) -> Cow<'a, str> {
+    fn encode_value<'a>(
+        col: &Column<'_>,
+        dialect: Dialect,
+        value: Option<&'a JsonValue>,
+    ) -> Cow<'a, str> {
         if let Some(value) = value {
             match value {
                 JsonValue::Null => "NULL".into(),
@@ -40,8 +48,8 @@ impl EncodeColumn<DatabaseDriver> for Column<'_> {
                 JsonValue::Number(n) => n.to_string().into(),
                 JsonValue::String(s) => {
                     if s.is_empty() {
-                        if let Some(value) = self.default_value() {
-                            self.format_value(value).into_owned().into()
+                        if let Some(value) = col.default_value() {
+                            Self::format_value(col, dialect, value).into_owned().into()
                         } else {
                             "''".into()
                         }
@@ -50,7 +58,7 @@ impl EncodeColumn<DatabaseDriver> for Column<'_> {
                     } else if s == "not_null" {
                         "NOT NULL".into()
                     } else {
-                        self.format_value(s)
+                        Self::format_value(col, dialect, s)
                     }
                 }
                 JsonValue::Array(vec) => {
@@ -58,22 +66,22 @@ impl EncodeColumn<DatabaseDriver> for Column<'_> {
                         .iter()
                         .map(|v| match v {
                             JsonValue::String(v) => Query::escape_string(v),
-                            _ => self.encode_value(Some(v)).into_owned(),
+                            _ => Self::encode_value(col, dialect, Some(v)).into_owned(),
                         })
                         .collect::<Vec<_>>();
                     format!(r#"json_array({})"#, values.join(",")).into()
                 }
                 JsonValue::Object(_) => Query::escape_string(value).into(),
             }
-        } else if self.default_value().is_some() {
+        } else if col.default_value().is_some() {
             "DEFAULT".into()
         } else {
             "NULL".into()
         }
     }
 
-    fn format_value<'a>(&self, value: &'a str) -> Cow<'a, str> {
-        match self.type_name() {
+    fn format_value<'a>(col: &Column<'_>, _dialect: Dialect, value: &'a str) -> Cow<'a, str> {
+        match col.type_name() {
             "bool" => {
                 let value = if value == "true" { "TRUE" } else { "FALSE" };
                 value.into()
@@ -99,7 +107,15 @@ impl EncodeColumn<DatabaseDriver> for Column<'_> {
                 "today" => "datetime('now', 'start of day')".into(),
                 "tomorrow" => "datetime('now', 'start of day', '+1 day')".into(),
                 "yesterday" => "datetime('now', 'start of day', '-1 day')".into(),
-                _ => Query::escape_string(value).into(),
+                _ => {
+                    if col.type_name() == "DateTime"
+                        && let Some(timestamp) = parse_utc_timestamp(value)
+                    {
+                        Query::escape_string(timestamp).into()
+                    } else {
+                        Query::escape_string(value).into()
+                    }
+                }
             },
             "Date" | "NaiveDate" => match value {
                 "epoch" => "'1970-01-01'".into(),
@@ -131,15 +147,15 @@ impl EncodeColumn<DatabaseDriver> for Column<'_> {
         }
     }
 
-    fn format_filter(&self, field: &str, value: &JsonValue) -> String {
-        let type_name = self.type_name();
-        let field = Query::format_field(field);
+    fn format_filter(col: &Column<'_>, dialect: Dialect, field: &str, value: &JsonValue) -> String {
+        let type_name = col.type_name();
+        let field = dialect.format_field(field);
         if let Some(filter) = value.as_object() {
             let mut conditions = Vec::with_capacity(filter.len());
             if type_name == "Map" {
                 for (key, value) in filter {
                     let key = Query::escape_string(key);
-                    let value = self.encode_value(Some(value));
+                    let value = Self::encode_value(col, dialect, Some(value));
                     let condition =
                         format!(r#"json_tree.key = {key} AND json_tree.value = {value}"#);
                     conditions.push(condition);
@@ -181,7 +197,7 @@ impl EncodeColumn<DatabaseDriver> for Column<'_> {
                             } else {
                                 let value = values
                                     .iter()
-                                    .map(|v| self.encode_value(Some(v)))
+                                    .map(|v| Self::encode_value(col, dialect, Some(v)))
                                     .collect::<Vec<_>>()
                                     .join(", ");
                                 let condition = format!(r#"{field} {operator} ({value})"#);
@@ -191,8 +207,8 @@ impl EncodeColumn<DatabaseDriver> for Column<'_> {
                     } else if operator == "BETWEEN" {
                         if let Some(values) = value.as_array() {
                             if let [min_value, max_value] = values.as_slice() {
-                                let min_value = self.encode_value(Some(min_value));
-                                let max_value = self.encode_value(Some(max_value));
+                                let min_value = Self::encode_value(col, dialect, Some(min_value));
+                                let max_value = Self::encode_value(col, dialect, Some(max_value));
                                 let condition =
                                     format!(r#"({field} BETWEEN {min_value} AND {max_value})"#);
                                 conditions.push(condition);
@@ -200,14 +216,14 @@ impl EncodeColumn<DatabaseDriver> for Column<'_> {
                         } else if let Some(values) = value.parse_str_array()
                             && let [min_value, max_value] = values.as_slice()
                         {
-                            let min_value = self.format_value(min_value);
-                            let max_value = self.format_value(max_value);
+                            let min_value = Self::format_value(col, dialect, min_value);
+                            let max_value = Self::format_value(col, dialect, max_value);
                             let condition =
                                 format!(r#"({field} BETWEEN {min_value} AND {max_value})"#);
                             conditions.push(condition);
                         }
                     } else if operator == "ILIKE" {
-                        let value = self.encode_value(Some(value));
+                        let value = Self::encode_value(col, dialect, Some(value));
                         let condition = format!(r#"LOWER({field}) LIKE LOWER({value})"#);
                         conditions.push(condition);
                     } else if operator == "json_array_length" {
@@ -216,7 +232,7 @@ impl EncodeColumn<DatabaseDriver> for Column<'_> {
                             conditions.push(condition);
                         }
                     } else {
-                        let value = self.encode_value(Some(value));
+                        let value = Self::encode_value(col, dialect, Some(value));
                         let condition = format!(r#"{field} {operator} {value}"#);
                         conditions.push(condition);
                     }
@@ -229,8 +245,8 @@ impl EncodeColumn<DatabaseDriver> for Column<'_> {
             }
         } else if value.is_null() {
             return format!(r#"{field} IS NULL"#);
-        } else if self.has_attribute("exact_filter") {
-            let value = self.encode_value(Some(value));
+        } else if col.has_attribute("exact_filter") {
+            let value = Self::encode_value(col, dialect, Some(value));
             return format!(r#"{field} = {value}"#);
         } else if let Some(value) = value.as_str() {
             if value == "null" {
@@ -238,17 +254,17 @@ impl EncodeColumn<DatabaseDriver> for Column<'_> {
             } else if value == "not_null" {
                 return format!(r#"{field} IS NOT NULL"#);
             } else if let Some((min_value, max_value)) =
-                value.split_once(',').filter(|_| self.is_temporal_type())
+                value.split_once(',').filter(|_| col.is_temporal_type())
             {
-                let min_value = self.format_value(min_value);
-                let max_value = self.format_value(max_value);
+                let min_value = Self::format_value(col, dialect, min_value);
+                let max_value = Self::format_value(col, dialect, max_value);
                 return format!(r#"{field} >= {min_value} AND {field} < {max_value}"#);
             }
         }
 
         match type_name {
             "bool" => {
-                let value = self.encode_value(Some(value));
+                let value = Self::encode_value(col, dialect, Some(value));
                 if value == "TRUE" {
                     format!(r#"{field} IS TRUE"#)
                 } else {
@@ -264,11 +280,11 @@ impl EncodeColumn<DatabaseDriver> for Column<'_> {
                         let value = value.split(',').collect::<Vec<_>>().join(",");
                         format!(r#"{field} IN ({value})"#)
                     } else {
-                        let value = self.format_value(value);
+                        let value = Self::format_value(col, dialect, value);
                         format!(r#"{field} = {value}"#)
                     }
                 } else {
-                    let value = self.encode_value(Some(value));
+                    let value = Self::encode_value(col, dialect, Some(value));
                     format!(r#"{field} = {value}"#)
                 }
             }
@@ -279,7 +295,7 @@ impl EncodeColumn<DatabaseDriver> for Column<'_> {
                         format!(r#"({field} = '') IS NOT FALSE"#)
                     } else if value == "nonempty" {
                         format!(r#"({field} = '') IS FALSE"#)
-                    } else if self.fuzzy_search() {
+                    } else if col.fuzzy_search() {
                         if value.contains(',') {
                             let exprs = value
                                 .split(',')
@@ -305,14 +321,14 @@ impl EncodeColumn<DatabaseDriver> for Column<'_> {
                         format!(r#"{field} = {value}"#)
                     }
                 } else {
-                    let value = self.encode_value(Some(value));
+                    let value = Self::encode_value(col, dialect, Some(value));
                     format!(r#"{field} = {value}"#)
                 }
             }
             "DateTime" | "NaiveDateTime" => {
                 if let Some(value) = value.as_str() {
                     let length = value.len();
-                    let value = self.format_value(value);
+                    let value = Self::format_value(col, dialect, value);
                     match length {
                         4 => format!(r#"strftime('%Y', {field}) = {value}"#),
                         7 => format!(r#"strftime('%Y-%m', {field}) = {value}"#),
@@ -320,28 +336,28 @@ impl EncodeColumn<DatabaseDriver> for Column<'_> {
                         _ => format!(r#"{field} = {value}"#),
                     }
                 } else {
-                    let value = self.encode_value(Some(value));
+                    let value = Self::encode_value(col, dialect, Some(value));
                     format!(r#"{field} = {value}"#)
                 }
             }
             "Date" | "NaiveDate" => {
                 if let Some(value) = value.as_str() {
                     let length = value.len();
-                    let value = self.format_value(value);
+                    let value = Self::format_value(col, dialect, value);
                     match length {
                         4 => format!(r#"strftime('%Y', {field}) = {value}"#),
                         7 => format!(r#"strftime('%Y-%m', {field}) = {value}"#),
                         _ => format!(r#"{field} = {value}"#),
                     }
                 } else {
-                    let value = self.encode_value(Some(value));
+                    let value = Self::encode_value(col, dialect, Some(value));
                     format!(r#"{field} = {value}"#)
                 }
             }
             "Time" | "NaiveTime" => {
                 if let Some(value) = value.as_str() {
                     let length = value.len();
-                    let value = self.format_value(value);
+                    let value = Self::format_value(col, dialect, value);
                     match length {
                         2 => format!(r#"strftime('%H', {field}) = {value}"#),
                         5 => format!(r#"strftime('%H:%M', {field}) = {value}"#),
@@ -349,7 +365,7 @@ impl EncodeColumn<DatabaseDriver> for Column<'_> {
                         _ => format!(r#"{field} = {value}"#),
                     }
                 } else {
-                    let value = self.encode_value(Some(value));
+                    let value = Self::encode_value(col, dialect, Some(value));
                     format!(r#"{field} = {value}"#)
                 }
             }
@@ -367,7 +383,7 @@ impl EncodeColumn<DatabaseDriver> for Column<'_> {
                         format!(r#"{field} = {value}"#)
                     }
                 } else {
-                    let value = self.encode_value(Some(value));
+                    let value = Self::encode_value(col, dialect, Some(value));
                     format!(r#"{field} = {value}"#)
                 }
             }
@@ -390,39 +406,103 @@ impl EncodeColumn<DatabaseDriver> for Column<'_> {
                     let exprs = values
                         .iter()
                         .map(|v| {
-                            let value = self.encode_value(Some(v));
+                            let value = Self::encode_value(col, dialect, Some(v));
                             format!(r#"json_each.value = {value}"#)
                         })
                         .collect::<Vec<_>>();
                     format!("({})", exprs.join(" OR "))
                 } else {
-                    let value = self.encode_value(Some(value));
+                    let value = Self::encode_value(col, dialect, Some(value));
                     format!(r#"{field} = {value}"#)
                 }
             }
             _ => {
-                let value = self.encode_value(Some(value));
+                let value = Self::encode_value(col, dialect, Some(value));
                 format!(r#"{field} = {value}"#)
             }
         }
     }
+
+    fn format_table_name<M: Schema>(query: &Query) -> String {
+        let dialect = M::dialect();
+        let table_name = query
+            .query_extra()
+            .get_str("table_name")
+            .unwrap_or_else(|| M::table_name());
+        let table_name = dialect.escape_table_name(table_name);
+        let model_name = M::model_name();
+        let filters = query.query_filters();
+        let mut virtual_tables = vec![format!(
+            "{table_name} AS {}",
+            dialect.format_field(model_name)
+        )];
+        for col in M::columns() {
+            let col_name = col.name();
+            if filters.contains_key(col_name) {
+                let field = dialect
+                    .format_field(&[model_name, ".", col_name].concat())
+                    .into_owned();
+                match col.type_name() {
+                    "Vec<String>" | "Vec<Uuid>" | "Vec<u64>" | "Vec<i64>" | "Vec<u32>"
+                    | "Vec<i32>" => {
+                        virtual_tables.push(format!("json_each({field})"));
+                    }
+                    "Map" => {
+                        virtual_tables.push(format!("json_tree({field})"));
+                    }
+                    _ => (),
+                }
+            }
+        }
+        virtual_tables.join(", ")
+    }
+
+    fn parse_text_search<M: Schema>(filter: &Map) -> Option<String> {
+        let (fts_table, search) = parse_text_search_match::<M>(filter)?;
+        Some(format!(
+            "rowid IN (SELECT rowid FROM {fts_table} WHERE {fts_table} MATCH {search})"
+        ))
+    }
+
+    fn format_text_search_rank<M: Schema>(filter: &Map) -> Option<String> {
+        let (fts_table, search) = parse_text_search_match::<M>(filter)?;
+        let model_name = M::model_name();
+        Some(format!(
+            "(SELECT -rank FROM {fts_table} \
+                WHERE {fts_table} MATCH {search} AND rowid = `{model_name}`.rowid)"
+        ))
+    }
+
+    fn format_text_search_highlight<M: Schema>(filter: &Map, field: &str) -> Option<String> {
+        let (fts_table, search) = parse_text_search_match::<M>(filter)?;
+        let column_name = field.rsplit_once('.').map_or(field, |(_, name)| name);
+        let column_index = M::columns()
+            .iter()
+            .filter(|col| col.has_fulltext_index())
+            .position(|col| col.name() == column_name)?;
+        let model_name = M::model_name();
+        Some(format!(
+            "(SELECT snippet({fts_table}, {column_index}, '<mark>', '</mark>', '...', 32) \
+                FROM {fts_table} WHERE {fts_table} MATCH {search} AND rowid = `{model_name}`.rowid)"
+        ))
+    }
 }
 
-#[cfg(feature = "orm-sqlx")]
-impl DecodeRow<DatabaseRow> for Map {
-    type Error = Error;
+impl RowExt for sqlx::sqlite::SqliteRow {
+    #[inline]
+    fn into_database_row(self, _dialect: Dialect) -> DatabaseRow {
+        DatabaseRow::Sqlite(self)
+    }
 
-    fn decode_row(row: &DatabaseRow) -> Result<Self, Self::Error> {
+    fn decode_map(&self, _dialect: Dialect) -> Result<Map, Error> {
         let mut map = Map::new();
-        for col in row.columns() {
+        for col in self.columns() {
             let field = col.name();
             let index = col.ordinal();
-            let raw_value = row.try_get_raw(index)?;
+            let raw_value = self.try_get_raw(index)?;
             let value = if raw_value.is_null() {
                 JsonValue::Null
             } else {
-                use super::decode::decode_raw;
-
                 let type_info = col.type_info();
                 let value_type_info = raw_value.type_info();
                 let column_type = if type_info.is_null() {
@@ -431,11 +511,11 @@ impl DecodeRow<DatabaseRow> for Map {
                     type_info.name()
                 };
                 match column_type {
-                    "BOOLEAN" => decode_raw::<bool>(field, raw_value)?.into(),
-                    "INTEGER" => decode_raw::<i64>(field, raw_value)?.into(),
-                    "REAL" => decode_raw::<f64>(field, raw_value)?.into(),
+                    "BOOLEAN" => decode_raw::<bool, Sqlite>(field, raw_value)?.into(),
+                    "INTEGER" => decode_raw::<i64, Sqlite>(field, raw_value)?.into(),
+                    "REAL" => decode_raw::<f64, Sqlite>(field, raw_value)?.into(),
                     "TEXT" => {
-                        let value = decode_raw::<String>(field, raw_value)?;
+                        let value = decode_raw::<String, Sqlite>(field, raw_value)?;
                         if value.starts_with('[') && value.ends_with(']')
                             || value.starts_with('{') && value.ends_with('}')
                         {
@@ -444,11 +524,11 @@ impl DecodeRow<DatabaseRow> for Map {
                             value.into()
                         }
                     }
-                    "DATETIME" => decode_raw::<DateTime>(field, raw_value)?.into(),
-                    "DATE" => decode_raw::<Date>(field, raw_value)?.into(),
-                    "TIME" => decode_raw::<Time>(field, raw_value)?.into(),
+                    "DATETIME" => decode_raw::<DateTime, Sqlite>(field, raw_value)?.into(),
+                    "DATE" => decode_raw::<Date, Sqlite>(field, raw_value)?.into(),
+                    "TIME" => decode_raw::<Time, Sqlite>(field, raw_value)?.into(),
                     "BLOB" => {
-                        let bytes = decode_raw::<Vec<u8>>(field, raw_value)?;
+                        let bytes = decode_raw::<Vec<u8>, Sqlite>(field, raw_value)?;
                         if bytes.starts_with(b"[") && bytes.ends_with(b"]")
                             || bytes.starts_with(b"{") && bytes.ends_with(b"}")
                         {
@@ -464,7 +544,7 @@ impl DecodeRow<DatabaseRow> for Map {
                             bytes.into()
                         }
                     }
-                    _ => decode_raw::<String>(field, raw_value)?.into(),
+                    _ => decode_raw::<String, Sqlite>(field, raw_value)?.into(),
                 }
             };
             if !value.is_ignorable() {
@@ -473,24 +553,17 @@ impl DecodeRow<DatabaseRow> for Map {
         }
         Ok(map)
     }
-}
-
-#[cfg(feature = "orm-sqlx")]
-impl DecodeRow<DatabaseRow> for Record {
-    type Error = Error;
 
-    fn decode_row(row: &DatabaseRow) -> Result<Self, Self::Error> {
-        let columns = row.columns();
+    fn decode_record(&self, _dialect: Dialect) -> Result<Record, Error> {
+        let columns = self.columns();
         let mut record = Record::with_capacity(columns.len());
         for col in columns {
             let field = col.name();
             let index = col.ordinal();
-            let raw_value = row.try_get_raw(index)?;
+            let raw_value = self.try_get_raw(index)?;
             let value = if raw_value.is_null() {
                 AvroValue::Null
             } else {
-                use super::decode::decode_raw;
-
                 let type_info = col.type_info();
                 let value_type_info = raw_value.type_info();
                 let column_type = if type_info.is_null() {
@@ -499,11 +572,11 @@ impl DecodeRow<DatabaseRow> for Record {
                     type_info.name()
                 };
                 match column_type {
-                    "BOOLEAN" => decode_raw::<bool>(field, raw_value)?.into(),
-                    "INTEGER" => decode_raw::<i64>(field, raw_value)?.into(),
-                    "REAL" => decode_raw::<f64>(field, raw_value)?.into(),
+                    "BOOLEAN" => decode_raw::<bool, Sqlite>(field, raw_value)?.into(),
+                    "INTEGER" => decode_raw::<i64, Sqlite>(field, raw_value)?.into(),
+                    "REAL" => decode_raw::<f64, Sqlite>(field, raw_value)?.into(),
                     "TEXT" => {
-                        let value = decode_raw::<String>(field, raw_value)?;
+                        let value = decode_raw::<String, Sqlite>(field, raw_value)?;
                         if value.starts_with('[') && value.ends_with(']')
                             || value.starts_with('{') && value.ends_with('}')
                         {
@@ -512,11 +585,13 @@ impl DecodeRow<DatabaseRow> for Record {
                             value.into()
                         }
                     }
-                    "DATETIME" => decode_raw::<DateTime>(field, raw_value)?.to_string().into(),
-                    "DATE" => decode_raw::<Date>(field, raw_value)?.into(),
-                    "TIME" => decode_raw::<Time>(field, raw_value)?.into(),
+                    "DATETIME" => decode_raw::<DateTime, Sqlite>(field, raw_value)?
+                        .to_string()
+                        .into(),
+                    "DATE" => decode_raw::<Date, Sqlite>(field, raw_value)?.into(),
+                    "TIME" => decode_raw::<Time, Sqlite>(field, raw_value)?.into(),
                     "BLOB" => {
-                        let bytes = decode_raw::<Vec<u8>>(field, raw_value)?;
+                        let bytes = decode_raw::<Vec<u8>, Sqlite>(field, raw_value)?;
                         if bytes.starts_with(b"[") && bytes.ends_with(b"]")
                             || bytes.starts_with(b"{") && bytes.ends_with(b"}")
                         {
@@ -533,7 +608,7 @@ impl DecodeRow<DatabaseRow> for Record {
                             bytes.into()
                         }
                     }
-                    _ => decode_raw::<String>(field, raw_value)?.into(),
+                    _ => decode_raw::<String, Sqlite>(field, raw_value)?.into(),
                 }
             };
             record.push((field.to_owned(), value));
@@ -542,163 +617,33 @@ impl DecodeRow<DatabaseRow> for Record {
     }
 }
 
-#[cfg(feature = "orm-sqlx")]
-impl QueryExt<DatabaseDriver> for Query {
-    type QueryResult = sqlx::sqlite::SqliteQueryResult;
-
-    #[inline]
-    fn parse_query_result(query_result: Self::QueryResult) -> (Option<i64>, u64) {
-        let last_insert_id = query_result.last_insert_rowid();
-        let rows_affected = query_result.rows_affected();
-        (Some(last_insert_id), rows_affected)
-    }
-
-    #[inline]
-    fn query_fields(&self) -> &[String] {
-        self.fields()
-    }
-
-    #[inline]
-    fn query_filters(&self) -> &Map {
-        self.filters()
+/// Parses the FTS5 table and the full-text query of the text search filter.
+fn parse_text_search_match<M: Schema>(filter: &Map) -> Option<(String, String)> {
+    let search = filter.parse_string("$search")?;
+    let terms = search
+        .split_whitespace()
+        .map(|term| ["\"", &term.replace('"', "\"\""), "\""].concat())
+        .collect::<Vec<_>>();
+    if terms.is_empty() {
+        return None;
     }
 
-    #[inline]
-    fn query_order(&self) -> &[QueryOrder] {
-        self.sort_order()
-    }
+    let terms = terms.join(" ");
+    let full_text_query = if let Some(fields) = filter.parse_str_array("$fields") {
+        let columns = fields
+            .into_iter()
+            .map(|field| field.rsplit_once('.').map_or(field, |(_, name)| name))
+            .collect::<Vec<_>>()
+            .join(" ");
+        format!("{{{columns}}} : ({terms})")
+    } else {
+        terms
+    };
 
-    #[inline]
-    fn query_offset(&self) -> usize {
-        self.offset()
-    }
-
-    #[inline]
-    fn query_limit(&self) -> usize {
-        self.limit()
-    }
-
-    #[inline]
-    fn placeholder(_n: usize) -> SharedString {
-        "?".into()
-    }
-
-    #[inline]
-    fn prepare_query<'a>(
-        query: &'a str,
-        params: Option<&'a Map>,
-    ) -> (Cow<'a, str>, Vec<&'a JsonValue>) {
-        crate::query::prepare_sql_query(query, params, '?')
-    }
-
-    fn format_field(field: &str) -> Cow<'_, str> {
-        if field.contains('`') {
-            field.into()
-        } else if field.contains('.') {
-            field
-                .split('.')
-                .map(|s| ["`", s, "`"].concat())
-                .collect::<Vec<_>>()
-                .join(".")
-                .into()
-        } else {
-            ["`", field, "`"].concat().into()
-        }
-    }
-
-    fn format_table_fields<M: Schema>(&self) -> Cow<'_, str> {
-        let model_name = M::model_name();
-        let fields = self.query_fields();
-        if fields.is_empty() {
-            "*".into()
-        } else {
-            fields
-                .iter()
-                .map(|field| {
-                    if let Some((alias, expr)) = field.split_once(':') {
-                        let alias = Self::format_field(alias.trim());
-                        format!(r#"{expr} AS {alias}"#)
-                    } else if field.contains('"') {
-                        field.into()
-                    } else if field.contains('.') {
-                        field
-                            .split('.')
-                            .map(|s| ["`", s, "`"].concat())
-                            .collect::<Vec<_>>()
-                            .join(".")
-                    } else {
-                        format!(r#"`{model_name}`.`{field}`"#)
-                    }
-                })
-                .collect::<Vec<_>>()
-                .join(", ")
-                .into()
-        }
-    }
-
-    fn format_table_name<M: Schema>(&self) -> String {
-        let table_name = self
-            .extra()
-            .get_str("table_name")
-            .unwrap_or_else(|| M::table_name());
-        let model_name = M::model_name();
-        let filters = self.query_filters();
-        let mut virtual_tables = Vec::new();
-        for col in M::columns() {
-            let col_name = col.name();
-            if filters.contains_key(col_name) {
-                match col.type_name() {
-                    "Vec<String>" | "Vec<Uuid>" | "Vec<u64>" | "Vec<i64>" | "Vec<u32>"
-                    | "Vec<i32>" => {
-                        let virtual_table = format!("json_each(`{model_name}`.`{col_name}`)");
-                        virtual_tables.push(virtual_table);
-                    }
-                    "Map" => {
-                        let virtual_table = format!("json_tree(`{model_name}`.`{col_name}`)");
-                        virtual_tables.push(virtual_table);
-                    }
-                    _ => (),
-                }
-            }
-        }
-
-        let table_name = if table_name.contains('.') {
-            table_name
-                .split('.')
-                .map(|s| ["`", s, "`"].concat())
-                .collect::<Vec<_>>()
-                .join(".")
-        } else {
-            ["`", table_name, "`"].concat()
-        };
-        if virtual_tables.is_empty() {
-            format!(r#"{table_name} AS `{model_name}`"#)
-        } else {
-            format!(
-                r#"{table_name} AS `{model_name}`, {}"#,
-                virtual_tables.join(", ")
-            )
-        }
-    }
-
-    fn escape_table_name(table_name: &str) -> String {
-        if table_name.contains('.') {
-            table_name
-                .split('.')
-                .map(|s| ["`", s, "`"].concat())
-                .collect::<Vec<_>>()
-                .join(".")
-        } else {
-            ["`", table_name, "`"].concat()
-        }
-    }
-
-    fn parse_text_search(filter: &Map) -> Option<String> {
-        let fields = filter.parse_str_array("$fields")?;
-        filter.parse_string("$search").map(|search| {
-            let fields = fields.join(", ");
-            let search = Query::escape_string(search.as_ref());
-            format!("{fields} MATCH {search}")
-        })
-    }
+    let table_name = M::table_name();
+    let table_name = table_name
+        .rsplit_once('.')
+        .map_or(table_name, |(_, name)| name);
+    let fts_table = format!("`{table_name}_fts`");
+    Some((fts_table, Query::escape_string(full_text_query)))
 }
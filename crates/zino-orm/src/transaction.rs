@@ -1,18 +1,15 @@
 use super::{
-    DatabaseDriver, EncodeColumn, executor::Executor, mutation::MutationExt, query::QueryExt,
-    schema::Schema,
+    ConnectionPool, DatabaseTransaction, Dialect, EncodeColumn, QueryCache, executor::Executor,
+    mutation::MutationExt, query::QueryExt, schema::Schema,
 };
 use std::fmt::Display;
 use zino_core::{
-    BoxFuture, Map,
+    BoxFuture, Map, bail,
     error::Error,
     extension::JsonValueExt,
     model::{Mutation, Query},
 };
 
-#[cfg(feature = "orm-sqlx")]
-use sqlx::Acquire;
-
 /// An in-progress database transaction.
 ///
 /// # Examples
@@ -61,6 +58,10 @@ where
     /// Executes the specific operations inside of a transaction.
     /// If the operations return an error, the transaction will be rolled back;
     /// if not, the transaction will be committed.
+    ///
+    /// Only the query cache of the model is invalidated after the commit.
+    /// The other models written inside of the transaction should be invalidated
+    /// via [`QueryCache::invalidate`] explicitly.
     async fn transaction<F, T>(tx: F) -> Result<T, Error>
     where
         F: for<'t> FnOnce(&'t mut Tx) -> BoxFuture<'t, Result<T, Error>>;
@@ -84,30 +85,31 @@ where
 }
 
 #[cfg(feature = "orm-sqlx")]
-impl<'c, M, K> Transaction<K, sqlx::Transaction<'c, DatabaseDriver>> for M
+impl<M, K> Transaction<K, DatabaseTransaction> for M
 where
     M: Schema<PrimaryKey = K>,
     K: Default + Display + PartialEq,
 {
     async fn transaction<F, T>(tx: F) -> Result<T, Error>
     where
-        F: for<'t> FnOnce(
-            &'t mut sqlx::Transaction<'c, DatabaseDriver>,
-        ) -> BoxFuture<'t, Result<T, Error>>,
+        F: for<'t> FnOnce(&'t mut DatabaseTransaction) -> BoxFuture<'t, Result<T, Error>>,
     {
         let mut transaction = Self::acquire_writer().await?.pool().begin().await?;
         let data = tx(&mut transaction).await?;
         transaction.commit().await?;
+        QueryCache::invalidate::<Self>();
         Ok(data)
     }
 
     async fn transactional_execute(queries: &[&str], params: Option<&Map>) -> Result<u64, Error> {
-        let mut transaction = Self::acquire_writer().await?.pool().begin().await?;
-        let connection = transaction.acquire().await?;
+        let connection_pool = Self::acquire_writer().await?;
+        let dialect = connection_pool.dialect();
+        let mut transaction = connection_pool.pool().begin().await?;
+        let connection = &mut transaction;
 
         let mut total_rows = 0;
         for query in queries {
-            let (sql, values) = Query::prepare_query(query, params);
+            let (sql, values) = Query::prepare_query(dialect, query, params);
             let mut ctx = Self::before_scan(&sql).await?;
             ctx.set_query(sql);
 
@@ -125,19 +127,22 @@ where
             Self::after_scan(&ctx).await?;
         }
         transaction.commit().await?;
+        QueryCache::invalidate::<Self>();
         Ok(total_rows)
     }
 
     async fn transactional_insert<S: Schema>(mut self, associations: Vec<S>) -> Result<u64, Error> {
-        let mut transaction = Self::acquire_writer().await?.pool().begin().await?;
-        let connection = transaction.acquire().await?;
+        let connection_pool = Self::acquire_writer().await?;
+        let dialect = connection_pool.dialect();
+        let mut transaction = connection_pool.pool().begin().await?;
+        let connection = &mut transaction;
 
         // Inserts the model
         let model_data = self.before_insert().await?;
         let table_name = if let Some(table) = self.before_prepare().await? {
-            Query::escape_table_name(&table)
+            dialect.escape_table_name(&table)
         } else {
-            Query::escape_table_name(Self::table_name())
+            dialect.escape_table_name(Self::table_name())
         };
         let map = self.into_map();
         let columns = Self::columns();
@@ -150,8 +155,8 @@ where
                     None
                 } else {
                     let name = col.name();
-                    fields.push(name);
-                    Some(col.encode_value(map.get(name)))
+                    fields.push(dialect.format_field(name));
+                    Some(col.encode_value(dialect, map.get(name)))
                 }
             })
             .collect::<Vec<_>>()
@@ -163,7 +168,8 @@ where
 
         let mut total_rows = 0;
         let query_result = connection.execute(ctx.query()).await?;
-        let (last_insert_id, rows_affected) = Query::parse_query_result(query_result);
+        let last_insert_id = query_result.last_insert_id();
+        let rows_affected = query_result.rows_affected();
         let success = rows_affected == 1;
         if let Some(last_insert_id) = last_insert_id {
             ctx.set_last_insert_id(last_insert_id);
@@ -181,14 +187,18 @@ where
             let map = association.into_map();
             let entries = columns
                 .iter()
-                .map(|col| col.encode_value(map.get(col.name())))
+                .map(|col| col.encode_value(dialect, map.get(col.name())))
                 .collect::<Vec<_>>()
                 .join(", ");
             values.push(format!("({entries})"));
         }
 
-        let table_name = Query::escape_table_name(S::table_name());
-        let fields = S::fields().join(", ");
+        let table_name = dialect.escape_table_name(S::table_name());
+        let fields = S::fields()
+            .iter()
+            .map(|field| dialect.format_field(field))
+            .collect::<Vec<_>>()
+            .join(", ");
         let values = values.join(", ");
         let sql = format!("INSERT INTO {table_name} ({fields}) VALUES {values};");
         let mut ctx = S::before_scan(&sql).await?;
@@ -201,6 +211,8 @@ where
 
         // Commits the transaction
         transaction.commit().await?;
+        QueryCache::invalidate::<Self>();
+        QueryCache::invalidate::<S>();
         Ok(total_rows)
     }
 
@@ -209,7 +221,7 @@ where
         mutations: (&mut Mutation, &mut Mutation),
     ) -> Result<u64, Error> {
         let mut transaction = Self::acquire_writer().await?.pool().begin().await?;
-        let connection = transaction.acquire().await?;
+        let connection = &mut transaction;
 
         let query = queries.0;
         let mutation = mutations.0;
@@ -248,12 +260,14 @@ where
 
         // Commits the transaction
         transaction.commit().await?;
+        QueryCache::invalidate::<Self>();
+        QueryCache::invalidate::<S>();
         Ok(total_rows)
     }
 
     async fn transactional_delete<S: Schema>(queries: (&Query, &Query)) -> Result<u64, Error> {
         let mut transaction = Self::acquire_writer().await?.pool().begin().await?;
-        let connection = transaction.acquire().await?;
+        let connection = &mut transaction;
 
         let query = queries.0;
         Self::before_query(query).await?;
@@ -288,6 +302,477 @@ where
 
         // Commits the transaction
         transaction.commit().await?;
+        QueryCache::invalidate::<Self>();
+        QueryCache::invalidate::<S>();
         Ok(total_rows)
     }
 }
+
+/// Isolation levels of a transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IsolationLevel {
+    /// Read uncommitted.
+    ReadUncommitted,
+    /// Read committed.
+    ReadCommitted,
+    /// Repeatable read.
+    RepeatableRead,
+    /// Serializable.
+    Serializable,
+}
+
+impl IsolationLevel {
+    /// Parses the isolation level.
+    pub fn parse(level: &str) -> Option<Self> {
+        match level {
+            "read-uncommitted" | "read_uncommitted" => Some(Self::ReadUncommitted),
+            "read-committed" | "read_committed" => Some(Self::ReadCommitted),
+            "repeatable-read" | "repeatable_read" => Some(Self::RepeatableRead),
+            "serializable" => Some(Self::Serializable),
+            _ => None,
+        }
+    }
+
+    /// Returns the SQL representation.
+    #[inline]
+    pub fn as_sql(&self) -> &'static str {
+        match self {
+            Self::ReadUncommitted => "READ UNCOMMITTED",
+            Self::ReadCommitted => "READ COMMITTED",
+            Self::RepeatableRead => "REPEATABLE READ",
+            Self::Serializable => "SERIALIZABLE",
+        }
+    }
+}
+
+/// Options to begin a transaction.
+///
+/// The isolation level and the read-only access mode are ignored for SQLite,
+/// whose transactions are always serializable.
+#[derive(Debug, Clone, Copy)]
+pub struct TransactionOptions {
+    /// Isolation level.
+    isolation_level: Option<IsolationLevel>,
+    /// A flag to indicate whether the transaction is read-only.
+    read_only: bool,
+    /// Max number of retries on serialization failures.
+    max_retries: usize,
+}
+
+impl TransactionOptions {
+    /// Creates a new instance with the default options.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            isolation_level: None,
+            read_only: false,
+            max_retries: 3,
+        }
+    }
+
+    /// Sets the isolation level.
+    #[inline]
+    pub fn isolation_level(mut self, level: IsolationLevel) -> Self {
+        self.isolation_level = Some(level);
+        self
+    }
+
+    /// Makes the transaction read-only.
+    #[inline]
+    pub fn read_only(mut self) -> Self {
+        self.read_only = true;
+        self
+    }
+
+    /// Sets the max number of retries on serialization failures.
+    /// It only takes effect for PostgreSQL.
+    #[inline]
+    pub fn max_retries(mut self, max_retries: usize) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Formats the statement to begin a transaction for the dialect.
+    fn format_begin_statement(&self, dialect: Dialect) -> Option<String> {
+        let access_mode = self.read_only.then_some("READ ONLY");
+        match dialect {
+            Dialect::Sqlite => None,
+            Dialect::Postgres => {
+                let isolation_level = self
+                    .isolation_level
+                    .map(|level| ["ISOLATION LEVEL ", level.as_sql()].concat());
+                let modes = isolation_level
+                    .as_deref()
+                    .into_iter()
+                    .chain(access_mode)
+                    .collect::<Vec<_>>();
+                (!modes.is_empty()).then(|| format!("BEGIN {};", modes.join(" ")))
+            }
+            _ => {
+                if self.isolation_level.is_none() && access_mode.is_none() {
+                    return None;
+                }
+
+                let mut sql = String::new();
+                if let Some(level) = self.isolation_level {
+                    sql += &format!("SET TRANSACTION ISOLATION LEVEL {};", level.as_sql());
+                }
+                if let Some(access_mode) = access_mode {
+                    sql += &format!("START TRANSACTION {access_mode};");
+                } else {
+                    sql += "START TRANSACTION;";
+                }
+                Some(sql)
+            }
+        }
+    }
+}
+
+impl Default for TransactionOptions {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A handle of an in-progress transaction, which the model operations can run against
+/// via the `*_in` methods of [`Schema`], such as [`Schema::insert_in`].
+///
+/// Nested scopes are backed by savepoints. The query cache of the written models
+/// is invalidated after the outermost transaction has been committed.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use crate::model::{Order, Stock};
+/// use zino_orm::{IsolationLevel, Schema, TransactionOptions, TransactionScope};
+///
+/// let options = TransactionOptions::new().isolation_level(IsolationLevel::Serializable);
+/// TransactionScope::run::<Order, _, _>(&options, |tx| {
+///     let order = order.clone();
+///     let stock = stock.clone();
+///     Box::pin(async move {
+///         order.insert_in(tx).await?;
+///         tx.nested(|tx| Box::pin(async move {
+///             stock.update_in(tx).await?;
+///             Ok(())
+///         }))
+///         .await?;
+///         Ok(())
+///     })
+/// })
+/// .await?;
+/// ```
+#[cfg(feature = "orm-sqlx")]
+pub struct TransactionScope<'c> {
+    /// Connection of the scope.
+    connection: ScopeConnection<'c>,
+    /// Name of the database service.
+    name: &'static str,
+    /// SQL dialect.
+    dialect: Dialect,
+    /// Nesting depth.
+    depth: usize,
+    /// Query cache invalidations of the written models.
+    invalidations: Vec<fn()>,
+}
+
+/// Connection of a transaction scope.
+#[cfg(feature = "orm-sqlx")]
+enum ScopeConnection<'c> {
+    /// The outermost transaction.
+    Transaction(DatabaseTransaction),
+    /// The transaction borrowed from the enclosing scope for a savepoint.
+    Savepoint(&'c mut DatabaseTransaction),
+}
+
+#[cfg(feature = "orm-sqlx")]
+impl TransactionScope<'static> {
+    /// Begins a new transaction with the options.
+    pub async fn begin(
+        connection_pool: &ConnectionPool,
+        options: &TransactionOptions,
+    ) -> Result<Self, Error> {
+        let dialect = connection_pool.dialect();
+        let pool = connection_pool.pool();
+        let transaction = if let Some(sql) = options.format_begin_statement(dialect) {
+            pool.begin_with(sql).await?
+        } else {
+            pool.begin().await?
+        };
+        Ok(Self {
+            connection: ScopeConnection::Transaction(transaction),
+            name: connection_pool.name(),
+            dialect,
+            depth: 0,
+            invalidations: Vec::new(),
+        })
+    }
+
+    /// Executes the operations inside of a transaction on the writer of the model.
+    /// If the operations return an error, the transaction will be rolled back;
+    /// if not, the transaction will be committed.
+    ///
+    /// For PostgreSQL, the whole transaction is retried on serialization failures,
+    /// so the operations should be idempotent.
+    pub async fn run<M, F, T>(options: &TransactionOptions, mut tx: F) -> Result<T, Error>
+    where
+        M: Schema,
+        F: for<'t> FnMut(&'t mut TransactionScope<'static>) -> BoxFuture<'t, Result<T, Error>>,
+    {
+        let connection_pool = M::acquire_writer().await?;
+        let max_retries = if connection_pool.dialect() == Dialect::Postgres {
+            options.max_retries
+        } else {
+            0
+        };
+        let mut retries = 0;
+        loop {
+            let mut scope = Self::begin(connection_pool, options).await?;
+            let result = match tx(&mut scope).await {
+                Ok(data) => scope.commit().await.map(|_| data),
+                Err(err) => {
+                    if let Err(err) = scope.rollback().await {
+                        tracing::error!("fail to roll back the transaction: {err}");
+                    }
+                    Err(err)
+                }
+            };
+            match result {
+                Err(err) if retries < max_retries && is_serialization_failure(&err) => {
+                    retries += 1;
+                    tracing::warn!(retries, "retry the transaction: {err}");
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Commits the transaction and invalidates the query cache of the written models.
+    pub async fn commit(self) -> Result<(), Error> {
+        let ScopeConnection::Transaction(transaction) = self.connection else {
+            bail!("a nested scope can not be committed directly");
+        };
+        transaction.commit().await?;
+        if !self.invalidations.is_empty() {
+            super::GlobalPool::record_write(self.name);
+            for invalidate in self.invalidations {
+                invalidate();
+            }
+        }
+        Ok(())
+    }
+
+    /// Rolls back the transaction.
+    pub async fn rollback(self) -> Result<(), Error> {
+        let ScopeConnection::Transaction(transaction) = self.connection else {
+            bail!("a nested scope can not be rolled back directly");
+        };
+        transaction.rollback().await?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "orm-sqlx")]
+impl TransactionScope<'_> {
+    /// Returns the SQL dialect.
+    #[inline]
+    pub fn dialect(&self) -> Dialect {
+        self.dialect
+    }
+
+    /// Returns the nesting depth, which is `0` for the outermost transaction.
+    #[inline]
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Returns a mutable reference to the underlying transaction.
+    #[inline]
+    pub fn connection(&mut self) -> &mut DatabaseTransaction {
+        match &mut self.connection {
+            ScopeConnection::Transaction(transaction) => transaction,
+            ScopeConnection::Savepoint(connection) => connection,
+        }
+    }
+
+    /// Executes the operations inside of a nested scope backed by a savepoint.
+    /// If the operations return an error, it will be rolled back to the savepoint
+    /// without aborting the enclosing transaction.
+    pub async fn nested<F, T>(&mut self, tx: F) -> Result<T, Error>
+    where
+        F: for<'t> FnOnce(&'t mut TransactionScope<'_>) -> BoxFuture<'t, Result<T, Error>>,
+    {
+        let depth = self.depth + 1;
+        let sql = format_savepoint_statement("SAVEPOINT", depth);
+        self.connection().execute(&sql).await?;
+
+        let mut scope = TransactionScope {
+            name: self.name,
+            dialect: self.dialect,
+            depth,
+            invalidations: Vec::new(),
+            connection: ScopeConnection::Savepoint(self.connection()),
+        };
+        let result = tx(&mut scope).await;
+        let mut invalidations = scope.invalidations;
+        match result {
+            Ok(data) => {
+                let sql = format_savepoint_statement("RELEASE SAVEPOINT", depth);
+                self.connection().execute(&sql).await?;
+                self.invalidations.append(&mut invalidations);
+                Ok(data)
+            }
+            Err(err) => {
+                let sql = format_savepoint_statement("ROLLBACK TO SAVEPOINT", depth);
+                if let Err(rollback_err) = self.connection().execute(&sql).await {
+                    tracing::error!(depth, "fail to roll back to the savepoint: {rollback_err}");
+                }
+                Err(err)
+            }
+        }
+    }
+
+    /// Defers the invalidation of the query cache for the model
+    /// until the outermost transaction has been committed.
+    #[inline]
+    pub(crate) fn invalidate_on_commit<M: Schema>(&mut self) {
+        self.invalidations.push(QueryCache::invalidate::<M>);
+    }
+}
+
+/// Formats the statement for the savepoint of a nested scope at the depth.
+#[cfg(feature = "orm-sqlx")]
+fn format_savepoint_statement(command: &str, depth: usize) -> String {
+    format!("{command} zino_savepoint_{depth};")
+}
+
+/// Returns `true` if the error is caused by a serialization failure or a deadlock.
+#[cfg(feature = "orm-sqlx")]
+fn is_serialization_failure(err: &Error) -> bool {
+    err.get_context::<sqlx::Error>()
+        .and_then(|err| err.as_database_error())
+        .and_then(|err| err.code())
+        .is_some_and(|code| code == "40001" || code == "40P01")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Dialect, IsolationLevel, TransactionOptions};
+
+    #[test]
+    fn it_formats_begin_statements() {
+        let options = TransactionOptions::new();
+        for dialect in [Dialect::MySql, Dialect::Postgres, Dialect::Sqlite] {
+            assert_eq!(options.format_begin_statement(dialect), None);
+        }
+
+        let options = TransactionOptions::new()
+            .isolation_level(IsolationLevel::Serializable)
+            .read_only();
+        assert_eq!(
+            options.format_begin_statement(Dialect::Postgres).as_deref(),
+            Some("BEGIN ISOLATION LEVEL SERIALIZABLE READ ONLY;")
+        );
+        assert_eq!(
+            options.format_begin_statement(Dialect::MySql).as_deref(),
+            Some("SET TRANSACTION ISOLATION LEVEL SERIALIZABLE;START TRANSACTION READ ONLY;")
+        );
+        assert_eq!(options.format_begin_statement(Dialect::Sqlite), None);
+
+        let options = TransactionOptions::new().isolation_level(IsolationLevel::ReadCommitted);
+        assert_eq!(
+            options.format_begin_statement(Dialect::MariaDb).as_deref(),
+            Some("SET TRANSACTION ISOLATION LEVEL READ COMMITTED;START TRANSACTION;")
+        );
+        assert_eq!(
+            IsolationLevel::parse("repeatable-read"),
+            Some(IsolationLevel::RepeatableRead)
+        );
+    }
+
+    #[cfg(feature = "orm-sqlx")]
+    #[test]
+    fn it_formats_savepoint_statements() {
+        assert_eq!(
+            super::format_savepoint_statement("SAVEPOINT", 1),
+            "SAVEPOINT zino_savepoint_1;"
+        );
+        assert_eq!(
+            super::format_savepoint_statement("RELEASE SAVEPOINT", 1),
+            "RELEASE SAVEPOINT zino_savepoint_1;"
+        );
+        assert_eq!(
+            super::format_savepoint_statement("ROLLBACK TO SAVEPOINT", 2),
+            "ROLLBACK TO SAVEPOINT zino_savepoint_2;"
+        );
+    }
+
+    #[cfg(feature = "orm-sqlx")]
+    #[tokio::test]
+    async fn it_rolls_back_nested_scopes() {
+        use super::{ConnectionPool, Error, Executor, TransactionScope};
+        use crate::DatabasePool;
+        use sqlx::sqlite::SqlitePoolOptions;
+
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("fail to connect the database");
+        pool.execute("CREATE TABLE t (id INTEGER PRIMARY KEY);")
+            .await
+            .expect("fail to execute the statement");
+
+        let mut connection_pool =
+            ConnectionPool::new("main", "test", DatabasePool::Sqlite(pool.clone()));
+        connection_pool.set_dialect(Dialect::Sqlite);
+        let mut scope = TransactionScope::begin(&connection_pool, &TransactionOptions::new())
+            .await
+            .expect("fail to begin the transaction");
+        scope
+            .connection()
+            .execute("INSERT INTO t (id) VALUES (1);")
+            .await
+            .expect("fail to execute the statement");
+
+        let result = scope
+            .nested(|tx| {
+                Box::pin(async move {
+                    assert_eq!(tx.depth(), 1);
+                    tx.connection()
+                        .execute("INSERT INTO t (id) VALUES (2);")
+                        .await?;
+                    Err::<(), _>(Error::new("nested scope failed"))
+                })
+            })
+            .await;
+        assert!(result.is_err_and(|err| err.to_string() == "nested scope failed"));
+
+        scope
+            .nested(|tx| {
+                Box::pin(async move {
+                    tx.connection()
+                        .execute("INSERT INTO t (id) VALUES (3);")
+                        .await?;
+                    Ok(())
+                })
+            })
+            .await
+            .expect("fail to run the nested transaction");
+        scope
+            .commit()
+            .await
+            .expect("fail to commit the transaction");
+
+        let rows = pool
+            .fetch("SELECT id FROM t ORDER BY id;")
+            .await
+            .expect("fail to fetch the rows");
+        let ids = rows
+            .iter()
+            .map(|row| sqlx::Row::get::<i64, _>(row, 0))
+            .collect::<Vec<_>>();
+        assert_eq!(ids, [1, 3]);
+    }
+}
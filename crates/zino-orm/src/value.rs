@@ -51,13 +51,11 @@ impl_into_sql_value!(
 );
 
 impl IntoSqlValue for DateTime {
+    /// The date-time is converted with its offset, and the drivers without
+    /// the support of time zones format it as a UTC timestamp.
     #[inline]
     fn into_sql_value(self) -> JsonValue {
-        if cfg!(feature = "orm-postgres") {
-            self.to_string().into()
-        } else {
-            self.to_utc_timestamp().into()
-        }
+        self.to_string().into()
     }
 }
 
@@ -144,3 +142,10 @@ impl<E: Entity + Schema> IntoSqlValue for QueryBuilder<E> {
         Map::from_entry("$subquery", self.build_subquery()).into()
     }
 }
+
+/// Parses a date-time with an offset converted by [`IntoSqlValue`] as a UTC timestamp.
+pub(super) fn parse_utc_timestamp(value: &str) -> Option<String> {
+    chrono::DateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S%.f %z")
+        .ok()
+        .map(|dt| dt.naive_utc().format("%Y-%m-%d %H:%M:%S%.6f").to_string())
+}
@@ -1,6 +1,5 @@
 use self::WindownFunction::*;
-use super::{Entity, query::QueryExt};
-use zino_core::model::Query;
+use super::Entity;
 
 /// A windown function.
 #[derive(Debug, Clone, Copy)]
@@ -251,14 +250,15 @@ impl<E: Entity> Window<E> {
 
     /// Returns the SQL expression.
     pub(super) fn expr(&self) -> String {
+        let dialect = E::dialect();
         let partition_col_name = E::format_column(&self.partition);
-        let partition = Query::format_field(&partition_col_name);
+        let partition = dialect.format_field(&partition_col_name);
         let sort = self
             .order
             .as_ref()
             .map(|(col, descending)| {
                 let col_name = E::format_column(col);
-                let sort_field = Query::format_field(&col_name);
+                let sort_field = dialect.format_field(&col_name);
                 if *descending {
                     format!(" ORDER BY {sort_field} DESC")
                 } else {
@@ -269,27 +269,27 @@ impl<E: Entity> Window<E> {
         match &self.function {
             Count(col) => {
                 let col_name = E::format_column(col);
-                let field = Query::format_field(&col_name);
+                let field = dialect.format_field(&col_name);
                 format!("count({field}) OVER (PARTITION BY {partition}{sort})")
             }
             Sum(col) => {
                 let col_name = E::format_column(col);
-                let field = Query::format_field(&col_name);
+                let field = dialect.format_field(&col_name);
                 format!("sum({field}) OVER (PARTITION BY {partition}{sort})")
             }
             Avg(col) => {
                 let col_name = E::format_column(col);
-                let field = Query::format_field(&col_name);
+                let field = dialect.format_field(&col_name);
                 format!("avg({field}) OVER (PARTITION BY {partition}{sort})")
             }
             Min(col) => {
                 let col_name = E::format_column(col);
-                let field = Query::format_field(&col_name);
+                let field = dialect.format_field(&col_name);
                 format!("min({field}) OVER (PARTITION BY {partition}{sort})")
             }
             Max(col) => {
                 let col_name = E::format_column(col);
-                let field = Query::format_field(&col_name);
+                let field = dialect.format_field(&col_name);
                 format!("max({field}) OVER (PARTITION BY {partition}{sort})")
             }
             RowNumber => {
@@ -312,27 +312,27 @@ impl<E: Entity> Window<E> {
             }
             Lag(col, offset) => {
                 let col_name = E::format_column(col);
-                let field = Query::format_field(&col_name);
+                let field = dialect.format_field(&col_name);
                 format!("lag({field}, {offset}) OVER (PARTITION BY {partition}{sort})")
             }
             Lead(col, offset) => {
                 let col_name = E::format_column(col);
-                let field = Query::format_field(&col_name);
+                let field = dialect.format_field(&col_name);
                 format!("lead({field}, {offset}) OVER (PARTITION BY {partition}{sort})")
             }
             FirstValue(col) => {
                 let col_name = E::format_column(col);
-                let field = Query::format_field(&col_name);
+                let field = dialect.format_field(&col_name);
                 format!("first_value({field}) OVER (PARTITION BY {partition}{sort})")
             }
             LastValue(col) => {
                 let col_name = E::format_column(col);
-                let field = Query::format_field(&col_name);
+                let field = dialect.format_field(&col_name);
                 format!("last_value({field}) OVER (PARTITION BY {partition}{sort})")
             }
             NthValue(col, n) => {
                 let col_name = E::format_column(col);
-                let field = Query::format_field(&col_name);
+                let field = dialect.format_field(&col_name);
                 format!("nth_value({field}, {n}) OVER (PARTITION BY {partition}{sort})")
             }
         }
@@ -1,3 +1,15 @@
 #![doc = include_str!("../README.md")]
 #![doc(html_favicon_url = "https://zino.cc/assets/zino-logo.png")]
 #![doc(html_logo_url = "https://zino.cc/assets/zino-logo.svg")]
+
+mod middleware;
+mod path;
+mod request;
+mod route;
+mod router;
+
+pub use middleware::{Middleware, Next, from_fn};
+pub use path::{ParamType, PathParams, PathPattern};
+pub use request::{FutureFlavor, HandlerFuture, IntoBoxed, RouteRequest, Sendable, ThreadLocal};
+pub use route::{BoxHandler, Route, RouteInfo, RouteMetadata};
+pub use router::{RouteTable, Router};
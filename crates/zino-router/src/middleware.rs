@@ -0,0 +1,80 @@
+use super::{BoxHandler, HandlerFuture, IntoBoxed, RouteInfo, RouteRequest};
+use std::{future::Future, sync::Arc};
+
+/// A framework-neutral middleware which wraps the handler of a route.
+pub type Middleware<Req, Res> =
+    Arc<dyn Fn(Req, Next<Req, Res>) -> HandlerFuture<Req, Res> + Send + Sync>;
+
+/// Creates a middleware from an async function.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use zino::{Request, Result, prelude::*};
+/// use zino_router::{Next, from_fn};
+///
+/// let check_roles = from_fn(|req: Request, next: Next<Request, Result>| async move {
+///     let roles = next.route().metadata().roles();
+///     if !roles.is_empty() {
+///         // Checks the roles of the user.
+///     }
+///     next.run(req).await
+/// });
+/// ```
+pub fn from_fn<Req, Res, F, Fut>(f: F) -> Middleware<Req, Res>
+where
+    Req: RouteRequest,
+    Res: 'static,
+    F: Fn(Req, Next<Req, Res>) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Res> + IntoBoxed<Req::Flavor>,
+{
+    Arc::new(move |req, next| f(req, next).into_boxed())
+}
+
+/// The remaining middleware and the handler of a route.
+pub struct Next<Req: RouteRequest, Res: 'static> {
+    /// Information of the route.
+    route: Arc<RouteInfo>,
+    /// Middleware of the route.
+    middleware: Arc<[Middleware<Req, Res>]>,
+    /// Number of the remaining middleware.
+    remaining: usize,
+    /// Handler of the route.
+    handler: BoxHandler<Req, Res>,
+}
+
+impl<Req: RouteRequest, Res: 'static> Next<Req, Res> {
+    /// Creates a new instance.
+    #[inline]
+    pub(crate) fn new(
+        route: Arc<RouteInfo>,
+        middleware: Arc<[Middleware<Req, Res>]>,
+        handler: BoxHandler<Req, Res>,
+    ) -> Self {
+        let remaining = middleware.len();
+        Self {
+            route,
+            middleware,
+            remaining,
+            handler,
+        }
+    }
+
+    /// Returns a reference to the information of the route.
+    #[inline]
+    pub fn route(&self) -> &RouteInfo {
+        &self.route
+    }
+
+    /// Runs the remaining middleware and the handler.
+    /// The middleware added later is executed earlier.
+    pub fn run(mut self, req: Req) -> HandlerFuture<Req, Res> {
+        if self.remaining == 0 {
+            (self.handler)(req)
+        } else {
+            self.remaining -= 1;
+            let middleware = self.middleware[self.remaining].clone();
+            middleware(req, self)
+        }
+    }
+}
@@ -0,0 +1,320 @@
+use std::{fmt, str::FromStr};
+use zino_core::{Uuid, error::Error, warn};
+
+/// Type of a path parameter.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ParamType {
+    /// A string segment.
+    #[default]
+    String,
+    /// A signed integer.
+    Integer,
+    /// An unsigned integer.
+    UnsignedInteger,
+    /// A UUID.
+    Uuid,
+    /// The remaining path, which is only allowed for the last segment.
+    Wildcard,
+}
+
+impl ParamType {
+    /// Parses the type annotation of a path parameter.
+    fn parse(ty: &str) -> Option<Self> {
+        match ty {
+            "str" | "string" => Some(Self::String),
+            "i32" | "i64" | "int" | "integer" => Some(Self::Integer),
+            "u32" | "u64" | "uint" => Some(Self::UnsignedInteger),
+            "uuid" => Some(Self::Uuid),
+            _ => None,
+        }
+    }
+
+    /// Returns the type annotation.
+    #[inline]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::String => "string",
+            Self::Integer => "i64",
+            Self::UnsignedInteger => "u64",
+            Self::Uuid => "uuid",
+            Self::Wildcard => "*",
+        }
+    }
+
+    /// Returns `true` if the value is valid for the type.
+    pub fn is_valid(&self, value: &str) -> bool {
+        match self {
+            Self::String => !value.is_empty(),
+            Self::Integer => value.parse::<i64>().is_ok(),
+            Self::UnsignedInteger => value.parse::<u64>().is_ok(),
+            Self::Uuid => value.parse::<Uuid>().is_ok(),
+            Self::Wildcard => true,
+        }
+    }
+}
+
+/// A segment of the path pattern.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    /// A static segment.
+    Static(String),
+    /// A path parameter with the name and type.
+    Param(String, ParamType),
+}
+
+/// A path pattern with typed captures, such as `/user/{id:uuid}/view`.
+///
+/// A capture is written as `{name}` or `{name:type}`, where the type can be
+/// `string`, `i64`, `u64` or `uuid`. The remaining path can be captured
+/// by `{*name}` in the last segment.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PathPattern {
+    /// Segments of the pattern.
+    segments: Vec<Segment>,
+}
+
+impl PathPattern {
+    /// Attempts to parse the path pattern.
+    pub fn parse(pattern: &str) -> Result<Self, Error> {
+        let mut segments = Vec::new();
+        let mut has_wildcard = false;
+        for segment in pattern.split('/').filter(|s| !s.is_empty()) {
+            if has_wildcard {
+                return Err(warn!("the wildcard should be the last segment"));
+            }
+            if let Some(capture) = segment.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+                let (name, ty) = if let Some(name) = capture.strip_prefix('*') {
+                    has_wildcard = true;
+                    (name, ParamType::Wildcard)
+                } else if let Some((name, ty)) = capture.split_once(':') {
+                    let ty = ParamType::parse(ty.trim())
+                        .ok_or_else(|| warn!("unsupported type `{}` of the path parameter", ty))?;
+                    (name.trim(), ty)
+                } else {
+                    (capture.trim(), ParamType::String)
+                };
+                if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+                    return Err(warn!("invalid name `{}` of the path parameter", name));
+                }
+                if segments
+                    .iter()
+                    .any(|s| matches!(s, Segment::Param(param, _) if param == name))
+                {
+                    return Err(warn!("duplicate path parameter `{}`", name));
+                }
+                segments.push(Segment::Param(name.to_owned(), ty));
+            } else if segment.contains(['{', '}']) {
+                return Err(warn!("invalid path segment `{}`", segment));
+            } else {
+                segments.push(Segment::Static(segment.to_owned()));
+            }
+        }
+        Ok(Self { segments })
+    }
+
+    /// Joins the pattern with a nested one.
+    pub fn join(&self, nested: &PathPattern) -> Result<Self, Error> {
+        if self.has_wildcard() {
+            return Err(warn!("a wildcard pattern can not be nested"));
+        }
+
+        let mut segments = self.segments.clone();
+        for segment in &nested.segments {
+            if let Segment::Param(name, _) = segment
+                && self.param_type(name).is_some()
+            {
+                return Err(warn!("duplicate path parameter `{}`", name));
+            }
+            segments.push(segment.clone());
+        }
+        Ok(Self { segments })
+    }
+
+    /// Returns an iterator visiting the names and types of the path parameters.
+    #[inline]
+    pub fn params(&self) -> impl Iterator<Item = (&str, ParamType)> {
+        self.segments.iter().filter_map(|segment| match segment {
+            Segment::Param(name, ty) => Some((name.as_str(), *ty)),
+            _ => None,
+        })
+    }
+
+    /// Returns the type of the path parameter.
+    #[inline]
+    pub fn param_type(&self, name: &str) -> Option<ParamType> {
+        self.params()
+            .find(|param| param.0 == name)
+            .map(|param| param.1)
+    }
+
+    /// Returns `true` if the pattern has any captures with non-string types.
+    #[inline]
+    pub fn is_typed(&self) -> bool {
+        self.params()
+            .any(|(_, ty)| !matches!(ty, ParamType::String | ParamType::Wildcard))
+    }
+
+    /// Returns `true` if the last segment is a wildcard.
+    #[inline]
+    pub fn has_wildcard(&self) -> bool {
+        self.segments
+            .last()
+            .is_some_and(|s| matches!(s, Segment::Param(_, ParamType::Wildcard)))
+    }
+
+    /// Matches the request path and extracts the path parameters.
+    /// It returns `None` if the path does not match or any of the typed captures is invalid.
+    pub fn matches(&self, path: &str) -> Option<PathParams> {
+        let mut params = Vec::new();
+        let mut parts = path.split('/').filter(|s| !s.is_empty());
+        for segment in &self.segments {
+            match segment {
+                Segment::Static(s) => {
+                    if parts.next()? != s {
+                        return None;
+                    }
+                }
+                Segment::Param(name, ParamType::Wildcard) => {
+                    let rest = parts.by_ref().collect::<Vec<_>>().join("/");
+                    params.push((name.to_owned(), rest));
+                }
+                Segment::Param(name, ty) => {
+                    let part = parts.next()?;
+                    if !ty.is_valid(part) {
+                        return None;
+                    }
+                    params.push((name.to_owned(), part.to_owned()));
+                }
+            }
+        }
+        parts.next().is_none().then_some(PathParams { params })
+    }
+
+    /// Formats the pattern with a function which formats the path parameters.
+    /// It can be used to convert the pattern into the syntax of the underlying framework.
+    pub fn format_with(&self, format_param: impl Fn(&str, ParamType) -> String) -> String {
+        if self.segments.is_empty() {
+            return "/".to_owned();
+        }
+
+        let mut path = String::new();
+        for segment in &self.segments {
+            path.push('/');
+            match segment {
+                Segment::Static(s) => path.push_str(s),
+                Segment::Param(name, ty) => path.push_str(&format_param(name, *ty)),
+            }
+        }
+        path
+    }
+
+    /// Returns the path without the type annotations, such as `/user/{id}/view`,
+    /// and the wildcard is formatted as `{*name}`.
+    #[inline]
+    pub fn to_path(&self) -> String {
+        self.format_with(|name, ty| {
+            if ty == ParamType::Wildcard {
+                format!("{{*{name}}}")
+            } else {
+                format!("{{{name}}}")
+            }
+        })
+    }
+}
+
+impl fmt::Display for PathPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = self.format_with(|name, ty| match ty {
+            ParamType::String => format!("{{{name}}}"),
+            ParamType::Wildcard => format!("{{*{name}}}"),
+            _ => format!("{{{name}:{}}}", ty.as_str()),
+        });
+        write!(f, "{path}")
+    }
+}
+
+impl FromStr for PathPattern {
+    type Err = Error;
+
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+/// Path parameters extracted by a [`PathPattern`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PathParams {
+    /// Names and values of the parameters.
+    params: Vec<(String, String)>,
+}
+
+impl PathParams {
+    /// Returns the value of the path parameter.
+    #[inline]
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|param| param.0 == name)
+            .map(|param| param.1.as_str())
+    }
+
+    /// Parses the value of the path parameter.
+    #[inline]
+    pub fn parse<T: FromStr>(&self, name: &str) -> Option<Result<T, T::Err>> {
+        self.get(name).map(|value| value.parse())
+    }
+
+    /// Returns an iterator visiting the names and values of the path parameters.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.params
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+
+    /// Returns the number of the path parameters.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.params.len()
+    }
+
+    /// Returns `true` if there are no path parameters.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.params.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ParamType, PathPattern};
+
+    #[test]
+    fn it_matches_typed_paths() {
+        let pattern =
+            PathPattern::parse("/user/{id:u64}/files/{*path}").expect("fail to parse the value");
+        assert_eq!(pattern.to_path(), "/user/{id}/files/{*path}");
+        assert_eq!(pattern.to_string(), "/user/{id:u64}/files/{*path}");
+        assert_eq!(pattern.param_type("id"), Some(ParamType::UnsignedInteger));
+        assert!(pattern.is_typed());
+
+        let params = pattern
+            .matches("/user/42/files/docs/readme.md")
+            .expect("the path should be matched");
+        assert_eq!(params.parse::<u64>("id"), Some(Ok(42)));
+        assert_eq!(params.get("path"), Some("docs/readme.md"));
+        assert!(pattern.matches("/user/abc/files/readme.md").is_none());
+        assert!(pattern.matches("/tag/42/files").is_none());
+
+        let nested = PathPattern::parse("/v1")
+            .expect("fail to parse the value")
+            .join(&pattern)
+            .expect("fail to join the thread");
+        assert_eq!(nested.to_path(), "/v1/user/{id}/files/{*path}");
+        assert!(PathPattern::parse("/{*path}/view").is_err());
+        assert!(PathPattern::parse("/{id}/{id}").is_err());
+        assert!(PathPattern::parse("/{id:float}").is_err());
+    }
+}
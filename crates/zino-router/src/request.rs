@@ -0,0 +1,65 @@
+use std::{future::Future, pin::Pin};
+use zino_core::BoxFuture;
+
+/// A request type which can be handled by the framework-neutral routes.
+///
+/// The flavor determines whether the futures returned by the handlers should be `Send`.
+/// It should be [`Sendable`] for the frameworks running the handlers on a work-stealing runtime,
+/// and [`ThreadLocal`] for the frameworks whose requests are bound to the worker thread.
+pub trait RouteRequest: Sized + 'static {
+    /// Flavor of the futures returned by the handlers.
+    type Flavor: FutureFlavor;
+}
+
+/// A flavor of the futures returned by the handlers.
+pub trait FutureFlavor: 'static {
+    /// Boxed future with the output.
+    type Boxed<T: 'static>: Future<Output = T> + Unpin + 'static;
+}
+
+/// The flavor of the futures which can be sent across threads,
+/// used by `axum`, `salvo` and the hyper server.
+#[derive(Debug, Clone, Copy)]
+pub enum Sendable {}
+
+impl FutureFlavor for Sendable {
+    type Boxed<T: 'static> = BoxFuture<'static, T>;
+}
+
+/// The flavor of the futures which are bound to the current thread,
+/// used by `actix-web` and `ntex`.
+#[derive(Debug, Clone, Copy)]
+pub enum ThreadLocal {}
+
+impl FutureFlavor for ThreadLocal {
+    type Boxed<T: 'static> = Pin<Box<dyn Future<Output = T> + 'static>>;
+}
+
+/// A future which can be boxed as the flavor.
+pub trait IntoBoxed<F: FutureFlavor>: Future<Output: 'static> {
+    /// Boxes the future.
+    fn into_boxed(self) -> F::Boxed<Self::Output>;
+}
+
+impl<Fut> IntoBoxed<Sendable> for Fut
+where
+    Fut: Future<Output: 'static> + Send + 'static,
+{
+    #[inline]
+    fn into_boxed(self) -> BoxFuture<'static, Self::Output> {
+        Box::pin(self)
+    }
+}
+
+impl<Fut> IntoBoxed<ThreadLocal> for Fut
+where
+    Fut: Future<Output: 'static> + 'static,
+{
+    #[inline]
+    fn into_boxed(self) -> Pin<Box<dyn Future<Output = Self::Output> + 'static>> {
+        Box::pin(self)
+    }
+}
+
+/// Boxed future returned by the handlers of the request type.
+pub type HandlerFuture<Req, Res> = <<Req as RouteRequest>::Flavor as FutureFlavor>::Boxed<Res>;
@@ -0,0 +1,310 @@
+use super::{HandlerFuture, IntoBoxed, Middleware, Next, PathPattern, RouteRequest};
+use http::Method;
+use std::{fmt, future::Future, sync::Arc};
+use zino_core::SharedString;
+
+/// A type-erased handler of a route.
+pub type BoxHandler<Req, Res> = Arc<dyn Fn(Req) -> HandlerFuture<Req, Res> + Send + Sync>;
+
+/// Metadata of a route.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RouteMetadata {
+    /// Route name, which is used as the operation ID.
+    name: Option<SharedString>,
+    /// A short summary.
+    summary: Option<SharedString>,
+    /// A verbose explanation.
+    description: Option<SharedString>,
+    /// Tags for grouping the routes.
+    tags: Vec<SharedString>,
+    /// Roles required to access the route.
+    roles: Vec<SharedString>,
+    /// A flag to indicate the route is deprecated.
+    deprecated: bool,
+}
+
+impl RouteMetadata {
+    /// Returns the route name.
+    #[inline]
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Returns the summary.
+    #[inline]
+    pub fn summary(&self) -> Option<&str> {
+        self.summary.as_deref()
+    }
+
+    /// Returns the description.
+    #[inline]
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    /// Returns the tags.
+    #[inline]
+    pub fn tags(&self) -> &[SharedString] {
+        &self.tags
+    }
+
+    /// Returns the required roles.
+    #[inline]
+    pub fn roles(&self) -> &[SharedString] {
+        &self.roles
+    }
+
+    /// Returns `true` if the route is deprecated.
+    #[inline]
+    pub fn is_deprecated(&self) -> bool {
+        self.deprecated
+    }
+
+    /// Adds a tag if it does not exist.
+    pub(crate) fn add_tag(&mut self, tag: SharedString) {
+        if !self.tags.contains(&tag) {
+            self.tags.push(tag);
+        }
+    }
+
+    /// Adds a required role if it does not exist.
+    pub(crate) fn add_role(&mut self, role: SharedString) {
+        if !self.roles.contains(&role) {
+            self.roles.push(role);
+        }
+    }
+}
+
+/// Information of a route without the handler.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RouteInfo {
+    /// HTTP method.
+    method: Method,
+    /// Path pattern.
+    pattern: PathPattern,
+    /// Route metadata.
+    metadata: RouteMetadata,
+}
+
+impl RouteInfo {
+    /// Returns a reference to the HTTP method.
+    #[inline]
+    pub fn method(&self) -> &Method {
+        &self.method
+    }
+
+    /// Returns a reference to the path pattern.
+    #[inline]
+    pub fn pattern(&self) -> &PathPattern {
+        &self.pattern
+    }
+
+    /// Returns a reference to the route metadata.
+    #[inline]
+    pub fn metadata(&self) -> &RouteMetadata {
+        &self.metadata
+    }
+}
+
+/// A route with the HTTP method, path pattern, handler, middleware and metadata.
+pub struct Route<Req: RouteRequest, Res: 'static> {
+    /// Information of the route.
+    info: RouteInfo,
+    /// Handler of the route.
+    handler: BoxHandler<Req, Res>,
+    /// Middleware of the route.
+    middleware: Vec<Middleware<Req, Res>>,
+}
+
+impl<Req: RouteRequest, Res: 'static> Route<Req, Res> {
+    /// Creates a new instance.
+    ///
+    /// # Panics
+    ///
+    /// It will panic if the path pattern is invalid.
+    pub fn new<F, Fut>(method: Method, path: &str, handler: F) -> Self
+    where
+        F: Fn(Req) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Res> + IntoBoxed<Req::Flavor>,
+    {
+        let pattern = PathPattern::parse(path)
+            .unwrap_or_else(|err| panic!("invalid path pattern `{path}`: {err}"));
+        Self {
+            info: RouteInfo {
+                method,
+                pattern,
+                metadata: RouteMetadata::default(),
+            },
+            handler: Arc::new(move |req| handler(req).into_boxed()),
+            middleware: Vec::new(),
+        }
+    }
+
+    /// Creates a new instance for the `GET` method.
+    #[inline]
+    pub fn get<F, Fut>(path: &str, handler: F) -> Self
+    where
+        F: Fn(Req) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Res> + IntoBoxed<Req::Flavor>,
+    {
+        Self::new(Method::GET, path, handler)
+    }
+
+    /// Creates a new instance for the `POST` method.
+    #[inline]
+    pub fn post<F, Fut>(path: &str, handler: F) -> Self
+    where
+        F: Fn(Req) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Res> + IntoBoxed<Req::Flavor>,
+    {
+        Self::new(Method::POST, path, handler)
+    }
+
+    /// Creates a new instance for the `PUT` method.
+    #[inline]
+    pub fn put<F, Fut>(path: &str, handler: F) -> Self
+    where
+        F: Fn(Req) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Res> + IntoBoxed<Req::Flavor>,
+    {
+        Self::new(Method::PUT, path, handler)
+    }
+
+    /// Creates a new instance for the `PATCH` method.
+    #[inline]
+    pub fn patch<F, Fut>(path: &str, handler: F) -> Self
+    where
+        F: Fn(Req) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Res> + IntoBoxed<Req::Flavor>,
+    {
+        Self::new(Method::PATCH, path, handler)
+    }
+
+    /// Creates a new instance for the `DELETE` method.
+    #[inline]
+    pub fn delete<F, Fut>(path: &str, handler: F) -> Self
+    where
+        F: Fn(Req) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Res> + IntoBoxed<Req::Flavor>,
+    {
+        Self::new(Method::DELETE, path, handler)
+    }
+
+    /// Adds a middleware for the route.
+    #[inline]
+    pub fn layer(mut self, middleware: Middleware<Req, Res>) -> Self {
+        self.middleware.push(middleware);
+        self
+    }
+
+    /// Sets the route name.
+    #[inline]
+    pub fn name(mut self, name: impl Into<SharedString>) -> Self {
+        self.info.metadata.name = Some(name.into());
+        self
+    }
+
+    /// Sets the summary.
+    #[inline]
+    pub fn summary(mut self, summary: impl Into<SharedString>) -> Self {
+        self.info.metadata.summary = Some(summary.into());
+        self
+    }
+
+    /// Sets the description.
+    #[inline]
+    pub fn description(mut self, description: impl Into<SharedString>) -> Self {
+        self.info.metadata.description = Some(description.into());
+        self
+    }
+
+    /// Adds a tag.
+    #[inline]
+    pub fn tag(mut self, tag: impl Into<SharedString>) -> Self {
+        self.info.metadata.add_tag(tag.into());
+        self
+    }
+
+    /// Adds the required roles.
+    #[inline]
+    pub fn roles<T: Into<SharedString>>(mut self, roles: impl IntoIterator<Item = T>) -> Self {
+        for role in roles {
+            self.info.metadata.add_role(role.into());
+        }
+        self
+    }
+
+    /// Marks the route as deprecated.
+    #[inline]
+    pub fn deprecated(mut self) -> Self {
+        self.info.metadata.deprecated = true;
+        self
+    }
+
+    /// Returns a reference to the HTTP method.
+    #[inline]
+    pub fn method(&self) -> &Method {
+        &self.info.method
+    }
+
+    /// Returns a reference to the path pattern.
+    #[inline]
+    pub fn pattern(&self) -> &PathPattern {
+        &self.info.pattern
+    }
+
+    /// Returns a reference to the route metadata.
+    #[inline]
+    pub fn metadata(&self) -> &RouteMetadata {
+        &self.info.metadata
+    }
+
+    /// Returns a reference to the information of the route.
+    #[inline]
+    pub fn info(&self) -> &RouteInfo {
+        &self.info
+    }
+
+    /// Consumes the route and returns a handler wrapped by the middleware.
+    pub fn into_handler(self) -> BoxHandler<Req, Res> {
+        if self.middleware.is_empty() {
+            return self.handler;
+        }
+
+        let info = Arc::new(self.info);
+        let middleware = Arc::<[_]>::from(self.middleware);
+        let handler = self.handler;
+        Arc::new(move |req| {
+            let next = Next::new(info.clone(), middleware.clone(), handler.clone());
+            next.run(req)
+        })
+    }
+
+    /// Returns a mutable reference to the path pattern.
+    #[inline]
+    pub(crate) fn pattern_mut(&mut self) -> &mut PathPattern {
+        &mut self.info.pattern
+    }
+}
+
+impl<Req: RouteRequest, Res: 'static> Clone for Route<Req, Res> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            info: self.info.clone(),
+            handler: self.handler.clone(),
+            middleware: self.middleware.clone(),
+        }
+    }
+}
+
+impl<Req: RouteRequest, Res: 'static> fmt::Debug for Route<Req, Res> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Route")
+            .field("method", &self.info.method)
+            .field("pattern", &self.info.pattern)
+            .field("metadata", &self.info.metadata)
+            .field("middleware", &self.middleware.len())
+            .finish()
+    }
+}
@@ -0,0 +1,286 @@
+use super::{IntoBoxed, Middleware, PathPattern, Route, RouteInfo, RouteRequest};
+use std::{future::Future, sync::RwLock};
+use zino_core::{LazyLock, SharedString};
+
+/// A framework-neutral route table.
+///
+/// The middleware, tags and roles added to a router apply to all the routes
+/// which have been added before, so they should be added after the routes.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use zino::{Cluster, DefaultController, Request, Result, prelude::*};
+/// use zino_router::{Route, Router, from_fn};
+///
+/// let user_router = Router::<Request, Result>::new()
+///     .get("/{id:uuid}/view", User::view)
+///     .post("/{id:uuid}/update", User::update)
+///     .route(Route::post("/{id:uuid}/delete", User::delete).roles(["admin"]))
+///     .layer(from_fn(middleware::init_user_session))
+///     .tag("user");
+/// let router = Router::new().nest("/user", user_router);
+/// Cluster::boot().register_router(router).run();
+/// ```
+pub struct Router<Req: RouteRequest, Res: 'static> {
+    /// Routes.
+    routes: Vec<Route<Req, Res>>,
+}
+
+impl<Req: RouteRequest, Res: 'static> Router<Req, Res> {
+    /// Creates a new instance.
+    #[inline]
+    pub fn new() -> Self {
+        Self { routes: Vec::new() }
+    }
+
+    /// Adds a route.
+    #[inline]
+    pub fn route(mut self, route: Route<Req, Res>) -> Self {
+        self.routes.push(route);
+        self
+    }
+
+    /// Adds a route for the `GET` method.
+    #[inline]
+    pub fn get<F, Fut>(self, path: &str, handler: F) -> Self
+    where
+        F: Fn(Req) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Res> + IntoBoxed<Req::Flavor>,
+    {
+        self.route(Route::get(path, handler))
+    }
+
+    /// Adds a route for the `POST` method.
+    #[inline]
+    pub fn post<F, Fut>(self, path: &str, handler: F) -> Self
+    where
+        F: Fn(Req) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Res> + IntoBoxed<Req::Flavor>,
+    {
+        self.route(Route::post(path, handler))
+    }
+
+    /// Adds a route for the `PUT` method.
+    #[inline]
+    pub fn put<F, Fut>(self, path: &str, handler: F) -> Self
+    where
+        F: Fn(Req) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Res> + IntoBoxed<Req::Flavor>,
+    {
+        self.route(Route::put(path, handler))
+    }
+
+    /// Adds a route for the `PATCH` method.
+    #[inline]
+    pub fn patch<F, Fut>(self, path: &str, handler: F) -> Self
+    where
+        F: Fn(Req) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Res> + IntoBoxed<Req::Flavor>,
+    {
+        self.route(Route::patch(path, handler))
+    }
+
+    /// Adds a route for the `DELETE` method.
+    #[inline]
+    pub fn delete<F, Fut>(self, path: &str, handler: F) -> Self
+    where
+        F: Fn(Req) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Res> + IntoBoxed<Req::Flavor>,
+    {
+        self.route(Route::delete(path, handler))
+    }
+
+    /// Nests the routes of another router under the path prefix.
+    ///
+    /// # Panics
+    ///
+    /// It will panic if the prefix is invalid or conflicts with the nested paths.
+    pub fn nest(mut self, prefix: &str, router: Router<Req, Res>) -> Self {
+        let prefix = PathPattern::parse(prefix)
+            .unwrap_or_else(|err| panic!("invalid path prefix `{prefix}`: {err}"));
+        for mut route in router.routes {
+            let pattern = prefix.join(route.pattern()).unwrap_or_else(|err| {
+                panic!("fail to nest `{}` under `{prefix}`: {err}", route.pattern())
+            });
+            *route.pattern_mut() = pattern;
+            self.routes.push(route);
+        }
+        self
+    }
+
+    /// Merges the routes of another router.
+    #[inline]
+    pub fn merge(mut self, mut router: Router<Req, Res>) -> Self {
+        self.routes.append(&mut router.routes);
+        self
+    }
+
+    /// Adds a middleware for all the routes.
+    #[inline]
+    pub fn layer(mut self, middleware: Middleware<Req, Res>) -> Self {
+        self.routes = self
+            .routes
+            .into_iter()
+            .map(|route| route.layer(middleware.clone()))
+            .collect();
+        self
+    }
+
+    /// Adds a tag for all the routes.
+    pub fn tag(mut self, tag: impl Into<SharedString>) -> Self {
+        let tag = tag.into();
+        self.routes = self
+            .routes
+            .into_iter()
+            .map(|route| route.tag(tag.clone()))
+            .collect();
+        self
+    }
+
+    /// Adds the required roles for all the routes.
+    pub fn roles<T: Into<SharedString>>(mut self, roles: impl IntoIterator<Item = T>) -> Self {
+        let roles = roles
+            .into_iter()
+            .map(|role| role.into())
+            .collect::<Vec<_>>();
+        self.routes = self
+            .routes
+            .into_iter()
+            .map(|route| route.roles(roles.iter().cloned()))
+            .collect();
+        self
+    }
+
+    /// Returns a reference to the routes.
+    #[inline]
+    pub fn routes(&self) -> &[Route<Req, Res>] {
+        &self.routes
+    }
+
+    /// Consumes the router and returns the routes.
+    #[inline]
+    pub fn into_routes(self) -> Vec<Route<Req, Res>> {
+        self.routes
+    }
+
+    /// Returns the number of routes.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.routes.len()
+    }
+
+    /// Returns `true` if there are no routes.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.routes.is_empty()
+    }
+}
+
+impl<Req: RouteRequest, Res: 'static> Default for Router<Req, Res> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Req: RouteRequest, Res: 'static> Clone for Router<Req, Res> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            routes: self.routes.clone(),
+        }
+    }
+}
+
+/// Global table of the routes registered by the applications.
+/// It is used to introspect the routes, such as generating the OpenAPI paths.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RouteTable;
+
+impl RouteTable {
+    /// Records the routes of the router.
+    pub fn record<Req: RouteRequest, Res: 'static>(router: &Router<Req, Res>) {
+        if let Ok(mut routes) = REGISTERED_ROUTES.write() {
+            for route in router.routes() {
+                let info = route.info();
+                if !routes.contains(info) {
+                    routes.push(info.clone());
+                }
+            }
+        }
+    }
+
+    /// Returns the information of all the registered routes.
+    pub fn routes() -> Vec<RouteInfo> {
+        REGISTERED_ROUTES
+            .read()
+            .map(|routes| routes.clone())
+            .unwrap_or_default()
+    }
+}
+
+/// Registered routes.
+static REGISTERED_ROUTES: LazyLock<RwLock<Vec<RouteInfo>>> =
+    LazyLock::new(|| RwLock::new(Vec::new()));
+
+#[cfg(test)]
+mod tests {
+    use super::{Route, Router};
+    use crate::{Next, RouteRequest, Sendable, ThreadLocal, from_fn};
+    use std::{
+        rc::Rc,
+        task::{Context, Poll, Waker},
+    };
+
+    impl RouteRequest for String {
+        type Flavor = Sendable;
+    }
+
+    impl RouteRequest for Rc<str> {
+        type Flavor = ThreadLocal;
+    }
+
+    #[test]
+    fn it_nests_routes_with_middleware() {
+        let append = |suffix: &'static str| {
+            from_fn(move |req: String, next: Next<String, String>| async move {
+                let res = next.run(req).await;
+                format!("{res}>{suffix}")
+            })
+        };
+        let user_router = Router::new()
+            .get("/{id:u64}/view", |req: String| async move { req })
+            .route(Route::post("/new", |req: String| async move { req }).roles(["admin"]))
+            .layer(append("inner"))
+            .tag("user");
+        let router = Router::new()
+            .nest("/v1/user", user_router)
+            .layer(append("outer"));
+        let routes = router.into_routes();
+        assert_eq!(routes.len(), 2);
+        assert_eq!(routes[0].pattern().to_path(), "/v1/user/{id}/view");
+        assert_eq!(routes[1].metadata().roles(), ["admin"]);
+        assert_eq!(routes[1].metadata().tags(), ["user"]);
+
+        let handler = routes[0].clone().into_handler();
+        let mut cx = Context::from_waker(Waker::noop());
+        let output = handler("req".to_owned()).as_mut().poll(&mut cx);
+        assert_eq!(output, Poll::Ready("req>inner>outer".to_owned()));
+    }
+
+    #[test]
+    fn it_routes_thread_local_requests() {
+        let prefix = from_fn(|req: Rc<str>, next: Next<Rc<str>, String>| async move {
+            let res = next.run(req.clone()).await;
+            format!("{req}:{res}")
+        });
+        let router = Router::new()
+            .get("/{name}", |req: Rc<str>| async move { req.to_uppercase() })
+            .layer(prefix);
+        let handler = router.into_routes().remove(0).into_handler();
+        let mut cx = Context::from_waker(Waker::noop());
+        let output = handler(Rc::from("req")).as_mut().poll(&mut cx);
+        assert_eq!(output, Poll::Ready("req:REQ".to_owned()));
+    }
+}
@@ -0,0 +1,387 @@
+use super::{RpcRequest, RpcResponse};
+use serde::{Serialize, de::DeserializeOwned};
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering::Relaxed},
+    },
+    time::Duration,
+};
+use toml::Table;
+use zino_core::{
+    JsonValue, Map,
+    application::Agent,
+    bail,
+    error::Error,
+    extension::{JsonObjectExt, TomlTableExt},
+    trace::TraceContext,
+    warn,
+};
+
+/// A JSON-RPC 2.0 client over HTTP.
+///
+/// The requests are sent with the shared HTTP client of the application,
+/// and the `traceparent` and `tracestate` headers are propagated.
+///
+/// ```toml
+/// [rpc-client]
+/// endpoint = "http://127.0.0.1:6080/rpc"
+/// timeout = "10s"
+/// headers = { authorization = "Bearer ..." }
+/// ```
+#[derive(Debug, Clone)]
+pub struct RpcClient {
+    /// Endpoint URL.
+    endpoint: String,
+    /// Custom headers.
+    headers: Map,
+    /// Request timeout.
+    timeout: Option<Duration>,
+    /// Trace context.
+    trace_context: Option<TraceContext>,
+    /// Next request ID.
+    next_id: Arc<AtomicU64>,
+}
+
+impl RpcClient {
+    /// Creates a new instance with the endpoint URL.
+    #[inline]
+    pub fn new(endpoint: impl Into<String>) -> Self {
+        Self {
+            endpoint: endpoint.into(),
+            headers: Map::new(),
+            timeout: None,
+            trace_context: None,
+            next_id: Arc::new(AtomicU64::new(1)),
+        }
+    }
+
+    /// Attempts to create a new instance with the configuration.
+    pub fn try_with_config(config: &Table) -> Result<Self, Error> {
+        let Some(endpoint) = config.get_str("endpoint") else {
+            bail!("field `endpoint` should be specified for the RPC client");
+        };
+
+        let mut client = Self::new(endpoint);
+        if let Some(headers) = config.get_table("headers") {
+            for (key, value) in headers {
+                if let Some(value) = value.as_str() {
+                    client.headers.upsert(key, value);
+                }
+            }
+        }
+        client.timeout = config.get_duration("timeout");
+        Ok(client)
+    }
+
+    /// Sets a custom header for the requests.
+    #[inline]
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.upsert(name, value.into());
+        self
+    }
+
+    /// Sets the timeout for the requests.
+    #[inline]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the trace context to be propagated,
+    /// which is usually obtained by `RequestContext::new_trace_context()`.
+    /// If it is not set, a new trace context is generated for each request.
+    #[inline]
+    pub fn with_trace_context(mut self, trace_context: TraceContext) -> Self {
+        self.trace_context = Some(trace_context);
+        self
+    }
+
+    /// Returns the endpoint URL.
+    #[inline]
+    pub fn endpoint(&self) -> &str {
+        self.endpoint.as_str()
+    }
+
+    /// Calls the method with the params and deserializes the result.
+    pub async fn call<P, T>(&self, method: &str, params: P) -> Result<T, Error>
+    where
+        P: Serialize,
+        T: DeserializeOwned,
+    {
+        let request = RpcRequest::new(method)
+            .with_params(params)?
+            .with_id(self.next_id());
+        let Some(payload) = self.send(request.into_map().into()).await? else {
+            bail!("no response is received for the method `{}`", method);
+        };
+        let JsonValue::Object(map) = payload else {
+            bail!(
+                "the response of the method `{}` should be an object",
+                method
+            );
+        };
+
+        let result = RpcResponse::try_from_map(map)?.into_result()?;
+        serde_json::from_value(result).map_err(Error::from)
+    }
+
+    /// Sends a notification with the params.
+    pub async fn notify<P: Serialize>(&self, method: &str, params: P) -> Result<(), Error> {
+        let request = RpcRequest::new(method).with_params(params)?;
+        self.send(request.into_map().into()).await?;
+        Ok(())
+    }
+
+    /// Sends a batch of requests. The responses are returned in the same order
+    /// as the requests, and there are no responses for the notifications.
+    /// The request IDs can be allocated by [`next_id()`](Self::next_id).
+    pub async fn batch(&self, requests: Vec<RpcRequest>) -> Result<Vec<RpcResponse>, Error> {
+        let mut ids = Vec::with_capacity(requests.len());
+        let mut payload = Vec::with_capacity(requests.len());
+        for request in requests {
+            if let Some(id) = request.id() {
+                ids.push(id.clone());
+            }
+            payload.push(request.into_map().into());
+        }
+        if payload.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let Some(payload) = self.send(JsonValue::Array(payload)).await? else {
+            return Ok(Vec::new());
+        };
+        let JsonValue::Array(responses) = payload else {
+            bail!("the response of a batch should be an array");
+        };
+
+        let mut responses = responses
+            .into_iter()
+            .map(|response| match response {
+                JsonValue::Object(map) => RpcResponse::try_from_map(map),
+                _ => Err(warn!("the response should be an object")),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        responses.sort_by_key(|response| {
+            ids.iter()
+                .position(|id| id == response.id())
+                .unwrap_or(usize::MAX)
+        });
+        Ok(responses)
+    }
+
+    /// Allocates a new request ID.
+    #[inline]
+    pub fn next_id(&self) -> u64 {
+        self.next_id.fetch_add(1, Relaxed)
+    }
+
+    /// Sends the payload and returns the response body if it is nonempty.
+    async fn send(&self, payload: JsonValue) -> Result<Option<JsonValue>, Error> {
+        let mut trace_context = self
+            .trace_context
+            .as_ref()
+            .map(|ctx| ctx.child())
+            .unwrap_or_default();
+        trace_context.record_trace_state();
+
+        let mut options = Map::from_entry("method", "POST");
+        if !self.headers.is_empty() {
+            options.upsert("headers", self.headers.clone());
+        }
+        if let Some(timeout) = self.timeout {
+            options.upsert("timeout", timeout.as_millis() as u64);
+        }
+
+        let response = Agent::request_builder(&self.endpoint, Some(&options))?
+            .header("content-type", "application/json")
+            .header("traceparent", trace_context.traceparent())
+            .header("tracestate", trace_context.tracestate())
+            .body(serde_json::to_vec(&payload)?)
+            .send()
+            .await?
+            .error_for_status()?;
+        let bytes = response.bytes().await?;
+        if bytes.is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some(serde_json::from_slice(&bytes)?))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RpcClient;
+    use crate::RpcRequest;
+    use serde_json::json;
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        thread,
+    };
+    use zino_core::{
+        JsonValue,
+        application::{AppType, Application},
+        schedule::AsyncScheduler,
+        trace::TraceContext,
+    };
+
+    #[derive(Default)]
+    struct TestApp;
+
+    impl Application for TestApp {
+        type Routes = ();
+
+        const APP_TYPE: AppType = AppType::Agent;
+
+        fn register(self, _routes: Self::Routes) -> Self {
+            self
+        }
+
+        fn run_with<T: AsyncScheduler + Send + 'static>(self, _scheduler: T) {}
+    }
+
+    /// Serves one request per response and returns the received headers and bodies.
+    fn serve(responses: Vec<JsonValue>) -> (String, thread::JoinHandle<Vec<(String, JsonValue)>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("fail to bind the listener");
+        let endpoint = format!(
+            "http://{}/rpc",
+            listener
+                .local_addr()
+                .expect("fail to get the local address")
+        );
+        let handle = thread::spawn(move || {
+            let mut requests = Vec::new();
+            for response in responses {
+                let (stream, _) = listener.accept().expect("fail to accept the connection");
+                let mut reader = BufReader::new(stream);
+                let mut headers = String::new();
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).expect("fail to read the line");
+                    if line == "\r\n" {
+                        break;
+                    }
+                    let header = line.to_ascii_lowercase();
+                    if let Some(value) = header.strip_prefix("content-length:") {
+                        content_length = value.trim().parse().expect("fail to parse the length");
+                    }
+                    headers.push_str(&header);
+                }
+
+                let mut body = vec![0; content_length];
+                reader.read_exact(&mut body).expect("fail to read the body");
+                let payload = serde_json::from_slice(&body).expect("fail to parse the body");
+                requests.push((headers, payload));
+
+                let body = if response.is_null() {
+                    String::new()
+                } else {
+                    response.to_string()
+                };
+                let mut stream = reader.into_inner();
+                write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\n\
+                        content-length: {}\r\nconnection: close\r\n\r\n{body}",
+                    body.len()
+                )
+                .expect("fail to write the response");
+            }
+            requests
+        });
+        (endpoint, handle)
+    }
+
+    #[tokio::test]
+    async fn it_calls_remote_methods() {
+        TestApp::boot();
+
+        let (endpoint, handle) = serve(vec![
+            json!({ "jsonrpc": "2.0", "result": 3, "id": 1 }),
+            json!({ "jsonrpc": "2.0", "error": { "code": -32000, "message": "failed" }, "id": 2 }),
+            JsonValue::Null,
+            json!([
+                { "jsonrpc": "2.0", "result": 7, "id": 5 },
+                { "jsonrpc": "2.0", "result": 5, "id": 4 },
+            ]),
+        ]);
+        let trace_context = TraceContext::new();
+        let client = RpcClient::new(endpoint)
+            .header("x-api-key", "secret")
+            .with_trace_context(trace_context.clone());
+
+        let sum: i64 = client
+            .call("add", (1, 2))
+            .await
+            .expect("fail to call the method");
+        assert_eq!(sum, 3);
+
+        let err = client
+            .call::<_, ()>("fail", ())
+            .await
+            .expect_err("the method should fail");
+        assert!(err.to_string().contains("failed"));
+
+        client
+            .notify("log", ["hello"])
+            .await
+            .expect("fail to send the notification");
+
+        let requests = vec![
+            RpcRequest::new("add")
+                .with_params((2, 3))
+                .expect("fail to set the params")
+                .with_id(client.next_id()),
+            RpcRequest::new("add")
+                .with_params((3, 4))
+                .expect("fail to set the params")
+                .with_id(client.next_id()),
+        ];
+        let responses = client
+            .batch(requests)
+            .await
+            .expect("fail to send the batch");
+        assert_eq!(responses.len(), 2);
+        assert_eq!(responses[0].id(), &json!(4));
+        assert_eq!(responses[1].id(), &json!(5));
+
+        let requests = handle.join().expect("fail to join the thread");
+        assert_eq!(requests.len(), 4);
+
+        let (headers, payload) = &requests[0];
+        assert_eq!(
+            payload,
+            &json!({ "jsonrpc": "2.0", "method": "add", "params": [1, 2], "id": 1 })
+        );
+        assert!(headers.starts_with("post /rpc "));
+        assert!(headers.contains("content-type: application/json"));
+        assert!(headers.contains("x-api-key: secret"));
+
+        let traceparent = headers
+            .lines()
+            .find_map(|line| line.strip_prefix("traceparent:"))
+            .expect("the `traceparent` header should exist");
+        let child_context = TraceContext::from_traceparent(traceparent.trim())
+            .expect("fail to parse the `traceparent` header");
+        let child_span_id = child_context
+            .parent_id()
+            .expect("the child span ID should exist");
+        assert_eq!(child_context.trace_id(), trace_context.trace_id());
+        assert_ne!(child_span_id, trace_context.span_id());
+
+        let tracestate = headers
+            .lines()
+            .find_map(|line| line.strip_prefix("tracestate:"))
+            .expect("the `tracestate` header should exist");
+        assert!(tracestate.trim().ends_with(&format!("={child_span_id:x}")));
+
+        let (_, payload) = &requests[2];
+        assert_eq!(payload["method"], "log");
+        assert!(payload.get("id").is_none());
+        assert!(requests[3].1.is_array());
+    }
+}
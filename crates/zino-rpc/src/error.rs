@@ -0,0 +1,254 @@
+use std::fmt;
+use zino_core::{
+    JsonValue, Map, SharedString, error::Error, extension::JsonObjectExt, validation::Validation,
+};
+use zino_http::response::Rejection;
+
+/// An error object defined in the JSON-RPC 2.0 specification.
+///
+/// A handler can return an `RpcError` directly to control the error code,
+/// or return an [`Error`] which is mapped to a standard error code:
+///
+/// - An error converted from an `RpcError` keeps its original code.
+/// - An error with a [`Validation`] context is mapped to an invalid params error.
+/// - An error with a [`Rejection`] context is mapped by the status code:
+///   `400 Bad Request` to an invalid params error,
+///   `405 Method Not Allowed` to a method not found error,
+///   and others to internal errors.
+/// - Other errors are mapped to internal errors.
+///
+/// The error message is never inspected to determine the error code.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RpcError {
+    /// Error code.
+    code: i32,
+    /// A short description of the error.
+    message: SharedString,
+    /// Additional information about the error.
+    data: Option<JsonValue>,
+}
+
+impl RpcError {
+    /// Invalid JSON was received by the server.
+    pub const PARSE_ERROR: i32 = -32700;
+    /// The JSON sent is not a valid request object.
+    pub const INVALID_REQUEST: i32 = -32600;
+    /// The method does not exist or is not available.
+    pub const METHOD_NOT_FOUND: i32 = -32601;
+    /// Invalid method parameters.
+    pub const INVALID_PARAMS: i32 = -32602;
+    /// Internal JSON-RPC error.
+    pub const INTERNAL_ERROR: i32 = -32603;
+
+    /// Creates a new instance with the error code and message.
+    /// The codes from `-32099` to `-32000` are reserved for implementation-defined server errors.
+    #[inline]
+    pub fn new(code: i32, message: impl Into<SharedString>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            data: None,
+        }
+    }
+
+    /// Creates a parse error.
+    #[inline]
+    pub fn parse_error(message: impl Into<SharedString>) -> Self {
+        Self::new(Self::PARSE_ERROR, message)
+    }
+
+    /// Creates an invalid request error.
+    #[inline]
+    pub fn invalid_request(message: impl Into<SharedString>) -> Self {
+        Self::new(Self::INVALID_REQUEST, message)
+    }
+
+    /// Creates a method not found error.
+    #[inline]
+    pub fn method_not_found(method: &str) -> Self {
+        Self::new(
+            Self::METHOD_NOT_FOUND,
+            format!("method `{method}` is not found"),
+        )
+    }
+
+    /// Creates an invalid params error.
+    #[inline]
+    pub fn invalid_params(message: impl Into<SharedString>) -> Self {
+        Self::new(Self::INVALID_PARAMS, message)
+    }
+
+    /// Creates an internal error.
+    #[inline]
+    pub fn internal_error(message: impl Into<SharedString>) -> Self {
+        Self::new(Self::INTERNAL_ERROR, message)
+    }
+
+    /// Sets the additional information about the error.
+    #[inline]
+    pub fn with_data(mut self, data: impl Into<JsonValue>) -> Self {
+        self.data = Some(data.into());
+        self
+    }
+
+    /// Returns the error code.
+    #[inline]
+    pub fn code(&self) -> i32 {
+        self.code
+    }
+
+    /// Returns the error message.
+    #[inline]
+    pub fn message(&self) -> &str {
+        self.message.as_ref()
+    }
+
+    /// Returns a reference to the additional information about the error.
+    #[inline]
+    pub fn data(&self) -> Option<&JsonValue> {
+        self.data.as_ref()
+    }
+
+    /// Attempts to construct an instance from the error object.
+    pub fn from_map(map: &Map) -> Option<Self> {
+        let code = map.get_i32("code")?;
+        let message = map.get_str("message")?.to_owned();
+        let data = map.get("data").filter(|v| !v.is_null()).cloned();
+        Some(Self {
+            code,
+            message: message.into(),
+            data,
+        })
+    }
+
+    /// Converts `self` into an error object.
+    pub fn into_map(self) -> Map {
+        let mut map = Map::new();
+        map.upsert("code", self.code);
+        map.upsert("message", self.message.into_owned());
+        if let Some(data) = self.data {
+            map.upsert("data", data);
+        }
+        map
+    }
+}
+
+impl fmt::Display for RpcError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (code: {})", self.message, self.code)
+    }
+}
+
+impl From<Error> for RpcError {
+    fn from(mut err: Error) -> Self {
+        // Taking the context out drops it if the type is mismatched.
+        if err.has_context::<RpcError>()
+            && let Some(rpc_error) = err.take_context::<RpcError>()
+        {
+            *rpc_error
+        } else if err.has_context::<Validation>()
+            && let Some(validation) = err.take_context::<Validation>()
+        {
+            (*validation).into()
+        } else if err.has_context::<Rejection>()
+            && let Some(rejection) = err.take_context::<Rejection>()
+        {
+            (*rejection).into()
+        } else {
+            Self::internal_error(err.to_string())
+        }
+    }
+}
+
+impl From<Rejection> for RpcError {
+    fn from(rejection: Rejection) -> Self {
+        let status_code = rejection.status_code();
+        let err = rejection.into_error();
+        match status_code {
+            400 => err.into(),
+            405 => Self::new(Self::METHOD_NOT_FOUND, err.to_string()),
+            _ => Self::internal_error(err.to_string()),
+        }
+    }
+}
+
+impl From<Validation> for RpcError {
+    #[inline]
+    fn from(validation: Validation) -> Self {
+        Self::invalid_params(validation.to_string()).with_data(validation.into_map())
+    }
+}
+
+impl From<RpcError> for Error {
+    #[inline]
+    fn from(rpc_error: RpcError) -> Self {
+        let mut err = Error::new(rpc_error.to_string());
+        err.set_context(rpc_error);
+        err
+    }
+}
+
+impl From<serde_json::Error> for RpcError {
+    #[inline]
+    fn from(err: serde_json::Error) -> Self {
+        Self::internal_error(err.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RpcError;
+    use zino_core::{error::Error, validation::Validation};
+    use zino_http::response::Rejection;
+
+    #[test]
+    fn it_maps_errors_to_codes() {
+        let rpc_error = RpcError::new(-32000, "failed").with_data("details");
+        assert_eq!(RpcError::from(Error::from(rpc_error.clone())), rpc_error);
+
+        let validation = Validation::from_entry("name", Error::new("should be nonempty"));
+        let err = RpcError::from(validation);
+        assert_eq!(err.code(), RpcError::INVALID_PARAMS);
+        assert_eq!(
+            err.message(),
+            "invalid value for `name`: should be nonempty"
+        );
+        assert_eq!(
+            err.data().expect("the validation data should exist")["name"],
+            "should be nonempty"
+        );
+
+        let mut err = Error::new("fail to validate the params");
+        err.set_context(Validation::from_entry(
+            "id",
+            Error::new("should be positive"),
+        ));
+        assert_eq!(RpcError::from(err).code(), RpcError::INVALID_PARAMS);
+
+        let rejection = Rejection::from_validation_entry("id", Error::new("should be positive"));
+        let err = RpcError::from(rejection);
+        assert_eq!(err.code(), RpcError::INVALID_PARAMS);
+        assert!(err.data().is_some());
+
+        let rejection = Rejection::method_not_allowed(Error::new("the method is disabled"));
+        let err = RpcError::from(rejection);
+        assert_eq!(err.code(), RpcError::METHOD_NOT_FOUND);
+        assert_eq!(err.message(), "the method is disabled");
+
+        let mut err = Error::new("fail to call the method");
+        err.set_context(Rejection::not_found(Error::new("the user does not exist")));
+        assert_eq!(RpcError::from(err).code(), RpcError::INTERNAL_ERROR);
+
+        // The error messages are not inspected
+        for message in [
+            "400 Bad Request: the `id` should be positive",
+            "invalid value for `id`: should be positive",
+            "405 Method Not Allowed: the method is disabled",
+        ] {
+            let err = RpcError::from(Error::new(message));
+            assert_eq!(err.code(), RpcError::INTERNAL_ERROR);
+            assert_eq!(err.message(), message);
+        }
+    }
+}
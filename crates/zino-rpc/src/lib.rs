@@ -1,3 +1,15 @@
 #![doc = include_str!("../README.md")]
 #![doc(html_favicon_url = "https://zino.cc/assets/zino-logo.png")]
 #![doc(html_logo_url = "https://zino.cc/assets/zino-logo.svg")]
+
+mod client;
+mod error;
+mod registry;
+mod request;
+mod response;
+
+pub use client::RpcClient;
+pub use error::RpcError;
+pub use registry::RpcRegistry;
+pub use request::RpcRequest;
+pub use response::RpcResponse;
@@ -0,0 +1,263 @@
+use super::{RpcError, RpcRequest, RpcResponse};
+use futures::future;
+use serde::{Serialize, de::DeserializeOwned};
+use std::{collections::HashMap, future::Future};
+use zino_core::{BoxFuture, JsonValue};
+use zino_http::{
+    request::RequestContext,
+    response::{Response, ResponseCode},
+};
+
+/// A boxed method handler.
+type RpcMethod =
+    Box<dyn Fn(JsonValue) -> BoxFuture<'static, Result<JsonValue, RpcError>> + Send + Sync>;
+
+/// A registry of the JSON-RPC methods.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use zino::{Request, Response, Result, prelude::*};
+/// use zino_rpc::{RpcError, RpcRegistry};
+///
+/// static RPC_REGISTRY: LazyLock<RpcRegistry> = LazyLock::new(|| {
+///     RpcRegistry::new()
+///         .register("add", |(a, b): (i64, i64)| async move { Ok::<_, Error>(a + b) })
+///         .register("divide", |(a, b): (i64, i64)| async move {
+///             if b == 0 {
+///                 return Err(RpcError::invalid_params("the divisor should be nonzero"));
+///             }
+///             Ok(a / b)
+///         })
+/// });
+///
+/// pub async fn rpc(mut req: Request) -> Result {
+///     let res: Response = RPC_REGISTRY.handle(&mut req).await;
+///     Ok(res.into())
+/// }
+/// ```
+#[derive(Default)]
+pub struct RpcRegistry {
+    /// Registered methods.
+    methods: HashMap<String, RpcMethod>,
+}
+
+impl RpcRegistry {
+    /// Creates a new instance.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a method with the handler. The parameters are deserialized from
+    /// the `params` member, which will be `null` if it is absent.
+    /// Failures of the deserialization are reported as invalid params errors.
+    pub fn register<P, T, E, F, Fut>(mut self, method: impl Into<String>, handler: F) -> Self
+    where
+        P: DeserializeOwned + 'static,
+        T: Serialize + 'static,
+        E: Into<RpcError> + 'static,
+        F: Fn(P) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<T, E>> + Send + 'static,
+    {
+        let method_handler: RpcMethod =
+            Box::new(move |params| match serde_json::from_value::<P>(params) {
+                Ok(params) => {
+                    let future = handler(params);
+                    Box::pin(async move {
+                        let value = future.await.map_err(|err| err.into())?;
+                        Ok(serde_json::to_value(value)?)
+                    })
+                }
+                Err(err) => {
+                    let err = RpcError::invalid_params(err.to_string());
+                    Box::pin(future::ready(Err(err)))
+                }
+            });
+        self.methods.insert(method.into(), method_handler);
+        self
+    }
+
+    /// Returns `true` if the method has been registered.
+    #[inline]
+    pub fn contains(&self, method: &str) -> bool {
+        self.methods.contains_key(method)
+    }
+
+    /// Returns an iterator visiting all the method names in arbitrary order.
+    #[inline]
+    pub fn method_names(&self) -> impl Iterator<Item = &str> {
+        self.methods.keys().map(|method| method.as_str())
+    }
+
+    /// Calls the method for the request.
+    /// It returns `None` if the request is a notification.
+    pub async fn call(&self, request: RpcRequest) -> Option<RpcResponse> {
+        let (method, params, id) = request.into_parts();
+        let result = if let Some(handler) = self.methods.get(&method) {
+            handler(params.unwrap_or_default()).await
+        } else {
+            Err(RpcError::method_not_found(&method))
+        };
+        if let Some(id) = id {
+            match result {
+                Ok(value) => Some(RpcResponse::success(id, value)),
+                Err(err) => Some(RpcResponse::error(id, err)),
+            }
+        } else {
+            if let Err(err) = result {
+                tracing::warn!(method, "fail to handle the notification: {err}");
+            }
+            None
+        }
+    }
+
+    /// Dispatches a single request or a batch of requests.
+    /// It returns `None` if there is nothing to respond,
+    /// i.e. all the requests are notifications.
+    pub async fn dispatch(&self, payload: JsonValue) -> Option<JsonValue> {
+        match payload {
+            JsonValue::Array(requests) if !requests.is_empty() => {
+                let responses = future::join_all(requests.into_iter().map(|request| async {
+                    match parse_request(request) {
+                        Ok(request) => self.call(request).await,
+                        Err(response) => Some(response),
+                    }
+                }))
+                .await
+                .into_iter()
+                .flatten()
+                .map(|response| response.into_map().into())
+                .collect::<Vec<JsonValue>>();
+                (!responses.is_empty()).then_some(responses.into())
+            }
+            JsonValue::Array(_) => {
+                let err = RpcError::invalid_request("the batch should not be empty");
+                Some(RpcResponse::error(JsonValue::Null, err).into_map().into())
+            }
+            request => {
+                let response = match parse_request(request) {
+                    Ok(request) => self.call(request).await?,
+                    Err(response) => response,
+                };
+                Some(response.into_map().into())
+            }
+        }
+    }
+
+    /// Dispatches the requests in the raw bytes.
+    pub async fn dispatch_bytes(&self, bytes: &[u8]) -> Option<JsonValue> {
+        match serde_json::from_slice::<JsonValue>(bytes) {
+            Ok(payload) => self.dispatch(payload).await,
+            Err(err) => {
+                let err = RpcError::parse_error(err.to_string());
+                Some(RpcResponse::error(JsonValue::Null, err).into_map().into())
+            }
+        }
+    }
+
+    /// Handles the JSON-RPC requests in the HTTP request body,
+    /// which makes the registry mountable as a handler of any cluster.
+    ///
+    /// The responses are sent as a JSON body with the status `200 OK`,
+    /// and an empty body with the status `204 No Content` is sent for the notifications.
+    pub async fn handle<Ctx, S>(&self, req: &mut Ctx) -> Response<S>
+    where
+        Ctx: RequestContext,
+        S: ResponseCode,
+    {
+        let output = match req.read_body_bytes().await {
+            Ok(bytes) => self.dispatch_bytes(&bytes).await,
+            Err(err) => {
+                let err = RpcError::parse_error(err.to_string());
+                Some(RpcResponse::error(JsonValue::Null, err).into_map().into())
+            }
+        };
+        let mut res = Response::with_context(S::OK, req);
+        if let Some(output) = output {
+            res.set_json_response(output);
+        } else {
+            res.set_status_code(204u16);
+            res.set_text_response(String::new());
+        }
+        res
+    }
+}
+
+/// Parses a request object, or returns an error response if it is invalid.
+fn parse_request(request: JsonValue) -> Result<RpcRequest, RpcResponse> {
+    if let JsonValue::Object(map) = request {
+        RpcRequest::try_from_map(map).map_err(|(err, id)| RpcResponse::error(id, err))
+    } else {
+        let err = RpcError::invalid_request("the request should be an object");
+        Err(RpcResponse::error(JsonValue::Null, err))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RpcError, RpcRegistry};
+    use futures::executor::block_on;
+    use serde_json::json;
+    use zino_core::error::Error;
+    use zino_http::response::Rejection;
+
+    #[test]
+    fn it_dispatches_batch_requests() {
+        let registry = RpcRegistry::new()
+            .register("add", |(a, b): (i64, i64)| async move {
+                Ok::<_, RpcError>(a + b)
+            })
+            .register("fail", |_: ()| async move {
+                Err::<(), _>(RpcError::new(-32000, "failed"))
+            })
+            .register("divide", |(a, b): (i64, i64)| async move {
+                if b == 0 {
+                    let err = Error::new("should be nonzero");
+                    return Err(Rejection::from_validation_entry("b", err));
+                }
+                Ok(a / b)
+            });
+        let payload = json!([
+            { "jsonrpc": "2.0", "method": "add", "params": [1, 2], "id": 1 },
+            { "jsonrpc": "2.0", "method": "add", "params": [1, 2] },
+            { "jsonrpc": "2.0", "method": "add", "params": ["a"], "id": 2 },
+            { "jsonrpc": "2.0", "method": "missing", "id": "3" },
+            { "jsonrpc": "2.0", "method": "fail", "id": 4 },
+            { "jsonrpc": "1.0", "method": "add", "id": 5 },
+            1,
+        ]);
+        let output = block_on(registry.dispatch(payload)).unwrap_or_default();
+        let responses = output.as_array().expect("responses should be an array");
+        assert_eq!(responses.len(), 6);
+        assert_eq!(
+            responses[0],
+            json!({ "jsonrpc": "2.0", "result": 3, "id": 1 })
+        );
+        assert_eq!(responses[1]["error"]["code"], RpcError::INVALID_PARAMS);
+        assert_eq!(responses[1]["id"], 2);
+        assert_eq!(responses[2]["error"]["code"], RpcError::METHOD_NOT_FOUND);
+        assert_eq!(responses[2]["id"], "3");
+        assert_eq!(
+            responses[3]["error"],
+            json!({ "code": -32000, "message": "failed" })
+        );
+        assert_eq!(responses[4]["error"]["code"], RpcError::INVALID_REQUEST);
+        assert_eq!(responses[4]["id"], 5);
+        assert_eq!(responses[5]["error"]["code"], RpcError::INVALID_REQUEST);
+        assert!(responses[5]["id"].is_null());
+
+        let request = json!({ "jsonrpc": "2.0", "method": "divide", "params": [1, 0], "id": 6 });
+        let output = block_on(registry.dispatch(request)).unwrap_or_default();
+        assert_eq!(output["error"]["code"], RpcError::INVALID_PARAMS);
+
+        let notification = json!({ "jsonrpc": "2.0", "method": "add", "params": [1, 2] });
+        assert_eq!(block_on(registry.dispatch(notification)), None);
+
+        let output = block_on(registry.dispatch_bytes(b"[{"));
+        assert_eq!(
+            output.expect("the output should exist")["error"]["code"],
+            RpcError::PARSE_ERROR
+        );
+    }
+}
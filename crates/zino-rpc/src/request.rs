@@ -0,0 +1,121 @@
+use super::RpcError;
+use serde::Serialize;
+use zino_core::{JsonValue, Map, error::Error, extension::JsonObjectExt};
+
+/// A request object defined in the JSON-RPC 2.0 specification.
+/// It is a notification if the `id` member is absent.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RpcRequest {
+    /// Method name.
+    method: String,
+    /// Parameters of the method, which should be an array or an object.
+    params: Option<JsonValue>,
+    /// Request ID, which should be a string, a number or `null`.
+    id: Option<JsonValue>,
+}
+
+impl RpcRequest {
+    /// Creates a new notification with the method name.
+    #[inline]
+    pub fn new(method: impl Into<String>) -> Self {
+        Self {
+            method: method.into(),
+            params: None,
+            id: None,
+        }
+    }
+
+    /// Sets the parameters by serializing the value into an array or an object.
+    pub fn with_params<T: Serialize>(mut self, params: T) -> Result<Self, Error> {
+        let params = serde_json::to_value(params)?;
+        if !params.is_null() {
+            self.params = Some(params);
+        }
+        Ok(self)
+    }
+
+    /// Sets the request ID.
+    #[inline]
+    pub fn with_id(mut self, id: impl Into<JsonValue>) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    /// Returns the method name.
+    #[inline]
+    pub fn method(&self) -> &str {
+        self.method.as_str()
+    }
+
+    /// Returns a reference to the parameters.
+    #[inline]
+    pub fn params(&self) -> Option<&JsonValue> {
+        self.params.as_ref()
+    }
+
+    /// Returns a reference to the request ID.
+    #[inline]
+    pub fn id(&self) -> Option<&JsonValue> {
+        self.id.as_ref()
+    }
+
+    /// Returns `true` if the request is a notification.
+    #[inline]
+    pub fn is_notification(&self) -> bool {
+        self.id.is_none()
+    }
+
+    /// Attempts to construct an instance from the request object.
+    /// If it is invalid, the error is returned with the request ID if it can be determined.
+    pub fn try_from_map(mut map: Map) -> Result<Self, (RpcError, JsonValue)> {
+        let id = map.remove("id");
+        if id
+            .as_ref()
+            .is_some_and(|id| !(id.is_null() || id.is_string() || id.is_number()))
+        {
+            let err = RpcError::invalid_request("the `id` should be a string, a number or null");
+            return Err((err, JsonValue::Null));
+        }
+
+        let request_id = id.clone().unwrap_or_default();
+        if map.get_str("jsonrpc") != Some("2.0") {
+            let err = RpcError::invalid_request("the `jsonrpc` version should be `2.0`");
+            return Err((err, request_id));
+        }
+
+        let Some(JsonValue::String(method)) = map.remove("method") else {
+            let err = RpcError::invalid_request("the `method` should be a string");
+            return Err((err, request_id));
+        };
+
+        let params = map.remove("params");
+        if params
+            .as_ref()
+            .is_some_and(|params| !(params.is_array() || params.is_object()))
+        {
+            let err = RpcError::invalid_request("the `params` should be an array or an object");
+            return Err((err, request_id));
+        }
+        Ok(Self { method, params, id })
+    }
+
+    /// Converts `self` into a request object.
+    pub fn into_map(self) -> Map {
+        let mut map = Map::new();
+        map.upsert("jsonrpc", "2.0");
+        map.upsert("method", self.method);
+        if let Some(params) = self.params {
+            map.upsert("params", params);
+        }
+        if let Some(id) = self.id {
+            map.upsert("id", id);
+        }
+        map
+    }
+
+    /// Consumes `self` and returns the method name, the parameters and the request ID.
+    #[inline]
+    pub(crate) fn into_parts(self) -> (String, Option<JsonValue>, Option<JsonValue>) {
+        (self.method, self.params, self.id)
+    }
+}
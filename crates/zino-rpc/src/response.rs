@@ -0,0 +1,90 @@
+use super::RpcError;
+use zino_core::{JsonValue, Map, error::Error, extension::JsonObjectExt, warn};
+
+/// A response object defined in the JSON-RPC 2.0 specification.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RpcResponse {
+    /// Request ID, which is `null` if it can not be determined.
+    id: JsonValue,
+    /// Result of the method or the error object.
+    result: Result<JsonValue, RpcError>,
+}
+
+impl RpcResponse {
+    /// Creates a new response with the result.
+    #[inline]
+    pub fn success(id: impl Into<JsonValue>, result: impl Into<JsonValue>) -> Self {
+        Self {
+            id: id.into(),
+            result: Ok(result.into()),
+        }
+    }
+
+    /// Creates a new response with the error object.
+    #[inline]
+    pub fn error(id: impl Into<JsonValue>, error: RpcError) -> Self {
+        Self {
+            id: id.into(),
+            result: Err(error),
+        }
+    }
+
+    /// Returns a reference to the request ID.
+    #[inline]
+    pub fn id(&self) -> &JsonValue {
+        &self.id
+    }
+
+    /// Returns `true` if the response is successful.
+    #[inline]
+    pub fn is_success(&self) -> bool {
+        self.result.is_ok()
+    }
+
+    /// Returns a reference to the result.
+    #[inline]
+    pub fn result(&self) -> Result<&JsonValue, &RpcError> {
+        self.result.as_ref()
+    }
+
+    /// Consumes `self` and returns the result.
+    #[inline]
+    pub fn into_result(self) -> Result<JsonValue, RpcError> {
+        self.result
+    }
+
+    /// Attempts to construct an instance from the response object.
+    pub fn try_from_map(mut map: Map) -> Result<Self, Error> {
+        if map.get_str("jsonrpc") != Some("2.0") {
+            return Err(warn!(
+                "the `jsonrpc` version of the response should be `2.0`"
+            ));
+        }
+
+        let id = map.remove("id").unwrap_or_default();
+        let result = if let Some(error) = map.get_object("error") {
+            let error = RpcError::from_map(error)
+                .ok_or_else(|| warn!("invalid error object in the response"))?;
+            Err(error)
+        } else if let Some(result) = map.remove("result") {
+            Ok(result)
+        } else {
+            return Err(warn!(
+                "the response should contain a `result` or an `error`"
+            ));
+        };
+        Ok(Self { id, result })
+    }
+
+    /// Converts `self` into a response object.
+    pub fn into_map(self) -> Map {
+        let mut map = Map::new();
+        map.upsert("jsonrpc", "2.0");
+        match self.result {
+            Ok(result) => map.upsert("result", result),
+            Err(error) => map.upsert("error", error.into_map()),
+        };
+        map.upsert("id", self.id);
+        map
+    }
+}
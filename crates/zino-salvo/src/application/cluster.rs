@@ -0,0 +1,469 @@
+use crate::{RouterConfigure, from_fn, middleware, response::build_http_response};
+use salvo::{
+    Depot, FlowCtrl, Handler, Request, Response, Router, Server, Service, async_trait,
+    catcher::Catcher,
+    compression::{Compression, CompressionLevel},
+    conn::{Listener, TcpListener},
+    http::StatusCode,
+    routing::filters::MethodFilter,
+    serve_static::{StaticDir, StaticFile},
+    size_limiter,
+    timeout::Timeout,
+    writing::Text,
+};
+use std::{fs, mem, sync::Arc, time::Duration};
+use tokio::{runtime::Builder, time};
+use utoipa_rapidoc::RapiDoc;
+use zino_core::{
+    application::{AppType, Application, Plugin, ServerTag, Shutdown},
+    error::Error,
+    extension::TomlTableExt,
+    schedule::AsyncScheduler,
+};
+use zino_http::{request::RequestContext, response::Rejection};
+use zino_router::{ParamType, RouteTable};
+
+/// Framework-neutral routes.
+type NeutralRouter = zino_router::Router<crate::Request, crate::Result>;
+
+/// An HTTP server cluster.
+#[derive(Default)]
+pub struct Cluster {
+    /// Custom plugins.
+    custom_plugins: Vec<Plugin>,
+    /// Default routes.
+    default_routes: Vec<RouterConfigure>,
+    /// Tagged routes.
+    tagged_routes: Vec<(ServerTag, Vec<RouterConfigure>)>,
+    /// Default framework-neutral routes.
+    default_routers: Vec<NeutralRouter>,
+    /// Tagged framework-neutral routes.
+    tagged_routers: Vec<(ServerTag, NeutralRouter)>,
+}
+
+impl Cluster {
+    /// Registers the framework-neutral routes.
+    pub fn register_router(mut self, router: NeutralRouter) -> Self {
+        RouteTable::record(&router);
+        self.default_routers.push(router);
+        self
+    }
+
+    /// Registers the framework-neutral routes with a server tag.
+    pub fn register_router_with(mut self, server_tag: ServerTag, router: NeutralRouter) -> Self {
+        RouteTable::record(&router);
+        self.tagged_routers.push((server_tag, router));
+        self
+    }
+}
+
+impl Application for Cluster {
+    type Routes = Vec<RouterConfigure>;
+
+    const APP_TYPE: AppType = AppType::Server;
+
+    #[inline]
+    fn register(mut self, mut routes: Self::Routes) -> Self {
+        self.default_routes.append(&mut routes);
+        self
+    }
+
+    #[inline]
+    fn register_with(mut self, server_tag: ServerTag, routes: Self::Routes) -> Self {
+        self.tagged_routes.push((server_tag, routes));
+        self
+    }
+
+    #[inline]
+    fn add_plugin(mut self, plugin: Plugin) -> Self {
+        self.custom_plugins.push(plugin);
+        self
+    }
+
+    fn run_with<T: AsyncScheduler + Send + 'static>(self, mut scheduler: T) {
+        let runtime = Builder::new_multi_thread()
+            .thread_keep_alive(Duration::from_secs(60))
+            .thread_stack_size(2 * 1024 * 1024)
+            .global_queue_interval(61)
+            .enable_all()
+            .build()
+            .expect("fail to build Tokio runtime for `SalvoCluster`");
+        let app_env = Self::env();
+        runtime.block_on(async {
+            #[cfg(feature = "orm")]
+            {
+                zino_orm::GlobalPool::connect_all().await;
+                zino_orm::GlobalPool::migrate_all().await;
+                Shutdown::add_hook(
+                    "orm",
+                    Box::pin(async {
+                        zino_orm::GlobalPool::close_all().await;
+                        Ok(())
+                    }),
+                );
+            }
+            Self::load().await;
+            app_env.load_plugins(self.custom_plugins).await;
+        });
+        runtime.spawn(Shutdown::listen());
+
+        #[cfg(feature = "orm")]
+        runtime.spawn(zino_orm::GlobalPool::monitor_replicas());
+
+        let scheduler_task = scheduler.is_ready().then(|| {
+            if scheduler.is_blocking() {
+                runtime.spawn(async move {
+                    if let Err(err) = scheduler.run().await {
+                        tracing::error!("fail to run the async scheduler: {err}");
+                    }
+                })
+            } else {
+                runtime.spawn(async move {
+                    // Stops the scheduler after the current jobs on the graceful shutdown.
+                    while !Shutdown::is_triggered() {
+                        scheduler.tick().await;
+
+                        // Cannot use `std::thread::sleep` because it blocks the Tokio runtime.
+                        if let Some(duration) = scheduler.time_till_next_job() {
+                            tokio::select! {
+                                _ = time::sleep(duration) => {},
+                                _ = Shutdown::wait() => {},
+                            }
+                        }
+                    }
+                    // Waits for the jobs running in the background.
+                    scheduler.tick().await;
+                })
+            }
+        });
+
+        runtime.block_on(async {
+            let default_routes = self.default_routes;
+            let tagged_routes = self.tagged_routes;
+            let default_routers = self.default_routers;
+            let tagged_routers = self.tagged_routers;
+            let app_state = Self::shared_state();
+            let app_name = Self::name();
+            let app_version = Self::version();
+            let shutdown_timeout = Self::shutdown_timeout();
+            let listeners = app_state.listeners();
+            let has_debug_server = listeners.iter().any(|listener| listener.0.is_debug());
+            let servers = listeners.into_iter().map(|listener| {
+                let server_tag = listener.0;
+                let addr = listener.1;
+                tracing::warn!(
+                    server_tag = server_tag.as_str(),
+                    app_env = app_env.as_str(),
+                    app_name,
+                    app_version,
+                    "listen on `{addr}`",
+                );
+
+                // Server config
+                let mut auto_routing = true;
+                let mut public_dir = "public";
+                let mut public_route_prefix = "/public";
+                let mut body_limit = 128 * 1024 * 1024; // 128MB
+                let mut request_timeout = Duration::from_secs(60); // 60 seconds
+                let mut keep_alive_timeout = 75; // 75 seconds
+                if let Some(config) = app_state.get_config("server") {
+                    if let Some(auto) = config.get_bool("auto-routing") {
+                        auto_routing = auto;
+                    }
+                    if let Some(dir) = config.get_str("public-dir") {
+                        public_dir = dir;
+                    }
+                    if let Some(route_prefix) = config.get_str("public-route-prefix") {
+                        public_route_prefix = route_prefix;
+                    }
+                    if let Some(limit) = config.get_u64("body-limit") {
+                        body_limit = limit;
+                    }
+                    if let Some(timeout) = config.get_duration("request-timeout") {
+                        request_timeout = timeout;
+                    }
+                    if let Some(timeout) = config.get_duration("keep-alive-timeout") {
+                        keep_alive_timeout = timeout.as_secs();
+                    }
+                }
+
+                let mut app = Router::new();
+                let public_dir = Self::parse_path(public_dir);
+                let not_found_file = public_dir.join("404.html");
+                if public_dir.exists() {
+                    let index_file = public_dir.join("index.html");
+                    if index_file.exists() {
+                        app = app.get(StaticFile::new(index_file));
+                    }
+                    if auto_routing && let Ok(entries) = fs::read_dir(&public_dir) {
+                        for entry in entries {
+                            if let Some(entry) = entry
+                                .ok()
+                                .filter(|dir| dir.file_type().is_ok_and(|ty| ty.is_file()))
+                                && let Some(file_name) = entry.file_name().to_str()
+                            {
+                                let serve_file = StaticFile::new(entry.path());
+                                app = app.push(Router::with_path(file_name).get(serve_file));
+                            }
+                        }
+                    }
+
+                    let serve_dir = StaticDir::new([public_dir]).defaults("index.html");
+                    let route_path =
+                        format!("{}/{{**path}}", public_route_prefix.trim_matches('/'));
+                    let mut serve_dir_route = Router::with_path(route_path);
+                    if auto_routing {
+                        serve_dir_route = serve_dir_route.hoop(middleware::StaticPages);
+                    }
+                    app = app.push(serve_dir_route.get(serve_dir));
+                    tracing::info!(
+                        "Static pages `{public_route_prefix}/**` are registered for `{addr}`"
+                    );
+                }
+                for route in &default_routes {
+                    app = route(app);
+                }
+                for (tag, routes) in &tagged_routes {
+                    if tag == &server_tag || server_tag.is_debug() {
+                        for route in routes {
+                            app = route(app);
+                        }
+                    }
+                }
+                for router in &default_routers {
+                    app = push_routes(app, router);
+                }
+                for (tag, router) in &tagged_routers {
+                    if tag == &server_tag || server_tag.is_debug() {
+                        app = push_routes(app, router);
+                    }
+                }
+
+                // OpenAPI docs
+                let is_docs_server = if has_debug_server {
+                    server_tag.is_debug()
+                } else {
+                    server_tag.is_main()
+                };
+                if is_docs_server {
+                    let openapi = zino_openapi::openapi();
+                    let openapi_json = openapi.to_json().unwrap_or_default();
+                    let mut rapidoc_path = "/rapidoc";
+                    let mut rapidoc = RapiDoc::new("/api-docs/openapi.json");
+                    let mut show_docs = true;
+                    let mut serve_openapi = true;
+                    if let Some(config) = app_state.get_config("openapi") {
+                        if config.get_bool("show-docs") == Some(false) {
+                            show_docs = false;
+                        }
+                        if let Some(path) = config.get_str("rapidoc-route") {
+                            rapidoc_path = path;
+                        }
+
+                        // If the `spec-url` has been configured, the user should
+                        // provide the generated OpenAPI object with a derivation.
+                        if let Some(url) = config.get_str("spec-url") {
+                            rapidoc = RapiDoc::new(url);
+                            serve_openapi = false;
+                        }
+                        if let Some(custom_html) = config.get_str("custom-html") {
+                            let custom_html_file = Self::parse_path(custom_html);
+                            if let Ok(html) = fs::read_to_string(custom_html_file) {
+                                rapidoc = rapidoc.custom_html(html);
+                            }
+                        }
+                    }
+                    if show_docs {
+                        if serve_openapi {
+                            let openapi_route = Router::with_path("api-docs/openapi.json").get(
+                                crate::from_fn(move |_req| {
+                                    let openapi_json = openapi_json.clone();
+                                    async move { Text::Json(openapi_json) }
+                                }),
+                            );
+                            app = app.push(openapi_route);
+                        }
+
+                        let rapidoc_html = rapidoc.to_html();
+                        let rapidoc_route = Router::with_path(rapidoc_path.trim_matches('/')).get(
+                            crate::from_fn(move |_req| {
+                                let rapidoc_html = rapidoc_html.clone();
+                                async move { Text::Html(rapidoc_html) }
+                            }),
+                        );
+                        app = app.push(rapidoc_route);
+                        tracing::info!(
+                            "RapiDoc router `{rapidoc_path}` is registered for `{addr}`"
+                        );
+                    }
+                }
+
+                let not_found_file = not_found_file
+                    .exists()
+                    .then(|| StaticFile::new(not_found_file));
+                let catcher = Catcher::default().hoop(NotFound { not_found_file });
+                let service = Service::new(app)
+                    .catcher(catcher)
+                    .hoop(middleware::KeepAlive::new(keep_alive_timeout))
+                    .hoop(size_limiter::max_size(body_limit))
+                    .hoop(
+                        Compression::new()
+                            .disable_all()
+                            .enable_gzip(CompressionLevel::Default),
+                    )
+                    .hoop(middleware::TracingLogger::new())
+                    .hoop(middleware::cors_middleware())
+                    .hoop(middleware::RequestContextInitializer)
+                    .hoop(middleware::ETagFinalizer)
+                    .hoop(middleware::CatchPanic)
+                    .hoop(Timeout::new(request_timeout));
+                Box::pin(async move {
+                    let acceptor = TcpListener::new(addr)
+                        .try_bind()
+                        .await
+                        .unwrap_or_else(|err| panic!("fail to listen on {addr}: {err}"));
+                    let server = Server::new(acceptor);
+                    let handle = server.handle();
+                    tokio::spawn(async move {
+                        Shutdown::wait().await;
+                        handle.stop_graceful(Some(shutdown_timeout));
+                    });
+                    server.try_serve(service).await
+                })
+            });
+            for result in futures::future::join_all(servers).await {
+                if let Err(err) = result {
+                    tracing::error!("salvo server error: {err}");
+                }
+            }
+            if let Some(task) = scheduler_task
+                && time::timeout(shutdown_timeout, task).await.is_err()
+            {
+                tracing::warn!("timed out to wait for the async scheduler");
+            }
+            Self::teardown().await;
+        });
+    }
+}
+
+/// A handler for the `404 Not Found` responses.
+struct NotFound {
+    /// Custom `404.html` file in the public directory.
+    not_found_file: Option<StaticFile>,
+}
+
+#[async_trait]
+impl Handler for NotFound {
+    async fn handle(
+        &self,
+        req: &mut Request,
+        depot: &mut Depot,
+        res: &mut Response,
+        ctrl: &mut FlowCtrl,
+    ) {
+        if res.status_code == Some(StatusCode::NOT_FOUND) {
+            if let Some(not_found_file) = &self.not_found_file {
+                not_found_file.handle(req, depot, res, ctrl).await;
+            } else {
+                let extractor = crate::Request::from(mem::take(req));
+                let response = crate::Response::new(StatusCode::NOT_FOUND).context(&extractor);
+                *req = extractor.into();
+                build_http_response(response, res);
+            }
+            ctrl.skip_rest();
+        }
+    }
+}
+
+/// Pushes the framework-neutral routes into the salvo router.
+fn push_routes(mut app: Router, router: &NeutralRouter) -> Router {
+    for route in router.routes().iter().cloned() {
+        let path = route.pattern().format_with(|name, ty| {
+            if ty == ParamType::Wildcard {
+                format!("{{**{name}}}")
+            } else {
+                format!("{{{name}}}")
+            }
+        });
+        let method_filter = MethodFilter::new(route.method().clone());
+        let pattern = Arc::new(route.pattern().clone());
+        let handler = route.into_handler();
+        let goal = from_fn(move |req: crate::Request| {
+            let pattern = pattern.clone();
+            let handler = handler.clone();
+            async move {
+                // Typed captures are checked here since salvo does not know the types.
+                if pattern.is_typed() && pattern.matches(req.request_path()).is_none() {
+                    let err = Error::new(format!("invalid path parameters for `{pattern}`"));
+                    return Err(Rejection::not_found(err).context(&req).into());
+                }
+                handler(req).await
+            }
+        });
+        let route = Router::with_path(path.trim_start_matches('/'))
+            .filter(method_filter)
+            .goal(goal);
+        app = app.push(route);
+    }
+    app
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{NeutralRouter, push_routes};
+    use crate::{Request, Response};
+    use salvo::{
+        Router, Service,
+        http::StatusCode,
+        test::{ResponseExt, TestClient},
+    };
+
+    #[tokio::test]
+    async fn it_pushes_neutral_routes() {
+        let router = NeutralRouter::new()
+            .get("/users/{id:u64}", |req: Request| async move {
+                let mut res = Response::new(StatusCode::OK);
+                res.set_bytes_data(
+                    req.param::<String>("id")
+                        .expect("the path parameter should exist"),
+                );
+                Ok(res.into())
+            })
+            .get("/files/{*path}", |req: Request| async move {
+                let mut res = Response::new(StatusCode::OK);
+                res.set_bytes_data(
+                    req.param::<String>("path")
+                        .expect("the path parameter should exist"),
+                );
+                Ok(res.into())
+            });
+        let service = Service::new(push_routes(Router::new(), &router));
+
+        let mut res = TestClient::get("http://127.0.0.1:6080/users/42")
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::OK));
+        assert_eq!(
+            res.take_string().await.expect("fail to read the body"),
+            "42"
+        );
+
+        let res = TestClient::get("http://127.0.0.1:6080/users/alice")
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::NOT_FOUND));
+
+        let mut res = TestClient::get("http://127.0.0.1:6080/files/a/b.txt")
+            .send(&service)
+            .await;
+        assert_eq!(
+            res.take_string().await.expect("fail to read the body"),
+            "a/b.txt"
+        );
+
+        let res = TestClient::post("http://127.0.0.1:6080/users/42")
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::NOT_FOUND));
+    }
+}
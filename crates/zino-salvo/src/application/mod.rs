@@ -0,0 +1,3 @@
+mod cluster;
+
+pub use cluster::Cluster;
@@ -0,0 +1,56 @@
+use crate::Extractor;
+use salvo::{Depot, FlowCtrl, Handler, Request, Response, async_trait, writing::Writer};
+use std::{future::Future, mem};
+
+/// A handler for an async function which takes the request extractor as the only argument,
+/// such as the controllers of `zino`.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use salvo::Router;
+/// use zino::DefaultController;
+/// use zino_salvo::from_fn;
+///
+/// pub fn user_routes(router: Router) -> Router {
+///     router.push(
+///         Router::with_path("user")
+///             .push(Router::with_path("new").post(from_fn(User::new)))
+///             .push(Router::with_path("list").get(from_fn(User::list)))
+///             .push(Router::with_path("{id}/view").get(from_fn(User::view))),
+///     )
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct FnHandler<F>(F);
+
+/// Creates a new handler for the async function.
+#[inline]
+pub fn from_fn<F, Fut>(f: F) -> FnHandler<F>
+where
+    F: Fn(crate::Request) -> Fut + Send + Sync + 'static,
+    Fut: Future + Send,
+    Fut::Output: Writer + Send,
+{
+    FnHandler(f)
+}
+
+#[async_trait]
+impl<F, Fut> Handler for FnHandler<F>
+where
+    F: Fn(crate::Request) -> Fut + Send + Sync + 'static,
+    Fut: Future + Send,
+    Fut::Output: Writer + Send,
+{
+    async fn handle(
+        &self,
+        req: &mut Request,
+        depot: &mut Depot,
+        res: &mut Response,
+        _ctrl: &mut FlowCtrl,
+    ) {
+        let extractor = Extractor::from(mem::take(req));
+        let output = (self.0)(extractor).await;
+        output.write(req, depot, res).await;
+    }
+}
@@ -2,3 +2,27 @@
 #![doc = include_str!("../README.md")]
 #![doc(html_favicon_url = "https://zino.cc/assets/zino-logo.png")]
 #![doc(html_logo_url = "https://zino.cc/assets/zino-logo.svg")]
+
+mod application;
+mod handler;
+mod middleware;
+mod request;
+mod response;
+
+pub use application::Cluster;
+pub use handler::{FnHandler, from_fn};
+pub use middleware::MiddlewareAdapter;
+pub use request::Extractor;
+pub use response::{SalvoRejection, SalvoResponse};
+
+/// Router configure.
+pub type RouterConfigure = fn(router: salvo::Router) -> salvo::Router;
+
+/// A specialized request extractor.
+pub type Request = Extractor<salvo::Request>;
+
+/// A specialized response.
+pub type Response = zino_http::response::Response<salvo::http::StatusCode>;
+
+/// A specialized `Result` type.
+pub type Result<T = SalvoResponse> = std::result::Result<T, SalvoRejection>;
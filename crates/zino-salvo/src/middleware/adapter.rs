@@ -0,0 +1,59 @@
+use crate::SalvoRejection;
+use salvo::{
+    Depot, FlowCtrl, Handler, Request, Response, async_trait,
+    http::header::{HeaderName, HeaderValue},
+    writing::Scribe,
+};
+use std::{mem, sync::Arc};
+use zino_middleware::Middleware;
+
+/// An adapter which applies a framework-neutral middleware to the `salvo` routers.
+pub struct MiddlewareAdapter<M> {
+    /// The middleware.
+    middleware: Arc<M>,
+}
+
+impl<M> MiddlewareAdapter<M> {
+    /// Creates a new instance.
+    #[inline]
+    pub fn new(middleware: M) -> Self {
+        Self {
+            middleware: Arc::new(middleware),
+        }
+    }
+}
+
+#[async_trait]
+impl<M: Middleware + 'static> Handler for MiddlewareAdapter<M> {
+    async fn handle(
+        &self,
+        req: &mut Request,
+        depot: &mut Depot,
+        res: &mut Response,
+        ctrl: &mut FlowCtrl,
+    ) {
+        let mut extractor = crate::Request::from(mem::take(req));
+        let result = self.middleware.before_request(&mut extractor);
+        *req = extractor.into();
+        match result {
+            Ok(state) => {
+                ctrl.call_next(req, depot, res).await;
+
+                let mut headers = Default::default();
+                self.middleware.after_response(state, &mut headers);
+                for (name, value) in headers {
+                    if let Some(Ok(header_name)) =
+                        name.map(|name| HeaderName::try_from(name.as_str()))
+                        && let Ok(header_value) = HeaderValue::try_from(value)
+                    {
+                        res.headers_mut().insert(header_name, header_value);
+                    }
+                }
+            }
+            Err(rejection) => {
+                SalvoRejection::from(rejection).render(res);
+                ctrl.skip_rest();
+            }
+        }
+    }
+}
@@ -0,0 +1,32 @@
+use futures::FutureExt;
+use salvo::{Depot, FlowCtrl, Handler, Request, Response, async_trait, http::StatusCode};
+use std::{borrow::Cow, panic::AssertUnwindSafe};
+
+/// A middleware which converts the panics into internal server error responses.
+#[derive(Default)]
+pub(crate) struct CatchPanic;
+
+#[async_trait]
+impl Handler for CatchPanic {
+    async fn handle(
+        &self,
+        req: &mut Request,
+        depot: &mut Depot,
+        res: &mut Response,
+        ctrl: &mut FlowCtrl,
+    ) {
+        let fut = AssertUnwindSafe(ctrl.call_next(req, depot, res));
+        if let Err(err) = fut.catch_unwind().await {
+            let details = if let Some(s) = err.downcast_ref::<String>() {
+                Cow::Owned(s.to_owned())
+            } else if let Some(s) = err.downcast_ref::<&str>() {
+                Cow::Borrowed(*s)
+            } else {
+                Cow::Borrowed("Unknown panic message")
+            };
+            let mut response = crate::Response::new(StatusCode::INTERNAL_SERVER_ERROR);
+            response.set_message(details);
+            crate::response::build_http_response(response, res);
+        }
+    }
+}
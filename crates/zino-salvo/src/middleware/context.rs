@@ -0,0 +1,38 @@
+use salvo::{Depot, FlowCtrl, Handler, Request, Response, async_trait};
+use std::{mem, sync::Arc};
+use tracing::Span;
+use zino_http::request::RequestContext;
+
+/// A middleware which initializes the request context.
+#[derive(Default)]
+pub(crate) struct RequestContextInitializer;
+
+#[async_trait]
+impl Handler for RequestContextInitializer {
+    async fn handle(
+        &self,
+        req: &mut Request,
+        depot: &mut Depot,
+        res: &mut Response,
+        ctrl: &mut FlowCtrl,
+    ) {
+        let extractor = crate::Request::from(mem::take(req));
+        let new_context = extractor
+            .get_context()
+            .is_none()
+            .then(|| extractor.new_context());
+
+        *req = extractor.into();
+        if let Some(ctx) = new_context {
+            Span::current().record("context.request_id", ctx.request_id().to_string());
+            req.extensions_mut().insert(Arc::new(ctx));
+        }
+
+        // Routes the reads following a write in the request to the writer
+        #[cfg(feature = "orm")]
+        zino_orm::ReadYourWrites::new(ctrl.call_next(req, depot, res)).await;
+
+        #[cfg(not(feature = "orm"))]
+        ctrl.call_next(req, depot, res).await;
+    }
+}
@@ -0,0 +1,37 @@
+use salvo::{
+    Depot, FlowCtrl, Handler, Request, Response, async_trait,
+    http::{
+        StatusCode,
+        header::{ETAG, IF_NONE_MATCH},
+    },
+};
+
+/// A middleware which finalizes the `etag` header.
+#[derive(Default)]
+pub(crate) struct ETagFinalizer;
+
+#[async_trait]
+impl Handler for ETagFinalizer {
+    async fn handle(
+        &self,
+        req: &mut Request,
+        depot: &mut Depot,
+        res: &mut Response,
+        ctrl: &mut FlowCtrl,
+    ) {
+        if req.method().is_idempotent() {
+            let req_etag = req.headers().get(IF_NONE_MATCH).cloned();
+            ctrl.call_next(req, depot, res).await;
+            if let Some(etag) = res.headers_mut().remove("x-etag") {
+                if req_etag.as_ref() == Some(&etag)
+                    && res.status_code.is_none_or(|status| status.is_success())
+                {
+                    res.status_code(StatusCode::NOT_MODIFIED);
+                }
+                res.headers_mut().insert(ETAG, etag);
+            }
+        } else {
+            ctrl.call_next(req, depot, res).await;
+        }
+    }
+}
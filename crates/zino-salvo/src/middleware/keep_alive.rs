@@ -0,0 +1,40 @@
+use salvo::{
+    Depot, FlowCtrl, Handler, Request, Response, async_trait,
+    http::header::{CONNECTION, HeaderName, HeaderValue},
+};
+
+/// A middleware which sets the `connection` and `keep-alive` headers if not present.
+pub(crate) struct KeepAlive {
+    /// Value of the `keep-alive` header.
+    keep_alive: HeaderValue,
+}
+
+impl KeepAlive {
+    /// Creates a new instance with the timeout in seconds.
+    pub(crate) fn new(timeout: u64) -> Self {
+        let keep_alive = HeaderValue::from_str(&format!("timeout={timeout}"))
+            .expect("fail to set the `keep-alive` header value");
+        Self { keep_alive }
+    }
+}
+
+#[async_trait]
+impl Handler for KeepAlive {
+    async fn handle(
+        &self,
+        req: &mut Request,
+        depot: &mut Depot,
+        res: &mut Response,
+        ctrl: &mut FlowCtrl,
+    ) {
+        ctrl.call_next(req, depot, res).await;
+
+        let headers = res.headers_mut();
+        headers
+            .entry(CONNECTION)
+            .or_insert_with(|| HeaderValue::from_static("keep-alive"));
+        headers
+            .entry(HeaderName::from_static("keep-alive"))
+            .or_insert_with(|| self.keep_alive.clone());
+    }
+}
@@ -0,0 +1,18 @@
+mod adapter;
+mod catch_panic;
+mod context;
+mod cors;
+mod etag;
+mod keep_alive;
+mod static_pages;
+mod tracing;
+
+pub use self::adapter::MiddlewareAdapter;
+
+pub(crate) use self::catch_panic::CatchPanic;
+pub(crate) use self::context::RequestContextInitializer;
+pub(crate) use self::cors::cors_middleware;
+pub(crate) use self::etag::ETagFinalizer;
+pub(crate) use self::keep_alive::KeepAlive;
+pub(crate) use self::static_pages::StaticPages;
+pub(crate) use self::tracing::TracingLogger;
@@ -0,0 +1,33 @@
+use salvo::{Depot, FlowCtrl, Handler, Request, Response, async_trait};
+
+/// A middleware which corrects the path of static pages.
+#[derive(Default)]
+pub(crate) struct StaticPages;
+
+// Corrects the `path` parameter to achieve the following goals:
+//   /pages -> ${public-dir}/index.html
+//   /pages/login -> ${public-dir}/login.html
+#[async_trait]
+impl Handler for StaticPages {
+    async fn handle(
+        &self,
+        req: &mut Request,
+        depot: &mut Depot,
+        res: &mut Response,
+        ctrl: &mut FlowCtrl,
+    ) {
+        let path = req.params().get("path").cloned().unwrap_or_default();
+        let name = path
+            .rsplit_once('/')
+            .map_or(path.as_str(), |(_, name)| name);
+        if !name.contains('.') {
+            let path = if name.is_empty() {
+                [path.as_str(), "index.html"].concat()
+            } else {
+                [path.as_str(), ".html"].concat()
+            };
+            req.params_mut().insert("path", path);
+        }
+        ctrl.call_next(req, depot, res).await;
+    }
+}
@@ -0,0 +1,201 @@
+use crate::Cluster;
+use salvo::{Depot, FlowCtrl, Handler, Request, Response, async_trait, http::StatusCode};
+use std::{
+    sync::atomic::{AtomicBool, Ordering::Relaxed},
+    time::{Duration, Instant},
+};
+use tracing::{Instrument, Span, field::Empty};
+use zino_core::{
+    Uuid,
+    application::Application,
+    extension::{HeaderMapExt, TomlTableExt},
+    trace::TraceContext,
+};
+
+/// Tracing middleware.
+pub(crate) struct TracingLogger;
+
+impl TracingLogger {
+    /// Creates a new instance.
+    pub(crate) fn new() -> Self {
+        if let Some(config) = Cluster::shared_state().get_config("tracing")
+            && config.get_bool("record-user-agent") == Some(true)
+        {
+            RECORD_USER_AGENT.store(true, Relaxed);
+        }
+        Self
+    }
+}
+
+#[async_trait]
+impl Handler for TracingLogger {
+    async fn handle(
+        &self,
+        req: &mut Request,
+        depot: &mut Depot,
+        res: &mut Response,
+        ctrl: &mut FlowCtrl,
+    ) {
+        let span = custom_make_span(req);
+        span.in_scope(|| custom_on_request(req, &span));
+
+        let start_time = Instant::now();
+        ctrl.call_next(req, depot, res)
+            .instrument(span.clone())
+            .await;
+
+        let latency = start_time.elapsed();
+        span.in_scope(|| {
+            custom_on_response(res, latency, &span);
+            if let Some(status_code) = res
+                .status_code
+                .filter(|status| status.is_client_error() || status.is_server_error())
+            {
+                custom_on_failure(status_code, &span);
+            }
+        });
+    }
+}
+
+/// A flag to enable recording the user agent.
+static RECORD_USER_AGENT: AtomicBool = AtomicBool::new(false);
+
+fn custom_make_span(request: &Request) -> Span {
+    let name = Cluster::name();
+    let method = request.method();
+
+    // URI
+    let uri = request.uri();
+    let scheme = uri.scheme_str();
+    let host = uri.host();
+    let port = uri.port_u16();
+    let path = uri.path();
+    let query = uri.query();
+
+    // Headers
+    let headers = request.headers();
+    let client_ip = headers.get_client_ip().map(|ip| ip.to_string());
+    let user_agent = if RECORD_USER_AGENT.load(Relaxed) {
+        headers.get_str("user-agent")
+    } else {
+        None
+    };
+
+    if method.is_safe() {
+        tracing::info_span!(
+            "HTTP request",
+            "otel.kind" = "server",
+            "otel.name" = name,
+            "otel.status_code" = Empty,
+            "url.scheme" = scheme,
+            "url.path" = path,
+            "url.query" = query,
+            "http.request.method" = method.as_str(),
+            "http.request.header.traceparent" = Empty,
+            "http.request.header.tracestate" = Empty,
+            "http.response.header.traceparent" = Empty,
+            "http.response.header.tracestate" = Empty,
+            "http.response.header.server_timing" = Empty,
+            "http.response.status_code" = Empty,
+            "client.address" = client_ip,
+            "server.address" = host,
+            "server.port" = port,
+            "user_agent.original" = user_agent,
+            "context.session_id" = Empty,
+            "context.trace_id" = Empty,
+            "context.request_id" = Empty,
+            "context.span_id" = Empty,
+            "context.parent_id" = Empty,
+        )
+    } else {
+        tracing::warn_span!(
+            "HTTP request",
+            "otel.kind" = "server",
+            "otel.name" = name,
+            "otel.status_code" = Empty,
+            "url.scheme" = scheme,
+            "url.path" = path,
+            "url.query" = query,
+            "http.request.method" = method.as_str(),
+            "http.request.header.traceparent" = Empty,
+            "http.request.header.tracestate" = Empty,
+            "http.response.header.traceparent" = Empty,
+            "http.response.header.tracestate" = Empty,
+            "http.response.header.server_timing" = Empty,
+            "http.response.status_code" = Empty,
+            "client.address" = client_ip,
+            "server.address" = host,
+            "server.port" = port,
+            "user_agent.original" = user_agent,
+            "context.session_id" = Empty,
+            "context.trace_id" = Empty,
+            "context.request_id" = Empty,
+            "context.span_id" = Empty,
+            "context.parent_id" = Empty,
+        )
+    }
+}
+
+fn custom_on_request(request: &Request, span: &Span) {
+    let headers = request.headers();
+    let traceparent = headers.get_str("traceparent");
+    let trace_context = traceparent.and_then(TraceContext::from_traceparent);
+    span.record("http.request.header.traceparent", traceparent);
+    span.record(
+        "http.request.header.tracestate",
+        headers.get_str("tracestate"),
+    );
+    span.record(
+        "context.parent_id",
+        trace_context
+            .and_then(|ctx| ctx.parent_id())
+            .map(|parent_id| format!("{parent_id:x}")),
+    );
+    span.record("context.session_id", headers.get_str("session-id"));
+    span.record(
+        "context.span_id",
+        span.id().map(|id| format!("{:x}", id.into_u64())),
+    );
+    tracing::debug!("started processing request");
+}
+
+fn custom_on_response(response: &Response, latency: Duration, span: &Span) {
+    let headers = response.headers();
+    let traceparent = headers.get_str("traceparent");
+    span.record(
+        "context.trace_id",
+        traceparent
+            .and_then(TraceContext::from_traceparent)
+            .map(|ctx| Uuid::from_u128(ctx.trace_id()).to_string()),
+    );
+    span.record("http.response.header.traceparent", traceparent);
+    span.record(
+        "http.response.header.tracestate",
+        headers.get_str("tracestate"),
+    );
+    span.record(
+        "http.response.header.server_timing",
+        headers.get_str("server-timing"),
+    );
+    span.record(
+        "http.response.status_code",
+        response.status_code.unwrap_or(StatusCode::OK).as_u16(),
+    );
+    span.record(
+        "http.server.duration",
+        u64::try_from(latency.as_millis()).ok(),
+    );
+    span.record("otel.status_code", "OK");
+    tracing::info!("finished processing request");
+}
+
+fn custom_on_failure(status_code: StatusCode, span: &Span) {
+    span.record("http.response.status_code", status_code.as_u16());
+    if status_code.is_client_error() {
+        span.record("otel.status_code", "OK");
+        tracing::warn!("response failed");
+    } else {
+        span.record("otel.status_code", "ERROR");
+        tracing::error!("response failed");
+    }
+}
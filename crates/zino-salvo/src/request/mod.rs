@@ -0,0 +1,130 @@
+use bytes::Bytes;
+use salvo::{
+    Request,
+    http::{Method, uri::Uri},
+};
+use std::{
+    borrow::Cow,
+    net::IpAddr,
+    ops::{Deref, DerefMut},
+    sync::Arc,
+};
+use zino_core::{error::Error, extension::HeaderMapExt, state::Data};
+use zino_http::request::{Context, RequestContext};
+use zino_router::{RouteRequest, Sendable};
+
+/// An HTTP request extractor.
+pub struct Extractor<T>(T);
+
+impl<T> Deref for Extractor<T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Extractor<T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl From<Request> for Extractor<Request> {
+    #[inline]
+    fn from(request: Request) -> Self {
+        Self(request)
+    }
+}
+
+impl From<Extractor<Request>> for Request {
+    #[inline]
+    fn from(extractor: Extractor<Request>) -> Self {
+        extractor.0
+    }
+}
+
+impl RouteRequest for Extractor<Request> {
+    type Flavor = Sendable;
+}
+
+impl RequestContext for Extractor<Request> {
+    type Method = Method;
+    type Uri = Uri;
+
+    #[inline]
+    fn request_method(&self) -> &Self::Method {
+        self.method()
+    }
+
+    #[inline]
+    fn original_uri(&self) -> &Self::Uri {
+        self.uri()
+    }
+
+    #[inline]
+    fn matched_route(&self) -> Cow<'_, str> {
+        // The pattern of the matched route is not retained by `salvo`,
+        // so the route parameters are extracted from the `PathParams` instead.
+        self.uri().path().into()
+    }
+
+    #[inline]
+    fn request_path(&self) -> &str {
+        self.uri().path()
+    }
+
+    #[inline]
+    fn get_query_string(&self) -> Option<&str> {
+        self.uri().query()
+    }
+
+    #[inline]
+    fn get_header(&self, name: &str) -> Option<&str> {
+        self.headers().get(name)?.to_str().ok()
+    }
+
+    #[inline]
+    fn client_ip(&self) -> Option<IpAddr> {
+        self.headers().get_client_ip().or_else(|| self.peer_ip())
+    }
+
+    #[inline]
+    fn peer_ip(&self) -> Option<IpAddr> {
+        self.remote_addr()
+            .clone()
+            .into_std()
+            .map(|socket| socket.ip())
+    }
+
+    #[inline]
+    fn get_param(&self, name: &str) -> Option<&str> {
+        self.params().get(name).map(|value| value.as_str())
+    }
+
+    #[inline]
+    fn get_context(&self) -> Option<Arc<Context>> {
+        self.extensions().get::<Arc<Context>>().cloned()
+    }
+
+    #[inline]
+    fn get_data<T: Clone + Send + Sync + 'static>(&self) -> Option<T> {
+        self.extensions().get::<Data<T>>().map(|data| data.get())
+    }
+
+    #[inline]
+    fn set_data<T: Clone + Send + Sync + 'static>(&mut self, value: T) -> Option<T> {
+        self.extensions_mut()
+            .insert(Data::new(value))
+            .map(|data| data.into_inner())
+    }
+
+    #[inline]
+    async fn read_body_bytes(&mut self) -> Result<Bytes, Error> {
+        // The body size has been limited by the `max_size` middleware.
+        let bytes = self.payload_with_max_size(usize::MAX).await?;
+        Ok(bytes.clone())
+    }
+}
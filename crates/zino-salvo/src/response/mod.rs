@@ -0,0 +1,98 @@
+use futures::TryStreamExt;
+use salvo::{
+    http::{
+        ResBody, StatusCode,
+        header::{self, HeaderValue},
+    },
+    writing::Scribe,
+};
+use std::{fmt, io};
+use zino_http::response::{Rejection, Response, ResponseCode};
+
+/// An HTTP response for `salvo`.
+pub struct SalvoResponse<S: ResponseCode = StatusCode>(Response<S>);
+
+impl<S: ResponseCode> From<Response<S>> for SalvoResponse<S> {
+    #[inline]
+    fn from(response: Response<S>) -> Self {
+        Self(response)
+    }
+}
+
+impl<S: ResponseCode + Send> Scribe for SalvoResponse<S> {
+    #[inline]
+    fn render(self, res: &mut salvo::Response) {
+        build_http_response(self.0, res);
+    }
+}
+
+/// An HTTP rejection response for `salvo`.
+pub struct SalvoRejection(Response<StatusCode>);
+
+impl fmt::Debug for SalvoRejection {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.0.message().unwrap_or("OK"))
+    }
+}
+
+impl fmt::Display for SalvoRejection {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.status_code().fmt(f)
+    }
+}
+
+impl From<Rejection> for SalvoRejection {
+    #[inline]
+    fn from(rejection: Rejection) -> Self {
+        Self(rejection.into())
+    }
+}
+
+impl Scribe for SalvoRejection {
+    #[inline]
+    fn render(self, res: &mut salvo::Response) {
+        build_http_response(self.0, res);
+    }
+}
+
+/// Build http response from `zino_core::response::Response`.
+pub(crate) fn build_http_response<S: ResponseCode>(
+    mut response: Response<S>,
+    res: &mut salvo::Response,
+) {
+    let result = if let Some(stream) = response.take_stream_data() {
+        let stream = stream.map_err(|err| io::Error::other(err.to_string()));
+        Ok(ResBody::stream(stream))
+    } else {
+        response.read_bytes().map(ResBody::Once)
+    };
+    match result {
+        Ok(body) => {
+            let status_code = StatusCode::from_u16(response.status_code())
+                .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+            res.status_code(status_code);
+            if let Ok(header_value) = HeaderValue::try_from(response.content_type()) {
+                res.headers_mut().insert(header::CONTENT_TYPE, header_value);
+            }
+            res.body(body);
+        }
+        Err(err) => {
+            res.status_code(StatusCode::INTERNAL_SERVER_ERROR);
+            res.headers_mut().insert(
+                header::CONTENT_TYPE,
+                HeaderValue::from_static("text/plain; charset=utf-8"),
+            );
+            res.body(ResBody::Once(err.to_string().into()));
+        }
+    }
+
+    for (name, value) in response.finalize() {
+        if let Some(header_name) = name
+            && let Ok(header_value) = HeaderValue::try_from(value)
+        {
+            res.headers_mut().insert(header_name, header_value);
+        }
+    }
+}
@@ -0,0 +1,537 @@
+use crate::{
+    Request, Result,
+    request::{MatchedRoute, RemoteAddr},
+    response::{ResponseBody, build_http_response},
+};
+use futures::FutureExt;
+use http::{
+    Method, StatusCode, Version,
+    header::{self, HeaderName, HeaderValue},
+};
+use http_body_util::Limited;
+use hyper::{body::Incoming, service::service_fn};
+use hyper_util::{
+    rt::{TokioExecutor, TokioIo},
+    server::{conn::auto, graceful::GracefulShutdown},
+};
+use std::{
+    borrow::Cow, convert::Infallible, future::Future, net::SocketAddr, panic::AssertUnwindSafe,
+    pin::pin, sync::Arc, time::Duration,
+};
+use tokio::{net::TcpListener, runtime::Builder, time};
+use zino_core::{
+    application::{AppType, Application, Plugin, ServerTag, Shutdown},
+    extension::TomlTableExt,
+    schedule::AsyncScheduler,
+};
+use zino_http::{request::RequestContext, response::Response};
+use zino_router::{BoxHandler, PathPattern, RouteTable, Router};
+
+/// An HTTP server cluster built on `hyper`.
+///
+/// It serves HTTP/1.1 and HTTP/2 on each listener,
+/// and drains the in-flight requests on the graceful shutdown.
+#[derive(Default)]
+pub struct Cluster {
+    /// Custom plugins.
+    custom_plugins: Vec<Plugin>,
+    /// Default routes.
+    default_routes: Vec<Arc<Endpoint>>,
+    /// Tagged routes.
+    tagged_routes: Vec<(ServerTag, Vec<Arc<Endpoint>>)>,
+}
+
+impl Application for Cluster {
+    type Routes = Router<Request, Result>;
+
+    const APP_TYPE: AppType = AppType::Server;
+
+    #[inline]
+    fn register(mut self, routes: Self::Routes) -> Self {
+        self.default_routes.append(&mut build_endpoints(routes));
+        self
+    }
+
+    #[inline]
+    fn register_with(mut self, server_tag: ServerTag, routes: Self::Routes) -> Self {
+        self.tagged_routes
+            .push((server_tag, build_endpoints(routes)));
+        self
+    }
+
+    #[inline]
+    fn add_plugin(mut self, plugin: Plugin) -> Self {
+        self.custom_plugins.push(plugin);
+        self
+    }
+
+    fn run_with<T: AsyncScheduler + Send + 'static>(self, mut scheduler: T) {
+        let runtime = Builder::new_multi_thread()
+            .thread_keep_alive(Duration::from_secs(60))
+            .thread_stack_size(2 * 1024 * 1024)
+            .global_queue_interval(61)
+            .enable_all()
+            .build()
+            .expect("fail to build Tokio runtime for `ServerCluster`");
+        let app_env = Self::env();
+        runtime.block_on(async {
+            #[cfg(feature = "orm")]
+            {
+                zino_orm::GlobalPool::connect_all().await;
+                zino_orm::GlobalPool::migrate_all().await;
+                Shutdown::add_hook(
+                    "orm",
+                    Box::pin(async {
+                        zino_orm::GlobalPool::close_all().await;
+                        Ok(())
+                    }),
+                );
+            }
+            Self::load().await;
+            app_env.load_plugins(self.custom_plugins).await;
+        });
+        runtime.spawn(Shutdown::listen());
+
+        #[cfg(feature = "orm")]
+        runtime.spawn(zino_orm::GlobalPool::monitor_replicas());
+
+        let scheduler_task = scheduler.is_ready().then(|| {
+            if scheduler.is_blocking() {
+                runtime.spawn(async move {
+                    if let Err(err) = scheduler.run().await {
+                        tracing::error!("fail to run the async scheduler: {err}");
+                    }
+                })
+            } else {
+                runtime.spawn(async move {
+                    // Stops the scheduler after the current jobs on the graceful shutdown.
+                    while !Shutdown::is_triggered() {
+                        scheduler.tick().await;
+
+                        // Cannot use `std::thread::sleep` because it blocks the Tokio runtime.
+                        if let Some(duration) = scheduler.time_till_next_job() {
+                            tokio::select! {
+                                _ = time::sleep(duration) => {},
+                                _ = Shutdown::wait() => {},
+                            }
+                        }
+                    }
+                    // Waits for the jobs running in the background.
+                    scheduler.tick().await;
+                })
+            }
+        });
+
+        runtime.block_on(async {
+            let default_routes = self.default_routes;
+            let tagged_routes = self.tagged_routes;
+            let app_state = Self::shared_state();
+            let app_name = Self::name();
+            let app_version = Self::version();
+            let shutdown_timeout = Self::shutdown_timeout();
+
+            // Server config
+            let mut body_limit = 128 * 1024 * 1024; // 128MB
+            let mut request_timeout = Duration::from_secs(60); // 60 seconds
+            let mut keep_alive_timeout = 75; // 75 seconds
+            if let Some(config) = app_state.get_config("server") {
+                if let Some(limit) = config.get_usize("body-limit") {
+                    body_limit = limit;
+                }
+                if let Some(timeout) = config.get_duration("request-timeout") {
+                    request_timeout = timeout;
+                }
+                if let Some(timeout) = config.get_duration("keep-alive-timeout") {
+                    keep_alive_timeout = timeout.as_secs();
+                }
+            }
+            let keep_alive = HeaderValue::from_str(&format!("timeout={keep_alive_timeout}"))
+                .expect("fail to set the `keep-alive` header value");
+
+            let servers = app_state.listeners().into_iter().map(|listener| {
+                let server_tag = listener.0;
+                let addr = listener.1;
+                tracing::warn!(
+                    server_tag = server_tag.as_str(),
+                    app_env = app_env.as_str(),
+                    app_name,
+                    app_version,
+                    "listen on `{addr}`",
+                );
+
+                let mut endpoints = default_routes.clone();
+                for (tag, routes) in &tagged_routes {
+                    if tag == &server_tag || server_tag.is_debug() {
+                        endpoints.extend(routes.iter().cloned());
+                    }
+                }
+                let dispatcher = Arc::new(Dispatcher {
+                    endpoints,
+                    body_limit,
+                    request_timeout,
+                    keep_alive: keep_alive.clone(),
+                });
+                async move {
+                    let tcp_listener = TcpListener::bind(&addr)
+                        .await
+                        .unwrap_or_else(|err| panic!("fail to listen on {addr}: {err}"));
+                    serve(tcp_listener, dispatcher, Shutdown::wait(), shutdown_timeout).await;
+                }
+            });
+            futures::future::join_all(servers).await;
+            if let Some(task) = scheduler_task
+                && time::timeout(shutdown_timeout, task).await.is_err()
+            {
+                tracing::warn!("timed out to wait for the async scheduler");
+            }
+            Self::teardown().await;
+        });
+    }
+}
+
+/// Serves the connections on the listener until the shutdown signal resolves,
+/// and then drains the in-flight requests within the timeout.
+async fn serve(
+    tcp_listener: TcpListener,
+    dispatcher: Arc<Dispatcher>,
+    signal: impl Future<Output = ()>,
+    shutdown_timeout: Duration,
+) {
+    let addr = tcp_listener
+        .local_addr()
+        .map(|addr| addr.to_string())
+        .unwrap_or_default();
+    let builder = auto::Builder::new(TokioExecutor::new());
+    let graceful = GracefulShutdown::new();
+    let mut signal = pin!(signal);
+    loop {
+        tokio::select! {
+            result = tcp_listener.accept() => {
+                let (stream, remote_addr) = match result {
+                    Ok(accepted) => accepted,
+                    Err(err) => {
+                        tracing::error!("fail to accept the connection: {err}");
+                        continue;
+                    }
+                };
+                let dispatcher = dispatcher.clone();
+                let service = service_fn(move |req| {
+                    let dispatcher = dispatcher.clone();
+                    async move {
+                        let res = dispatcher.dispatch(req, remote_addr).await;
+                        Ok::<_, Infallible>(res)
+                    }
+                });
+                let conn = builder
+                    .serve_connection_with_upgrades(TokioIo::new(stream), service)
+                    .into_owned();
+                let conn = graceful.watch(conn);
+                tokio::spawn(async move {
+                    if let Err(err) = conn.await {
+                        tracing::debug!("hyper server connection error: {err}");
+                    }
+                });
+            }
+            _ = &mut signal => break,
+        }
+    }
+
+    // Stops accepting new connections and drains the in-flight requests.
+    drop(tcp_listener);
+    tokio::select! {
+        _ = graceful.shutdown() => {
+            tracing::info!("all connections on `{addr}` are closed");
+        }
+        _ = time::sleep(shutdown_timeout) => {
+            tracing::warn!("timed out to wait for the connections on `{addr}`");
+        }
+    }
+}
+
+/// An endpoint resolved from the framework-neutral routes.
+struct Endpoint {
+    /// HTTP method.
+    method: Method,
+    /// Path pattern.
+    pattern: PathPattern,
+    /// Path without the type annotations.
+    path: Arc<str>,
+    /// Handler wrapped by the middleware.
+    handler: BoxHandler<Request, Result>,
+}
+
+/// Builds the endpoints from the framework-neutral routes.
+fn build_endpoints(router: Router<Request, Result>) -> Vec<Arc<Endpoint>> {
+    RouteTable::record(&router);
+    router
+        .into_routes()
+        .into_iter()
+        .map(|route| {
+            let method = route.method().clone();
+            let pattern = route.pattern().clone();
+            let path = pattern.to_path().into();
+            let handler = route.into_handler();
+            Arc::new(Endpoint {
+                method,
+                pattern,
+                path,
+                handler,
+            })
+        })
+        .collect()
+}
+
+/// A dispatcher which routes the requests to the endpoints of a listener.
+struct Dispatcher {
+    /// Endpoints in the order of registration.
+    endpoints: Vec<Arc<Endpoint>>,
+    /// Maximum size of the request body in bytes.
+    body_limit: usize,
+    /// Timeout of a request.
+    request_timeout: Duration,
+    /// Value of the `keep-alive` header.
+    keep_alive: HeaderValue,
+}
+
+impl Dispatcher {
+    /// Dispatches the request to the first endpoint which matches it.
+    async fn dispatch(
+        &self,
+        req: hyper::Request<Incoming>,
+        remote_addr: SocketAddr,
+    ) -> hyper::Response<ResponseBody> {
+        let version = req.version();
+        let body_limit = self.body_limit;
+        let mut req = Request::from(req.map(|body| Limited::new(body, body_limit)));
+        req.extensions_mut().insert(RemoteAddr(remote_addr));
+        if req.get_context().is_none() {
+            let ctx = req.new_context();
+            req.extensions_mut().insert(Arc::new(ctx));
+        }
+
+        let content_length = req
+            .headers()
+            .get(header::CONTENT_LENGTH)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse::<usize>().ok());
+        let mut res = if content_length.is_some_and(|length| length > body_limit) {
+            let res = Response::new(StatusCode::PAYLOAD_TOO_LARGE).context(&req);
+            build_http_response(res)
+        } else {
+            let path = req.uri().path();
+            let method = req.method();
+            let mut path_matched = false;
+            let endpoint = self.endpoints.iter().find(|endpoint| {
+                if endpoint.pattern.matches(path).is_some() {
+                    path_matched = true;
+                    endpoint.method == method
+                } else {
+                    false
+                }
+            });
+            match endpoint {
+                Some(endpoint) => {
+                    req.extensions_mut()
+                        .insert(MatchedRoute(endpoint.path.clone()));
+                    self.call(&endpoint.handler, req).await
+                }
+                None => {
+                    let status_code = if path_matched {
+                        StatusCode::METHOD_NOT_ALLOWED
+                    } else {
+                        StatusCode::NOT_FOUND
+                    };
+                    build_http_response(Response::new(status_code).context(&req))
+                }
+            }
+        };
+
+        // The `keep-alive` header is prohibited in HTTP/2.
+        if version == Version::HTTP_11 {
+            res.headers_mut()
+                .entry(HeaderName::from_static("keep-alive"))
+                .or_insert_with(|| self.keep_alive.clone());
+        }
+        res
+    }
+
+    /// Calls the handler with the request timeout and catches the panic.
+    async fn call(
+        &self,
+        handler: &BoxHandler<Request, Result>,
+        req: Request,
+    ) -> hyper::Response<ResponseBody> {
+        let fut = AssertUnwindSafe(handler(req)).catch_unwind();
+
+        // Routes the reads following a write in the request to the writer
+        #[cfg(feature = "orm")]
+        let fut = zino_orm::ReadYourWrites::new(fut);
+
+        match time::timeout(self.request_timeout, fut).await {
+            Ok(Ok(Ok(res))) => res.into_http_response(),
+            Ok(Ok(Err(rejection))) => rejection.into_http_response(),
+            Ok(Err(err)) => {
+                let details = if let Some(s) = err.downcast_ref::<String>() {
+                    Cow::Owned(s.to_owned())
+                } else if let Some(s) = err.downcast_ref::<&str>() {
+                    Cow::Borrowed(*s)
+                } else {
+                    Cow::Borrowed("Unknown panic message")
+                };
+                let mut res = Response::new(StatusCode::INTERNAL_SERVER_ERROR);
+                res.set_message(details);
+                build_http_response(res)
+            }
+            Err(_) => build_http_response(Response::new(StatusCode::REQUEST_TIMEOUT)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Dispatcher, build_endpoints, serve};
+    use crate::{Request, Response};
+    use futures::channel::oneshot;
+    use http::{StatusCode, header::HeaderValue};
+    use std::{
+        io::{Read, Write},
+        net::{SocketAddr, TcpStream},
+        sync::Arc,
+        time::Duration,
+    };
+    use tokio::{
+        net::TcpListener,
+        task::{self, JoinHandle},
+        time,
+    };
+    use zino_router::Router;
+
+    /// Sends a raw HTTP/1.1 request and reads the response until the connection is closed.
+    async fn send(addr: SocketAddr, request: &'static str) -> String {
+        task::spawn_blocking(move || {
+            let mut stream = TcpStream::connect(addr).expect("fail to connect the server");
+            stream
+                .write_all(request.as_bytes())
+                .expect("fail to write the stream");
+            let mut response = String::new();
+            stream
+                .read_to_string(&mut response)
+                .expect("fail to read the stream");
+            response
+        })
+        .await
+        .expect("fail to join the task")
+    }
+
+    /// Starts a server with the routes and returns its address, the shutdown trigger
+    /// and the server task.
+    async fn start(
+        router: Router<Request, crate::Result>,
+    ) -> (SocketAddr, oneshot::Sender<()>, JoinHandle<()>) {
+        let dispatcher = Arc::new(Dispatcher {
+            endpoints: build_endpoints(router),
+            body_limit: 16,
+            request_timeout: Duration::from_millis(500),
+            keep_alive: HeaderValue::from_static("timeout=75"),
+        });
+        let tcp_listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("fail to bind the listener");
+        let addr = tcp_listener
+            .local_addr()
+            .expect("fail to get the local address");
+        let (sender, receiver) = oneshot::channel();
+        let signal = async {
+            receiver.await.ok();
+        };
+        let server = tokio::spawn(serve(
+            tcp_listener,
+            dispatcher,
+            signal,
+            Duration::from_secs(5),
+        ));
+        (addr, sender, server)
+    }
+
+    #[tokio::test]
+    async fn it_dispatches_requests() {
+        let router = Router::new()
+            .get("/users/{id:u64}", |req: Request| async move {
+                let mut res = Response::new(StatusCode::OK);
+                res.set_bytes_data(req.uri().path().to_owned());
+                Ok(res.into())
+            })
+            .get("/panic", |req: Request| async move {
+                let mut res = Response::new(StatusCode::OK);
+                res.set_bytes_data(
+                    req.uri()
+                        .query()
+                        .expect("query should be present")
+                        .to_owned(),
+                );
+                Ok(res.into())
+            })
+            .get("/slow", |_req: Request| async move {
+                time::sleep(Duration::from_secs(5)).await;
+                Ok(Response::new(StatusCode::OK).into())
+            });
+        let (addr, _sender, _server) = start(router).await;
+
+        let res = send(addr, "GET /users/42 HTTP/1.1\r\nConnection: close\r\n\r\n").await;
+        assert!(res.starts_with("HTTP/1.1 200 OK"));
+        assert!(res.contains("keep-alive: timeout=75"));
+        assert!(res.ends_with("/users/42"));
+
+        let res = send(
+            addr,
+            "GET /users/alice HTTP/1.1\r\nConnection: close\r\n\r\n",
+        )
+        .await;
+        assert!(res.starts_with("HTTP/1.1 404 Not Found"));
+
+        let res = send(addr, "POST /users/42 HTTP/1.1\r\nConnection: close\r\n\r\n").await;
+        assert!(res.starts_with("HTTP/1.1 405 Method Not Allowed"));
+
+        let res = send(
+            addr,
+            "POST /users/42 HTTP/1.1\r\nConnection: close\r\nContent-Length: 17\r\n\r\n",
+        )
+        .await;
+        assert!(res.starts_with("HTTP/1.1 413 Payload Too Large"));
+
+        let res = send(addr, "GET /panic HTTP/1.1\r\nConnection: close\r\n\r\n").await;
+        assert!(res.starts_with("HTTP/1.1 500 Internal Server Error"));
+        assert!(res.contains("query should be present"));
+
+        let res = send(addr, "GET /slow HTTP/1.1\r\nConnection: close\r\n\r\n").await;
+        assert!(res.starts_with("HTTP/1.1 408 Request Timeout"));
+    }
+
+    #[tokio::test]
+    async fn it_drains_requests_on_shutdown() {
+        let router = Router::new().get("/slow", |_req: Request| async move {
+            time::sleep(Duration::from_millis(200)).await;
+            let mut res = Response::new(StatusCode::OK);
+            res.set_bytes_data("done");
+            Ok(res.into())
+        });
+        let (addr, sender, server) = start(router).await;
+
+        let in_flight = tokio::spawn(send(
+            addr,
+            "GET /slow HTTP/1.1\r\nConnection: close\r\n\r\n",
+        ));
+        time::sleep(Duration::from_millis(50)).await;
+        sender.send(()).expect("fail to send the value");
+
+        let res = in_flight.await.expect("fail to join the task");
+        assert!(res.starts_with("HTTP/1.1 200 OK"));
+        assert!(res.ends_with("done"));
+
+        time::timeout(Duration::from_secs(1), server)
+            .await
+            .expect("the server should be stopped in time")
+            .expect("the group should exist");
+        assert!(TcpStream::connect(addr).is_err());
+    }
+}
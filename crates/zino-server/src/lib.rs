@@ -1,3 +1,23 @@
 #![doc = include_str!("../README.md")]
 #![doc(html_favicon_url = "https://zino.cc/assets/zino-logo.png")]
 #![doc(html_logo_url = "https://zino.cc/assets/zino-logo.svg")]
+
+mod application;
+mod request;
+mod response;
+
+pub use application::Cluster;
+pub use request::Extractor;
+pub use response::{ResponseBody, ServerRejection, ServerResponse};
+
+/// The request body with a size limit.
+pub type Body = http_body_util::Limited<hyper::body::Incoming>;
+
+/// A specialized request extractor.
+pub type Request = Extractor<hyper::Request<Body>>;
+
+/// A specialized response.
+pub type Response = zino_http::response::Response<http::StatusCode>;
+
+/// A specialized `Result` type.
+pub type Result<T = ServerResponse> = std::result::Result<T, ServerRejection>;
@@ -0,0 +1,136 @@
+use crate::Body;
+use bytes::Bytes;
+use http::{Method, Request, Uri};
+use http_body_util::BodyExt;
+use std::{
+    borrow::Cow,
+    net::{IpAddr, SocketAddr},
+    ops::{Deref, DerefMut},
+    sync::Arc,
+};
+use zino_core::{error::Error, extension::HeaderMapExt, state::Data};
+use zino_http::request::{Context, RequestContext};
+use zino_router::{RouteRequest, Sendable};
+
+/// An HTTP request extractor.
+pub struct Extractor<T>(T);
+
+/// Remote address of the connection.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct RemoteAddr(pub(crate) SocketAddr);
+
+/// Route that matches the request.
+#[derive(Debug, Clone)]
+pub(crate) struct MatchedRoute(pub(crate) Arc<str>);
+
+impl<T> Deref for Extractor<T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Extractor<T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl From<Request<Body>> for Extractor<Request<Body>> {
+    #[inline]
+    fn from(request: Request<Body>) -> Self {
+        Self(request)
+    }
+}
+
+impl From<Extractor<Request<Body>>> for Request<Body> {
+    #[inline]
+    fn from(extractor: Extractor<Request<Body>>) -> Self {
+        extractor.0
+    }
+}
+
+impl RouteRequest for Extractor<Request<Body>> {
+    type Flavor = Sendable;
+}
+
+impl RequestContext for Extractor<Request<Body>> {
+    type Method = Method;
+    type Uri = Uri;
+
+    #[inline]
+    fn request_method(&self) -> &Self::Method {
+        self.method()
+    }
+
+    #[inline]
+    fn original_uri(&self) -> &Self::Uri {
+        self.uri()
+    }
+
+    #[inline]
+    fn matched_route(&self) -> Cow<'_, str> {
+        if let Some(route) = self.extensions().get::<MatchedRoute>() {
+            route.0.as_ref().into()
+        } else {
+            self.uri().path().into()
+        }
+    }
+
+    #[inline]
+    fn request_path(&self) -> &str {
+        self.uri().path()
+    }
+
+    #[inline]
+    fn get_query_string(&self) -> Option<&str> {
+        self.uri().query()
+    }
+
+    #[inline]
+    fn get_header(&self, name: &str) -> Option<&str> {
+        self.headers().get(name)?.to_str().ok()
+    }
+
+    #[inline]
+    fn client_ip(&self) -> Option<IpAddr> {
+        self.headers().get_client_ip().or_else(|| self.peer_ip())
+    }
+
+    #[inline]
+    fn peer_ip(&self) -> Option<IpAddr> {
+        self.extensions()
+            .get::<RemoteAddr>()
+            .map(|remote_addr| remote_addr.0.ip())
+    }
+
+    #[inline]
+    fn get_context(&self) -> Option<Arc<Context>> {
+        self.extensions().get::<Arc<Context>>().cloned()
+    }
+
+    #[inline]
+    fn get_data<T: Clone + Send + Sync + 'static>(&self) -> Option<T> {
+        self.extensions().get::<Data<T>>().map(|data| data.get())
+    }
+
+    #[inline]
+    fn set_data<T: Clone + Send + Sync + 'static>(&mut self, value: T) -> Option<T> {
+        self.extensions_mut()
+            .insert(Data::new(value))
+            .map(|data| data.into_inner())
+    }
+
+    #[inline]
+    async fn read_body_bytes(&mut self) -> Result<Bytes, Error> {
+        let collected = self
+            .body_mut()
+            .collect()
+            .await
+            .map_err(|err| Error::new(err.to_string()))?;
+        Ok(collected.to_bytes())
+    }
+}
@@ -0,0 +1,107 @@
+use bytes::Bytes;
+use futures::TryStreamExt;
+use http::{
+    StatusCode,
+    header::{self, HeaderValue},
+};
+use http_body_util::{BodyExt, Full, StreamBody, combinators::UnsyncBoxBody};
+use hyper::body::Frame;
+use std::{fmt, io};
+use zino_http::response::{Rejection, Response, ResponseCode};
+
+/// A body of the HTTP response, which is either buffered or streamed.
+pub type ResponseBody = UnsyncBoxBody<Bytes, io::Error>;
+
+/// An HTTP response for the hyper server.
+pub struct ServerResponse<S: ResponseCode = StatusCode>(Response<S>);
+
+impl<S: ResponseCode> From<Response<S>> for ServerResponse<S> {
+    #[inline]
+    fn from(response: Response<S>) -> Self {
+        Self(response)
+    }
+}
+
+impl<S: ResponseCode> ServerResponse<S> {
+    /// Converts `self` into an HTTP response.
+    #[inline]
+    pub fn into_http_response(self) -> hyper::Response<ResponseBody> {
+        build_http_response(self.0)
+    }
+}
+
+/// An HTTP rejection response for the hyper server.
+pub struct ServerRejection(Response<StatusCode>);
+
+impl fmt::Debug for ServerRejection {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.0.message().unwrap_or("OK"))
+    }
+}
+
+impl fmt::Display for ServerRejection {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.status_code().fmt(f)
+    }
+}
+
+impl From<Rejection> for ServerRejection {
+    #[inline]
+    fn from(rejection: Rejection) -> Self {
+        Self(rejection.into())
+    }
+}
+
+impl ServerRejection {
+    /// Converts `self` into an HTTP response.
+    #[inline]
+    pub fn into_http_response(self) -> hyper::Response<ResponseBody> {
+        build_http_response(self.0)
+    }
+}
+
+/// Build http response from `zino_core::response::Response`.
+pub(crate) fn build_http_response<S: ResponseCode>(
+    mut response: Response<S>,
+) -> hyper::Response<ResponseBody> {
+    let result = if let Some(stream) = response.take_stream_data() {
+        let stream = stream
+            .map_ok(Frame::data)
+            .map_err(|err| io::Error::other(err.to_string()));
+        Ok(StreamBody::new(stream).boxed_unsync())
+    } else {
+        response.read_bytes().map(full_body)
+    };
+    let mut res = match result {
+        Ok(body) => hyper::Response::builder()
+            .status(response.status_code())
+            .header(header::CONTENT_TYPE, response.content_type())
+            .body(body)
+            .unwrap_or_default(),
+        Err(err) => hyper::Response::builder()
+            .status(S::INTERNAL_SERVER_ERROR.status_code())
+            .header(header::CONTENT_TYPE, "text/plain; charset=utf-8")
+            .body(full_body(err.to_string().into()))
+            .unwrap_or_default(),
+    };
+
+    for (name, value) in response.finalize() {
+        if let Some(header_name) = name
+            && let Ok(header_value) = HeaderValue::try_from(value)
+        {
+            res.headers_mut().insert(header_name, header_value);
+        }
+    }
+
+    res
+}
+
+/// Creates a response body with the bytes.
+#[inline]
+fn full_body(data: Bytes) -> ResponseBody {
+    Full::new(data)
+        .map_err(|never| match never {})
+        .boxed_unsync()
+}
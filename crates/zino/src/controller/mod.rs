@@ -24,6 +24,9 @@ pub trait DefaultController<K> {
     /// Fetch models.
     async fn fetch(req: Self::Request) -> Self::Result;
 
+    /// Searches models with the full-text search indexes.
+    async fn search(req: Self::Request) -> Self::Result;
+
     /// Logically deletes a model.
     async fn soft_delete(req: Self::Request) -> Self::Result;
 
@@ -33,6 +36,15 @@ pub trait DefaultController<K> {
     /// Archives a model.
     async fn archive(req: Self::Request) -> Self::Result;
 
+    /// Lists the revisions of a model.
+    async fn revisions(req: Self::Request) -> Self::Result;
+
+    /// Diffs two versions of a model.
+    async fn diff(req: Self::Request) -> Self::Result;
+
+    /// Restores a model to a previous version.
+    async fn restore(req: Self::Request) -> Self::Result;
+
     /// Batch inserts multiple models.
     async fn batch_insert(req: Self::Request) -> Self::Result;
 
@@ -61,27 +73,66 @@ pub trait DefaultController<K> {
     async fn mock(req: Self::Request) -> Self::Result;
 }
 
-#[cfg(any(feature = "actix", feature = "axum", feature = "ntex"))]
+#[cfg(any(
+    feature = "actix",
+    feature = "axum",
+    feature = "ntex",
+    feature = "salvo"
+))]
+#[cfg(feature = "orm")]
+use futures::{SinkExt, StreamExt, channel::mpsc, executor::block_on};
+
+#[cfg(any(
+    feature = "actix",
+    feature = "axum",
+    feature = "ntex",
+    feature = "salvo"
+))]
+#[cfg(feature = "orm")]
+use std::{pin::pin, thread};
+
+#[cfg(any(
+    feature = "actix",
+    feature = "axum",
+    feature = "ntex",
+    feature = "salvo"
+))]
 #[cfg(feature = "orm")]
 use zino_core::{
     JsonValue, Map,
     error::Error,
     extension::JsonObjectExt,
-    model::{ModelHooks, Mutation, Query},
+    model::{ModelHooks, Mutation, Query, QueryCursor},
+    validation::Validation,
 };
 
-#[cfg(any(feature = "actix", feature = "axum", feature = "ntex"))]
+#[cfg(any(
+    feature = "actix",
+    feature = "axum",
+    feature = "ntex",
+    feature = "salvo"
+))]
 #[cfg(feature = "orm")]
 use zino_http::{
     request::RequestContext,
     response::{ExtractRejection, Rejection, Response},
 };
 
-#[cfg(any(feature = "actix", feature = "axum", feature = "ntex"))]
+#[cfg(any(
+    feature = "actix",
+    feature = "axum",
+    feature = "ntex",
+    feature = "salvo"
+))]
 #[cfg(feature = "orm")]
-use zino_orm::{ModelAccessor, ModelHelper};
-
-#[cfg(any(feature = "actix", feature = "axum", feature = "ntex"))]
+use zino_orm::{CommonTableExpr, ModelAccessor, ModelHelper, Schema};
+
+#[cfg(any(
+    feature = "actix",
+    feature = "axum",
+    feature = "ntex",
+    feature = "salvo"
+))]
 #[cfg(feature = "orm")]
 impl<K, M> DefaultController<K> for M
 where
@@ -174,6 +225,7 @@ where
             _ => Self::default_list_query(),
         };
         let mut res = req.query_validation(&mut query)?;
+        query.order_by_tiebreaker(Self::PRIMARY_KEY_NAME);
         let extension = req.get_data::<<Self as ModelHooks>::Extension>();
         Self::before_list(&mut query, extension.as_ref())
             .await
@@ -200,9 +252,30 @@ where
             models
         };
 
+        let limit = query.limit();
+        let has_more = limit > 0 && models.len() == limit;
+        let (has_next, has_prev) = match query.cursor() {
+            Some(cursor) if cursor.is_backward() => (true, has_more),
+            Some(_) => (has_more, true),
+            None => (has_more, query.offset() > 0),
+        };
+        let sort_order = query.sort_order();
+        let next_cursor = models
+            .last()
+            .filter(|_| has_next)
+            .map(|model| QueryCursor::next(sort_order, Self::PRIMARY_KEY_NAME, model).encode());
+        let prev_cursor = models
+            .first()
+            .filter(|_| has_prev)
+            .map(|model| QueryCursor::prev(sort_order, Self::PRIMARY_KEY_NAME, model).encode());
+
         let mut data = Self::data_items(models);
+        data.upsert("next_cursor", next_cursor);
+        data.upsert("prev_cursor", prev_cursor);
         if let Some(page_size) = req.get_query("page_size").and_then(|s| s.parse().ok()) {
             if req.get_query("total_rows").is_none() {
+                query.remove_cursor();
+
                 let total_rows = Self::count(&query).await.extract(&req)?;
                 let page_count = total_rows.div_ceil(page_size);
                 data.upsert("total_rows", total_rows);
@@ -244,6 +317,63 @@ where
         Ok(res.emit(&req).into())
     }
 
+    async fn search(req: Self::Request) -> Self::Result {
+        let mut query = Self::default_list_query();
+        query.set_order(Vec::new());
+
+        let mut res = req.query_validation(&mut query)?;
+        let Some(JsonValue::String(search)) = query.remove_filter("q") else {
+            let err = Error::new("the search text should be nonempty");
+            let rejection = Rejection::from_validation_entry("q", err);
+            return Err(rejection.context(&req).into());
+        };
+        let columns = Self::columns()
+            .iter()
+            .filter(|col| col.has_fulltext_index())
+            .collect::<Vec<_>>();
+        let Some(language) = columns.first().map(|col| col.fulltext_language()) else {
+            let err = Error::new("the model does not have a full-text search index");
+            return Err(Rejection::not_found(err).context(&req).into());
+        };
+
+        let fields = columns.iter().map(|col| col.name()).collect::<Vec<_>>();
+        let mut text_search = Map::new();
+        text_search.upsert("$fields", fields.clone());
+        text_search.upsert("$search", search);
+        if let Some(language) = language {
+            text_search.upsert("$language", language);
+        }
+        text_search.upsert("$rank", true);
+        text_search.upsert("$highlight", fields);
+        query.add_filter("$text", text_search);
+        if query.sort_order().is_empty() {
+            query.order_desc("search_rank");
+        }
+        query.order_by_tiebreaker(Self::PRIMARY_KEY_NAME);
+
+        let extension = req.get_data::<<Self as ModelHooks>::Extension>();
+        Self::before_list(&mut query, extension.as_ref())
+            .await
+            .extract(&req)?;
+
+        let mut models = Self::find(&query).await.extract(&req)?;
+        let translate_enabled = query.translate_enabled();
+        for model in models.iter_mut() {
+            translate_enabled.then(|| Self::translate_model(model));
+            Self::after_decode(model).await.extract(&req)?;
+            Self::before_respond(model, extension.as_ref())
+                .await
+                .extract(&req)?;
+        }
+
+        let limit = query.limit();
+        let has_more = limit > 0 && models.len() == limit;
+        let mut data = Self::data_items(models);
+        data.upsert("has_more", has_more);
+        res.set_json_data(data);
+        Ok(res.emit(&req).into())
+    }
+
     async fn soft_delete(req: Self::Request) -> Self::Result {
         let id = req.parse_param::<K>("id")?;
         Self::soft_delete_by_id(&id).await.extract(&req)?;
@@ -268,6 +398,74 @@ where
         Ok(res.emit(&req).into())
     }
 
+    async fn revisions(req: Self::Request) -> Self::Result {
+        let id = req.parse_param::<K>("id")?;
+        let revisions = Self::list_revisions(&id).await.extract(&req)?;
+
+        let mut res = Response::default().context(&req);
+        res.set_json_data(Self::data_items(revisions));
+        Ok(res.emit(&req).into())
+    }
+
+    async fn diff(req: Self::Request) -> Self::Result {
+        let id = req.parse_param::<K>("id")?;
+        let query = req.parse_query::<Map>()?;
+        let mut validation = Validation::new();
+        let mut versions = Vec::with_capacity(2);
+        for key in ["from", "to"] {
+            match query.parse_u64(key) {
+                Some(Ok(version)) => versions.push(version),
+                Some(Err(err)) => validation.record_fail(key, err),
+                None => validation.record(key, "should be specified"),
+            }
+        }
+        if !validation.is_success() {
+            return Err(Rejection::bad_request(validation).context(&req).into());
+        }
+
+        let (from_version, to_version) = (versions[0], versions[1]);
+        let diff = Self::diff_versions(&id, from_version, to_version)
+            .await
+            .extract(&req)?;
+        let mut data = Map::new();
+        data.upsert("from_version", from_version);
+        data.upsert("to_version", to_version);
+        data.upsert("diff", diff);
+
+        let mut res = Response::default().context(&req);
+        res.set_json_data(data);
+        Ok(res.emit(&req).into())
+    }
+
+    async fn restore(req: Self::Request) -> Self::Result {
+        let id = req.parse_param::<K>("id")?;
+        let query = req.parse_query::<Map>()?;
+        let version = match query.parse_u64("version") {
+            Some(Ok(version)) => version,
+            Some(Err(err)) => {
+                let rejection = Rejection::from_validation_entry("version", err);
+                return Err(rejection.context(&req).into());
+            }
+            None => {
+                let err = Error::new("should be specified");
+                let rejection = Rejection::from_validation_entry("version", err);
+                return Err(rejection.context(&req).into());
+            }
+        };
+
+        let extension = req.get_data::<<Self as ModelHooks>::Extension>();
+        let model = Self::restore_by_id(&id, version).await.extract(&req)?;
+        let mut model_data = model.into_map();
+        Self::translate_model(&mut model_data);
+        Self::before_respond(&mut model_data, extension.as_ref())
+            .await
+            .extract(&req)?;
+
+        let mut res = Response::default().context(&req);
+        res.set_json_data(Self::data_item(model_data));
+        Ok(res.emit(&req).into())
+    }
+
     async fn batch_insert(mut req: Self::Request) -> Self::Result {
         let data = req.parse_body::<Vec<Map>>().await?;
         let extension = req.get_data::<<Self as ModelHooks>::Extension>();
@@ -471,22 +669,13 @@ where
             .await
             .extract(&req)?;
 
-        let mut models = Self::find(&query).await.extract(&req)?;
-        let translate_enabled = query.translate_enabled();
-        for model in models.iter_mut() {
-            translate_enabled.then(|| Self::translate_model(model));
-            Self::after_decode(model).await.extract(&req)?;
-            Self::before_respond(model, extension.as_ref())
-                .await
-                .extract(&req)?;
-        }
-
-        let format = req.get_query("format").unwrap_or("json");
-        match format {
-            "csv" => res.set_csv_response(models),
-            "jsonlines" => res.set_jsonlines_response(models),
-            _ => res.set_json_response(models),
+        let format = req.get_query("format").unwrap_or("json").to_owned();
+        match format.as_str() {
+            "csv" => res.set_content_type("text/csv; charset=utf-8"),
+            "jsonlines" => res.set_content_type("application/jsonlines; charset=utf-8"),
+            _ => res.set_content_type("application/json; charset=utf-8"),
         }
+        res.set_stream_data(spawn_export::<Self>(query, extension, format));
         Ok(res.emit(&req).into())
     }
 
@@ -501,39 +690,74 @@ where
         let parent_id = req.get_query("parent_id").unwrap_or("null");
         query.add_filter("parent_id", parent_id);
 
-        let mut models = if query.populate_enabled() {
-            Self::fetch(&query).await.extract(&req)?
+        // Fetches the root models and all of their descendants in a single round trip
+        let primary_key_name = Self::PRIMARY_KEY_NAME;
+        let table_name = Self::table_name();
+        let roots_name = [table_name, "_tree_roots"].concat();
+        let tree_name = [table_name, "_tree"].concat();
+        let mut fields = query.fields().to_vec();
+        if !fields.is_empty() {
+            for field in [primary_key_name, "parent_id"] {
+                if !fields.iter().any(|f| f == field) {
+                    fields.push(field.to_owned());
+                }
+            }
+            query.set_fields(fields.clone());
+        }
+
+        let mut anchor = Query::default();
+        anchor.set_fields(fields.clone());
+        anchor.set_extra_flag("table_name", roots_name.as_str());
+
+        let mut recursive = Query::default();
+        recursive.set_fields(fields.clone());
+        recursive.add_filter("status", Map::from_entry("$ne", "Deleted"));
+
+        let mut tree_query = Query::default();
+        tree_query.set_fields(fields);
+        tree_query.set_order(query.sort_order().to_vec());
+        tree_query.set_extra_flag("table_name", tree_name.as_str());
+        tree_query.disable_limit();
+        if let Some(populate) = query.extra().get("populate") {
+            tree_query.set_extra_flag("populate", populate.to_owned());
+        }
+        if let Some(translate) = query.extra().get("translate") {
+            tree_query.set_extra_flag("translate", translate.to_owned());
+        }
+        CommonTableExpr::new::<Self>(roots_name, &query).attach_to(&mut tree_query);
+        CommonTableExpr::recursive::<Self>(
+            tree_name,
+            &anchor,
+            &recursive,
+            "parent_id",
+            primary_key_name,
+        )
+        .attach_to(&mut tree_query);
+
+        let mut nodes = if tree_query.populate_enabled() {
+            Self::fetch(&tree_query).await.extract(&req)?
         } else {
-            let mut models = Self::find(&query).await.extract(&req)?;
-            let translate_enabled = query.translate_enabled();
-            for model in models.iter_mut() {
-                translate_enabled.then(|| Self::translate_model(model));
-                Self::after_decode(model).await.extract(&req)?;
+            let mut nodes = Self::find(&tree_query).await.extract(&req)?;
+            let translate_enabled = tree_query.translate_enabled();
+            for node in nodes.iter_mut() {
+                translate_enabled.then(|| Self::translate_model(node));
+                Self::after_decode(node).await.extract(&req)?;
             }
-            models
+            nodes
         };
 
-        let primary_key_name = Self::PRIMARY_KEY_NAME;
-        let values = models
+        let node_ids = nodes
             .iter()
-            .filter_map(|model| model.get(primary_key_name).cloned())
+            .filter_map(|node| node.get(primary_key_name).cloned())
             .collect::<Vec<_>>();
-        let mut query = Self::default_snapshot_query();
-        query.add_filter("parent_id", Map::from_entry("$in", values));
-        query.add_filter("status", Map::from_entry("$ne", "Deleted"));
-        query.order_desc("parent_id");
-        query.order_desc("created_at");
-        query.disable_limit();
-
-        let mut children = Self::find::<Map>(&query).await.extract(&req)?;
-        let total_rows = children.len();
-        for model in models.iter_mut() {
-            let model_id = model.get(primary_key_name);
-            let model_children = children
-                .extract_if(.., |d| d.get("parent_id") == model_id)
-                .collect::<Vec<_>>();
-            model.upsert("children", model_children);
-        }
+        let mut models = nodes
+            .extract_if(.., |node| {
+                node.get("parent_id")
+                    .is_none_or(|parent_id| !node_ids.contains(parent_id))
+            })
+            .collect::<Vec<_>>();
+        let total_rows = nodes.len();
+        attach_children(&mut models, &mut nodes, primary_key_name);
 
         let mut data = Self::data_items(models);
         data.upsert("total_rows", total_rows);
@@ -621,3 +845,254 @@ where
         Ok(res.emit(&req).into())
     }
 }
+
+/// Attaches the child nodes to the models recursively.
+#[cfg(any(
+    feature = "actix",
+    feature = "axum",
+    feature = "ntex",
+    feature = "salvo"
+))]
+#[cfg(feature = "orm")]
+fn attach_children(models: &mut [Map], nodes: &mut Vec<Map>, primary_key_name: &str) {
+    for model in models.iter_mut() {
+        let model_id = model.get(primary_key_name).cloned();
+        let mut children = nodes
+            .extract_if(.., |node| node.get("parent_id") == model_id.as_ref())
+            .collect::<Vec<_>>();
+        attach_children(&mut children, nodes, primary_key_name);
+        model.upsert("children", children);
+    }
+}
+
+/// Spawns a task to export the models selected by the query,
+/// and returns a receiver of the encoded chunks.
+///
+/// The rows are written incrementally so that the models are never buffered as a whole.
+/// Since the futures of the model hooks are not `Send`, the task is driven by
+/// a dedicated thread instead of the async runtime. It enters the runtime context
+/// of the caller if there is one, and stops as soon as the receiver has been dropped.
+#[cfg(any(
+    feature = "actix",
+    feature = "axum",
+    feature = "ntex",
+    feature = "salvo"
+))]
+#[cfg(feature = "orm")]
+fn spawn_export<M: Schema>(
+    query: Query,
+    extension: Option<M::Extension>,
+    format: String,
+) -> mpsc::Receiver<Result<Vec<u8>, Error>> {
+    let (tx, rx) = mpsc::channel(16);
+    let runtime_handle = tokio::runtime::Handle::try_current().ok();
+    let mut error_tx = tx.clone();
+    let result = thread::Builder::new()
+        .name("zino-export".to_owned())
+        .spawn(move || {
+            let _guard = runtime_handle.as_ref().map(|handle| handle.enter());
+            block_on(export_models::<M>(query, extension, format, tx));
+        });
+    if let Err(err) = result {
+        let err = Error::new(format!("fail to spawn a thread for the export: {err}"));
+        error_tx.try_send(Err(err)).ok();
+    }
+    rx
+}
+
+/// Exports the models selected by the query to the sender.
+#[cfg(any(
+    feature = "actix",
+    feature = "axum",
+    feature = "ntex",
+    feature = "salvo"
+))]
+#[cfg(feature = "orm")]
+async fn export_models<M: Schema>(
+    query: Query,
+    extension: Option<M::Extension>,
+    format: String,
+    mut tx: mpsc::Sender<Result<Vec<u8>, Error>>,
+) {
+    let fields = export_fields(query.fields(), M::fields(), M::write_only_fields());
+    let result: Result<(), Error> = async {
+        let mut stream = pin!(M::find_stream_as::<Map>(&query).await?);
+        let mut num_rows = 0;
+        if format == "csv" {
+            tx.send(encode_csv_header(&fields)).await?;
+        }
+        while let Some(result) = stream.next().await {
+            let mut model = result?;
+            M::before_respond(&mut model, extension.as_ref()).await?;
+            tx.send(encode_export_row(&format, &model, &fields, num_rows))
+                .await?;
+            num_rows += 1;
+        }
+        if format != "csv" && format != "jsonlines" {
+            let footer = if num_rows == 0 { b"[]" as &[u8] } else { b"]" };
+            tx.send(Ok(footer.to_vec())).await?;
+        }
+        Ok(())
+    }
+    .await;
+    if let Err(err) = result {
+        // The response body is aborted with the error
+        tx.send(Err(err)).await.ok();
+    }
+}
+
+/// Returns the fields of the exported data, which are the projection fields of the query
+/// or the readable fields of the model.
+#[cfg(any(
+    feature = "actix",
+    feature = "axum",
+    feature = "ntex",
+    feature = "salvo"
+))]
+#[cfg(feature = "orm")]
+fn export_fields(
+    query_fields: &[String],
+    model_fields: &[&str],
+    write_only_fields: &[&str],
+) -> Vec<String> {
+    if query_fields.is_empty() {
+        model_fields
+            .iter()
+            .filter(|field| !write_only_fields.contains(field))
+            .map(|&field| field.to_owned())
+            .collect()
+    } else {
+        query_fields
+            .iter()
+            .map(|field| {
+                let field = field
+                    .split_once(':')
+                    .map_or(field.as_str(), |(alias, _)| alias);
+                let field = field.trim();
+                field
+                    .rsplit_once('.')
+                    .map_or(field, |(_, name)| name)
+                    .to_owned()
+            })
+            .collect()
+    }
+}
+
+/// Encodes the header of the exported CSV data.
+#[cfg(any(
+    feature = "actix",
+    feature = "axum",
+    feature = "ntex",
+    feature = "salvo"
+))]
+#[cfg(feature = "orm")]
+fn encode_csv_header(fields: &[String]) -> Result<Vec<u8>, Error> {
+    let header = fields
+        .iter()
+        .map(|field| (field.to_owned(), field.as_str().into()))
+        .collect::<Map>();
+    header
+        .to_csv_record(fields, Vec::new())
+        .map_err(Error::from)
+}
+
+/// Encodes the `index`-th row of the exported data in the format.
+#[cfg(any(
+    feature = "actix",
+    feature = "axum",
+    feature = "ntex",
+    feature = "salvo"
+))]
+#[cfg(feature = "orm")]
+fn encode_export_row(
+    format: &str,
+    model: &Map,
+    fields: &[String],
+    index: usize,
+) -> Result<Vec<u8>, Error> {
+    match format {
+        "csv" => model.to_csv_record(fields, Vec::new()).map_err(Error::from),
+        "jsonlines" => {
+            let mut buffer = serde_json::to_vec(model)?;
+            buffer.push(b'\n');
+            Ok(buffer)
+        }
+        _ => {
+            let mut buffer = vec![if index == 0 { b'[' } else { b',' }];
+            serde_json::to_writer(&mut buffer, model)?;
+            Ok(buffer)
+        }
+    }
+}
+
+#[cfg(any(
+    feature = "actix",
+    feature = "axum",
+    feature = "ntex",
+    feature = "salvo"
+))]
+#[cfg(feature = "orm")]
+#[cfg(test)]
+mod tests {
+    use zino_core::{JsonValue, Map, extension::JsonObjectExt};
+
+    #[test]
+    fn it_collects_export_fields() {
+        let model_fields = ["id", "name", "password", "status"];
+        let fields = super::export_fields(&[], &model_fields, &["password"]);
+        assert_eq!(fields, ["id", "name", "status"]);
+
+        let query_fields = ["user.id".to_owned(), "nickname:name".to_owned()];
+        let fields = super::export_fields(&query_fields, &model_fields, &["password"]);
+        assert_eq!(fields, ["id", "nickname"]);
+    }
+
+    #[test]
+    fn it_encodes_export_rows() {
+        let fields = vec!["id".to_owned(), "name".to_owned(), "tags".to_owned()];
+        let mut alice = Map::new();
+        alice.upsert("id", 1);
+        alice.upsert("name", "alice");
+        let mut bob = Map::new();
+        bob.upsert("id", 2);
+        bob.upsert("name", "bob, jr.");
+        bob.upsert("tags", vec!["admin"]);
+
+        // The CSV header is built from the fields even if the first row misses some of them
+        let mut csv = super::encode_csv_header(&fields).expect("fail to encode the header");
+        csv.extend(
+            super::encode_export_row("csv", &alice, &fields, 0).expect("fail to encode the row"),
+        );
+        csv.extend(
+            super::encode_export_row("csv", &bob, &fields, 1).expect("fail to encode the row"),
+        );
+        assert_eq!(
+            String::from_utf8(csv).expect("the CSV data should be UTF-8"),
+            "id,name,tags\n1,alice,\n2,\"bob, jr.\",\"[\"\"admin\"\"]\"\n"
+        );
+
+        let mut jsonlines = super::encode_export_row("jsonlines", &alice, &fields, 0)
+            .expect("fail to encode the row");
+        jsonlines.extend(
+            super::encode_export_row("jsonlines", &bob, &fields, 1)
+                .expect("fail to encode the row"),
+        );
+        assert_eq!(
+            String::from_utf8(jsonlines)
+                .expect("the JSON lines should be UTF-8")
+                .lines()
+                .count(),
+            2
+        );
+
+        let mut json =
+            super::encode_export_row("json", &alice, &fields, 0).expect("fail to encode the row");
+        json.extend(
+            super::encode_export_row("json", &bob, &fields, 1).expect("fail to encode the row"),
+        );
+        json.push(b']');
+        let value =
+            serde_json::from_slice::<JsonValue>(&json).expect("fail to parse the JSON data");
+        assert_eq!(value, JsonValue::from(vec![alice, bob]));
+    }
+}
@@ -20,6 +20,9 @@ cfg_if::cfg_if! {
     } else if #[cfg(feature = "ntex")] {
         #[doc(no_inline)]
         pub use zino_ntex::{Cluster, Request, Response, Result, RouterConfigure};
+    } else if #[cfg(feature = "salvo")] {
+        #[doc(no_inline)]
+        pub use zino_salvo::{Cluster, Request, Response, Result, RouterConfigure};
     }
 }
 